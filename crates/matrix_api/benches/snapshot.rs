@@ -0,0 +1,52 @@
+//! Save file encoding and decoding of a lived-in universe: 13.8 Gyr of regions, the stars
+//! and life of its densest regions, history, and a full default-size particle set
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use matrix_api::{SerializedParticle, SimConfig, Universe, UniverseSnapshot};
+use matrix_physics::particle;
+use matrix_storage::{Compression, decode_snapshot, encode_snapshot};
+use rand::SeedableRng;
+
+fn lived_in_snapshot() -> UniverseSnapshot {
+    // Regions, stars and life don't need the particles, so they are added afterwards
+    let config = SimConfig {
+        particle_count: 0,
+        ..SimConfig::default()
+    };
+    let mut universe = Universe::from_config(config);
+    universe.advance(13.8);
+    for region_id in universe.densest_regions(5) {
+        universe.sample_region(region_id);
+    }
+    let mut snapshot = universe.snapshot();
+
+    let config = SimConfig::default();
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+    snapshot.particles = particle::generate_big_bang(&config, &mut rng)
+        .iter()
+        .map(SerializedParticle::from)
+        .collect();
+    snapshot
+}
+
+fn snapshot_io(c: &mut Criterion) {
+    let snapshot = lived_in_snapshot();
+    for (name, compression) in [("none", Compression::None), ("lz4", Compression::Lz4)] {
+        let encoded = encode_snapshot(&snapshot, compression).expect("snapshot encodes");
+        let mut group = c.benchmark_group("snapshot");
+        group.sample_size(20);
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+        group.bench_function(BenchmarkId::new("encode", name), |b| {
+            b.iter(|| encode_snapshot(black_box(&snapshot), compression))
+        });
+        group.bench_function(BenchmarkId::new("decode", name), |b| {
+            b.iter(|| decode_snapshot(black_box(&encoded)))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, snapshot_io);
+criterion_main!(benches);
@@ -0,0 +1,327 @@
+//! Drive the simulation from plain Rust, without Bevy: create a universe from a
+//! `SimConfig`, advance it, visit regions and read back the life and civilizations
+//! found. Regions load around a virtual camera as in the app (`--headless` does the
+//! same ticks); `sample_region` loads any other region on demand.
+//!
+//! ```
+//! use matrix_api::{SimConfig, Universe};
+//!
+//! // Regions, stars and life don't need the particle simulation
+//! let config = SimConfig { seed: 42, particle_count: 0, ..SimConfig::default() };
+//! let mut universe = Universe::from_config(config);
+//! universe.advance(13.8);
+//! for region_id in universe.densest_regions(5) {
+//!     universe.sample_region(region_id);
+//! }
+//! for civ in universe.civilizations() {
+//!     println!("{} (tech level {:.1})", universe.planet_name(civ.planet_id), civ.tech_level);
+//! }
+//! assert!(!universe.enumerate_life().is_empty());
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use matrix_physics::particle;
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::{UniverseHistory, sample_of};
+use matrix_sim::lazy_universe::{LazyUniverse, restart_regions_for_cycle};
+use matrix_sim::universe::UniverseState;
+use rand::SeedableRng;
+
+pub use matrix_core::{
+    Civilization, FeedEntry, Galaxy, HistorySample, LifeDiscovery, Region, SerializedParticle,
+    SimConfig, Star,
+};
+pub use matrix_storage::UniverseSnapshot;
+
+/// Simulated time per tick of `advance` (Gyr)
+pub const DEFAULT_STEP_GYR: f64 = 0.01;
+/// Regions only exist once the Big Bang phase is over (like `--headless`)
+const REGIONS_FROM_GYR: f64 = 1.0;
+
+/// What a region holds at the current age, as loaded by `Universe::sample_region`
+#[derive(Debug, Clone)]
+pub struct RegionReport {
+    pub region: Region,
+    pub name: String,
+    /// Universe age the report was taken at (Gyr)
+    pub age_gyr: f64,
+    pub galaxies: Vec<Galaxy>,
+    pub stars: Vec<Star>,
+    /// Life found in this region so far, including earlier visits
+    pub life: Vec<LifeDiscovery>,
+    /// Civilizations of this region, including extinct ones
+    pub civilizations: Vec<Civilization>,
+}
+
+/// A simulated universe: particle state, regions, the event feed and sampled history
+pub struct Universe {
+    state: UniverseState,
+    lazy: LazyUniverse,
+    feed: EventFeed,
+    history: UniverseHistory,
+    /// Regions load around this point while advancing
+    camera: [f64; 3],
+    step_gyr: f64,
+}
+
+impl Universe {
+    /// A fresh Big Bang for `config` (particles from `config.seed`, as in the app)
+    pub fn from_config(config: SimConfig) -> Self {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+        let mut state = UniverseState::new(config.clone(), particles);
+        // Ticks are in Gyr already
+        state.time_scale = 1.0;
+        let mut lazy = LazyUniverse::new(config, 0.0);
+        // No frame loop to poll background loads: generate regions in place
+        lazy.async_loading = false;
+        Self {
+            state,
+            lazy,
+            feed: EventFeed::default(),
+            history: UniverseHistory::default(),
+            camera: [0.0; 3],
+            step_gyr: DEFAULT_STEP_GYR,
+        }
+    }
+
+    /// Simulated time per tick (Gyr). Smaller steps follow the particles more closely
+    /// and find regions' life at finer ages, at the cost of more ticks.
+    pub fn with_step(mut self, step_gyr: f64) -> Self {
+        self.step_gyr = step_gyr;
+        self
+    }
+
+    /// Move the point regions load around while advancing
+    pub fn set_camera(&mut self, position: [f64; 3]) {
+        self.camera = position;
+    }
+
+    /// Universe age (Gyr, since the start of the current cycle)
+    pub fn age(&self) -> f64 {
+        self.state.age
+    }
+
+    /// Run the simulation for `gyr` of simulated time: particles, phases, orbits,
+    /// cycle restarts, and region loading and life discovery around the camera
+    pub fn advance(&mut self, gyr: f64) {
+        let mut remaining = gyr;
+        while remaining > 0.0 {
+            let dt = remaining.min(self.step_gyr);
+            remaining -= dt;
+            self.state.tick(dt);
+            self.lazy.advance_orbits(dt);
+            restart_regions_for_cycle(&mut self.state, &mut self.lazy);
+            if self.state.age >= REGIONS_FROM_GYR {
+                self.lazy.update_lod(self.camera, self.state.age);
+            }
+            self.feed.collect(&mut self.state, &mut self.lazy);
+            self.history.record(sample_of(&self.state, &self.lazy));
+        }
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.lazy.regions
+    }
+
+    /// Ids of the `n` densest regions, densest first. Subdivided regions are left out:
+    /// their children are the ones with stars to sample.
+    pub fn densest_regions(&self, n: usize) -> Vec<u64> {
+        let subdivided = self.lazy.subdivided_regions();
+        let mut regions: Vec<&Region> =
+            self.lazy.regions.iter().filter(|r| !subdivided.contains(&r.id)).collect();
+        regions.sort_by(|a, b| b.density.total_cmp(&a.density));
+        regions.into_iter().take(n).map(|r| r.id).collect()
+    }
+
+    /// Load `region_id` at the current age (recording any life found there) and report
+    /// what it holds. `None` for an unknown id. The camera's region comes back on the
+    /// next `advance`.
+    pub fn sample_region(&mut self, region_id: u64) -> Option<RegionReport> {
+        let region = self.lazy.regions.iter().find(|r| r.id == region_id)?.clone();
+        self.lazy.enter_region(region_id, self.state.age);
+        self.feed.collect(&mut self.state, &mut self.lazy);
+        Some(RegionReport {
+            name: self.lazy.region_name(region_id),
+            region,
+            age_gyr: self.state.age,
+            galaxies: self.lazy.loaded_galaxies.clone(),
+            stars: self.lazy.loaded_stars.to_vec(),
+            life: self
+                .lazy
+                .discoveries
+                .iter()
+                .filter(|d| d.region_id == region_id)
+                .cloned()
+                .collect(),
+            civilizations: self
+                .lazy
+                .civilizations
+                .iter()
+                .filter(|c| c.region_id == region_id)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Every life-bearing planet found so far in this cycle, in discovery order
+    pub fn enumerate_life(&self) -> Vec<LifeDiscovery> {
+        self.lazy.discoveries.clone()
+    }
+
+    /// Tracked civilizations of this cycle, including extinct ones
+    pub fn civilizations(&self) -> &[Civilization] {
+        &self.lazy.civilizations
+    }
+
+    /// Recent events (life found, phase transitions, new cycles, ...), oldest first
+    pub fn events(&self) -> impl Iterator<Item = &FeedEntry> {
+        self.feed.entries().iter()
+    }
+
+    /// Key metrics sampled while advancing, oldest first (denser early on, at most
+    /// `matrix_sim::history::HISTORY_CAPACITY`)
+    pub fn history(&self) -> &[HistorySample] {
+        self.history.samples()
+    }
+
+    pub fn region_name(&self, region_id: u64) -> String {
+        self.lazy.region_name(region_id)
+    }
+
+    /// Name of a planet of the loaded region or a discovered life world
+    pub fn planet_name(&self, planet_id: u64) -> String {
+        let region_id = self
+            .lazy
+            .discoveries
+            .iter()
+            .find(|d| d.planet_id == planet_id)
+            .map(|d| d.region_id)
+            .or(self.lazy.current_region_id)
+            .unwrap_or_default();
+        self.lazy.planet_name_of(region_id, planet_id)
+    }
+
+    /// The whole state as a save snapshot (`matrix_storage::save_snapshot` writes it)
+    pub fn snapshot(&self) -> UniverseSnapshot {
+        let (state, lazy) = (&self.state, &self.lazy);
+        UniverseSnapshot {
+            age: state.age,
+            scale_factor: state.scale_factor,
+            phase: state.phase,
+            cycle: state.cycle,
+            temperature: state.temperature,
+            total_entropy: state.total_entropy,
+            config: state.config.clone(),
+            particles: state.particles.iter().map(SerializedParticle::from).collect(),
+            regions: lazy.regions.to_vec(),
+            current_region_id: lazy.current_region_id,
+            loaded_stars: lazy.loaded_stars.to_vec(),
+            stars_age: lazy.stars_age,
+            galaxies: lazy.loaded_galaxies.clone(),
+            life_planets: lazy.life_planets.clone(),
+            civilization_count: lazy.civilization_count,
+            time_scale: state.time_scale,
+            paused: state.paused,
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            discoveries: lazy.discoveries.clone(),
+            civilizations: lazy.civilizations.clone(),
+            events: self.feed.entries().iter().cloned().collect(),
+            surface_memory: lazy.surface_memory.clone(),
+            bestiary: lazy.bestiary.clone(),
+            names: lazy.names.clone(),
+            bookmarks: lazy.bookmarks.clone(),
+            history: self.history.samples().to_vec(),
+        }
+    }
+
+    /// Continue from `snapshot` (from `snapshot` or a save file). The step and camera
+    /// are kept; the saved time scale is not, since ticks are in Gyr.
+    pub fn restore(&mut self, snapshot: UniverseSnapshot) {
+        let mut state = UniverseState::new(
+            snapshot.config.clone(),
+            snapshot.particles.iter().map(|p| p.into()).collect(),
+        );
+        state.age = snapshot.age;
+        state.scale_factor = snapshot.scale_factor;
+        state.phase = snapshot.phase;
+        state.cycle = snapshot.cycle;
+        state.temperature = snapshot.temperature;
+        state.total_entropy = snapshot.total_entropy;
+        state.paused = snapshot.paused;
+
+        let mut lazy = LazyUniverse::new(snapshot.config, snapshot.age);
+        lazy.async_loading = false;
+        lazy.regions = snapshot.regions.into();
+        lazy.current_region_id = snapshot.current_region_id;
+        lazy.loaded_stars = snapshot.loaded_stars.into();
+        lazy.stars_age = snapshot.stars_age;
+        lazy.loaded_galaxies = snapshot.galaxies;
+        // Keep the saved orbits instead of regenerating stars on the next age reload
+        lazy.last_reload_age = snapshot.age;
+        lazy.life_planets = snapshot.life_planets;
+        lazy.discoveries = snapshot.discoveries;
+        lazy.civilizations = snapshot.civilizations;
+        lazy.civilization_count = snapshot.civilization_count;
+        lazy.surface_memory = snapshot.surface_memory;
+        lazy.bestiary = snapshot.bestiary;
+        lazy.names = snapshot.names;
+        lazy.bookmarks = snapshot.bookmarks;
+        lazy.refresh_region_flags();
+        lazy.rebuild_signals(snapshot.age);
+
+        self.state = state;
+        self.lazy = lazy;
+        self.feed.restore(snapshot.events);
+        self.history.restore(snapshot.history);
+    }
+
+    /// The underlying simulation, for anything the facade doesn't cover
+    pub fn state(&self) -> &UniverseState {
+        &self.state
+    }
+
+    pub fn lazy(&self) -> &LazyUniverse {
+        &self.lazy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_restores_the_same_universe() {
+        let config = SimConfig {
+            seed: 7,
+            particle_count: 200,
+            ..SimConfig::default()
+        };
+        let mut universe = Universe::from_config(config.clone());
+        universe.advance(11.0);
+        let region_id = universe.densest_regions(1)[0];
+        let report = universe.sample_region(region_id).unwrap();
+        assert_eq!(report.region.id, region_id);
+        assert!(!report.stars.is_empty());
+        assert!(universe.sample_region(u64::MAX).is_none());
+
+        let snapshot = universe.snapshot();
+        let mut restored = Universe::from_config(config);
+        restored.restore(snapshot);
+        assert_eq!(restored.age(), universe.age());
+        assert_eq!(restored.enumerate_life().len(), universe.enumerate_life().len());
+        assert_eq!(restored.lazy().current_region_id, Some(region_id));
+        assert_eq!(restored.events().count(), universe.events().count());
+        assert!(!universe.history().is_empty());
+        assert_eq!(restored.history(), universe.history());
+
+        // Both carry on identically
+        universe.advance(0.5);
+        restored.advance(0.5);
+        assert_eq!(restored.state().phase, universe.state().phase);
+        assert_eq!(restored.regions().len(), universe.regions().len());
+    }
+}
@@ -0,0 +1,89 @@
+//! Camera bookmarks: places the player stored to come back to later.
+
+use serde::{Deserialize, Serialize};
+
+/// Bookmark slots, numbered 1 to `BOOKMARK_SLOTS` for the player
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// A stored camera view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Zoom level index, 0 = Cosmic to 4 = Surface
+    pub zoom_level: u8,
+    /// Region loaded when the bookmark was stored
+    pub region_id: Option<u64>,
+    /// Selected planet: (star id, planet id)
+    pub planet: Option<(u64, u64)>,
+    /// Shown in the HUD, named after the planet or region
+    pub label: String,
+}
+
+/// The player's bookmarks. Persisted in snapshots and, per seed, in `bookmarks.ron`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    slots: [Option<Bookmark>; BOOKMARK_SLOTS],
+}
+
+impl Bookmarks {
+    /// Bookmark in `slot` (1-based); None when empty or out of range
+    pub fn get(&self, slot: usize) -> Option<&Bookmark> {
+        self.slots.get(slot.checked_sub(1)?)?.as_ref()
+    }
+
+    /// Store `bookmark` in `slot` (1-based), replacing what was there. False if out of range.
+    pub fn set(&mut self, slot: usize, bookmark: Bookmark) -> bool {
+        match slot.checked_sub(1).and_then(|i| self.slots.get_mut(i)) {
+            Some(entry) => {
+                *entry = Some(bookmark);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Occupied slots in order: (slot, bookmark)
+    pub fn occupied(&self) -> impl Iterator<Item = (usize, &Bookmark)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_ref().map(|b| (i + 1, b)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_are_one_based() {
+        let bookmark = Bookmark {
+            position: [1.0, 2.0, 3.0],
+            yaw: 0.5,
+            pitch: -0.1,
+            zoom_level: 2,
+            region_id: Some(72),
+            planet: None,
+            label: "Region 72".to_string(),
+        };
+        let mut bookmarks = Bookmarks::default();
+        assert!(bookmarks.is_empty());
+        assert!(!bookmarks.set(0, bookmark.clone()));
+        assert!(!bookmarks.set(BOOKMARK_SLOTS + 1, bookmark.clone()));
+        assert!(bookmarks.is_empty());
+
+        assert!(bookmarks.set(3, bookmark.clone()));
+        assert!(bookmarks.set(BOOKMARK_SLOTS, bookmark.clone()));
+        assert_eq!(bookmarks.get(3), Some(&bookmark));
+        assert_eq!(bookmarks.get(0), None);
+        assert_eq!(bookmarks.get(1), None);
+        let slots: Vec<usize> = bookmarks.occupied().map(|(slot, _)| slot).collect();
+        assert_eq!(slots, [3, BOOKMARK_SLOTS]);
+    }
+}
@@ -1,5 +1,94 @@
 use serde::{Deserialize, Serialize};
 
+/// Time integration scheme for particle dynamics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Explicit Euler: v += a·dt, x += v·dt (cheap, drifts in energy)
+    #[default]
+    Euler,
+    /// Symplectic kick-drift-kick leapfrog (bounded energy error)
+    Leapfrog,
+}
+
+/// How a new universe starts
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum StartScenario {
+    /// Age 0: the Big Bang particle cloud at the origin, no region loaded until it has spread
+    #[default]
+    BigBang,
+    /// `age` Gyr in: no Big Bang particles, the densest region's stars and particles
+    /// loaded from the start
+    MatureUniverse { age: f64 },
+}
+
+impl StartScenario {
+    /// Universe age (Gyr) the scenario starts at
+    pub fn age(&self) -> f64 {
+        match self {
+            Self::BigBang => 0.0,
+            Self::MatureUniverse { age } => *age,
+        }
+    }
+}
+
+/// How space is cut into regions: a grid of equal cubes centered on the origin, and
+/// optionally a second level where the densest cubes are split into 8 children each
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegionLayout {
+    /// Cubes along x, y and z (a flat "slab" universe has a 1 here)
+    pub grid: [u32; 3],
+    /// Side of a cube (Mpc)
+    pub size: f64,
+    /// Cubes denser than this (relative to the cosmic average) are split once into 8
+    /// children of half the side; `None` keeps one level
+    pub subdivide_above: Option<f64>,
+}
+
+impl Default for RegionLayout {
+    /// 8×8×8 cubes of 100 Mpc, the layout every universe had before it was configurable
+    fn default() -> Self {
+        Self {
+            grid: [8, 8, 8],
+            size: 100.0,
+            subdivide_above: None,
+        }
+    }
+}
+
+impl RegionLayout {
+    /// Number of cubes of the grid (children not counted)
+    pub fn cell_count(&self) -> u64 {
+        self.grid.iter().map(|&n| n as u64).product()
+    }
+
+    /// Id of the cube at grid position `cell`: x-major, so the ids of the default grid
+    /// are the ones older saves have
+    pub fn cell_id(&self, cell: [u32; 3]) -> u64 {
+        let [_, ny, nz] = self.grid.map(|n| n as u64);
+        cell[0] as u64 * ny * nz + cell[1] as u64 * nz + cell[2] as u64
+    }
+
+    /// Center of the cube at grid position `cell`
+    pub fn cell_center(&self, cell: [u32; 3]) -> [f64; 3] {
+        std::array::from_fn(|k| (cell[k] as f64 + 0.5 - self.grid[k] as f64 / 2.0) * self.size)
+    }
+
+    /// Id of child `octant` (0–7) of cube `parent`. Children are numbered after every
+    /// cube, 8 per cube, so no two regions share an id.
+    pub fn child_id(&self, parent: u64, octant: u64) -> u64 {
+        self.cell_count() + parent * 8 + octant
+    }
+
+    /// Center of child `octant` of a region at `center` with side `size`: bits 2, 1 and 0
+    /// of the octant pick the upper half along x, y and z
+    pub fn child_center(center: [f64; 3], size: f64, octant: u64) -> [f64; 3] {
+        std::array::from_fn(|k| {
+            let upper = (octant >> (2 - k)) & 1 == 1;
+            center[k] + if upper { size / 4.0 } else { -size / 4.0 }
+        })
+    }
+}
+
 /// Simulation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimConfig {
@@ -13,6 +102,50 @@ pub struct SimConfig {
     pub gravity_scale: f32,
     /// Dark matter fraction (0.0 - 1.0)
     pub dark_matter_fraction: f32,
+    /// Particle integration scheme
+    pub integrator: Integrator,
+    /// Universe age (Gyr) a typical universe's entropy reaches the Heat Death
+    /// threshold (90% of `MAX_ENTROPY`) around
+    pub heat_death_age: f64,
+    /// Size and shape of the region grid. Saved, so a universe loads on the grid it was
+    /// generated on.
+    pub region_layout: RegionLayout,
+    /// Run particle gravity on the GPU when a compatible adapter exists.
+    /// A property of the machine, not the universe — not saved in snapshots.
+    #[serde(skip, default = "default_use_gpu")]
+    pub use_gpu: bool,
+    /// Generated regions kept in memory for instant revisits (0 disables the cache).
+    /// Bounded by the machine's memory, so not saved in snapshots either.
+    #[serde(skip, default = "default_region_cache_size")]
+    pub region_cache_size: usize,
+    /// Rewind bookmarks ([K]) leave out the particles above this many, regenerating
+    /// them on rewind instead — keeps the in-memory ring small. Machine-local, not saved.
+    #[serde(skip, default = "default_rewind_particle_budget")]
+    pub rewind_particle_budget: usize,
+    /// Frame time (ms) CPU gravity may use, amortized over the frames between steps.
+    /// The step interval adapts to stay within it. Machine-local, not saved.
+    #[serde(skip, default = "default_gravity_budget_ms")]
+    pub gravity_budget_ms: f32,
+    /// How a new universe starts. Only read when one is generated (a save carries its
+    /// own age), so not saved.
+    #[serde(skip, default)]
+    pub start: StartScenario,
+}
+
+fn default_use_gpu() -> bool {
+    true
+}
+
+fn default_region_cache_size() -> usize {
+    8
+}
+
+fn default_rewind_particle_budget() -> usize {
+    250_000
+}
+
+fn default_gravity_budget_ms() -> f32 {
+    4.0
 }
 
 impl Default for SimConfig {
@@ -23,6 +156,14 @@ impl Default for SimConfig {
             big_bang_velocity: 5.0,
             gravity_scale: 1.0,
             dark_matter_fraction: 0.27,
+            integrator: Integrator::Euler,
+            heat_death_age: 60.0,
+            region_layout: RegionLayout::default(),
+            use_gpu: true,
+            region_cache_size: default_region_cache_size(),
+            rewind_particle_budget: default_rewind_particle_budget(),
+            gravity_budget_ms: default_gravity_budget_ms(),
+            start: StartScenario::default(),
         }
     }
 }
@@ -23,6 +23,12 @@ pub const INITIAL_PARTICLE_COUNT: u32 = 100_000;
 /// Maximum entropy threshold for heat death
 pub const MAX_ENTROPY: f64 = 1_000_000.0;
 
+/// Share of `MAX_ENTROPY` at which the Heat Death begins
+pub const HEAT_DEATH_ENTROPY: f64 = 0.9;
+
+/// Surface gravity of Earth (m/s²); planets are sized in Earth masses and radii
+pub const EARTH_SURFACE_GRAVITY: f64 = 9.81;
+
 /// Time step for simulation (in Gyr)
 pub const DT: f32 = 0.001;
 
@@ -35,5 +41,22 @@ pub const NEAR_FIELD_K: usize = 32;
 /// Near-field softening (much smaller than grid softening for fine-grained interactions)
 pub const NEAR_FIELD_SOFTENING: f32 = 0.01;
 
+/// Coulomb constant in simulation units: between unit charges, how much stronger the
+/// electromagnetic pull is than gravity between unit masses (`G`)
+pub const COULOMB_K: f32 = 0.01;
+
+/// Softening of the near-field Coulomb term: light electrons would otherwise be flung
+/// out of close encounters
+pub const ELECTROMAGNETIC_SOFTENING: f32 = 0.05;
+
+/// Cosmic temperature (K) below which electrons and protons stay bound in atoms (~380 kyr)
+pub const RECOMBINATION_TEMPERATURE: f64 = 3000.0;
+
+/// Below `RECOMBINATION_TEMPERATURE`, an electron this close to a proton binds into hydrogen
+pub const RECOMBINATION_CAPTURE_RADIUS: f32 = 0.5;
+
+/// Particles closer than this to a black hole particle fall in and add to its mass
+pub const BLACK_HOLE_CAPTURE_RADIUS: f32 = 0.5;
+
 /// Workgroup size for GPU compute shaders
 pub const WORKGROUP_SIZE: u32 = 256;
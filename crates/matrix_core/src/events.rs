@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::UniversePhase;
+
+/// Something the player should hear about: shown in the on-screen feed and kept in snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimEvent {
+    LifeDiscovered {
+        region_id: u64,
+        planet_id: u64,
+        /// `LifeDiscovery::describe` of the find
+        description: String,
+    },
+    /// A technological civilization was found on arrival or emerged while watching
+    CivilizationDetected { region_id: u64, planet_id: u64 },
+    CivilizationExtinct {
+        region_id: u64,
+        planet_id: u64,
+        cause: String,
+    },
+    PhaseTransition {
+        from: UniversePhase,
+        to: UniversePhase,
+    },
+    /// Recent supernovae in the loaded region
+    SupernovaNearby { region_id: u64, count: u32 },
+    /// A Big Crunch bounced into a new cycle
+    CycleBegan { cycle: u32 },
+    SnapshotSaved { path: String },
+    /// A civilization's radio signal started being heard at the camera
+    SignalDetected { region_id: u64, planet_id: u64 },
+}
+
+impl SimEvent {
+    /// One line for the feed
+    pub fn describe(&self) -> String {
+        match self {
+            Self::LifeDiscovered { description, .. } => format!("Life found: {description}"),
+            Self::CivilizationDetected {
+                region_id,
+                planet_id,
+            } => format!("Civilization detected on planet #{planet_id} (region #{region_id})"),
+            Self::CivilizationExtinct {
+                planet_id, cause, ..
+            } => format!("Civilization on planet #{planet_id} went extinct ({cause})"),
+            Self::PhaseTransition { from, to } => {
+                format!("Phase transition: {} → {}", from.name(), to.name())
+            }
+            Self::SupernovaNearby { region_id, count: 1 } => {
+                format!("Supernova in region #{region_id}")
+            }
+            Self::SupernovaNearby { region_id, count } => {
+                format!("{count} supernovae in region #{region_id}")
+            }
+            Self::CycleBegan { cycle } => format!("Big Crunch — cycle {cycle} begins"),
+            Self::SnapshotSaved { path } => format!("Snapshot saved: {path}"),
+            Self::SignalDetected {
+                region_id,
+                planet_id,
+            } => format!("Artificial signal from planet #{planet_id} (region #{region_id})"),
+        }
+    }
+}
+
+/// A `SimEvent` stamped with the universe age it happened at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedEntry {
+    pub age_gyr: f64,
+    pub event: SimEvent,
+}
@@ -0,0 +1,100 @@
+//! The universe's history: key metrics sampled over time, persisted in snapshots and
+//! drawn as graphs.
+
+use serde::{Deserialize, Serialize};
+
+/// The metrics of the universe at one moment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistorySample {
+    pub cycle: u32,
+    /// Universe age (Gyr, since the start of `cycle`)
+    pub age: f64,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub scale_factor: f64,
+    /// Alive particles
+    pub particles: u64,
+    /// Estimated stars across all regions
+    pub stars: u64,
+    /// Life-bearing planets discovered
+    pub life: u32,
+    /// Civilizations found, including extinct ones
+    pub civilizations: u32,
+}
+
+impl HistorySample {
+    /// Whether this sample was taken later in the universe's history than `other`
+    pub fn is_after(&self, other: &HistorySample) -> bool {
+        (self.cycle, self.age) > (other.cycle, other.age)
+    }
+}
+
+/// A metric of `HistorySample` that can be graphed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoryMetric {
+    Temperature,
+    Entropy,
+    ScaleFactor,
+    Particles,
+    Stars,
+    Life,
+    Civilizations,
+    Age,
+}
+
+impl HistoryMetric {
+    pub const ALL: [HistoryMetric; 8] = [
+        HistoryMetric::Temperature,
+        HistoryMetric::Entropy,
+        HistoryMetric::ScaleFactor,
+        HistoryMetric::Particles,
+        HistoryMetric::Stars,
+        HistoryMetric::Life,
+        HistoryMetric::Civilizations,
+        HistoryMetric::Age,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Temperature => "Temperature",
+            Self::Entropy => "Entropy",
+            Self::ScaleFactor => "Scale factor",
+            Self::Particles => "Particles",
+            Self::Stars => "Stars",
+            Self::Life => "Life",
+            Self::Civilizations => "Civilizations",
+            Self::Age => "Age",
+        }
+    }
+
+    pub fn value(&self, sample: &HistorySample) -> f64 {
+        match self {
+            Self::Temperature => sample.temperature,
+            Self::Entropy => sample.total_entropy,
+            Self::ScaleFactor => sample.scale_factor,
+            Self::Particles => sample.particles as f64,
+            Self::Stars => sample.stars as f64,
+            Self::Life => sample.life as f64,
+            Self::Civilizations => sample.civilizations as f64,
+            Self::Age => sample.age,
+        }
+    }
+
+    /// Spans many orders of magnitude over a cycle, so graphed on a log scale
+    pub fn log_scale(&self) -> bool {
+        matches!(self, Self::Temperature | Self::Entropy)
+    }
+
+    /// `value` as shown next to the graph
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            Self::Temperature => format!("{value:.3e} K"),
+            Self::Entropy => format!("{value:.3e}"),
+            Self::ScaleFactor => format!("{value:.3}"),
+            Self::Age => format!("{value:.2} Gyr"),
+            Self::Particles | Self::Stars | Self::Life | Self::Civilizations => {
+                format!("{value:.0}")
+            }
+        }
+    }
+}
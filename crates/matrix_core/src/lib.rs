@@ -1,9 +1,22 @@
+pub mod bookmarks;
 pub mod config;
 pub mod constants;
+pub mod cow_vec;
+pub mod events;
+pub mod history;
+pub mod names;
 pub mod region;
+pub mod seeding;
+pub mod serialized;
 pub mod types;
 
-pub use config::SimConfig;
+pub use bookmarks::{Bookmark, Bookmarks};
+pub use config::{Integrator, RegionLayout, SimConfig, StartScenario};
 pub use constants::*;
+pub use cow_vec::CowVec;
+pub use events::{FeedEntry, SimEvent};
+pub use history::{HistoryMetric, HistorySample};
+pub use names::{CustomNames, NameKind};
 pub use region::*;
+pub use serialized::SerializedParticle;
 pub use types::*;
@@ -0,0 +1,193 @@
+//! Procedural names for regions, stars and planets, and the player's renames.
+//!
+//! Star and planet ids repeat from region to region (every region numbers its
+//! stars from 0), so they are named through `name_seed`, which mixes in the
+//! region's seed.
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Syllable onsets. Single consonants or consonant pairs, so a name always splits
+/// back into the same syllables.
+const ONSETS: [&str; 16] = [
+    "b", "d", "g", "k", "l", "m", "n", "r", "s", "t", "v", "z", "th", "kh", "sh", "dr",
+];
+const VOWELS: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "ou", "ei"];
+const CODAS: [&str; 8] = ["", "", "n", "r", "l", "s", "th", "x"];
+const REGION_SUFFIXES: [&str; 8] = [
+    "Reach", "Expanse", "Drift", "Deep", "Veil", "Field", "Gulf", "Marches",
+];
+
+/// Ids from the same region that lie within this many of each other get distinct star names
+const UNIQUE_SPAN_BITS: u32 = 12;
+const UNIQUE_SPAN_MASK: u64 = (1 << UNIQUE_SPAN_BITS) - 1;
+
+/// Planet ids are `star_id * 1000 + index` (`procgen::generate_star`)
+pub const PLANET_ID_STRIDE: u64 = 1000;
+/// Star ids are `region_id * 1_000_000 + index` (`procgen::generate_galaxies`), so star
+/// and planet ids are unique across the universe
+pub const STAR_ID_STRIDE: u64 = 1_000_000;
+
+/// A star or planet id without its region part: with the region seed, what names are
+/// seeded from
+pub fn local_id(kind: NameKind, id: u64) -> u64 {
+    match kind {
+        NameKind::Region => id,
+        NameKind::Star => id % STAR_ID_STRIDE,
+        NameKind::Planet => id % (STAR_ID_STRIDE * PLANET_ID_STRIDE),
+    }
+}
+
+/// Onset + vowel for a 7-bit syllable index
+fn syllable(index: u64) -> String {
+    format!("{}{}", ONSETS[(index & 15) as usize], VOWELS[((index >> 4) & 7) as usize])
+}
+
+fn capitalized(mut word: String) -> String {
+    if let Some(first) = word.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    word
+}
+
+/// A pronounceable lower-case word of `syllables` random syllables
+pub fn word(syllables: usize, rng: &mut impl Rng) -> String {
+    let mut word = String::new();
+    for i in 0..syllables {
+        word.push_str(ONSETS.choose(rng).unwrap());
+        word.push_str(VOWELS.choose(rng).unwrap());
+        if i + 1 == syllables || rng.gen_bool(0.2) {
+            word.push_str(CODAS.choose(rng).unwrap());
+        }
+    }
+    word
+}
+
+/// Seed naming star or planet `id` of the region with seed `region_seed`
+pub fn name_seed(region_seed: u64, id: u64) -> u64 {
+    region_seed
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .rotate_left(29)
+        .wrapping_add(id)
+}
+
+/// Star name, e.g. "Thouvan". Seeds within 4096 of each other never share a name,
+/// so the (at most 1000, consecutively numbered) stars of a region are all distinct.
+pub fn name_for_star(seed: u64) -> String {
+    // Bijective scramble of the low bits: neighbouring ids get unrelated names
+    let mut low = seed & UNIQUE_SPAN_MASK;
+    low = low.wrapping_mul(0x5BD) & UNIQUE_SPAN_MASK;
+    low ^= low >> 5;
+    low = low.wrapping_mul(0x9E3) & UNIQUE_SPAN_MASK;
+    low ^= low >> 6;
+    // The rest only adds variety between regions
+    let high = (seed >> UNIQUE_SPAN_BITS).wrapping_mul(0xD6E8_FEB8_6659_FD93) >> 58;
+
+    let first = syllable(low & 127);
+    let second = syllable((low >> 7) | ((high & 3) << 5));
+    let coda = CODAS[((high >> 2) & 7) as usize];
+    capitalized(format!("{first}{second}{coda}"))
+}
+
+/// Planet `index` of a star: "Thouvan b", "Thouvan c", …
+pub fn name_for_planet(star_name: &str, index: u64) -> String {
+    match u8::try_from(index).ok().filter(|i| *i < 25) {
+        Some(i) => format!("{star_name} {}", (b'b' + i) as char),
+        None => format!("{star_name} {}", index + 1),
+    }
+}
+
+/// Region name, e.g. "Kessar Drift"
+pub fn name_for_region(seed: u64) -> String {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0x6A09_E667_F3BC_C909);
+    let syllables = rng.gen_range(2..=3);
+    let name = capitalized(word(syllables, &mut rng));
+    format!("{name} {}", REGION_SUFFIXES.choose(&mut rng).unwrap())
+}
+
+/// What a custom name applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Region,
+    Star,
+    Planet,
+}
+
+impl NameKind {
+    pub fn parse(word: &str) -> Option<Self> {
+        match word {
+            "region" => Some(Self::Region),
+            "star" => Some(Self::Star),
+            "planet" => Some(Self::Planet),
+            _ => None,
+        }
+    }
+}
+
+/// Names given by the player, replacing the generated ones. Regions are keyed by
+/// id, stars and planets by their `name_seed`. Persisted in snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomNames {
+    pub regions: HashMap<u64, String>,
+    pub stars: HashMap<u64, String>,
+    pub planets: HashMap<u64, String>,
+}
+
+impl CustomNames {
+    fn map(&self, kind: NameKind) -> &HashMap<u64, String> {
+        match kind {
+            NameKind::Region => &self.regions,
+            NameKind::Star => &self.stars,
+            NameKind::Planet => &self.planets,
+        }
+    }
+
+    pub fn get(&self, kind: NameKind, key: u64) -> Option<&str> {
+        self.map(kind).get(&key).map(String::as_str)
+    }
+
+    /// Rename `key`; an empty name restores the generated one
+    pub fn set(&mut self, kind: NameKind, key: u64, name: &str) {
+        let map = match kind {
+            NameKind::Region => &mut self.regions,
+            NameKind::Star => &mut self.stars,
+            NameKind::Planet => &mut self.planets,
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            map.remove(&key);
+        } else {
+            map.insert(key, name.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty() && self.stars.is_empty() && self.planets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_star_names_are_deterministic_and_unique_within_a_region() {
+        for region_seed in [0, 42, 0xDEAD_BEEF, u64::MAX] {
+            let names: Vec<String> = (0..1000)
+                .map(|id| name_for_star(name_seed(region_seed, id)))
+                .collect();
+            let unique: std::collections::HashSet<&String> = names.iter().collect();
+            assert_eq!(unique.len(), names.len(), "duplicate star name in region {region_seed}");
+            assert_eq!(names[7], name_for_star(name_seed(region_seed, 7)));
+            assert!(names[7].starts_with(|c: char| c.is_ascii_uppercase()));
+        }
+
+        assert_eq!(name_for_planet("Thouvan", 0), "Thouvan b");
+        assert_eq!(name_for_planet("Thouvan", 2), "Thouvan d");
+        assert_eq!(name_for_region(9), name_for_region(9));
+    }
+}
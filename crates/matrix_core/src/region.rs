@@ -1,13 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+use crate::constants::EARTH_SURFACE_GRAVITY;
+
 /// A region of space at cosmological scale.
 /// The universe is divided into regions; each has statistical properties
 /// computed from equations, not individual particles.
 /// Detail is generated procedurally when the camera enters.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Region {
-    /// Unique region ID
+    /// Unique region ID (`RegionLayout::cell_id`, `RegionLayout::child_id`)
     pub id: u64,
+    /// Region this is one of the 8 children of (`RegionLayout::subdivide_above`), `None`
+    /// for a cube of the grid
+    pub parent: Option<u64>,
     /// Center position in universe coordinates
     pub center: [f64; 3],
     /// Size of this region (cube side length)
@@ -24,16 +29,20 @@ pub struct Region {
     pub star_count: u64,
     /// Number of planets (estimated)
     pub planet_count: u64,
-    /// Whether life conditions are met on any planet
+    /// Whether life has been found on any of its planets
     pub has_life: bool,
+    /// Whether a living civilization is known in it
+    pub has_civilization: bool,
+    /// Mass (solar masses) of the supermassive black hole at the center, if any
+    pub central_black_hole: Option<f64>,
     /// Detail level currently loaded
     pub detail: RegionDetail,
     /// Seed for deterministic procedural generation
     pub seed: u64,
 }
 
-/// How much detail is loaded for a region
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// How much detail is loaded for a region. Each level includes the ones before it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RegionDetail {
     /// Only statistical properties (density, temp, composition)
     /// Used for distant regions — zero CPU cost
@@ -48,8 +57,80 @@ pub enum RegionDetail {
     Biosphere,
 }
 
+/// Aggregate mass standing in for part of a galaxy of a region at `RegionDetail::Galactic`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassPoint {
+    /// Universe coordinates
+    pub position: [f64; 3],
+    /// Solar masses
+    pub mass: f64,
+}
+
+/// Surface parameters of a planet worked out before landing (`RegionDetail::Planetary`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfacePreset {
+    pub planet_id: u64,
+    pub terrain_seed: u64,
+    /// Lowest and highest surveyed height around the landing site (meters)
+    pub height_range: (f32, f32),
+    /// Height of the liquid surface (meters), if the planet has one
+    pub sea_level: Option<f32>,
+    /// Weather epoch of a first landing
+    pub weather_epoch: u64,
+}
+
+/// Morphology of a galaxy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GalaxyKind {
+    /// Exponential disk with logarithmic spiral arms
+    Spiral,
+    /// Centrally concentrated, slightly flattened spheroid
+    Elliptical,
+    /// Clumpy, no regular structure
+    Irregular,
+}
+
+impl GalaxyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Spiral => "spiral",
+            Self::Elliptical => "elliptical",
+            Self::Irregular => "irregular",
+        }
+    }
+}
+
+/// A galaxy inside a detailed region; stars are sampled from its profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Galaxy {
+    /// Index within the region
+    pub id: u64,
+    /// Center in universe coordinates
+    pub position: [f64; 3],
+    pub kind: GalaxyKind,
+    /// Visible radius (same units as `Region::size`)
+    pub radius: f64,
+    /// Disk normal / flattening axis (unit vector)
+    pub axis: [f64; 3],
+    /// Number of spiral arms (0 unless `kind` is `Spiral`)
+    pub arms: u32,
+    /// Share of the region's `star_count`
+    pub star_budget: u64,
+    /// Generated stars of this galaxy have IDs `first_star..first_star + star_count`
+    pub first_star: u64,
+    pub star_count: u32,
+    /// Seed for the galaxy's own structure (irregular clumps)
+    pub seed: u64,
+}
+
+impl Galaxy {
+    pub fn contains_star(&self, star_id: u64) -> bool {
+        (self.first_star..self.first_star + self.star_count as u64).contains(&star_id)
+    }
+}
+
 /// A star within a detailed region
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Star {
     pub id: u64,
     /// Position relative to region center
@@ -65,11 +146,120 @@ pub struct Star {
     pub spectral_class: SpectralClass,
     /// Age in Gyr
     pub age: f64,
+    /// Set once the star has left the main sequence
+    pub remnant: Option<StellarRemnant>,
     /// Planets orbiting this star
     pub planets: Vec<Planet>,
+    /// Main asteroid belt, if the system has one
+    pub asteroid_belt: Option<AsteroidBelt>,
+    /// Comets on eccentric orbits around this star
+    pub comets: Vec<Comet>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl Star {
+    /// A planet or moon of this star by id
+    pub fn body(&self, id: u64) -> Option<&Planet> {
+        self.planets
+            .iter()
+            .find_map(|p| if p.id == id { Some(p) } else { p.moons.iter().find(|m| m.id == id) })
+    }
+
+    /// The planet `moon_id` orbits, if it is a moon of this star
+    pub fn moon_parent(&self, moon_id: u64) -> Option<&Planet> {
+        self.planets
+            .iter()
+            .find(|p| p.moons.iter().any(|m| m.id == moon_id))
+    }
+}
+
+/// A ring of asteroids around a star, drawn as a point cloud
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AsteroidBelt {
+    /// Inner edge (AU)
+    pub inner_radius: f64,
+    /// Outer edge (AU)
+    pub outer_radius: f64,
+    /// Points drawn for the belt
+    pub particle_budget: u32,
+    /// Seed for the point placement
+    pub seed: u64,
+}
+
+/// A comet on an eccentric orbit in the plane of the planets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comet {
+    pub id: u64,
+    /// Semi-major axis (AU)
+    pub semi_major_axis: f64,
+    /// 0 = circle, close to 1 = long thin ellipse
+    pub eccentricity: f64,
+    /// Orbital period in years
+    pub orbital_period: f64,
+    /// Mean anomaly (radians): advances uniformly in time like a planet's orbital angle
+    pub orbital_angle: f64,
+    /// Direction of the perihelion in the orbital plane (radians)
+    pub perihelion_angle: f64,
+}
+
+impl Comet {
+    /// Closest approach to the star (AU)
+    pub fn perihelion(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.eccentricity)
+    }
+
+    /// Position relative to the star (AU) in the orbital plane at mean anomaly `mean_anomaly`:
+    /// Kepler's equation M = E − e·sin E solved by Newton's method
+    pub fn position_at(&self, mean_anomaly: f64) -> [f64; 2] {
+        let e = self.eccentricity;
+        let mut ecc = if e > 0.8 { std::f64::consts::PI } else { mean_anomaly };
+        for _ in 0..30 {
+            let step = (ecc - e * ecc.sin() - mean_anomaly) / (1.0 - e * ecc.cos());
+            ecc -= step;
+            if step.abs() < 1e-12 {
+                break;
+            }
+        }
+        let a = self.semi_major_axis;
+        // Perihelion along +x before rotating into place
+        let x = a * (ecc.cos() - e);
+        let y = a * (1.0 - e * e).sqrt() * ecc.sin();
+        let (sin, cos) = self.perihelion_angle.sin_cos();
+        [x * cos - y * sin, x * sin + y * cos]
+    }
+
+    /// Current position relative to the star (AU)
+    pub fn position(&self) -> [f64; 2] {
+        self.position_at(self.orbital_angle)
+    }
+}
+
+/// What is left of a star after its main-sequence lifetime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StellarRemnant {
+    /// Below ~8 solar masses: sheds its envelope, no supernova
+    WhiteDwarf,
+    /// ~8-25 solar masses: core-collapse supernova
+    NeutronStar,
+    /// Above ~25 solar masses: core-collapse supernova
+    BlackHole,
+}
+
+impl StellarRemnant {
+    /// Whether the star exploded on its way to this remnant
+    pub fn from_supernova(&self) -> bool {
+        !matches!(self, Self::WhiteDwarf)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WhiteDwarf => "white dwarf",
+            Self::NeutronStar => "neutron star",
+            Self::BlackHole => "black hole",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SpectralClass {
     O, // Blue giant, >30000K
     B, // Blue-white, 10000-30000K
@@ -81,6 +271,16 @@ pub enum SpectralClass {
 }
 
 impl SpectralClass {
+    pub const ALL: [SpectralClass; 7] = [
+        Self::O,
+        Self::B,
+        Self::A,
+        Self::F,
+        Self::G,
+        Self::K,
+        Self::M,
+    ];
+
     pub fn color(&self) -> [f32; 4] {
         match self {
             Self::O => [0.6, 0.7, 1.0, 1.0],
@@ -113,7 +313,7 @@ impl SpectralClass {
 }
 
 /// A planet orbiting a star
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Planet {
     pub id: u64,
     /// Orbital radius in AU
@@ -126,8 +326,11 @@ pub struct Planet {
     pub mass: f64,
     /// Planet radius in Earth radii
     pub radius: f64,
-    /// Surface temperature in Kelvin
+    /// Surface temperature in Kelvin (of the terminator when `tidally_locked`)
     pub surface_temp: f64,
+    /// Always shows its star the same face: no day/night cycle, a scorched day side and
+    /// a frozen night side (planets only; moons are locked to their planet)
+    pub tidally_locked: bool,
     /// Does it have liquid water?
     pub has_water: bool,
     /// Does it have atmosphere?
@@ -138,9 +341,45 @@ pub struct Planet {
     pub planet_type: PlanetType,
     /// Life on this planet (if any)
     pub life: Option<Biosphere>,
+    /// Moons (orbital radius and period around this planet); moons have none of their own
+    pub moons: Vec<Planet>,
+    /// Ring system (gas and ice giants only)
+    pub rings: Option<RingSystem>,
+}
+
+/// Rings around a giant planet, a flat annulus in its equatorial plane
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RingSystem {
+    /// Inner edge (planet radii)
+    pub inner_radius: f64,
+    /// Outer edge (planet radii)
+    pub outer_radius: f64,
+    /// Opacity of the densest band, 0–1
+    pub density: f64,
+    /// Tilt of the ring plane from the orbital plane (radians)
+    pub tilt: f64,
+}
+
+impl Planet {
+    /// Length of a local day in hours. Not stored: derived from mass (giants spin
+    /// fast, small worlds slowly) with a 0.5–2× spread fixed by the planet id.
+    pub fn rotation_period_hours(&self) -> f64 {
+        let base = if self.mass > 10.0 {
+            10.0
+        } else {
+            24.0 * self.mass.max(0.01).powf(-0.2)
+        };
+        let u = (self.id.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 11) as f64 / (1u64 << 53) as f64;
+        base * 0.5 * 4f64.powf(u)
+    }
+
+    /// Surface gravity in m/s² (g ∝ M / R²)
+    pub fn surface_gravity(&self) -> f64 {
+        EARTH_SURFACE_GRAVITY * self.mass / self.radius.max(0.01).powi(2)
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlanetType {
     Rocky,      // Like Earth, Mars
     GasGiant,   // Like Jupiter
@@ -148,9 +387,25 @@ pub enum PlanetType {
     Ocean,      // Water world
     Lava,       // Too close to star
     Frozen,     // Too far from star
+    Barren,     // Airless rock, like most moons
 }
 
 impl PlanetType {
+    pub const ALL: [PlanetType; 7] = [
+        Self::Rocky,
+        Self::GasGiant,
+        Self::IceGiant,
+        Self::Ocean,
+        Self::Lava,
+        Self::Frozen,
+        Self::Barren,
+    ];
+
+    /// Gas and ice giants: no surface to land on, may have rings
+    pub fn is_giant(&self) -> bool {
+        matches!(self, Self::GasGiant | Self::IceGiant)
+    }
+
     pub fn color(&self) -> [f32; 4] {
         match self {
             Self::Rocky => [0.6, 0.5, 0.4, 1.0],
@@ -159,11 +414,12 @@ impl PlanetType {
             Self::Ocean => [0.2, 0.4, 0.9, 1.0],
             Self::Lava => [1.0, 0.3, 0.1, 1.0],
             Self::Frozen => [0.8, 0.9, 1.0, 1.0],
+            Self::Barren => [0.55, 0.53, 0.5, 1.0],
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AtmosphereType {
     None,
     ThinCO2,       // Mars-like
@@ -175,7 +431,7 @@ pub enum AtmosphereType {
 }
 
 /// Life on a planet — abstract, emergent, NOT human-specific
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Biosphere {
     /// How long life has existed (Gyr)
     pub age: f64,
@@ -191,10 +447,146 @@ pub struct Biosphere {
     pub biomass: f64,
 }
 
+/// A biosphere found during exploration, with enough context to catalogue it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifeDiscovery {
+    pub planet_id: u64,
+    pub star_id: u64,
+    pub region_id: u64,
+    /// Seed of the universe it was found in
+    pub seed: u64,
+    pub genome: Genome,
+    pub complexity: f64,
+    pub species_count: u64,
+    pub has_technology: bool,
+    pub planet_type: PlanetType,
+    /// Surface temperature in Kelvin
+    pub surface_temp: f64,
+    /// Universe age (Gyr) when the planet was discovered
+    pub discovery_age: f64,
+}
+
+impl LifeDiscovery {
+    pub fn new(
+        seed: u64,
+        region_id: u64,
+        star: &Star,
+        planet: &Planet,
+        bio: &Biosphere,
+        discovery_age: f64,
+    ) -> Self {
+        Self {
+            planet_id: planet.id,
+            star_id: star.id,
+            region_id,
+            seed,
+            genome: bio.dominant_genome.clone(),
+            complexity: bio.complexity,
+            species_count: bio.species_count,
+            has_technology: bio.has_technology,
+            planet_type: planet.planet_type,
+            surface_temp: planet.surface_temp,
+            discovery_age,
+        }
+    }
+
+    /// One-line summary (used for the HUD and logs); ids follow the names for debugging
+    pub fn describe(&self, planet_name: &str, star_name: &str) -> String {
+        format!(
+            "{} (#{}) orbiting {} — {} (complexity: {:.1}, species: {})",
+            planet_name,
+            self.planet_id,
+            star_name,
+            self.genome.describe(),
+            self.complexity,
+            self.species_count,
+        )
+    }
+}
+
+/// A technological civilization, tracked from the moment its planet gains technology
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Civilization {
+    /// Home planet
+    pub planet_id: u64,
+    pub star_id: u64,
+    pub region_id: u64,
+    /// Kardashev scale: 0.0 (pre-industrial) to 3.0 (galactic)
+    pub tech_level: f64,
+    /// Individuals across home and colonies
+    pub population: f64,
+    /// How far from the home orbit it can settle (AU)
+    pub expansion_radius: f64,
+    /// Chance of extinction per Gyr at the current tech level
+    pub extinction_risk: f64,
+    /// Planets of the home star it has settled
+    pub colonies: Vec<u64>,
+    /// Universe age (Gyr) when it was first tracked
+    pub founded_age: f64,
+    /// Set when it dies out; the planet keeps ruins
+    pub extinction: Option<Extinction>,
+}
+
+/// How and when a civilization ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Extinction {
+    /// Universe age (Gyr)
+    pub age: f64,
+    pub cause: ExtinctionCause,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtinctionCause {
+    /// War, runaway technology, ecological collapse
+    SelfDestruction,
+    /// Host star died (supernova, red giant phase)
+    StellarEvent,
+}
+
+impl ExtinctionCause {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SelfDestruction => "self-destruction",
+            Self::StellarEvent => "stellar event",
+        }
+    }
+}
+
+impl Civilization {
+    pub fn new(region_id: u64, star_id: u64, planet_id: u64, founded_age: f64) -> Self {
+        Self {
+            planet_id,
+            star_id,
+            region_id,
+            tech_level: 0.0,
+            population: 1e6,
+            expansion_radius: 0.0,
+            extinction_risk: 0.0,
+            colonies: Vec::new(),
+            founded_age,
+            extinction: None,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.extinction.is_none()
+    }
+
+    /// Kardashev type as a label ("Type 0.7")
+    pub fn kardashev_label(&self) -> String {
+        format!("Type {:.1}", self.tech_level)
+    }
+
+    /// Whether this civilization lives on `planet_id` (home or colony)
+    pub fn occupies(&self, planet_id: u64) -> bool {
+        self.planet_id == planet_id || self.colonies.contains(&planet_id)
+    }
+}
+
 /// Genome — grounded in real biochemistry and astrobiology.
 /// Constrained by planetary environment. No magic.
 /// Most life is microbial. Complex life is rare. Intelligence is extremely rare.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Genome {
     /// Substrate: biochemical basis (constrained by planet conditions)
     /// 0=carbon-water (Earth-like), 1=carbon-ammonia (cold worlds),
@@ -266,9 +658,9 @@ impl Genome {
         }
     }
 
-    /// Describe this life form — grounded in real biochemistry
-    pub fn describe(&self) -> String {
-        let substrate = match self.substrate {
+    /// Name of the biochemical basis ("carbon-water", "silicon", ...)
+    pub fn substrate_name(&self) -> &'static str {
+        match self.substrate {
             0 => "carbon-water",
             1 => "carbon-ammonia",
             2 => "carbon-methane",
@@ -276,7 +668,17 @@ impl Genome {
             4 => "sulfur-iron",
             5 => "hydrocarbon",
             _ => "carbon-water",
-        };
+        }
+    }
+
+    /// Whether life is built on anything but carbon and water
+    pub fn is_exotic(&self) -> bool {
+        self.substrate_name() != "carbon-water"
+    }
+
+    /// Describe this life form — grounded in real biochemistry
+    pub fn describe(&self) -> String {
+        let substrate = self.substrate_name();
 
         let form = match self.structure {
             0 => "unicellular",
@@ -327,7 +729,19 @@ impl Genome {
             "solitary"
         };
 
-        let energy = match self.energy_source {
+        let energy = Self::energy_name(self.energy_source);
+
+        let motion = Self::motility_name(self.motility);
+
+        format!(
+            "{} {} {} {} ({}, {}, {})",
+            scale, substrate, form, mind, social, energy, motion
+        )
+    }
+
+    /// Adjective for an energy source code
+    pub fn energy_name(energy_source: u32) -> &'static str {
+        match energy_source {
             0 => "photosynthetic",
             1 => "chemosynthetic",
             2 => "geothermal",
@@ -336,9 +750,12 @@ impl Genome {
             5 => "osmotrophic",
             6 => "thermosynthetic",
             _ => "heterotroph",
-        };
+        }
+    }
 
-        let motion = match self.motility {
+    /// Adjective for a motility code
+    pub fn motility_name(motility: u32) -> &'static str {
+        match motility {
             0 => "sessile",
             1 => "drifting",
             2 => "flagellar",
@@ -347,12 +764,7 @@ impl Genome {
             5 => "walking",
             6 => "gliding",
             _ => "flying",
-        };
-
-        format!(
-            "{} {} {} {} ({}, {}, {})",
-            scale, substrate, form, mind, social, energy, motion
-        )
+        }
     }
 
     /// Short emoji-free tag for HUD
@@ -394,3 +806,213 @@ impl Genome {
         senses
     }
 }
+
+/// Ecological role of a surface creature (only sampled for complexity ≥ 5 biospheres)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CreatureRole {
+    Grazer,
+    Predator,
+    Scavenger,
+}
+
+impl CreatureRole {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Grazer => "grazer",
+            Self::Predator => "predator",
+            Self::Scavenger => "scavenger",
+        }
+    }
+
+    /// Movement speed relative to the motility's base speed
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            Self::Grazer => 0.8,
+            Self::Predator => 1.4,
+            Self::Scavenger => 1.0,
+        }
+    }
+}
+
+/// Traits of one surface creature or species: the dominant genome perturbed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndividualTraits {
+    /// Size scale (log10 of meters), within ±0.3 of the genome's
+    pub size_log: f64,
+    /// Body color hue shift from the substrate's base color (degrees)
+    pub hue_shift: f32,
+    /// Body length relative to width (1.0 = the body plan's proportions)
+    pub elongation: f32,
+    /// Locomotion mode: usually the genome's, occasionally a secondary one
+    pub motility: u32,
+    pub role: Option<CreatureRole>,
+}
+
+impl IndividualTraits {
+    /// Size in meters
+    pub fn size_m(&self) -> f64 {
+        10f64.powf(self.size_log)
+    }
+
+    /// Short description relative to the typical size, e.g. "large grazer, 2.3 m"
+    pub fn describe(&self, typical_size_log: f64) -> String {
+        let size = if self.size_log > typical_size_log + 0.15 {
+            "large "
+        } else if self.size_log < typical_size_log - 0.15 {
+            "small "
+        } else {
+            ""
+        };
+        let kind = match self.role {
+            Some(role) => role.name().to_string(),
+            None => format!("{} creature", Genome::motility_name(self.motility)),
+        };
+        let meters = self.size_m();
+        let length = if meters >= 1.0 {
+            format!("{meters:.1} m")
+        } else if meters >= 0.01 {
+            format!("{:.1} cm", meters * 100.0)
+        } else {
+            format!("{:.1} mm", meters * 1000.0)
+        };
+        format!("{size}{kind}, {length}")
+    }
+}
+
+/// What a planet's surface looked like when the player last left it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanetSurfaceMemory {
+    /// Camera position on the surface when the player took off
+    pub position: [f32; 3],
+    /// Eye height above the terrain (meters)
+    pub eye_height: f32,
+    /// Number of landings so far
+    pub visits: u32,
+    /// Weather epoch reached, so the sky picks up where it left off
+    pub weather_epoch: u64,
+    /// Species indices of creatures eaten and not yet respawned
+    pub eaten: Vec<usize>,
+}
+
+impl Default for PlanetSurfaceMemory {
+    fn default() -> Self {
+        Self {
+            position: [0.0; 3],
+            eye_height: 2.0,
+            visits: 0,
+            weather_epoch: 0,
+            eaten: Vec::new(),
+        }
+    }
+}
+
+/// Per-planet surface memory, keyed by planet id. Persisted in snapshots and
+/// cleared with the rest of the universe when a new cycle begins.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceMemory {
+    planets: std::collections::HashMap<u64, PlanetSurfaceMemory>,
+}
+
+impl SurfaceMemory {
+    pub fn get(&self, planet_id: u64) -> Option<&PlanetSurfaceMemory> {
+        self.planets.get(&planet_id)
+    }
+
+    /// Memory for `planet_id`, created on first use
+    pub fn entry(&mut self, planet_id: u64) -> &mut PlanetSurfaceMemory {
+        self.planets.entry(planet_id).or_default()
+    }
+
+    /// Whether the player has landed on `planet_id`
+    pub fn is_visited(&self, planet_id: u64) -> bool {
+        self.get(planet_id).is_some_and(|m| m.visits > 0)
+    }
+
+    /// Number of planets with a memory
+    pub fn len(&self) -> usize {
+        self.planets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.planets.is_empty()
+    }
+
+    /// Re-key every memory through `f` (snapshot migrations)
+    pub fn map_planet_ids(&mut self, mut f: impl FnMut(u64) -> u64) {
+        self.planets = std::mem::take(&mut self.planets)
+            .into_iter()
+            .map(|(id, memory)| (f(id), memory))
+            .collect();
+    }
+}
+
+/// A surface species the player has scanned up close
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestiaryEntry {
+    /// Binomial name, e.g. "Vorth khelar"
+    pub name: String,
+    pub planet_id: u64,
+    pub planet_type: PlanetType,
+    /// Index into the planet's surface species
+    pub species: usize,
+    /// Traits of the individual that was scanned
+    pub traits: IndividualTraits,
+    /// The biosphere's dominant genome at scan time
+    pub genome: Genome,
+    /// Universe age (Gyr) when the species was scanned
+    pub scanned_age: f64,
+}
+
+impl BestiaryEntry {
+    /// Field-guide text: the individual, then its lineage
+    pub fn portrait(&self) -> String {
+        format!(
+            "{} — {}. Senses: {}",
+            self.traits.describe(self.genome.size_log),
+            self.genome.describe(),
+            self.genome.sense_list().join(", ")
+        )
+    }
+}
+
+/// Every species scanned in this save, in scan order. Persisted in snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bestiary {
+    entries: Vec<BestiaryEntry>,
+}
+
+impl Bestiary {
+    /// Add `entry` unless its species was already scanned; returns whether it is new
+    pub fn record(&mut self, entry: BestiaryEntry) -> bool {
+        if self.contains(entry.planet_id, entry.species) {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    pub fn contains(&self, planet_id: u64, species: usize) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.planet_id == planet_id && e.species == species)
+    }
+
+    pub fn entries(&self) -> &[BestiaryEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Change the planet id of every entry through `f` (snapshot migrations)
+    pub fn map_planet_ids(&mut self, mut f: impl FnMut(u64) -> u64) {
+        for entry in &mut self.entries {
+            entry.planet_id = f(entry.planet_id);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{GpuParticle, ParticleKind};
+
+/// Kind stored for GPU particles whose `kind` index isn't a known `ParticleKind`
+pub const FALLBACK_KIND: ParticleKind = ParticleKind::Hydrogen;
+
+/// Serializable version of `GpuParticle` (GpuParticle uses Pod which prevents Serialize).
+///
+/// Unlike the GPU layout, the values packed into the `w` lanes get their own fields
+/// and `kind` is the enum. Snapshots are bincode, so adding a field here changes the
+/// layout: bump `SNAPSHOT_VERSION` and give older saves a default in `migration.rs`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SerializedParticle {
+    pub position: [f32; 3],
+    pub velocity: [f32; 3],
+    pub mass: f32,
+    pub charge: f32,
+    pub kind: ParticleKind,
+    /// Bitflags: bit 0 = alive, bit 1 = interacting
+    pub flags: u32,
+    pub temperature: f32,
+}
+
+impl From<&GpuParticle> for SerializedParticle {
+    fn from(p: &GpuParticle) -> Self {
+        Self {
+            position: [p.position[0], p.position[1], p.position[2]],
+            velocity: [p.velocity[0], p.velocity[1], p.velocity[2]],
+            mass: p.position[3],
+            charge: p.velocity[3],
+            kind: ParticleKind::from_u32(p.kind).unwrap_or(FALLBACK_KIND),
+            flags: p.flags,
+            temperature: p.temperature,
+        }
+    }
+}
+
+impl From<&SerializedParticle> for GpuParticle {
+    fn from(p: &SerializedParticle) -> Self {
+        Self {
+            position: [p.position[0], p.position[1], p.position[2], p.mass],
+            velocity: [p.velocity[0], p.velocity[1], p.velocity[2], p.charge],
+            kind: p.kind as u32,
+            flags: p.flags,
+            temperature: p.temperature,
+            _pad: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Any f32 bit pattern, including NaNs, infinities and subnormals
+    fn any_f32() -> impl Strategy<Value = f32> {
+        any::<u32>().prop_map(f32::from_bits)
+    }
+
+    fn any_gpu_particle() -> impl Strategy<Value = GpuParticle> {
+        (
+            prop::array::uniform4(any_f32()),
+            prop::array::uniform4(any_f32()),
+            prop::sample::select(ParticleKind::ALL.to_vec()),
+            any::<u32>(),
+            any_f32(),
+        )
+            .prop_map(|(position, velocity, kind, flags, temperature)| GpuParticle {
+                position,
+                velocity,
+                kind: kind as u32,
+                flags,
+                temperature,
+                _pad: 0.0,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_gpu_round_trip_is_bit_identical(p in any_gpu_particle()) {
+            let restored = GpuParticle::from(&SerializedParticle::from(&p));
+            prop_assert_eq!(bytemuck::bytes_of(&restored), bytemuck::bytes_of(&p));
+        }
+    }
+
+    #[test]
+    fn test_unknown_kind_falls_back() {
+        let mut p = GpuParticle::new([0.0; 3], [0.0; 3], 2.0, -1.0, ParticleKind::Iron);
+        p.kind = 999;
+        let s = SerializedParticle::from(&p);
+        assert_eq!(s.kind, FALLBACK_KIND);
+        assert_eq!(s.mass, 2.0);
+        assert_eq!(s.charge, -1.0);
+    }
+}
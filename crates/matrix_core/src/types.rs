@@ -44,43 +44,12 @@ impl GpuParticle {
         [self.velocity[0], self.velocity[1], self.velocity[2]]
     }
 
-    pub fn is_alive(&self) -> bool {
-        self.flags & 1 != 0
-    }
-}
-
-/// Serializable version of GpuParticle (GpuParticle uses Pod which prevents Serialize)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SerializedParticle {
-    pub position: [f32; 4],
-    pub velocity: [f32; 4],
-    pub kind: u32,
-    pub flags: u32,
-    pub temperature: f32,
-}
-
-impl From<&GpuParticle> for SerializedParticle {
-    fn from(p: &GpuParticle) -> Self {
-        Self {
-            position: p.position,
-            velocity: p.velocity,
-            kind: p.kind,
-            flags: p.flags,
-            temperature: p.temperature,
-        }
+    pub fn charge(&self) -> f32 {
+        self.velocity[3]
     }
-}
 
-impl From<&SerializedParticle> for GpuParticle {
-    fn from(p: &SerializedParticle) -> Self {
-        Self {
-            position: p.position,
-            velocity: p.velocity,
-            kind: p.kind,
-            flags: p.flags,
-            temperature: p.temperature,
-            _pad: 0.0,
-        }
+    pub fn is_alive(&self) -> bool {
+        self.flags & 1 != 0
     }
 }
 
@@ -109,9 +78,35 @@ pub enum ParticleKind {
     Iron = 25,
     // Cosmic structures
     DarkMatter = 100,
+    BlackHole = 101,
 }
 
 impl ParticleKind {
+    /// Every particle kind, in discriminant order
+    pub const ALL: [ParticleKind; 16] = [
+        Self::UpQuark,
+        Self::DownQuark,
+        Self::Electron,
+        Self::Neutrino,
+        Self::Photon,
+        Self::Gluon,
+        Self::Proton,
+        Self::Neutron,
+        Self::Hydrogen,
+        Self::Helium,
+        Self::Carbon,
+        Self::Nitrogen,
+        Self::Oxygen,
+        Self::Iron,
+        Self::DarkMatter,
+        Self::BlackHole,
+    ];
+
+    /// Kind for a `GpuParticle::kind` index (None for unknown indices)
+    pub fn from_u32(kind: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| *k as u32 == kind)
+    }
+
     /// Get the color for rendering this particle type [r, g, b, a]
     pub fn color(&self) -> [f32; 4] {
         match self {
@@ -130,6 +125,18 @@ impl ParticleKind {
             Self::Oxygen => [0.2, 0.6, 1.0, 1.0],         // Light blue
             Self::Iron => [0.7, 0.4, 0.2, 1.0],           // Brown
             Self::DarkMatter => [0.1, 0.0, 0.2, 0.15],    // Very faint purple
+            Self::BlackHole => [0.02, 0.0, 0.04, 1.0],    // Near black
+        }
+    }
+
+    /// Electric charge in units of the elementary charge (whole atoms are neutral)
+    pub fn charge(&self) -> f32 {
+        match self {
+            Self::UpQuark => 2.0 / 3.0,
+            Self::DownQuark => -1.0 / 3.0,
+            Self::Electron => -1.0,
+            Self::Proton => 1.0,
+            _ => 0.0,
         }
     }
 
@@ -147,10 +154,41 @@ impl ParticleKind {
             Self::Oxygen => 16.0,
             Self::Iron => 56.0,
             Self::DarkMatter => 10.0,
+            // Thousands of times a particle: enough to pull in its surroundings
+            Self::BlackHole => 5000.0,
         }
     }
 }
 
+/// Glow of matter at each log10 temperature (K): dim when cold, red, yellow, then
+/// white-blue from 10⁴ K up. Linear in between.
+const TEMPERATURE_RAMP: [(f32, [f32; 3]); 6] = [
+    (0.0, [0.3, 0.12, 0.1]),
+    (2.0, [0.5, 0.2, 0.1]),
+    (3.0, [0.9, 0.3, 0.1]),
+    (3.5, [1.0, 0.7, 0.3]),
+    (3.8, [1.0, 0.95, 0.8]),
+    (4.0, [0.8, 0.9, 1.0]),
+];
+
+/// Blackbody-ish color [r, g, b, a] of a particle at `temperature` (K), multiplied into
+/// its kind's color when rendering
+pub fn temperature_to_color(temperature: f32) -> [f32; 4] {
+    let t = temperature.max(1.0).log10();
+    let (first, last) = (TEMPERATURE_RAMP[0], TEMPERATURE_RAMP[TEMPERATURE_RAMP.len() - 1]);
+    let rgb = if t <= first.0 {
+        first.1
+    } else if t >= last.0 {
+        last.1
+    } else {
+        let i = TEMPERATURE_RAMP.iter().position(|(at, _)| *at > t).unwrap_or(1);
+        let ((t0, c0), (t1, c1)) = (TEMPERATURE_RAMP[i - 1], TEMPERATURE_RAMP[i]);
+        let f = (t - t0) / (t1 - t0);
+        std::array::from_fn(|k| c0[k] + (c1[k] - c0[k]) * f)
+    };
+    [rgb[0], rgb[1], rgb[2], 1.0]
+}
+
 /// Universe phase enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UniversePhase {
@@ -182,3 +220,35 @@ impl UniversePhase {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temperature_to_color_anchors() {
+        let brightness = |c: [f32; 4]| c[0] + c[1] + c[2];
+        // Plasma is white-blue, however hot
+        let plasma = temperature_to_color(1e10);
+        assert_eq!(plasma, temperature_to_color(1e4));
+        assert!(plasma[2] > plasma[0]);
+        // A Sun-like 6000 K is yellowish white, 1000 K red, the CMB dim
+        let sun = temperature_to_color(6000.0);
+        assert!(sun[0] > sun[2] && sun[1] > 0.85);
+        let red = temperature_to_color(1000.0);
+        assert_eq!(red, [0.9, 0.3, 0.1, 1.0]);
+        let cmb = temperature_to_color(2.7);
+        assert!(cmb[0] > cmb[2]);
+        assert_eq!(temperature_to_color(0.0), temperature_to_color(1.0));
+        assert!(brightness(cmb) < brightness(red) && brightness(red) < brightness(sun));
+
+        // Cooling only ever dims or reddens
+        let mut last = temperature_to_color(1e4);
+        for t in (0..=40).rev().map(|i| 10f32.powf(i as f32 / 10.0)) {
+            let c = temperature_to_color(t);
+            assert!(c[2] <= last[2] + 1e-6, "bluer at {t} K");
+            assert_eq!(c[3], 1.0);
+            last = c;
+        }
+    }
+}
@@ -1,11 +1,22 @@
-use bevy::render::renderer::RenderDevice;
+use bevy::render::renderer::{RenderDevice, RenderQueue};
 use bevy::render::render_resource::*;
 use matrix_core::GpuParticle;
+use std::sync::{Arc, Mutex};
+
+/// Progress of an asynchronous buffer mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MapState {
+    Idle,
+    Pending,
+    Ready,
+    Failed,
+}
 
 /// Staging buffer for reading particles back from GPU to CPU
 pub struct ReadbackBuffer {
     pub staging: Buffer,
     pub size: u64,
+    state: Arc<Mutex<MapState>>,
 }
 
 impl ReadbackBuffer {
@@ -17,6 +28,68 @@ impl ReadbackBuffer {
             usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
-        Self { staging, size }
+        Self {
+            staging,
+            size,
+            state: Arc::new(Mutex::new(MapState::Idle)),
+        }
+    }
+
+    /// True while a copy/map started by `start` hasn't been collected yet
+    pub fn is_busy(&self) -> bool {
+        *self.state.lock().unwrap() != MapState::Idle
+    }
+
+    /// Copy `src` into the staging buffer and begin mapping it. Does not block.
+    pub fn start(&self, device: &RenderDevice, queue: &RenderQueue, src: &Buffer) {
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("readback_encoder"),
+        });
+        encoder.copy_buffer_to_buffer(src, 0, &self.staging, 0, self.size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        *self.state.lock().unwrap() = MapState::Pending;
+        let state = self.state.clone();
+        device.map_buffer(&self.staging.slice(..), MapMode::Read, move |result| {
+            *state.lock().unwrap() = if result.is_ok() {
+                MapState::Ready
+            } else {
+                MapState::Failed
+            };
+        });
+    }
+
+    /// Poll the device without blocking.
+    /// Returns `None` while the mapping is still in flight (or nothing was started),
+    /// `Some(Ok(particles))` once the data is available, `Some(Err(()))` if mapping failed.
+    pub fn try_read(&self, device: &RenderDevice) -> Option<Result<Vec<GpuParticle>, ()>> {
+        device.poll(Maintain::Poll);
+        self.collect()
+    }
+
+    /// Like `try_read`, but waits for the GPU to finish (tests / shutdown only)
+    pub fn read_blocking(&self, device: &RenderDevice) -> Option<Result<Vec<GpuParticle>, ()>> {
+        device.poll(Maintain::Wait);
+        self.collect()
+    }
+
+    fn collect(&self) -> Option<Result<Vec<GpuParticle>, ()>> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            MapState::Idle | MapState::Pending => None,
+            MapState::Failed => {
+                *state = MapState::Idle;
+                Some(Err(()))
+            }
+            MapState::Ready => {
+                let particles = {
+                    let view = self.staging.slice(..).get_mapped_range();
+                    bytemuck::cast_slice::<u8, GpuParticle>(&view).to_vec()
+                };
+                self.staging.unmap();
+                *state = MapState::Idle;
+                Some(Ok(particles))
+            }
+        }
     }
 }
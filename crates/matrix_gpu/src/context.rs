@@ -1,6 +1,7 @@
-use bevy::render::renderer::RenderDevice;
+use bevy::render::renderer::{RenderAdapter, RenderDevice, RenderQueue};
 use bevy::render::render_resource::*;
 use matrix_core::GpuParticle;
+use matrix_core::constants::WORKGROUP_SIZE;
 
 /// Simulation parameters sent to GPU as uniform buffer
 #[repr(C)]
@@ -174,6 +175,12 @@ impl GpuContext {
         }
     }
 
+    /// Overwrite the particle data in place (same particle count only)
+    pub fn upload(&mut self, queue: &RenderQueue, particles: &[GpuParticle]) {
+        debug_assert_eq!(particles.len() as u32, self.particle_count);
+        queue.write_buffer(self.current_read_buffer(), 0, bytemuck::cast_slice(particles));
+    }
+
     /// Get the buffer that has the latest particle data
     pub fn current_read_buffer(&self) -> &Buffer {
         if self.current_buffer == 0 {
@@ -183,3 +190,22 @@ impl GpuContext {
         }
     }
 }
+
+/// Whether the adapter can run the N-body compute shader at all
+pub fn is_supported(adapter: &RenderAdapter, device: &RenderDevice) -> bool {
+    let compute = adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::COMPUTE_SHADERS);
+    let limits = device.limits();
+    compute
+        && limits.max_storage_buffers_per_shader_stage >= 2
+        && limits.max_compute_workgroup_size_x >= WORKGROUP_SIZE
+        && limits.max_compute_invocations_per_workgroup >= WORKGROUP_SIZE
+}
+
+/// Whether `particle_count` particles fit in a single storage buffer binding
+pub fn fits_in_storage(device: &RenderDevice, particle_count: usize) -> bool {
+    let bytes = (std::mem::size_of::<GpuParticle>() * particle_count) as u64;
+    bytes <= device.limits().max_storage_buffer_binding_size as u64
+}
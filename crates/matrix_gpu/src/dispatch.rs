@@ -34,7 +34,7 @@ pub fn dispatch_nbody(
         };
         pass.set_bind_group(0, bind_group, &[]);
 
-        let workgroups = (ctx.particle_count + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+        let workgroups = ctx.particle_count.div_ceil(WORKGROUP_SIZE);
         pass.dispatch_workgroups(workgroups, 1, 1);
     }
 
@@ -43,3 +43,106 @@ pub fn dispatch_nbody(
     // Flip ping-pong
     ctx.current_buffer = 1 - ctx.current_buffer;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffers::ReadbackBuffer;
+    use bevy::render::renderer::WgpuWrapper;
+    use matrix_core::constants::NEAR_FIELD_SOFTENING;
+    use matrix_core::{GpuParticle, ParticleKind};
+    use matrix_physics::forces::near_field_gravity;
+    use matrix_physics::integrator::euler_step;
+    use std::sync::Arc;
+
+    /// Device/queue without a window; None when no compute-capable adapter exists
+    fn headless_device() -> Option<(RenderDevice, RenderQueue)> {
+        let instance = wgpu::Instance::default();
+        let adapter = bevy::tasks::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+        )?;
+        if !adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        {
+            return None;
+        }
+        let (device, queue) = bevy::tasks::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: adapter.limits(),
+                memory_hints: Default::default(),
+            },
+            None,
+        ))
+        .ok()?;
+        Some((
+            RenderDevice::from(device),
+            RenderQueue(Arc::new(WgpuWrapper::new(queue))),
+        ))
+    }
+
+    /// Reference step on the CPU: matrix_physics' direct gravity from every other live
+    /// particle, then the simulation's explicit Euler step
+    fn step_cpu(particles: &[GpuParticle], params: &SimParams) -> Vec<GpuParticle> {
+        let accel: Vec<[f32; 3]> = (0..particles.len())
+            .map(|i| {
+                let others: Vec<usize> = (0..particles.len())
+                    .filter(|&j| j != i && particles[j].is_alive())
+                    .collect();
+                near_field_gravity(particles[i].pos(), &others, particles, params.gravity_scale)
+            })
+            .collect();
+        let mut out = particles.to_vec();
+        euler_step(&mut out, &accel, params.dt);
+        out
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn gpu_step_matches_cpu() {
+        let (device, queue) = headless_device().expect("no compute-capable GPU adapter");
+
+        // Deterministic shell of 300 particles
+        let particles: Vec<GpuParticle> = (0..300)
+            .map(|i| {
+                let t = i as f32 * 0.37;
+                let r = 5.0 + (i % 17) as f32;
+                GpuParticle::new(
+                    [r * t.cos(), (i % 11) as f32 - 5.0, r * t.sin()],
+                    [-t.sin(), 0.0, t.cos()],
+                    1.0 + (i % 3) as f32,
+                    0.0,
+                    ParticleKind::Hydrogen,
+                )
+            })
+            .collect();
+        let params = SimParams {
+            dt: 0.01,
+            softening: NEAR_FIELD_SOFTENING,
+            gravity_scale: 0.5,
+            particle_count: particles.len() as u32,
+            scale_factor: 1.0,
+            // The CPU applies expansion outside the integrator
+            hubble: 0.0,
+            _pad1: 0.0,
+            _pad2: 0.0,
+        };
+
+        let mut ctx = GpuContext::new(&device, &particles, &params);
+        dispatch_nbody(&device, &queue, &mut ctx, &params);
+        let readback = ReadbackBuffer::new(&device, particles.len());
+        readback.start(&device, &queue, ctx.current_read_buffer());
+        let gpu = readback.read_blocking(&device).unwrap().unwrap();
+        let cpu = step_cpu(&particles, &params);
+
+        for (g, c) in gpu.iter().zip(&cpu) {
+            for k in 0..3 {
+                assert!((g.position[k] - c.position[k]).abs() < 1e-3 * (1.0 + c.position[k].abs()));
+                assert!((g.velocity[k] - c.velocity[k]).abs() < 1e-3 * (1.0 + c.velocity[k].abs()));
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Near-field neighbor search: building the spatial hash over an expanding Big Bang, then
+//! the K nearest neighbors of every particle, as each CPU gravity step does
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use matrix_core::constants::NEAR_FIELD_K;
+use matrix_core::{GpuParticle, SimConfig};
+use matrix_physics::forces::SpatialHash;
+use matrix_physics::particle;
+use rand::SeedableRng;
+
+const PARTICLE_COUNTS: [u32; 3] = [10_000, 50_000, 100_000];
+/// How long the Big Bang coasts before it is measured. A fresh one is a cube 0.02 across,
+/// all in one hash cell; this spreads it into a ball like the first steps do.
+const COAST: f32 = 2.0;
+
+fn big_bang(particle_count: u32) -> Vec<GpuParticle> {
+    let config = SimConfig {
+        particle_count,
+        ..SimConfig::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+    let mut particles = particle::generate_big_bang(&config, &mut rng);
+    for p in &mut particles {
+        for k in 0..3 {
+            p.position[k] += p.velocity[k] * COAST;
+        }
+    }
+    particles
+}
+
+/// Cell size giving ~24 particles per cell, as `UniverseState` picks it
+fn cell_size(particles: &[GpuParticle]) -> f32 {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in particles {
+        for k in 0..3 {
+            min[k] = min[k].min(p.position[k]);
+            max[k] = max[k].max(p.position[k]);
+        }
+    }
+    let avg_range = (0..3).map(|k| (max[k] - min[k]).max(1.0)).sum::<f32>() / 3.0;
+    avg_range / (particles.len() as f32 / 24.0).cbrt().max(1.0)
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spatial_hash_build");
+    for count in PARTICLE_COUNTS {
+        let particles = big_bang(count);
+        let size = cell_size(&particles);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &particles, |b, particles| {
+            b.iter(|| SpatialHash::build(black_box(particles), size))
+        });
+    }
+    group.finish();
+}
+
+fn nearest_neighbors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nearest_neighbors");
+    group.sample_size(10);
+    for count in PARTICLE_COUNTS {
+        let particles = big_bang(count);
+        let hash = SpatialHash::build(&particles, cell_size(&particles));
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &particles, |b, particles| {
+            b.iter(|| {
+                particles
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| hash.nearest_neighbors(p.pos(), i, particles, NEAR_FIELD_K).len())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build, nearest_neighbors);
+criterion_main!(benches);
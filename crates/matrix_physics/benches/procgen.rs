@@ -0,0 +1,46 @@
+//! Region generation (the whole 512-region grid) and the star systems of the densest
+//! region, at a few universe ages
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use matrix_core::{Region, SimConfig};
+use matrix_physics::procgen::{generate_regions, generate_stellar_detail};
+
+/// Ages (Gyr): cosmic dawn, the peak of star formation, today
+const AGES: [f64; 3] = [1.0, 3.5, 13.8];
+
+fn densest(regions: &[Region]) -> &Region {
+    regions
+        .iter()
+        .max_by(|a, b| a.density.total_cmp(&b.density))
+        .expect("the region grid is never empty")
+}
+
+fn regions(c: &mut Criterion) {
+    let config = SimConfig::default();
+    let mut group = c.benchmark_group("generate_regions");
+    for age in AGES {
+        group.bench_with_input(BenchmarkId::from_parameter(age), &age, |b, &age| {
+            b.iter(|| generate_regions(black_box(&config), age))
+        });
+    }
+    group.finish();
+}
+
+fn stellar_detail(c: &mut Criterion) {
+    let config = SimConfig::default();
+    let mut group = c.benchmark_group("generate_stellar_detail");
+    group.sample_size(20);
+    for age in AGES {
+        let regions = generate_regions(&config, age);
+        let region = densest(&regions);
+        group.bench_with_input(BenchmarkId::from_parameter(age), &age, |b, &age| {
+            b.iter(|| generate_stellar_detail(black_box(region), age))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, regions, stellar_detail);
+criterion_main!(benches);
@@ -23,7 +23,8 @@ pub fn scale_factor(age_gyr: f64) -> f64 {
 /// T(t) = T_0 / a(t) where T_0 = 2.725 K today
 pub fn cosmic_temperature(age_gyr: f64) -> f64 {
     let a = scale_factor(age_gyr);
-    if a < 1e-10 {
+    // `scale_factor` floors the Big Bang itself at 0.001
+    if age_gyr <= 0.0 || a < 1e-10 {
         return 1e12; // Quark-gluon plasma temperature
     }
     2.725 / a
@@ -68,14 +69,22 @@ pub fn chemical_composition(age_gyr: f64) -> [f64; 3] {
     [h, he, metals]
 }
 
-/// Estimate number of stars in a region based on density and age
-pub fn estimate_stars(density_ratio: f64, region_volume_mpc3: f64, age_gyr: f64) -> u64 {
+/// Density of baryons (what forms stars) relative to the cosmic mean, for a region whose
+/// total density is `density_ratio` and whose matter is `dark_fraction` dark, in a universe
+/// that is `mean_dark_fraction` dark overall
+pub fn baryonic_density(density_ratio: f64, dark_fraction: f64, mean_dark_fraction: f64) -> f64 {
+    density_ratio * (1.0 - dark_fraction).max(0.0) / (1.0 - mean_dark_fraction).max(f64::EPSILON)
+}
+
+/// Estimate number of stars in a region based on its baryonic density (`baryonic_density`)
+/// and age: dark matter forms no stars
+pub fn estimate_stars(baryonic_density_ratio: f64, region_volume_mpc3: f64, age_gyr: f64) -> u64 {
     // Integrate star formation rate over time, scaled by density
     // SFR gives solar masses per year per Mpc^3
     // Multiply by age in years to get total stellar mass formed per Mpc^3
     // Divide by ~average star mass (~1 solar mass) to get star count
     let sfr = star_formation_rate(age_gyr);
-    let stars_per_mpc3 = sfr * age_gyr * 1e9 * density_ratio;
+    let stars_per_mpc3 = sfr * age_gyr * 1e9 * baryonic_density_ratio;
     let n = stars_per_mpc3 * region_volume_mpc3;
     n.max(0.0) as u64
 }
@@ -115,10 +124,98 @@ pub fn planet_surface_temp(star_luminosity_solar: f64, orbital_radius_au: f64) -
     278.0 * star_luminosity_solar.powf(0.25) / r.sqrt()
 }
 
+/// Orbital radius (AU) around a 1 M☉ star within which a planet has been spun down into
+/// tidal lock: the despinning time grows as a⁶ / M², so at a given age the radius goes as M^⅓
+pub const TIDAL_LOCK_RADIUS_AU: f64 = 0.35;
+
+/// Whether a planet `orbital_radius_au` from a star of `star_mass_solar` always shows it
+/// the same face (close-in planets of M dwarfs nearly always do)
+pub fn is_tidally_locked(star_mass_solar: f64, orbital_radius_au: f64) -> bool {
+    orbital_radius_au < TIDAL_LOCK_RADIUS_AU * star_mass_solar.max(0.0).cbrt()
+}
+
+/// Share of the day side's heat carried round to the night side of a tidally locked
+/// planet, with and without an atmosphere
+const LOCKED_HEAT_TRANSPORT: (f64, f64) = (0.3, 0.02);
+
+/// Temperatures (K) across a tidally locked planet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LockedTemperatures {
+    /// Mean of the hemisphere facing the star
+    pub day: f64,
+    /// Mean of the hemisphere facing away
+    pub night: f64,
+    /// The twilight ring between them: the planet's `surface_temp`
+    pub terminator: f64,
+}
+
+/// Temperatures of a tidally locked planet with the equilibrium temperature `equilibrium`
+/// (`planet_surface_temp`). Together the hemispheres radiate what a turning planet would
+/// (day⁴ + night⁴ = 2·T⁴), the night side the share its atmosphere carries over; the
+/// terminator lies midway.
+pub fn locked_temperatures(equilibrium: f64, has_atmosphere: bool) -> LockedTemperatures {
+    let transport = if has_atmosphere { LOCKED_HEAT_TRANSPORT.0 } else { LOCKED_HEAT_TRANSPORT.1 };
+    let day = equilibrium * (2.0 - transport).powf(0.25);
+    let night = equilibrium * transport.powf(0.25);
+    LockedTemperatures { day, night, terminator: (day + night) / 2.0 }
+}
+
+/// `locked_temperatures` of a locked planet, from its terminator (`Planet::surface_temp`)
+pub fn locked_temperatures_from_terminator(terminator: f64, has_atmosphere: bool) -> LockedTemperatures {
+    let unit = locked_temperatures(1.0, has_atmosphere);
+    let scale = terminator / unit.terminator;
+    LockedTemperatures {
+        day: unit.day * scale,
+        night: unit.night * scale,
+        terminator,
+    }
+}
+
+/// Surface temperature range (K) counted as the habitable zone
+pub const HABITABLE_TEMP_MIN: f64 = 240.0;
+pub const HABITABLE_TEMP_MAX: f64 = 400.0;
+
+/// Inner and outer habitable-zone radius (AU): `planet_surface_temp` inverted,
+/// r = (278 · L^0.25 / T)², at `HABITABLE_TEMP_MAX` and `HABITABLE_TEMP_MIN`
+pub fn habitable_zone_radii(star_luminosity_solar: f64) -> (f64, f64) {
+    let radius_at = |temp: f64| (278.0 * star_luminosity_solar.max(0.0).powf(0.25) / temp).powi(2);
+    (radius_at(HABITABLE_TEMP_MAX), radius_at(HABITABLE_TEMP_MIN))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_tidal_locking_threshold() {
+        // Mercury turns; the habitable zone of a 0.3 M☉ M dwarf (~0.1 AU) is locked
+        assert!(!is_tidally_locked(1.0, 0.39));
+        assert!(is_tidally_locked(1.0, 0.3));
+        assert!(is_tidally_locked(0.3, 0.1));
+        assert!(!is_tidally_locked(0.3, 0.3));
+        // Heavier stars lock further out
+        assert!(is_tidally_locked(2.0, 0.4) && !is_tidally_locked(1.0, 0.4));
+        assert!(!is_tidally_locked(0.0, 0.01));
+    }
+
+    #[test]
+    fn test_locked_temperature_split() {
+        for has_atmosphere in [true, false] {
+            let t = locked_temperatures(300.0, has_atmosphere);
+            assert!(t.night < t.terminator && t.terminator < t.day);
+            assert!(t.day > 300.0 && t.night < 300.0);
+            // The hemispheres radiate what a turning planet at 300 K would
+            assert!((t.day.powi(4) + t.night.powi(4) - 2.0 * 300f64.powi(4)).abs() < 1e-3 * 300f64.powi(4));
+            let back = locked_temperatures_from_terminator(t.terminator, has_atmosphere);
+            assert!((back.day - t.day).abs() < 1e-9 && (back.night - t.night).abs() < 1e-9);
+        }
+        // An atmosphere evens the sides out
+        let air = locked_temperatures(300.0, true);
+        let bare = locked_temperatures(300.0, false);
+        assert!(air.day - air.night < bare.day - bare.night);
+        assert!(bare.night < 120.0);
+    }
+
     #[test]
     fn test_scale_factor_monotonic() {
         let a1 = scale_factor(1.0);
@@ -165,4 +262,27 @@ mod tests {
         let temp_nep = planet_surface_temp(1.0, 30.0);
         assert!(temp_nep < 100.0);
     }
+
+    #[test]
+    fn test_habitable_zone_inverts_surface_temp() {
+        for lum in [0.01, 0.5, 1.0, 10.0, 1000.0] {
+            let (inner, outer) = habitable_zone_radii(lum);
+            assert!(inner < outer);
+            assert!((planet_surface_temp(lum, inner) - HABITABLE_TEMP_MAX).abs() < 1e-6);
+            assert!((planet_surface_temp(lum, outer) - HABITABLE_TEMP_MIN).abs() < 1e-6);
+            let mid = (inner + outer) / 2.0;
+            let temp = planet_surface_temp(lum, mid);
+            assert!((HABITABLE_TEMP_MIN..=HABITABLE_TEMP_MAX).contains(&temp));
+        }
+    }
+
+    #[test]
+    fn test_habitable_zone_sun() {
+        // Sun: roughly 0.48–1.34 AU in this model, Earth inside
+        let (inner, outer) = habitable_zone_radii(1.0);
+        assert!(inner < 1.0 && outer > 1.0, "HZ = {inner:.2}..{outer:.2}");
+        // Brighter stars push the zone outward (∝ √L)
+        let (inner_100, _) = habitable_zone_radii(100.0);
+        assert!((inner_100 / inner - 10.0).abs() < 1e-9);
+    }
 }
@@ -1,4 +1,4 @@
-use matrix_core::constants::{G, NEAR_FIELD_SOFTENING, SOFTENING};
+use matrix_core::constants::{ELECTROMAGNETIC_SOFTENING, G, NEAR_FIELD_SOFTENING, SOFTENING};
 use matrix_core::GpuParticle;
 use std::collections::HashMap;
 
@@ -26,20 +26,24 @@ pub fn gravity_acceleration(
 pub struct SpatialHash {
     pub cells: HashMap<(i32, i32, i32), Vec<usize>>,
     pub cell_size: f32,
+    /// Particles in all cells
+    len: usize,
 }
 
 impl SpatialHash {
     /// Build spatial hash from alive particles
     pub fn build(particles: &[GpuParticle], cell_size: f32) -> Self {
         let mut cells: HashMap<(i32, i32, i32), Vec<usize>> = HashMap::new();
+        let mut len = 0;
         for (i, p) in particles.iter().enumerate() {
             if !p.is_alive() {
                 continue;
             }
             let key = Self::cell_key(p.position[0], p.position[1], p.position[2], cell_size);
             cells.entry(key).or_default().push(i);
+            len += 1;
         }
-        Self { cells, cell_size }
+        Self { cells, cell_size, len }
     }
 
     #[inline]
@@ -53,6 +57,9 @@ impl SpatialHash {
 
     /// Find K nearest neighbors for a particle at given position
     /// Returns indices sorted by distance (closest first)
+    ///
+    /// Searches shells of cells outwards until K candidates lie closer than any cell not
+    /// yet searched, so the result is exact however sparse the neighborhood is
     pub fn nearest_neighbors(
         &self,
         pos: [f32; 3],
@@ -61,39 +68,105 @@ impl SpatialHash {
         k: usize,
     ) -> Vec<usize> {
         let key = Self::cell_key(pos[0], pos[1], pos[2], self.cell_size);
-
-        // Collect candidates from own cell + 26 neighbors
         let mut candidates: Vec<(usize, f32)> = Vec::with_capacity(128);
-        for dx in -1..=1 {
-            for dy in -1..=1 {
-                for dz in -1..=1 {
-                    let neighbor_key = (key.0 + dx, key.1 + dy, key.2 + dz);
-                    if let Some(indices) = self.cells.get(&neighbor_key) {
-                        for &idx in indices {
-                            if idx == exclude_idx {
-                                continue;
+        let mut seen = 0usize;
+        let mut visit = |indices: &[usize], candidates: &mut Vec<(usize, f32)>| {
+            seen += indices.len();
+            for &idx in indices {
+                if idx == exclude_idx {
+                    continue;
+                }
+                let p = &particles[idx];
+                let ddx = p.position[0] - pos[0];
+                let ddy = p.position[1] - pos[1];
+                let ddz = p.position[2] - pos[2];
+                candidates.push((idx, ddx * ddx + ddy * ddy + ddz * ddz));
+            }
+            seen
+        };
+
+        let mut ring = 0i32;
+        loop {
+            let shell_cells = if ring == 0 { 1 } else { 24 * ring as usize * ring as usize + 2 };
+            let mut all_seen = false;
+            if shell_cells > self.cells.len() {
+                // Cheaper to take every cell not searched yet than to walk mostly empty shells
+                for (cell, indices) in &self.cells {
+                    let ring_of_cell = (cell.0 - key.0)
+                        .abs()
+                        .max((cell.1 - key.1).abs())
+                        .max((cell.2 - key.2).abs());
+                    if ring_of_cell >= ring {
+                        visit(indices, &mut candidates);
+                    }
+                }
+                all_seen = true;
+            } else {
+                for dx in -ring..=ring {
+                    for dy in -ring..=ring {
+                        // Inside the shell only its two z faces are new
+                        let on_face = dx.abs() == ring || dy.abs() == ring;
+                        let z_step = if on_face { 1 } else { 2 * ring };
+                        let mut dz = -ring;
+                        while dz <= ring {
+                            let cell = (key.0 + dx, key.1 + dy, key.2 + dz);
+                            if let Some(indices) = self.cells.get(&cell) {
+                                all_seen = visit(indices, &mut candidates) >= self.len;
                             }
-                            let p = &particles[idx];
-                            let ddx = p.position[0] - pos[0];
-                            let ddy = p.position[1] - pos[1];
-                            let ddz = p.position[2] - pos[2];
-                            let dist_sq = ddx * ddx + ddy * ddy + ddz * ddz;
-                            candidates.push((idx, dist_sq));
+                            dz += z_step;
                         }
                     }
                 }
             }
+            if all_seen {
+                break;
+            }
+            // Particles in cells beyond this shell are at least `ring` cells away
+            let reach = ring as f32 * self.cell_size;
+            if ring > 0 && candidates.iter().filter(|c| c.1 <= reach * reach).count() >= k {
+                break;
+            }
+            ring += 1;
         }
 
         // Partial sort: only need K closest
+        let by_distance =
+            |a: &(usize, f32), b: &(usize, f32)| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0));
         if candidates.len() > k {
-            candidates.select_nth_unstable_by(k, |a, b| a.1.partial_cmp(&b.1).unwrap());
+            candidates.select_nth_unstable_by(k, by_distance);
             candidates.truncate(k);
         }
+        candidates.sort_unstable_by(by_distance);
 
         candidates.iter().map(|&(idx, _)| idx).collect()
     }
 
+    /// Indices of particles within `radius` of `pos`, searching as many cells as the
+    /// radius spans (unsorted)
+    pub fn within_radius(&self, pos: [f32; 3], radius: f32, particles: &[GpuParticle]) -> Vec<usize> {
+        let key = Self::cell_key(pos[0], pos[1], pos[2], self.cell_size);
+        let reach = (radius / self.cell_size).ceil().max(1.0) as i32;
+        let radius_sq = radius * radius;
+        let mut found = Vec::new();
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                for dz in -reach..=reach {
+                    let Some(indices) = self.cells.get(&(key.0 + dx, key.1 + dy, key.2 + dz)) else {
+                        continue;
+                    };
+                    found.extend(indices.iter().copied().filter(|&idx| {
+                        let p = &particles[idx];
+                        let ddx = p.position[0] - pos[0];
+                        let ddy = p.position[1] - pos[1];
+                        let ddz = p.position[2] - pos[2];
+                        ddx * ddx + ddy * ddy + ddz * ddz <= radius_sq
+                    }));
+                }
+            }
+        }
+        found
+    }
+
     /// Get the set of cell keys that are "near" a given position (own cell + 26 neighbors)
     pub fn neighbor_cell_keys(&self, pos: [f32; 3]) -> Vec<(i32, i32, i32)> {
         let key = Self::cell_key(pos[0], pos[1], pos[2], self.cell_size);
@@ -138,6 +211,44 @@ pub fn near_field_gravity(
     [ax, ay, az]
 }
 
+/// Near-field Coulomb acceleration of a particle (`charge`, `mass`) from its K nearest
+/// neighbors: like charges repel, opposite ones attract. Zero for neutral or massless particles.
+pub fn near_field_electromagnetic(
+    pos: [f32; 3],
+    charge: f32,
+    mass: f32,
+    neighbors: &[usize],
+    particles: &[GpuParticle],
+    coulomb_strength: f32,
+) -> [f32; 3] {
+    if charge == 0.0 || mass <= 0.0 {
+        return [0.0; 3];
+    }
+    let mut acc = [0.0f32; 3];
+    let soft2 = ELECTROMAGNETIC_SOFTENING * ELECTROMAGNETIC_SOFTENING;
+
+    for &j in neighbors {
+        let p = &particles[j];
+        if p.charge() == 0.0 {
+            continue;
+        }
+        let d = [
+            p.position[0] - pos[0],
+            p.position[1] - pos[1],
+            p.position[2] - pos[2],
+        ];
+        let r2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2] + soft2;
+        let inv_r3 = 1.0 / (r2 * r2.sqrt());
+        // Toward the neighbor when the charges differ in sign
+        let f = -coulomb_strength * charge * p.charge() / mass * inv_r3;
+        for k in 0..3 {
+            acc[k] += f * d[k];
+        }
+    }
+
+    acc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,4 +273,32 @@ mod tests {
         let ratio = a_near[0] / a_far[0];
         assert!((ratio - 4.0).abs() < 0.5); // approximate due to softening
     }
+
+    #[test]
+    fn test_opposite_charges_attract_harder_than_gravity() {
+        use matrix_core::constants::COULOMB_K;
+        use matrix_core::ParticleKind;
+
+        let particle = |x: f32, kind: ParticleKind| {
+            GpuParticle::new([x, 0.0, 0.0], [0.0; 3], kind.default_mass(), kind.charge(), kind)
+        };
+        let electron = particle(0.0, ParticleKind::Electron);
+        let near = [particle(0.2, ParticleKind::Proton), particle(0.2, ParticleKind::Electron)];
+        let field = |j: usize| {
+            near_field_electromagnetic(electron.pos(), electron.charge(), electron.mass(), &[j], &near, COULOMB_K)
+        };
+        let gravity = near_field_gravity(electron.pos(), &[0], &near, 0.5);
+
+        // The proton pulls the electron toward it (+x), far harder than its gravity does
+        let attraction = field(0);
+        assert!(attraction[0] > 0.0);
+        assert!(attraction[0] > 10.0 * gravity[0], "{} vs {}", attraction[0], gravity[0]);
+        // Another electron pushes it away (-x) just as hard
+        let repulsion = field(1);
+        assert!((repulsion[0] + attraction[0]).abs() < 1e-3 * attraction[0]);
+
+        // Neutral particles feel nothing
+        let atom = particle(0.0, ParticleKind::Hydrogen);
+        assert_eq!(near_field_electromagnetic(atom.pos(), atom.charge(), atom.mass(), &[0], &near, COULOMB_K), [0.0; 3]);
+    }
 }
@@ -0,0 +1,141 @@
+use matrix_core::GpuParticle;
+
+/// Explicit Euler: v += a·dt, then x += v·dt
+pub fn euler_step(particles: &mut [GpuParticle], accel: &[[f32; 3]], dt: f32) {
+    for (p, a) in particles.iter_mut().zip(accel) {
+        if !p.is_alive() {
+            continue;
+        }
+        for (k, a) in a.iter().enumerate() {
+            p.velocity[k] += a * dt;
+            p.position[k] += p.velocity[k] * dt;
+        }
+    }
+}
+
+/// Leapfrog, first half: kick with the previous step's acceleration, then drift.
+/// Follow with `leapfrog_kick` using the acceleration at the new positions.
+pub fn leapfrog_kick_drift(particles: &mut [GpuParticle], accel_prev: &[[f32; 3]], dt: f32) {
+    let half = dt * 0.5;
+    for (p, a) in particles.iter_mut().zip(accel_prev) {
+        if !p.is_alive() {
+            continue;
+        }
+        for (k, a) in a.iter().enumerate() {
+            p.velocity[k] += a * half;
+            p.position[k] += p.velocity[k] * dt;
+        }
+    }
+}
+
+/// Leapfrog, second half: kick with the acceleration at the drifted positions
+pub fn leapfrog_kick(particles: &mut [GpuParticle], accel: &[[f32; 3]], dt: f32) {
+    let half = dt * 0.5;
+    for (p, a) in particles.iter_mut().zip(accel) {
+        if !p.is_alive() {
+            continue;
+        }
+        for (k, a) in a.iter().enumerate() {
+            p.velocity[k] += a * half;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::ParticleKind;
+
+    const G: f32 = 1.0;
+
+    /// Pairwise gravity for a tiny system (no softening)
+    fn accelerations(particles: &[GpuParticle]) -> Vec<[f32; 3]> {
+        particles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let mut acc = [0.0f32; 3];
+                for (j, o) in particles.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+                    let d = [
+                        o.position[0] - p.position[0],
+                        o.position[1] - p.position[1],
+                        o.position[2] - p.position[2],
+                    ];
+                    let r2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+                    let f = G * o.mass() / (r2 * r2.sqrt());
+                    acc[0] += f * d[0];
+                    acc[1] += f * d[1];
+                    acc[2] += f * d[2];
+                }
+                acc
+            })
+            .collect()
+    }
+
+    fn total_energy(particles: &[GpuParticle]) -> f64 {
+        let kinetic: f64 = particles
+            .iter()
+            .map(|p| {
+                let v = p.vel();
+                0.5 * p.mass() as f64 * (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]) as f64
+            })
+            .sum();
+        let (a, b) = (&particles[0], &particles[1]);
+        let d = [
+            (a.position[0] - b.position[0]) as f64,
+            (a.position[1] - b.position[1]) as f64,
+            (a.position[2] - b.position[2]) as f64,
+        ];
+        let r = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        kinetic - G as f64 * a.mass() as f64 * b.mass() as f64 / r
+    }
+
+    /// Equal masses at separation 2 with `speed` × the circular orbital speed
+    fn binary(speed: f32) -> Vec<GpuParticle> {
+        let m = 1.0f32;
+        // Circular: v² / r = G m / (2r)²  with r = 1
+        let v = (G * m / 4.0).sqrt() * speed;
+        vec![
+            GpuParticle::new([1.0, 0.0, 0.0], [0.0, v, 0.0], m, 0.0, ParticleKind::Hydrogen),
+            GpuParticle::new([-1.0, 0.0, 0.0], [0.0, -v, 0.0], m, 0.0, ParticleKind::Hydrogen),
+        ]
+    }
+
+    /// Largest relative energy error seen over the run
+    fn energy_drift(speed: f32, leapfrog: bool) -> f64 {
+        let mut particles = binary(speed);
+        let e0 = total_energy(&particles);
+        let dt = 0.02;
+        let mut accel = accelerations(&particles);
+        let mut worst = 0.0f64;
+        // Several orbits (circular period = 2π·r / v ≈ 12.6)
+        for _ in 0..5_000 {
+            if leapfrog {
+                leapfrog_kick_drift(&mut particles, &accel, dt);
+                accel = accelerations(&particles);
+                leapfrog_kick(&mut particles, &accel, dt);
+            } else {
+                euler_step(&mut particles, &accel, dt);
+                accel = accelerations(&particles);
+            }
+            worst = worst.max(((total_energy(&particles) - e0) / e0).abs());
+        }
+        worst
+    }
+
+    #[test]
+    fn test_leapfrog_conserves_energy_better_than_euler() {
+        // Circular orbit, and an eccentric one (pericenter passages stress the integrator)
+        for speed in [1.0, 0.7] {
+            let euler = energy_drift(speed, false);
+            let leapfrog = energy_drift(speed, true);
+            assert!(
+                leapfrog * 10.0 < euler,
+                "speed {speed}: leapfrog drift {leapfrog:e} vs euler drift {euler:e}"
+            );
+        }
+    }
+}
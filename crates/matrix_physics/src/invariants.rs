@@ -0,0 +1,144 @@
+//! Property tests for invariants the physics core must keep whatever the input:
+//! momentum conservation, exact neighbor search, order-free thermodynamics,
+//! monotone planet temperatures and biospheres within their planet's limits
+
+use matrix_core::{AtmosphereType, GpuParticle, ParticleKind, PlanetType};
+use proptest::prelude::*;
+use rand::SeedableRng;
+
+use crate::cosmology::planet_surface_temp;
+use crate::forces::{SpatialHash, near_field_gravity};
+use crate::procgen::{generate_biosphere, max_complexity};
+use crate::thermodynamics::calculate_entropy_and_temperature;
+
+const ATMOSPHERES: [AtmosphereType; 7] = [
+    AtmosphereType::None,
+    AtmosphereType::ThinCO2,
+    AtmosphereType::ThickCO2,
+    AtmosphereType::NitrogenOxygen,
+    AtmosphereType::Hydrogen,
+    AtmosphereType::Methane,
+    AtmosphereType::Exotic,
+];
+
+/// Flight, in the genome's motility codes
+const FLIGHT: u32 = 7;
+
+fn position(extent: f32) -> impl Strategy<Value = [f32; 3]> {
+    prop::array::uniform3(-extent..extent)
+}
+
+/// A particle that may be dead, with any velocity up to `speed` and a non-negative mass
+fn particle(extent: f32, speed: f32) -> impl Strategy<Value = GpuParticle> {
+    (position(extent), prop::array::uniform3(-speed..speed), 0.0f32..100.0, any::<bool>()).prop_map(
+        |(pos, vel, mass, alive)| {
+            let mut p = GpuParticle::new(pos, vel, mass, 0.0, ParticleKind::Proton);
+            p.flags = alive as u32;
+            p
+        },
+    )
+}
+
+fn distance_sq(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let dx = b[0] - a[0];
+    let dy = b[1] - a[1];
+    let dz = b[2] - a[2];
+    dx * dx + dy * dy + dz * dz
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+proptest! {
+    #[test]
+    fn test_pair_gravity_conserves_momentum(
+        a in position(50.0),
+        b in position(50.0),
+        mass_a in 0.01f32..100.0,
+        mass_b in 0.01f32..100.0,
+        strength in 0.1f32..10.0,
+    ) {
+        let pair = [
+            GpuParticle::new(a, [0.0; 3], mass_a, 0.0, ParticleKind::Proton),
+            GpuParticle::new(b, [0.0; 3], mass_b, 0.0, ParticleKind::Proton),
+        ];
+        let acc_a = near_field_gravity(a, &[1], &pair, strength);
+        let acc_b = near_field_gravity(b, &[0], &pair, strength);
+        for k in 0..3 {
+            let push_a = mass_a * acc_a[k];
+            let push_b = mass_b * acc_b[k];
+            prop_assert!(
+                (push_a + push_b).abs() <= 1e-5 * push_a.abs().max(push_b.abs()) + 1e-30,
+                "axis {}: m_a·a_a = {}, m_b·a_b = {}", k, push_a, push_b
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_neighbors_match_brute_force(
+        particles in prop::collection::vec(particle(10.0, 1.0), 1..200),
+        cell_size in 0.1f32..4.0,
+        k in 0usize..40,
+        query in any::<prop::sample::Index>(),
+    ) {
+        let hash = SpatialHash::build(&particles, cell_size);
+        let exclude = query.index(particles.len());
+        let pos = particles[exclude].pos();
+
+        let found = hash.nearest_neighbors(pos, exclude, &particles, k);
+        prop_assert!(!found.contains(&exclude));
+        let found: Vec<f32> = found.iter().map(|&j| distance_sq(pos, particles[j].pos())).collect();
+
+        let mut expected: Vec<f32> = particles
+            .iter()
+            .enumerate()
+            .filter(|&(j, p)| j != exclude && p.is_alive())
+            .map(|(_, p)| distance_sq(pos, p.pos()))
+            .collect();
+        expected.sort_by(f32::total_cmp);
+        expected.truncate(k);
+        prop_assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_entropy_and_temperature_ignore_order(
+        (particles, shuffled) in prop::collection::vec(particle(10.0, 1000.0), 0..200)
+            .prop_flat_map(|v| (Just(v.clone()), Just(v).prop_shuffle())),
+    ) {
+        let (entropy, temperature) = calculate_entropy_and_temperature(&particles);
+        let (entropy_shuffled, temperature_shuffled) = calculate_entropy_and_temperature(&shuffled);
+        prop_assert!(entropy >= 0.0);
+        prop_assert!(temperature >= 0.0);
+        prop_assert!(close(entropy, entropy_shuffled), "{} vs {}", entropy, entropy_shuffled);
+        prop_assert!(close(temperature, temperature_shuffled), "{} vs {}", temperature, temperature_shuffled);
+    }
+
+    #[test]
+    fn test_planet_surface_temp_is_monotone(
+        luminosity in 1e-4f64..1e6,
+        radius in 1e-3f64..1e3,
+        factor in 1.0f64..100.0,
+    ) {
+        let temp = planet_surface_temp(luminosity, radius);
+        prop_assert!(temp > 0.0);
+        prop_assert!(planet_surface_temp(luminosity, radius * factor) <= temp);
+        prop_assert!(planet_surface_temp(luminosity * factor, radius) >= temp);
+    }
+
+    #[test]
+    fn test_biosphere_respects_its_planet(
+        life_age in 0.0f64..13.0,
+        surface_temp in 50.0f64..600.0,
+        planet_type in prop::sample::select(PlanetType::ALL.to_vec()),
+        atmosphere in prop::sample::select(ATMOSPHERES.to_vec()),
+        seed in any::<u64>(),
+    ) {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        let bio = generate_biosphere(life_age, surface_temp, &planet_type, &atmosphere, &mut rng);
+        prop_assert!(bio.complexity <= max_complexity(&planet_type));
+        if matches!(atmosphere, AtmosphereType::None | AtmosphereType::ThinCO2) {
+            prop_assert_ne!(bio.dominant_genome.motility, FLIGHT);
+        }
+    }
+}
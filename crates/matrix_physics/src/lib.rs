@@ -1,6 +1,12 @@
 pub mod cosmology;
 pub mod forces;
+pub mod integrator;
+#[cfg(test)]
+mod invariants;
 pub mod particle;
 pub mod procgen;
 pub mod spacetime;
+pub mod stats;
+pub mod stellar;
+pub mod terrain;
 pub mod thermodynamics;
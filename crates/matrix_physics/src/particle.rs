@@ -1,8 +1,10 @@
-use matrix_core::{GpuParticle, ParticleKind, Region, SimConfig};
+use matrix_core::{Galaxy, GpuParticle, ParticleKind, Region, SimConfig};
 use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
+use super::procgen;
+
 /// Generate the initial particle distribution for the Big Bang
 pub fn generate_big_bang(config: &SimConfig, rng: &mut impl Rng) -> Vec<GpuParticle> {
     let mut particles = Vec::with_capacity(config.particle_count as usize);
@@ -59,14 +61,119 @@ fn create_big_bang_particle(
 
     let mass = kind.default_mass() * rng.gen_range(0.5..1.5f32);
 
-    GpuParticle::new(pos, vel, mass.max(0.001), 0.0, kind)
+    GpuParticle::new(pos, vel, mass.max(0.001), kind.charge(), kind)
+}
+
+/// Offset of a region's particle stream from the region seed
+const PARTICLE_SEED_OFFSET: u64 = 42_000;
+/// Offset of the stream laying out a region's filaments from the region seed
+const FILAMENT_SEED_OFFSET: u64 = 42_001;
+/// Share of a region's particles in the uniform background between the structures
+const BACKGROUND_SHARE: f64 = 0.1;
+/// Gaussian width of the gas around a galaxy, relative to its radius
+const HALO_WIDTH: f64 = 0.5;
+/// Dark matter halos reach further out than the gas that fell into them
+const DARK_HALO_WIDTH: f64 = 0.8;
+/// Gaussian thickness of a filament, relative to the region size
+const FILAMENT_WIDTH: f64 = 0.02;
+
+/// Shares of a region's particles in halos, in filaments and in the uniform background.
+/// Denser regions have more of their matter collapsed into halos; voids keep theirs
+/// strung out along filaments.
+pub fn structure_weights(density: f64) -> [f64; 3] {
+    let halos = (0.25 + 0.2 * density).clamp(0.3, 0.75);
+    [halos, 1.0 - halos - BACKGROUND_SHARE, BACKGROUND_SHARE]
+}
+
+/// Where a region's matter lies: Gaussian halos around its galaxies, 2–4 filaments
+/// spanning the region through them, and a uniform background
+struct RegionStructure {
+    /// Halo centers and widths (the galaxy's radius)
+    halos: Vec<([f64; 3], f64)>,
+    /// Running total of the halos' weights (radius², like the galaxies' share of stars)
+    halo_weights: Vec<f64>,
+    /// Midpoint and half-length vector of each filament
+    filaments: Vec<([f64; 3], [f64; 3])>,
+    weights: [f64; 3],
+    center: [f64; 3],
+    half_size: f64,
+}
+
+impl RegionStructure {
+    fn new(region: &Region, halos: &[Galaxy]) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(FILAMENT_SEED_OFFSET));
+        let half_size = region.size / 2.0;
+        let filaments = (0..rng.gen_range(2..=4usize))
+            .map(|i| {
+                // Through a galaxy where there is one, so the halos sit on the web
+                let through = match halos.get(i % halos.len().max(1)) {
+                    Some(galaxy) => galaxy.position,
+                    None => std::array::from_fn(|k| {
+                        region.center[k] + rng.gen_range(-half_size..half_size) * 0.5
+                    }),
+                };
+                let direction = random_velocity(&mut rng, 1.0);
+                let norm = direction.iter().map(|d| d * d).sum::<f32>().sqrt().max(1e-6);
+                let half_length = std::array::from_fn(|k| direction[k] as f64 / norm as f64 * half_size);
+                (through, half_length)
+            })
+            .collect();
+
+        let mut weights = structure_weights(region.density);
+        if halos.is_empty() {
+            weights[2] += weights[0];
+            weights[0] = 0.0;
+        }
+        Self {
+            halos: halos.iter().map(|g| (g.position, g.radius)).collect(),
+            halo_weights: halos
+                .iter()
+                .scan(0.0, |total, g| {
+                    *total += g.radius * g.radius;
+                    Some(*total)
+                })
+                .collect(),
+            filaments,
+            weights,
+            center: region.center,
+            half_size,
+        }
+    }
+
+    /// One position from the mixture, halos `halo_width` of their radius wide, kept
+    /// within the region
+    fn sample(&self, halo_width: f64, rng: &mut impl Rng) -> [f32; 3] {
+        let roll = rng.gen_range(0.0..1.0);
+        let pos: [f64; 3] = if roll < self.weights[0] {
+            let total = self.halo_weights.last().copied().unwrap_or(0.0);
+            let pick = rng.gen_range(0.0..total.max(f64::MIN_POSITIVE));
+            let i = self.halo_weights.partition_point(|&w| w <= pick).min(self.halos.len() - 1);
+            let (center, radius) = self.halos[i];
+            let sigma = radius * halo_width;
+            std::array::from_fn(|k| center[k] + procgen::gaussian(rng) * sigma)
+        } else if roll < self.weights[0] + self.weights[1] {
+            let (mid, half_length) = self.filaments[rng.gen_range(0..self.filaments.len())];
+            let t = rng.gen_range(-1.0..1.0);
+            let sigma = self.half_size * 2.0 * FILAMENT_WIDTH;
+            std::array::from_fn(|k| mid[k] + half_length[k] * t + procgen::gaussian(rng) * sigma)
+        } else {
+            std::array::from_fn(|k| self.center[k] + rng.gen_range(-self.half_size..self.half_size))
+        };
+        std::array::from_fn(|k| {
+            pos[k].clamp(self.center[k] - self.half_size, self.center[k] + self.half_size) as f32
+        })
+    }
 }
 
 /// Generate particles for a specific region, appropriate for the universe age.
-/// Deterministic: seeded from region.seed + 42_000.
+/// Deterministic: seeded from region.seed + `PARTICLE_SEED_OFFSET`.
 /// Denser regions get more particles. Particle kinds match the current cosmological era.
-pub fn generate_region_particles(region: &Region, age_gyr: f64) -> Vec<GpuParticle> {
-    let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(42_000));
+/// Positions follow the region's structure (`structure_weights`): gas and dark matter
+/// gather in halos around `halos` (the region's galaxies, `procgen::generate_galaxies`)
+/// and along filaments through them, over a thin uniform background.
+/// A region with a central black hole gets it as one heavy particle at rest at its center.
+pub fn generate_region_particles(region: &Region, halos: &[Galaxy], age_gyr: f64) -> Vec<GpuParticle> {
+    let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(PARTICLE_SEED_OFFSET));
     let count = (region.density * 5000.0).clamp(500.0, 10_000.0) as usize;
     let dark_fraction = region.dark_matter.min(0.9);
 
@@ -77,7 +184,7 @@ pub fn generate_region_particles(region: &Region, age_gyr: f64) -> Vec<GpuPartic
     let max_vel = velocity_for_age(age_gyr);
     let temp = temperature_for_age(age_gyr);
 
-    let half_size = region.size as f32 * 0.4; // scatter within 80% of region volume
+    let structure = RegionStructure::new(region, halos);
     let center = [
         region.center[0] as f32,
         region.center[1] as f32,
@@ -89,25 +196,17 @@ pub fn generate_region_particles(region: &Region, age_gyr: f64) -> Vec<GpuPartic
     // Baryonic matter
     for _ in 0..n_baryonic {
         let kind = kinds[rng.gen_range(0..kinds.len())];
-        let pos = [
-            center[0] + rng.gen_range(-half_size..half_size),
-            center[1] + rng.gen_range(-half_size..half_size),
-            center[2] + rng.gen_range(-half_size..half_size),
-        ];
+        let pos = structure.sample(HALO_WIDTH, &mut rng);
         let vel = random_velocity(&mut rng, max_vel);
         let mass = kind.default_mass() * rng.gen_range(0.5..1.5f32);
-        let mut p = GpuParticle::new(pos, vel, mass.max(0.001), 0.0, kind);
+        let mut p = GpuParticle::new(pos, vel, mass.max(0.001), kind.charge(), kind);
         p.temperature = temp;
         particles.push(p);
     }
 
     // Dark matter
     for _ in 0..n_dark {
-        let pos = [
-            center[0] + rng.gen_range(-half_size..half_size),
-            center[1] + rng.gen_range(-half_size..half_size),
-            center[2] + rng.gen_range(-half_size..half_size),
-        ];
+        let pos = structure.sample(DARK_HALO_WIDTH, &mut rng);
         let vel = random_velocity(&mut rng, max_vel * 0.8);
         let mass = ParticleKind::DarkMatter.default_mass() * rng.gen_range(0.5..1.5f32);
         let mut p = GpuParticle::new(pos, vel, mass.max(0.001), 0.0, ParticleKind::DarkMatter);
@@ -115,6 +214,14 @@ pub fn generate_region_particles(region: &Region, age_gyr: f64) -> Vec<GpuPartic
         particles.push(p);
     }
 
+    if region.central_black_hole.is_some() {
+        let mass = ParticleKind::BlackHole.default_mass();
+        let mut p = GpuParticle::new(center, [0.0; 3], mass, 0.0, ParticleKind::BlackHole);
+        // Accretion glow: raised by captures, fades in between
+        p.temperature = 0.0;
+        particles.push(p);
+    }
+
     particles
 }
 
@@ -192,3 +299,51 @@ fn random_velocity(rng: &mut impl Rng, max_vel: f32) -> [f32; 3] {
         speed * phi.cos(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_particles_gather_in_halos() {
+        let regions = procgen::generate_regions(&SimConfig::default(), 13.8);
+        for region in regions.iter().step_by(101) {
+            let galaxies = procgen::generate_galaxies(region);
+            let particles = generate_region_particles(region, &galaxies, 13.8);
+            let again = generate_region_particles(region, &galaxies, 13.8);
+            assert!(particles.iter().zip(&again).all(|(a, b)| a.position == b.position));
+
+            let half = region.size / 2.0;
+            let mut near = 0usize;
+            for p in &particles {
+                let pos = p.pos();
+                assert!((0..3).all(|k| (pos[k] as f64 - region.center[k]).abs() <= half + 1e-3));
+                let in_halo = galaxies.iter().any(|g| {
+                    let d2: f64 = (0..3).map(|k| (pos[k] as f64 - g.position[k]).powi(2)).sum();
+                    d2 < g.radius * g.radius
+                });
+                near += usize::from(in_halo);
+            }
+            // Overlapping halos count their volume twice, which only lowers the contrast
+            let halo_volume: f64 = galaxies
+                .iter()
+                .map(|g| 4.0 / 3.0 * std::f64::consts::PI * g.radius.powi(3))
+                .sum();
+            let halo_density = near as f64 / halo_volume;
+            let mean_density = particles.len() as f64 / region.size.powi(3);
+            assert!(
+                halo_density >= 5.0 * mean_density,
+                "region {} (density {:.2}): {halo_density:.2e} near halos vs {mean_density:.2e}",
+                region.id,
+                region.density
+            );
+        }
+
+        // Voids keep most of their matter in filaments, clusters in halos
+        let [void_halos, void_filaments, _] = structure_weights(0.3);
+        let [cluster_halos, _, background] = structure_weights(3.0);
+        assert!(void_filaments > void_halos && cluster_halos > void_halos);
+        assert!((void_halos + void_filaments + BACKGROUND_SHARE - 1.0).abs() < 1e-12);
+        assert_eq!(background, BACKGROUND_SHARE);
+    }
+}
@@ -4,63 +4,153 @@ use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-use super::cosmology;
+use super::{cosmology, terrain};
 
-/// Generate the initial set of universe regions (octree-like subdivision)
+/// Generate the universe's regions: a cube of `config.region_layout` at each grid
+/// position, then, with `subdivide_above` set, the 8 children of every cube denser than it
+/// (after all the cubes, in the order of their parents)
 pub fn generate_regions(config: &SimConfig, age_gyr: f64) -> Vec<Region> {
-    let mut regions = Vec::new();
-
-    // Create a grid of regions covering the observable universe
-    // 8x8x8 = 512 regions, each ~100 Mpc across
-    let grid = 8i64;
-    let region_size = 100.0; // Mpc
-    let offset = (grid as f64 * region_size) / 2.0;
-
-    let composition = cosmology::chemical_composition(age_gyr);
-
-    for x in 0..grid {
-        for y in 0..grid {
-            for z in 0..grid {
-                let id = (x * grid * grid + y * grid + z) as u64;
-                let seed = config.seed.wrapping_add(id * 7919);
+    let layout = &config.region_layout;
+    let [nx, ny, nz] = layout.grid;
+
+    let mut regions = Vec::with_capacity(layout.cell_count() as usize);
+    for x in 0..nx {
+        for y in 0..ny {
+            for z in 0..nz {
+                let id = layout.cell_id([x, y, z]);
+                let seed = region_seed(config, id);
                 let mut local_rng = ChaCha8Rng::seed_from_u64(seed);
 
                 // Density fluctuation (cosmic web: filaments, voids, clusters)
                 let density = generate_density(&mut local_rng);
-
-                let center = [
-                    x as f64 * region_size - offset + region_size / 2.0,
-                    y as f64 * region_size - offset + region_size / 2.0,
-                    z as f64 * region_size - offset + region_size / 2.0,
-                ];
-
-                let volume = region_size.powi(3);
-                let star_count = cosmology::estimate_stars(density, volume, age_gyr);
-
-                // Rough planet estimate: ~1-10 planets per star
-                let planet_count = (star_count as f64 * local_rng.gen_range(1.0..8.0)) as u64;
-
-                regions.push(Region {
-                    id,
-                    center,
-                    size: region_size,
-                    density,
-                    temperature: cosmology::cosmic_temperature(age_gyr),
-                    composition,
-                    dark_matter: config.dark_matter_fraction as f64,
-                    star_count,
-                    planet_count,
-                    has_life: false, // Computed later
-                    detail: RegionDetail::Statistical,
-                    seed,
-                });
+                let dark_matter =
+                    region_dark_fraction(seed, density, config.dark_matter_fraction as f64);
+                regions.push(statistical_region(
+                    config,
+                    RegionCell {
+                        id,
+                        parent: None,
+                        center: layout.cell_center([x, y, z]),
+                        size: layout.size,
+                        density,
+                        dark_matter,
+                    },
+                    &mut local_rng,
+                    age_gyr,
+                ));
             }
         }
     }
 
+    if let Some(threshold) = layout.subdivide_above {
+        let children: Vec<Region> = regions
+            .iter()
+            .filter(|r| r.density > threshold)
+            .flat_map(|r| subdivide_region(config, r, age_gyr))
+            .collect();
+        regions.extend(children);
+    }
     regions
 }
 
+/// Seed of region `id`, which everything generated for it derives from
+fn region_seed(config: &SimConfig, id: u64) -> u64 {
+    config.seed.wrapping_add(id * 7919)
+}
+
+/// Where a region sits and what it is made of, before its statistics are worked out
+struct RegionCell {
+    id: u64,
+    parent: Option<u64>,
+    center: [f64; 3],
+    size: f64,
+    density: f64,
+    dark_matter: f64,
+}
+
+/// A region at `RegionDetail::Statistical`: star and planet estimates for its cell at
+/// `age_gyr`. `rng` is the region's own stream, past its density.
+fn statistical_region(
+    config: &SimConfig,
+    cell: RegionCell,
+    rng: &mut ChaCha8Rng,
+    age_gyr: f64,
+) -> Region {
+    let seed = region_seed(config, cell.id);
+    let baryons = cosmology::baryonic_density(
+        cell.density,
+        cell.dark_matter,
+        config.dark_matter_fraction as f64,
+    );
+    let star_count = cosmology::estimate_stars(baryons, cell.size.powi(3), age_gyr);
+
+    // Rough planet estimate: ~1-10 planets per star
+    let planet_count = (star_count as f64 * rng.gen_range(1.0..8.0)) as u64;
+
+    Region {
+        id: cell.id,
+        parent: cell.parent,
+        center: cell.center,
+        size: cell.size,
+        density: cell.density,
+        temperature: cosmology::cosmic_temperature(age_gyr),
+        composition: cosmology::chemical_composition(age_gyr),
+        dark_matter: cell.dark_matter,
+        star_count,
+        planet_count,
+        // Set as life and civilizations are found (`LazyUniverse::refresh_region_flags`)
+        has_life: false,
+        has_civilization: false,
+        central_black_hole: central_black_hole(seed, cell.density),
+        detail: RegionDetail::Statistical,
+        seed,
+    }
+}
+
+/// The 8 children `parent` splits into. Their densities scatter around the parent's and
+/// average to it, and they keep its dark matter fraction, so the stars they hold add up
+/// to the parent's estimate.
+pub fn subdivide_region(config: &SimConfig, parent: &Region, age_gyr: f64) -> Vec<Region> {
+    let layout = &config.region_layout;
+    let mut rng = ChaCha8Rng::seed_from_u64(parent.seed.wrapping_add(SUBDIVISION_SEED_OFFSET));
+    let weights: [f64; 8] = std::array::from_fn(|_| generate_density(&mut rng));
+    let mean = weights.iter().sum::<f64>() / 8.0;
+
+    (0..8u64)
+        .map(|octant| {
+            let id = layout.child_id(parent.id, octant);
+            let mut local_rng = ChaCha8Rng::seed_from_u64(region_seed(config, id));
+            let cell = RegionCell {
+                id,
+                parent: Some(parent.id),
+                center: RegionLayout::child_center(parent.center, parent.size, octant),
+                size: parent.size / 2.0,
+                density: parent.density * weights[octant as usize] / mean,
+                dark_matter: parent.dark_matter,
+            };
+            statistical_region(config, cell, &mut local_rng, age_gyr)
+        })
+        .collect()
+}
+
+/// Mass (solar masses) of the supermassive black hole at the center of a region with this
+/// seed and density, if it has one. Only overdense regions can host one, the densest
+/// almost always do. Drawn from its own stream so the rest of the region is unchanged.
+pub fn central_black_hole(region_seed: u64, density: f64) -> Option<f64> {
+    let mut rng = ChaCha8Rng::seed_from_u64(region_seed.wrapping_add(SMBH_SEED_OFFSET));
+    let chance = ((density - 1.0) / 1.5).clamp(0.0, 0.9);
+    // 10^6 to 10^9 M☉, heavier in denser regions
+    rng.gen_bool(chance).then(|| 10f64.powf(rng.gen_range(6.0..9.0)) * density.sqrt())
+}
+
+/// Dark matter share of a region's matter: scattered around the universe's `mean`, higher
+/// in overdense regions, whose halos are the dark scaffold the baryons fell into. Drawn
+/// from its own stream so the rest of the region is unchanged.
+pub fn region_dark_fraction(region_seed: u64, density: f64, mean: f64) -> f64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(region_seed.wrapping_add(DARK_MATTER_SEED_OFFSET));
+    (mean * density.powf(0.3) * (0.25 * gaussian(&mut rng)).exp()).clamp(0.05, 0.95)
+}
+
 /// Generate density fluctuation using simple power spectrum approximation
 fn generate_density(rng: &mut impl Rng) -> f64 {
     // Log-normal distribution for cosmic density field
@@ -69,31 +159,296 @@ fn generate_density(rng: &mut impl Rng) -> f64 {
     (normal * 0.5).exp() // density ratio: 0.3x to 3x average
 }
 
-/// Generate detailed star systems for a region when camera enters
+/// Offset of the central black hole's RNG stream from the region seed
+const SMBH_SEED_OFFSET: u64 = 77_000;
+/// Offset of the Galactic-detail mass points' RNG stream from the region seed
+const MASS_POINT_SEED_OFFSET: u64 = 6;
+/// Offset of the seed that, with a planet's id and the age, decides whether it has life
+const LIFE_SEED_OFFSET: u64 = 7;
+/// Offset of a region's dark matter fraction stream from the region seed
+const DARK_MATTER_SEED_OFFSET: u64 = 8;
+/// Offset of the stream that spreads a region's density over its children
+const SUBDIVISION_SEED_OFFSET: u64 = 9;
+/// Keys of the random streams under a star's seed (`seeding::child`). Planets and the
+/// moons of each planet are one level further down, keyed by orbit index.
+const STAR_STREAM: u64 = 0;
+const POSITION_STREAM: u64 = 1;
+const PLANET_STREAM: u64 = 2;
+const MOON_STREAM: u64 = 3;
+const BELT_STREAM: u64 = 4;
+const COMET_STREAM: u64 = 5;
+const RING_STREAM: u64 = 6;
+/// Keys of the random streams under a biosphere's seed
+const GATE_STREAM: u64 = 0;
+const SPECIES_STREAM: u64 = 1;
+const GENOME_STREAM: u64 = 2;
+const BIOMASS_STREAM: u64 = 3;
+/// Aggregate mass points of a region at `RegionDetail::Galactic`
+pub const MASS_POINTS: usize = 100;
+/// Most moons a gas giant can have
+const MAX_MOONS: u64 = 6;
+/// Chance of a gas or ice giant having rings
+const RING_CHANCE: f64 = 0.3;
+/// Most comets a star can have
+const MAX_COMETS: u64 = 4;
+/// Most galaxies a region can hold
+const MAX_GALAXIES: usize = 20;
+/// Fraction of spiral-disk stars that follow an arm (the rest fill the disk)
+const SPIRAL_ARM_FRACTION: f64 = 0.7;
+/// tan of the spiral arms' pitch angle (~15°)
+const SPIRAL_PITCH_TAN: f64 = 0.27;
+/// Angular scatter of stars around their arm (radians)
+const SPIRAL_ARM_SPREAD: f64 = 0.25;
+/// Disk thickness relative to the galaxy radius
+const DISK_THICKNESS: f64 = 0.03;
+/// Minor/major axis ratio of ellipticals
+const ELLIPTICAL_FLATTENING: f64 = 0.7;
+/// Star-forming clumps per irregular galaxy
+const IRREGULAR_CLUMPS: usize = 3;
+
+/// Galaxies of a region: 1–20 depending on density, deterministic from the region seed.
+/// The region's rendered stars (`min(star_count, 1000)`) are split between them by size.
+pub fn generate_galaxies(region: &Region) -> Vec<Galaxy> {
+    let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(2));
+    let count = ((region.density * rng.gen_range(3.0..8.0)).round() as usize).clamp(1, MAX_GALAXIES);
+    let half = region.size / 2.0;
+
+    let mut galaxies: Vec<Galaxy> = (0..count as u64)
+        .map(|id| {
+            let roll: f64 = rng.gen_range(0.0..1.0);
+            let (kind, radius) = if roll < 0.6 {
+                (GalaxyKind::Spiral, rng.gen_range(4.0..12.0))
+            } else if roll < 0.85 {
+                (GalaxyKind::Elliptical, rng.gen_range(3.0..15.0))
+            } else {
+                (GalaxyKind::Irregular, rng.gen_range(1.5..5.0))
+            };
+            let radius = f64::min(radius, half * 0.5);
+            let reach = half - radius;
+            let position = [
+                region.center[0] + rng.gen_range(-reach..reach),
+                region.center[1] + rng.gen_range(-reach..reach),
+                region.center[2] + rng.gen_range(-reach..reach),
+            ];
+            let arms = if kind == GalaxyKind::Spiral { rng.gen_range(2..=4) } else { 0 };
+            Galaxy {
+                id,
+                position,
+                kind,
+                radius,
+                axis: random_unit_vector(&mut rng),
+                arms,
+                star_budget: 0,
+                first_star: 0,
+                star_count: 0,
+                seed: rng.r#gen(),
+            }
+        })
+        .collect();
+
+    // Split stars by area (bigger galaxies hold more), largest remainders get the leftovers
+    let total_weight: f64 = galaxies.iter().map(|g| g.radius * g.radius).sum();
+    let rendered = region.star_count.min(1000);
+    let mut shares: Vec<(usize, f64)> = galaxies
+        .iter()
+        .enumerate()
+        .map(|(i, g)| (i, rendered as f64 * g.radius * g.radius / total_weight))
+        .collect();
+    let mut assigned = 0u64;
+    for (i, share) in &shares {
+        let galaxy = &mut galaxies[*i];
+        galaxy.star_count = share.floor() as u32;
+        galaxy.star_budget =
+            (region.star_count as f64 * galaxy.radius * galaxy.radius / total_weight) as u64;
+        assigned += galaxy.star_count as u64;
+    }
+    shares.sort_by(|a, b| (b.1 - b.1.floor()).total_cmp(&(a.1 - a.1.floor())));
+    for (i, _) in shares.iter().take((rendered - assigned) as usize) {
+        galaxies[*i].star_count += 1;
+    }
+
+    // Star ids are unique across the universe (`names::STAR_ID_STRIDE`)
+    let mut first_star = region.id * names::STAR_ID_STRIDE;
+    for galaxy in &mut galaxies {
+        galaxy.first_star = first_star;
+        first_star += galaxy.star_count as u64;
+    }
+    galaxies
+}
+
+/// `MASS_POINTS` aggregate masses standing in for a region's galaxies without generating
+/// its stars (`RegionDetail::Galactic`): spread through each galaxy's radius, more of
+/// them in bigger galaxies, sharing the region's stars (~1 M☉ each) between them
+pub fn generate_mass_points(region: &Region) -> Vec<MassPoint> {
+    let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(MASS_POINT_SEED_OFFSET));
+    let galaxies = generate_galaxies(region);
+    let total_weight: f64 = galaxies.iter().map(|g| g.radius * g.radius).sum();
+    let mass = region.star_count as f64 / MASS_POINTS as f64;
+    (0..MASS_POINTS)
+        .map(|_| {
+            let mut pick = rng.gen_range(0.0..total_weight);
+            let galaxy = galaxies
+                .iter()
+                .find(|g| {
+                    pick -= g.radius * g.radius;
+                    pick < 0.0
+                })
+                .unwrap_or(&galaxies[galaxies.len() - 1]);
+            let direction = random_unit_vector(&mut rng);
+            let reach = galaxy.radius * rng.gen_range(0.0..1.0f64).sqrt();
+            MassPoint {
+                position: std::array::from_fn(|k| galaxy.position[k] + direction[k] * reach),
+                mass,
+            }
+        })
+        .collect()
+}
+
+/// Landing parameters for every planet and moon of `star` (`RegionDetail::Planetary`):
+/// terrain seed, the landing survey and the weather epoch, which moves on every Myr
+pub fn generate_surface_presets(star: &Star, age_gyr: f64) -> Vec<SurfacePreset> {
+    star.planets
+        .iter()
+        .flat_map(|p| std::iter::once(p).chain(&p.moons))
+        .map(|planet| {
+            let terrain_seed = planet.id;
+            let sampler = terrain::TerrainSampler::new(terrain_seed, planet.planet_type);
+            let (height_range, sea_level) = terrain::survey_landing_site(&sampler, planet);
+            SurfacePreset {
+                planet_id: planet.id,
+                terrain_seed,
+                height_range,
+                sea_level,
+                weather_epoch: (age_gyr.max(0.0) * 1000.0) as u64,
+            }
+        })
+        .collect()
+}
+
+/// Generate detailed star systems for a region when camera enters.
+/// Stars are placed galaxy by galaxy (see `generate_galaxies`), so star IDs are contiguous per galaxy.
+/// Each star draws from its own seed (`star_seed`), so no star depends on what another drew.
 pub fn generate_stellar_detail(region: &Region, age_gyr: f64) -> Vec<Star> {
-    let mut rng = ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(1));
+    let life_seed = region.seed.wrapping_add(LIFE_SEED_OFFSET);
     let mut stars = Vec::new();
 
-    // Generate representative stars (max ~1000 for rendering)
-    let n = (region.star_count).min(1000) as usize;
-
-    for i in 0..n {
-        let star = generate_star(i as u64, region, age_gyr, &mut rng);
-        stars.push(star);
+    for galaxy in generate_galaxies(region) {
+        let clumps = irregular_clumps(&galaxy);
+        for i in 0..galaxy.star_count as u64 {
+            let seed = star_seed(region, stars.len() as u64);
+            let mut pos_rng = seeding::child_rng(seed, POSITION_STREAM);
+            let position = sample_galaxy_position(&galaxy, &clumps, &mut pos_rng);
+            let mut star = generate_star(galaxy.first_star + i, position, age_gyr, seed, life_seed);
+            generate_small_bodies(&mut star, seed);
+            stars.push(star);
+        }
     }
 
     stars
 }
 
-fn generate_star(id: u64, region: &Region, age_gyr: f64, rng: &mut impl Rng) -> Star {
-    // Position: random within region
-    let half = region.size / 2.0;
-    let position = [
-        region.center[0] + rng.gen_range(-half..half),
-        region.center[1] + rng.gen_range(-half..half),
-        region.center[2] + rng.gen_range(-half..half),
-    ];
+/// Seed of star `index` (in generation order) of a region
+fn star_seed(region: &Region, index: u64) -> u64 {
+    seeding::child(region.seed.wrapping_add(1), index)
+}
+
+/// Standard normal sample (Box–Muller)
+pub(crate) fn gaussian(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+fn random_unit_vector(rng: &mut impl Rng) -> [f64; 3] {
+    loop {
+        let v = [gaussian(rng), gaussian(rng), gaussian(rng)];
+        let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        if len > 1e-9 {
+            return [v[0] / len, v[1] / len, v[2] / len];
+        }
+    }
+}
+
+/// Two unit vectors spanning the plane perpendicular to `axis`
+fn plane_basis(axis: [f64; 3]) -> ([f64; 3], [f64; 3]) {
+    let helper = if axis[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let cross = |a: [f64; 3], b: [f64; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let u = cross(helper, axis);
+    let len = (u[0] * u[0] + u[1] * u[1] + u[2] * u[2]).sqrt();
+    let u = [u[0] / len, u[1] / len, u[2] / len];
+    (u, cross(axis, u))
+}
+
+/// Clump centers of an irregular galaxy, as offsets in galaxy-local (u, v, axis) coordinates
+fn irregular_clumps(galaxy: &Galaxy) -> Vec<[f64; 3]> {
+    if galaxy.kind != GalaxyKind::Irregular {
+        return Vec::new();
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(galaxy.seed);
+    (0..IRREGULAR_CLUMPS)
+        .map(|_| {
+            [
+                gaussian(&mut rng) * galaxy.radius * 0.4,
+                gaussian(&mut rng) * galaxy.radius * 0.4,
+                gaussian(&mut rng) * galaxy.radius * 0.2,
+            ]
+        })
+        .collect()
+}
+
+/// Sample a star position from the galaxy's density profile
+fn sample_galaxy_position(galaxy: &Galaxy, clumps: &[[f64; 3]], rng: &mut impl Rng) -> [f64; 3] {
+    let r_max = galaxy.radius;
+    let local = match galaxy.kind {
+        GalaxyKind::Spiral => {
+            // Exponential disk: surface density ∝ e^(-r/h) → r ~ Gamma(2, h)
+            let h = r_max / 3.0;
+            let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+            let u2: f64 = rng.gen_range(f64::EPSILON..1.0);
+            let r = (-h * (u1 * u2).ln()).min(r_max);
+            let theta = if rng.gen_bool(SPIRAL_ARM_FRACTION) {
+                // Logarithmic spiral r = h·e^(tan(pitch)·θ), one per arm
+                let arm = rng.gen_range(0..galaxy.arms.max(1)) as f64;
+                arm * std::f64::consts::TAU / galaxy.arms.max(1) as f64
+                    + (r.max(0.05 * h) / h).ln() / SPIRAL_PITCH_TAN
+                    + gaussian(rng) * SPIRAL_ARM_SPREAD
+            } else {
+                rng.gen_range(0.0..std::f64::consts::TAU)
+            };
+            [r * theta.cos(), r * theta.sin(), gaussian(rng) * DISK_THICKNESS * r_max]
+        }
+        GalaxyKind::Elliptical => {
+            // Hernquist profile (close to de Vaucouleurs' r^1/4 law): M(<r) ∝ r²/(r+a)²
+            let a = r_max / 4.0;
+            let s = rng.gen_range(0.0..0.95f64).sqrt();
+            let r = (a * s / (1.0 - s)).min(r_max);
+            let d = random_unit_vector(rng);
+            [r * d[0], r * d[1], r * d[2] * ELLIPTICAL_FLATTENING]
+        }
+        GalaxyKind::Irregular => {
+            let center = clumps.get(rng.gen_range(0..clumps.len().max(1))).copied().unwrap_or([0.0; 3]);
+            let spread = r_max * 0.3;
+            [
+                center[0] + gaussian(rng) * spread,
+                center[1] + gaussian(rng) * spread,
+                center[2] + gaussian(rng) * spread * 0.6,
+            ]
+        }
+    };
 
+    let (u, v) = plane_basis(galaxy.axis);
+    let n = galaxy.axis;
+    std::array::from_fn(|k| galaxy.position[k] + local[0] * u[k] + local[1] * v[k] + local[2] * n[k])
+}
+
+fn generate_star(id: u64, position: [f64; 3], age_gyr: f64, seed: u64, life_seed: u64) -> Star {
+    let mut rng = seeding::child_rng(seed, STAR_STREAM);
     let velocity = [
         rng.gen_range(-100.0..100.0),
         rng.gen_range(-100.0..100.0),
@@ -122,11 +477,13 @@ fn generate_star(id: u64, region: &Region, age_gyr: f64, rng: &mut impl Rng) ->
     let mut planets = Vec::new();
     for j in 0..planet_count {
         planets.push(generate_planet(
-            id * 1000 + j,
+            id * names::PLANET_ID_STRIDE + j,
+            mass,
             luminosity,
             age_gyr,
             j,
-            rng,
+            seeding::descend(seed, &[PLANET_STREAM, j]),
+            life_seed,
         ));
     }
 
@@ -139,17 +496,116 @@ fn generate_star(id: u64, region: &Region, age_gyr: f64, rng: &mut impl Rng) ->
         surface_temp,
         spectral_class,
         age: star_age,
+        remnant: None,
         planets,
+        asteroid_belt: None,
+        comets: Vec::new(),
+    }
+}
+
+/// Moons of the gas giants, rings of the giants, an asteroid belt and comets for `star`
+/// (with this seed). Drawn from their own streams after the star is complete, so stars
+/// and planets are the same with or without them.
+fn generate_small_bodies(star: &mut Star, seed: u64) {
+    for (j, planet) in star.planets.iter_mut().enumerate() {
+        if planet.planet_type.is_giant() {
+            let mut rng = ChaCha8Rng::seed_from_u64(seeding::descend(seed, &[RING_STREAM, j as u64]));
+            planet.rings = rng.gen_bool(RING_CHANCE).then(|| generate_rings(&mut rng));
+        }
+        if planet.planet_type != PlanetType::GasGiant {
+            continue;
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(seeding::descend(seed, &[MOON_STREAM, j as u64]));
+        let count = rng.gen_range(0..=MAX_MOONS);
+        planet.moons = (0..count).map(|m| generate_moon(planet, m, &mut rng)).collect();
+    }
+
+    let mut rng = seeding::child_rng(seed, BELT_STREAM);
+
+    // Inside the innermost gas giant, between its 4:1 and 2:1 resonances (Kirkwood gaps)
+    star.asteroid_belt = star
+        .planets
+        .iter()
+        .filter(|p| p.planet_type == PlanetType::GasGiant)
+        .map(|p| p.orbital_radius)
+        .min_by(f64::total_cmp)
+        .map(|giant| AsteroidBelt {
+            inner_radius: giant * 4f64.powf(-2.0 / 3.0),
+            outer_radius: giant * 2f64.powf(-2.0 / 3.0),
+            particle_budget: rng.gen_range(200..1500),
+            seed: rng.r#gen(),
+        });
+
+    let mut rng = seeding::child_rng(seed, COMET_STREAM);
+    let count = rng.gen_range(0..=MAX_COMETS);
+    star.comets = (0..count)
+        .map(|k| {
+            let semi_major_axis = rng.gen_range(5.0..60.0);
+            Comet {
+                id: star.id * 1000 + 900 + k,
+                semi_major_axis,
+                eccentricity: rng.gen_range(0.6..0.97),
+                orbital_period: f64::powf(semi_major_axis, 1.5),
+                orbital_angle: rng.gen_range(0.0..std::f64::consts::TAU),
+                perihelion_angle: rng.gen_range(0.0..std::f64::consts::TAU),
+            }
+        })
+        .collect();
+}
+
+/// Rings of a giant: an annulus a little above its cloud tops, tilted with its equator
+fn generate_rings(rng: &mut impl Rng) -> RingSystem {
+    let inner_radius = rng.gen_range(1.2..1.7);
+    RingSystem {
+        inner_radius,
+        outer_radius: inner_radius + rng.gen_range(0.4..1.6),
+        density: rng.gen_range(0.25..0.9),
+        tilt: rng.gen_range(-0.5..0.5),
+    }
+}
+
+/// Moon `index` of a gas giant: a small airless body on its own orbit around `parent`
+fn generate_moon(parent: &Planet, index: u64, rng: &mut impl Rng) -> Planet {
+    // Moon orbits (AU) widen outwards like the planets'
+    let orbital_radius = 0.002 * 1.6f64.powf(index as f64) * rng.gen_range(0.8..1.2);
+    // Kepler's third law around the planet (solar masses)
+    let orbital_period = (orbital_radius.powi(3) / (parent.mass / 333_000.0)).sqrt();
+    let mass = 10.0f64.powf(rng.gen_range(-4.0..-1.3));
+    let planet_type = if parent.surface_temp < 150.0 {
+        PlanetType::Frozen
+    } else {
+        PlanetType::Barren
+    };
+
+    Planet {
+        id: parent.id + 100 * (index + 1),
+        orbital_radius,
+        orbital_period,
+        orbital_angle: rng.gen_range(0.0..std::f64::consts::TAU),
+        mass,
+        radius: mass.powf(0.27),
+        surface_temp: parent.surface_temp,
+        tidally_locked: false,
+        has_water: false,
+        has_atmosphere: false,
+        atmosphere: AtmosphereType::None,
+        planet_type,
+        life: None,
+        moons: Vec::new(),
+        rings: None,
     }
 }
 
 fn generate_planet(
     id: u64,
+    star_mass: f64,
     star_luminosity: f64,
     age_gyr: f64,
     orbit_index: u64,
-    rng: &mut impl Rng,
+    seed: u64,
+    life_seed: u64,
 ) -> Planet {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
     // Titius-Bode-like orbital spacing
     let orbital_radius = 0.2 * (1.5f64).powf(orbit_index as f64) + rng.gen_range(-0.1..0.1);
     let orbital_radius = orbital_radius.max(0.05);
@@ -171,7 +627,15 @@ fn generate_planet(
         mass.powf(-0.04) * 11.0 // Gas giant (radius plateaus)
     };
 
-    let surface_temp = cosmology::planet_surface_temp(star_luminosity, orbital_radius);
+    // A locked planet's climate, and where life could take hold, is that of its terminator
+    let equilibrium_temp = cosmology::planet_surface_temp(star_luminosity, orbital_radius);
+    let tidally_locked = cosmology::is_tidally_locked(star_mass, orbital_radius);
+    let holds_atmosphere = |temp: f64| mass > 0.3 && temp < 2000.0;
+    let surface_temp = if tidally_locked {
+        cosmology::locked_temperatures(equilibrium_temp, holds_atmosphere(equilibrium_temp)).terminator
+    } else {
+        equilibrium_temp
+    };
 
     // Planet type from mass and temperature
     let planet_type = if mass > 100.0 {
@@ -189,7 +653,7 @@ fn generate_planet(
     };
 
     // Atmosphere and water
-    let has_atmosphere = mass > 0.3 && surface_temp < 2000.0;
+    let has_atmosphere = holds_atmosphere(equilibrium_temp);
     let has_water = has_atmosphere && (240.0..=400.0).contains(&surface_temp);
 
     let atmosphere = if !has_atmosphere {
@@ -212,12 +676,15 @@ fn generate_planet(
     // 1. Habitable zone (temp, water, atmosphere)
     // 2. Enough time (>1 Gyr minimum for even prokaryotes)
     // 3. Probabilistic abiogenesis (most planets stay sterile)
+    // Rolled from the planet's own stream, so regenerating its region within the same Gyr
+    // gives it the same biosphere whatever else was drawn before it
     let habitable = cosmology::is_habitable(surface_temp, has_water, has_atmosphere);
     let life = if habitable && age_gyr > 1.0 {
         let life_age = (age_gyr - 1.0).max(0.0);
-        let p = probability_of_life(surface_temp, has_water, &planet_type, life_age);
-        if life_age > 0.0 && rng.gen_bool(p) {
-            Some(generate_biosphere(life_age, surface_temp, &planet_type, &atmosphere, rng))
+        let p = probability_of_life(surface_temp, has_water, &planet_type, life_age, tidally_locked);
+        let mut life_rng = biosphere_rng(life_seed, id, age_gyr);
+        if life_age > 0.0 && life_rng.gen_bool(p) {
+            Some(generate_biosphere(life_age, surface_temp, &planet_type, &atmosphere, &mut life_rng))
         } else {
             None
         }
@@ -233,18 +700,31 @@ fn generate_planet(
         mass,
         radius,
         surface_temp,
+        tidally_locked,
         has_water,
         has_atmosphere,
         atmosphere,
         planet_type,
         life,
+        moons: Vec::new(),
+        rings: None,
     }
 }
 
+/// Life on a tidally locked planet is confined to the terminator and its atmosphere
+/// may freeze out on the night side: less likely, but far from ruled out
+const TIDALLY_LOCKED_LIFE_FACTOR: f64 = 0.3;
+
 /// Probability of life arising — Drake-equation inspired, MUCH rarer than before.
 /// On Earth, life appeared after ~0.5 Gyr. But we have n=1.
 /// Most habitable planets probably stay sterile.
-fn probability_of_life(surface_temp: f64, has_water: bool, planet_type: &PlanetType, life_age_gyr: f64) -> f64 {
+pub fn probability_of_life(
+    surface_temp: f64,
+    has_water: bool,
+    planet_type: &PlanetType,
+    life_age_gyr: f64,
+    tidally_locked: bool,
+) -> f64 {
     // Without liquid water: extremely unlikely (but not zero — exotic chemistries)
     if !has_water {
         return 1e-6;
@@ -271,12 +751,18 @@ fn probability_of_life(surface_temp: f64, has_water: bool, planet_type: &PlanetT
     let time_factor = (1.0 - (-life_age_gyr * 0.3).exp()).max(0.0);
     p *= time_factor;
 
+    if tidally_locked {
+        p *= TIDALLY_LOCKED_LIFE_FACTOR;
+    }
+
     p.clamp(1e-7, 0.15)
 }
 
 /// Generate a biosphere — realistic complexity curve based on Earth's timeline.
 /// Most biospheres are microbial. Multicellular life is rare. Intelligence is extremely rare.
-fn generate_biosphere(
+/// Takes one seed from `rng`; the gates, species count, genome and biomass each draw from
+/// their own stream under it, so the genome doesn't depend on how many gates were rolled.
+pub fn generate_biosphere(
     life_age_gyr: f64,
     surface_temp: f64,
     planet_type: &PlanetType,
@@ -291,6 +777,8 @@ fn generate_biosphere(
     // 4-5 Gyr: complex animals — requires ~5% chance (complexity 5.0-7.0)
     // 5+ Gyr: intelligence — requires ~1% chance (complexity 7.0-10.0)
 
+    let seed: u64 = rng.r#gen();
+    let mut gates = seeding::child_rng(seed, GATE_STREAM);
     let mut complexity = 0.0;
 
     // Stage 1: Prokaryotes (guaranteed if life exists)
@@ -305,19 +793,19 @@ fn generate_biosphere(
 
     // Stage 3: Eukaryotes — the Great Oxidation Event equivalent
     // On Earth this took ~2 Gyr and may have been a fluke
-    if life_age_gyr > 2.0 && rng.gen_bool(0.2) {
+    if life_age_gyr > 2.0 && gates.gen_bool(0.2) {
         complexity = 2.0 + ((life_age_gyr - 2.0) / 1.0).min(1.0);
 
         // Stage 4: Multicellular life — another major transition
-        if life_age_gyr > 3.0 && rng.gen_bool(0.1) {
+        if life_age_gyr > 3.0 && gates.gen_bool(0.1) {
             complexity = 3.0 + ((life_age_gyr - 3.0) / 1.0).min(2.0);
 
             // Stage 5: Complex body plans (Cambrian explosion equivalent)
-            if life_age_gyr > 3.5 && rng.gen_bool(0.05) {
+            if life_age_gyr > 3.5 && gates.gen_bool(0.05) {
                 complexity = 5.0 + ((life_age_gyr - 3.5) / 1.5).min(2.0);
 
                 // Stage 6: Intelligence — extremely rare
-                if life_age_gyr > 4.5 && rng.gen_bool(0.01) {
+                if life_age_gyr > 4.5 && gates.gen_bool(0.01) {
                     complexity = 7.0 + ((life_age_gyr - 4.5) / 2.0).min(3.0);
                 }
             }
@@ -325,28 +813,16 @@ fn generate_biosphere(
     }
 
     // Environmental modifiers — harsh environments cap complexity
-    let max_complexity = match planet_type {
-        PlanetType::Ocean => 6.0,   // No land → hard to develop fire/tools
-        PlanetType::Frozen => 2.0,  // Subsurface life stays simple
-        _ => 10.0,
-    };
-    complexity = complexity.min(max_complexity);
+    complexity = complexity.min(max_complexity(planet_type));
 
-    let species_count = if complexity < 1.0 {
-        rng.gen_range(1..100) as u64
-    } else if complexity < 3.0 {
-        rng.gen_range(100..10_000) as u64
-    } else if complexity < 5.0 {
-        rng.gen_range(10_000..1_000_000) as u64
-    } else {
-        rng.gen_range(1_000_000..50_000_000) as u64
-    };
+    let species_count = species_count_for(complexity, &mut seeding::child_rng(seed, SPECIES_STREAM));
 
     let mut genome = Genome::primordial();
-    evolve_genome(&mut genome, life_age_gyr, complexity, surface_temp, planet_type, atmosphere, rng);
+    let mut genome_rng = seeding::child_rng(seed, GENOME_STREAM);
+    evolve_genome(&mut genome, life_age_gyr, complexity, surface_temp, planet_type, atmosphere, &mut genome_rng);
 
     let has_technology = genome.cognition > 0.8 && complexity >= 7.0;
-    let biomass = complexity.powf(1.5) * rng.gen_range(0.1..5.0);
+    let biomass = complexity.powf(1.5) * seeding::child_rng(seed, BIOMASS_STREAM).gen_range(0.1..5.0);
 
     Biosphere {
         age: life_age_gyr,
@@ -358,6 +834,222 @@ fn generate_biosphere(
     }
 }
 
+/// Environmental complexity cap
+pub(crate) fn max_complexity(planet_type: &PlanetType) -> f64 {
+    match planet_type {
+        PlanetType::Ocean => 6.0,   // No land → hard to develop fire/tools
+        PlanetType::Frozen => 2.0,  // Subsurface life stays simple
+        _ => 10.0,
+    }
+}
+
+/// Complexity band that determines the species count range
+fn species_band(complexity: f64) -> u8 {
+    if complexity < 1.0 {
+        0
+    } else if complexity < 3.0 {
+        1
+    } else if complexity < 5.0 {
+        2
+    } else {
+        3
+    }
+}
+
+/// Species count drawn from the range of a complexity band
+fn species_count_for(complexity: f64, rng: &mut impl Rng) -> u64 {
+    match species_band(complexity) {
+        0 => rng.gen_range(1..100) as u64,
+        1 => rng.gen_range(100..10_000) as u64,
+        2 => rng.gen_range(10_000..1_000_000) as u64,
+        _ => rng.gen_range(1_000_000..50_000_000) as u64,
+    }
+}
+
+/// One row of `STAGES`
+type Stage = (f64, f64, Option<(f64, f64)>);
+
+/// Complexity stages as (ceiling, growth per Gyr, gate into the next stage).
+/// A gate is (minimum life age in Gyr, chance per Gyr) — the same milestones
+/// and odds as `generate_biosphere`, rolled continuously instead of once.
+const STAGES: [Stage; 5] = [
+    (2.0, 0.75, Some((2.0, 0.2))),  // prokaryotes → eukaryotes
+    (3.0, 1.0, Some((3.0, 0.1))),   // eukaryotes → multicellular
+    (5.0, 1.0, Some((3.5, 0.05))),  // multicellular → complex body plans
+    (7.0, 0.75, Some((4.5, 0.01))), // complex animals → intelligence
+    (10.0, 0.5, None),              // intelligence
+];
+
+/// Index into `STAGES` for a complexity value (stage ceilings are inclusive)
+fn stage_of(complexity: f64) -> usize {
+    STAGES
+        .iter()
+        .position(|(ceiling, _, _)| complexity <= *ceiling)
+        .unwrap_or(STAGES.len() - 1)
+}
+
+/// Deterministic RNG for evolving a planet's biosphere during one Gyr epoch,
+/// so replaying the same session produces the same evolutionary outcomes
+pub fn biosphere_rng(seed: u64, planet_id: u64, age_gyr: f64) -> ChaCha8Rng {
+    let epoch = age_gyr.max(0.0).floor() as u64;
+    ChaCha8Rng::seed_from_u64(seeding::descend(seed, &[planet_id, epoch]))
+}
+
+/// Advance an existing biosphere by `dt_gyr`.
+/// Complexity grows within its current stage; at the top of a stage the next
+/// gate (eukaryotes, multicellular, complex life, intelligence) can be crossed.
+/// Species count, biomass and the dominant genome follow the new complexity,
+/// and technology can appear once intelligence is reached.
+pub fn evolve_biosphere(bio: &mut Biosphere, dt_gyr: f64, planet: &Planet, rng: &mut impl Rng) {
+    if dt_gyr <= 0.0 {
+        return;
+    }
+    let old_complexity = bio.complexity;
+    let old_stage = stage_of(old_complexity);
+    let cap = max_complexity(&planet.planet_type);
+
+    // Step in half-Gyr slices so a gate crossed early still leaves time to grow
+    let mut complexity = old_complexity;
+    let mut remaining = dt_gyr;
+    while remaining > 0.0 {
+        let step = remaining.min(0.5);
+        remaining -= step;
+        bio.age += step;
+
+        let (ceiling, rate, gate) = STAGES[stage_of(complexity)];
+        complexity = (complexity + rate * step).min(ceiling).min(cap);
+
+        if complexity >= ceiling && let Some((min_age, per_gyr)) = gate {
+            let p = 1.0 - (1.0 - per_gyr).powf(step);
+            if bio.age > min_age && rng.gen_bool(p) {
+                complexity = (ceiling + 0.01).min(cap);
+            }
+        }
+    }
+    bio.complexity = complexity;
+
+    let new_stage = stage_of(complexity);
+    if species_band(complexity) != species_band(old_complexity) {
+        bio.species_count = species_count_for(complexity, rng);
+    }
+    if old_complexity > 0.0 {
+        bio.biomass *= (complexity / old_complexity).powf(1.5);
+    }
+
+    // A new stage means a new dominant life form
+    if new_stage != old_stage {
+        evolve_genome(
+            &mut bio.dominant_genome,
+            bio.age,
+            complexity,
+            planet.surface_temp,
+            &planet.planet_type,
+            &planet.atmosphere,
+            rng,
+        );
+    }
+
+    if bio.dominant_genome.cognition > 0.8 && complexity >= 7.0 {
+        bio.has_technology = true;
+    }
+}
+
+/// Evolve every biosphere of `stars` (a region with this seed) by `dt_gyr`, up to `age_gyr`.
+/// Returns the (star id, planet id) of each planet where technology appeared.
+pub fn evolve_biospheres(
+    stars: &mut [Star],
+    region_seed: u64,
+    dt_gyr: f64,
+    age_gyr: f64,
+) -> Vec<(u64, u64)> {
+    let mut emerged = Vec::new();
+    for star in stars {
+        for planet in &mut star.planets {
+            let Some(mut bio) = planet.life.take() else {
+                continue;
+            };
+            let had_technology = bio.has_technology;
+            let mut rng = biosphere_rng(region_seed, planet.id, age_gyr);
+            evolve_biosphere(&mut bio, dt_gyr, planet, &mut rng);
+            if bio.has_technology && !had_technology {
+                emerged.push((star.id, planet.id));
+            }
+            planet.life = Some(bio);
+        }
+    }
+    emerged
+}
+
+/// Advance a civilization by `dt_gyr` at universe age `age_gyr`:
+/// Kardashev growth, settling other planets of the home star within reach,
+/// and extinction rolls. Returns true if it went extinct during this step.
+pub fn advance_civilization(
+    civ: &mut Civilization,
+    dt_gyr: f64,
+    star: &Star,
+    age_gyr: f64,
+    rng: &mut impl Rng,
+) -> bool {
+    if !civ.is_alive() || dt_gyr <= 0.0 {
+        return false;
+    }
+
+    // Dead host star: only a Type II+ civilization could have moved away in time
+    if star.remnant.is_some() && civ.tech_level < 2.0 {
+        civ.extinction = Some(Extinction {
+            age: age_gyr,
+            cause: ExtinctionCause::StellarEvent,
+        });
+        return true;
+    }
+
+    let home_radius = star
+        .planets
+        .iter()
+        .find(|p| p.id == civ.planet_id)
+        .map_or(1.0, |p| p.orbital_radius);
+
+    let mut remaining = dt_gyr;
+    while remaining > 0.0 {
+        let step = remaining.min(0.5);
+        remaining -= step;
+
+        // Noisy growth that slows toward the top of the scale
+        let growth = rng.gen_range(0.05..0.4) * (1.0 - civ.tech_level / 3.0).max(0.05);
+        civ.tech_level = (civ.tech_level + growth * step).min(3.0);
+        civ.expansion_radius = 0.5 * (10f64.powf(civ.tech_level) - 1.0);
+
+        // Settle solid planets of the home system within reach
+        for planet in &star.planets {
+            let settleable = !matches!(planet.planet_type, PlanetType::GasGiant | PlanetType::IceGiant);
+            if planet.id != civ.planet_id
+                && settleable
+                && !civ.colonies.contains(&planet.id)
+                && (planet.orbital_radius - home_radius).abs() <= civ.expansion_radius
+            {
+                civ.colonies.push(planet.id);
+            }
+        }
+
+        // ~1e6 at Type 0, ~1e9 at Type I, ~1e12 at Type II
+        civ.population = 1e6 * 1000f64.powf(civ.tech_level) * (1 + civ.colonies.len()) as f64;
+
+        // Great filter around the industrial-to-planetary transition; colonies spread the risk
+        let filter = (-((civ.tech_level - 0.7) / 0.3).powi(2)).exp();
+        civ.extinction_risk = (0.02 + 0.3 * filter) / (1 + civ.colonies.len()) as f64;
+
+        let p = 1.0 - (1.0 - civ.extinction_risk).powf(step);
+        if rng.gen_bool(p.clamp(0.0, 1.0)) {
+            civ.extinction = Some(Extinction {
+                age: age_gyr - remaining,
+                cause: ExtinctionCause::SelfDestruction,
+            });
+            return true;
+        }
+    }
+    false
+}
+
 /// Evolve a genome — constrained by environment, complexity, and physics.
 /// No magic. No plasma beings on 300K planets. No telekinesis.
 /// Structure must follow complexity gates. Senses follow environment.
@@ -380,10 +1072,8 @@ fn evolve_genome(
             if rng.gen_bool(0.3) { 3 } // silicon-based (speculative)
             else { 4 } // sulfur-iron (hydrothermal)
         }
-        PlanetType::Ocean | PlanetType::Rocky => {
-            if surface_temp > 350.0 { 4 } // sulfur-iron at high temp
-            else { 0 } // carbon-water (most common)
-        }
+        // Sulfur-iron at high temp, otherwise carbon-water (most common)
+        PlanetType::Ocean | PlanetType::Rocky if surface_temp > 350.0 => 4,
         _ => 0, // default carbon-water
     };
 
@@ -457,9 +1147,8 @@ fn evolve_genome(
     genome.energy_source = if complexity < 1.5 {
         // Early life: chemosynthesis or photosynthesis
         match atmosphere {
-            AtmosphereType::None | AtmosphereType::ThinCO2 => {
-                if rng.gen_bool(0.5) { 0 } else { 1 } // photo or chemo
-            }
+            // Thin air: photo or chemo, a coin flip
+            AtmosphereType::None | AtmosphereType::ThinCO2 if rng.gen_bool(0.5) => 0,
             _ => 1, // chemosynthesis in dark/thick atmospheres
         }
     } else if complexity < 3.0 {
@@ -554,3 +1243,365 @@ fn evolve_genome(
         rng.gen_range(0.001..0.05)
     };
 }
+
+/// Secondary locomotion a creature may use instead of its genome's, by motility code
+const SECONDARY_MOTILITY: [u32; 8] = [1, 2, 3, 6, 1, 6, 5, 5];
+
+/// Sample one surface creature from a genome: size within ±0.3 dex, a hue jitter
+/// that is wider for exotic chemistries, occasionally a secondary motility, and
+/// an ecological role once the biosphere has complex body plans (complexity ≥ 5).
+pub fn sample_individual(genome: &Genome, complexity: f64, rng: &mut impl Rng) -> IndividualTraits {
+    let hue_jitter = match genome.substrate {
+        0 => 25.0,
+        1 | 2 => 15.0,
+        3 => 10.0,
+        _ => 40.0,
+    };
+    let motility = if rng.gen_bool(0.15) {
+        SECONDARY_MOTILITY[genome.motility.min(7) as usize]
+    } else {
+        genome.motility
+    };
+    let role = (complexity >= 5.0).then(|| match rng.gen_range(0..10) {
+        0..=5 => CreatureRole::Grazer,
+        6..=7 => CreatureRole::Predator,
+        _ => CreatureRole::Scavenger,
+    });
+
+    IndividualTraits {
+        size_log: genome.size_log + rng.gen_range(-0.3..=0.3),
+        hue_shift: rng.gen_range(-hue_jitter..=hue_jitter),
+        elongation: rng.gen_range(0.7..1.4),
+        motility,
+        role,
+    }
+}
+
+/// The 2–4 surface species of a planet's biosphere, each a variant of the dominant
+/// genome. Deterministic per planet id.
+pub fn surface_species(bio: &Biosphere, planet_id: u64) -> Vec<IndividualTraits> {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id.wrapping_mul(0x2545_F491).wrapping_add(31));
+    let count = rng.gen_range(2..=4);
+    (0..count)
+        .map(|_| sample_individual(&bio.dominant_genome, bio.complexity, &mut rng))
+        .collect()
+}
+
+/// Binomial name of a surface species, e.g. "Vorth khelar". The genus follows the
+/// planet and its dominant genome, so every species of one biosphere shares it;
+/// the epithet is per species. Deterministic.
+pub fn binomial_name(planet_id: u64, genome: &Genome, species: usize) -> String {
+    let lineage = [
+        genome.substrate,
+        genome.structure,
+        genome.senses,
+        genome.energy_source,
+        genome.propagation,
+        genome.motility,
+        genome.interface,
+    ]
+    .iter()
+    .fold(planet_id.wrapping_mul(0x9E37_79B9_7F4A_7C15), |h, &v| {
+        (h ^ v as u64).wrapping_mul(0x0100_0000_01B3)
+    });
+
+    let mut rng = ChaCha8Rng::seed_from_u64(lineage);
+    let syllables = rng.gen_range(1..=2);
+    let mut genus = names::word(syllables, &mut rng);
+    if let Some(first) = genus.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(lineage ^ (species as u64 + 1).wrapping_mul(0xD6E8_FEB8_6659_FD93));
+    let syllables = rng.gen_range(2..=3);
+    let epithet = names::word(syllables, &mut rng);
+    format!("{genus} {epithet}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rocky_planet(life: Biosphere) -> Planet {
+        Planet {
+            id: 4_002,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 288.0,
+            tidally_locked: false,
+            has_water: true,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type: PlanetType::Rocky,
+            life: Some(life),
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    fn microbes() -> Biosphere {
+        Biosphere {
+            age: 1.0,
+            complexity: 1.2,
+            species_count: 500,
+            dominant_genome: Genome::primordial(),
+            has_technology: false,
+            biomass: 1.0,
+        }
+    }
+
+    /// Evolve for `gyr` in 1 Gyr reloads, the way `LazyUniverse` would
+    fn run(gyr: u32) -> Biosphere {
+        let mut planet = rocky_planet(microbes());
+        let mut bio = planet.life.take().unwrap();
+        for epoch in 1..=gyr {
+            let mut rng = biosphere_rng(11, planet.id, epoch as f64);
+            evolve_biosphere(&mut bio, 1.0, &planet, &mut rng);
+        }
+        bio
+    }
+
+    #[test]
+    fn test_civilization_dies_with_its_star() {
+        let planet = rocky_planet(microbes());
+        let mut star = Star {
+            id: 4,
+            position: [0.0; 3],
+            velocity: [0.0; 3],
+            mass: 1.0,
+            luminosity: 1.0,
+            surface_temp: 5778.0,
+            spectral_class: SpectralClass::G,
+            age: 0.0,
+            remnant: None,
+            planets: vec![planet],
+            asteroid_belt: None,
+            comets: Vec::new(),
+        };
+        let mut civ = Civilization::new(0, star.id, 4_002, 10.0);
+        let mut rng = biosphere_rng(3, civ.planet_id, 10.0);
+        advance_civilization(&mut civ, 0.5, &star, 10.5, &mut rng);
+        assert!(civ.tech_level > 0.0);
+
+        star.remnant = Some(StellarRemnant::WhiteDwarf);
+        civ.extinction = None;
+        civ.tech_level = 1.0;
+        assert!(advance_civilization(&mut civ, 1.0, &star, 11.5, &mut rng));
+        assert_eq!(civ.extinction.unwrap().cause, ExtinctionCause::StellarEvent);
+        assert!(!civ.is_alive());
+    }
+
+    #[test]
+    fn test_evolve_biosphere_is_deterministic_and_progresses() {
+        let a = run(10);
+        let b = run(10);
+        assert_eq!(a.complexity, b.complexity);
+        assert_eq!(a.species_count, b.species_count);
+        assert_eq!(a.has_technology, b.has_technology);
+
+        assert!((a.age - 11.0).abs() < 1e-9);
+        // At least finishes prokaryotic diversification
+        assert!(a.complexity >= 2.0, "complexity {}", a.complexity);
+    }
+
+    #[test]
+    fn test_surface_species_vary_around_the_dominant_genome() {
+        let mut bio = microbes();
+        bio.complexity = 6.0;
+        bio.dominant_genome.size_log = 0.3;
+        bio.dominant_genome.motility = 5;
+
+        let species = surface_species(&bio, 4_002);
+        assert!((2..=4).contains(&species.len()));
+        assert_eq!(species, surface_species(&bio, 4_002));
+        for s in &species {
+            assert!((s.size_log - 0.3).abs() <= 0.3 + 1e-9);
+            assert!(s.role.is_some());
+            assert!(s.motility == 5 || s.motility == SECONDARY_MOTILITY[5]);
+        }
+
+        // Simple biospheres have no ecological roles
+        bio.complexity = 3.0;
+        assert!(surface_species(&bio, 4_002).iter().all(|s| s.role.is_none()));
+    }
+
+    #[test]
+    fn test_binomial_names_share_a_genus_per_biosphere() {
+        let genome = Genome::primordial();
+        let first = binomial_name(4_002, &genome, 0);
+        assert_eq!(first, binomial_name(4_002, &genome, 0));
+
+        let (genus, epithet) = first.split_once(' ').unwrap();
+        assert!(genus.starts_with(|c: char| c.is_ascii_uppercase()));
+        assert!(epithet.chars().all(|c| c.is_ascii_lowercase()));
+
+        let sibling = binomial_name(4_002, &genome, 1);
+        assert_eq!(sibling.split_once(' ').unwrap().0, genus);
+        assert_ne!(sibling, first);
+    }
+
+    #[test]
+    fn test_region_layout_shapes_the_grid() {
+        // A slab of 50 Mpc cubes, centered on the origin
+        let config = SimConfig {
+            region_layout: RegionLayout {
+                grid: [6, 1, 4],
+                size: 50.0,
+                subdivide_above: None,
+            },
+            ..SimConfig::default()
+        };
+        let regions = generate_regions(&config, 10.0);
+        assert_eq!(regions.len(), 24);
+        assert!(regions.iter().all(|r| r.size == 50.0 && r.center[1] == 0.0 && r.parent.is_none()));
+        assert_eq!(regions.iter().map(|r| r.center[0]).fold(f64::MIN, f64::max), 125.0);
+        assert_eq!(regions.iter().map(|r| r.center[2]).fold(f64::MAX, f64::min), -75.0);
+        let ids: Vec<u64> = regions.iter().map(|r| r.id).collect();
+        assert_eq!(ids, (0..24).collect::<Vec<_>>());
+
+        // The default layout is the 8×8×8 grid of 100 Mpc cubes
+        let regions = generate_regions(&SimConfig::default(), 10.0);
+        assert_eq!(regions.len(), 512);
+        assert_eq!(regions[9].center, [-350.0, -250.0, -250.0]);
+    }
+
+    #[test]
+    fn test_subdivided_regions_conserve_stars_and_keep_ids_unique() {
+        let mut config = SimConfig::default();
+        config.region_layout.subdivide_above = Some(1.5);
+        let regions = generate_regions(&config, 13.8);
+        let cells = regions.iter().filter(|r| r.parent.is_none()).count();
+        assert_eq!(cells, 512);
+        let parents: Vec<&Region> = regions.iter().filter(|r| r.density > 1.5 && r.parent.is_none()).collect();
+        assert!(!parents.is_empty());
+        assert_eq!(regions.len(), cells + parents.len() * 8);
+
+        let ids: std::collections::HashSet<u64> = regions.iter().map(|r| r.id).collect();
+        assert_eq!(ids.len(), regions.len());
+        // The cubes are the same as without subdivision
+        let flat = generate_regions(&SimConfig::default(), 13.8);
+        assert!(flat.iter().zip(&regions).all(|(a, b)| a.id == b.id && a.seed == b.seed));
+
+        for parent in parents {
+            let children: Vec<&Region> =
+                regions.iter().filter(|r| r.parent == Some(parent.id)).collect();
+            assert_eq!(children.len(), 8);
+            let stars: u64 = children.iter().map(|c| c.star_count).sum();
+            let error = (stars as f64 - parent.star_count as f64).abs();
+            assert!(error <= 8.0, "children hold {stars} stars, their parent {}", parent.star_count);
+            for child in &children {
+                assert_eq!(child.size, parent.size / 2.0);
+                // Inside the parent, and no two children in the same octant
+                assert!((0..3).all(|k| (child.center[k] - parent.center[k]).abs() == parent.size / 4.0));
+                assert!(child.density != parent.density);
+            }
+            let mean = children.iter().map(|c| c.density).sum::<f64>() / 8.0;
+            assert!((mean - parent.density).abs() < 1e-9);
+            let centers: std::collections::HashSet<[u64; 3]> =
+                children.iter().map(|c| c.center.map(f64::to_bits)).collect();
+            assert_eq!(centers.len(), 8);
+        }
+    }
+
+    #[test]
+    fn test_stars_cluster_into_galaxies() {
+        let config = SimConfig::default();
+        let region = generate_regions(&config, 10.0)
+            .into_iter()
+            .max_by(|a, b| a.density.total_cmp(&b.density))
+            .unwrap();
+        let galaxies = generate_galaxies(&region);
+        assert!((1..=MAX_GALAXIES).contains(&galaxies.len()));
+        assert_eq!(galaxies, generate_galaxies(&region));
+
+        let stars = generate_stellar_detail(&region, 10.0);
+        let total: u64 = galaxies.iter().map(|g| g.star_count as u64).sum();
+        assert_eq!(total, stars.len() as u64);
+        assert_eq!(stars.len() as u64, region.star_count.min(1000));
+
+        for star in &stars {
+            let galaxy = galaxies.iter().find(|g| g.contains_star(star.id)).unwrap();
+            let d = (0..3)
+                .map(|k| (star.position[k] - galaxy.position[k]).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            // Irregular clumps scatter a little past the nominal radius
+            assert!(d < galaxy.radius * 3.0, "star {} is {d:.1} from its galaxy", star.id);
+        }
+    }
+
+    #[test]
+    fn test_small_bodies_leave_planets_unchanged() {
+        let config = SimConfig::default();
+        let region = generate_regions(&config, 10.0)
+            .into_iter()
+            .max_by(|a, b| a.density.total_cmp(&b.density))
+            .unwrap();
+        let stars = generate_stellar_detail(&region, 10.0);
+
+        // The planets are what the star's own streams alone draw
+        let life_seed = region.seed.wrapping_add(LIFE_SEED_OFFSET);
+        let first = generate_star(stars[0].id, stars[0].position, 10.0, star_seed(&region, 0), life_seed);
+        assert_eq!(first.planets.len(), stars[0].planets.len());
+        for (planet, bare) in stars[0].planets.iter().zip(&first.planets) {
+            assert_eq!(
+                Planet {
+                    moons: Vec::new(),
+                    rings: None,
+                    ..planet.clone()
+                },
+                *bare
+            );
+        }
+
+        let mut ids = std::collections::HashSet::new();
+        for star in &stars {
+            for planet in &star.planets {
+                assert!(ids.insert(planet.id));
+                assert!(planet.moons.len() as u64 <= MAX_MOONS);
+                if planet.planet_type != PlanetType::GasGiant {
+                    assert!(planet.moons.is_empty());
+                }
+                if let Some(rings) = planet.rings {
+                    assert!(planet.planet_type.is_giant());
+                    assert!(1.0 < rings.inner_radius && rings.inner_radius < rings.outer_radius);
+                    assert!(rings.density > 0.0 && rings.density < 1.0);
+                }
+                for moon in &planet.moons {
+                    assert!(ids.insert(moon.id));
+                    assert_eq!(star.body(moon.id), Some(moon));
+                    assert_eq!(star.moon_parent(moon.id).map(|p| p.id), Some(planet.id));
+                    assert!(matches!(moon.planet_type, PlanetType::Barren | PlanetType::Frozen));
+                    assert!(moon.life.is_none() && moon.moons.is_empty());
+                }
+            }
+            if let Some(belt) = &star.asteroid_belt {
+                assert!(belt.inner_radius < belt.outer_radius);
+                // Clear of every planet's orbit but the gas giant's it is tuned to
+                let giant = star
+                    .planets
+                    .iter()
+                    .filter(|p| p.planet_type == PlanetType::GasGiant)
+                    .map(|p| p.orbital_radius)
+                    .fold(f64::INFINITY, f64::min);
+                assert!(belt.outer_radius < giant);
+            }
+            for comet in &star.comets {
+                assert!(ids.insert(comet.id));
+                assert!(comet.perihelion() < comet.semi_major_axis);
+                // Mean anomaly 0 is the perihelion
+                let [x, y] = comet.position_at(0.0);
+                assert!((x.hypot(y) - comet.perihelion()).abs() < 1e-9);
+                let [x, y] = comet.position_at(std::f64::consts::PI);
+                let aphelion = comet.semi_major_axis * (1.0 + comet.eccentricity);
+                assert!((x.hypot(y) - aphelion).abs() < 1e-9);
+            }
+        }
+        assert!(stars.iter().any(|s| s.asteroid_belt.is_some()));
+        assert!(stars.iter().any(|s| !s.comets.is_empty()));
+        assert!(stars.iter().flat_map(|s| &s.planets).any(|p| !p.moons.is_empty()));
+    }
+}
@@ -17,7 +17,11 @@ pub fn hubble_parameter(_age: f64, phase: UniversePhase) -> f64 {
     }
 }
 
-/// Calculate new scale factor based on Hubble expansion
+/// Relative growth of the scale factor per unit of Hubble parameter and Gyr
+pub const EXPANSION_PER_HUBBLE_GYR: f64 = 0.001;
+
+/// Calculate new scale factor based on Hubble expansion.
+/// Contraction is limited to halving per step so large time steps can't flip the sign.
 pub fn expand_scale_factor(current: f64, hubble: f64, dt: f64) -> f64 {
-    current * (1.0 + hubble * dt * 0.001)
+    current * (1.0 + hubble * dt * EXPANSION_PER_HUBBLE_GYR).max(0.5)
 }
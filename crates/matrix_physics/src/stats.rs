@@ -0,0 +1,386 @@
+use matrix_core::*;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use std::time::{Duration, Instant};
+
+use super::{cosmology, procgen};
+
+/// Mixed into the universe seed for the choice of sampled regions
+const CENSUS_SEED: u64 = 0xCE_2505;
+/// Two-sided 95% normal quantile
+const Z_95: f64 = 1.96;
+/// Cells a side of a `SeedPreview` density map
+pub const PREVIEW_MAP_SIZE: usize = 8;
+
+/// Filter stages of the Drake breakdown, each a subset of the one before
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Planets,
+    /// Liquid water and an atmosphere at a survivable temperature (`cosmology::is_habitable`)
+    Habitable,
+    Life,
+    /// Complexity 3 and up
+    Multicellular,
+    /// Complexity 7 and up
+    Intelligent,
+    Technological,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Planets,
+        Stage::Habitable,
+        Stage::Life,
+        Stage::Multicellular,
+        Stage::Intelligent,
+        Stage::Technological,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Planets => "Planets",
+            Stage::Habitable => "Habitable",
+            Stage::Life => "Life",
+            Stage::Multicellular => "Multicellular",
+            Stage::Intelligent => "Intelligent",
+            Stage::Technological => "Technological",
+        }
+    }
+}
+
+/// Planets of one region at each stage, indexed by `Stage as usize`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageCounts(pub [u64; 6]);
+
+impl StageCounts {
+    /// Count the planets of a region's stars
+    pub fn of_stars(stars: &[Star]) -> Self {
+        let mut counts = Self::default();
+        for planet in stars.iter().flat_map(|star| &star.planets) {
+            let habitable = cosmology::is_habitable(
+                planet.surface_temp,
+                planet.has_water,
+                planet.has_atmosphere,
+            );
+            let complexity = planet.life.as_ref().map(|life| life.complexity);
+            let reached = [
+                true,
+                habitable,
+                planet.life.is_some(),
+                complexity.is_some_and(|c| c >= 3.0),
+                complexity.is_some_and(|c| c >= 7.0),
+                planet.life.as_ref().is_some_and(|life| life.has_technology),
+            ];
+            for (count, reached) in counts.0.iter_mut().zip(reached) {
+                *count += reached as u64;
+            }
+        }
+        counts
+    }
+}
+
+/// One stage of a census: what the sample found and what that implies for the universe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageEstimate {
+    pub stage: Stage,
+    /// Planets at this stage in the sampled regions
+    pub sampled: u64,
+    /// Fraction of the previous stage's planets that reach this one (1 for `Planets`)
+    pub rate: f64,
+    /// Extrapolated count over all regions
+    pub total: f64,
+    /// 95% confidence interval of `total`, never below `sampled`
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Drake-equation style breakdown of a universe at one age: how many planets pass each
+/// filter, from the full stellar detail of a sample of regions scaled up to all of them.
+/// Biospheres are as procgen rolls them at `age_gyr`, without the evolution a visit adds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseCensus {
+    pub seed: u64,
+    pub age_gyr: f64,
+    pub sampled_regions: usize,
+    pub total_regions: usize,
+    pub stages: [StageEstimate; 6],
+}
+
+impl UniverseCensus {
+    /// Generate `sample_regions` regions (chosen by the seed, without repeats) in detail
+    /// and count their planets. Every region costs a stellar detail generation. Only the
+    /// cubes of the grid are sampled: children would count their parent's stars twice.
+    pub fn compute(config: &SimConfig, age_gyr: f64, sample_regions: usize) -> Self {
+        let regions = procgen::generate_regions(config, age_gyr);
+        let cubes = regions.iter().filter(|r| r.parent.is_none()).collect();
+        Self::sample(config, age_gyr, cubes, sample_regions, None)
+    }
+
+    /// Census of `cubes` (all of the grid's). Past `deadline` no further region is
+    /// generated, though the first always is.
+    fn sample(
+        config: &SimConfig,
+        age_gyr: f64,
+        mut cubes: Vec<&Region>,
+        sample_regions: usize,
+        deadline: Option<Instant>,
+    ) -> Self {
+        let total_regions = cubes.len();
+        cubes.shuffle(&mut ChaCha8Rng::seed_from_u64(config.seed ^ CENSUS_SEED));
+        cubes.truncate(sample_regions.clamp(1, total_regions));
+        let mut samples = Vec::with_capacity(cubes.len());
+        for region in cubes {
+            if !samples.is_empty() && deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            samples.push(StageCounts::of_stars(&procgen::generate_stellar_detail(region, age_gyr)));
+        }
+        Self::from_samples(config.seed, age_gyr, &samples, total_regions)
+    }
+
+    /// Extrapolate per-region counts to `total_regions`: each total is the sample mean times
+    /// the region count, its interval the normal one from the sample variance with the
+    /// finite population correction (exact once every region is sampled)
+    pub fn from_samples(
+        seed: u64,
+        age_gyr: f64,
+        samples: &[StageCounts],
+        total_regions: usize,
+    ) -> Self {
+        let n = samples.len() as f64;
+        let regions = total_regions as f64;
+        let stages = std::array::from_fn(|i| {
+            let counts = samples.iter().map(|s| s.0[i] as f64);
+            let sampled: f64 = counts.clone().sum();
+            let mean = sampled / n.max(1.0);
+            let total = mean * regions;
+            let margin = if n >= 2.0 {
+                let variance = counts.map(|c| (c - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                let correction = (1.0 - n / regions).max(0.0);
+                Z_95 * regions * (variance / n * correction).sqrt()
+            } else {
+                // A single region says nothing about the spread
+                f64::INFINITY
+            };
+            let previous: u64 = match i {
+                0 => 0,
+                _ => samples.iter().map(|s| s.0[i - 1]).sum(),
+            };
+            StageEstimate {
+                stage: Stage::ALL[i],
+                sampled: sampled as u64,
+                rate: match i {
+                    0 => 1.0,
+                    _ if previous == 0 => 0.0,
+                    _ => sampled / previous as f64,
+                },
+                total,
+                low: (total - margin).max(sampled),
+                high: total + margin,
+            }
+        });
+        Self {
+            seed,
+            age_gyr,
+            sampled_regions: samples.len(),
+            total_regions,
+            stages,
+        }
+    }
+
+    pub fn stage(&self, stage: Stage) -> &StageEstimate {
+        &self.stages[stage as usize]
+    }
+}
+
+/// A quick look at a seed before it is played (the menu's universe gallery): its stars, a
+/// census cut short after a time budget and a density map through the middle of the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedPreview {
+    pub seed: u64,
+    /// Stars in the cubes of the grid, from their statistical estimates
+    pub stars: u64,
+    pub census: UniverseCensus,
+    /// Densities of the cubes in the grid's middle z layer, row by row from the lowest y,
+    /// `PREVIEW_MAP_SIZE` a side (the nearest cube for other grid sizes)
+    pub density_map: [f64; PREVIEW_MAP_SIZE * PREVIEW_MAP_SIZE],
+}
+
+impl SeedPreview {
+    /// Preview `config`'s universe at `age_gyr`, sampling up to `sample_regions` regions
+    /// for the census but none after `budget` has passed
+    pub fn compute(
+        config: &SimConfig,
+        age_gyr: f64,
+        sample_regions: usize,
+        budget: Duration,
+    ) -> Self {
+        let deadline = Instant::now() + budget;
+        let regions = procgen::generate_regions(config, age_gyr);
+        let cubes: Vec<&Region> = regions.iter().filter(|r| r.parent.is_none()).collect();
+        let stars = cubes.iter().fold(0u64, |acc, r| acc.saturating_add(r.star_count));
+
+        let layout = &config.region_layout;
+        let [nx, ny, nz] = layout.grid;
+        let side = PREVIEW_MAP_SIZE as u32;
+        let density_map = std::array::from_fn(|i| {
+            let (col, row) = ((i % PREVIEW_MAP_SIZE) as u32, (i / PREVIEW_MAP_SIZE) as u32);
+            let id = layout.cell_id([col * nx / side, row * ny / side, nz / 2]);
+            cubes.iter().find(|r| r.id == id).map_or(0.0, |r| r.density)
+        });
+
+        let census = UniverseCensus::sample(config, age_gyr, cubes, sample_regions, Some(deadline));
+        Self {
+            seed: config.seed,
+            stars,
+            census,
+            density_map,
+        }
+    }
+}
+
+/// Baryonic particles bound to a cluster, and their velocity dispersion (rms speed about
+/// the cluster's center-of-mass velocity, 0 with fewer than two)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BoundCluster {
+    pub bound: usize,
+    pub dispersion: f64,
+}
+
+/// A cluster's baryons as bound by all of its mass, and as they would be by the baryons
+/// alone: how much of the cluster the dark matter holds together
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClusterDynamics {
+    pub with_dark: BoundCluster,
+    pub without_dark: BoundCluster,
+}
+
+/// Bound baryons of the cluster of particles within `radius` of `center`. A particle is
+/// bound when its kinetic energy about the members' center-of-mass velocity is below the
+/// potential of the cluster mass at its distance (a point mass, softened within 1% of
+/// `radius`); `gravity_strength` as in the gravity step.
+pub fn cluster_dynamics(
+    particles: &[GpuParticle],
+    center: [f32; 3],
+    radius: f32,
+    gravity_strength: f32,
+) -> ClusterDynamics {
+    let distance = |p: &GpuParticle| -> f64 {
+        (0..3).map(|k| ((p.position[k] - center[k]) as f64).powi(2)).sum::<f64>().sqrt()
+    };
+    let members: Vec<&GpuParticle> = particles
+        .iter()
+        .filter(|p| p.is_alive() && p.mass() > 0.0 && distance(p) <= radius as f64)
+        .collect();
+    let is_dark = |p: &GpuParticle| p.kind == ParticleKind::DarkMatter as u32;
+
+    let bound = |include_dark: bool| -> BoundCluster {
+        let mass_of: Vec<&GpuParticle> =
+            members.iter().copied().filter(|p| include_dark || !is_dark(p)).collect();
+        let total: f64 = mass_of.iter().map(|p| p.mass() as f64).sum();
+        if total <= 0.0 {
+            return BoundCluster::default();
+        }
+        let mean_velocity: [f64; 3] = std::array::from_fn(|k| {
+            mass_of.iter().map(|p| p.velocity[k] as f64 * p.mass() as f64).sum::<f64>() / total
+        });
+        let speed_sq = |p: &GpuParticle| -> f64 {
+            (0..3).map(|k| (p.velocity[k] as f64 - mean_velocity[k]).powi(2)).sum()
+        };
+        let softening = 0.01 * radius as f64;
+        let bound: Vec<f64> = members
+            .iter()
+            .filter(|p| !is_dark(p))
+            .filter_map(|p| {
+                let v2 = speed_sq(p);
+                let potential = gravity_strength as f64 * total / distance(p).max(softening);
+                (0.5 * v2 < potential).then_some(v2)
+            })
+            .collect();
+        let dispersion = if bound.len() < 2 {
+            0.0
+        } else {
+            (bound.iter().sum::<f64>() / bound.len() as f64).sqrt()
+        };
+        BoundCluster { bound: bound.len(), dispersion }
+    };
+
+    ClusterDynamics {
+        with_dark: bound(true),
+        without_dark: bound(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_census_extrapolation_and_monotone_stages() {
+        let samples = [
+            StageCounts([100, 10, 4, 2, 1, 0]),
+            StageCounts([300, 30, 6, 2, 1, 1]),
+        ];
+        let census = UniverseCensus::from_samples(7, 13.8, &samples, 8);
+        let planets = census.stage(Stage::Planets);
+        // Mean 200 per region over 8 regions
+        assert_eq!(planets.sampled, 400);
+        assert!((planets.total - 1600.0).abs() < 1e-9);
+        // Variance 20000, se = 8 * sqrt(20000 / 2 * (1 - 2/8)) = 8 * sqrt(7500)
+        let margin = Z_95 * 8.0 * 7500.0f64.sqrt();
+        assert!((planets.high - (1600.0 + margin)).abs() < 1e-9);
+        // 1600 - margin is below the 400 planets already seen
+        assert_eq!(planets.low, 400.0);
+        assert!((census.stage(Stage::Habitable).rate - 0.1).abs() < 1e-12);
+        assert!((census.stage(Stage::Life).rate - 0.25).abs() < 1e-12);
+        // Identical counts: no spread
+        let intelligent = census.stage(Stage::Intelligent);
+        assert_eq!((intelligent.low, intelligent.high), (8.0, 8.0));
+        assert!(census.stage(Stage::Technological).low >= 1.0);
+        // Every region sampled: exact
+        let full = UniverseCensus::from_samples(7, 13.8, &samples, 2);
+        assert_eq!(full.stage(Stage::Planets).high, 400.0);
+
+        let census = UniverseCensus::compute(&SimConfig::default(), 13.8, 6);
+        assert_eq!((census.sampled_regions, census.total_regions), (6, 512));
+        assert_eq!(census, UniverseCensus::compute(&SimConfig::default(), 13.8, 6));
+        assert!(census.stage(Stage::Planets).sampled > 0);
+        for pair in census.stages.windows(2) {
+            assert!(pair[1].sampled <= pair[0].sampled, "{:?}", census.stages);
+            assert!(pair[1].total <= pair[0].total);
+            assert!((0.0..=1.0).contains(&pair[1].rate));
+        }
+    }
+
+    #[test]
+    fn test_seed_preview_maps_the_middle_layer_within_its_budget() {
+        let config = SimConfig::default();
+        let regions = procgen::generate_regions(&config, 13.8);
+        let preview = SeedPreview::compute(&config, 13.8, 6, Duration::from_secs(600));
+        // With time to spare it is the plain census
+        assert_eq!(preview.census, UniverseCensus::compute(&config, 13.8, 6));
+        let stars: u64 = regions.iter().map(|r| r.star_count).sum();
+        assert_eq!(preview.stars, stars);
+        // The default grid is 8 a side: one cube per cell, at z = 4
+        let layout = &config.region_layout;
+        for (i, &density) in preview.density_map.iter().enumerate() {
+            let id = layout.cell_id([(i % 8) as u32, (i / 8) as u32, 4]);
+            assert_eq!(density, regions[id as usize].density);
+        }
+
+        // Out of time, a single region is sampled
+        let rushed = SeedPreview::compute(&config, 13.8, 48, Duration::ZERO);
+        assert_eq!(rushed.census.sampled_regions, 1);
+        assert_eq!((rushed.stars, rushed.density_map), (preview.stars, preview.density_map));
+
+        // A smaller grid repeats its cubes across the map
+        let mut small = config.clone();
+        small.region_layout.grid = [4, 2, 1];
+        let preview = SeedPreview::compute(&small, 13.8, 2, Duration::from_secs(600));
+        let regions = procgen::generate_regions(&small, 13.8);
+        let corner = small.region_layout.cell_id([3, 1, 0]);
+        assert_eq!(preview.density_map[63], regions[corner as usize].density);
+        assert_eq!(preview.density_map[0], preview.density_map[1]);
+    }
+}
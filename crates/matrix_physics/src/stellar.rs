@@ -0,0 +1,236 @@
+use matrix_core::{Region, SpectralClass, Star, StellarRemnant};
+
+use super::procgen;
+
+/// Stars above this mass end in a core-collapse supernova
+pub const SUPERNOVA_MIN_MASS: f64 = 8.0;
+/// Stars above this mass collapse into a black hole instead of a neutron star
+pub const BLACK_HOLE_MIN_MASS: f64 = 25.0;
+/// Metals fraction added to a region per supernova
+const METALS_PER_SUPERNOVA: f64 = 2e-4;
+/// Enrichment cap (well above solar metallicity)
+const MAX_METALS: f64 = 0.1;
+/// Planets of stars within this distance of a supernova get heated
+pub const SUPERNOVA_HEAT_RADIUS: f64 = 5.0;
+/// Extra surface temperature at the exploding star itself (falls off with distance)
+const SUPERNOVA_PEAK_HEATING_K: f64 = 1500.0;
+
+/// A supernova produced while evolving a region's stars
+#[derive(Debug, Clone, PartialEq)]
+pub struct Supernova {
+    pub star_id: u64,
+    pub position: [f64; 3],
+    /// Universe age of the explosion (Gyr)
+    pub age: f64,
+    pub remnant: StellarRemnant,
+}
+
+/// Main-sequence lifetime in Gyr: t ≈ 10 Gyr · M^-2.5 (M in solar masses)
+pub fn main_sequence_lifetime(mass: f64) -> f64 {
+    10.0 * mass.max(0.01).powf(-2.5)
+}
+
+/// Share of a region's stars generated at `age_gyr` that are already remnants: formation
+/// ages uniform over the universe's age, masses from the procgen IMF, dead once
+/// `main_sequence_lifetime` has passed
+pub fn dead_star_fraction(age_gyr: f64) -> f64 {
+    const STEPS: usize = 64;
+    if age_gyr <= 0.0 {
+        return 0.0;
+    }
+    // Share of IMF masses above `mass` (procgen: 0.08 + 0.3·(1 − u)^(−1/1.3))
+    let heavier_than = |mass: f64| ((mass - 0.08) / 0.3).max(1.0).powf(-1.3);
+    (0..STEPS)
+        .map(|i| {
+            // Stars formed `lived` Gyr ago die above the mass whose lifetime that is
+            let lived = age_gyr * (i as f64 + 0.5) / STEPS as f64;
+            heavier_than((10.0 / lived).powf(0.4))
+        })
+        .sum::<f64>()
+        / STEPS as f64
+}
+
+/// Remnant left behind by a star of this initial mass
+pub fn remnant_for_mass(mass: f64) -> StellarRemnant {
+    if mass >= BLACK_HOLE_MIN_MASS {
+        StellarRemnant::BlackHole
+    } else if mass >= SUPERNOVA_MIN_MASS {
+        StellarRemnant::NeutronStar
+    } else {
+        StellarRemnant::WhiteDwarf
+    }
+}
+
+/// Remnant state of a star formed at `formed_at` once the universe reaches `age_gyr`,
+/// or `None` while it is still on the main sequence
+pub fn remnant_at(mass: f64, formed_at: f64, age_gyr: f64) -> Option<StellarRemnant> {
+    (age_gyr > formed_at + main_sequence_lifetime(mass)).then(|| remnant_for_mass(mass))
+}
+
+/// The stars of `region` as generated at `age_gyr`: procgen, then every star whose
+/// lifetime already ran out turned into its remnant. Pure, like `generate_stellar_detail`,
+/// so saves can store the loaded stars as a seed and age and regenerate them.
+pub fn generate_region_stars(region: &Region, age_gyr: f64) -> (Vec<Star>, Vec<Supernova>) {
+    let mut stars = procgen::generate_stellar_detail(region, age_gyr);
+    let supernovae = evolve_stars(&mut stars, age_gyr);
+    (stars, supernovae)
+}
+
+/// Turn every star whose lifetime has run out into its remnant.
+/// `Star::age` is treated as the universe age the star formed at.
+/// Planets near a supernova are heated; the explosions are returned
+/// so the caller can enrich the region and show them.
+pub fn evolve_stars(stars: &mut [Star], age_gyr: f64) -> Vec<Supernova> {
+    let mut supernovae = Vec::new();
+
+    for star in stars.iter_mut() {
+        if star.remnant.is_some() {
+            continue;
+        }
+        let Some(remnant) = remnant_at(star.mass, star.age, age_gyr) else {
+            continue;
+        };
+        if remnant.from_supernova() {
+            supernovae.push(Supernova {
+                star_id: star.id,
+                position: star.position,
+                age: star.age + main_sequence_lifetime(star.mass),
+                remnant,
+            });
+        }
+        become_remnant(star, remnant);
+    }
+
+    for sn in &supernovae {
+        heat_planets(stars, sn);
+    }
+
+    supernovae
+}
+
+/// Replace main-sequence properties with the remnant's
+fn become_remnant(star: &mut Star, remnant: StellarRemnant) {
+    let (mass, luminosity, surface_temp) = match remnant {
+        // Chandrasekhar-limited carbon-oxygen core
+        StellarRemnant::WhiteDwarf => ((0.45 + 0.1 * star.mass).min(1.4), 1e-3, 10_000.0),
+        StellarRemnant::NeutronStar => (1.4, 1e-4, 600_000.0),
+        // Roughly a third of the progenitor collapses, nothing shines
+        StellarRemnant::BlackHole => (star.mass * 0.3, 0.0, 0.0),
+    };
+    star.mass = mass;
+    star.luminosity = luminosity;
+    star.surface_temp = surface_temp;
+    star.spectral_class = SpectralClass::from_temperature(surface_temp);
+    star.remnant = Some(remnant);
+}
+
+/// Raise the surface temperature of planets around and near a supernova
+fn heat_planets(stars: &mut [Star], sn: &Supernova) {
+    for star in stars.iter_mut() {
+        let d = [
+            star.position[0] - sn.position[0],
+            star.position[1] - sn.position[1],
+            star.position[2] - sn.position[2],
+        ];
+        let dist = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        if dist > SUPERNOVA_HEAT_RADIUS {
+            continue;
+        }
+        let falloff = 1.0 - dist / SUPERNOVA_HEAT_RADIUS;
+        for planet in &mut star.planets {
+            planet.surface_temp += SUPERNOVA_PEAK_HEATING_K * falloff * falloff;
+        }
+    }
+}
+
+/// Region composition [H, He, metals] after `supernovae` explosions seeded it with metals.
+/// Metals come out of H and He in the same ratio as `cosmology::chemical_composition`.
+pub fn enrich_composition(composition: [f64; 3], supernovae: usize) -> [f64; 3] {
+    let [h, he, metals] = composition;
+    let added = (supernovae as f64 * METALS_PER_SUPERNOVA).min((MAX_METALS - metals).max(0.0));
+    [h - added * 0.6, he - added * 0.4, metals + added]
+}
+
+/// Number of stars in `stars` that ended in a supernova
+pub fn supernova_count(stars: &[Star]) -> usize {
+    stars
+        .iter()
+        .filter(|s| s.remnant.is_some_and(|r| r.from_supernova()))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{AtmosphereType, Planet, PlanetType};
+
+    fn star(id: u64, mass: f64, position: [f64; 3]) -> Star {
+        Star {
+            id,
+            position,
+            velocity: [0.0; 3],
+            mass,
+            luminosity: mass.powf(3.5),
+            surface_temp: 5778.0,
+            spectral_class: SpectralClass::G,
+            age: 0.0,
+            remnant: None,
+            planets: vec![Planet {
+                id: id * 1000,
+                orbital_radius: 1.0,
+                orbital_period: 1.0,
+                orbital_angle: 0.0,
+                mass: 1.0,
+                radius: 1.0,
+                surface_temp: 288.0,
+                tidally_locked: false,
+                has_water: true,
+                has_atmosphere: true,
+                atmosphere: AtmosphereType::NitrogenOxygen,
+                planet_type: PlanetType::Rocky,
+                life: None,
+                moons: Vec::new(),
+                rings: None,
+            }],
+            asteroid_belt: None,
+            comets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_massive_star_becomes_remnant() {
+        assert!(remnant_at(20.0, 0.0, 10.0).is_some());
+        assert_eq!(remnant_at(20.0, 0.0, 10.0), Some(StellarRemnant::NeutronStar));
+        assert_eq!(remnant_at(40.0, 0.0, 10.0), Some(StellarRemnant::BlackHole));
+    }
+
+    #[test]
+    fn test_low_mass_star_stays_on_main_sequence() {
+        // t(0.5 M☉) ≈ 57 Gyr
+        assert!(remnant_at(0.5, 0.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_supernova_enriches_and_heats() {
+        let mut stars = vec![
+            star(1, 20.0, [0.0; 3]),
+            star(2, 0.5, [1.0, 0.0, 0.0]),
+            star(3, 0.5, [50.0, 0.0, 0.0]),
+        ];
+        let supernovae = evolve_stars(&mut stars, 10.0);
+
+        assert_eq!(supernovae.len(), 1);
+        assert_eq!(stars[0].remnant, Some(StellarRemnant::NeutronStar));
+        assert!(stars[1].remnant.is_none());
+        assert!(stars[1].planets[0].surface_temp > 288.0);
+        assert_eq!(stars[2].planets[0].surface_temp, 288.0);
+
+        let before = [0.75, 0.24, 0.01];
+        let after = enrich_composition(before, supernova_count(&stars));
+        assert!(after[2] > before[2]);
+        assert!((after.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        // Already-evolved stars don't explode twice
+        assert!(evolve_stars(&mut stars, 12.0).is_empty());
+    }
+}
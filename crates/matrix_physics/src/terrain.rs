@@ -0,0 +1,467 @@
+//! Surface terrain: seeded value-noise fBm with domain warping, shaped by a
+//! per-planet-type preset. Heights are in meters; surface water sits at `WATER_LEVEL`.
+
+use std::cell::RefCell;
+
+use matrix_core::{Planet, PlanetType};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Nominal sea level the presets are built around (meters). The surface places its
+/// actual liquid level from the height distribution (`HeightSurvey::percentile`).
+pub const WATER_LEVEL: f32 = -0.5;
+/// Continentalness above which ocean planets have land
+const COAST_THRESHOLD: f32 = 0.1;
+/// Half extent of the area surveyed on landing for the biome height bands and sea level (meters)
+pub const SURVEY_EXTENT: f32 = 160.0;
+/// Samples along each side of the landing survey
+pub const SURVEY_SAMPLES: usize = 33;
+
+/// Fractal Brownian motion: `octaves` layers of noise, each `lacunarity` times the
+/// frequency and `gain` times the amplitude of the previous one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fbm {
+    pub octaves: u32,
+    /// Frequency of the first octave (cycles per meter)
+    pub frequency: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+/// Overall landform of a planet type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainStyle {
+    /// Ridged ranges, flattening into dune fields where the ground is arid
+    Mountains,
+    /// Islands rising out of the sea where continentalness crosses the coast threshold
+    Archipelago,
+    /// Basalt plateaus split by a network of cracks
+    CrackedBasalt,
+    /// Smooth rolling ice plains
+    Rolling,
+    /// Nearly flat cloud-top "surface" of the giants
+    CloudDeck,
+}
+
+/// How a planet type's terrain is shaped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainPreset {
+    pub style: TerrainStyle,
+    /// Peak height of the main landform (meters)
+    pub amplitude: f32,
+    pub fbm: Fbm,
+    /// Domain warp distance (meters); bends features into organic shapes
+    pub warp: f32,
+}
+
+impl TerrainPreset {
+    pub fn for_planet(planet_type: PlanetType) -> Self {
+        let fbm = |octaves, frequency, gain| Fbm {
+            octaves,
+            frequency,
+            lacunarity: 2.0,
+            gain,
+        };
+        match planet_type {
+            PlanetType::Rocky => Self {
+                style: TerrainStyle::Mountains,
+                amplitude: 22.0,
+                fbm: fbm(6, 1.0 / 90.0, 0.5),
+                warp: 14.0,
+            },
+            PlanetType::Ocean => Self {
+                style: TerrainStyle::Archipelago,
+                amplitude: 14.0,
+                fbm: fbm(5, 1.0 / 40.0, 0.5),
+                warp: 20.0,
+            },
+            PlanetType::Lava => Self {
+                style: TerrainStyle::CrackedBasalt,
+                amplitude: 12.0,
+                fbm: fbm(5, 1.0 / 70.0, 0.5),
+                warp: 8.0,
+            },
+            PlanetType::Frozen => Self {
+                style: TerrainStyle::Rolling,
+                amplitude: 10.0,
+                fbm: fbm(5, 1.0 / 110.0, 0.45),
+                warp: 10.0,
+            },
+            // Airless moons: rough regolith, never eroded flat
+            PlanetType::Barren => Self {
+                style: TerrainStyle::Rolling,
+                amplitude: 16.0,
+                fbm: fbm(6, 1.0 / 55.0, 0.55),
+                warp: 4.0,
+            },
+            PlanetType::GasGiant | PlanetType::IceGiant => Self {
+                style: TerrainStyle::CloudDeck,
+                amplitude: 2.5,
+                fbm: fbm(3, 1.0 / 60.0, 0.5),
+                warp: 6.0,
+            },
+        }
+    }
+}
+
+/// Terrain of one surface, with the seed's noise tables computed once.
+/// Build one per landing and sample it as often as needed.
+#[derive(Debug, Clone)]
+pub struct TerrainSampler {
+    pub seed: u64,
+    pub planet_type: PlanetType,
+    pub preset: TerrainPreset,
+    /// Lattice permutation, doubled so `perm[perm[i] + j]` never wraps
+    perm: [u8; 512],
+    /// Lattice values in [-1, 1]
+    values: [f32; 256],
+}
+
+impl TerrainSampler {
+    pub fn new(seed: u64, planet_type: PlanetType) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0x7e44_a1d5_0c3f_9b21);
+        let mut order: Vec<u8> = (0..=255).collect();
+        order.shuffle(&mut rng);
+        let mut perm = [0u8; 512];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = order[i & 255];
+        }
+        let mut values = [0.0f32; 256];
+        for v in &mut values {
+            *v = rng.gen_range(-1.0..1.0);
+        }
+        Self {
+            seed,
+            planet_type,
+            preset: TerrainPreset::for_planet(planet_type),
+            perm,
+            values,
+        }
+    }
+
+    /// Terrain height at (x, z) in meters
+    pub fn height(&self, x: f32, z: f32) -> f32 {
+        let p = &self.preset;
+        let (wx, wz) = self.warp(x, z);
+        match p.style {
+            TerrainStyle::Mountains => {
+                let base = self.fbm(wx, wz, &p.fbm);
+                let ridged = 1.0 - self.fbm(wx + 311.0, wz - 127.0, &p.fbm).abs();
+                let mountains = p.amplitude * (0.5 * base + 0.8 * ridged * ridged - 0.25);
+                // Dune fields: gentle swell plus noise-bent parallel crests
+                let bend = self.noise(x * 0.02 + 53.0, z * 0.02 - 19.0) * 6.0;
+                let crest = 1.0 - (x * 0.11 + z * 0.04 + bend).sin().abs();
+                let dunes = p.amplitude * 0.12 * base + 2.5 * crest * crest;
+                lerp(mountains, dunes, self.aridity(x, z))
+            }
+            TerrainStyle::Archipelago => {
+                let land = self.continentalness(x, z) - COAST_THRESHOLD;
+                let detail = self.fbm(wx, wz, &p.fbm);
+                // Hills only on land, fading in from the coast
+                let hills = detail * 3.0 * smoothstep(-0.05, 0.25, land);
+                WATER_LEVEL + land * p.amplitude + hills
+            }
+            TerrainStyle::CrackedBasalt => {
+                let plateau = self.fbm(wx, wz, &p.fbm) * p.amplitude * 0.6;
+                // Cracks follow the zero lines of a second noise field
+                let line = self.noise(wx / 24.0 + 71.0, wz / 24.0 - 33.0).abs();
+                let crack = 1.0 - smoothstep(0.0, 0.2, line);
+                plateau - crack * p.amplitude * 0.25
+            }
+            TerrainStyle::Rolling | TerrainStyle::CloudDeck => self.fbm(wx, wz, &p.fbm) * p.amplitude,
+        }
+    }
+
+    /// Upper bound on `|height|` for this preset
+    pub fn max_height(&self) -> f32 {
+        let p = &self.preset;
+        match p.style {
+            TerrainStyle::Mountains => p.amplitude * 1.05 + 2.5,
+            TerrainStyle::Archipelago => -WATER_LEVEL + p.amplitude * (1.0 + COAST_THRESHOLD) + 3.0,
+            TerrainStyle::CrackedBasalt => p.amplitude * 0.85,
+            TerrainStyle::Rolling | TerrainStyle::CloudDeck => p.amplitude,
+        }
+    }
+
+    /// Large-scale land/sea channel in [-1, 1]; ocean planets have land above the coast threshold
+    pub fn continentalness(&self, x: f32, z: f32) -> f32 {
+        let continents = Fbm {
+            octaves: 3,
+            frequency: 1.0 / 140.0,
+            lacunarity: 2.0,
+            gain: 0.5,
+        };
+        let (wx, wz) = self.warp(x, z);
+        (self.fbm(wx - 977.0, wz + 433.0, &continents) * 1.6).clamp(-1.0, 1.0)
+    }
+
+    /// How arid the ground is, 0 (wet) to 1 (dune field). Only rocky worlds grow dunes.
+    pub fn aridity(&self, x: f32, z: f32) -> f32 {
+        if self.preset.style != TerrainStyle::Mountains {
+            return 0.0;
+        }
+        let dryness = self.noise(x / 160.0 + 401.0, z / 160.0 + 199.0);
+        smoothstep(0.15, 0.45, dryness)
+    }
+
+    /// Steepness at (x, z): rise over run of the height field
+    pub fn slope(&self, x: f32, z: f32) -> f32 {
+        let (dx, dz) = self.gradient(x, z);
+        (dx * dx + dz * dz).sqrt()
+    }
+
+    /// Unit surface normal at (x, z)
+    pub fn normal(&self, x: f32, z: f32) -> [f32; 3] {
+        let (dx, dz) = self.gradient(x, z);
+        let len = (dx * dx + 1.0 + dz * dz).sqrt();
+        [-dx / len, 1.0 / len, -dz / len]
+    }
+
+    /// Height gradient by central differences
+    fn gradient(&self, x: f32, z: f32) -> (f32, f32) {
+        const H: f32 = 0.1;
+        let dx = (self.height(x + H, z) - self.height(x - H, z)) / (2.0 * H);
+        let dz = (self.height(x, z + H) - self.height(x, z - H)) / (2.0 * H);
+        (dx, dz)
+    }
+
+    /// Displace the sample point by two low-frequency noise fields
+    fn warp(&self, x: f32, z: f32) -> (f32, f32) {
+        let w = self.preset.warp;
+        if w == 0.0 {
+            return (x, z);
+        }
+        let f = 1.0 / 80.0;
+        (
+            x + self.noise(x * f + 5.2, z * f + 1.3) * w,
+            z + self.noise(x * f - 8.7, z * f + 2.8) * w,
+        )
+    }
+
+    /// fBm normalized to [-1, 1]. Octaves are offset so their lattices don't line up.
+    pub fn fbm(&self, x: f32, z: f32, fbm: &Fbm) -> f32 {
+        let mut sum = 0.0;
+        let mut norm = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = fbm.frequency;
+        for octave in 0..fbm.octaves {
+            let offset = octave as f32 * 19.19;
+            sum += self.noise(x * frequency + offset, z * frequency - offset) * amplitude;
+            norm += amplitude;
+            amplitude *= fbm.gain;
+            frequency *= fbm.lacunarity;
+        }
+        if norm > 0.0 { sum / norm } else { 0.0 }
+    }
+
+    /// Smoothly interpolated value noise in [-1, 1], one lattice cell per unit
+    pub fn noise(&self, x: f32, z: f32) -> f32 {
+        let (x0, z0) = (x.floor(), z.floor());
+        let (u, v) = (fade(x - x0), fade(z - z0));
+        let (ix, iz) = (x0 as i64, z0 as i64);
+        let corner = |i: i64, j: i64| {
+            let a = self.perm[(i & 255) as usize] as usize;
+            self.values[self.perm[a + (j & 255) as usize] as usize]
+        };
+        lerp(
+            lerp(corner(ix, iz), corner(ix + 1, iz), u),
+            lerp(corner(ix, iz + 1), corner(ix + 1, iz + 1), u),
+            v,
+        )
+    }
+}
+
+/// Sorted terrain heights sampled on a grid around the origin
+#[derive(Debug, Clone)]
+pub struct HeightSurvey {
+    sorted: Vec<f32>,
+}
+
+impl HeightSurvey {
+    /// Sample `samples`² heights over ±`extent` meters around the origin
+    pub fn new(sampler: &TerrainSampler, extent: f32, samples: usize) -> Self {
+        let samples = samples.max(2);
+        let step = 2.0 * extent / (samples - 1) as f32;
+        let mut sorted = Vec::with_capacity(samples * samples);
+        for i in 0..samples {
+            for j in 0..samples {
+                sorted.push(sampler.height(i as f32 * step - extent, j as f32 * step - extent));
+            }
+        }
+        sorted.sort_by(f32::total_cmp);
+        Self { sorted }
+    }
+
+    pub fn min(&self) -> f32 {
+        self.sorted.first().copied().unwrap_or(0.0)
+    }
+
+    pub fn max(&self) -> f32 {
+        self.sorted.last().copied().unwrap_or(0.0)
+    }
+
+    /// Height below which `fraction` (0–1) of the samples lie
+    pub fn percentile(&self, fraction: f32) -> f32 {
+        if self.sorted.is_empty() {
+            return 0.0;
+        }
+        let index = (fraction.clamp(0.0, 1.0) * (self.sorted.len() - 1) as f32).round() as usize;
+        self.sorted[index]
+    }
+}
+
+/// Fraction of the landing survey below the liquid level, for planets with a liquid layer
+/// (oceans, lakes on wet rocky worlds, lava seas, ice sheets)
+pub fn liquid_coverage(planet: &Planet) -> Option<f32> {
+    match planet.planet_type {
+        PlanetType::Ocean => Some(0.6),
+        PlanetType::Rocky if planet.has_water => Some(0.3),
+        PlanetType::Frozen => Some(0.3),
+        PlanetType::Lava => Some(0.2),
+        _ => None,
+    }
+}
+
+/// Survey the terrain around the landing site of `planet`: the lowest and highest height
+/// (the biome color bands) and the liquid level, if it has one
+pub fn survey_landing_site(sampler: &TerrainSampler, planet: &Planet) -> ((f32, f32), Option<f32>) {
+    let survey = HeightSurvey::new(sampler, SURVEY_EXTENT, SURVEY_SAMPLES);
+    let sea_level = liquid_coverage(planet).map(|coverage| survey.percentile(coverage));
+    ((survey.min(), survey.max()), sea_level)
+}
+
+thread_local! {
+    /// Sampler of the last surface asked for; landing keeps asking for the same one
+    static CACHED_SAMPLER: RefCell<Option<TerrainSampler>> = const { RefCell::new(None) };
+}
+
+/// Run `f` on the sampler of a surface. Reuses the last sampler built on this thread,
+/// so repeated calls for the same surface don't rebuild the noise tables.
+fn with_sampler<R>(seed: u64, planet_type: PlanetType, f: impl FnOnce(&TerrainSampler) -> R) -> R {
+    CACHED_SAMPLER.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let sampler = match cache.as_ref() {
+            Some(s) if s.seed == seed && s.planet_type == planet_type => s,
+            _ => cache.insert(TerrainSampler::new(seed, planet_type)),
+        };
+        f(sampler)
+    })
+}
+
+/// Terrain height at (x, z) for a surface
+pub fn terrain_height(x: f32, z: f32, seed: u64, planet_type: &PlanetType) -> f32 {
+    with_sampler(seed, *planet_type, |s| s.height(x, z))
+}
+
+/// Unit terrain normal at (x, z) for a surface
+pub fn terrain_normal(x: f32, z: f32, seed: u64, planet_type: &PlanetType) -> [f32; 3] {
+    with_sampler(seed, *planet_type, |s| s.normal(x, z))
+}
+
+/// Quintic fade curve (zero first and second derivative at the lattice points)
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_TYPES: [PlanetType; 6] = [
+        PlanetType::Rocky,
+        PlanetType::Ocean,
+        PlanetType::Lava,
+        PlanetType::Frozen,
+        PlanetType::GasGiant,
+        PlanetType::IceGiant,
+    ];
+
+    /// Points across a 200 m surface
+    fn grid() -> impl Iterator<Item = (f32, f32)> {
+        (0..=40).flat_map(|i| (0..=40).map(move |j| (i as f32 * 5.0 - 100.0, j as f32 * 5.0 - 100.0)))
+    }
+
+    #[test]
+    fn test_terrain_is_deterministic() {
+        for planet_type in ALL_TYPES {
+            let a = TerrainSampler::new(42, planet_type);
+            let b = TerrainSampler::new(42, planet_type);
+            assert!(grid().all(|(x, z)| a.height(x, z) == b.height(x, z)));
+            assert!(grid().all(|(x, z)| terrain_height(x, z, 42, &planet_type) == a.height(x, z)));
+        }
+        let other = TerrainSampler::new(43, PlanetType::Rocky);
+        let rocky = TerrainSampler::new(42, PlanetType::Rocky);
+        assert!(grid().any(|(x, z)| other.height(x, z) != rocky.height(x, z)));
+    }
+
+    #[test]
+    fn test_terrain_stays_in_range() {
+        for seed in [1, 7, 99, 12_345] {
+            for planet_type in ALL_TYPES {
+                let sampler = TerrainSampler::new(seed, planet_type);
+                let bound = sampler.max_height();
+                for (x, z) in grid() {
+                    let h = sampler.height(x, z);
+                    assert!(h.is_finite() && h.abs() <= bound, "{planet_type:?} seed {seed}: {h} > {bound}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_terrain_is_continuous() {
+        for seed in [3, 1_000] {
+            for planet_type in ALL_TYPES {
+                let sampler = TerrainSampler::new(seed, planet_type);
+                for row in [-80.0, -13.0, 0.0, 41.0] {
+                    let mut previous = sampler.height(-100.0, row);
+                    for i in 1..=800 {
+                        let h = sampler.height(-100.0 + i as f32 * 0.25, row);
+                        assert!((h - previous).abs() < 1.0, "{planet_type:?}: jump of {}", h - previous);
+                        previous = h;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_survey_percentiles() {
+        let sampler = TerrainSampler::new(5, PlanetType::Rocky);
+        let survey = HeightSurvey::new(&sampler, 100.0, 21);
+        let level = survey.percentile(0.3);
+        assert!(survey.min() <= level && level <= survey.max());
+        assert_eq!(survey.percentile(0.0), survey.min());
+        assert_eq!(survey.percentile(1.0), survey.max());
+        let below = grid().filter(|&(x, z)| sampler.height(x, z) < level).count();
+        let fraction = below as f32 / grid().count() as f32;
+        assert!((0.2..0.4).contains(&fraction), "{fraction}");
+    }
+
+    #[test]
+    fn test_ocean_planets_have_coastlines() {
+        // Over a few seeds, ocean surfaces have both land and sea
+        let (mut land, mut sea) = (0, 0);
+        for seed in 0..8 {
+            let sampler = TerrainSampler::new(seed, PlanetType::Ocean);
+            for (x, z) in grid() {
+                if sampler.height(x, z) > WATER_LEVEL {
+                    land += 1;
+                } else {
+                    sea += 1;
+                }
+            }
+        }
+        assert!(land > 0 && sea > 0, "land {land}, sea {sea}");
+    }
+}
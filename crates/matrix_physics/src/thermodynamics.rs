@@ -9,12 +9,9 @@ pub fn calculate_entropy_and_temperature(particles: &[GpuParticle]) -> (f64, f64
     }
 
     let mut n: u32 = 0;
-    let mut sum_vx: f64 = 0.0;
-    let mut sum_vy: f64 = 0.0;
-    let mut sum_vz: f64 = 0.0;
-    let mut sum_v2x: f64 = 0.0;
-    let mut sum_v2y: f64 = 0.0;
-    let mut sum_v2z: f64 = 0.0;
+    let mut mean = [0.0f64; 3];
+    // Sum of squared deviations from the running mean, per axis
+    let mut m2 = [0.0f64; 3];
     let mut total_ke: f64 = 0.0;
 
     for p in particles {
@@ -22,20 +19,14 @@ pub fn calculate_entropy_and_temperature(particles: &[GpuParticle]) -> (f64, f64
             continue;
         }
         n += 1;
-        let vx = p.velocity[0] as f64;
-        let vy = p.velocity[1] as f64;
-        let vz = p.velocity[2] as f64;
-
-        sum_vx += vx;
-        sum_vy += vy;
-        sum_vz += vz;
-        sum_v2x += vx * vx;
-        sum_v2y += vy * vy;
-        sum_v2z += vz * vz;
-
-        let v2 = (p.velocity[0] * p.velocity[0]
-            + p.velocity[1] * p.velocity[1]
-            + p.velocity[2] * p.velocity[2]) as f64;
+        let mut v2 = 0.0;
+        for k in 0..3 {
+            let v = p.velocity[k] as f64;
+            let delta = v - mean[k];
+            mean[k] += delta / n as f64;
+            m2[k] += delta * (v - mean[k]);
+            v2 += v * v;
+        }
         total_ke += 0.5 * p.mass() as f64 * v2;
     }
 
@@ -44,12 +35,7 @@ pub fn calculate_entropy_and_temperature(particles: &[GpuParticle]) -> (f64, f64
     }
 
     let nf = n as f64;
-
-    // Variance = E[X²] - E[X]² (single-pass formula)
-    let var_vx = sum_v2x / nf - (sum_vx / nf) * (sum_vx / nf);
-    let var_vy = sum_v2y / nf - (sum_vy / nf) * (sum_vy / nf);
-    let var_vz = sum_v2z / nf - (sum_vz / nf) * (sum_vz / nf);
-    let dispersion = (var_vx + var_vy + var_vz).max(1e-30);
+    let dispersion = (m2.iter().sum::<f64>() / nf).max(1e-30);
 
     let entropy = dispersion.ln().max(0.0) * nf;
     let temperature = total_ke / nf;
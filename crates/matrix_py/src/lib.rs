@@ -0,0 +1,169 @@
+//! Python bindings for procedural generation, for statistical studies outside the app.
+//! Built only with the `python` feature (`maturin develop` in this directory enables it),
+//! so the rest of the workspace never needs a Python interpreter.
+//!
+//! Values cross over in their serde form: structs are dicts with the Rust field names,
+//! unit enums (`PlanetType`, `AtmosphereType`, ...) are strings like `"Rocky"`.
+#![cfg(feature = "python")]
+
+use matrix_core::{AtmosphereType, PlanetType, Region, SimConfig};
+use matrix_physics::procgen;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use pyo3::IntoPyObjectExt;
+use rand::SeedableRng;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    value_to_py(py, &serde_json::to_value(value).map_err(value_error)?)
+}
+
+fn from_py<T: DeserializeOwned>(obj: &Bound<'_, PyAny>) -> PyResult<T> {
+    serde_json::from_value(py_to_value(obj)?).map_err(value_error)
+}
+
+fn value_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    match value {
+        Value::Null => Ok(py.None()),
+        Value::Bool(b) => b.into_py_any(py),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                u.into_py_any(py)
+            } else if let Some(i) = n.as_i64() {
+                i.into_py_any(py)
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_py_any(py)
+            }
+        }
+        Value::String(s) => s.into_py_any(py),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|v| value_to_py(py, v))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_py_any(py)
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (k, v) in fields {
+                dict.set_item(k, value_to_py(py, v)?)?;
+            }
+            dict.into_py_any(py)
+        }
+    }
+}
+
+fn py_to_value(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        Ok(Value::Null)
+    } else if obj.is_instance_of::<PyBool>() {
+        Ok(Value::Bool(obj.extract()?))
+    } else if obj.is_instance_of::<PyInt>() {
+        match obj.extract::<u64>() {
+            Ok(u) => Ok(Value::from(u)),
+            Err(_) => Ok(Value::from(obj.extract::<i64>()?)),
+        }
+    } else if obj.is_instance_of::<PyFloat>() {
+        Number::from_f64(obj.extract()?)
+            .map(Value::Number)
+            .ok_or_else(|| value_error("NaN and infinities can't be passed in"))
+    } else if obj.is_instance_of::<PyString>() {
+        Ok(Value::String(obj.extract()?))
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        obj.try_iter()?
+            .map(|item| py_to_value(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut fields = Map::new();
+        for (k, v) in dict.iter() {
+            fields.insert(k.extract()?, py_to_value(&v)?);
+        }
+        Ok(Value::Object(fields))
+    } else {
+        Err(value_error(format!(
+            "can't convert {} to a simulation value",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+/// Region grid of universe `seed` at `age_gyr` (default config otherwise), as dicts
+#[pyfunction]
+fn generate_regions(py: Python<'_>, seed: u64, age_gyr: f64) -> PyResult<PyObject> {
+    let config = SimConfig {
+        seed,
+        ..SimConfig::default()
+    };
+    to_py(py, &procgen::generate_regions(&config, age_gyr))
+}
+
+/// Stars of `region` (a dict from `generate_regions`) at `age_gyr`, with planets and life
+#[pyfunction]
+fn generate_stellar_detail(
+    py: Python<'_>,
+    region: &Bound<'_, PyAny>,
+    age_gyr: f64,
+) -> PyResult<PyObject> {
+    let region: Region = from_py(region)?;
+    to_py(py, &procgen::generate_stellar_detail(&region, age_gyr))
+}
+
+/// Chance that life arises on a habitable planet, as rolled once per planet by procgen
+#[pyfunction]
+#[pyo3(signature = (surface_temp, has_water, planet_type, life_age_gyr, tidally_locked = false))]
+fn probability_of_life(
+    surface_temp: f64,
+    has_water: bool,
+    planet_type: &Bound<'_, PyAny>,
+    life_age_gyr: f64,
+    tidally_locked: bool,
+) -> PyResult<f64> {
+    let planet_type: PlanetType = from_py(planet_type)?;
+    Ok(procgen::probability_of_life(
+        surface_temp,
+        has_water,
+        &planet_type,
+        life_age_gyr,
+        tidally_locked,
+    ))
+}
+
+/// A biosphere that has had `life_age_gyr` to evolve, deterministic in `seed`
+#[pyfunction]
+fn generate_biosphere(
+    py: Python<'_>,
+    life_age_gyr: f64,
+    surface_temp: f64,
+    planet_type: &Bound<'_, PyAny>,
+    atmosphere: &Bound<'_, PyAny>,
+    seed: u64,
+) -> PyResult<PyObject> {
+    let planet_type: PlanetType = from_py(planet_type)?;
+    let atmosphere: AtmosphereType = from_py(atmosphere)?;
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+    let bio = procgen::generate_biosphere(
+        life_age_gyr,
+        surface_temp,
+        &planet_type,
+        &atmosphere,
+        &mut rng,
+    );
+    to_py(py, &bio)
+}
+
+#[pymodule]
+fn matrix_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate_regions, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_stellar_detail, m)?)?;
+    m.add_function(wrap_pyfunction!(probability_of_life, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_biosphere, m)?)?;
+    Ok(())
+}
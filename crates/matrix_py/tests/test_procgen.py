@@ -0,0 +1,49 @@
+"""Checks for the Python bindings. Skipped unless the module is built
+(`maturin develop` in crates/matrix_py)."""
+
+import pytest
+
+matrix_py = pytest.importorskip("matrix_py")
+
+AGE = 13.8
+
+
+def densest(regions, n):
+    return sorted(regions, key=lambda r: r["density"], reverse=True)[:n]
+
+
+def test_same_seed_gives_the_same_stars():
+    a = matrix_py.generate_regions(42, AGE)
+    b = matrix_py.generate_regions(42, AGE)
+    assert a == b
+    for region in densest(a, 3):
+        stars_a = matrix_py.generate_stellar_detail(region, AGE)
+        stars_b = matrix_py.generate_stellar_detail(region, AGE)
+        assert len(stars_a) == len(stars_b)
+        assert stars_a == stars_b
+
+
+def test_known_seed_has_life():
+    planets = [
+        planet
+        for region in densest(matrix_py.generate_regions(42, AGE), 5)
+        for star in matrix_py.generate_stellar_detail(region, AGE)
+        for planet in star["planets"]
+    ]
+    assert any(planet["life"] is not None for planet in planets)
+
+
+def test_life_odds_and_biospheres():
+    earth_like = matrix_py.probability_of_life(288.0, True, "Rocky", 4.0)
+    dry = matrix_py.probability_of_life(288.0, False, "Rocky", 4.0)
+    assert 0.0 < dry < earth_like <= 0.15
+    locked = matrix_py.probability_of_life(288.0, True, "Rocky", 4.0, tidally_locked=True)
+    assert 0.0 < locked < earth_like
+
+    bio = matrix_py.generate_biosphere(4.0, 288.0, "Rocky", "NitrogenOxygen", 7)
+    assert bio == matrix_py.generate_biosphere(4.0, 288.0, "Rocky", "NitrogenOxygen", 7)
+    assert bio["species_count"] > 0
+    assert "substrate" in bio["dominant_genome"]
+
+    with pytest.raises(ValueError):
+        matrix_py.probability_of_life(288.0, True, "Banana", 4.0)
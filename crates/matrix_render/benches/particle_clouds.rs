@@ -0,0 +1,68 @@
+//! The particle cloud mesh update on a 100K-particle Big Bang, over one throttle period
+//! (3 frames): after a particle step (`moving`) and with nothing changed (`still`)
+
+use bevy::prelude::*;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use matrix_core::SimConfig;
+use matrix_physics::particle;
+use matrix_render::budget::RenderBudget;
+use matrix_render::camera::FlyCamera;
+use matrix_render::legend::ParticleViewFilter;
+use matrix_render::particles::{ParticleCloudState, sync_particle_clouds, update_particle_clouds};
+use matrix_sim::timings::SimTimings;
+use matrix_sim::universe::UniverseState;
+use rand::SeedableRng;
+
+const PARTICLE_COUNT: u32 = 100_000;
+/// How long the Big Bang coasts before it is drawn, so the cloud has a spread
+const COAST: f32 = 2.0;
+
+fn world() -> World {
+    let config = SimConfig {
+        particle_count: PARTICLE_COUNT,
+        ..SimConfig::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+    let mut particles = particle::generate_big_bang(&config, &mut rng);
+    for p in &mut particles {
+        for k in 0..3 {
+            p.position[k] += p.velocity[k] * COAST;
+        }
+    }
+
+    let mut world = World::new();
+    world.insert_resource(UniverseState::new(config, particles));
+    world.init_resource::<RenderBudget>();
+    world.init_resource::<ParticleViewFilter>();
+    world.init_resource::<ParticleCloudState>();
+    world.init_resource::<SimTimings>();
+    world.init_resource::<Assets<Mesh>>();
+    world.init_resource::<Assets<StandardMaterial>>();
+    world.spawn((FlyCamera::default(), Transform::from_xyz(0.0, 0.0, 50.0)));
+    world
+}
+
+fn particle_clouds(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_particle_clouds");
+    for (name, stepping) in [("moving", true), ("still", false)] {
+        let mut world = world();
+        let mut schedule = Schedule::default();
+        schedule.add_systems((sync_particle_clouds, update_particle_clouds).chain());
+        schedule.run(&mut world);
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                if stepping {
+                    let mut universe = world.resource_mut::<UniverseState>();
+                    universe.particle_steps = universe.particle_steps.wrapping_add(1);
+                }
+                for _ in 0..3 {
+                    schedule.run(&mut world);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, particle_clouds);
+criterion_main!(benches);
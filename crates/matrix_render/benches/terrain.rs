@@ -0,0 +1,34 @@
+//! Terrain chunk construction (height field, normals, ground colors, indices) for a few
+//! planet types, with a sea where the planet has one
+
+use std::hint::black_box;
+
+use bevy::math::IVec2;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use matrix_core::PlanetType;
+use matrix_physics::terrain::{self, TerrainSampler};
+use matrix_render::chunks::{LiquidKind, SurfaceLiquid, build_chunk_mesh};
+
+fn chunk_mesh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_chunk_mesh");
+    for (name, planet_type) in [
+        ("rocky", PlanetType::Rocky),
+        ("ocean", PlanetType::Ocean),
+        ("frozen", PlanetType::Frozen),
+    ] {
+        let sampler = TerrainSampler::new(2024, planet_type);
+        let survey = terrain::HeightSurvey::new(&sampler, terrain::SURVEY_EXTENT, 9);
+        let band = (survey.min(), survey.max());
+        let water = (planet_type != PlanetType::Frozen).then(|| SurfaceLiquid {
+            kind: LiquidKind::Water,
+            level: survey.percentile(0.3),
+        });
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| build_chunk_mesh(black_box(&sampler), IVec2::new(3, -2), band, water))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, chunk_mesh);
+criterion_main!(benches);
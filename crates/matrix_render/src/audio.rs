@@ -0,0 +1,659 @@
+//! Sound: an ambient bed (a drone in space, wind, rumble or near-silence on surfaces),
+//! calls from nearby thinking creatures and cues for feed events. Every sound is
+//! synthesized into a sample buffer at startup, so there are no audio files.
+
+use std::f32::consts::{PI, TAU};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Decodable, Source, Volume};
+use bevy::prelude::*;
+use matrix_core::{AtmosphereType, PlanetType, SimEvent, UniversePhase};
+use matrix_sim::events::EventFeed;
+use matrix_sim::state::AppState;
+use matrix_sim::universe::UniverseState;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::camera::FlyCamera;
+use super::settings::UserSettings;
+use super::surface::{self, NearestCreatureInfo, SurfaceState};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+/// Creatures call when the nearest one is this close (m)...
+const CALL_RANGE: f32 = 15.0;
+/// ...and their biosphere's dominant genome at least this clever
+const CALL_MIN_COGNITION: f64 = 0.2;
+/// Seconds between calls, plus up to as much again at random
+const CALL_INTERVAL: f32 = 3.0;
+/// Rate the space drone's pitch follows the phase (per second)
+const PITCH_RATE: f32 = 0.5;
+
+/// A synthesized mono sound
+#[derive(Asset, TypePath, Clone)]
+pub struct SynthSound {
+    samples: Arc<[f32]>,
+}
+
+impl SynthSound {
+    pub fn new(samples: Vec<f32>) -> Self {
+        Self {
+            samples: samples.into(),
+        }
+    }
+}
+
+/// Plays a `SynthSound` from the start
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    next: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.next).copied();
+        self.next += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.next))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(self.samples.len() as f64 / SAMPLE_RATE as f64))
+    }
+}
+
+impl Decodable for SynthSound {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            next: 0,
+        }
+    }
+}
+
+/// Looping background sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmbientBed {
+    /// Low drone in space, its pitch following the universe's phase
+    Space,
+    /// Breezy gusts: Earth-like and thin CO₂ air
+    Wind,
+    /// Slow heavy roar: thick CO₂ and hydrogen
+    Gale,
+    /// Deep rumble of a lava world
+    Rumble,
+    /// Beating tones in methane and exotic air
+    Eerie,
+    /// No air: next to nothing
+    Hush,
+}
+
+impl AmbientBed {
+    const ALL: [AmbientBed; 6] = [
+        AmbientBed::Space,
+        AmbientBed::Wind,
+        AmbientBed::Gale,
+        AmbientBed::Rumble,
+        AmbientBed::Eerie,
+        AmbientBed::Hush,
+    ];
+
+    /// Loudness before the volume settings
+    fn volume(self) -> f32 {
+        match self {
+            AmbientBed::Space => 0.35,
+            AmbientBed::Wind | AmbientBed::Gale => 0.5,
+            AmbientBed::Rumble => 0.6,
+            AmbientBed::Eerie => 0.3,
+            AmbientBed::Hush => 0.08,
+        }
+    }
+
+    fn samples(self) -> Vec<f32> {
+        match self {
+            AmbientBed::Space => drone(),
+            AmbientBed::Wind => wind(0.02, 6.0),
+            AmbientBed::Gale => wind(0.006, 8.0),
+            AmbientBed::Rumble => rumble(),
+            AmbientBed::Eerie => eerie(),
+            AmbientBed::Hush => wind(0.004, 8.0).into_iter().map(|s| s * 0.3).collect(),
+        }
+    }
+}
+
+/// The bed of a planet's surface: lava worlds rumble whatever their air, the rest
+/// sound like their atmosphere
+pub fn surface_bed(planet_type: PlanetType, atmosphere: &AtmosphereType) -> AmbientBed {
+    if planet_type == PlanetType::Lava {
+        return AmbientBed::Rumble;
+    }
+    match atmosphere {
+        AtmosphereType::None => AmbientBed::Hush,
+        AtmosphereType::NitrogenOxygen | AtmosphereType::ThinCO2 => AmbientBed::Wind,
+        AtmosphereType::ThickCO2 | AtmosphereType::Hydrogen => AmbientBed::Gale,
+        AtmosphereType::Methane | AtmosphereType::Exotic => AmbientBed::Eerie,
+    }
+}
+
+/// Drone playback speed in each phase: bright and fast in the hot early universe,
+/// deepest in the Heat Death, rising again in a collapse
+pub fn phase_pitch(phase: UniversePhase) -> f32 {
+    match phase {
+        UniversePhase::BigBang => 1.6,
+        UniversePhase::Inflation => 1.45,
+        UniversePhase::NuclearEra => 1.3,
+        UniversePhase::AtomicEra => 1.15,
+        UniversePhase::CosmicDawn => 1.05,
+        UniversePhase::StellarEra => 1.0,
+        UniversePhase::BiologicalEra | UniversePhase::CivilizationEra => 0.95,
+        UniversePhase::HeatDeath => 0.7,
+        UniversePhase::Collapse => 1.25,
+    }
+}
+
+/// One-shot sound for a feed event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    LifeDiscovered,
+    CivilizationDetected,
+    PhaseTransition,
+    SaveComplete,
+}
+
+impl Cue {
+    const ALL: [Cue; 4] = [
+        Cue::LifeDiscovered,
+        Cue::CivilizationDetected,
+        Cue::PhaseTransition,
+        Cue::SaveComplete,
+    ];
+
+    /// The cue a feed event plays, if any
+    pub fn for_event(event: &SimEvent) -> Option<Cue> {
+        match event {
+            SimEvent::LifeDiscovered { .. } => Some(Cue::LifeDiscovered),
+            SimEvent::CivilizationDetected { .. } => Some(Cue::CivilizationDetected),
+            SimEvent::PhaseTransition { .. } => Some(Cue::PhaseTransition),
+            SimEvent::SnapshotSaved { .. } => Some(Cue::SaveComplete),
+            _ => None,
+        }
+    }
+
+    fn samples(self) -> Vec<f32> {
+        match self {
+            // Rising C major arpeggio
+            Cue::LifeDiscovered => sequence(&[523.25, 659.25, 783.99], 0.14, |f, t| pluck(f, t, 6.0)),
+            // Two bell strikes a fifth apart
+            Cue::CivilizationDetected => sequence(&[440.0, 659.25], 0.35, bell),
+            Cue::PhaseTransition => swell(),
+            Cue::SaveComplete => sequence(&[880.0, 1318.5], 0.07, |f, t| pluck(f, t, 30.0)),
+        }
+    }
+}
+
+// --- Synthesis ---
+
+fn seconds(len: f32) -> usize {
+    (len * SAMPLE_RATE as f32) as usize
+}
+
+fn sine(freq: f32, t: f32) -> f32 {
+    (TAU * freq * t).sin()
+}
+
+/// Scale to a peak of `peak`
+fn normalize(mut samples: Vec<f32>, peak: f32) -> Vec<f32> {
+    let max = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if max > 0.0 {
+        samples.iter_mut().for_each(|s| *s *= peak / max);
+    }
+    samples
+}
+
+/// `len` seconds of a loop from a generator run `fade` seconds longer: the extra tail is
+/// faded into the start, so the end runs straight back into it
+fn seamless(len: f32, fade: f32, generate: impl FnOnce(usize) -> Vec<f32>) -> Vec<f32> {
+    let (n, fade) = (seconds(len), seconds(fade));
+    let mut samples = generate(n + fade);
+    for i in 0..fade {
+        let t = i as f32 / fade as f32;
+        samples[i] = samples[i] * t + samples[n + i] * (1.0 - t);
+    }
+    samples.truncate(n);
+    samples
+}
+
+/// White noise through a one-pole low-pass; smaller `alpha` is darker
+fn filtered_noise(n: usize, alpha: f32, seed: u64) -> Vec<f32> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut y = 0.0;
+    (0..n)
+        .map(|_| {
+            y += alpha * (rng.gen_range(-1.0f32..1.0) - y);
+            y
+        })
+        .collect()
+}
+
+/// Detuned low sines beating slowly. Every partial fits a whole number of cycles in the
+/// 4 s loop.
+fn drone() -> Vec<f32> {
+    let samples = (0..seconds(4.0))
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let swell = 0.8 + 0.2 * sine(0.25, t);
+            swell
+                * (0.5 * sine(55.0, t)
+                    + 0.25 * sine(55.25, t)
+                    + 0.3 * sine(82.5, t)
+                    + 0.1 * sine(110.0, t))
+        })
+        .collect();
+    normalize(samples, 0.8)
+}
+
+/// Low-passed noise in gusts lasting about `gust` seconds
+fn wind(alpha: f32, gust: f32) -> Vec<f32> {
+    seamless(gust, 0.5, |n| {
+        let noise = normalize(filtered_noise(n, alpha, 0x3d1a_77e5), 1.0);
+        let samples = noise
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let envelope = 0.55 + 0.45 * sine(1.0 / gust, t).abs();
+                s * envelope
+            })
+            .collect();
+        normalize(samples, 0.8)
+    })
+}
+
+fn rumble() -> Vec<f32> {
+    seamless(4.0, 0.5, |n| {
+        let noise = normalize(filtered_noise(n, 0.003, 0x1a7a_0b2c), 1.0);
+        let samples = noise
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                0.7 * s + 0.3 * sine(32.0, t) * (0.6 + 0.4 * sine(0.5, t))
+            })
+            .collect();
+        normalize(samples, 0.9)
+    })
+}
+
+fn eerie() -> Vec<f32> {
+    let samples = (0..seconds(4.0))
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            sine(220.0, t) + sine(221.5, t) + 0.5 * sine(330.25, t) * sine(0.25, t)
+        })
+        .collect();
+    normalize(samples, 0.6)
+}
+
+/// Plucked tone: quick attack, exponential decay at `decay` per second
+fn pluck(freq: f32, t: f32, decay: f32) -> f32 {
+    (t / 0.005).min(1.0) * (-t * decay).exp() * sine(freq, t)
+}
+
+/// Struck bell: inharmonic partials, the higher ones dying first
+fn bell(freq: f32, t: f32) -> f32 {
+    let attack = (t / 0.003).min(1.0);
+    attack
+        * (sine(freq, t) * (-t * 3.0).exp()
+            + 0.5 * sine(freq * 2.76, t) * (-t * 5.0).exp()
+            + 0.25 * sine(freq * 5.4, t) * (-t * 8.0).exp())
+}
+
+/// Notes `step` seconds apart, each left to ring out over the rest
+fn sequence(freqs: &[f32], step: f32, note: impl Fn(f32, f32) -> f32) -> Vec<f32> {
+    let len = step * freqs.len() as f32 + 0.8;
+    let samples = (0..seconds(len))
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            freqs
+                .iter()
+                .enumerate()
+                .map(|(k, &f)| {
+                    let start = k as f32 * step;
+                    if t >= start { note(f, t - start) } else { 0.0 }
+                })
+                .sum()
+        })
+        .collect();
+    normalize(samples, 0.8)
+}
+
+/// Two seconds of a low tone sliding up an octave, fading in and out
+fn swell() -> Vec<f32> {
+    let len = 2.0;
+    let mut phase = 0.0;
+    let samples = (0..seconds(len))
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let freq = 70.0 * 2f32.powf(t / len);
+            phase += TAU * freq / SAMPLE_RATE as f32;
+            (PI * t / len).sin() * (phase.sin() + 0.3 * (2.0 * phase).sin())
+        })
+        .collect();
+    normalize(samples, 0.8)
+}
+
+/// A creature's call: two quick upward chirps
+fn chirp() -> Vec<f32> {
+    let (len, gap) = (0.08, 0.05);
+    let mut phase = 0.0;
+    let samples = (0..seconds(2.0 * len + gap))
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let local = if t < len { t } else { t - len - gap };
+            if !(0.0..len).contains(&local) {
+                return 0.0;
+            }
+            let freq = 1800.0 + 800.0 * local / len;
+            phase += TAU * freq / SAMPLE_RATE as f32;
+            (PI * local / len).sin() * phase.sin()
+        })
+        .collect();
+    normalize(samples, 0.8)
+}
+
+// --- Resources & systems ---
+
+/// Handles of every synthesized sound
+#[derive(Resource)]
+pub struct SynthSounds {
+    beds: Vec<(AmbientBed, Handle<SynthSound>)>,
+    cues: Vec<(Cue, Handle<SynthSound>)>,
+    chirp: Handle<SynthSound>,
+}
+
+impl SynthSounds {
+    fn bed(&self, bed: AmbientBed) -> Handle<SynthSound> {
+        self.beds.iter().find(|(b, _)| *b == bed).map(|(_, h)| h.clone()).unwrap_or_default()
+    }
+
+    fn cue(&self, cue: Cue) -> Handle<SynthSound> {
+        self.cues.iter().find(|(c, _)| *c == cue).map(|(_, h)| h.clone()).unwrap_or_default()
+    }
+}
+
+/// The ambient bed playing, if any
+#[derive(Resource, Default)]
+pub struct AmbientPlayer {
+    bed: Option<AmbientBed>,
+    entity: Option<Entity>,
+    /// Current drone speed, easing toward `phase_pitch`
+    pitch: f32,
+}
+
+/// Every entity playing a sound; all of them go when the game stops running
+#[derive(Component)]
+pub struct MatrixSound;
+
+/// Volume of an ambient bed after the master and ambient sliders
+fn ambient_volume(settings: &UserSettings, bed: AmbientBed) -> f32 {
+    settings.master_volume * settings.ambient_volume * bed.volume()
+}
+
+fn effects_volume(settings: &UserSettings) -> f32 {
+    settings.master_volume * settings.effects_volume
+}
+
+/// Synthesize every sound (a fraction of a second, once)
+pub fn build_sounds(mut commands: Commands, mut assets: ResMut<Assets<SynthSound>>) {
+    let mut add = |samples| assets.add(SynthSound::new(samples));
+    let beds = AmbientBed::ALL.map(|bed| (bed, add(bed.samples()))).to_vec();
+    let cues = Cue::ALL.map(|cue| (cue, add(cue.samples()))).to_vec();
+    let chirp = add(chirp());
+    commands.insert_resource(SynthSounds { beds, cues, chirp });
+}
+
+/// The camera hears spatial sounds (creature calls)
+pub fn attach_listener(
+    mut commands: Commands,
+    camera_q: Query<Entity, (With<FlyCamera>, Without<SpatialListener>)>,
+) {
+    for entity in &camera_q {
+        commands.entity(entity).insert(SpatialListener::new(0.3));
+    }
+}
+
+/// Switch the bed with the surroundings; keep its volume on the sliders and, in space,
+/// the drone's pitch on the universe's phase
+pub fn ambient_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    (state, universe): (Res<SurfaceState>, Res<UniverseState>),
+    (settings, sounds): (Res<UserSettings>, Res<SynthSounds>),
+    mut ambient: ResMut<AmbientPlayer>,
+    sink_q: Query<&AudioSink>,
+) {
+    let bed = match state.planet {
+        Some(ref planet) if state.active => surface_bed(planet.planet_type, &planet.atmosphere),
+        _ => AmbientBed::Space,
+    };
+    let target_pitch = if bed == AmbientBed::Space {
+        phase_pitch(universe.phase)
+    } else {
+        1.0
+    };
+    if ambient.bed != Some(bed) {
+        if let Some(entity) = ambient.entity.take() {
+            commands.entity(entity).despawn();
+        }
+        let entity = commands
+            .spawn((
+                AudioPlayer(sounds.bed(bed)),
+                PlaybackSettings::LOOP
+                    .with_volume(Volume::new(ambient_volume(&settings, bed)))
+                    .with_speed(target_pitch),
+                MatrixSound,
+            ))
+            .id();
+        *ambient = AmbientPlayer {
+            bed: Some(bed),
+            entity: Some(entity),
+            pitch: target_pitch,
+        };
+        return;
+    }
+
+    let ease = (PITCH_RATE * time.delta_secs()).min(1.0);
+    ambient.pitch += (target_pitch - ambient.pitch) * ease;
+    if let Some(sink) = ambient.entity.and_then(|e| sink_q.get(e).ok()) {
+        sink.set_volume(ambient_volume(&settings, bed));
+        sink.set_speed(ambient.pitch);
+    }
+}
+
+/// A call from the nearest creature every few seconds while it is within earshot and
+/// its kind is clever enough, higher-pitched for some species than others
+pub fn creature_call_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    state: Res<SurfaceState>,
+    nearest: Res<NearestCreatureInfo>,
+    settings: Res<UserSettings>,
+    sounds: Res<SynthSounds>,
+    mut cooldown: Local<f32>,
+) {
+    *cooldown -= time.delta_secs();
+    let cognition = state
+        .planet
+        .as_ref()
+        .and_then(|p| p.life.as_ref())
+        .map_or(0.0, |bio| bio.dominant_genome.cognition);
+    if !state.active
+        || cognition <= CALL_MIN_COGNITION
+        || nearest.distance > CALL_RANGE
+        || *cooldown > 0.0
+    {
+        return;
+    }
+    *cooldown = CALL_INTERVAL * (1.0 + rand::random::<f32>());
+    commands.spawn((
+        AudioPlayer(sounds.chirp.clone()),
+        PlaybackSettings::DESPAWN
+            .with_spatial(true)
+            .with_volume(Volume::new(effects_volume(&settings)))
+            .with_speed(0.7 + 0.2 * (nearest.species % 4) as f32),
+        Transform::from_translation(nearest.position),
+        MatrixSound,
+    ));
+}
+
+/// Play the cue of each new feed event (one per kind per frame)
+pub fn event_cue_system(
+    mut commands: Commands,
+    feed: Res<EventFeed>,
+    settings: Res<UserSettings>,
+    sounds: Res<SynthSounds>,
+    mut seen: Local<Option<u64>>,
+) {
+    // Entries from before the first run (and a new universe's reset count) stay quiet
+    let pushed = feed.pushed();
+    let last = seen.filter(|&s| s <= pushed).unwrap_or(pushed);
+    *seen = Some(pushed);
+    let fresh = ((pushed - last) as usize).min(feed.entries().len());
+    let mut played = Vec::new();
+    for entry in feed.entries().iter().rev().take(fresh) {
+        let Some(cue) = Cue::for_event(&entry.event) else {
+            continue;
+        };
+        if played.contains(&cue) {
+            continue;
+        }
+        played.push(cue);
+        commands.spawn((
+            AudioPlayer(sounds.cue(cue)),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(effects_volume(&settings))),
+            MatrixSound,
+        ));
+    }
+}
+
+/// Silence everything when the pause menu or the main menu comes up; the bed starts
+/// again when the game runs
+pub fn stop_all_sounds(
+    mut commands: Commands,
+    mut ambient: ResMut<AmbientPlayer>,
+    sound_q: Query<Entity, With<MatrixSound>>,
+) {
+    for entity in &sound_q {
+        commands.entity(entity).despawn();
+    }
+    *ambient = AmbientPlayer::default();
+}
+
+/// Ambient sound, creature calls and event cues
+pub struct MatrixAudioPlugin;
+
+impl Plugin for MatrixAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<SynthSound>()
+            .init_resource::<AmbientPlayer>()
+            .add_systems(Startup, build_sounds)
+            .add_systems(OnExit(AppState::Running), stop_all_sounds)
+            .add_systems(
+                Update,
+                (
+                    attach_listener,
+                    ambient_system.after(surface::surface_enter_exit_system),
+                    creature_call_system.after(surface::creature_proximity_system),
+                    event_cue_system,
+                )
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+    use matrix_core::{FeedEntry, SimConfig};
+
+    #[test]
+    fn test_sounds_loop_and_stop_with_the_game() {
+        // Loops run back into their start, everything stays in range
+        for bed in AmbientBed::ALL {
+            let samples = bed.samples();
+            // The jump from the end back to the start is no bigger than any step inside
+            let step = samples.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f32::max);
+            let wrap = (samples[0] - samples[samples.len() - 1]).abs();
+            assert!(wrap <= step, "{bed:?} clicks at the loop point");
+            assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        }
+        for cue in Cue::ALL {
+            let samples = cue.samples();
+            assert!(samples.iter().all(|s| s.abs() <= 1.0));
+            assert!(samples.iter().any(|s| s.abs() > 0.5), "{cue:?} is silent");
+        }
+        assert_eq!(surface_bed(PlanetType::Lava, &AtmosphereType::None), AmbientBed::Rumble);
+        assert_eq!(surface_bed(PlanetType::Rocky, &AtmosphereType::None), AmbientBed::Hush);
+        assert_eq!(surface_bed(PlanetType::Ocean, &AtmosphereType::NitrogenOxygen), AmbientBed::Wind);
+        assert!(phase_pitch(UniversePhase::BigBang) > phase_pitch(UniversePhase::HeatDeath));
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .init_asset::<SynthSound>()
+            .init_state::<AppState>()
+            .insert_resource(UniverseState::empty(SimConfig::default()))
+            .init_resource::<EventFeed>()
+            .init_resource::<SurfaceState>()
+            .init_resource::<NearestCreatureInfo>()
+            .init_resource::<UserSettings>()
+            .init_resource::<AmbientPlayer>()
+            .add_systems(Startup, build_sounds)
+            .add_systems(OnExit(AppState::Running), stop_all_sounds)
+            .add_systems(
+                Update,
+                (ambient_system, event_cue_system).run_if(in_state(AppState::Running)),
+            );
+        app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Running);
+        app.update();
+        let sounds = |app: &mut App| {
+            let mut q = app.world_mut().query_filtered::<Entity, With<MatrixSound>>();
+            q.iter(app.world()).count()
+        };
+        // The space drone, no cue for the events from before
+        assert_eq!(sounds(&mut app), 1);
+        assert_eq!(app.world().resource::<AmbientPlayer>().bed, Some(AmbientBed::Space));
+
+        let entry = |event| FeedEntry { age_gyr: 1.0, event };
+        let mut feed = app.world_mut().resource_mut::<EventFeed>();
+        feed.push(entry(SimEvent::SnapshotSaved { path: "a".into() }));
+        feed.push(entry(SimEvent::SnapshotSaved { path: "b".into() }));
+        feed.push(entry(SimEvent::CycleBegan { cycle: 2 }));
+        app.update();
+        // One save cue for two saves, nothing for the new cycle
+        assert_eq!(sounds(&mut app), 2);
+
+        app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Paused);
+        app.update();
+        assert_eq!(sounds(&mut app), 0);
+        assert_eq!(app.world().resource::<AmbientPlayer>().bed, None);
+    }
+}
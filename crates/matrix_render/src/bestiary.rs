@@ -0,0 +1,320 @@
+use bevy::prelude::*;
+use matrix_core::BestiaryEntry;
+use matrix_physics::procgen;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+
+use super::input_capture::CapturesPointer;
+use super::locale::Strings;
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{NearestCreatureInfo, SurfaceState};
+
+/// Creatures closer than this can be scanned with [E] (they hold still at this range too)
+pub const INSPECT_RANGE: f32 = 3.0;
+
+const PANEL_COLOR: Color = Color::srgba(0.0, 0.05, 0.02, 0.9);
+const TEXT_COLOR: Color = Color::srgba(0.4, 1.0, 0.6, 0.95);
+
+/// [E] inspection card and [I] bestiary screen state
+#[derive(Resource, Default)]
+pub struct BestiaryUi {
+    /// The scanned creature shown on the card
+    pub card: Option<BestiaryEntry>,
+    pub screen_open: bool,
+    /// Bestiary size when the screen text was last built
+    shown: Option<usize>,
+}
+
+/// Marker for the inspection card root node
+#[derive(Component)]
+pub struct InspectCardRoot;
+
+/// Marker for the inspection card text
+#[derive(Component)]
+pub struct InspectCardText;
+
+/// Marker for the bestiary screen root node
+#[derive(Component)]
+pub struct BestiaryRoot;
+
+/// Marker for the bestiary screen text
+#[derive(Component)]
+pub struct BestiaryText;
+
+/// Spawn the (hidden) inspection card, top center, and bestiary screen, center
+pub fn spawn_bestiary_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            InspectCardRoot,
+        ))
+        .with_children(|card| {
+            card.spawn((panel_text(14.0), InspectCardText));
+        });
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(12.0),
+                left: Val::Percent(20.0),
+                width: Val::Percent(60.0),
+                max_height: Val::Percent(76.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                overflow: Overflow::clip_y(),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            BestiaryRoot,
+            CapturesPointer,
+        ))
+        .with_children(|screen| {
+            screen.spawn((panel_text(13.0), BestiaryText));
+        });
+}
+
+fn panel_text(font_size: f32) -> impl Bundle {
+    (
+        Text::new(""),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+    )
+}
+
+/// [E] next to a creature scans it: names the species, records it in the bestiary
+/// and opens its card. [E] again, walking away or leaving the surface closes the card.
+pub fn inspect_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    (state, nearest): (Res<SurfaceState>, Res<NearestCreatureInfo>),
+    (universe, strings, mut lazy): (Res<UniverseState>, Res<Strings>, ResMut<LazyUniverse>),
+    mut ui: ResMut<BestiaryUi>,
+    mut root_q: Query<&mut Node, With<InspectCardRoot>>,
+    mut text_q: Query<&mut Text, With<InspectCardText>>,
+) {
+    let in_range = state.active && nearest.traits.is_some() && nearest.distance < INSPECT_RANGE;
+    let pressed = action_just_pressed(&settings, &keyboard, Action::Inspect);
+
+    let was_open = ui.card.is_some();
+    if was_open && (pressed || !in_range) {
+        ui.card = None;
+    } else if !was_open && pressed && in_range {
+        let (Some(planet), Some(traits)) = (&state.planet, &nearest.traits) else {
+            return;
+        };
+        let Some(bio) = &planet.life else {
+            return;
+        };
+        let entry = BestiaryEntry {
+            name: procgen::binomial_name(planet.id, &bio.dominant_genome, nearest.species),
+            planet_id: planet.id,
+            planet_type: planet.planet_type,
+            species: nearest.species,
+            traits: traits.clone(),
+            genome: bio.dominant_genome.clone(),
+            scanned_age: universe.age,
+        };
+        let new = lazy.bestiary.record(entry.clone());
+        if let Ok(mut text) = text_q.get_single_mut() {
+            let home = lazy.planet_name(planet.id);
+            **text = card_text(&entry, &home, &nearest, new, lazy.bestiary.len(), &strings);
+        }
+        ui.card = Some(entry);
+    }
+
+    if ui.card.is_some() != was_open {
+        for mut node in &mut root_q {
+            node.display = if ui.card.is_some() { Display::Flex } else { Display::None };
+        }
+    }
+}
+
+fn card_text(
+    entry: &BestiaryEntry,
+    home: &str,
+    nearest: &NearestCreatureInfo,
+    new: bool,
+    catalogued: usize,
+    strings: &Strings,
+) -> String {
+    let traits = &entry.traits;
+    let genome = &entry.genome;
+    let role = traits
+        .role
+        .map_or_else(|| strings.tr("bestiary.no_role", &[]), |r| r.name().to_string());
+    let new = if new { strings.tr("bestiary.new_species", &[]) } else { String::new() };
+    strings.tr(
+        "bestiary.card",
+        &[
+            ("name", &entry.name),
+            ("new", &new),
+            ("description", &traits.describe(genome.size_log)),
+            ("state", &nearest.state.name()),
+            ("size", &strings.number(traits.size_m(), 2)),
+            ("elongation", &strings.number(traits.elongation as f64, 2)),
+            ("hue", &format!("{:+.0}", traits.hue_shift)),
+            ("role", &role),
+            ("motility", &matrix_core::Genome::motility_name(traits.motility)),
+            ("genome", &genome.describe()),
+            ("senses", &genome.sense_list().join(", ")),
+            ("home", &home),
+            ("id", &entry.planet_id),
+            ("kind", &format!("{:?}", entry.planet_type)),
+            ("count", &catalogued),
+        ],
+    )
+}
+
+/// [I] shows / hides the bestiary screen, rebuilt when a species is added
+pub fn bestiary_screen_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    lazy: Res<LazyUniverse>,
+    strings: Res<Strings>,
+    mut ui: ResMut<BestiaryUi>,
+    mut root_q: Query<&mut Node, With<BestiaryRoot>>,
+    mut text_q: Query<&mut Text, With<BestiaryText>>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::Bestiary) {
+        ui.screen_open = !ui.screen_open;
+        ui.shown = None;
+        for mut node in &mut root_q {
+            node.display = if ui.screen_open { Display::Flex } else { Display::None };
+        }
+    }
+    if !ui.screen_open || (ui.shown == Some(lazy.bestiary.len()) && !strings.is_changed()) {
+        return;
+    }
+    ui.shown = Some(lazy.bestiary.len());
+    if let Ok(mut text) = text_q.get_single_mut() {
+        **text = bestiary_text(lazy.bestiary.entries(), &strings);
+    }
+}
+
+fn bestiary_text(entries: &[BestiaryEntry], strings: &Strings) -> String {
+    let mut lines = vec![strings.tr("bestiary.title", &[("count", &entries.len())])];
+    if entries.is_empty() {
+        lines.push(String::new());
+        lines.push(strings.tr("bestiary.empty", &[]));
+    }
+    for entry in entries {
+        lines.push(String::new());
+        lines.push(strings.tr(
+            "bestiary.entry",
+            &[
+                ("name", &entry.name),
+                ("planet", &entry.planet_id),
+                ("kind", &format!("{:?}", entry.planet_type)),
+                ("age", &strings.number(entry.scanned_age, 2)),
+            ],
+        ));
+        lines.push(format!("  {}", entry.portrait()));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{AtmosphereType, Biosphere, CreatureRole, Genome, IndividualTraits, Planet, PlanetType};
+
+    fn planet() -> Planet {
+        let mut genome = Genome::primordial();
+        genome.size_log = 0.2;
+        genome.motility = 5;
+        Planet {
+            id: 3_001,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 288.0,
+            tidally_locked: false,
+            has_water: true,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type: PlanetType::Rocky,
+            life: Some(Biosphere {
+                age: 3.0,
+                complexity: 6.0,
+                species_count: 4_000,
+                dominant_genome: genome,
+                has_technology: false,
+                biomass: 1.0,
+            }),
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    fn press_e(app: &mut App) {
+        let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard.release(KeyCode::KeyE);
+        keyboard.clear();
+        keyboard.press(KeyCode::KeyE);
+        app.update();
+    }
+
+    #[test]
+    fn test_scanning_records_each_species_once() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<BestiaryUi>()
+            .init_resource::<UserSettings>()
+            .init_resource::<Strings>()
+            .insert_resource(UniverseState::new(matrix_core::SimConfig::default(), Vec::new()))
+            .insert_resource(LazyUniverse::empty(matrix_core::SimConfig::default()))
+            .insert_resource(SurfaceState {
+                active: true,
+                planet: Some(planet()),
+                ..default()
+            })
+            .insert_resource(NearestCreatureInfo {
+                distance: 2.0,
+                species: 1,
+                traits: Some(IndividualTraits {
+                    size_log: 0.3,
+                    hue_shift: 5.0,
+                    elongation: 1.2,
+                    motility: 5,
+                    role: Some(CreatureRole::Grazer),
+                }),
+                ..default()
+            })
+            .add_systems(Startup, spawn_bestiary_ui)
+            .add_systems(Update, inspect_system);
+        app.update();
+
+        press_e(&mut app);
+        let card = app.world().resource::<BestiaryUi>().card.clone().unwrap();
+        assert_eq!(card.species, 1);
+        assert_eq!(card.name, procgen::binomial_name(3_001, &planet().life.unwrap().dominant_genome, 1));
+        assert_eq!(app.world().resource::<LazyUniverse>().bestiary.len(), 1);
+
+        // [E] closes, scanning again doesn't duplicate the species
+        press_e(&mut app);
+        assert!(app.world().resource::<BestiaryUi>().card.is_none());
+        press_e(&mut app);
+        assert!(app.world().resource::<BestiaryUi>().card.is_some());
+        assert_eq!(app.world().resource::<LazyUniverse>().bestiary.len(), 1);
+
+        // Walking away closes the card
+        app.world_mut().resource_mut::<NearestCreatureInfo>().distance = 4.0;
+        app.update();
+        assert!(app.world().resource::<BestiaryUi>().card.is_none());
+    }
+}
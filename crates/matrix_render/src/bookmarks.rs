@@ -0,0 +1,353 @@
+//! [Ctrl+1..9] stores the camera view in a bookmark slot, [Alt+1..9] (or `bm <slot>` in
+//! the console) returns to it. Bookmarks live in `LazyUniverse::bookmarks`, so snapshots
+//! carry them, and per seed in `bookmarks.ron` next to the executable, so a new world
+//! of the same seed starts with them.
+
+use bevy::prelude::*;
+use matrix_core::bookmarks::BOOKMARK_SLOTS;
+use matrix_core::names::PLANET_ID_STRIDE;
+use matrix_core::{Bookmark, Bookmarks};
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::replay::ReplayRecorder;
+use matrix_sim::universe::UniverseState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::camera::{self, CameraGlide, FlyCamera, LEVEL_PIN_SECS, ZoomLevel};
+use super::locale::Strings;
+use super::surface::{self, PlanetSelection, SystemFocus};
+
+/// Keys of slots 1 to 9
+const SLOT_KEYS: [KeyCode; BOOKMARK_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+/// `Bookmark::zoom_level` indexes this
+const LEVELS: [ZoomLevel; 5] = [
+    ZoomLevel::Cosmic,
+    ZoomLevel::Galactic,
+    ZoomLevel::Stellar,
+    ZoomLevel::Planetary,
+    ZoomLevel::Surface,
+];
+
+/// Return trips in progress
+#[derive(Resource, Default)]
+pub struct BookmarkRecall {
+    /// Slot asked for from the console, recalled on the next frame
+    pub requested: Option<usize>,
+    /// Planet to select once its region is loaded: (region, star, planet)
+    pub pending: Option<(u64, u64, u64)>,
+}
+
+pub fn bookmarks_path() -> PathBuf {
+    PathBuf::from("bookmarks.ron")
+}
+
+/// `bookmarks.ron`: every seed's bookmarks
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkFile {
+    pub seeds: BTreeMap<u64, Bookmarks>,
+}
+
+impl BookmarkFile {
+    /// Empty when the file is missing or malformed (logged)
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("Bookmarks: cannot read {}: {e}", path.display());
+                return Self::default();
+            }
+        };
+        ron::from_str(&text).unwrap_or_else(|e| {
+            warn!("Bookmarks: ignoring malformed {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        std::fs::write(path, text)
+    }
+}
+
+/// Ctrl or Alt held: the digit keys pick bookmark slots instead of time presets
+pub fn slot_modifier_held(keyboard: &ButtonInput<KeyCode>) -> bool {
+    keyboard.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+    ])
+}
+
+/// The camera's view as a bookmark, named after the selected planet, the focused star or
+/// the loaded region
+pub fn bookmark_here(
+    lazy: &LazyUniverse,
+    selection: &PlanetSelection,
+    focus: &SystemFocus,
+    transform: &Transform,
+    cam: &FlyCamera,
+) -> Bookmark {
+    let planet = selection
+        .selected_planet
+        .as_ref()
+        .map(|(p, _)| (p.id / PLANET_ID_STRIDE, p.id));
+    let pos = transform.translation;
+    let label = match (planet, focus.star_id, lazy.current_region_id) {
+        (Some((_, planet_id)), _, _) => lazy.planet_name(planet_id),
+        (None, Some(star_id), _) => lazy.star_name(star_id),
+        (None, None, Some(region_id)) => lazy.region_name(region_id),
+        (None, None, None) => format!("({:.0}, {:.0}, {:.0})", pos.x, pos.y, pos.z),
+    };
+    Bookmark {
+        position: pos.to_array(),
+        yaw: cam.yaw,
+        pitch: cam.pitch,
+        zoom_level: cam.zoom_level as u8,
+        region_id: lazy.current_region_id,
+        planet,
+        label,
+    }
+}
+
+/// Head back to `bookmark`: a glide within the loaded region, a jump into another one
+/// (which loads it). Its planet is selected now if loaded, else once its region is
+/// (`bookmark_pending_system`). Returns where the camera is headed.
+pub fn recall_bookmark(
+    lazy: &LazyUniverse,
+    bookmark: &Bookmark,
+    recall: &mut BookmarkRecall,
+    selection: &mut PlanetSelection,
+    transform: &mut Transform,
+    cam: &mut FlyCamera,
+) -> Vec3 {
+    let position = Vec3::from_array(bookmark.position);
+    let glide = CameraGlide {
+        position,
+        yaw: bookmark.yaw,
+        pitch: bookmark.pitch,
+        rate: camera::GLIDE_RATE,
+    };
+    cam.tracking = None;
+    cam.frame_region = None;
+    if let Some(&level) = LEVELS.get(bookmark.zoom_level as usize) {
+        cam.zoom_level = level;
+        cam.level_pin = LEVEL_PIN_SECS;
+    }
+    if bookmark.region_id.is_some() && bookmark.region_id != lazy.current_region_id {
+        cam.glide = None;
+        cam.velocity = Vec3::ZERO;
+        cam.yaw = glide.yaw;
+        cam.pitch = glide.pitch;
+        transform.translation = position;
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, cam.roll);
+    } else {
+        cam.glide = Some(glide);
+    }
+
+    recall.pending = None;
+    if let Some((star_id, planet_id)) = bookmark.planet {
+        if let Some((star, planet)) = surface::find_planet(lazy, star_id, planet_id) {
+            select_planet(selection, star, planet);
+        } else if let Some(region_id) = bookmark.region_id {
+            recall.pending = Some((region_id, star_id, planet_id));
+        }
+    }
+    position
+}
+
+fn select_planet(selection: &mut PlanetSelection, star: &matrix_core::Star, planet: &matrix_core::Planet) {
+    selection.selected_planet = Some((planet.clone(), star.spectral_class));
+    selection.selected_star = None;
+    selection.selected_region = None;
+}
+
+/// [Ctrl+N] stores the view in slot N, [Alt+N] (or the console's `bm N`) returns to it
+pub fn bookmark_keys_system(
+    (keyboard, mut recall): (Res<ButtonInput<KeyCode>>, ResMut<BookmarkRecall>),
+    (universe, mut lazy): (Res<UniverseState>, ResMut<LazyUniverse>),
+    (mut selection, focus): (ResMut<PlanetSelection>, Res<SystemFocus>),
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    let store = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let pressed = SLOT_KEYS
+        .iter()
+        .position(|&key| keyboard.just_pressed(key))
+        .map(|i| i + 1)
+        .filter(|_| slot_modifier_held(&keyboard));
+    let (slot, store) = match (recall.requested.take(), pressed) {
+        (Some(slot), _) => (slot, false),
+        (None, Some(slot)) => (slot, store),
+        (None, None) => return,
+    };
+    let Ok((mut transform, mut cam)) = cam_q.get_single_mut() else {
+        return;
+    };
+
+    if store {
+        let bookmark = bookmark_here(&lazy, &selection, &focus, &transform, &cam);
+        info!("Bookmarks: slot {slot} = {}", bookmark.label);
+        lazy.bookmarks.set(slot, bookmark);
+        let path = bookmarks_path();
+        let mut file = BookmarkFile::load(&path);
+        file.seeds.insert(lazy.config.seed, lazy.bookmarks.clone());
+        if let Err(e) = file.save(&path) {
+            warn!("Bookmarks: cannot write {}: {e}", path.display());
+        }
+        return;
+    }
+
+    let Some(bookmark) = lazy.bookmarks.get(slot) else {
+        info!("Bookmarks: slot {slot} is empty");
+        return;
+    };
+    let position = recall_bookmark(&lazy, bookmark, &mut recall, &mut selection, &mut transform, &mut cam);
+    info!("Bookmarks: returning to slot {slot} ({})", bookmark.label);
+    if let Some(mut recorder) = recorder {
+        camera::record_teleport(&mut recorder, universe.age, position);
+    }
+}
+
+/// Select a recalled bookmark's planet once its region's stars are loaded; drop it if the
+/// camera has left the region meanwhile
+pub fn bookmark_pending_system(
+    lazy: Res<LazyUniverse>,
+    mut recall: ResMut<BookmarkRecall>,
+    mut selection: ResMut<PlanetSelection>,
+    cam_q: Query<&Transform, With<FlyCamera>>,
+) {
+    let Some((region_id, star_id, planet_id)) = recall.pending else {
+        return;
+    };
+    let Ok(transform) = cam_q.get_single() else {
+        return;
+    };
+    let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) else {
+        recall.pending = None;
+        return;
+    };
+    let center = Vec3::from_array(region.center.map(|c| c as f32));
+    if transform.translation.distance(center) > region.size as f32 {
+        recall.pending = None;
+    } else if let Some((star, planet)) = surface::find_planet(&lazy, star_id, planet_id) {
+        select_planet(&mut selection, star, planet);
+        recall.pending = None;
+    }
+}
+
+/// A world without bookmarks of its own (new, or saved before v23) picks up its seed's
+/// from `bookmarks.ron`. Runs once a world is generated or loaded.
+pub fn load_seed_bookmarks(mut lazy: ResMut<LazyUniverse>, mut recall: ResMut<BookmarkRecall>) {
+    *recall = BookmarkRecall::default();
+    if !lazy.bookmarks.is_empty() {
+        return;
+    }
+    let seed = lazy.config.seed;
+    if let Some(bookmarks) = BookmarkFile::load(&bookmarks_path()).seeds.remove(&seed) {
+        info!("Bookmarks: {} for seed {seed}", bookmarks.occupied().count());
+        lazy.bookmarks = bookmarks;
+    }
+}
+
+/// HUD line listing the occupied slots
+pub fn hud_line(strings: &Strings, bookmarks: &Bookmarks) -> String {
+    if bookmarks.is_empty() {
+        return strings.tr("hud.views_none", &[]);
+    }
+    let slots: Vec<String> = bookmarks
+        .occupied()
+        .map(|(slot, b)| format!("[{slot}] {}", b.label))
+        .collect();
+    strings.tr("hud.views", &[("views", &slots.join("  "))])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    #[test]
+    fn test_store_and_recall() {
+        let mut lazy = LazyUniverse::new(SimConfig::default(), 13.0);
+        let region = lazy.regions[3].clone();
+        let mut selection = PlanetSelection::default();
+        let mut recall = BookmarkRecall::default();
+        let mut transform = Transform::from_xyz(123.4, -56.7, 890.1);
+        let mut cam = FlyCamera {
+            yaw: 0.7,
+            pitch: -0.2,
+            zoom_level: ZoomLevel::Planetary,
+            ..default()
+        };
+        lazy.current_region_id = Some(region.id);
+        let mut bookmark = bookmark_here(&lazy, &selection, &SystemFocus::default(), &transform, &cam);
+        assert_eq!(bookmark.label, lazy.region_name(region.id));
+        assert_eq!(LEVELS[bookmark.zoom_level as usize], ZoomLevel::Planetary);
+        assert_eq!(bookmark.region_id, Some(region.id));
+        assert!(bookmark.planet.is_none());
+
+        // Same region: glide back, facing the same way
+        transform.translation = Vec3::ZERO;
+        cam.zoom_level = ZoomLevel::Cosmic;
+        recall_bookmark(&lazy, &bookmark, &mut recall, &mut selection, &mut transform, &mut cam);
+        let glide = cam.glide.expect("glides back");
+        assert_eq!(glide.position, Vec3::new(123.4, -56.7, 890.1));
+        assert_eq!((glide.yaw, glide.pitch), (0.7, -0.2));
+        assert_eq!(cam.zoom_level, ZoomLevel::Planetary);
+        assert_eq!(transform.translation, Vec3::ZERO);
+
+        // Another region: jump there at once, and select the planet once it is loaded
+        bookmark.planet = Some((999_999, 999_999_001));
+        lazy.current_region_id = Some(region.id + 1);
+        recall_bookmark(&lazy, &bookmark, &mut recall, &mut selection, &mut transform, &mut cam);
+        assert!(cam.glide.is_none());
+        assert_eq!(transform.translation, Vec3::new(123.4, -56.7, 890.1));
+        assert_eq!(recall.pending.map(|(r, _, _)| r), Some(region.id));
+        assert!(selection.selected_planet.is_none());
+    }
+
+    #[test]
+    fn test_bookmark_file_round_trips_and_tolerates_garbage() {
+        let dir = std::env::temp_dir().join(format!("matrix_bookmarks_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bookmarks.ron");
+        assert_eq!(BookmarkFile::load(&path), BookmarkFile::default());
+
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.set(
+            4,
+            Bookmark {
+                position: [1.0, 2.0, 3.0],
+                yaw: 0.0,
+                pitch: 0.0,
+                zoom_level: 2,
+                region_id: None,
+                planet: None,
+                label: "(1, 2, 3)".to_string(),
+            },
+        );
+        let mut file = BookmarkFile::default();
+        file.seeds.insert(42, bookmarks.clone());
+        file.save(&path).unwrap();
+        assert_eq!(BookmarkFile::load(&path).seeds.get(&42), Some(&bookmarks));
+        assert_eq!(hud_line(&Strings::default(), &bookmarks), "Views: [4] (1, 2, 3) ([Alt+N] to return)");
+
+        std::fs::write(&path, "(seeds: {42: oops").unwrap();
+        assert_eq!(BookmarkFile::load(&path), BookmarkFile::default());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
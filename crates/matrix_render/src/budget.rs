@@ -0,0 +1,276 @@
+//! Render budget: one cap on the entities the spawning systems create together, picked
+//! as a preset on the settings page. Each system asks `RenderBudget::request` for the
+//! count it would spawn and gets it scaled down by the same factor as every other
+//! category, so all of them at their largest stay within the preset. The preset also
+//! gates a few expensive extras (star point lights, life planet animation range, the
+//! particle cloud sample). Changing it bumps `RenderBudget::generation`, and the systems
+//! that compare it respawn at the new counts; creatures keep theirs until the next
+//! landing, since respawning them would restart the food chain.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::settings::UserSettings;
+
+/// How much the renderer may spawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BudgetPreset {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl BudgetPreset {
+    pub const ALL: [BudgetPreset; 3] = [BudgetPreset::Low, BudgetPreset::Medium, BudgetPreset::High];
+
+    /// Entities all categories may hold together. High is above every nominal count.
+    pub fn entity_cap(self) -> usize {
+        match self {
+            BudgetPreset::Low => 500,
+            BudgetPreset::Medium => 1_000,
+            BudgetPreset::High => 2_000,
+        }
+    }
+
+    /// Nearest stars that get a point light
+    pub fn star_lights(self) -> usize {
+        match self {
+            BudgetPreset::Low => 0,
+            BudgetPreset::Medium => 1,
+            BudgetPreset::High => 2,
+        }
+    }
+
+    /// Distance from the camera within which life planets pulse (render units)
+    pub fn life_animation_distance(self) -> f32 {
+        match self {
+            BudgetPreset::Low => 30.0,
+            BudgetPreset::Medium => 60.0,
+            BudgetPreset::High => 100.0,
+        }
+    }
+
+    /// Most particles sampled into the particle clouds
+    pub fn particle_sample(self) -> usize {
+        match self {
+            BudgetPreset::Low => 1_000,
+            BudgetPreset::Medium => 2_000,
+            BudgetPreset::High => 3_000,
+        }
+    }
+
+    /// Locale key of the preset's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            BudgetPreset::Low => "option.budget_low",
+            BudgetPreset::Medium => "option.budget_medium",
+            BudgetPreset::High => "option.budget_high",
+        }
+    }
+
+    /// One stepper click up or down, stopping at either end
+    pub fn step(self, dir: i32) -> Self {
+        let index = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        let index = index.saturating_add_signed(dir.signum() as isize).min(Self::ALL.len() - 1);
+        Self::ALL[index]
+    }
+}
+
+/// A kind of entity the budget shares out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    /// Region overview sprites (their life / civilization badges come along)
+    RegionSprites,
+    /// Sky dome stars on a surface
+    SkyStars,
+    Creatures,
+    /// Rocks and undergrowth around the camera
+    SurfaceDetail,
+    /// Rain, snow, dust and ember particles
+    Weather,
+    Microbes,
+}
+
+impl Category {
+    pub const ALL: [Category; 6] = [
+        Category::RegionSprites,
+        Category::SkyStars,
+        Category::Creatures,
+        Category::SurfaceDetail,
+        Category::Weather,
+        Category::Microbes,
+    ];
+
+    /// Released when leaving the surface
+    pub const SURFACE: [Category; 5] = [
+        Category::SkyStars,
+        Category::Creatures,
+        Category::SurfaceDetail,
+        Category::Weather,
+        Category::Microbes,
+    ];
+
+    /// The most the category's system ever asks for: its own cap
+    pub fn nominal(self) -> usize {
+        match self {
+            // One per region of the default 8×8×8 grid; bigger grids show those with
+            // the most stars
+            Category::RegionSprites => 512,
+            // 400 stars of an airless sky and a tenth of them again bright
+            Category::SkyStars => 440,
+            Category::Creatures => 80,
+            Category::SurfaceDetail => 50,
+            Category::Weather => 400,
+            // Up to 10 cells in each of the 3×3 population cells
+            Category::Microbes => 90,
+        }
+    }
+
+    /// Name in the debug overlay
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::RegionSprites => "regions",
+            Category::SkyStars => "sky stars",
+            Category::Creatures => "creatures",
+            Category::SurfaceDetail => "details",
+            Category::Weather => "weather",
+            Category::Microbes => "microbes",
+        }
+    }
+}
+
+/// What a category last asked for and got
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub desired: usize,
+    pub granted: usize,
+}
+
+/// The current preset and what each category holds of it
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RenderBudget {
+    preset: BudgetPreset,
+    usage: BTreeMap<Category, Usage>,
+    /// Bumped when the preset changes
+    generation: u32,
+}
+
+impl RenderBudget {
+    pub fn new(preset: BudgetPreset) -> Self {
+        Self {
+            preset,
+            ..default()
+        }
+    }
+
+    pub fn preset(&self) -> BudgetPreset {
+        self.preset
+    }
+
+    /// Switch presets; systems built at another generation respawn
+    pub fn set_preset(&mut self, preset: BudgetPreset) {
+        if preset != self.preset {
+            self.preset = preset;
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Factor every category's count is scaled by: the cap over the sum of the nominal
+    /// counts, or 1 when they all fit
+    pub fn scale(&self) -> f32 {
+        let nominal: usize = Category::ALL.into_iter().map(Category::nominal).sum();
+        (self.preset.entity_cap() as f32 / nominal as f32).min(1.0)
+    }
+
+    /// How many of `desired` entities the category may spawn, recorded as its usage.
+    /// Requests above the category's nominal count are cut to it first.
+    pub fn request(&mut self, category: Category, desired: usize) -> usize {
+        let granted = (desired.min(category.nominal()) as f32 * self.scale()) as usize;
+        self.usage.insert(category, Usage { desired, granted });
+        granted
+    }
+
+    /// The category's entities are gone
+    pub fn release(&mut self, category: Category) {
+        self.usage.remove(&category);
+    }
+
+    /// Per-category usage, for the debug overlay
+    pub fn usage(&self) -> impl Iterator<Item = (Category, Usage)> + '_ {
+        self.usage.iter().map(|(&category, &usage)| (category, usage))
+    }
+
+    pub fn total_granted(&self) -> usize {
+        self.usage.values().map(|u| u.granted).sum()
+    }
+}
+
+/// Follow the preset picked on the settings page
+pub fn budget_preset_system(settings: Res<UserSettings>, mut budget: ResMut<RenderBudget>) {
+    if settings.is_changed() && settings.render_budget != budget.preset {
+        budget.set_preset(settings.render_budget);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_scale_proportionally() {
+        let mut budget = RenderBudget::default();
+        assert_eq!(budget.scale(), 1.0);
+        assert_eq!(budget.request(Category::Creatures, 60), 60);
+        assert_eq!(budget.request(Category::SkyStars, 1_000), 440);
+
+        budget.set_preset(BudgetPreset::Medium);
+        assert_eq!(budget.generation(), 1);
+        let scale = budget.scale();
+        assert!(scale > 0.5 && scale < 1.0);
+        // Every category loses the same share, so their ratios hold
+        let creatures = budget.request(Category::Creatures, 80);
+        let details = budget.request(Category::SurfaceDetail, 40);
+        assert_eq!(creatures, (80.0 * scale) as usize);
+        assert_eq!(details, (40.0 * scale) as usize);
+        assert_eq!(budget.total_granted(), creatures + details + 440);
+        budget.release(Category::SkyStars);
+        assert_eq!(budget.total_granted(), creatures + details);
+
+        // The same preset again is not a change
+        budget.set_preset(BudgetPreset::Medium);
+        assert_eq!(budget.generation(), 1);
+        assert_eq!(BudgetPreset::Medium.step(1), BudgetPreset::High);
+        assert_eq!(BudgetPreset::High.step(1), BudgetPreset::High);
+        assert_eq!(BudgetPreset::Low.step(-1), BudgetPreset::Low);
+    }
+
+    #[test]
+    fn test_every_preset_holds_all_systems_at_their_largest() {
+        for preset in BudgetPreset::ALL {
+            let mut budget = RenderBudget::new(preset);
+            for category in Category::ALL {
+                let granted = budget.request(category, category.nominal() * 2);
+                assert!(granted <= category.nominal());
+            }
+            assert!(
+                budget.total_granted() <= preset.entity_cap(),
+                "{preset:?}: {} entities",
+                budget.total_granted()
+            );
+            assert_eq!(budget.usage().count(), Category::ALL.len());
+        }
+        // High leaves every system at its own cap
+        let mut high = RenderBudget::new(BudgetPreset::High);
+        for category in Category::ALL {
+            assert_eq!(high.request(category, category.nominal()), category.nominal());
+        }
+        assert!(BudgetPreset::Low.particle_sample() < BudgetPreset::High.particle_sample());
+    }
+}
@@ -1,15 +1,31 @@
 use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll};
 use bevy::prelude::*;
-use bevy::render::camera::ScalingMode;
-use bevy::render::view::RenderLayers;
-use matrix_core::SerializedParticle;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::{NoFrustumCulling, RenderLayers};
+use matrix_core::{C, Region};
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
 use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::replay::{ReplayEvent, ReplayPlayer, ReplayRecorder};
 use matrix_sim::universe::UniverseState;
-use matrix_storage::UniverseSnapshot;
+use matrix_storage::Compression;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
-/// Scale levels for the multi-level zoom system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use super::hud_layout::HudLayout;
+use super::input_capture::UiInputCapture;
+use super::photo::{PHOTO_ROLL_SPEED, PhotoMode};
+use super::saving::{self, PendingSaves, SnapshotCapture, saves_dir, unix_now};
+use super::settings::{Action, Controls, UserSettings, action_just_pressed};
+use super::skybox;
+use super::speed;
+use super::surface::{PlanetSelection, SurfaceState};
+use super::surface_map::{SurfaceMapView, place_map_view};
+
+/// Scale levels for the multi-level zoom system, from the outermost in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ZoomLevel {
     /// >500 units: region outlines, density stats only
     Cosmic,
@@ -40,13 +56,35 @@ impl ZoomLevel {
     }
 }
 
+/// Where the camera starts (and returns to on quitting to the menu): above the
+/// origin at Cosmic distance — Big Bang particles are at (0,0,0)
+pub const START_POSITION: Vec3 = Vec3::new(0.0, 400.0, 500.0);
+
+/// Ambient light in space, so planets without emissive are still visible
+pub fn space_ambient() -> AmbientLight {
+    AmbientLight {
+        color: Color::srgb(0.3, 0.3, 0.5),
+        brightness: 50.0,
+    }
+}
+
 /// Marker for our free-fly camera
 #[derive(Component)]
 pub struct FlyCamera {
+    /// Fly speed in manual mode (units/s)
     pub speed: f32,
-    pub sensitivity: f32,
+    /// Speed follows the distance to the nearest object (`auto_fly_speed`) instead of `speed`
+    pub auto_speed: bool,
+    /// Scroll factor on the auto speed
+    pub speed_multiplier: f32,
+    /// Current velocity, eased towards the input (units/s), at most light speed
+    pub velocity: Vec3,
+    /// The input asks for more than light speed: movement is held at c (`speed` module)
+    pub warp: bool,
     pub yaw: f32,
     pub pitch: f32,
+    /// Roll around the view axis (photo mode only, zeroed on exit)
+    pub roll: f32,
     /// Index of particle being tracked (None = free fly)
     pub tracking: Option<usize>,
     /// Current particle kind filter for Tab cycling
@@ -59,25 +97,142 @@ pub struct FlyCamera {
     pub zoom_frame: u32,
     /// Current index for region cycling (G/H keys)
     pub region_nav_idx: usize,
+    /// Smooth move in progress (system focus enter/exit)
+    pub glide: Option<CameraGlide>,
+    /// Region entered whose stars the camera glides to frame once they are loaded
+    pub frame_region: Option<u64>,
+    /// Zoom level follows `nearest_dist` (`auto_zoom_level`) instead of only [B] / [Esc]
+    pub auto_level: bool,
+    /// Seconds an explicit level change keeps the auto level from overriding it
+    pub level_pin: f32,
+}
+
+/// Where a gliding camera ends up and which way it faces there
+#[derive(Debug, Clone, Copy)]
+pub struct CameraGlide {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Ease-out rate (1/s): the remaining distance shrinks by e^-rate per second
+    pub rate: f32,
+}
+
+impl CameraGlide {
+    /// Glide to `position`, facing `target`
+    pub fn looking_at(position: Vec3, target: Vec3) -> Self {
+        let dir = (target - position).normalize_or(Vec3::NEG_Z);
+        Self {
+            position,
+            // Camera looks down -Z at yaw 0 (see `fly_camera_system`)
+            yaw: (-dir.x).atan2(-dir.z),
+            pitch: dir.y.clamp(-1.0, 1.0).asin().clamp(-1.5, 1.5),
+            rate: GLIDE_RATE,
+        }
+    }
+
+    /// The same glide at another ease-out rate
+    pub fn with_rate(self, rate: f32) -> Self {
+        Self { rate, ..self }
+    }
+}
+
+/// Default glide ease-out rate (1/s)
+pub const GLIDE_RATE: f32 = 4.0;
+/// How long an explicit [B] / [Esc] level change holds against the auto level (s)
+pub const LEVEL_PIN_SECS: f32 = 3.0;
+/// The auto level goes one level deeper below this fraction of the level's boundary
+/// distance, and back out above `LEVEL_EXIT_BAND` of it, so it doesn't flicker
+const LEVEL_ENTER_BAND: f32 = 0.9;
+const LEVEL_EXIT_BAND: f32 = 1.2;
+/// Auto fly speed per unit of distance to the nearest object (units/s)
+const AUTO_SPEED_PER_DIST: f32 = 0.5;
+const AUTO_SPEED_MIN: f32 = 0.2;
+const AUTO_SPEED_MAX: f32 = 10000.0;
+/// Velocity easing rate (1/s): the gap to the input velocity shrinks by e^-rate per second
+const FLY_EASE_RATE: f32 = 6.0;
+/// Below this fraction of the fly speed, a coasting camera stops
+const FLY_STOP_FRACTION: f32 = 0.01;
+
+/// Fly speed for a camera `nearest_dist` away from the nearest object: slow near planets,
+/// fast between regions
+pub fn auto_fly_speed(nearest_dist: f32) -> f32 {
+    (nearest_dist * AUTO_SPEED_PER_DIST).clamp(AUTO_SPEED_MIN, AUTO_SPEED_MAX)
+}
+
+impl FlyCamera {
+    /// Speed the camera flies at without boost (units/s)
+    pub fn effective_speed(&self) -> f32 {
+        if self.auto_speed {
+            auto_fly_speed(self.nearest_dist) * self.speed_multiplier
+        } else {
+            self.speed
+        }
+    }
+}
+
+/// Ease `velocity` towards `target` over `dt`, stopping once it coasts below
+/// `FLY_STOP_FRACTION` of `speed`
+fn ease_velocity(velocity: Vec3, target: Vec3, speed: f32, dt: f32) -> Vec3 {
+    let eased = velocity.lerp(target, 1.0 - (-FLY_EASE_RATE * dt).exp());
+    if target == Vec3::ZERO && eased.length() < speed * FLY_STOP_FRACTION {
+        Vec3::ZERO
+    } else {
+        eased
+    }
 }
 
 impl Default for FlyCamera {
     fn default() -> Self {
         Self {
             speed: 50.0,
-            sensitivity: 0.003,
+            auto_speed: true,
+            speed_multiplier: 1.0,
+            velocity: Vec3::ZERO,
             yaw: 0.0,
             pitch: 0.0,
+            roll: 0.0,
             tracking: None,
             kind_filter_idx: 0,
             zoom_level: ZoomLevel::Cosmic,
             nearest_dist: 999.0,
             zoom_frame: 0,
             region_nav_idx: 0,
+            glide: None,
+            frame_region: None,
+            auto_level: false,
+            level_pin: 0.0,
+            warp: false,
         }
     }
 }
 
+/// Where the camera enters a region, relative to its center, until its stars are framed
+pub const REGION_ENTRY_OFFSET: Vec3 = Vec3::new(0.0, 20.0, 50.0);
+
+/// Jump to `region`'s entry point; `region_framing_system` then glides to its densest
+/// clump of stars once they are loaded. Returns the entry point.
+pub fn enter_region_view(transform: &mut Transform, cam: &mut FlyCamera, region: &Region) -> Vec3 {
+    let center = Vec3::from_array(region.center.map(|c| c as f32));
+    transform.translation = center + REGION_ENTRY_OFFSET;
+    cam.tracking = None;
+    cam.glide = None;
+    cam.frame_region = Some(region.id);
+    transform.translation
+}
+
+/// Put the camera at `position` facing `target` at once, dropping any move in progress
+pub fn place_camera(transform: &mut Transform, cam: &mut FlyCamera, position: Vec3, target: Vec3) {
+    let heading = CameraGlide::looking_at(position, target);
+    cam.yaw = heading.yaw;
+    cam.pitch = heading.pitch;
+    cam.roll = 0.0;
+    cam.velocity = Vec3::ZERO;
+    cam.tracking = None;
+    cam.glide = None;
+    *transform = Transform::from_translation(position)
+        .with_rotation(Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, cam.roll));
+}
+
 /// Marker for the minimap camera
 #[derive(Component)]
 pub struct MinimapCamera;
@@ -86,6 +241,30 @@ pub struct MinimapCamera;
 #[derive(Component)]
 pub struct MinimapIndicator;
 
+/// Top-down density map of the region slab the camera is in, drawn as one
+/// vertex-colored mesh on the minimap layer
+#[derive(Resource)]
+pub struct MinimapHeatmap {
+    mesh: Handle<Mesh>,
+    /// Center Y of the slab last drawn
+    slab_y: Option<f64>,
+    /// Whether the camera was in the slab's upper half (which children of subdivided
+    /// regions were drawn)
+    upper_half: bool,
+    /// `LazyUniverse::stats_generation` the map was built from
+    stats_generation: u32,
+    /// `LazyUniverse::region_flags_generation` the map was built from
+    region_flags_generation: u32,
+    advanced_civ_regions: usize,
+}
+
+/// Minimap camera height above the heat map
+const MINIMAP_HEIGHT: f32 = 2000.0;
+/// Region cell size on the heat map, relative to the region size (leaves a grid gap)
+const HEATMAP_CELL_FILL: f32 = 0.92;
+/// Where the main camera stops relative to a region picked on the minimap
+const MINIMAP_GOTO_OFFSET: Vec3 = Vec3::new(0.0, 20.0, 50.0);
+
 /// Particle kinds for Tab cycling
 const PARTICLE_KINDS: &[(u32, &str)] = &[
     (0, "Up Quark"),
@@ -105,8 +284,7 @@ pub fn spawn_camera(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    // Start above origin at Cosmic distance — Big Bang particles are at (0,0,0)
-    let pos = Vec3::new(0.0, 400.0, 500.0);
+    let pos = START_POSITION;
     let look_at = Vec3::ZERO;
 
     info!("Camera spawned at ({:.0}, {:.0}, {:.0})", pos.x, pos.y, pos.z);
@@ -114,19 +292,18 @@ pub fn spawn_camera(
     commands.spawn((
         Camera3d::default(),
         IsDefaultUiCamera,
+        // Far enough to see the sky sphere around it
+        Projection::from(PerspectiveProjection {
+            far: skybox::SKY_RADIUS * 1.2,
+            ..default()
+        }),
         Transform::from_translation(pos).looking_at(look_at, Vec3::Y),
         FlyCamera::default(),
     ));
 
-    // Ambient light so planets without emissive are still visible
-    commands.insert_resource(AmbientLight {
-        color: Color::srgb(0.3, 0.3, 0.5),
-        brightness: 50.0,
-    });
+    commands.insert_resource(space_ambient());
 
-    // Minimap camera — STATIC orthographic top-down overview (proper scale, no perspective distortion)
-    let region_center = Vec3::ZERO;
-    let minimap_height = 2000.0;
+    // Minimap camera — STATIC orthographic top-down view of the heat map (layer 1 only)
     commands.spawn((
         Camera3d::default(),
         Projection::from(OrthographicProjection {
@@ -137,7 +314,8 @@ pub fn spawn_camera(
         }),
         Camera {
             order: 1,
-            viewport: Some(bevy::render::camera::Viewport {
+            // Placed and sized every frame by `minimap_system`
+            viewport: Some(Viewport {
                 physical_position: UVec2::new(0, 0),
                 physical_size: UVec2::new(280, 280),
                 ..default()
@@ -145,11 +323,33 @@ pub fn spawn_camera(
             clear_color: ClearColorConfig::Custom(Color::srgba(0.0, 0.0, 0.05, 0.8)),
             ..default()
         },
-        Transform::from_translation(region_center + Vec3::new(0.0, minimap_height, 0.0))
-            .looking_at(region_center, Vec3::Z),
+        Transform::from_translation(Vec3::new(0.0, MINIMAP_HEIGHT, 0.0)).looking_at(Vec3::ZERO, Vec3::Z),
         MinimapCamera,
-        RenderLayers::from_layers(&[0, 1]),
+        RenderLayers::layer(1),
+    ));
+
+    // Region density heat map — filled in by `minimap_system` once regions exist
+    let heatmap_mesh = meshes.add(heatmap_mesh(&[]));
+    commands.spawn((
+        Mesh3d(heatmap_mesh.clone()),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        })),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        RenderLayers::layer(1),
     ));
+    commands.insert_resource(MinimapHeatmap {
+        mesh: heatmap_mesh,
+        slab_y: None,
+        upper_half: false,
+        stats_generation: 0,
+        region_flags_generation: 0,
+        advanced_civ_regions: 0,
+    });
 
     // Camera position indicator (flat bright rectangle — ONLY visible on minimap via layer 1)
     let indicator_mesh = meshes.add(Cuboid::new(1.0, 0.1, 1.0));
@@ -168,13 +368,17 @@ pub fn spawn_camera(
     ));
 }
 
-/// Handle camera movement with WASD + mouse
+/// Handle camera movement with WASD + mouse, or the gamepad sticks and triggers
 pub fn fly_camera_system(
     time: Res<Time>,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mouse_motion: Res<AccumulatedMouseMotion>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    mouse_scroll: Res<AccumulatedMouseScroll>,
+    controls: Controls,
+    (mouse_motion, mouse_button, mouse_scroll, capture): (
+        Res<AccumulatedMouseMotion>,
+        Res<ButtonInput<MouseButton>>,
+        Res<AccumulatedMouseScroll>,
+        Res<UiInputCapture>,
+    ),
+    photo: Res<PhotoMode>,
     mut query: Query<(&mut Transform, &mut FlyCamera)>,
 ) {
     let Ok((mut transform, mut cam)) = query.get_single_mut() else {
@@ -183,92 +387,172 @@ pub fn fly_camera_system(
 
     let dt = time.delta_secs();
 
-    // Mouse look (only when right-click held)
-    if mouse_button.pressed(MouseButton::Right) {
-        let delta = mouse_motion.delta;
-        cam.yaw -= delta.x * cam.sensitivity;
-        cam.pitch -= delta.y * cam.sensitivity;
-        cam.pitch = cam.pitch.clamp(-1.5, 1.5);
+    // Mouse look (only when right-click held, and the UI doesn't have the mouse)
+    if mouse_button.pressed(MouseButton::Right) && !capture.blocks_mouse() {
+        let look = controls.settings.look(mouse_motion.delta);
+        cam.yaw += look.x;
+        cam.pitch = (cam.pitch + look.y).clamp(-1.5, 1.5);
+    }
+    let stick = controls.stick_look(dt);
+    cam.yaw += stick.x;
+    cam.pitch = (cam.pitch + stick.y).clamp(-1.5, 1.5);
+
+    // Z / C roll in photo mode
+    if photo.active {
+        if controls.pressed(Action::RollLeft) {
+            cam.roll += PHOTO_ROLL_SPEED * dt;
+        }
+        if controls.pressed(Action::RollRight) {
+            cam.roll -= PHOTO_ROLL_SPEED * dt;
+        }
     }
 
-    // Apply rotation
-    transform.rotation = Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, 0.0);
+    // Apply rotation: yaw, then pitch, then roll around the view axis
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, cam.roll);
 
-    // Scroll to adjust speed
+    // [M] auto / manual speed
+    if controls.just_pressed(Action::AutoSpeed) {
+        cam.auto_speed = !cam.auto_speed;
+        info!(
+            "Camera: {} fly speed",
+            if cam.auto_speed { "auto" } else { "manual" }
+        );
+    }
+
+    if controls.just_pressed(Action::AutoLevel) {
+        cam.auto_level = !cam.auto_level;
+        info!(
+            "Camera: {} zoom level",
+            if cam.auto_level { "auto" } else { "manual" }
+        );
+    }
+
+    // Scroll scales the auto speed, or sets the manual one
     let scroll = mouse_scroll.delta.y;
-    if scroll != 0.0 {
-        cam.speed = (cam.speed * (1.0 + scroll * 0.1)).clamp(1.0, 10000.0);
+    if scroll != 0.0 && !capture.blocks_mouse() {
+        let factor = 1.0 + scroll * 0.1;
+        if cam.auto_speed {
+            cam.speed_multiplier = (cam.speed_multiplier * factor).clamp(0.01, 100.0);
+        } else {
+            cam.speed = (cam.speed * factor).clamp(1.0, 10000.0);
+        }
     }
 
-    // WASD movement (cancels tracking)
+    // WASD / left stick movement (cancels tracking)
     let forward = *transform.forward();
     let right = *transform.right();
     let up = Vec3::Y;
 
     let mut velocity = Vec3::ZERO;
 
-    if keyboard.pressed(KeyCode::KeyW) {
+    if controls.pressed(Action::MoveForward) {
         velocity += forward;
     }
-    if keyboard.pressed(KeyCode::KeyS) {
+    if controls.pressed(Action::MoveBack) {
         velocity -= forward;
     }
-    if keyboard.pressed(KeyCode::KeyA) {
+    if controls.pressed(Action::MoveLeft) {
         velocity -= right;
     }
-    if keyboard.pressed(KeyCode::KeyD) {
+    if controls.pressed(Action::MoveRight) {
         velocity += right;
     }
-    if keyboard.pressed(KeyCode::KeyE) {
+    if controls.pressed(Action::MoveUp) {
         velocity += up;
     }
-    if keyboard.pressed(KeyCode::KeyQ) {
+    if controls.pressed(Action::MoveDown) {
         velocity -= up;
     }
 
-    // Boost with shift
-    let boost = if keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight) {
+    // Boost (shift by default)
+    let boost = if controls.pressed(Action::Boost) {
         5.0
     } else {
         1.0
     };
 
+    // Keys move at full speed, the stick in proportion to its tilt
+    let movement = controls.gamepad.movement;
+    velocity = (velocity.normalize_or_zero() + forward * movement.y + right * movement.x)
+        .clamp_length_max(1.0);
+
     if velocity.length_squared() > 0.0 {
-        velocity = velocity.normalize();
-        transform.translation += velocity * cam.speed * boost * dt;
-        // Cancel tracking if manually moving
+        // Cancel tracking / glides / framing if manually moving
         cam.tracking = None;
+        cam.glide = None;
+        cam.frame_region = None;
+    } else if cam.tracking.is_some() || cam.glide.is_some() {
+        // They move the camera themselves: don't coast on top of them
+        cam.velocity = Vec3::ZERO;
+        cam.warp = false;
+        return;
+    }
+
+    // Light speed is the limit: faster input eases into it and shows as warp
+    let speed = cam.effective_speed() * boost;
+    let target = velocity * speed;
+    cam.warp = speed::light_fraction(target) > 1.0;
+    cam.velocity = ease_velocity(cam.velocity, speed::cap_to_light(target), speed.min(C), dt);
+    transform.translation += cam.velocity * dt;
+}
+
+/// Ease the camera towards `FlyCamera::glide`, turning to face the glide's heading
+pub fn camera_glide_system(time: Res<Time>, mut query: Query<(&mut Transform, &mut FlyCamera)>) {
+    let Ok((mut transform, mut cam)) = query.get_single_mut() else {
+        return;
+    };
+    let Some(glide) = cam.glide else {
+        return;
+    };
+
+    let t = 1.0 - (-glide.rate * time.delta_secs()).exp();
+    transform.translation = transform.translation.lerp(glide.position, t);
+    // Turn the short way round
+    let yaw_delta = (glide.yaw - cam.yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+        - std::f32::consts::PI;
+    cam.yaw += yaw_delta * t;
+    cam.pitch += (glide.pitch - cam.pitch) * t;
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, cam.roll);
+
+    if transform.translation.distance_squared(glide.position) < 1e-4
+        && yaw_delta.abs() < 1e-3
+        && (glide.pitch - cam.pitch).abs() < 1e-3
+    {
+        transform.translation = glide.position;
+        cam.yaw = glide.yaw;
+        cam.pitch = glide.pitch;
+        cam.glide = None;
     }
 }
 
 /// Handle navigation hotkeys (teleport, track, search)
 pub fn navigation_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
     universe: Res<UniverseState>,
     lazy: Res<LazyUniverse>,
     mut query: Query<(&mut Transform, &mut FlyCamera)>,
+    recorder: Option<ResMut<ReplayRecorder>>,
 ) {
     let Ok((mut transform, mut cam)) = query.get_single_mut() else {
         return;
     };
+    let start_pos = transform.translation;
 
     // [O] Origin — teleport to center
-    if keyboard.just_pressed(KeyCode::KeyO) {
+    if controls.just_pressed(Action::Origin) {
         transform.translation = Vec3::new(0.0, 5.0, 50.0);
         cam.tracking = None;
         info!("Camera: teleported to origin");
     }
 
     // [F] Find densest cluster — teleport there
-    if keyboard.just_pressed(KeyCode::KeyF) {
+    if controls.just_pressed(Action::Densest) {
         // Try region-based dense cluster first, fallback to particle-based
-        if let Some(center) = lazy.find_densest_region() {
-            transform.translation =
-                Vec3::new(center[0] as f32, center[1] as f32 + 20.0, center[2] as f32 + 50.0);
-            cam.tracking = None;
+        if let Some(region) = lazy.find_densest_region() {
+            enter_region_view(&mut transform, &mut cam, region);
             info!(
                 "Camera: teleported to densest region at ({:.0}, {:.0}, {:.0})",
-                center[0], center[1], center[2]
+                region.center[0], region.center[1], region.center[2]
             );
         } else {
             let center = universe.find_densest_cluster();
@@ -278,7 +562,7 @@ pub fn navigation_system(
     }
 
     // [N] Nearest particle — jump to closest
-    if keyboard.just_pressed(KeyCode::KeyN) {
+    if controls.just_pressed(Action::NearestParticle) {
         let cam_pos = [
             transform.translation.x,
             transform.translation.y,
@@ -294,7 +578,7 @@ pub fn navigation_system(
     }
 
     // [T] Track — follow a random particle
-    if keyboard.just_pressed(KeyCode::KeyT) {
+    if controls.just_pressed(Action::TrackParticle) {
         if cam.tracking.is_some() {
             cam.tracking = None;
             info!("Camera: stopped tracking");
@@ -306,7 +590,7 @@ pub fn navigation_system(
     }
 
     // [Tab] Cycle through particle types and jump to one
-    if keyboard.just_pressed(KeyCode::Tab) {
+    if controls.just_pressed(Action::CycleParticleKind) {
         cam.kind_filter_idx = (cam.kind_filter_idx + 1) % PARTICLE_KINDS.len();
         let (kind, name) = PARTICLE_KINDS[cam.kind_filter_idx];
         if let Some((idx, pos)) = universe.find_particle_by_kind(Some(kind)) {
@@ -319,78 +603,41 @@ pub fn navigation_system(
     }
 
     // [G] Next region — cycle forward through regions
-    if keyboard.just_pressed(KeyCode::KeyG) {
-        if !lazy.regions.is_empty() {
-            cam.region_nav_idx = (cam.region_nav_idx + 1) % lazy.regions.len();
-            let r = &lazy.regions[cam.region_nav_idx];
-            transform.translation = Vec3::new(
-                r.center[0] as f32,
-                r.center[1] as f32 + 20.0,
-                r.center[2] as f32 + 50.0,
-            );
-            cam.tracking = None;
-            info!(
-                "Camera: region #{} ({}/{}) density={:.2} stars={}",
-                r.id,
-                cam.region_nav_idx + 1,
-                lazy.regions.len(),
-                r.density,
-                r.star_count
-            );
-        }
+    if controls.just_pressed(Action::NextRegion) && !lazy.regions.is_empty() {
+        cam.region_nav_idx = (cam.region_nav_idx + 1) % lazy.regions.len();
+        let r = &lazy.regions[cam.region_nav_idx];
+        enter_region_view(&mut transform, &mut cam, r);
+        info!(
+            "Camera: region #{} ({}/{}) density={:.2} stars={}",
+            r.id,
+            cam.region_nav_idx + 1,
+            lazy.regions.len(),
+            r.density,
+            r.star_count
+        );
     }
 
     // [H] Previous region — cycle backward
-    if keyboard.just_pressed(KeyCode::KeyH) {
-        if !lazy.regions.is_empty() {
-            if cam.region_nav_idx == 0 {
-                cam.region_nav_idx = lazy.regions.len() - 1;
-            } else {
-                cam.region_nav_idx -= 1;
-            }
-            let r = &lazy.regions[cam.region_nav_idx];
-            transform.translation = Vec3::new(
-                r.center[0] as f32,
-                r.center[1] as f32 + 20.0,
-                r.center[2] as f32 + 50.0,
-            );
-            cam.tracking = None;
-            info!(
-                "Camera: region #{} ({}/{}) density={:.2} stars={}",
-                r.id,
-                cam.region_nav_idx + 1,
-                lazy.regions.len(),
-                r.density,
-                r.star_count
-            );
-        }
-    }
-
-    // [P] Go to coordinates — reads from goto.txt (format: "x y z")
-    if keyboard.just_pressed(KeyCode::KeyP) {
-        if let Ok(content) = std::fs::read_to_string("goto.txt") {
-            let parts: Vec<f32> = content
-                .trim()
-                .split_whitespace()
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            if parts.len() >= 3 {
-                transform.translation = Vec3::new(parts[0], parts[1], parts[2]);
-                cam.tracking = None;
-                info!(
-                    "Camera: teleported to ({:.1}, {:.1}, {:.1}) from goto.txt",
-                    parts[0], parts[1], parts[2]
-                );
-            } else {
-                info!("Camera: goto.txt should contain 'x y z' (e.g. '150 -40 200')");
-            }
+    if controls.just_pressed(Action::PrevRegion) && !lazy.regions.is_empty() {
+        if cam.region_nav_idx == 0 {
+            cam.region_nav_idx = lazy.regions.len() - 1;
         } else {
-            info!("Camera: no goto.txt found. Create file with 'x y z' coordinates");
+            cam.region_nav_idx -= 1;
         }
+        let r = &lazy.regions[cam.region_nav_idx];
+        enter_region_view(&mut transform, &mut cam, r);
+        info!(
+            "Camera: region #{} ({}/{}) density={:.2} stars={}",
+            r.id,
+            cam.region_nav_idx + 1,
+            lazy.regions.len(),
+            r.density,
+            r.star_count
+        );
     }
 
     // [-] Zoom out — stay within current level (no level transition)
-    if keyboard.just_pressed(KeyCode::Minus) {
+    if controls.just_pressed(Action::ZoomOut) {
         let old_pos = transform.translation;
         let dir = transform.forward().as_vec3();
         let jump = match cam.zoom_level {
@@ -409,7 +656,7 @@ pub fn navigation_system(
     }
 
     // [=] Zoom in — stay within current level (no level transition)
-    if keyboard.just_pressed(KeyCode::Equal) {
+    if controls.just_pressed(Action::ZoomIn) {
         let old_pos = transform.translation;
         let dir = transform.forward().as_vec3();
         let jump = match cam.zoom_level {
@@ -429,7 +676,7 @@ pub fn navigation_system(
     }
 
     // [L] Find life — teleport to a planet with life
-    if keyboard.just_pressed(KeyCode::KeyL) {
+    if controls.just_pressed(Action::FindLife) {
         if let Some(pos) = lazy.find_life() {
             transform.translation =
                 Vec3::new(pos[0] as f32, pos[1] as f32 + 2.0, pos[2] as f32 + 10.0);
@@ -440,6 +687,74 @@ pub fn navigation_system(
         }
     }
 
+    if transform.translation != start_pos && let Some(mut recorder) = recorder {
+        record_teleport(&mut recorder, universe.age, transform.translation);
+    }
+}
+
+/// Record a camera jump into the active replay log
+pub fn record_teleport(recorder: &mut ReplayRecorder, age: f64, pos: Vec3) {
+    recorder.record(
+        age,
+        ReplayEvent::Teleport {
+            position: [pos.x as f64, pos.y as f64, pos.z as f64],
+        },
+    );
+}
+
+/// Move the camera to teleports coming from replay playback
+pub fn replay_camera_system(
+    player: Option<ResMut<ReplayPlayer>>,
+    mut query: Query<(&mut Transform, &mut FlyCamera)>,
+) {
+    let Some(mut player) = player else {
+        return;
+    };
+    let Some(pos) = player.pending_camera.take() else {
+        return;
+    };
+    let Ok((mut transform, mut cam)) = query.get_single_mut() else {
+        return;
+    };
+    transform.translation = Vec3::new(pos[0] as f32, pos[1] as f32, pos[2] as f32);
+    cam.tracking = None;
+}
+
+/// Glide to frame the stars of `FlyCamera::frame_region` once they are loaded
+/// (`LazyUniverse::suggest_viewpoint`), looking the way the camera already does
+pub fn region_framing_system(
+    universe: Res<UniverseState>,
+    lazy: Res<LazyUniverse>,
+    mut query: Query<(&Transform, &mut FlyCamera)>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    let Ok((transform, mut cam)) = query.get_single_mut() else {
+        return;
+    };
+    let Some(region_id) = cam.frame_region else {
+        return;
+    };
+    // Teleported somewhere else meanwhile
+    let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) else {
+        cam.frame_region = None;
+        return;
+    };
+    let center = Vec3::from_array(region.center.map(|c| c as f32));
+    if transform.translation.distance(center) > region.size as f32 {
+        cam.frame_region = None;
+        return;
+    }
+
+    let forward = transform.forward().as_vec3().as_dvec3().to_array();
+    let Some((position, look_at)) = lazy.suggest_viewpoint(region_id, forward) else {
+        return;
+    };
+    let position = Vec3::from_array(position.map(|c| c as f32));
+    cam.glide = Some(CameraGlide::looking_at(position, Vec3::from_array(look_at.map(|c| c as f32))));
+    cam.frame_region = None;
+    if let Some(mut recorder) = recorder {
+        record_teleport(&mut recorder, universe.age, position);
+    }
 }
 
 /// If tracking a particle, follow it smoothly
@@ -457,6 +772,7 @@ pub fn tracking_system(
             let target = Vec3::new(p.position[0], p.position[1] + 1.0, p.position[2] + 5.0);
             // Smooth follow
             transform.translation = transform.translation.lerp(target, 0.1);
+            cam.velocity = Vec3::ZERO;
         } else {
             cam.tracking = None;
         }
@@ -495,10 +811,26 @@ fn dist_to_level(dist: f32) -> ZoomLevel {
     }
 }
 
-/// Update nearest_dist for HUD display. Does NOT change zoom_level.
-/// Zoom level changes ONLY via explicit B (enter) / Esc (exit) actions.
+/// Zoom level for a camera `dist` from the nearest object, currently at `current`:
+/// `dist_to_level` with a hysteresis band around each boundary
+pub fn auto_level(current: ZoomLevel, dist: f32) -> ZoomLevel {
+    let deeper = dist_to_level(dist / LEVEL_ENTER_BAND);
+    let shallower = dist_to_level(dist / LEVEL_EXIT_BAND);
+    if deeper > current {
+        deeper
+    } else if shallower < current {
+        shallower
+    } else {
+        current
+    }
+}
+
+/// Update nearest_dist for HUD display. In auto level mode, also move zoom_level with it
+/// (`auto_level`) unless an explicit [B] / [Esc] pinned the level; otherwise zoom level
+/// changes ONLY via those.
 /// Throttled: only recomputes every 15 frames.
 pub fn zoom_update_system(
+    time: Res<Time>,
     lazy: Res<LazyUniverse>,
     mut query: Query<(&Transform, &mut FlyCamera)>,
 ) {
@@ -506,6 +838,7 @@ pub fn zoom_update_system(
         return;
     };
 
+    cam.level_pin = (cam.level_pin - time.delta_secs()).max(0.0);
     cam.zoom_frame = cam.zoom_frame.wrapping_add(1);
     if cam.zoom_frame % 15 != 0 {
         return;
@@ -527,33 +860,60 @@ pub fn zoom_update_system(
     }
 
     cam.nearest_dist = min_dist;
-    // zoom_level is NOT auto-changed — only set by B/Esc level transitions
+    // Glides are explicit moves to a level too
+    if !cam.auto_level || cam.level_pin > 0.0 || cam.glide.is_some() {
+        return;
+    }
+    let level = auto_level(cam.zoom_level, min_dist);
+    if level != cam.zoom_level {
+        info!("Camera: auto level {} -> {}", cam.zoom_level.name(), level.name());
+        cam.zoom_level = level;
+    }
 }
 
-/// Update minimap: STATIC camera above region center, indicator rectangle follows player
+/// The main camera, kept apart from the minimap camera and indicator
+type MainCamera = (Without<MinimapCamera>, Without<MinimapIndicator>);
+/// The minimap camera, kept apart from the main camera and the indicator
+type MinimapCameras = (With<MinimapCamera>, Without<MinimapIndicator>, Without<FlyCamera>);
+/// The indicator rectangle, kept apart from both cameras
+type MinimapIndicators = (With<MinimapIndicator>, Without<MinimapCamera>, Without<FlyCamera>);
+/// The surface map, moved aside when the minimap grows
+type SurfaceMapNode<'w, 's> = Query<'w, 's, &'static mut Node, With<SurfaceMapView>>;
+
+/// Update minimap: heat map of the camera's region slab, indicator rectangle follows
+/// the player, and a click on a cell glides there and selects that region
 pub fn minimap_system(
-    main_cam_q: Query<(&Transform, &FlyCamera), (Without<MinimapCamera>, Without<MinimapIndicator>)>,
-    mut mini_cam_q: Query<
-        (&mut Transform, &mut Camera),
-        (With<MinimapCamera>, Without<MinimapIndicator>, Without<FlyCamera>),
-    >,
-    mut indicator_q: Query<
-        &mut Transform,
-        (With<MinimapIndicator>, Without<MinimapCamera>, Without<FlyCamera>),
-    >,
-    window_q: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    surface: Res<super::surface::SurfaceState>,
-    lazy: Res<LazyUniverse>,
+    mut main_cam_q: Query<(&Transform, &mut FlyCamera), MainCamera>,
+    mut mini_cam_q: Query<(&mut Transform, &mut Camera, &GlobalTransform), MinimapCameras>,
+    mut indicator_q: Query<&mut Transform, MinimapIndicators>,
+    (window_q, mut surface_map_q): (
+        Query<&Window, With<bevy::window::PrimaryWindow>>,
+        SurfaceMapNode,
+    ),
+    (mouse, surface, lazy, photo): (
+        Res<ButtonInput<MouseButton>>,
+        Res<SurfaceState>,
+        Res<LazyUniverse>,
+        Res<PhotoMode>,
+    ),
+    (mut heatmap, mut meshes): (ResMut<MinimapHeatmap>, ResMut<Assets<Mesh>>),
+    (mut selection, settings, layout): (ResMut<PlanetSelection>, Res<UserSettings>, Res<HudLayout>),
 ) {
-    let Ok((main_tf, main_cam)) = main_cam_q.get_single() else {
+    let Ok((main_tf, mut main_cam)) = main_cam_q.get_single_mut() else {
         return;
     };
-    let Ok((mut mini_tf, mut mini_camera)) = mini_cam_q.get_single_mut() else {
+    let Ok((mut mini_tf, mut mini_camera, mini_gtf)) = mini_cam_q.get_single_mut() else {
         return;
     };
 
-    // Hide minimap + indicator on surface
-    if surface.active {
+    // On a surface the corner shows the surface map instead (`surface_map_system`)
+    let hidden = photo.active || layout.compact || !settings.minimap_enabled;
+    if let Ok(mut map_node) = surface_map_q.get_single_mut() {
+        place_map_view(&mut map_node, &layout, surface.active && !hidden);
+    }
+
+    // Hide minimap + indicator on surface, in photo mode, with the compact HUD or when turned off
+    if surface.active || hidden {
         mini_camera.is_active = false;
         if let Ok(mut ind_tf) = indicator_q.get_single_mut() {
             ind_tf.scale = Vec3::ZERO;
@@ -562,27 +922,53 @@ pub fn minimap_system(
     }
     mini_camera.is_active = true;
 
-    // STATIC: reposition minimap camera above current region center (only moves on region change)
-    let minimap_height = 2000.0;
-    if let Some(rid) = lazy.current_region_id {
-        if let Some(region) = lazy.regions.iter().find(|r| r.id == rid) {
-            let rc = Vec3::new(
-                region.center[0] as f32,
-                region.center[1] as f32,
-                region.center[2] as f32,
-            );
-            mini_tf.translation = rc + Vec3::new(0.0, minimap_height, 0.0);
-            mini_tf.look_at(rc, Vec3::Z);
+    // Rebuild the heat map when the camera changes slab or region stats / life / civilizations change
+    let cam_pos = main_tf.translation;
+    let slab_y = slab_center_y(&lazy.regions, cam_pos.y as f64);
+    let upper_half = slab_y.is_some_and(|y| cam_pos.y as f64 >= y);
+    let slab = slab_y
+        .map(|y| slab_cells(&lazy.regions, y, cam_pos.y as f64))
+        .unwrap_or_default();
+    let advanced_civ_regions = slab
+        .iter()
+        .filter(|r| lazy.has_advanced_civilization(r.id))
+        .count();
+    if slab_y != heatmap.slab_y
+        || upper_half != heatmap.upper_half
+        || lazy.stats_generation != heatmap.stats_generation
+        || lazy.region_flags_generation != heatmap.region_flags_generation
+        || advanced_civ_regions != heatmap.advanced_civ_regions
+    {
+        heatmap.slab_y = slab_y;
+        heatmap.upper_half = upper_half;
+        heatmap.stats_generation = lazy.stats_generation;
+        heatmap.region_flags_generation = lazy.region_flags_generation;
+        heatmap.advanced_civ_regions = advanced_civ_regions;
+
+        let cells: Vec<(Vec2, f32, LinearRgba)> = slab
+            .iter()
+            .map(|r| {
+                let center = Vec2::new(r.center[0] as f32, r.center[2] as f32);
+                let color = heat_color(r, lazy.has_advanced_civilization(r.id));
+                (center, r.size as f32 * HEATMAP_CELL_FILL * 0.5, color)
+            })
+            .collect();
+        if let Some(mesh) = meshes.get_mut(&heatmap.mesh) {
+            *mesh = heatmap_mesh(&cells);
+        }
+
+        // STATIC: center the minimap camera over the slab (only moves when the slab changes)
+        if !cells.is_empty() {
+            let mid = cells.iter().map(|c| c.0).sum::<Vec2>() / cells.len() as f32;
+            let center = Vec3::new(mid.x, 0.0, mid.y);
+            mini_tf.translation = center + Vec3::new(0.0, MINIMAP_HEIGHT, 0.0);
+            mini_tf.look_at(center, Vec3::Z);
         }
     }
 
-    // Move indicator rectangle to main camera position
+    // Move indicator rectangle to main camera position (just above the heat map)
     if let Ok(mut ind_tf) = indicator_q.get_single_mut() {
-        ind_tf.translation = Vec3::new(
-            main_tf.translation.x,
-            main_tf.translation.y + 2.0,
-            main_tf.translation.z,
-        );
+        ind_tf.translation = Vec3::new(cam_pos.x, 1.0, cam_pos.z);
         // Scale indicator based on zoom level (represents visible area)
         let size = match main_cam.zoom_level {
             ZoomLevel::Surface | ZoomLevel::Planetary => 5.0,
@@ -593,78 +979,176 @@ pub fn minimap_system(
         ind_tf.scale = Vec3::new(size, 1.0, size);
     }
 
-    // Update viewport position to bottom-right
-    if let Ok(window) = window_q.get_single() {
-        let w = window.physical_width();
-        let h = window.physical_height();
-        let size = 280u32.min(w / 3).min(h / 3);
-        let margin = 10u32;
-        if let Some(ref mut vp) = mini_camera.viewport {
-            vp.physical_position = UVec2::new(w - size - margin, h - size - margin);
-            vp.physical_size = UVec2::new(size, size);
-        }
+    let Ok(window) = window_q.get_single() else {
+        return;
+    };
+
+    // Update viewport position to bottom-right, sized by the HUD layout
+    let (position, size) = layout.minimap_viewport(window.physical_size(), window.scale_factor());
+    if let Some(ref mut vp) = mini_camera.viewport {
+        vp.physical_position = position;
+        vp.physical_size = size;
+    }
+
+    // Click on a cell: glide to that region and select it
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (Some(cursor), Some(vp)) = (window.cursor_position(), &mini_camera.viewport) else {
+        return;
+    };
+    let Some(local) = cursor_in_viewport(cursor, vp, window.scale_factor()) else {
+        return;
+    };
+    // Orthographic top-down: the ray's origin already holds the clicked X/Z
+    let Ok(ray) = mini_camera.viewport_to_world(mini_gtf, local) else {
+        return;
+    };
+    let Some(region) = region_at(&slab, ray.origin.x as f64, ray.origin.z as f64) else {
+        return;
+    };
+
+    let center = Vec3::new(
+        region.center[0] as f32,
+        region.center[1] as f32,
+        region.center[2] as f32,
+    );
+    main_cam.tracking = None;
+    main_cam.glide = Some(CameraGlide::looking_at(center + MINIMAP_GOTO_OFFSET, center));
+    selection.selected_region = Some(region.id);
+    selection.selected_star = None;
+    selection.selected_planet = None;
+    info!(
+        "Minimap: flying to region #{} density={:.2} stars={}",
+        region.id, region.density, region.star_count
+    );
+}
+
+/// Cursor position (logical window pixels) relative to `viewport`'s top-left corner, if
+/// it is inside it. The viewport is in physical pixels.
+pub(crate) fn cursor_in_viewport(cursor: Vec2, viewport: &Viewport, scale_factor: f32) -> Option<Vec2> {
+    let local = cursor - viewport.physical_position.as_vec2() / scale_factor;
+    let size = viewport.physical_size.as_vec2() / scale_factor;
+    (local.x >= 0.0 && local.y >= 0.0 && local.x <= size.x && local.y <= size.y).then_some(local)
+}
+
+/// Center Y of the horizontal slab of the grid nearest to `y`
+fn slab_center_y(regions: &[Region], y: f64) -> Option<f64> {
+    regions
+        .iter()
+        .filter(|r| r.parent.is_none())
+        .map(|r| r.center[1])
+        .min_by(|a, b| (a - y).abs().total_cmp(&(b - y).abs()))
+}
+
+/// Heat map cells of the slab centered on `slab_y`: its regions, each subdivided one
+/// replaced by its children in the half of the slab `y` is in
+fn slab_cells(regions: &[Region], slab_y: f64, y: f64) -> Vec<&Region> {
+    let subdivided: HashSet<u64> = regions.iter().filter_map(|r| r.parent).collect();
+    let upper = y >= slab_y;
+    regions
+        .iter()
+        .filter(|r| match r.parent {
+            None => !subdivided.contains(&r.id) && (r.center[1] - slab_y).abs() < r.size * 0.5,
+            // A child sits a quarter of its parent (half its own size) off the parent's center
+            Some(_) => (r.center[1] - slab_y).abs() < r.size && (r.center[1] >= slab_y) == upper,
+        })
+        .collect()
+}
+
+/// Cell covering the point (`x`, `z`)
+fn region_at<'a>(cells: &[&'a Region], x: f64, z: f64) -> Option<&'a Region> {
+    cells.iter().copied().find(|r| {
+        let half = r.size * 0.5;
+        (r.center[0] - x).abs() <= half && (r.center[2] - z).abs() <= half
+    })
+}
+
+/// Heat map cell color: cold blue (voids) to hot orange (clusters), green-tinted
+/// where there is life, gold for a Type I+ civilization
+fn heat_color(region: &Region, advanced_civ: bool) -> LinearRgba {
+    if advanced_civ {
+        return LinearRgba::rgb(1.0, 0.75, 0.15);
+    }
+    // Density is log-normal around 1 (≈0.37x–2.7x)
+    let t = ((region.density.ln() + 1.0) * 0.5).clamp(0.0, 1.0) as f32;
+    let heat = LinearRgba::rgb(0.02, 0.03, 0.3) * (1.0 - t) + LinearRgba::rgb(1.0, 0.3, 0.03) * t;
+    if region.has_life {
+        heat * 0.5 + LinearRgba::rgb(0.1, 0.9, 0.2) * 0.5
+    } else {
+        heat
     }
 }
 
-/// Get the saves directory path
-fn saves_dir() -> PathBuf {
-    PathBuf::from("saves")
+/// Flat quads in the XZ plane, one per (center, half size, color)
+fn heatmap_mesh(cells: &[(Vec2, f32, LinearRgba)]) -> Mesh {
+    let mut positions = Vec::with_capacity(cells.len() * 4);
+    let mut colors = Vec::with_capacity(cells.len() * 4);
+    let mut indices = Vec::with_capacity(cells.len() * 6);
+    for (i, &(center, half, color)) in cells.iter().enumerate() {
+        let base = (i * 4) as u32;
+        for (dx, dz) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            positions.push([center.x + dx * half, 0.0, center.y + dz * half]);
+            colors.push(color.to_f32_array());
+        }
+        indices.extend([base, base + 2, base + 1, base, base + 3, base + 2]);
+    }
+    let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices))
 }
 
-/// Handle F5 (save) / F9 (load) snapshot hotkeys
+/// Handle F5 (save) / F6 (export life catalogue) / F9 (load) snapshot hotkeys
 pub fn snapshot_system(
     keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
     mut universe: ResMut<UniverseState>,
     mut lazy: ResMut<LazyUniverse>,
+    mut saves: ResMut<PendingSaves>,
+    mut feed: ResMut<EventFeed>,
+    mut history: ResMut<UniverseHistory>,
 ) {
-    // F5 — Save snapshot
-    if keyboard.just_pressed(KeyCode::F5) {
-        let snapshot = UniverseSnapshot {
-            age: universe.age,
-            scale_factor: universe.scale_factor,
-            phase: universe.phase,
-            cycle: universe.cycle,
-            temperature: universe.temperature,
-            total_entropy: universe.total_entropy,
-            config: universe.config.clone(),
-            particles: universe.particles.iter().map(SerializedParticle::from).collect(),
-            regions: lazy.regions.clone(),
-            current_region_id: lazy.current_region_id,
-            loaded_stars: lazy.loaded_stars.clone(),
-            life_planets: lazy.life_planets.clone(),
-            civilization_count: lazy.civilization_count,
-            time_scale: universe.time_scale,
-            paused: universe.paused,
-        };
+    // F5 — Save snapshot: copy the state now, encode + write in the background
+    if action_just_pressed(&settings, &keyboard, Action::SaveSnapshot) {
+        let path = saves_dir().join(format!("snapshot_{}.bin", unix_now()));
+        info!("Saving snapshot: {}", path.display());
+        let capture = SnapshotCapture::capture(&universe, &lazy, &feed, &history);
+        saves.spawn(capture, path, Compression::Lz4);
+    }
 
+    // F6 — Export discovered life as JSON
+    if action_just_pressed(&settings, &keyboard, Action::ExportLife) {
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        let path = saves_dir().join(format!("snapshot_{timestamp}.bin"));
+        let path = PathBuf::from("exports").join(format!("life_{timestamp}.json"));
 
-        match matrix_storage::save_snapshot(&snapshot, &path) {
-            Ok(()) => info!("Snapshot saved: {}", path.display()),
-            Err(e) => error!("Failed to save snapshot: {e}"),
+        match matrix_storage::export_life_catalogue(
+            &lazy.discoveries,
+            &path,
+            matrix_storage::ExportFormat::Json,
+        ) {
+            Ok(()) => info!(
+                "Exported {} life discoveries: {}",
+                lazy.discoveries.len(),
+                path.display()
+            ),
+            Err(e) => error!("Failed to export life catalogue: {e}"),
         }
     }
 
     // F9 — Load latest snapshot
-    if keyboard.just_pressed(KeyCode::F9) {
+    if action_just_pressed(&settings, &keyboard, Action::LoadSnapshot) {
         let dir = saves_dir();
-        let latest = std::fs::read_dir(&dir)
-            .ok()
-            .and_then(|entries| {
-                entries
-                    .filter_map(|e| e.ok())
-                    .filter(|e| {
-                        e.path()
-                            .extension()
-                            .map_or(false, |ext| ext == "bin")
-                    })
-                    .max_by_key(|e| e.metadata().ok().and_then(|m| m.modified().ok()))
-                    .map(|e| e.path())
-            });
+        let latest = matrix_storage::list_snapshots(&dir)
+            .into_iter()
+            .next()
+            .map(|meta| meta.path);
 
         let Some(path) = latest else {
             warn!("No snapshots found in {}", dir.display());
@@ -673,30 +1157,140 @@ pub fn snapshot_system(
 
         match matrix_storage::load_snapshot(&path) {
             Ok(snapshot) => {
-                universe.age = snapshot.age;
-                universe.scale_factor = snapshot.scale_factor;
-                universe.phase = snapshot.phase;
-                universe.cycle = snapshot.cycle;
-                universe.temperature = snapshot.temperature;
-                universe.total_entropy = snapshot.total_entropy;
-                universe.config = snapshot.config;
-                universe.particles = snapshot.particles.iter().map(|p| p.into()).collect();
-                universe.time_scale = snapshot.time_scale;
-                universe.paused = snapshot.paused;
-
-                lazy.regions = snapshot.regions;
-                lazy.current_region_id = snapshot.current_region_id;
-                lazy.loaded_stars = snapshot.loaded_stars;
-                lazy.life_planets = snapshot.life_planets;
-                lazy.civilization_count = snapshot.civilization_count;
-                lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
-                lazy.particles_generation = lazy.particles_generation.wrapping_add(1);
-                universe.cached_alive_count = universe.particles.len();
-                universe.particles_generation = universe.particles_generation.wrapping_add(1);
-
-                info!("Snapshot loaded: {} (age: {:.4} Gyr)", path.display(), snapshot.age);
+                let age = snapshot.age;
+                saving::apply_snapshot(
+                    snapshot,
+                    &mut universe,
+                    &mut lazy,
+                    &mut feed,
+                    &mut history,
+                );
+                info!("Snapshot loaded: {} (age: {:.4} Gyr)", path.display(), age);
             }
             Err(e) => error!("Failed to load snapshot: {e}"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    #[test]
+    fn test_minimap_cell_lookup() {
+        let lazy = LazyUniverse::new(SimConfig::default(), 10.0);
+        let regions = &lazy.regions;
+        let region = &regions[regions.len() / 3];
+
+        // The slab of a point inside a region is that region's layer of the grid
+        let y = region.center[1] + region.size * 0.3;
+        let slab_y = slab_center_y(regions, y).unwrap();
+        assert_eq!(slab_y, region.center[1]);
+        let layer = slab_cells(regions, slab_y, y);
+        assert!(layer.len() > 1 && regions.len().is_multiple_of(layer.len()));
+
+        // Any point of a cell maps back to its region, points outside the grid to nothing
+        let hit = region_at(&layer, region.center[0] + region.size * 0.45, region.center[2] - 1.0);
+        assert_eq!(hit.map(|r| r.id), Some(region.id));
+        assert!(region_at(&layer, 1.0e6, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_minimap_shows_the_children_on_the_cameras_side() {
+        let mut config = SimConfig::default();
+        config.region_layout.subdivide_above = Some(1.5);
+        let lazy = LazyUniverse::new(config, 10.0);
+        let regions = &lazy.regions;
+        let parent = regions.iter().find(|r| r.parent.is_none() && r.density > 1.5).unwrap();
+        let cubes = lazy.config.region_layout.grid;
+
+        // Above the parent's middle its upper four children stand in for it
+        let y = parent.center[1] + 1.0;
+        let slab_y = slab_center_y(regions, y).unwrap();
+        assert_eq!(slab_y, parent.center[1]);
+        let layer = slab_cells(regions, slab_y, y);
+        assert!(!layer.iter().any(|r| r.id == parent.id));
+        let children: Vec<_> = layer.iter().filter(|r| r.parent == Some(parent.id)).collect();
+        assert_eq!(children.len(), 4);
+        assert!(children.iter().all(|c| c.center[1] > parent.center[1]));
+        let subdivided = layer.iter().filter(|r| r.parent.is_some()).count() / 4;
+        assert_eq!(layer.len(), (cubes[0] * cubes[2]) as usize + subdivided * 3);
+
+        // A click lands on the child under it
+        let child = children[0];
+        let hit = region_at(&layer, child.center[0], child.center[2]);
+        assert_eq!(hit.map(|r| r.id), Some(child.id));
+    }
+
+    #[test]
+    fn test_fly_speed_follows_distance_and_eases() {
+        // Auto speed grows with the distance and is scaled by the scroll multiplier
+        let mut cam = FlyCamera {
+            nearest_dist: 5.0,
+            ..default()
+        };
+        let near = cam.effective_speed();
+        cam.nearest_dist = 800.0;
+        assert!(cam.effective_speed() > near * 100.0);
+        cam.speed_multiplier = 2.0;
+        assert_eq!(cam.effective_speed(), auto_fly_speed(800.0) * 2.0);
+        assert_eq!(auto_fly_speed(0.0), AUTO_SPEED_MIN);
+        assert_eq!(auto_fly_speed(1.0e9), AUTO_SPEED_MAX);
+        cam.auto_speed = false;
+        assert_eq!(cam.effective_speed(), cam.speed);
+
+        // Velocity eases up to the input without overshooting, then coasts to a stop
+        let target = Vec3::X * 10.0;
+        let mut velocity = Vec3::ZERO;
+        let mut last = 0.0;
+        for _ in 0..120 {
+            velocity = ease_velocity(velocity, target, 10.0, 1.0 / 60.0);
+            assert!(velocity.x >= last && velocity.x <= target.x);
+            last = velocity.x;
+        }
+        assert!(velocity.distance(target) < 0.1);
+        let mut frames = 0;
+        while velocity != Vec3::ZERO {
+            velocity = ease_velocity(velocity, Vec3::ZERO, 10.0, 1.0 / 60.0);
+            frames += 1;
+            assert!(frames < 120, "never stopped");
+        }
+    }
+
+    #[test]
+    fn test_auto_level_hysteresis() {
+        use ZoomLevel::*;
+        // Into Stellar below 90, back out to Galactic only above 120
+        assert_eq!(auto_level(Galactic, 95.0), Galactic);
+        assert_eq!(auto_level(Galactic, 89.0), Stellar);
+        assert_eq!(auto_level(Stellar, 110.0), Stellar);
+        assert_eq!(auto_level(Stellar, 121.0), Galactic);
+        // Every other boundary has its band, and big jumps skip levels
+        assert_eq!(auto_level(Cosmic, 460.0), Cosmic);
+        assert_eq!(auto_level(Cosmic, 440.0), Galactic);
+        assert_eq!(auto_level(Galactic, 550.0), Galactic);
+        assert_eq!(auto_level(Stellar, 9.5), Stellar);
+        assert_eq!(auto_level(Stellar, 0.5), Surface);
+        assert_eq!(auto_level(Surface, 1.1), Surface);
+        assert_eq!(auto_level(Surface, 1000.0), Cosmic);
+
+        // Flying back and forth across a boundary inside the band never switches
+        let mut level = Stellar;
+        for dist in [95.0, 105.0, 115.0, 92.0, 119.0] {
+            level = auto_level(level, dist);
+            assert_eq!(level, Stellar);
+        }
+    }
+
+    #[test]
+    fn test_heatmap_mesh_has_one_quad_per_cell() {
+        let cells = [
+            (Vec2::ZERO, 1.0, LinearRgba::RED),
+            (Vec2::new(10.0, 0.0), 1.0, LinearRgba::GREEN),
+        ];
+        let mesh = heatmap_mesh(&cells);
+        assert_eq!(mesh.count_vertices(), 8);
+        assert_eq!(mesh.indices().unwrap().len(), 12);
+    }
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use matrix_core::SimConfig;
+use matrix_physics::stats::{Stage, UniverseCensus};
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+
+use super::console::ConsoleState;
+use super::input_capture::CapturesPointer;
+use super::locale::Strings;
+use super::settings::{Action, UserSettings, action_just_pressed};
+
+/// Regions a census samples unless the console asks for another number
+pub const CENSUS_REGIONS: usize = 48;
+/// A census this many Gyr older than the universe is redone when the panel opens
+const STALE_GYR: f64 = 1.0;
+
+/// The census panel: the last census of the universe and the one being computed
+#[derive(Resource, Default)]
+pub struct CensusPanel {
+    pub open: bool,
+    pub census: Option<UniverseCensus>,
+    task: Option<Task<UniverseCensus>>,
+    /// Asked for from the console: print the result there as well
+    to_console: bool,
+}
+
+impl CensusPanel {
+    /// Start a census on the async pool (replacing an unfinished one)
+    pub fn request(&mut self, config: SimConfig, age_gyr: f64, regions: usize, to_console: bool) {
+        self.to_console = to_console;
+        self.task = Some(
+            AsyncComputeTaskPool::get()
+                .spawn(async move { UniverseCensus::compute(&config, age_gyr, regions) }),
+        );
+    }
+
+    pub fn running(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Whether the last census is missing or no longer describes this universe
+    fn stale(&self, seed: u64, age_gyr: f64) -> bool {
+        self.census
+            .as_ref()
+            .is_none_or(|c| c.seed != seed || (age_gyr - c.age_gyr).abs() >= STALE_GYR)
+    }
+}
+
+#[derive(Component)]
+pub struct CensusRoot;
+
+#[derive(Component)]
+pub struct CensusText;
+
+/// Spawn the (hidden) census panel, left side
+pub fn spawn_census_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(40.0),
+                left: Val::Px(10.0),
+                min_width: Val::Px(320.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+            CensusRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+                CensusText,
+            ));
+        });
+}
+
+/// [F4] shows / hides the census, starting one when there's none for the current universe
+pub fn census_keys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    universe: Res<UniverseState>,
+    lazy: Res<LazyUniverse>,
+    mut panel: ResMut<CensusPanel>,
+) {
+    if !action_just_pressed(&settings, &keyboard, Action::Census) {
+        return;
+    }
+    panel.open = !panel.open;
+    if panel.open && !panel.running() && panel.stale(lazy.config.seed, universe.age) {
+        panel.request(lazy.config.clone(), universe.age, CENSUS_REGIONS, false);
+    }
+}
+
+/// Pick up a finished census
+pub fn census_poll_system(
+    mut panel: ResMut<CensusPanel>,
+    mut console: ResMut<ConsoleState>,
+    strings: Res<Strings>,
+) {
+    let Some(task) = panel.bypass_change_detection().task.as_mut() else {
+        return;
+    };
+    let Some(census) = block_on(poll_once(task)) else {
+        return;
+    };
+    info!(
+        "Census of seed {} at {:.1} Gyr over {} regions done",
+        census.seed, census.age_gyr, census.sampled_regions
+    );
+    if panel.to_console {
+        for line in census_lines(&census, &strings).lines() {
+            console.push(line.to_string(), false);
+        }
+    }
+    panel.task = None;
+    panel.census = Some(census);
+}
+
+pub fn update_census_panel(
+    panel: Res<CensusPanel>,
+    strings: Res<Strings>,
+    mut root_q: Query<&mut Node, With<CensusRoot>>,
+    mut text_q: Query<&mut Text, With<CensusText>>,
+) {
+    if !panel.is_changed() && !strings.is_changed() {
+        return;
+    }
+    for mut node in &mut root_q {
+        node.display = if panel.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    let label = match &panel.census {
+        Some(census) if !panel.running() => census_lines(census, &strings),
+        Some(census) => format!("{}\n{}", census_lines(census, &strings), strings.tr("census.updating", &[])),
+        None => strings.tr("census.sampling", &[]),
+    };
+    for mut text in &mut text_q {
+        **text = label.clone();
+    }
+}
+
+/// Header, then per stage: sampled count, rate from the stage before and the
+/// extrapolated total with its 95% interval
+pub fn census_lines(census: &UniverseCensus, strings: &Strings) -> String {
+    let mut lines = vec![strings.tr(
+        "census.header",
+        &[
+            ("seed", &census.seed),
+            ("age", &strings.number(census.age_gyr, 1)),
+            ("sampled", &census.sampled_regions),
+            ("total", &census.total_regions),
+        ],
+    )];
+    for estimate in &census.stages {
+        let rate = match estimate.stage {
+            Stage::Planets => String::new(),
+            _ => format!(" ({}%)", strings.number(estimate.rate * 100.0, 3)),
+        };
+        lines.push(strings.tr(
+            "census.stage",
+            &[
+                ("stage", &strings.tr(stage_key(estimate.stage), &[])),
+                ("sampled", &estimate.sampled),
+                ("rate", &rate),
+                ("total", &fmt_estimate(estimate.total)),
+                ("low", &fmt_estimate(estimate.low)),
+                ("high", &fmt_estimate(estimate.high)),
+            ],
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Locale key of a stage's name
+fn stage_key(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Planets => "census.planets",
+        Stage::Habitable => "census.habitable",
+        Stage::Life => "census.life",
+        Stage::Multicellular => "census.multicellular",
+        Stage::Intelligent => "census.intelligent",
+        Stage::Technological => "census.technological",
+    }
+}
+
+fn fmt_estimate(value: f64) -> String {
+    if value.is_infinite() {
+        "∞".to_string()
+    } else if value >= 1e6 {
+        format!("{:.2}M", value / 1e6)
+    } else if value >= 1e4 {
+        format!("{:.1}K", value / 1e3)
+    } else {
+        format!("{value:.0}")
+    }
+}
@@ -0,0 +1,780 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::{AsyncComputeTaskPool, Task, TaskPool, block_on, poll_once};
+use matrix_core::{Planet, PlanetType, SurfacePreset};
+use matrix_physics::terrain::{self, TerrainSampler};
+
+use super::camera::FlyCamera;
+use super::props::{PropAssets, PropPlanner, PropSpot, spawn_props};
+use super::surface::{SurfaceState, TerrainMesh, WaterPlane};
+
+/// Vertices along a chunk edge
+pub const CHUNK_VERTS: usize = 32;
+/// Chunk edge length (meters)
+pub const CHUNK_SIZE: f32 = 64.0;
+/// Distance between neighboring vertices
+const CHUNK_STEP: f32 = CHUNK_SIZE / (CHUNK_VERTS - 1) as f32;
+/// Default load radius (chunks) around the camera
+const DEFAULT_RADIUS: i32 = 3;
+/// Loaded chunks are kept this many chunks past the radius, so walking
+/// back and forth over a border doesn't rebuild them
+const UNLOAD_MARGIN: i32 = 1;
+/// Quads along each edge of the shared water plane (waves move its vertices)
+const WAVE_SUBDIVISIONS: u32 = 15;
+/// Crest height of the ocean swell (meters)
+const WAVE_HEIGHT: f32 = 0.12;
+/// Lava sea glow, before the slow pulse
+const LAVA_GLOW: f32 = 6.0;
+/// Side of the ice crack texture (pixels)
+const ICE_TEXTURE_SIZE: u32 = 128;
+
+/// Slope (rise over run) where faces start turning to bare cliff, and where they are fully cliff
+const CLIFF_SLOPE: (f32, f32) = (0.8, 1.4);
+/// Height above the water plane still colored as beach
+const SHORE_HEIGHT: f32 = 0.8;
+const DUNE_SAND: [f32; 4] = [0.82, 0.68, 0.45, 1.0];
+const SEABED: [f32; 4] = [0.35, 0.38, 0.30, 1.0];
+/// Hill shading on the surface map: brightness facing away from the light, and the
+/// extra facing it
+const MAP_SHADE: (f32, f32) = (0.55, 0.55);
+
+/// What fills the low ground of a surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidKind {
+    /// Translucent water with a gentle swell
+    Water,
+    /// Emissive molten rock, pulsing slowly
+    Lava,
+    /// Opaque, cracked ice sheet (solid ground)
+    Ice,
+}
+
+impl LiquidKind {
+    pub fn for_planet(planet: &Planet) -> Option<Self> {
+        match planet.planet_type {
+            PlanetType::Lava => Some(Self::Lava),
+            PlanetType::Frozen => Some(Self::Ice),
+            PlanetType::Ocean => Some(Self::Water),
+            PlanetType::Rocky if planet.has_water => Some(Self::Water),
+            _ => None,
+        }
+    }
+
+    /// Color of the liquid seen from above, on the surface map (linear)
+    pub fn map_color(self) -> [f32; 4] {
+        match self {
+            Self::Water => [0.05, 0.18, 0.5, 1.0],
+            Self::Lava => [1.0, 0.3, 0.02, 1.0],
+            Self::Ice => [0.72, 0.84, 0.95, 1.0],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Water => "water",
+            Self::Lava => "lava sea",
+            Self::Ice => "ice sheet",
+        }
+    }
+}
+
+/// The liquid layer of the current surface and its level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceLiquid {
+    pub kind: LiquidKind,
+    /// Height of the liquid surface (meters)
+    pub level: f32,
+}
+
+/// One terrain chunk, by chunk coordinate (x, z)
+#[derive(Component)]
+pub struct TerrainChunk(pub IVec2);
+
+/// Terrain, water and prop entities of a loaded chunk
+struct LoadedChunk {
+    terrain: Entity,
+    water: Option<Entity>,
+    props: Vec<Entity>,
+}
+
+/// Chunk-sized liquid plane shared by every chunk that dips below the level
+struct LiquidLayer {
+    liquid: SurfaceLiquid,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// What every chunk of the current surface is built from
+struct ChunkSource {
+    sampler: Arc<TerrainSampler>,
+    /// Heights mapped to the bottom and top of the biome color bands
+    band: (f32, f32),
+    material: Handle<StandardMaterial>,
+    liquid: Option<LiquidLayer>,
+    props: Option<(Arc<PropPlanner>, PropAssets)>,
+}
+
+/// Chunked surface terrain: chunks are built on the async task pool in a ring
+/// around the camera and despawned once they fall behind
+#[derive(Resource)]
+pub struct TerrainChunks {
+    /// Load radius in chunks
+    pub radius: i32,
+    source: Option<ChunkSource>,
+    loaded: HashMap<IVec2, LoadedChunk>,
+    pending: HashMap<IVec2, Task<ChunkMesh>>,
+}
+
+impl Default for TerrainChunks {
+    fn default() -> Self {
+        Self {
+            radius: DEFAULT_RADIUS,
+            source: None,
+            loaded: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl TerrainChunks {
+    /// Start streaming the terrain of `planet` (on landing). Returns the liquid layer,
+    /// placed so that its share of the terrain around the landing site lies below it.
+    /// A matching `preset` (worked out at `RegionDetail::Planetary`) skips the survey.
+    /// `tint` shifts the terrain colors (the day or night side of a tidally locked planet).
+    pub fn begin(
+        &mut self,
+        (seed, planet): (u64, &Planet),
+        preset: Option<&SurfacePreset>,
+        tint: Color,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        images: &mut Assets<Image>,
+    ) -> Option<SurfaceLiquid> {
+        self.clear();
+        let sampler = TerrainSampler::new(seed, planet.planet_type);
+        let preset = preset.filter(|p| p.planet_id == planet.id && p.terrain_seed == seed);
+        let (band, sea_level) = match preset {
+            Some(preset) => (preset.height_range, preset.sea_level),
+            None => terrain::survey_landing_site(&sampler, planet),
+        };
+        let material = materials.add(StandardMaterial {
+            base_color: tint, // vertex colors handle coloring, this shifts them
+            perceptual_roughness: 0.9,
+            ..default()
+        });
+        let liquid = LiquidKind::for_planet(planet).zip(sea_level).map(|(kind, level)| {
+            let liquid = SurfaceLiquid { kind, level };
+            let flat = || Plane3d::default().mesh().size(CHUNK_SIZE, CHUNK_SIZE).build();
+            let (mesh, material) = match kind {
+                LiquidKind::Water => (
+                    Plane3d::default()
+                        .mesh()
+                        .size(CHUNK_SIZE, CHUNK_SIZE)
+                        .subdivisions(WAVE_SUBDIVISIONS)
+                        .build(),
+                    StandardMaterial {
+                        base_color: Color::srgba(0.1, 0.3, 0.8, 0.6),
+                        alpha_mode: AlphaMode::Blend,
+                        perceptual_roughness: 0.1,
+                        ..default()
+                    },
+                ),
+                LiquidKind::Lava => (
+                    flat(),
+                    StandardMaterial {
+                        base_color: Color::srgb(0.35, 0.08, 0.0),
+                        emissive: LinearRgba::rgb(1.0, 0.35, 0.05) * LAVA_GLOW,
+                        perceptual_roughness: 0.6,
+                        ..default()
+                    },
+                ),
+                LiquidKind::Ice => (
+                    flat(),
+                    StandardMaterial {
+                        base_color: Color::srgb(0.85, 0.92, 1.0),
+                        base_color_texture: Some(images.add(ice_crack_image(&sampler))),
+                        perceptual_roughness: 0.3,
+                        ..default()
+                    },
+                ),
+            };
+            LiquidLayer {
+                liquid,
+                mesh: meshes.add(mesh),
+                material: materials.add(material),
+            }
+        });
+        let surface_liquid = liquid.as_ref().map(|layer| layer.liquid);
+        let props = PropPlanner::new(planet, &sampler, surface_liquid).zip(planet.life.as_ref()).map(
+            |(planner, bio)| (Arc::new(planner), PropAssets::new(planet.id, bio, meshes, materials)),
+        );
+        self.source = Some(ChunkSource {
+            sampler: Arc::new(sampler),
+            band,
+            material,
+            liquid,
+            props,
+        });
+        surface_liquid
+    }
+
+    /// Forget the current surface (on exit; the surface exit despawns the entities)
+    pub fn clear(&mut self) {
+        self.source = None;
+        self.loaded.clear();
+        self.pending.clear();
+    }
+
+    pub fn loaded_count(&self) -> usize {
+        self.loaded.len()
+    }
+
+    /// Top-down map of the current surface around `center` (`surface_map_image`)
+    pub fn map_image(&self, center: Vec2, extent: f32, size: u32) -> Option<Image> {
+        let source = self.source.as_ref()?;
+        let liquid = source.liquid.as_ref().map(|layer| layer.liquid);
+        Some(surface_map_image(&source.sampler, source.band, liquid, center, extent, size))
+    }
+
+    /// Settlement sites of the current surface (x, z)
+    pub fn settlements(&self) -> &[Vec2] {
+        match self.source.as_ref().and_then(|source| source.props.as_ref()) {
+            Some((planner, _)) => planner.settlements(),
+            None => &[],
+        }
+    }
+
+    /// Distance from (x, z) to the nearest settlement of the current surface
+    pub fn nearest_settlement(&self, x: f32, z: f32) -> Option<f32> {
+        self.settlements()
+            .iter()
+            .map(|site| site.distance(Vec2::new(x, z)))
+            .min_by(f32::total_cmp)
+    }
+}
+
+/// Chunk containing the point (x, z)
+pub fn chunk_coord(x: f32, z: f32) -> IVec2 {
+    IVec2::new((x / CHUNK_SIZE).floor() as i32, (z / CHUNK_SIZE).floor() as i32)
+}
+
+/// Load chunks within the radius of the camera's chunk, spawn the ones whose meshes
+/// finished building and despawn the ones left behind
+pub fn terrain_chunk_system(
+    mut commands: Commands,
+    state: Res<SurfaceState>,
+    mut chunks: ResMut<TerrainChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    camera_q: Query<&Transform, With<FlyCamera>>,
+) {
+    if !state.active {
+        return;
+    }
+    let Ok(cam_tf) = camera_q.get_single() else {
+        return;
+    };
+    let chunks = &mut *chunks;
+    let Some(ref source) = chunks.source else {
+        return;
+    };
+    let center = chunk_coord(cam_tf.translation.x, cam_tf.translation.z);
+    let radius = chunks.radius;
+    let keep = |coord: IVec2| (coord - center).abs().max_element() <= radius + UNLOAD_MARGIN;
+
+    chunks.loaded.retain(|&coord, chunk| {
+        if keep(coord) {
+            return true;
+        }
+        commands.entity(chunk.terrain).despawn();
+        if let Some(water) = chunk.water {
+            commands.entity(water).despawn();
+        }
+        for &prop in &chunk.props {
+            commands.entity(prop).despawn();
+        }
+        false
+    });
+    chunks.pending.retain(|&coord, _| keep(coord));
+
+    let pool = AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    for dz in -radius..=radius {
+        for dx in -radius..=radius {
+            let coord = center + IVec2::new(dx, dz);
+            if dx * dx + dz * dz > radius * radius
+                || chunks.loaded.contains_key(&coord)
+                || chunks.pending.contains_key(&coord)
+            {
+                continue;
+            }
+            let sampler = Arc::clone(&source.sampler);
+            let band = source.band;
+            let liquid = source.liquid.as_ref().map(|layer| layer.liquid);
+            let planner = source.props.as_ref().map(|(planner, _)| Arc::clone(planner));
+            let task = pool.spawn(async move {
+                let mut chunk = build_chunk_mesh(&sampler, coord, band, liquid);
+                if let Some(planner) = planner {
+                    chunk.props = planner.plan(&sampler, coord, liquid);
+                }
+                chunk
+            });
+            chunks.pending.insert(coord, task);
+        }
+    }
+
+    let ready: Vec<(IVec2, ChunkMesh)> = chunks
+        .pending
+        .iter_mut()
+        .filter_map(|(&coord, task)| block_on(poll_once(task)).map(|mesh| (coord, mesh)))
+        .collect();
+    for (coord, chunk) in ready {
+        chunks.pending.remove(&coord);
+        let terrain = commands
+            .spawn((
+                Mesh3d(meshes.add(chunk.mesh)),
+                MeshMaterial3d(source.material.clone()),
+                Transform::IDENTITY,
+                TerrainMesh,
+                TerrainChunk(coord),
+            ))
+            .id();
+        // Liquid only where the chunk dips below its level
+        let water = source
+            .liquid
+            .as_ref()
+            .filter(|layer| chunk.min_height < layer.liquid.level)
+            .map(|layer| {
+                let middle = (coord.as_vec2() + 0.5) * CHUNK_SIZE;
+                commands
+                    .spawn((
+                        Mesh3d(layer.mesh.clone()),
+                        MeshMaterial3d(layer.material.clone()),
+                        Transform::from_xyz(middle.x, layer.liquid.level, middle.y),
+                        WaterPlane,
+                        TerrainChunk(coord),
+                    ))
+                    .id()
+            });
+        let props = source
+            .props
+            .as_ref()
+            .map_or_else(Vec::new, |(_, assets)| spawn_props(&mut commands, assets, &chunk.props));
+        chunks.loaded.insert(coord, LoadedChunk { terrain, water, props });
+    }
+}
+
+/// Animate the liquid layer: a gentle swell on water (the shared plane's vertices,
+/// periodic over a chunk so neighbors stay seamless) and a slow pulse in the lava glow
+pub fn liquid_animation_system(
+    time: Res<Time>,
+    chunks: Res<TerrainChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(layer) = chunks.source.as_ref().and_then(|s| s.liquid.as_ref()) else {
+        return;
+    };
+    let t = time.elapsed_secs();
+    match layer.liquid.kind {
+        LiquidKind::Water => {
+            let Some(mesh) = meshes.get_mut(&layer.mesh) else {
+                return;
+            };
+            let mut normals = Vec::new();
+            if let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+            {
+                for p in positions.iter_mut() {
+                    let (y, dx, dz) = wave(p[0], p[2], t);
+                    p[1] = y;
+                    normals.push(Vec3::new(-dx, 1.0, -dz).normalize().to_array());
+                }
+            }
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        }
+        LiquidKind::Lava => {
+            if let Some(material) = materials.get_mut(&layer.material) {
+                let pulse = 1.0 + 0.35 * (t * 1.7).sin() + 0.1 * (t * 4.3).sin();
+                material.emissive = LinearRgba::rgb(1.0, 0.35, 0.05) * LAVA_GLOW * pulse;
+            }
+        }
+        LiquidKind::Ice => {}
+    }
+}
+
+/// Swell height and slope at chunk-local (x, z): whole wavelengths per chunk
+fn wave(x: f32, z: f32, t: f32) -> (f32, f32, f32) {
+    let k = std::f32::consts::TAU / CHUNK_SIZE;
+    let (a, b) = (2.0 * k * x + 1.3 * t, 3.0 * k * z - 0.9 * t);
+    let h = WAVE_HEIGHT * 0.5;
+    (
+        h * (a.sin() + b.sin()),
+        h * 2.0 * k * a.cos(),
+        h * 3.0 * k * b.cos(),
+    )
+}
+
+/// Pale blue ice with dark cracks along the zero lines of the terrain noise
+fn ice_crack_image(sampler: &TerrainSampler) -> Image {
+    let size = ICE_TEXTURE_SIZE;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (x as f32 / size as f32 * 6.0, y as f32 / size as f32 * 6.0);
+            let crack = sampler.noise(u + 512.0, v - 256.0).abs() < 0.035
+                || sampler.noise(u * 2.3 - 99.0, v * 2.3 + 41.0).abs() < 0.02;
+            let shade = 0.9 + 0.1 * sampler.noise(u * 4.0, v * 4.0);
+            let [r, g, b] = if crack {
+                [70, 95, 120]
+            } else {
+                [(220.0 * shade) as u8, (235.0 * shade) as u8, (250.0 * shade) as u8]
+            };
+            data.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// A built chunk mesh, its lowest point and its props
+pub struct ChunkMesh {
+    pub mesh: Mesh,
+    pub min_height: f32,
+    /// Props planned for the chunk (filled in by the chunk task)
+    pub props: Vec<PropSpot>,
+}
+
+/// Build one chunk: a `CHUNK_VERTS`² grid in world coordinates. Vertex positions come
+/// from global grid indices and normals from the continuous height field, so neighboring
+/// chunks share their border vertices exactly and shade seamlessly.
+pub fn build_chunk_mesh(
+    sampler: &TerrainSampler,
+    coord: IVec2,
+    band: (f32, f32),
+    liquid: Option<SurfaceLiquid>,
+) -> ChunkMesh {
+    let cells = (CHUNK_VERTS - 1) as i32;
+    let vert_count = CHUNK_VERTS * CHUNK_VERTS;
+    let mut positions = Vec::with_capacity(vert_count);
+    let mut normals = Vec::with_capacity(vert_count);
+    let mut uvs = Vec::with_capacity(vert_count);
+    let mut colors = Vec::with_capacity(vert_count);
+    let mut min_height = f32::MAX;
+
+    let sea_level = liquid.filter(|l| l.kind == LiquidKind::Water).map(|l| l.level);
+    for zi in 0..CHUNK_VERTS {
+        for xi in 0..CHUNK_VERTS {
+            let x = (coord.x * cells + xi as i32) as f32 * CHUNK_STEP;
+            let z = (coord.y * cells + zi as i32) as f32 * CHUNK_STEP;
+            let y = sampler.height(x, z);
+            min_height = min_height.min(y);
+            positions.push([x, y, z]);
+            normals.push(sampler.normal(x, z));
+            uvs.push([
+                xi as f32 / (CHUNK_VERTS - 1) as f32,
+                zi as f32 / (CHUNK_VERTS - 1) as f32,
+            ]);
+            colors.push(ground_color(sampler, band, sea_level, x, z, y, sampler.slope(x, z)));
+        }
+    }
+
+    let mut indices: Vec<u32> = Vec::with_capacity((cells * cells * 6) as usize);
+    for zi in 0..CHUNK_VERTS - 1 {
+        for xi in 0..CHUNK_VERTS - 1 {
+            let tl = (zi * CHUNK_VERTS + xi) as u32;
+            let tr = tl + 1;
+            let bl = tl + CHUNK_VERTS as u32;
+            let br = bl + 1;
+            indices.extend_from_slice(&[tl, bl, tr, tr, bl, br]);
+        }
+    }
+
+    let mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices));
+    ChunkMesh {
+        mesh,
+        min_height,
+        props: Vec::new(),
+    }
+}
+
+/// Color of the ground at (x, z), `y` high with the given slope: the biome band of its
+/// height, sand where arid, seabed and shore around the sea, bare rock on cliffs
+fn ground_color(
+    sampler: &TerrainSampler,
+    band: (f32, f32),
+    sea_level: Option<f32>,
+    x: f32,
+    z: f32,
+    y: f32,
+    slope: f32,
+) -> [f32; 4] {
+    let planet_type = &sampler.planet_type;
+    let t = ((y - band.0) / (band.1 - band.0).max(0.01)).clamp(0.0, 1.0);
+    let mut color = mix_color(biome_color(t, planet_type), DUNE_SAND, sampler.aridity(x, z));
+    if let Some(level) = sea_level {
+        if y < level {
+            color = SEABED;
+        } else if y < level + SHORE_HEIGHT {
+            color = biome_color(0.0, planet_type);
+        }
+    }
+    let steep = ((slope - CLIFF_SLOPE.0) / (CLIFF_SLOPE.1 - CLIFF_SLOPE.0)).clamp(0.0, 1.0);
+    mix_color(color, cliff_color(planet_type), steep)
+}
+
+/// Top-down map of the terrain: `size`² pixels covering `extent` meters around `center`
+/// (x, z), north (-Z) up. Ground in the chunk colors, shaded by a light from the
+/// north-west; liquids drawn over the ground below their level.
+pub fn surface_map_image(
+    sampler: &TerrainSampler,
+    band: (f32, f32),
+    liquid: Option<SurfaceLiquid>,
+    center: Vec2,
+    extent: f32,
+    size: u32,
+) -> Image {
+    let sea_level = liquid.filter(|l| l.kind == LiquidKind::Water).map(|l| l.level);
+    let light = Vec3::new(-1.0, 2.0, -1.0).normalize();
+    let step = extent / size as f32;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for row in 0..size {
+        for column in 0..size {
+            let x = center.x - extent / 2.0 + (column as f32 + 0.5) * step;
+            let z = center.y - extent / 2.0 + (row as f32 + 0.5) * step;
+            let y = sampler.height(x, z);
+            let color = match liquid {
+                Some(liquid) if y < liquid.level => liquid.kind.map_color(),
+                _ => {
+                    let normal = Vec3::from(sampler.normal(x, z));
+                    let slope = normal.xz().length() / normal.y.max(1e-3);
+                    let shade = MAP_SHADE.0 + MAP_SHADE.1 * normal.dot(light).max(0.0);
+                    let [r, g, b, a] = ground_color(sampler, band, sea_level, x, z, y, slope);
+                    [r * shade, g * shade, b * shade, a]
+                }
+            };
+            data.extend_from_slice(&Srgba::from(LinearRgba::from_f32_array(color)).to_u8_array());
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+fn biome_color(height_t: f32, planet_type: &PlanetType) -> [f32; 4] {
+    match planet_type {
+        PlanetType::Rocky => {
+            if height_t < 0.15 {
+                [0.76, 0.70, 0.50, 1.0] // shore/sand
+            } else if height_t < 0.4 {
+                [0.25, 0.50, 0.18, 1.0] // grassland
+            } else if height_t < 0.7 {
+                [0.18, 0.38, 0.12, 1.0] // forest
+            } else if height_t < 0.85 {
+                [0.50, 0.45, 0.38, 1.0] // rock
+            } else {
+                [0.90, 0.92, 0.95, 1.0] // snow
+            }
+        }
+        PlanetType::Frozen => {
+            if height_t < 0.3 {
+                [0.70, 0.80, 0.90, 1.0]
+            } else if height_t < 0.7 {
+                [0.80, 0.85, 0.92, 1.0]
+            } else {
+                [0.95, 0.97, 1.0, 1.0]
+            }
+        }
+        PlanetType::Lava => {
+            if height_t < 0.2 {
+                [1.0, 0.4, 0.0, 1.0] // lava glow
+            } else if height_t < 0.5 {
+                [0.25, 0.08, 0.02, 1.0] // dark basalt
+            } else {
+                [0.35, 0.20, 0.10, 1.0] // cooled rock
+            }
+        }
+        PlanetType::Ocean => {
+            if height_t < 0.2 {
+                [0.60, 0.58, 0.40, 1.0] // sandy shore
+            } else if height_t < 0.6 {
+                [0.30, 0.55, 0.25, 1.0] // vegetation
+            } else {
+                [0.40, 0.50, 0.35, 1.0] // highlands
+            }
+        }
+        PlanetType::Barren => {
+            if height_t < 0.3 {
+                [0.32, 0.31, 0.30, 1.0] // crater floors
+            } else if height_t < 0.75 {
+                [0.48, 0.46, 0.44, 1.0] // regolith
+            } else {
+                [0.62, 0.60, 0.57, 1.0] // bright rims
+            }
+        }
+        PlanetType::GasGiant => [0.70, 0.60, 0.40, 1.0],
+        PlanetType::IceGiant => [0.50, 0.60, 0.80, 1.0],
+    }
+}
+
+/// Bare rock showing on steep faces
+fn cliff_color(planet_type: &PlanetType) -> [f32; 4] {
+    match planet_type {
+        PlanetType::Rocky | PlanetType::Ocean => [0.42, 0.38, 0.34, 1.0],
+        PlanetType::Frozen => [0.45, 0.50, 0.58, 1.0],
+        PlanetType::Lava => [0.12, 0.06, 0.04, 1.0],
+        PlanetType::Barren => [0.26, 0.25, 0.24, 1.0],
+        PlanetType::GasGiant | PlanetType::IceGiant => biome_color(0.5, planet_type),
+    }
+}
+
+fn mix_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::VertexAttributeValues;
+
+    fn attribute(mesh: &Mesh, id: impl Into<bevy::render::mesh::MeshVertexAttributeId>) -> Vec<[f32; 3]> {
+        match mesh.attribute(id) {
+            Some(VertexAttributeValues::Float32x3(values)) => values.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_chunks_are_deterministic_and_stitched() {
+        let sampler = TerrainSampler::new(2024, PlanetType::Rocky);
+        let survey = terrain::HeightSurvey::new(&sampler, terrain::SURVEY_EXTENT, 9);
+        let band = (survey.min(), survey.max());
+        let water = Some(SurfaceLiquid {
+            kind: LiquidKind::Water,
+            level: survey.percentile(0.3),
+        });
+        let build = |x, z| build_chunk_mesh(&sampler, IVec2::new(x, z), band, water).mesh;
+
+        let a = build(3, -2);
+        let b = build(3, -2);
+        assert_eq!(attribute(&a, Mesh::ATTRIBUTE_POSITION), attribute(&b, Mesh::ATTRIBUTE_POSITION));
+        assert_eq!(attribute(&a, Mesh::ATTRIBUTE_NORMAL), attribute(&b, Mesh::ATTRIBUTE_NORMAL));
+        let rebuilt = TerrainSampler::new(2024, PlanetType::Rocky);
+        let c = build_chunk_mesh(&rebuilt, IVec2::new(3, -2), band, water).mesh;
+        assert_eq!(attribute(&a, Mesh::ATTRIBUTE_POSITION), attribute(&c, Mesh::ATTRIBUTE_POSITION));
+
+        // The east edge of one chunk is the west edge of the next, normals included
+        let east = build(4, -2);
+        let edge = |mesh: &Mesh, column: usize| {
+            let positions = attribute(mesh, Mesh::ATTRIBUTE_POSITION);
+            let normals = attribute(mesh, Mesh::ATTRIBUTE_NORMAL);
+            (0..CHUNK_VERTS)
+                .map(|row| (positions[row * CHUNK_VERTS + column], normals[row * CHUNK_VERTS + column]))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(edge(&a, CHUNK_VERTS - 1), edge(&east, 0));
+    }
+
+    #[test]
+    fn test_liquid_fits_the_planet() {
+        let mut planet = Planet {
+            id: 1,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 900.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: true,
+            atmosphere: matrix_core::AtmosphereType::ThickCO2,
+            planet_type: PlanetType::Lava,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        };
+        assert_eq!(LiquidKind::for_planet(&planet), Some(LiquidKind::Lava));
+        planet.planet_type = PlanetType::Frozen;
+        assert_eq!(LiquidKind::for_planet(&planet), Some(LiquidKind::Ice));
+        planet.planet_type = PlanetType::Rocky;
+        assert_eq!(LiquidKind::for_planet(&planet), None);
+        planet.has_water = true;
+        assert_eq!(LiquidKind::for_planet(&planet), Some(LiquidKind::Water));
+
+        // Waves repeat every chunk, so neighboring planes meet
+        let half = CHUNK_SIZE / 2.0;
+        let (west, _, _) = wave(-half, 7.0, 2.5);
+        let (east, _, _) = wave(half, 7.0, 2.5);
+        assert!((west - east).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_chunk_coords() {
+        assert_eq!(chunk_coord(0.0, 0.0), IVec2::ZERO);
+        assert_eq!(chunk_coord(CHUNK_SIZE - 0.01, -0.01), IVec2::new(0, -1));
+        assert_eq!(chunk_coord(-CHUNK_SIZE * 2.5, CHUNK_SIZE * 3.0), IVec2::new(-3, 3));
+    }
+
+    #[test]
+    fn test_surface_map_draws_the_sea_where_the_ground_dips_under_it() {
+        let sampler = TerrainSampler::new(77, PlanetType::Ocean);
+        let survey = terrain::HeightSurvey::new(&sampler, terrain::SURVEY_EXTENT, 9);
+        let band = (survey.min(), survey.max());
+        let water = SurfaceLiquid {
+            kind: LiquidKind::Water,
+            level: survey.percentile(0.5),
+        };
+        let (center, extent, size) = (Vec2::new(40.0, -25.0), 128.0, 32);
+        let image = surface_map_image(&sampler, band, Some(water), center, extent, size);
+        assert_eq!(image.size(), UVec2::splat(size));
+        assert_eq!(
+            image.data,
+            surface_map_image(&sampler, band, Some(water), center, extent, size).data
+        );
+
+        // Pixel (column, row) is the point at its center, north-west corner first
+        let sea = Srgba::from(LinearRgba::from_f32_array(LiquidKind::Water.map_color())).to_u8_array();
+        let (mut wet, mut dry) = (0, 0);
+        for row in 0..size {
+            for column in 0..size {
+                let x = center.x - extent / 2.0 + (column as f32 + 0.5) * extent / size as f32;
+                let z = center.y - extent / 2.0 + (row as f32 + 0.5) * extent / size as f32;
+                let at = ((row * size + column) * 4) as usize;
+                let underwater = sampler.height(x, z) < water.level;
+                assert_eq!(image.data[at..at + 4] == sea, underwater, "({x}, {z})");
+                if underwater {
+                    wet += 1;
+                } else {
+                    dry += 1;
+                }
+            }
+        }
+        assert!(wet > 0 && dry > 0, "{wet} wet, {dry} dry pixels");
+    }
+}
@@ -0,0 +1,689 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use matrix_core::NameKind;
+use matrix_core::bookmarks::BOOKMARK_SLOTS;
+use matrix_core::names::PLANET_ID_STRIDE;
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::replay::ReplayRecorder;
+use matrix_sim::search::PlanetQuery;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::Compression;
+use std::fmt;
+
+use super::bookmarks::BookmarkRecall;
+use super::camera::{self, FlyCamera};
+use super::census::{self, CensusPanel};
+use super::input_capture::CapturesPointer;
+use super::saving::{self, PendingSaves, SnapshotCapture};
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{PlanetSelection, SurfaceState, SystemFocus};
+
+/// Lines of history kept (and shown) in the console
+const MAX_HISTORY: usize = 12;
+
+/// Console open/closed state, the line being typed and past output
+#[derive(Resource, Default)]
+pub struct ConsoleState {
+    pub open: bool,
+    pub input: String,
+    pub history: Vec<ConsoleLine>,
+}
+
+impl ConsoleState {
+    pub(crate) fn push(&mut self, text: String, is_error: bool) {
+        self.history.push(ConsoleLine { text, is_error });
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
+
+/// One line of console history
+pub struct ConsoleLine {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// Why a console command failed
+#[derive(Debug, PartialEq)]
+pub enum ConsoleError {
+    /// No command registered under this name
+    UnknownCommand(String),
+    /// Wrong arguments; carries the command's usage string
+    Usage(&'static str),
+    /// Arguments were fine but the command could not do it
+    Failed(String),
+}
+
+impl fmt::Display for ConsoleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(name) => write!(f, "unknown command '{name}' (try 'help')"),
+            Self::Usage(usage) => write!(f, "usage: {usage}"),
+            Self::Failed(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// Everything a command may read or change
+pub struct ConsoleContext<'a> {
+    pub universe: &'a mut UniverseState,
+    pub lazy: &'a mut LazyUniverse,
+    pub transform: &'a mut Transform,
+    pub cam: &'a mut FlyCamera,
+    pub saves: &'a mut PendingSaves,
+    pub census: &'a mut CensusPanel,
+    pub recall: &'a mut BookmarkRecall,
+    pub feed: &'a EventFeed,
+    pub history: &'a UniverseHistory,
+    pub on_surface: bool,
+    /// Planet landed on, else the selected one
+    pub planet_id: Option<u64>,
+    /// Selected or focused star
+    pub star_id: Option<u64>,
+    /// Selected region, else the loaded one
+    pub region_id: Option<u64>,
+}
+
+/// Command handler: arguments after the name → output line
+pub type CommandFn = fn(&[&str], &mut ConsoleContext) -> Result<String, ConsoleError>;
+
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub run: CommandFn,
+}
+
+/// Registered console commands. Other plugins can add their own via `register`.
+#[derive(Resource)]
+pub struct CommandRegistry {
+    commands: Vec<ConsoleCommand>,
+}
+
+impl CommandRegistry {
+    pub fn empty() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Add a command (replaces one with the same name)
+    pub fn register(&mut self, name: &'static str, usage: &'static str, run: CommandFn) {
+        self.commands.retain(|c| c.name != name);
+        self.commands.push(ConsoleCommand { name, usage, run });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+
+    /// Parse `line` and look up its command: (command, arguments)
+    pub fn parse<'l>(
+        &self,
+        line: &'l str,
+    ) -> Result<Option<(&ConsoleCommand, Vec<&'l str>)>, ConsoleError> {
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(None);
+        };
+        let command = self
+            .get(name)
+            .ok_or_else(|| ConsoleError::UnknownCommand(name.to_string()))?;
+        Ok(Some((command, words.collect())))
+    }
+
+    /// One-line summary of every command's usage
+    fn help(&self) -> String {
+        let usages: Vec<&str> = self.commands.iter().map(|c| c.usage).collect();
+        format!("commands: {}", usages.join(" | "))
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("goto", "goto <x> <y> <z>", cmd_goto);
+        registry.register("region", "region <id>", cmd_region);
+        registry.register("seek", "seek life|tech|<criteria>", cmd_seek);
+        registry.register("timescale", "timescale <factor>", cmd_timescale);
+        registry.register("seed", "seed", cmd_seed);
+        registry.register("save", "save <name>", cmd_save);
+        registry.register("rename", "rename planet|star|region <name>", cmd_rename);
+        registry.register("census", "census [regions]", cmd_census);
+        registry.register("bm", "bm <slot>", cmd_bookmark);
+        registry.register("where", "where", cmd_where);
+        registry
+    }
+}
+
+fn parse_f32(arg: &str, usage: &'static str) -> Result<f32, ConsoleError> {
+    arg.parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or(ConsoleError::Usage(usage))
+}
+
+/// Teleports make no sense while walking on a planet
+fn require_space(ctx: &ConsoleContext) -> Result<(), ConsoleError> {
+    if ctx.on_surface {
+        return Err(ConsoleError::Failed("leave the planet surface first".to_string()));
+    }
+    Ok(())
+}
+
+fn teleport(ctx: &mut ConsoleContext, pos: Vec3) {
+    ctx.transform.translation = pos;
+    ctx.cam.tracking = None;
+}
+
+fn cmd_goto(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "goto <x> <y> <z>";
+    let [x, y, z] = args else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    let pos = Vec3::new(parse_f32(x, USAGE)?, parse_f32(y, USAGE)?, parse_f32(z, USAGE)?);
+    require_space(ctx)?;
+    teleport(ctx, pos);
+    Ok(format!("teleported to ({:.1}, {:.1}, {:.1})", pos.x, pos.y, pos.z))
+}
+
+fn cmd_region(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "region <id>";
+    let [id] = args else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    let id: u64 = id.parse().map_err(|_| ConsoleError::Usage(USAGE))?;
+    require_space(ctx)?;
+    let Some(idx) = ctx.lazy.regions.iter().position(|r| r.id == id) else {
+        return Err(ConsoleError::Failed(format!(
+            "no region #{id} ({} regions)",
+            ctx.lazy.regions.len()
+        )));
+    };
+    let r = &ctx.lazy.regions[idx];
+    let msg = format!("region #{id}: density {:.2}x, {} stars", r.density, r.star_count);
+    ctx.cam.region_nav_idx = idx;
+    camera::enter_region_view(ctx.transform, ctx.cam, r);
+    Ok(msg)
+}
+
+fn cmd_seek(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "seek life|tech|<class=G type=Ocean water=yes life=yes tech=no temp=240..400>";
+    let (found, what) = match args {
+        [] => return Err(ConsoleError::Usage(USAGE)),
+        ["life"] => (ctx.lazy.find_life(), args[0].to_string()),
+        ["tech"] => (ctx.lazy.find_technology(), args[0].to_string()),
+        criteria => {
+            let query = PlanetQuery::parse(criteria).map_err(ConsoleError::Failed)?;
+            let hits = ctx.lazy.query_planets(&query);
+            let what = match hits.first() {
+                Some(hit) => format!(
+                    "planet #{} of star #{} ({} matches)",
+                    hit.planet_id,
+                    hit.star_id,
+                    hits.len()
+                ),
+                None => "matching planet".to_string(),
+            };
+            (hits.first().map(|hit| hit.position), what)
+        }
+    };
+    require_space(ctx)?;
+    let Some(pos) = found else {
+        return Err(ConsoleError::Failed(format!(
+            "no {what} in the loaded region (explore more regions or speed up time)"
+        )));
+    };
+    teleport(
+        ctx,
+        Vec3::new(pos[0] as f32, pos[1] as f32 + 2.0, pos[2] as f32 + 10.0),
+    );
+    Ok(format!("found {what} at ({:.1}, {:.1}, {:.1})", pos[0], pos[1], pos[2]))
+}
+
+fn cmd_timescale(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "timescale <factor>";
+    let [factor] = args else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    let factor: f64 = factor
+        .parse()
+        .ok()
+        .filter(|f: &f64| f.is_finite() && *f > 0.0)
+        .ok_or(ConsoleError::Usage(USAGE))?;
+    ctx.universe.time_scale = factor;
+    Ok(format!("time scale {factor:e}x"))
+}
+
+fn cmd_seed(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    if !args.is_empty() {
+        return Err(ConsoleError::Usage("seed"));
+    }
+    Ok(format!(
+        "seed {} (cycle {} seed {})",
+        ctx.universe.config.seed,
+        ctx.universe.cycle,
+        ctx.universe.cycle_seed()
+    ))
+}
+
+fn cmd_save(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "save <name>";
+    let [name] = args else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    if !saving::valid_slot_name(name) {
+        return Err(ConsoleError::Failed(
+            "save names may only use letters, digits, '_' and '-'".to_string(),
+        ));
+    }
+    let path = saving::saves_dir().join(format!("{name}.bin"));
+    let capture = SnapshotCapture::capture(ctx.universe, ctx.lazy, ctx.feed, ctx.history);
+    ctx.saves.spawn(capture, path.clone(), Compression::Lz4);
+    Ok(format!("saving {}", path.display()))
+}
+
+fn cmd_rename(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "rename planet|star|region <name> (no name restores the generated one)";
+    let Some((kind, name)) = args.split_first() else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    let kind = NameKind::parse(kind).ok_or(ConsoleError::Usage(USAGE))?;
+    let name = name.join(" ");
+    let loaded_region = ctx.lazy.current_region_id.unwrap_or_default();
+    let (region_id, id, what) = match kind {
+        NameKind::Planet => (loaded_region, ctx.planet_id, "planet"),
+        NameKind::Star => (
+            loaded_region,
+            ctx.star_id.or(ctx.planet_id.map(|p| p / PLANET_ID_STRIDE)),
+            "star",
+        ),
+        NameKind::Region => (ctx.region_id.unwrap_or_default(), ctx.region_id, "region"),
+    };
+    let Some(id) = id else {
+        return Err(ConsoleError::Failed(format!("no {what} selected")));
+    };
+
+    let key = ctx.lazy.name_key(kind, region_id, id);
+    ctx.lazy.names.set(kind, key, &name);
+    let now = match kind {
+        NameKind::Planet => ctx.lazy.planet_name_of(region_id, id),
+        NameKind::Star => ctx.lazy.star_name_of(region_id, id),
+        NameKind::Region => ctx.lazy.region_name(id),
+    };
+    Ok(format!("{what} #{id} is now {now}"))
+}
+
+fn cmd_census(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "census [regions sampled, 1 up to the regions of the grid]";
+    let cells = ctx.lazy.config.region_layout.cell_count() as usize;
+    let regions = match args {
+        [] => census::CENSUS_REGIONS.min(cells),
+        [n] => n
+            .parse()
+            .ok()
+            .filter(|n| (1..=cells).contains(n))
+            .ok_or(ConsoleError::Usage(USAGE))?,
+        _ => return Err(ConsoleError::Usage(USAGE)),
+    };
+    ctx.census.request(ctx.lazy.config.clone(), ctx.universe.age, regions, true);
+    ctx.census.open = true;
+    Ok(format!(
+        "census of seed {} at {:.1} Gyr over {regions} regions started",
+        ctx.lazy.config.seed, ctx.universe.age
+    ))
+}
+
+fn cmd_bookmark(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    const USAGE: &str = "bm <slot 1-9>";
+    let [slot] = args else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+    let slot: usize = slot.parse().map_err(|_| ConsoleError::Usage(USAGE))?;
+    if !(1..=BOOKMARK_SLOTS).contains(&slot) {
+        return Err(ConsoleError::Usage(USAGE));
+    }
+    require_space(ctx)?;
+    let Some(bookmark) = ctx.lazy.bookmarks.get(slot) else {
+        return Err(ConsoleError::Failed(format!(
+            "bookmark {slot} is empty ([Ctrl+{slot}] stores the current view)"
+        )));
+    };
+    ctx.recall.requested = Some(slot);
+    Ok(format!("returning to bookmark {slot}: {}", bookmark.label))
+}
+
+fn cmd_where(args: &[&str], ctx: &mut ConsoleContext) -> Result<String, ConsoleError> {
+    if !args.is_empty() {
+        return Err(ConsoleError::Usage("where"));
+    }
+    let pos = ctx.transform.translation;
+    let region = match ctx.lazy.current_region_id {
+        Some(id) => id.to_string(),
+        None => "none".to_string(),
+    };
+    Ok(format!("pos {:.1} {:.1} {:.1} region {region}", pos.x, pos.y, pos.z))
+}
+
+// --- UI ---
+
+#[derive(Component)]
+pub struct ConsoleRoot;
+
+#[derive(Component)]
+pub struct ConsoleHistoryText;
+
+#[derive(Component)]
+pub struct ConsoleInputText;
+
+/// Spawn the (hidden) console panel along the bottom of the screen
+pub fn spawn_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+            ConsoleRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.4, 1.0, 0.6, 0.9)),
+                ConsoleHistoryText,
+            ));
+            panel.spawn((
+                Text::new("> "),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                ConsoleInputText,
+            ));
+        });
+}
+
+/// Backtick toggles the console. While it is open all keyboard input goes into the
+/// command line and is cleared before gameplay systems see it (no WASD flying while typing).
+/// Runs in `PreUpdate`, right after Bevy's input systems.
+pub fn console_input_system(
+    (mut console, registry): (ResMut<ConsoleState>, Res<CommandRegistry>),
+    (mut keyboard, settings, mut key_events): (ResMut<ButtonInput<KeyCode>>, Res<UserSettings>, EventReader<KeyboardInput>),
+    (mut universe, mut lazy): (ResMut<UniverseState>, ResMut<LazyUniverse>),
+    (surface, selection, focus, history): (
+        Res<SurfaceState>,
+        Res<PlanetSelection>,
+        Res<SystemFocus>,
+        Res<UniverseHistory>,
+    ),
+    (mut saves, mut census, mut recall, feed): (
+        ResMut<PendingSaves>,
+        ResMut<CensusPanel>,
+        ResMut<BookmarkRecall>,
+        Res<EventFeed>,
+    ),
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    if !console.open {
+        if action_just_pressed(&settings, &keyboard, Action::Console) {
+            console.open = true;
+            keyboard.reset_all();
+        }
+        key_events.clear();
+        return;
+    }
+
+    let mut submitted = None;
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => console.open = false,
+            Key::Enter => submitted = Some(std::mem::take(&mut console.input)),
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Space => console.input.push(' '),
+            Key::Character(chars) => {
+                if chars.as_str() == "`" {
+                    console.open = false;
+                } else {
+                    console.input.push_str(chars);
+                }
+            }
+            _ => {}
+        }
+    }
+    keyboard.reset_all();
+
+    let Some(line) = submitted else { return };
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    console.push(format!("> {line}"), false);
+
+    let Ok((mut transform, mut cam)) = cam_q.get_single_mut() else {
+        return;
+    };
+    let start_pos = transform.translation;
+    let result = if line == "help" {
+        Ok(registry.help())
+    } else {
+        match registry.parse(line) {
+            Ok(Some((command, args))) => {
+                let region_id = selection.selected_region.or(lazy.current_region_id);
+                let mut ctx = ConsoleContext {
+                    universe: &mut universe,
+                    lazy: &mut lazy,
+                    transform: &mut transform,
+                    cam: &mut cam,
+                    saves: &mut saves,
+                    census: &mut census,
+                    recall: &mut recall,
+                    feed: &feed,
+                    history: &history,
+                    on_surface: surface.active,
+                    planet_id: match &surface.planet {
+                        Some(planet) if surface.active => Some(planet.id),
+                        _ => selection.selected_planet.as_ref().map(|(p, _)| p.id),
+                    },
+                    star_id: selection.selected_star.or(focus.star_id),
+                    region_id,
+                };
+                (command.run)(&args, &mut ctx)
+            }
+            Ok(None) => return,
+            Err(e) => Err(e),
+        }
+    };
+
+    match result {
+        Ok(msg) => {
+            info!("Console: {msg}");
+            console.push(msg, false);
+        }
+        Err(e) => console.push(format!("error: {e}"), true),
+    }
+
+    if transform.translation != start_pos && let Some(mut recorder) = recorder {
+        camera::record_teleport(&mut recorder, universe.age, transform.translation);
+    }
+}
+
+/// Show/hide the panel and refresh its text
+pub fn update_console_ui(
+    console: Res<ConsoleState>,
+    mut root_q: Query<&mut Node, With<ConsoleRoot>>,
+    mut history_q: Query<&mut Text, (With<ConsoleHistoryText>, Without<ConsoleInputText>)>,
+    mut input_q: Query<&mut Text, (With<ConsoleInputText>, Without<ConsoleHistoryText>)>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    for mut node in &mut root_q {
+        node.display = if console.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    if let Ok(mut text) = history_q.get_single_mut() {
+        let lines: Vec<String> = console
+            .history
+            .iter()
+            .map(|l| {
+                if l.is_error {
+                    format!("! {}", l.text)
+                } else {
+                    l.text.clone()
+                }
+            })
+            .collect();
+        **text = lines.join("\n");
+    }
+    if let Ok(mut text) = input_q.get_single_mut() {
+        **text = format!("> {}_", console.input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_splits_command_and_arguments() {
+        let registry = CommandRegistry::default();
+        let (command, args) = registry.parse("  goto 150 -40   200 ").unwrap().unwrap();
+        assert_eq!(command.name, "goto");
+        assert_eq!(args, ["150", "-40", "200"]);
+
+        assert!(registry.parse("   ").unwrap().is_none());
+        assert_eq!(
+            registry.parse("warp 9").err(),
+            Some(ConsoleError::UnknownCommand("warp".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_existing_command() {
+        fn noop(_: &[&str], _: &mut ConsoleContext) -> Result<String, ConsoleError> {
+            Ok(String::new())
+        }
+        let mut registry = CommandRegistry::default();
+        let before = registry.commands.len();
+        registry.register("seed", "seed <new>", noop);
+        assert_eq!(registry.commands.len(), before);
+        assert_eq!(registry.get("seed").unwrap().usage, "seed <new>");
+    }
+
+    #[test]
+    fn test_rename_selected_planet_and_restore() {
+        let mut universe = UniverseState::new(matrix_core::SimConfig::default(), Vec::new());
+        let mut lazy = LazyUniverse::empty(matrix_core::SimConfig::default());
+        let mut transform = Transform::default();
+        let mut cam = FlyCamera::default();
+        let mut saves = PendingSaves::default();
+        let mut census = CensusPanel::default();
+        let mut recall = BookmarkRecall::default();
+        let feed = EventFeed::default();
+        let history = UniverseHistory::default();
+        let mut ctx = ConsoleContext {
+            universe: &mut universe,
+            lazy: &mut lazy,
+            transform: &mut transform,
+            cam: &mut cam,
+            saves: &mut saves,
+            census: &mut census,
+            recall: &mut recall,
+            feed: &feed,
+            history: &history,
+            on_surface: false,
+            planet_id: Some(73_021),
+            star_id: None,
+            region_id: None,
+        };
+        let generated = ctx.lazy.planet_name(73_021);
+        assert!(generated.starts_with(&ctx.lazy.star_name(73)));
+
+        let registry = CommandRegistry::default();
+        let (command, args) = registry.parse("rename planet New Aurelia").unwrap().unwrap();
+        assert_eq!((command.run)(&args, &mut ctx).unwrap(), "planet #73021 is now New Aurelia");
+        assert_eq!(ctx.lazy.planet_name(73_021), "New Aurelia");
+        // Its star keeps the generated name
+        assert!(!ctx.lazy.star_name(73).contains("Aurelia"));
+
+        let (command, args) = registry.parse("rename planet").unwrap().unwrap();
+        (command.run)(&args, &mut ctx).unwrap();
+        assert_eq!(ctx.lazy.planet_name(73_021), generated);
+
+        let (command, args) = registry.parse("rename region Home").unwrap().unwrap();
+        assert!(matches!((command.run)(&args, &mut ctx), Err(ConsoleError::Failed(_))));
+    }
+
+    #[test]
+    fn test_where_and_bookmark_recall() {
+        let mut universe = UniverseState::new(matrix_core::SimConfig::default(), Vec::new());
+        let mut lazy = LazyUniverse::empty(matrix_core::SimConfig::default());
+        lazy.current_region_id = Some(72);
+        let mut transform = Transform::from_xyz(123.4, -56.7, 890.1);
+        let mut cam = FlyCamera::default();
+        let mut saves = PendingSaves::default();
+        let mut census = CensusPanel::default();
+        let mut recall = BookmarkRecall::default();
+        let feed = EventFeed::default();
+        let history = UniverseHistory::default();
+        let mut ctx = ConsoleContext {
+            universe: &mut universe,
+            lazy: &mut lazy,
+            transform: &mut transform,
+            cam: &mut cam,
+            saves: &mut saves,
+            census: &mut census,
+            recall: &mut recall,
+            feed: &feed,
+            history: &history,
+            on_surface: false,
+            planet_id: None,
+            star_id: None,
+            region_id: None,
+        };
+        let registry = CommandRegistry::default();
+        let run = |line: &str, ctx: &mut ConsoleContext| {
+            let (command, args) = registry.parse(line).unwrap().unwrap();
+            (command.run)(&args, ctx)
+        };
+        assert_eq!(run("where", &mut ctx).unwrap(), "pos 123.4 -56.7 890.1 region 72");
+
+        assert!(matches!(run("bm 3", &mut ctx), Err(ConsoleError::Failed(_))));
+        assert!(matches!(run("bm 10", &mut ctx), Err(ConsoleError::Usage(_))));
+        let bookmark = crate::bookmarks::bookmark_here(
+            ctx.lazy,
+            &PlanetSelection::default(),
+            &SystemFocus::default(),
+            ctx.transform,
+            ctx.cam,
+        );
+        ctx.lazy.bookmarks.set(3, bookmark);
+        assert!(run("bm 3", &mut ctx).unwrap().starts_with("returning to bookmark 3"));
+        assert_eq!(ctx.recall.requested, Some(3));
+    }
+}
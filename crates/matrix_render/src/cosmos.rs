@@ -1,116 +1,351 @@
 use bevy::prelude::*;
-use matrix_core::SpectralClass;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use matrix_core::{Galaxy, GalaxyKind, Planet, SpectralClass, Star, StellarRemnant};
+use matrix_physics::cosmology;
 use matrix_sim::lazy_universe::LazyUniverse;
 use matrix_sim::universe::UniverseState;
 
+use super::budget::{Category, RenderBudget};
 use super::camera::{FlyCamera, ZoomLevel};
+use super::instances::{CosmosInstances, MoonVisual, PlanetBands, PlanetInstance, StarInstance};
+use super::particles;
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{PlanetSelection, SystemFocus};
 
-/// Marker for star visual entities
+/// Point light placed at one of the nearest stars
 #[derive(Component)]
-pub struct StarVisual {
-    pub star_id: u64,
+pub struct StarLight;
+
+/// Point cloud standing in for a whole galaxy (Galactic zoom, or galaxies other
+/// than the one the camera is in)
+#[derive(Component)]
+pub struct GalaxyVisual {
+    pub galaxy_id: u64,
+}
+
+/// Point cloud of a region around the loaded one: its stars once they are generated
+/// (`LazyUniverse::neighbor_stars`), else its aggregate mass points, dimmer
+#[derive(Component)]
+pub struct RegionCluster {
+    pub region_id: u64,
+}
+
+/// Expanding supernova shell, despawned after `SUPERNOVA_SHELL_SECS`
+#[derive(Component)]
+pub struct SupernovaShell {
+    /// Elapsed time (seconds) when the shell was spawned
+    pub spawned_at: f32,
+    pub material: Handle<StandardMaterial>,
 }
 
-/// Marker for planet visual entities
+/// Translucent habitable-zone annulus around a star ([V] overlay)
 #[derive(Component)]
-pub struct PlanetVisual {
-    pub planet_id: u64,
+pub struct HabitableZoneVisual {
     pub star_id: u64,
-    pub has_life: bool,
-    pub has_tech: bool,
-    pub base_scale: f32,
 }
 
-/// Marker for region overview cubes (visible at Cosmic/Galactic zoom)
+/// [V] overlay: orbit rings and habitable zones around the nearest stars
+#[derive(Resource, Default)]
+pub struct OrbitOverlay {
+    pub enabled: bool,
+    /// Stars the annuli are spawned for
+    stars: Vec<u64>,
+    /// `stars_generation` the annuli were spawned for
+    stars_generation: u32,
+}
+
+/// Marker for region overview sprites (visible at Cosmic/Galactic zoom)
 #[derive(Component)]
 pub struct RegionVisual {
     pub region_id: u64,
 }
 
+/// Life / civilization badge above a region sprite. Kept apart from the sprite so it
+/// can sit at a fixed offset on screen: `offset` is along the camera's right and up.
+#[derive(Component)]
+pub struct RegionBadge {
+    pub anchor: Vec3,
+    pub offset: Vec2,
+}
+
 /// Tracks when cosmos visuals were last rebuilt
 #[derive(Resource, Default)]
 pub struct CosmosRenderState {
     pub stars_generation: u32,
     /// Last camera position at which star sort was computed
     pub last_sort_pos: Vec3,
-    /// Whether region overview cubes are currently spawned
+    /// Whether region overview sprites are currently spawned
     pub regions_visible: bool,
+    /// Regions hosting a Type I+ civilization when the sprites were spawned
+    pub advanced_civ_regions: usize,
+    /// `region_flags_generation` the sprites' badges were spawned for
+    pub region_flags_generation: u32,
+    /// `RenderBudget::generation` the sprites were spawned for
+    pub region_budget_generation: u32,
+    /// `expanded_generation` the sprites were spawned for (which subdivided regions show
+    /// their children)
+    pub region_expanded_generation: u32,
+    /// `RenderBudget::generation` the star lights were spawned for
+    pub star_budget_generation: u32,
+    /// System focus the visuals were built for
+    pub focus_star: Option<u64>,
+    /// Galaxy drawn star by star (the others are point clouds); None at Galactic zoom
+    pub detail_galaxy: Option<u64>,
+    /// Whether individual stars were drawn at the last rebuild
+    pub stars_shown: bool,
+    /// `galactic_generation` and `neighbor_generation` the region clusters were built for
+    pub cluster_generations: (u32, u32),
 }
 
 /// Scale factor: 1 AU in render units
 pub(crate) const AU_RENDER_SCALE: f64 = 2.0;
-/// Max stars to render (limit entity count)
-const MAX_RENDER_STARS: usize = 80;
+/// Planet size boost for the focused system
+const FOCUS_PLANET_SCALE: f32 = 3.0;
+/// Brightness of the other stars while a system is focused
+const FOCUS_DIM: f32 = 0.15;
+/// Stars that get orbit rings and a habitable zone with the [V] overlay
+const OVERLAY_STARS: usize = 15;
+/// Nearest stars whose moons, planet rings and cloud bands, asteroid belts and comets are drawn
+pub(crate) const SMALL_BODY_STARS: usize = 5;
+/// Moon orbit radius in render units per AU, on top of the parent's render radius
+const MOON_ORBIT_SCALE: f64 = 60.0;
+/// Galaxy cloud points relative to particle-cloud points at the same distance
+const GALAXY_POINT_SCALE: f32 = 1.5;
+/// Mass points relative to galaxy-cloud points at the same distance
+const CLUSTER_POINT_SCALE: f32 = 1.5;
+/// How long a supernova shell stays visible (real seconds)
+const SUPERNOVA_SHELL_SECS: f32 = 4.0;
+/// Shell radius growth (render units per second)
+const SUPERNOVA_SHELL_SPEED: f32 = 6.0;
+/// Region sprite brightness per density level, dimmest first
+const REGION_BRIGHTNESS: [f32; 4] = [0.25, 0.45, 0.7, 1.0];
+/// Region sprite size (render units) for the fewest and most stars drawn apart
+const REGION_SPRITE_SIZE: (f32, f32) = (3.0, 18.0);
+/// log10 of the star counts mapped to the smallest and largest sprite
+const REGION_STAR_DECADES: (f32, f32) = (12.0, 16.0);
 
-/// Spawn cosmos render state resource
-pub fn init_cosmos_state(mut commands: Commands) {
-    commands.insert_resource(CosmosRenderState::default());
+/// Meshes and materials shared by every rebuild, created once at startup.
+/// Per-star and per-planet colors live in the instance meshes (`instances`); the only
+/// assets created per rebuild are galaxy cloud meshes and supernova shell materials,
+/// and those are removed when their entities are despawned.
+#[derive(Resource)]
+pub struct CosmosAssets {
+    /// Point-cloud material per `GalaxyKind`
+    pub galaxy_mats: [Handle<StandardMaterial>; 3],
+    /// Dim point-cloud material of the region clusters
+    pub cluster_mat: Handle<StandardMaterial>,
+    pub shell_mesh: Handle<Mesh>,
+    /// Soft-edged unit disc facing +Z, drawn for each region
+    pub region_mesh: Handle<Mesh>,
+    /// Region sprite materials: [plain, Type I+ civilization][density level]
+    pub region_mats: [[Handle<StandardMaterial>; 4]; 2],
+    /// Unit disc of the region badges, and its materials: life, civilization
+    pub badge_mesh: Handle<Mesh>,
+    pub badge_mats: [Handle<StandardMaterial>; 2],
+    /// Unit habitable-zone annulus (outer radius 1) in the XZ plane, and its material
+    pub habitable_mesh: Handle<Mesh>,
+    pub habitable_mat: Handle<StandardMaterial>,
 }
 
-/// Sync star/planet visuals with LazyUniverse loaded_stars
-pub fn update_cosmos_visuals(
+/// Spawn cosmos render state and shared asset resources
+pub fn init_cosmos_state(
     mut commands: Commands,
-    lazy: Res<LazyUniverse>,
-    mut state: ResMut<CosmosRenderState>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    star_query: Query<Entity, With<StarVisual>>,
-    planet_query: Query<Entity, With<PlanetVisual>>,
-    camera_query: Query<&Transform, With<FlyCamera>>,
 ) {
-    // Only rebuild when stars actually changed
-    if lazy.stars_generation == state.stars_generation {
+    let mut glow = |color: Color, emissive: f32| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            emissive: LinearRgba::from(color) * emissive,
+            unlit: true,
+            ..default()
+        })
+    };
+    let badge_mats = [
+        glow(Color::srgb(0.2, 1.0, 0.4), 12.0),
+        glow(Color::srgb(1.0, 0.8, 0.2), 12.0),
+    ];
+    // Additive, so overlapping sprites brighten instead of hiding each other
+    let region_mats = [Vec3::new(0.7, 0.8, 1.0), Vec3::new(0.8, 0.3, 1.0)].map(|tint| {
+        REGION_BRIGHTNESS.map(|brightness| {
+            let color = tint * brightness;
+            materials.add(StandardMaterial {
+                base_color: Color::srgb(color.x, color.y, color.z),
+                alpha_mode: AlphaMode::Add,
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                ..default()
+            })
+        })
+    });
+
+    // Cloud triangles face +Z: draw both sides
+    let mut cloud = |color: Color, emissive: f32| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            emissive: LinearRgba::from(color) * emissive,
+            unlit: true,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        })
+    };
+    let galaxy_mats = [GalaxyKind::Spiral, GalaxyKind::Elliptical, GalaxyKind::Irregular]
+        .map(|kind| cloud(galaxy_color(kind), 4.0));
+    let cluster_mat = cloud(Color::srgb(0.45, 0.5, 0.7), 1.0);
+
+    // The inner/outer ratio doesn't depend on luminosity: one mesh, scaled per star
+    let (inner, outer) = cosmology::habitable_zone_radii(1.0);
+    let habitable_mesh = Annulus::new((inner / outer) as f32, 1.0)
+        .mesh()
+        .resolution(64)
+        .build()
+        .rotated_by(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2));
+    let habitable_mat = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.2, 1.0, 0.4, 0.12),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.insert_resource(CosmosAssets {
+        galaxy_mats,
+        cluster_mat,
+        shell_mesh: meshes.add(Sphere::new(1.0).mesh().ico(2).unwrap()),
+        region_mesh: meshes.add(soft_disc_mesh()),
+        region_mats,
+        badge_mesh: meshes.add(Circle::new(1.0)),
+        badge_mats,
+        habitable_mesh: meshes.add(habitable_mesh),
+        habitable_mat,
+    });
+    commands.insert_resource(CosmosRenderState::default());
+}
+
+/// Sync star/planet visuals with LazyUniverse loaded_stars
+pub fn update_cosmos_visuals(
+    (mut commands, mut meshes, mut materials): (Commands, ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    (time, lazy, focus, mut selection): (
+        Res<Time>,
+        Res<LazyUniverse>,
+        Res<SystemFocus>,
+        ResMut<PlanetSelection>,
+    ),
+    (mut state, mut instances, cosmos_assets, budget): (
+        ResMut<CosmosRenderState>,
+        ResMut<CosmosInstances>,
+        Res<CosmosAssets>,
+        Res<RenderBudget>,
+    ),
+    light_query: Query<Entity, With<StarLight>>,
+    galaxy_query: Query<(Entity, &Mesh3d), With<GalaxyVisual>>,
+    shell_query: Query<(Entity, &SupernovaShell)>,
+    camera_query: Query<(&Transform, &FlyCamera)>,
+) {
+    let (cam_pos, zoom) = camera_query
+        .get_single()
+        .map(|(t, cam)| (t.translation, cam.zoom_level))
+        .unwrap_or((Vec3::ZERO, ZoomLevel::Cosmic));
+
+    // Which galaxy gets per-star rendering: the focused star's, else the nearest one.
+    // From Galactic zoom out every galaxy is a point cloud. Saves from before galaxies
+    // existed have none loaded: draw all stars as before.
+    let galaxies = &lazy.loaded_galaxies;
+    let far_zoom = matches!(zoom, ZoomLevel::Cosmic | ZoomLevel::Galactic);
+    let stars_shown = galaxies.is_empty() || !far_zoom;
+    let detail_galaxy = if galaxies.is_empty() || far_zoom {
+        None
+    } else {
+        focus
+            .star_id
+            .and_then(|id| lazy.galaxy_of_star(id))
+            .or_else(|| lazy.nearest_galaxy(cam_pos.as_dvec3().to_array()))
+            .map(|g| g.id)
+    };
+
+    // Only rebuild when stars actually changed (or the focus / detailed galaxy / budget did)
+    let stars_changed = lazy.stars_generation != state.stars_generation;
+    if !stars_changed
+        && focus.star_id == state.focus_star
+        && detail_galaxy == state.detail_galaxy
+        && stars_shown == state.stars_shown
+        && budget.generation() == state.star_budget_generation
+    {
         return;
     }
     state.stars_generation = lazy.stars_generation;
+    state.star_budget_generation = budget.generation();
+    state.focus_star = focus.star_id;
+    state.detail_galaxy = detail_galaxy;
+    state.stars_shown = stars_shown;
 
-    // Despawn old visuals
-    for entity in star_query.iter() {
+    // Drop old visuals (stars and planets are instances, only lights are entities)
+    instances.clear();
+    for entity in light_query.iter() {
         commands.entity(entity).despawn();
     }
-    for entity in planet_query.iter() {
+    for (entity, mesh) in galaxy_query.iter() {
+        meshes.remove(&mesh.0);
         commands.entity(entity).despawn();
     }
+    if stars_changed {
+        for (entity, shell) in shell_query.iter() {
+            materials.remove(&shell.material);
+            commands.entity(entity).despawn();
+        }
+        // Hovered and selected planets may be gone, or changed (evolved, remnants)
+        selection.refresh_stars(&lazy);
+    }
 
     if lazy.loaded_stars.is_empty() {
         return;
     }
 
-    let cam_pos = camera_query
-        .get_single()
-        .map(|t| t.translation)
-        .unwrap_or(Vec3::ZERO);
+    // Every galaxy but the detailed one as a point cloud of its stars
+    for galaxy in galaxies.iter().filter(|g| Some(g.id) != detail_galaxy) {
+        spawn_galaxy_cloud(&mut commands, &mut meshes, &cosmos_assets, &lazy.loaded_stars, galaxy, cam_pos);
+    }
+    if !stars_shown {
+        return;
+    }
+    let in_detail = |star: &Star| {
+        detail_galaxy.is_none_or(|id| galaxies.iter().any(|g| g.id == id && g.contains_star(star.id)))
+            || focus.star_id == Some(star.id)
+    };
 
-    // Sort stars by distance to camera, take nearest MAX_RENDER_STARS
-    let mut star_dists: Vec<(usize, f32)> = lazy.loaded_stars.iter().enumerate().map(|(i, s)| {
-        let sp = Vec3::new(s.position[0] as f32, s.position[1] as f32, s.position[2] as f32);
-        (i, cam_pos.distance_squared(sp))
-    }).collect();
+    // Sort stars by distance to camera (the focused star first) so the nearest get lights
+    let mut star_dists: Vec<(usize, f32)> = lazy
+        .loaded_stars
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| in_detail(s))
+        .map(|(i, s)| {
+            if focus.star_id == Some(s.id) {
+                return (i, -1.0);
+            }
+            let sp = Vec3::new(s.position[0] as f32, s.position[1] as f32, s.position[2] as f32);
+            (i, cam_pos.distance_squared(sp))
+        })
+        .collect();
     star_dists.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-    star_dists.truncate(MAX_RENDER_STARS);
-
-    // Shared meshes — lowest poly for performance
-    let star_mesh = meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap());
-    let planet_mesh = meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap());
-
-    // Shared materials per spectral class (avoid 1000 unique materials)
-    let mut star_mats: [Option<Handle<StandardMaterial>>; 7] = Default::default();
 
     for (idx, (star_idx, _dist)) in star_dists.iter().enumerate() {
         let star = &lazy.loaded_stars[*star_idx];
-        let color = spectral_color(&star.spectral_class);
-        let star_radius = (star.luminosity.log10() * 0.5 + 1.0).clamp(0.5, 5.0) as f32;
+        let (color, star_radius) = match star.remnant {
+            Some(remnant) => (remnant_color(remnant), 0.4),
+            None => (
+                spectral_color(&star.spectral_class),
+                (star.luminosity.log10() * 0.5 + 1.0).clamp(0.5, 5.0) as f32,
+            ),
+        };
 
-        // Reuse material per spectral class
-        let class_idx = star.spectral_class as usize;
-        let star_mat = star_mats[class_idx].get_or_insert_with(|| {
-            materials.add(StandardMaterial {
-                base_color: color,
-                emissive: LinearRgba::from(color) * 10.0,
-                unlit: true,
-                ..default()
-            })
-        }).clone();
+        // Dim stars outside the focused system
+        let dimmed = focus.star_id.is_some_and(|id| id != star.id);
+        let brightness = if dimmed { FOCUS_DIM } else { 1.0 };
 
         let star_pos = Vec3::new(
             star.position[0] as f32,
@@ -118,150 +353,554 @@ pub fn update_cosmos_visuals(
             star.position[2] as f32,
         );
 
-        commands.spawn((
-            Mesh3d(star_mesh.clone()),
-            MeshMaterial3d(star_mat),
-            Transform::from_translation(star_pos).with_scale(Vec3::splat(star_radius)),
-            StarVisual { star_id: star.id },
-        ));
+        instances.stars.push(StarInstance {
+            star_id: star.id,
+            position: star_pos,
+            radius: star_radius,
+            color: LinearRgba::from(color.with_luminance(color.luminance() * brightness)),
+        });
 
-        // Only 2 nearest stars get point lights (GPU perf)
-        if idx < 2 {
+        // Only the nearest stars get point lights (GPU perf), as many as the budget allows
+        if idx < budget.preset().star_lights() {
             commands.spawn((
                 PointLight {
-                    color: color,
+                    color,
                     intensity: (star.luminosity as f32).min(100.0) * 20_000.0,
                     range: 25.0,
                     shadows_enabled: false,
                     ..default()
                 },
                 Transform::from_translation(star_pos),
-                StarVisual { star_id: star.id },
+                StarLight,
             ));
         }
 
-        // Planets — only for nearest 15 stars (skip far ones)
-        if idx < 15 {
-            for planet in &star.planets {
-                let has_life = planet.life.is_some();
-                let has_tech = planet.life.as_ref().is_some_and(|b| b.has_technology);
-
-                let (planet_color, emissive_mult) = if has_tech {
-                    (Color::srgb(1.0, 0.85, 0.0), 20.0)
-                } else if has_life {
-                    (Color::srgb(0.1, 1.0, 0.3), 15.0)
-                } else {
-                    (planet_type_color(&planet.planet_type), 3.0)
-                };
+        for (planet_idx, planet) in star.planets.iter().enumerate() {
+            let has_life = planet.life.is_some();
+            let has_tech = planet.life.as_ref().is_some_and(|b| b.has_technology);
 
-                let size_mult = if has_tech { 4.0 } else if has_life { 2.5 } else { 1.0 };
-                let planet_radius = (planet.radius as f32 * 0.15).clamp(0.15, 1.5) * size_mult;
+            let planet_color = if has_tech {
+                Color::srgb(1.0, 0.85, 0.0)
+            } else if has_life {
+                Color::srgb(0.1, 1.0, 0.3)
+            } else {
+                planet_type_color(&planet.planet_type)
+            };
 
-                let planet_mat = materials.add(StandardMaterial {
-                    base_color: planet_color,
-                    emissive: LinearRgba::from(planet_color) * emissive_mult,
-                    unlit: true, // All unlit for performance
-                    ..default()
-                });
+            let size_mult = if has_tech { 4.0 } else if has_life { 2.5 } else { 1.0 };
+            let focus_mult = if focus.star_id == Some(star.id) { FOCUS_PLANET_SCALE } else { 1.0 };
+            let planet_radius =
+                (planet.radius as f32 * 0.15).clamp(0.15, 1.5) * size_mult * focus_mult;
 
-                let orbit_r = planet.orbital_radius * AU_RENDER_SCALE;
-                let px = star_pos.x + (orbit_r * planet.orbital_angle.cos()) as f32;
-                let py = star_pos.y;
-                let pz = star_pos.z + (orbit_r * planet.orbital_angle.sin()) as f32;
-
-                commands.spawn((
-                    Mesh3d(planet_mesh.clone()),
-                    MeshMaterial3d(planet_mat),
-                    Transform::from_xyz(px, py, pz).with_scale(Vec3::splat(planet_radius)),
-                    PlanetVisual {
-                        planet_id: planet.id,
-                        star_id: star.id,
-                        has_life,
-                        has_tech,
-                        base_scale: planet_radius,
-                    },
-                ));
+            let planet_pos = star_pos + orbit_offset(planet);
+            let planet_color = LinearRgba::from(planet_color);
+            let near = idx < SMALL_BODY_STARS;
+            let giant = near && planet.planet_type.is_giant() && !has_life;
+            instances.planets.push(PlanetInstance {
+                planet_id: planet.id,
+                star_id: star.id,
+                star_index: *star_idx,
+                planet_index: planet_idx,
+                position: planet_pos,
+                radius: planet_radius,
+                base_radius: planet_radius,
+                color: planet_color,
+                has_life,
+                has_tech,
+                moon: None,
+                bands: giant.then(|| PlanetBands::generate(planet_color, planet.id)),
+                rings: planet.rings.filter(|_| near),
+            });
+
+            if !near {
+                continue;
+            }
+            for (moon_idx, moon) in planet.moons.iter().enumerate() {
+                let moon_radius = (moon.radius as f32 * 0.15).clamp(0.06, 0.3) * focus_mult;
+                instances.planets.push(PlanetInstance {
+                    planet_id: moon.id,
+                    star_id: star.id,
+                    star_index: *star_idx,
+                    planet_index: moon_idx,
+                    position: planet_pos + moon_offset(moon, planet_radius),
+                    radius: moon_radius,
+                    base_radius: moon_radius,
+                    color: LinearRgba::from(planet_type_color(&moon.planet_type)),
+                    has_life: false,
+                    has_tech: false,
+                    moon: Some(MoonVisual {
+                        parent_index: planet_idx,
+                        parent_radius: planet_radius,
+                    }),
+                    bands: None,
+                    rings: None,
+                });
             }
         }
     }
 
+    // Recent supernovae: expanding shells around their remnants
+    if stars_changed && !lazy.recent_supernovae.is_empty() {
+        for sn in &lazy.recent_supernovae {
+            let material = materials.add(StandardMaterial {
+                base_color: Color::srgba(1.0, 0.6, 0.3, 0.6),
+                emissive: LinearRgba::rgb(8.0, 4.0, 1.5),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            });
+            commands.spawn((
+                Mesh3d(cosmos_assets.shell_mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(
+                    sn.position[0] as f32,
+                    sn.position[1] as f32,
+                    sn.position[2] as f32,
+                )
+                .with_scale(Vec3::splat(0.5)),
+                SupernovaShell {
+                    spawned_at: time.elapsed_secs(),
+                    material,
+                },
+            ));
+        }
+    }
+
     let life_count = lazy.loaded_stars.iter()
         .flat_map(|s| &s.planets)
         .filter(|p| p.life.is_some())
         .count();
 
     info!(
-        "Cosmos: rendered {}/{} stars, {} with life",
-        star_dists.len(), lazy.loaded_stars.len(), life_count
+        "Cosmos: rendered {}/{} stars, {} planets, {} with life",
+        instances.stars.len(), lazy.loaded_stars.len(), instances.planets.len(), life_count
+    );
+}
+
+/// Spawn the point cloud for one galaxy, built from its generated stars
+fn spawn_galaxy_cloud(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    cosmos_assets: &CosmosAssets,
+    stars: &[Star],
+    galaxy: &Galaxy,
+    cam_pos: Vec3,
+) {
+    let positions: Vec<[f32; 3]> = stars
+        .iter()
+        .filter(|s| galaxy.contains_star(s.id))
+        .map(|s| [s.position[0] as f32, s.position[1] as f32, s.position[2] as f32])
+        .collect();
+    if positions.is_empty() {
+        return;
+    }
+    let center = Vec3::new(
+        galaxy.position[0] as f32,
+        galaxy.position[1] as f32,
+        galaxy.position[2] as f32,
     );
+    let tri_size = particles::compute_tri_size(cam_pos, center) * GALAXY_POINT_SCALE;
+    commands.spawn((
+        Mesh3d(meshes.add(particles::build_triangle_cloud(&positions, tri_size))),
+        MeshMaterial3d(cosmos_assets.galaxy_mats[galaxy.kind as usize].clone()),
+        Transform::IDENTITY,
+        GalaxyVisual {
+            galaxy_id: galaxy.id,
+        },
+    ));
+}
+
+/// Draw the regions around the loaded one: the stars of the active neighbors as point
+/// clouds, and the mass points of the other regions at Galactic detail as dim ones.
+/// Rebuilt when either changes.
+pub fn update_region_clusters(
+    mut commands: Commands,
+    lazy: Res<LazyUniverse>,
+    mut state: ResMut<CosmosRenderState>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    cosmos_assets: Res<CosmosAssets>,
+    camera_query: Query<&Transform, With<FlyCamera>>,
+    cluster_query: Query<(Entity, &Mesh3d), With<RegionCluster>>,
+) {
+    let generations = (lazy.galactic_generation, lazy.neighbor_generation);
+    if generations == state.cluster_generations {
+        return;
+    }
+    state.cluster_generations = generations;
+    for (entity, mesh) in cluster_query.iter() {
+        meshes.remove(&mesh.0);
+        commands.entity(entity).despawn();
+    }
+
+    let cam_pos = camera_query.get_single().map_or(Vec3::ZERO, |t| t.translation);
+    let neighbors = lazy.neighbor_stars.iter().map(|n| {
+        let positions: Vec<[f64; 3]> = n.stars.iter().map(|s| s.position).collect();
+        (n.region_id, positions, &cosmos_assets.galaxy_mats[GalaxyKind::Spiral as usize], 1.0)
+    });
+    let galactic = lazy
+        .galactic_points
+        .iter()
+        .filter(|(id, _)| !lazy.neighbor_stars.iter().any(|n| n.region_id == *id))
+        .map(|(id, points)| {
+            let positions = points.iter().map(|p| p.position).collect();
+            (*id, positions, &cosmos_assets.cluster_mat, CLUSTER_POINT_SCALE)
+        });
+    for (region_id, positions, material, scale) in neighbors.chain(galactic) {
+        let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) else {
+            continue;
+        };
+        if positions.is_empty() {
+            continue;
+        }
+        let positions: Vec<[f32; 3]> = positions.iter().map(|p| p.map(|c| c as f32)).collect();
+        let center = Vec3::from_array(region.center.map(|c| c as f32));
+        let tri_size = particles::compute_tri_size(cam_pos, center) * GALAXY_POINT_SCALE * scale;
+        commands.spawn((
+            Mesh3d(meshes.add(particles::build_triangle_cloud(&positions, tri_size))),
+            MeshMaterial3d(material.clone()),
+            Transform::IDENTITY,
+            RegionCluster { region_id },
+        ));
+    }
 }
 
-/// Pulse life planets (stable oscillation using base_scale)
+/// Pulse life planets (stable oscillation around base_radius)
 /// Only animates planets near the camera
 pub fn animate_life_planets(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &PlanetVisual)>,
-    camera_query: Query<&Transform, (With<FlyCamera>, Without<PlanetVisual>)>,
+    budget: Res<RenderBudget>,
+    mut instances: ResMut<CosmosInstances>,
+    camera_query: Query<&Transform, With<FlyCamera>>,
 ) {
     let cam_pos = camera_query
         .get_single()
         .map(|t| t.translation)
         .unwrap_or(Vec3::ZERO);
     let t = time.elapsed_secs();
+    let max_dist_sq = budget.preset().life_animation_distance().powi(2);
 
-    for (mut transform, pv) in query.iter_mut() {
-        if !pv.has_life && !pv.has_tech {
+    for planet in instances.planets.iter_mut() {
+        if !planet.has_life && !planet.has_tech {
             continue;
         }
 
         // Skip animation for planets far from camera
-        let dist_sq = cam_pos.distance_squared(transform.translation);
-        if dist_sq > 10000.0 {
+        let dist_sq = cam_pos.distance_squared(planet.position);
+        if dist_sq > max_dist_sq {
             continue;
         }
 
-        if pv.has_tech {
-            let pulse = 1.0 + (t * 3.0).sin() * 0.2;
-            transform.scale = Vec3::splat(pv.base_scale * pulse);
+        let pulse = if planet.has_tech {
+            1.0 + (t * 3.0).sin() * 0.2
         } else {
-            let pulse = 1.0 + (t * 2.0).sin() * 0.1;
-            transform.scale = Vec3::splat(pv.base_scale * pulse);
+            1.0 + (t * 2.0).sin() * 0.1
+        };
+        planet.radius = planet.base_radius * pulse;
+    }
+}
+
+/// Offset of a planet from its star in render units (orbits lie in the XZ plane)
+pub(crate) fn orbit_offset(planet: &Planet) -> Vec3 {
+    let orbit_r = planet.orbital_radius * AU_RENDER_SCALE;
+    Vec3::new(
+        (orbit_r * planet.orbital_angle.cos()) as f32,
+        0.0,
+        (orbit_r * planet.orbital_angle.sin()) as f32,
+    )
+}
+
+/// Offset of a moon from its planet in render units. Real moon orbits would sit inside the
+/// enlarged planet spheres: they start at the parent's render radius instead.
+pub(crate) fn moon_offset(moon: &Planet, parent_radius: f32) -> Vec3 {
+    let orbit_r = parent_radius as f64 * 1.3 + moon.orbital_radius * MOON_ORBIT_SCALE;
+    Vec3::new(
+        (orbit_r * moon.orbital_angle.cos()) as f32,
+        0.0,
+        (orbit_r * moon.orbital_angle.sin()) as f32,
+    )
+}
+
+/// Move planet and moon instances along their orbits (angles are advanced by `matrix_sim::pipeline::orbit_tick`).
+/// At Stellar zoom, also draw faint orbit rings; in system focus, bright rings for the focused star only.
+/// The [V] overlay draws its own rings instead. Planets the player has landed on get a faint ring of their own.
+pub fn update_planet_orbits(
+    lazy: Res<LazyUniverse>,
+    focus: Res<SystemFocus>,
+    overlay: Res<OrbitOverlay>,
+    mut gizmos: Gizmos,
+    mut instances: ResMut<CosmosInstances>,
+    camera_query: Query<&FlyCamera>,
+) {
+    if instances.planets.is_empty() {
+        return;
+    }
+    let stellar_zoom = camera_query
+        .get_single()
+        .is_ok_and(|cam| cam.zoom_level == ZoomLevel::Stellar);
+    let ring_color = Color::srgba(0.5, 0.6, 0.8, 0.15);
+    let focus_ring_color = Color::srgba(0.6, 0.8, 1.0, 0.6);
+    let visited_ring_color = Color::srgba(0.9, 0.8, 0.5, 0.35);
+
+    for instance in instances.planets.iter_mut() {
+        // Indices are from the last rebuild; skip if the stars were swapped since
+        let Some(star) = lazy.loaded_stars.get(instance.star_index).filter(|s| s.id == instance.star_id)
+        else {
+            continue;
+        };
+        let star_pos = Vec3::new(
+            star.position[0] as f32,
+            star.position[1] as f32,
+            star.position[2] as f32,
+        );
+        // Moons circle their planet, planets their star
+        let center = match &instance.moon {
+            Some(moon) => {
+                let Some(parent) = star.planets.get(moon.parent_index) else {
+                    continue;
+                };
+                let Some(body) = parent.moons.get(instance.planet_index).filter(|m| m.id == instance.planet_id)
+                else {
+                    continue;
+                };
+                let parent_pos = star_pos + orbit_offset(parent);
+                instance.position = parent_pos + moon_offset(body, moon.parent_radius);
+                parent_pos
+            }
+            None => {
+                let Some(planet) = star.planets.get(instance.planet_index).filter(|p| p.id == instance.planet_id)
+                else {
+                    continue;
+                };
+                instance.position = star_pos + orbit_offset(planet);
+                star_pos
+            }
+        };
+        if lazy.surface_memory.is_visited(instance.planet_id) {
+            gizmos.circle(
+                Isometry3d::new(instance.position, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                instance.base_radius * 1.8,
+                visited_ring_color,
+            );
+        }
+
+        let color = match focus.star_id {
+            _ if overlay.enabled => None,
+            Some(id) if id == instance.star_id => Some(focus_ring_color),
+            Some(_) => None,
+            None => stellar_zoom.then_some(ring_color),
+        };
+        if let Some(color) = color {
+            gizmos.circle(
+                Isometry3d::new(center, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                instance.position.distance(center),
+                color,
+            );
+        }
+    }
+}
+
+/// [V] toggles the orbit overlay. At Stellar/Planetary zoom it draws every planet's orbit
+/// around the nearest stars (brighter for planets with life) and a translucent annulus over
+/// each star's habitable zone (240–400 K). Annuli are respawned when the nearest-star set
+/// or `stars_generation` changes.
+pub fn update_orbit_overlay(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    (settings, lazy, cosmos_assets): (Res<UserSettings>, Res<LazyUniverse>, Res<CosmosAssets>),
+    mut overlay: ResMut<OrbitOverlay>,
+    mut gizmos: Gizmos,
+    zone_q: Query<Entity, With<HabitableZoneVisual>>,
+    camera_query: Query<(&Transform, &FlyCamera)>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::Orbits) {
+        overlay.enabled = !overlay.enabled;
+        info!("Orbit overlay: {}", if overlay.enabled { "on" } else { "off" });
+    }
+    let Ok((cam_transform, cam)) = camera_query.get_single() else {
+        return;
+    };
+    let active = overlay.enabled && matches!(cam.zoom_level, ZoomLevel::Stellar | ZoomLevel::Planetary);
+
+    let stars = if active {
+        nearest_stars(&lazy.loaded_stars, cam_transform.translation, OVERLAY_STARS)
+    } else {
+        Vec::new()
+    };
+    let star_ids: Vec<u64> = stars.iter().map(|s| s.id).collect();
+
+    if star_ids != overlay.stars || lazy.stars_generation != overlay.stars_generation {
+        for entity in zone_q.iter() {
+            commands.entity(entity).despawn();
+        }
+        for star in &stars {
+            let (_, outer) = cosmology::habitable_zone_radii(star.luminosity);
+            commands.spawn((
+                Mesh3d(cosmos_assets.habitable_mesh.clone()),
+                MeshMaterial3d(cosmos_assets.habitable_mat.clone()),
+                Transform::from_translation(star_render_pos(star))
+                    .with_scale(Vec3::splat((outer * AU_RENDER_SCALE) as f32)),
+                HabitableZoneVisual { star_id: star.id },
+            ));
+        }
+        overlay.stars = star_ids;
+        overlay.stars_generation = lazy.stars_generation;
+    }
+
+    let ring_color = Color::srgba(0.6, 0.7, 0.9, 0.35);
+    let life_ring_color = Color::srgba(0.3, 1.0, 0.5, 0.9);
+    let ring_rotation = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    for star in &stars {
+        let star_pos = star_render_pos(star);
+        for planet in &star.planets {
+            let color = if planet.life.is_some() { life_ring_color } else { ring_color };
+            gizmos.circle(
+                Isometry3d::new(star_pos, ring_rotation),
+                (planet.orbital_radius * AU_RENDER_SCALE) as f32,
+                color,
+            );
         }
     }
 }
 
-/// Show/hide region overview cubes based on zoom level.
-/// At Cosmic/Galactic zoom: spawn cubes at each region center (sized by density, colored by properties).
+/// The `n` stars closest to `pos`, nearest first
+pub(crate) fn nearest_stars(stars: &[Star], pos: Vec3, n: usize) -> Vec<&Star> {
+    let mut by_dist: Vec<(f32, &Star)> = stars
+        .iter()
+        .map(|s| (pos.distance_squared(star_render_pos(s)), s))
+        .collect();
+    by_dist.sort_by(|a, b| a.0.total_cmp(&b.0));
+    by_dist.into_iter().take(n).map(|(_, s)| s).collect()
+}
+
+pub(crate) fn star_render_pos(star: &Star) -> Vec3 {
+    Vec3::new(
+        star.position[0] as f32,
+        star.position[1] as f32,
+        star.position[2] as f32,
+    )
+}
+
+/// Grow and fade supernova shells, despawning them once they've faded out
+pub fn animate_supernova_shells(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(Entity, &mut Transform, &SupernovaShell)>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, mut transform, shell) in query.iter_mut() {
+        let t = now - shell.spawned_at;
+        if t > SUPERNOVA_SHELL_SECS {
+            materials.remove(&shell.material);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.scale = Vec3::splat(0.5 + t * SUPERNOVA_SHELL_SPEED);
+
+        let fade = 1.0 - t / SUPERNOVA_SHELL_SECS;
+        if let Some(mat) = materials.get_mut(&shell.material) {
+            mat.base_color.set_alpha(0.6 * fade);
+            mat.emissive = LinearRgba::rgb(8.0, 4.0, 1.5) * fade;
+        }
+    }
+}
+
+/// Unit disc facing +Z whose vertex colors fade from a bright center to nothing at the rim
+pub(crate) fn soft_disc_mesh() -> Mesh {
+    const SEGMENTS: u32 = 32;
+    // (radius, brightness) of the center and the two rings
+    const RINGS: [(f32, f32); 2] = [(0.4, 0.55), (1.0, 0.0)];
+    let mut positions = vec![[0.0f32; 3]];
+    let mut colors = vec![[1.0f32; 4]];
+    for (radius, brightness) in RINGS {
+        for i in 0..SEGMENTS {
+            let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+            positions.push([angle.cos() * radius, angle.sin() * radius, 0.0]);
+            colors.push([brightness; 4]);
+        }
+    }
+    let ring = |r: u32, i: u32| 1 + r * SEGMENTS + i % SEGMENTS;
+    let mut indices = Vec::new();
+    for i in 0..SEGMENTS {
+        indices.extend([0, ring(0, i), ring(0, i + 1)]);
+        indices.extend([ring(0, i), ring(1, i), ring(1, i + 1)]);
+        indices.extend([ring(0, i), ring(1, i + 1), ring(0, i + 1)]);
+    }
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Region sprite size for `star_count` stars: grows with the number of decades
+pub(crate) fn region_sprite_size(star_count: u64) -> f32 {
+    let (min_decade, max_decade) = REGION_STAR_DECADES;
+    let (min_size, max_size) = REGION_SPRITE_SIZE;
+    let t = ((star_count.max(1) as f32).log10() - min_decade) / (max_decade - min_decade);
+    min_size + (max_size - min_size) * t.clamp(0.0, 1.0)
+}
+
+/// Index into `REGION_BRIGHTNESS` for a region of `density` (relative to average)
+fn region_brightness_level(density: f64) -> usize {
+    // Densities are log-normal around 1: spread ln(density) over [-1, 1]
+    let t = ((density.max(1e-6).ln() + 1.0) * 0.5).clamp(0.0, 1.0);
+    (t * (REGION_BRIGHTNESS.len() - 1) as f64).round() as usize
+}
+
+/// Show/hide region overview sprites based on zoom level.
+/// At Cosmic/Galactic zoom: spawn a soft sprite at each region center (sized by star count,
+/// brightness by density) with a badge above it for life or a civilization.
 /// At Stellar and closer: despawn them (individual stars take over).
 pub fn update_region_visuals(
     mut commands: Commands,
-    lazy: Res<LazyUniverse>,
-    universe: Res<UniverseState>,
-    mut state: ResMut<CosmosRenderState>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    (lazy, universe): (Res<LazyUniverse>, Res<UniverseState>),
+    (mut state, cosmos_assets): (ResMut<CosmosRenderState>, Res<CosmosAssets>),
     camera_query: Query<&FlyCamera>,
     region_q: Query<Entity, With<RegionVisual>>,
+    badge_q: Query<Entity, With<RegionBadge>>,
+    (mut selection, mut budget): (ResMut<PlanetSelection>, ResMut<RenderBudget>),
 ) {
     let Ok(cam) = camera_query.get_single() else {
         return;
     };
 
-    // Don't show region cubes during early universe — no stars yet, only Big Bang particles
+    // Don't show region sprites during early universe — no stars yet, only Big Bang particles
     let should_show = matches!(cam.zoom_level, ZoomLevel::Cosmic | ZoomLevel::Galactic)
         && universe.age >= 1.0;
 
-    if should_show == state.regions_visible {
+    // Also rebuild when a region gains or loses a Type I+ civilization (changes its tint)
+    let advanced_civ_regions = lazy
+        .regions
+        .iter()
+        .filter(|r| lazy.has_advanced_civilization(r.id))
+        .count();
+    // ...or when life or a civilization is found in one (changes its badge), the render
+    // budget changes, or the camera nears or leaves a subdivided region
+    if should_show == state.regions_visible
+        && (!should_show
+            || (advanced_civ_regions == state.advanced_civ_regions
+                && lazy.region_flags_generation == state.region_flags_generation
+                && budget.generation() == state.region_budget_generation
+                && lazy.expanded_generation == state.region_expanded_generation))
+    {
         return;
     }
     state.regions_visible = should_show;
+    state.advanced_civ_regions = advanced_civ_regions;
+    state.region_flags_generation = lazy.region_flags_generation;
+    state.region_budget_generation = budget.generation();
+    state.region_expanded_generation = lazy.expanded_generation;
 
     // Despawn old region visuals
-    for entity in region_q.iter() {
+    for entity in region_q.iter().chain(badge_q.iter()) {
         commands.entity(entity).despawn();
     }
+    selection.forget_region_sprites();
 
     if !should_show {
+        budget.release(Category::RegionSprites);
         info!(
             "Cosmos: hiding region visuals (zoomed to {})",
             cam.zoom_level.name()
@@ -269,74 +908,272 @@ pub fn update_region_visuals(
         return;
     }
 
-    // Spawn region cubes — shared materials by category for batching
-    let cube_mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
-
-    let life_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.2, 1.0, 0.4),
-        emissive: LinearRgba::from(Color::srgb(0.2, 1.0, 0.4)) * 12.0,
-        unlit: true,
-        ..default()
-    });
-    let high_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(1.0, 0.6, 0.3),
-        emissive: LinearRgba::from(Color::srgb(1.0, 0.6, 0.3)) * 8.0,
-        unlit: true,
-        ..default()
-    });
-    let mid_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.5, 0.6, 0.9),
-        emissive: LinearRgba::from(Color::srgb(0.5, 0.6, 0.9)) * 6.0,
-        unlit: true,
-        ..default()
-    });
-    let low_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.3, 0.3, 0.5),
-        emissive: LinearRgba::from(Color::srgb(0.3, 0.3, 0.5)) * 4.0,
-        unlit: true,
-        ..default()
-    });
+    // Over budget only the regions with the most stars get a sprite
+    let mut shown = lazy.overview_regions();
+    let overview = shown.len();
+    let granted = budget.request(Category::RegionSprites, overview);
+    if granted < shown.len() {
+        shown.sort_by_key(|r| std::cmp::Reverse(r.star_count));
+        shown.truncate(granted);
+    }
 
-    for region in &lazy.regions {
+    // Spawn region sprites — shared materials by category for batching; they're turned
+    // to face the camera by `face_region_sprites`
+    for &region in &shown {
         let pos = Vec3::new(
             region.center[0] as f32,
             region.center[1] as f32,
             region.center[2] as f32,
         );
 
-        let size = (region.density as f32 * 5.0).clamp(2.0, 20.0);
-
-        let mat = if region.has_life {
-            life_mat.clone()
-        } else if region.density > 2.0 {
-            high_mat.clone()
-        } else if region.density > 1.0 {
-            mid_mat.clone()
-        } else {
-            low_mat.clone()
-        };
+        let size = region_sprite_size(region.star_count);
+        let tint = usize::from(lazy.has_advanced_civilization(region.id));
+        let mat = &cosmos_assets.region_mats[tint][region_brightness_level(region.density)];
 
         commands.spawn((
-            Mesh3d(cube_mesh.clone()),
-            MeshMaterial3d(mat),
+            Mesh3d(cosmos_assets.region_mesh.clone()),
+            MeshMaterial3d(mat.clone()),
             Transform::from_translation(pos).with_scale(Vec3::splat(size)),
             RegionVisual { region_id: region.id },
         ));
+
+        let badge = if region.has_civilization {
+            Some(&cosmos_assets.badge_mats[1])
+        } else if region.has_life {
+            Some(&cosmos_assets.badge_mats[0])
+        } else {
+            None
+        };
+        if let Some(badge_mat) = badge {
+            let badge_size = size * 0.15;
+            commands.spawn((
+                Mesh3d(cosmos_assets.badge_mesh.clone()),
+                MeshMaterial3d(badge_mat.clone()),
+                Transform::from_translation(pos).with_scale(Vec3::splat(badge_size)),
+                RegionBadge {
+                    anchor: pos,
+                    offset: Vec2::new(0.0, size * 0.6 + badge_size),
+                },
+            ));
+        }
     }
 
     info!(
-        "Cosmos: spawned {} region visuals at {} zoom",
-        lazy.regions.len(),
+        "Cosmos: spawned {} of {} region visuals at {} zoom",
+        shown.len(),
+        overview,
         cam.zoom_level.name()
     );
 }
 
+/// The camera, kept apart from the region sprites and badges it turns
+type SpriteCamera = (With<FlyCamera>, Without<RegionVisual>, Without<RegionBadge>);
+
+/// Turn the region sprites and their badges to face the camera
+pub fn face_region_sprites(
+    camera_q: Query<&Transform, SpriteCamera>,
+    mut sprite_q: Query<&mut Transform, (With<RegionVisual>, Without<RegionBadge>)>,
+    mut badge_q: Query<(&RegionBadge, &mut Transform), Without<RegionVisual>>,
+) {
+    let Ok(cam) = camera_q.get_single() else {
+        return;
+    };
+    for mut transform in &mut sprite_q {
+        transform.rotation = cam.rotation;
+    }
+    let (right, up) = (cam.right(), cam.up());
+    for (badge, mut transform) in &mut badge_q {
+        transform.rotation = cam.rotation;
+        transform.translation = badge.anchor + right * badge.offset.x + up * badge.offset.y;
+    }
+}
+
 fn spectral_color(class: &SpectralClass) -> Color {
     let c = class.color();
     Color::srgba(c[0], c[1], c[2], c[3])
 }
 
+fn galaxy_color(kind: GalaxyKind) -> Color {
+    match kind {
+        GalaxyKind::Spiral => Color::srgb(0.75, 0.85, 1.0),
+        GalaxyKind::Elliptical => Color::srgb(1.0, 0.85, 0.6),
+        GalaxyKind::Irregular => Color::srgb(0.6, 0.75, 1.0),
+    }
+}
+
+fn remnant_color(remnant: StellarRemnant) -> Color {
+    match remnant {
+        StellarRemnant::WhiteDwarf => Color::srgb(0.9, 0.95, 1.0),
+        StellarRemnant::NeutronStar => Color::srgb(0.6, 0.8, 1.0),
+        StellarRemnant::BlackHole => Color::srgb(0.05, 0.02, 0.1),
+    }
+}
+
 fn planet_type_color(pt: &matrix_core::PlanetType) -> Color {
     let c = pt.color();
     Color::srgba(c[0], c[1], c[2], c[3])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances;
+    use matrix_core::SimConfig;
+    use matrix_sim::lazy_universe::generate_region_detail;
+
+    #[test]
+    fn test_asset_count_bounded_across_region_changes() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_resource::<SystemFocus>()
+            .init_resource::<PlanetSelection>()
+            .init_resource::<RenderBudget>()
+            .insert_resource(LazyUniverse::new(SimConfig::default(), 13.0))
+            .add_systems(Startup, (init_cosmos_state, instances::init_instances))
+            .add_systems(Update, update_cosmos_visuals);
+        app.world_mut().spawn((
+            Transform::default(),
+            FlyCamera {
+                zoom_level: ZoomLevel::Stellar,
+                ..default()
+            },
+        ));
+
+        // A few regions at a late age (remnants + supernova shells), cycled through
+        let details: Vec<_> = {
+            let lazy = app.world().resource::<LazyUniverse>();
+            lazy.regions
+                .iter()
+                .take(3)
+                .map(|r| generate_region_detail(r, 13.0))
+                .collect()
+        };
+
+        let mut counts = Vec::new();
+        for change in 0..50 {
+            let detail = &details[change % details.len()];
+            {
+                let mut lazy = app.world_mut().resource_mut::<LazyUniverse>();
+                lazy.loaded_stars = detail.stars.clone().into();
+                lazy.loaded_galaxies = detail.galaxies.clone();
+                lazy.recent_supernovae = detail.supernovae.clone();
+                lazy.current_region_id = Some(detail.region_id);
+                lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
+            }
+            app.update();
+            counts.push((
+                app.world().resource::<Assets<StandardMaterial>>().len(),
+                app.world().resource::<Assets<Mesh>>().len(),
+            ));
+        }
+
+        // Same region loaded again: same asset counts, nothing piled up
+        let n = details.len();
+        assert_eq!(counts[49 - n], counts[49]);
+        let max_shells = details.iter().map(|d| d.supernovae.len()).max().unwrap();
+        let (materials, _) = counts[49];
+        // galaxy, cluster, region, badge and habitable-zone materials, the shared instance
+        // material, the ring material, one per shell
+        assert!(materials <= 3 + 1 + 8 + 2 + 1 + 1 + 1 + max_shells, "{materials} materials");
+        assert!(!app.world().resource::<CosmosInstances>().stars.is_empty());
+
+        // Region sprites grow with the number of stars, within bounds
+        let sizes = [0, 1e12 as u64, 1e14 as u64, 1e16 as u64, u64::MAX].map(region_sprite_size);
+        assert!(sizes.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!((sizes[0], sizes[4]), REGION_SPRITE_SIZE);
+        assert!(sizes[2] > sizes[1] && sizes[3] > sizes[2]);
+        assert_eq!(region_brightness_level(1.0), 2);
+        assert_eq!(region_brightness_level(0.3), 0);
+    }
+
+    #[test]
+    fn test_selection_survives_rebuilds() {
+        let mut universe = UniverseState::empty(SimConfig::default());
+        universe.age = 13.0;
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_resource::<SystemFocus>()
+            .init_resource::<PlanetSelection>()
+            .init_resource::<RenderBudget>()
+            .insert_resource(LazyUniverse::new(SimConfig::default(), 13.0))
+            .insert_resource(universe)
+            .add_systems(Startup, (init_cosmos_state, instances::init_instances))
+            .add_systems(Update, (update_cosmos_visuals, update_region_visuals));
+        let camera = app
+            .world_mut()
+            .spawn((
+                Transform::default(),
+                FlyCamera {
+                    zoom_level: ZoomLevel::Stellar,
+                    ..default()
+                },
+            ))
+            .id();
+        let details: Vec<_> = {
+            let lazy = app.world().resource::<LazyUniverse>();
+            lazy.regions
+                .iter()
+                .take(2)
+                .map(|r| generate_region_detail(r, 13.0))
+                .collect()
+        };
+        let load = |app: &mut App, index: usize| {
+            let mut lazy = app.world_mut().resource_mut::<LazyUniverse>();
+            lazy.loaded_stars = details[index].stars.clone().into();
+            lazy.current_region_id = Some(details[index].region_id);
+            lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
+            app.update();
+        };
+        load(&mut app, 0);
+
+        // Hover and select a planet, holding data from before it changed
+        let star = details[0].stars.iter().find(|s| !s.planets.is_empty()).unwrap();
+        let planet = &star.planets[0];
+        {
+            let mut selection = app.world_mut().resource_mut::<PlanetSelection>();
+            selection.hovered = Some((star.id, planet.id));
+            let stale = Planet {
+                surface_temp: planet.surface_temp + 100.0,
+                ..planet.clone()
+            };
+            selection.selected_planet = Some((stale, star.spectral_class));
+            selection.selected_star = Some(star.id);
+        }
+
+        // Rebuilt with the same stars: the selection holds on, with the current data
+        load(&mut app, 0);
+        let selection = app.world().resource::<PlanetSelection>();
+        assert_eq!(selection.hovered, Some((star.id, planet.id)));
+        let (selected, _) = selection.selected_planet.as_ref().unwrap();
+        assert_eq!(selected.surface_temp, planet.surface_temp);
+        assert_eq!(selection.selected_star, Some(star.id));
+
+        // Another region's stars (ids are unique across regions): nothing is left pointing
+        // at the old ones
+        load(&mut app, 1);
+        let selection = app.world().resource::<PlanetSelection>();
+        assert!(selection.hovered.is_none() && selection.selected_planet.is_none());
+        assert!(selection.selected_star.is_none());
+
+        // Region sprites rebuilt while one is highlighted: the highlight goes with them
+        app.world_mut().get_mut::<FlyCamera>(camera).unwrap().zoom_level = ZoomLevel::Cosmic;
+        app.update();
+        let mut sprites = app.world_mut().query_filtered::<Entity, With<RegionVisual>>();
+        let sprite = sprites.iter(app.world()).next().unwrap();
+        {
+            let mut selection = app.world_mut().resource_mut::<PlanetSelection>();
+            selection.hovered_region = Some(sprite);
+            selection.original_materials.push((0, Handle::default()));
+        }
+        let mut lazy = app.world_mut().resource_mut::<LazyUniverse>();
+        lazy.region_flags_generation = lazy.region_flags_generation.wrapping_add(1);
+        app.update();
+        let selection = app.world().resource::<PlanetSelection>();
+        assert!(selection.hovered_region.is_none() && selection.original_materials.is_empty());
+        assert!(!app.world().entities().contains(sprite));
+    }
+}
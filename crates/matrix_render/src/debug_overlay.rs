@@ -0,0 +1,361 @@
+use std::collections::VecDeque;
+use std::mem::size_of;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use matrix_core::GpuParticle;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::timings::SimTimings;
+use matrix_sim::universe::UniverseState;
+
+use super::budget::RenderBudget;
+use super::instances::CosmosInstances;
+use super::particles::ParticleCloudState;
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{Creature, HerdMarker, SurfaceDetail, SurfaceState};
+
+/// Frames shown in the frame-time graph
+const GRAPH_FRAMES: usize = 90;
+/// Frame time that fills a bar (ms); slower frames are clipped
+const GRAPH_MAX_MS: f32 = 50.0;
+const GRAPH_HEIGHT: f32 = 48.0;
+const OVERLAY_WIDTH: f32 = 360.0;
+
+/// Marker for the overlay root node
+#[derive(Component)]
+pub struct DebugOverlayRoot;
+
+/// Marker for the counters text
+#[derive(Component)]
+pub struct DebugOverlayText;
+
+/// One bar of the frame-time graph; slot 0 is the oldest frame
+#[derive(Component)]
+pub struct FrameBar(pub usize);
+
+/// Real frame times (ms) while the overlay is open, oldest first
+#[derive(Default)]
+pub struct FrameHistory {
+    frames: VecDeque<f32>,
+}
+
+impl FrameHistory {
+    fn push(&mut self, frame_ms: f32) {
+        if self.frames.len() == GRAPH_FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_ms);
+    }
+
+    /// Average frames per second over the graph window
+    fn fps(&self) -> f32 {
+        let total: f32 = self.frames.iter().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.frames.len() as f32 * 1000.0 / total
+    }
+
+    fn worst_ms(&self) -> f32 {
+        self.frames.iter().copied().fold(0.0, f32::max)
+    }
+}
+
+/// Spawn the (hidden) debug overlay, top center
+pub fn spawn_debug_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-OVERLAY_WIDTH / 2.0)),
+                width: Val::Px(OVERLAY_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+            DebugOverlayRoot,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+                DebugOverlayText,
+            ));
+            panel
+                .spawn(Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(GRAPH_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    column_gap: Val::Px(1.0),
+                    ..default()
+                })
+                .with_children(|graph| {
+                    for slot in 0..GRAPH_FRAMES {
+                        graph.spawn((
+                            Node {
+                                flex_grow: 1.0,
+                                height: Val::Px(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::NONE),
+                            FrameBar(slot),
+                        ));
+                    }
+                });
+        });
+}
+
+/// [F3] show or hide the overlay. Timing capture is only enabled while it is shown.
+pub fn debug_overlay_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut timings: ResMut<SimTimings>,
+    mut root_q: Query<&mut Node, With<DebugOverlayRoot>>,
+) {
+    if !action_just_pressed(&settings, &keyboard, Action::DebugOverlay) {
+        return;
+    }
+    timings.enabled = !timings.enabled;
+    timings.clear();
+    for mut node in &mut root_q {
+        node.display = if timings.enabled {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Forward region load times from `LazyUniverse` into `SimTimings`. `LazyUniverse` is
+/// replaced on cycle restarts and loads, so its capture flag is re-synced every frame.
+pub fn sim_timings_system(mut timings: ResMut<SimTimings>, mut lazy: ResMut<LazyUniverse>) {
+    if lazy.time_loads != timings.enabled {
+        lazy.time_loads = timings.enabled;
+    }
+    if !timings.enabled {
+        return;
+    }
+    if let Some((region_id, elapsed)) = lazy.bypass_change_detection().take_load_time() {
+        timings.record_region_load(region_id, elapsed);
+    }
+}
+
+/// The entities with a `C`, for counting
+type Counted<'w, 's, C> = Query<'w, 's, (), With<C>>;
+
+/// Fill in the counters and the frame-time graph while the overlay is shown
+pub fn update_debug_overlay(
+    (time, timings, mut history): (Res<Time<Real>>, Res<SimTimings>, Local<FrameHistory>),
+    (universe, lazy, surface, budget): (Res<UniverseState>, Res<LazyUniverse>, Res<SurfaceState>, Res<RenderBudget>),
+    (instances, clouds, meshes): (Res<CosmosInstances>, Res<ParticleCloudState>, Res<Assets<Mesh>>),
+    (creatures, herds, details): (Counted<Creature>, Counted<HerdMarker>, Counted<SurfaceDetail>),
+    entities: Query<()>,
+    mut text_q: Query<&mut Text, With<DebugOverlayText>>,
+    mut bars_q: Query<(&FrameBar, &mut Node, &mut BackgroundColor)>,
+) {
+    if !timings.enabled {
+        if !history.frames.is_empty() {
+            history.frames.clear();
+        }
+        return;
+    }
+    history.push(time.delta_secs() * 1000.0);
+
+    let cloud_vertices: usize = clouds
+        .clouds
+        .values()
+        .filter_map(|(_, handle)| meshes.get(handle))
+        .map(Mesh::count_vertices)
+        .sum();
+    let memory = lazy.detail_memory();
+    let label = format!(
+        "{:.0} FPS | worst frame {:.1} ms\n\
+         Particles: {} alive / {} ({})\n\
+         Loaded stars: {} | instanced stars {} / planets {}\n\
+         Particle cloud vertices: {} | entities: {}\n\
+         {}\n\
+         {}\n\
+         Gravity (CPU): {}\n\
+         Particle cloud update: {}\n\
+         Region load: {}\n\
+         Detail: {} galactic regions ({}) | stellar {} | {} surface presets ({})",
+        history.fps(),
+        history.worst_ms(),
+        universe.cached_alive_count,
+        universe.particles.len(),
+        format_bytes(particle_memory(&universe, &lazy)),
+        lazy.loaded_stars.len(),
+        instances.stars.len(),
+        instances.planets.len(),
+        cloud_vertices,
+        entities.iter().count(),
+        if surface.active {
+            format!(
+                "Surface ({}): creatures {} | herd markers {} | details {}",
+                surface.surface_zoom.name(),
+                creatures.iter().count(),
+                herds.iter().count(),
+                details.iter().count()
+            )
+        } else {
+            format!("Creatures: {}", creatures.iter().count())
+        },
+        budget_line(&budget),
+        match timings.gravity {
+            Some(elapsed) => format!(
+                "{} for {} particles",
+                format_duration(elapsed),
+                timings.gravity_particles
+            ),
+            None if universe.gpu_active => "on the GPU".to_string(),
+            None => "—".to_string(),
+        },
+        timings.particle_clouds.map_or("—".to_string(), format_duration),
+        match (timings.region_load, timings.region_load_id) {
+            (Some(elapsed), Some(id)) => {
+                format!("{} ({})", format_duration(elapsed), lazy.region_name(id))
+            }
+            _ => "—".to_string(),
+        },
+        lazy.galactic_points.len(),
+        format_bytes(memory.galactic),
+        format_bytes(memory.stellar),
+        lazy.surface_presets.len(),
+        format_bytes(memory.planetary),
+    );
+    for mut text in &mut text_q {
+        **text = label.clone();
+    }
+
+    let first = GRAPH_FRAMES - history.frames.len();
+    for (bar, mut node, mut color) in &mut bars_q {
+        let Some(&frame_ms) = bar.0.checked_sub(first).and_then(|i| history.frames.get(i)) else {
+            node.height = Val::Px(0.0);
+            continue;
+        };
+        node.height = Val::Px(bar_height(frame_ms));
+        color.0 = bar_color(frame_ms);
+    }
+}
+
+/// The render budget's preset and cap, then each category's granted / desired entities
+fn budget_line(budget: &RenderBudget) -> String {
+    let preset = budget.preset();
+    let mut line = format!(
+        "Budget ({:?}): {} / {}",
+        preset,
+        budget.total_granted(),
+        preset.entity_cap()
+    );
+    for (category, usage) in budget.usage() {
+        line.push_str(&format!(" | {} {}/{}", category.name(), usage.granted, usage.desired));
+    }
+    line
+}
+
+/// Height of a frame-time bar (px)
+fn bar_height(frame_ms: f32) -> f32 {
+    (frame_ms / GRAPH_MAX_MS).clamp(0.02, 1.0) * GRAPH_HEIGHT
+}
+
+/// Green at 60 FPS, yellow below, red below 30
+fn bar_color(frame_ms: f32) -> Color {
+    if frame_ms <= 17.0 {
+        Color::srgb(0.3, 1.0, 0.4)
+    } else if frame_ms <= 34.0 {
+        Color::srgb(1.0, 0.85, 0.3)
+    } else {
+        Color::srgb(1.0, 0.35, 0.3)
+    }
+}
+
+/// Heap held by the particle Vecs (simulated, region copy and leapfrog accelerations)
+fn particle_memory(universe: &UniverseState, lazy: &LazyUniverse) -> usize {
+    (universe.particles.capacity() + lazy.loaded_particles.capacity()) * size_of::<GpuParticle>()
+        + universe.prev_accel.capacity() * size_of::<[f32; 3]>()
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    format!("{:.2} ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    #[test]
+    fn test_f3_enables_capture_and_records_region_loads() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<UserSettings>()
+            .init_resource::<SimTimings>()
+            .insert_resource(LazyUniverse::new(SimConfig::default(), 13.0))
+            .add_systems(
+                Update,
+                (debug_overlay_toggle_system, sim_timings_system).chain(),
+            );
+        let root = app
+            .world_mut()
+            .spawn((
+                Node {
+                    display: Display::None,
+                    ..default()
+                },
+                DebugOverlayRoot,
+            ))
+            .id();
+
+        // Off: loads are not measured
+        app.update();
+        let region_id = app.world().resource::<LazyUniverse>().regions[0].id;
+        app.world_mut()
+            .resource_mut::<LazyUniverse>()
+            .enter_region(region_id, 13.0);
+        app.update();
+        assert!(app.world().resource::<SimTimings>().region_load.is_none());
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::F3);
+        app.update();
+        assert!(app.world().resource::<SimTimings>().enabled);
+        assert!(app.world().resource::<LazyUniverse>().time_loads);
+        assert_eq!(
+            app.world().get::<Node>(root).unwrap().display,
+            Display::Flex
+        );
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+
+        let other = app.world().resource::<LazyUniverse>().regions[1].id;
+        app.world_mut()
+            .resource_mut::<LazyUniverse>()
+            .enter_region(other, 13.0);
+        app.update();
+        let timings = app.world().resource::<SimTimings>();
+        assert_eq!(timings.region_load_id, Some(other));
+        assert!(timings.region_load.is_some());
+
+        assert_eq!(bar_height(1000.0), GRAPH_HEIGHT);
+        assert!(bar_height(GRAPH_MAX_MS / 2.0) < GRAPH_HEIGHT);
+    }
+}
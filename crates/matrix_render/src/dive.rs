@@ -0,0 +1,497 @@
+//! [B] on a gas or ice giant: an atmosphere dive instead of a landing. Giants have no
+//! surface, so the camera falls through the planet's cloud decks into ever thicker haze,
+//! with the pressure and temperature in the HUD, until a probe would be crushed and it is
+//! lifted back to space. [Esc] or [B] turns back early.
+
+use bevy::prelude::*;
+use matrix_core::{Planet, PlanetType};
+use matrix_sim::lazy_universe::LazyUniverse;
+
+use super::camera::FlyCamera;
+use super::hud_layout::HudScaled;
+use super::settings::{Action, Controls};
+
+/// Pressure at the top of the dive, in the upper haze (bar)
+const TOP_PRESSURE_BAR: f64 = 0.1;
+/// Pressure that ends the fall (bar)
+pub const CRUSH_PRESSURE_BAR: f64 = 1000.0;
+/// Fall speed (km of depth per second)
+const FALL_SPEED_KM: f64 = 8.0;
+/// Climb speed back to the top (km per second)
+const RISE_SPEED_KM: f64 = 60.0;
+/// Render units per km of depth: the cloud decks pass the camera at this rate
+const UNITS_PER_KM: f32 = 0.5;
+/// Fog density at the top of the dive; grows with the square root of the pressure
+const FOG_DENSITY_TOP: f32 = 0.01;
+/// Half the edge of a cloud deck (render units)
+const DECK_HALF_SIZE: f32 = 300.0;
+/// Font size of the dive readout
+const PANEL_FONT_SIZE: f32 = 16.0;
+/// Space background, restored when the dive ends
+const SPACE_CLEAR_COLOR: Color = Color::srgb(0.0, 0.0, 0.02);
+
+/// A cloud deck of a giant's atmosphere
+#[derive(Debug)]
+pub struct CloudLayer {
+    pub name: &'static str,
+    /// Pressure at its top (bar)
+    pub top_bar: f64,
+    pub color: Color,
+}
+
+/// Jupiter-like decks: ammonia ice over ammonium hydrosulfide over water
+static GAS_GIANT_LAYERS: [CloudLayer; 5] = [
+    CloudLayer {
+        name: "Upper haze",
+        top_bar: 0.0,
+        color: Color::srgb(0.7, 0.66, 0.58),
+    },
+    CloudLayer {
+        name: "Ammonia ice clouds",
+        top_bar: 0.5,
+        color: Color::srgb(0.92, 0.88, 0.78),
+    },
+    CloudLayer {
+        name: "Ammonium hydrosulfide clouds",
+        top_bar: 1.5,
+        color: Color::srgb(0.72, 0.45, 0.25),
+    },
+    CloudLayer {
+        name: "Water clouds",
+        top_bar: 5.0,
+        color: Color::srgb(0.55, 0.6, 0.68),
+    },
+    CloudLayer {
+        name: "Hydrogen depths",
+        top_bar: 20.0,
+        color: Color::srgb(0.3, 0.14, 0.08),
+    },
+];
+
+/// Neptune-like decks: methane ice over hydrogen sulfide, deeper ammonium hydrosulfide
+/// and a water-ammonia layer
+static ICE_GIANT_LAYERS: [CloudLayer; 5] = [
+    CloudLayer {
+        name: "Upper haze",
+        top_bar: 0.0,
+        color: Color::srgb(0.5, 0.7, 0.8),
+    },
+    CloudLayer {
+        name: "Methane ice clouds",
+        top_bar: 1.0,
+        color: Color::srgb(0.6, 0.85, 0.95),
+    },
+    CloudLayer {
+        name: "Hydrogen sulfide clouds",
+        top_bar: 3.0,
+        color: Color::srgb(0.5, 0.62, 0.72),
+    },
+    CloudLayer {
+        name: "Ammonium hydrosulfide clouds",
+        top_bar: 20.0,
+        color: Color::srgb(0.42, 0.38, 0.48),
+    },
+    CloudLayer {
+        name: "Water-ammonia clouds",
+        top_bar: 50.0,
+        color: Color::srgb(0.2, 0.28, 0.42),
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DivePhase {
+    #[default]
+    Idle,
+    /// Sinking deeper until the crush pressure (or [Esc])
+    Falling,
+    /// Lifted back to the top, then to space
+    Surfacing,
+}
+
+/// The dive in progress. `level_transition_system` starts it and leaves the camera to it
+/// while it lasts; `atmosphere_dive_system` moves it on and draws it.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AtmosphereDive {
+    pub phase: DivePhase,
+    pub planet: Option<Planet>,
+    /// Below the top of the dive (km)
+    pub depth: f64,
+    /// Camera position when the dive began, restored when it ends
+    pub return_pos: Vec3,
+    /// Bumped when a dive begins or ends, for the visuals to catch up
+    generation: u32,
+}
+
+impl AtmosphereDive {
+    pub fn is_active(&self) -> bool {
+        self.phase != DivePhase::Idle
+    }
+
+    /// Start falling into `planet` from the camera at `return_pos`. Only gas and ice
+    /// giants, and not while another dive is on.
+    pub fn begin(&mut self, planet: Planet, return_pos: Vec3) -> bool {
+        if self.is_active() || !planet.planet_type.is_giant() {
+            return false;
+        }
+        *self = Self {
+            phase: DivePhase::Falling,
+            planet: Some(planet),
+            depth: 0.0,
+            return_pos,
+            generation: self.generation.wrapping_add(1),
+        };
+        true
+    }
+
+    /// Stop falling and head back up
+    pub fn abort(&mut self) {
+        if self.phase == DivePhase::Falling {
+            self.phase = DivePhase::Surfacing;
+        }
+    }
+
+    /// End the dive where it is, leaving the camera alone (the world is torn down)
+    pub fn cancel(&mut self) {
+        if self.is_active() {
+            self.phase = DivePhase::Idle;
+            self.planet = None;
+            self.depth = 0.0;
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+
+    /// Move the dive on by `dt` seconds: fall to the crush depth, then climb back to the
+    /// top, where it ends. True on the call that ends it.
+    pub fn advance(&mut self, dt: f64) -> bool {
+        match self.phase {
+            DivePhase::Idle => false,
+            DivePhase::Falling => {
+                let crush = self.crush_depth();
+                self.depth = (self.depth + FALL_SPEED_KM * dt).min(crush);
+                if self.depth >= crush {
+                    self.phase = DivePhase::Surfacing;
+                }
+                false
+            }
+            DivePhase::Surfacing => {
+                self.depth = (self.depth - RISE_SPEED_KM * dt).max(0.0);
+                if self.depth > 0.0 {
+                    return false;
+                }
+                self.phase = DivePhase::Idle;
+                self.planet = None;
+                self.generation = self.generation.wrapping_add(1);
+                true
+            }
+        }
+    }
+
+    fn is_ice_giant(&self) -> bool {
+        self.planet
+            .as_ref()
+            .is_some_and(|p| p.planet_type == PlanetType::IceGiant)
+    }
+
+    /// Depth over which the pressure grows e-fold (km): heavier molecules in ice giants
+    fn scale_height(&self) -> f64 {
+        if self.is_ice_giant() { 20.0 } else { 27.0 }
+    }
+
+    /// Depth of the crush pressure (km)
+    pub fn crush_depth(&self) -> f64 {
+        self.depth_at(CRUSH_PRESSURE_BAR)
+    }
+
+    /// Depth at which the pressure reaches `bar` (km)
+    fn depth_at(&self, bar: f64) -> f64 {
+        self.scale_height() * (bar / TOP_PRESSURE_BAR).ln()
+    }
+
+    /// Pressure at the current depth (bar), rising exponentially
+    pub fn pressure_bar(&self) -> f64 {
+        TOP_PRESSURE_BAR * (self.depth / self.scale_height()).exp()
+    }
+
+    /// Temperature at the current depth (K): the planet's, rising with the lapse rate
+    pub fn temperature_k(&self) -> f64 {
+        let top = self.planet.as_ref().map_or(100.0, |p| p.surface_temp);
+        let lapse = if self.is_ice_giant() { 1.5 } else { 2.0 };
+        top + lapse * self.depth
+    }
+
+    /// Cloud decks of the planet, top down
+    pub fn layers(&self) -> &'static [CloudLayer] {
+        if self.is_ice_giant() { &ICE_GIANT_LAYERS } else { &GAS_GIANT_LAYERS }
+    }
+
+    /// Deck the camera is in
+    pub fn layer(&self) -> &'static CloudLayer {
+        let pressure = self.pressure_bar();
+        let layers = self.layers();
+        layers
+            .iter()
+            .rev()
+            .find(|l| pressure >= l.top_bar)
+            .unwrap_or(&layers[0])
+    }
+
+    /// Fog density at the current depth: thicker as the pressure rises
+    pub fn fog_density(&self) -> f32 {
+        FOG_DENSITY_TOP * (self.pressure_bar() / TOP_PRESSURE_BAR).sqrt() as f32
+    }
+}
+
+/// Everything drawn for a dive (despawned when it ends)
+#[derive(Component)]
+pub struct DiveVisual;
+
+/// A translucent cloud deck plane, at the top of the layer with this index
+#[derive(Component)]
+pub struct DiveDeck(usize);
+
+/// Dive readout: layer, depth, pressure and temperature
+#[derive(Component)]
+pub struct DivePanel;
+
+/// Run the dive: [Esc] / [B] turn back, the fall advances, the decks rise past the held
+/// camera through thickening fog, and the readout follows. Spawns the visuals when a
+/// dive begins; when it ends, the camera is back where it started and the fog and
+/// background are those of space again.
+pub fn atmosphere_dive_system(
+    (mut commands, time, controls): (Commands, Res<Time>, Controls),
+    (lazy, mut dive, mut drawn): (Res<LazyUniverse>, ResMut<AtmosphereDive>, Local<u32>),
+    (mut clear_color, mut meshes, mut materials): (
+        ResMut<ClearColor>,
+        ResMut<Assets<Mesh>>,
+        ResMut<Assets<StandardMaterial>>,
+    ),
+    mut camera_q: Query<(Entity, &mut Transform), With<FlyCamera>>,
+    mut deck_q: Query<(&DiveDeck, &mut Transform), Without<FlyCamera>>,
+    mut panel_q: Query<&mut Text, With<DivePanel>>,
+    visual_q: Query<Entity, With<DiveVisual>>,
+) {
+    let Ok((camera, mut cam_tf)) = camera_q.get_single_mut() else {
+        return;
+    };
+
+    // The key that started the dive is still down on its first frame
+    if *drawn == dive.generation
+        && (controls.just_pressed(Action::BackLevel) || controls.just_pressed(Action::EnterLevel))
+    {
+        dive.abort();
+    }
+    if dive.advance(time.delta_secs_f64()) {
+        cam_tf.translation = dive.return_pos;
+        info!("Dive: back in space");
+    }
+
+    if *drawn != dive.generation {
+        *drawn = dive.generation;
+        for entity in &visual_q {
+            commands.entity(entity).despawn_recursive();
+        }
+        if !dive.is_active() {
+            commands.entity(camera).remove::<DistanceFog>();
+            clear_color.0 = SPACE_CLEAR_COLOR;
+            return;
+        }
+        spawn_dive_visuals(&mut commands, &dive, &mut meshes, &mut materials);
+        if let Some(planet) = &dive.planet {
+            info!("Dive: into {} ({:?}), [Esc] to turn back", lazy.planet_name(planet.id), planet.planet_type);
+        }
+        return;
+    }
+    if !dive.is_active() {
+        return;
+    }
+
+    // The camera only looks around; the atmosphere moves past it
+    cam_tf.translation = dive.return_pos;
+    let layer = dive.layer();
+    let darkness = (1.0 - dive.depth / dive.crush_depth() * 0.8) as f32;
+    let sky = (layer.color.to_linear() * darkness).with_alpha(1.0);
+    clear_color.0 = Color::from(sky);
+    commands.entity(camera).insert(DistanceFog {
+        color: Color::from(sky),
+        falloff: FogFalloff::Exponential {
+            density: dive.fog_density(),
+        },
+        ..default()
+    });
+
+    let layers = dive.layers();
+    for (deck, mut transform) in &mut deck_q {
+        let below = (dive.depth_at(layers[deck.0].top_bar) - dive.depth) as f32 * UNITS_PER_KM;
+        transform.translation = dive.return_pos - Vec3::Y * below;
+    }
+    if let Ok(mut text) = panel_q.get_single_mut() {
+        **text = dive_readout(&dive, &lazy);
+    }
+}
+
+/// Cloud decks below the camera and the readout panel
+fn spawn_dive_visuals(
+    commands: &mut Commands,
+    dive: &AtmosphereDive,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let mesh = meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(DECK_HALF_SIZE)));
+    // The top haze is what the camera starts in: no deck of its own
+    for (i, layer) in dive.layers().iter().enumerate().skip(1) {
+        let material = materials.add(StandardMaterial {
+            base_color: layer.color.with_alpha(0.55),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        });
+        let below = dive.depth_at(layer.top_bar) as f32 * UNITS_PER_KM;
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material),
+            Transform::from_translation(dive.return_pos - Vec3::Y * below),
+            DiveDeck(i),
+            DiveVisual,
+        ));
+    }
+
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: PANEL_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 0.9, 0.6, 0.95)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(20.0),
+            left: Val::Percent(50.0),
+            margin: UiRect::left(Val::Px(-160.0)),
+            width: Val::Px(320.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.03, 0.0, 0.6)),
+        HudScaled {
+            font_size: Some(PANEL_FONT_SIZE),
+            width: Some(320.0),
+            ..default()
+        },
+        DivePanel,
+        DiveVisual,
+    ));
+}
+
+fn dive_readout(dive: &AtmosphereDive, lazy: &LazyUniverse) -> String {
+    let name = dive.planet.as_ref().map_or_else(String::new, |p| lazy.planet_name(p.id));
+    let heading = match dive.phase {
+        DivePhase::Surfacing if dive.depth < dive.crush_depth() => "Climbing back to space",
+        DivePhase::Surfacing => "Crush depth: returning to space",
+        _ => "[Esc] Turn back",
+    };
+    format!(
+        "ATMOSPHERE DIVE — {name}\n{}\nDepth: {:.0} km\nPressure: {:.1} bar\nTemperature: {:.0} K\n{heading}",
+        dive.layer().name,
+        dive.depth,
+        dive.pressure_bar(),
+        dive.temperature_k(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::AtmosphereType;
+
+    fn giant(planet_type: PlanetType) -> Planet {
+        Planet {
+            id: 3_001,
+            orbital_radius: 5.2,
+            orbital_period: 11.9,
+            orbital_angle: 0.0,
+            mass: 318.0,
+            radius: 11.2,
+            surface_temp: 110.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::Hydrogen,
+            planet_type,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    #[test]
+    fn test_dive_falls_to_crush_depth_and_returns() {
+        let mut dive = AtmosphereDive::default();
+        assert!(!dive.is_active() && !dive.advance(1.0));
+
+        // Rocky worlds are landed on, not dived into
+        assert!(!dive.begin(giant(PlanetType::Rocky), Vec3::ZERO));
+        assert_eq!(dive.phase, DivePhase::Idle);
+
+        let start = Vec3::new(1.0, 2.0, 3.0);
+        assert!(dive.begin(giant(PlanetType::GasGiant), start));
+        assert_eq!(dive.phase, DivePhase::Falling);
+        assert!(!dive.begin(giant(PlanetType::IceGiant), Vec3::ZERO), "one dive at a time");
+        assert_eq!(dive.layer().name, "Upper haze");
+
+        // Deeper: pressure, temperature and fog rise through the decks
+        let mut last = (dive.pressure_bar(), dive.temperature_k(), dive.fog_density());
+        let mut layers = vec![dive.layer().name];
+        while dive.phase == DivePhase::Falling {
+            assert!(!dive.advance(0.5));
+            let now = (dive.pressure_bar(), dive.temperature_k(), dive.fog_density());
+            assert!(now.0 > last.0 && now.1 > last.1 && now.2 > last.2);
+            last = now;
+            if layers.last() != Some(&dive.layer().name) {
+                layers.push(dive.layer().name);
+            }
+        }
+        let all: Vec<_> = GAS_GIANT_LAYERS.iter().map(|l| l.name).collect();
+        assert_eq!(layers, all);
+        // Stopped at the crush pressure
+        assert_eq!(dive.phase, DivePhase::Surfacing);
+        assert!((dive.pressure_bar() - CRUSH_PRESSURE_BAR).abs() < 1e-6);
+
+        // Lifted back out, and done
+        let mut ended = false;
+        for _ in 0..1000 {
+            if dive.advance(0.5) {
+                ended = true;
+                break;
+            }
+        }
+        assert!(ended);
+        assert!(!dive.is_active() && dive.planet.is_none());
+        assert_eq!((dive.depth, dive.return_pos), (0.0, start));
+    }
+
+    #[test]
+    fn test_dive_abort_turns_back() {
+        let mut dive = AtmosphereDive::default();
+        assert!(dive.begin(giant(PlanetType::IceGiant), Vec3::ZERO));
+        dive.advance(3.0);
+        let depth = dive.depth;
+        assert!(depth > 0.0 && dive.pressure_bar() < CRUSH_PRESSURE_BAR);
+        assert_eq!(dive.layers()[1].name, "Methane ice clouds");
+
+        dive.abort();
+        assert_eq!(dive.phase, DivePhase::Surfacing);
+        dive.advance(0.1);
+        assert!(dive.depth < depth);
+        while !dive.advance(0.1) {}
+        assert_eq!(dive.phase, DivePhase::Idle);
+
+        // A new dive can begin once back; quitting the world drops it on the spot
+        assert!(dive.begin(giant(PlanetType::GasGiant), Vec3::ZERO));
+        dive.advance(1.0);
+        dive.cancel();
+        assert!(!dive.is_active() && dive.planet.is_none());
+        assert_eq!(dive.depth, 0.0);
+    }
+}
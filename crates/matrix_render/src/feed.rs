@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use matrix_core::SimEvent;
+use matrix_sim::events::EventFeed;
+use matrix_sim::lazy_universe::LazyUniverse;
+
+use super::console::ConsoleState;
+use super::hud_layout::{HudExtra, HudScaled};
+use super::locale::Strings;
+
+/// Entries shown at once
+const FEED_LINES: usize = 6;
+/// Seconds an entry stays fully visible before it fades
+const FEED_HOLD_SECS: f64 = 10.0;
+/// Seconds the fade-out takes
+const FEED_FADE_SECS: f64 = 2.0;
+
+/// Marker for the feed root node
+#[derive(Component)]
+pub struct FeedRoot;
+
+/// One feed line; slot 0 is the oldest shown, the newest is at the bottom
+#[derive(Component)]
+pub struct FeedLine(pub usize);
+
+/// When the shown entries arrived (real seconds), oldest first
+#[derive(Default)]
+pub struct FeedArrivals {
+    seen: u64,
+    times: VecDeque<f64>,
+}
+
+/// Spawn the event feed, bottom left
+pub fn spawn_event_feed(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                max_width: Val::Px(520.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            FeedRoot,
+            HudScaled {
+                max_width: Some(520.0),
+                ..default()
+            },
+            HudExtra,
+        ))
+        .with_children(|panel| {
+            for slot in 0..FEED_LINES {
+                panel.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::NONE),
+                    FeedLine(slot),
+                    HudScaled::font(14.0),
+                ));
+            }
+        });
+}
+
+/// Show the newest feed entries, fading each one out after it has been up a while.
+/// Runs on real time so entries still fade while the simulation is paused.
+pub fn update_event_feed(
+    time: Res<Time<Real>>,
+    (feed, lazy, strings): (Res<EventFeed>, Res<LazyUniverse>, Res<Strings>),
+    console: Res<ConsoleState>,
+    mut arrivals: Local<FeedArrivals>,
+    mut root_q: Query<&mut Node, With<FeedRoot>>,
+    mut lines_q: Query<(&FeedLine, &mut Text, &mut TextColor)>,
+) {
+    let now = time.elapsed_secs_f64();
+    let fresh = (feed.pushed() - arrivals.seen).min(FEED_LINES as u64);
+    arrivals.seen = feed.pushed();
+    for _ in 0..fresh {
+        arrivals.times.push_back(now);
+    }
+    while arrivals.times.len() > FEED_LINES {
+        arrivals.times.pop_front();
+    }
+
+    // The console covers the bottom of the screen while it is open
+    for mut node in &mut root_q {
+        node.display = if console.open {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+
+    let entries = feed.entries();
+    let shown = arrivals.times.len().min(entries.len());
+    let first_time = arrivals.times.len() - shown;
+    let first_entry = entries.len() - shown;
+    for (line, mut text, mut color) in &mut lines_q {
+        let Some(arrived) = arrivals.times.get(first_time + line.0) else {
+            color.0 = Color::NONE;
+            continue;
+        };
+        let alpha = fade_alpha(now - arrived);
+        if alpha <= 0.0 {
+            color.0 = Color::NONE;
+            continue;
+        }
+        let entry = &entries[first_entry + line.0];
+        let label = strings.tr(
+            "feed.entry",
+            &[
+                ("age", &strings.number(entry.age_gyr, 2)),
+                ("event", &describe(&entry.event, &lazy, &strings)),
+            ],
+        );
+        if **text != label {
+            **text = label;
+        }
+        color.0 = event_color(&entry.event).with_alpha(alpha);
+    }
+}
+
+/// `SimEvent::describe` in the player's language, with regions and planets called by name
+pub(crate) fn describe(event: &SimEvent, lazy: &LazyUniverse, strings: &Strings) -> String {
+    match event {
+        SimEvent::LifeDiscovered { description, .. } => {
+            strings.tr("feed.life", &[("description", description)])
+        }
+        SimEvent::CivilizationDetected {
+            region_id,
+            planet_id,
+        } => strings.tr(
+            "feed.civilization",
+            &[
+                ("planet", &lazy.planet_name_of(*region_id, *planet_id)),
+                ("id", planet_id),
+                ("region", &lazy.region_name(*region_id)),
+            ],
+        ),
+        SimEvent::CivilizationExtinct {
+            region_id,
+            planet_id,
+            cause,
+        } => strings.tr(
+            "feed.extinct",
+            &[
+                ("planet", &lazy.planet_name_of(*region_id, *planet_id)),
+                ("id", planet_id),
+                ("cause", cause),
+            ],
+        ),
+        SimEvent::PhaseTransition { from, to } => {
+            strings.tr("feed.phase", &[("from", &from.name()), ("to", &to.name())])
+        }
+        SimEvent::SupernovaNearby { region_id, count: 1 } => {
+            strings.tr("feed.supernova", &[("region", &lazy.region_name(*region_id))])
+        }
+        SimEvent::SupernovaNearby { region_id, count } => strings.tr(
+            "feed.supernovae",
+            &[("count", count), ("region", &lazy.region_name(*region_id))],
+        ),
+        SimEvent::CycleBegan { cycle } => strings.tr("feed.cycle", &[("cycle", cycle)]),
+        SimEvent::SnapshotSaved { path } => strings.tr("feed.snapshot", &[("path", path)]),
+        SimEvent::SignalDetected {
+            region_id,
+            planet_id,
+        } => strings.tr(
+            "feed.signal",
+            &[
+                ("planet", &lazy.planet_name_of(*region_id, *planet_id)),
+                ("id", planet_id),
+                ("region", &lazy.region_name(*region_id)),
+            ],
+        ),
+    }
+}
+
+/// Opacity of an entry that arrived `age_secs` ago
+fn fade_alpha(age_secs: f64) -> f32 {
+    (1.0 - (age_secs - FEED_HOLD_SECS) / FEED_FADE_SECS).clamp(0.0, 1.0) as f32
+}
+
+fn event_color(event: &SimEvent) -> Color {
+    match event {
+        SimEvent::LifeDiscovered { .. } => Color::srgb(0.3, 1.0, 0.4),
+        SimEvent::CivilizationDetected { .. } => Color::srgb(0.3, 0.8, 1.0),
+        SimEvent::CivilizationExtinct { .. } => Color::srgb(1.0, 0.35, 0.3),
+        SimEvent::PhaseTransition { .. } => Color::srgb(1.0, 0.85, 0.3),
+        SimEvent::SupernovaNearby { .. } => Color::srgb(1.0, 0.55, 0.2),
+        SimEvent::CycleBegan { .. } => Color::srgb(0.8, 0.5, 1.0),
+        SimEvent::SnapshotSaved { .. } => Color::srgb(0.7, 0.7, 0.7),
+        SimEvent::SignalDetected { .. } => Color::srgb(1.0, 0.75, 0.3),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_hold_then_fade_out() {
+        assert_eq!(fade_alpha(0.0), 1.0);
+        assert_eq!(fade_alpha(FEED_HOLD_SECS), 1.0);
+        let halfway = fade_alpha(FEED_HOLD_SECS + FEED_FADE_SECS / 2.0);
+        assert!((halfway - 0.5).abs() < 1e-6);
+        assert_eq!(fade_alpha(FEED_HOLD_SECS + FEED_FADE_SECS), 0.0);
+        assert_eq!(fade_alpha(1000.0), 0.0);
+    }
+
+    #[test]
+    fn test_descriptions_follow_the_language() {
+        let lazy = LazyUniverse::empty(matrix_core::SimConfig::default());
+        let english = Strings::default();
+        let ukrainian = Strings::load_from(std::path::Path::new("no_such_dir"), "uk");
+        let cycle = SimEvent::CycleBegan { cycle: 3 };
+        assert_eq!(describe(&cycle, &lazy, &english), "Big Crunch — cycle 3 begins");
+        assert_eq!(describe(&cycle, &lazy, &ukrainian), "Великий Стиск — починається цикл 3");
+        let saved = SimEvent::SnapshotSaved {
+            path: "saves/a.bin".to_string(),
+        };
+        assert_eq!(describe(&saved, &lazy, &ukrainian), "Знімок збережено: saves/a.bin");
+    }
+}
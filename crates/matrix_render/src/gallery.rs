@@ -0,0 +1,457 @@
+//! Universe gallery, reached from the main menu: a card per candidate seed with its stars,
+//! habitable and living planets, civilizations and a density map of the grid's middle
+//! layer (`matrix_physics::stats::SeedPreview`). The previews run on the async pool and
+//! their cards show up as they finish; clicking one starts that universe, [More seeds]
+//! replaces the cards with the next batch. Leaving the screen drops the previews: those
+//! not started yet never run, one already computing (it is synchronous) finishes within
+//! its time budget and is discarded.
+
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use matrix_core::SimConfig;
+use matrix_physics::stats::{PREVIEW_MAP_SIZE, SeedPreview, Stage};
+use matrix_sim::state::AppState;
+use std::time::Duration;
+
+use super::locale::Strings;
+use super::menu::{self, MenuRoot};
+
+/// Candidates per batch
+const GALLERY_BATCH: usize = 6;
+/// Age the candidates are previewed at (Gyr)
+const PREVIEW_AGE: f64 = 13.8;
+/// Regions a candidate's census samples at most, and how long it may take
+const PREVIEW_REGIONS: usize = 24;
+const PREVIEW_BUDGET: Duration = Duration::from_millis(1500);
+/// Edge of a card's density map (px)
+const THUMBNAIL_PX: f32 = 96.0;
+
+/// How the seeds of the next batch are picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedOrder {
+    /// Counting up from the config's seed
+    Sequential,
+    Random,
+}
+
+/// The gallery's candidates: the config they share apart from the seed, and the previews
+/// still being computed (dropping one before it starts skips it)
+#[derive(Resource)]
+pub(crate) struct SeedGallery {
+    base: SimConfig,
+    order: SeedOrder,
+    /// First seed of the next sequential batch
+    next_seed: u64,
+    /// Candidates in the current batch
+    batch: usize,
+    tasks: Vec<Task<SeedPreview>>,
+}
+
+impl SeedGallery {
+    fn new(base: SimConfig) -> Self {
+        Self {
+            next_seed: base.seed,
+            base,
+            order: SeedOrder::Sequential,
+            batch: 0,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Seeds of the next batch: on from the last sequential one, or random
+    fn next_seeds(&mut self) -> Vec<u64> {
+        match self.order {
+            SeedOrder::Sequential => {
+                let first = self.next_seed;
+                self.next_seed = first.wrapping_add(GALLERY_BATCH as u64);
+                (0..GALLERY_BATCH as u64).map(|i| first.wrapping_add(i)).collect()
+            }
+            // u32 keeps the seed short enough to read back and type in later
+            SeedOrder::Random => {
+                (0..GALLERY_BATCH).map(|_| u64::from(rand::random::<u32>())).collect()
+            }
+        }
+    }
+
+    /// Drop the previews of the last batch and start the next one
+    fn start_batch(&mut self) {
+        let pool = AsyncComputeTaskPool::get();
+        let seeds = self.next_seeds();
+        self.batch = seeds.len();
+        self.tasks = seeds
+            .into_iter()
+            .map(|seed| {
+                let config = SimConfig {
+                    seed,
+                    ..self.base.clone()
+                };
+                pool.spawn(async move {
+                    SeedPreview::compute(&config, PREVIEW_AGE, PREVIEW_REGIONS, PREVIEW_BUDGET)
+                })
+            })
+            .collect();
+    }
+}
+
+/// Container the cards are added to
+#[derive(Component)]
+pub(crate) struct GalleryGrid;
+
+/// "Previewing..." / "Click a universe" line above the cards
+#[derive(Component)]
+pub(crate) struct GalleryStatus;
+
+/// A finished candidate; clicking it starts the universe
+#[derive(Component)]
+pub(crate) struct GalleryCard {
+    seed: u64,
+}
+
+#[derive(Component)]
+pub(crate) struct MoreSeedsButton;
+
+#[derive(Component)]
+pub(crate) struct SeedOrderButton;
+
+#[derive(Component)]
+pub(crate) struct GalleryBackButton;
+
+/// Replace the main menu with the gallery and preview the first batch, for universes
+/// like `base` (the last one played)
+pub(crate) fn open_gallery(commands: &mut Commands, strings: &Strings, base: SimConfig) {
+    let mut gallery = SeedGallery::new(base);
+    gallery.start_batch();
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            MenuRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(strings.tr("gallery.title", &[])),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.0, 1.0, 0.4, 0.9)),
+            ));
+            parent.spawn((
+                Text::new(status_label(&gallery, strings)),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.0, 0.8, 0.3, 0.7)),
+                GalleryStatus,
+            ));
+
+            parent.spawn((
+                Node {
+                    width: Val::Px(680.0),
+                    min_height: Val::Px(2.0 * (THUMBNAIL_PX + 120.0)),
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    align_content: AlignContent::FlexStart,
+                    column_gap: Val::Px(10.0),
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                GalleryGrid,
+            ));
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    let grey = Color::srgba(0.3, 0.3, 0.3, 0.9);
+                    let blue = Color::srgba(0.1, 0.2, 0.5, 0.9);
+                    let back = strings.tr("menu.back", &[]);
+                    menu::spawn_text_button(row, &back, 140.0, grey, GalleryBackButton);
+                    let order = order_label(gallery.order, strings);
+                    menu::spawn_text_button(row, &order, 200.0, blue, SeedOrderButton);
+                    menu::spawn_text_button(
+                        row,
+                        &strings.tr("gallery.more", &[]),
+                        200.0,
+                        Color::srgba(0.0, 0.4, 0.1, 0.9),
+                        MoreSeedsButton,
+                    );
+                });
+        });
+    commands.insert_resource(gallery);
+}
+
+fn status_label(gallery: &SeedGallery, strings: &Strings) -> String {
+    if gallery.tasks.is_empty() {
+        strings.tr("gallery.ready", &[("age", &strings.number(PREVIEW_AGE, 1))])
+    } else {
+        let done = gallery.batch - gallery.tasks.len();
+        strings.tr("gallery.previewing", &[("done", &done), ("total", &gallery.batch)])
+    }
+}
+
+fn order_label(order: SeedOrder, strings: &Strings) -> String {
+    let key = match order {
+        SeedOrder::Sequential => "gallery.order_sequential",
+        SeedOrder::Random => "gallery.order_random",
+    };
+    strings.tr(key, &[])
+}
+
+/// Add a card for every preview that finished
+pub(crate) fn gallery_poll_system(
+    mut commands: Commands,
+    gallery: Option<ResMut<SeedGallery>>,
+    strings: Res<Strings>,
+    mut images: ResMut<Assets<Image>>,
+    grid_q: Query<Entity, With<GalleryGrid>>,
+    mut status_q: Query<&mut Text, With<GalleryStatus>>,
+) {
+    let Some(mut gallery) = gallery else { return };
+    let Ok(grid) = grid_q.get_single() else { return };
+    let mut finished = Vec::new();
+    gallery.tasks.retain_mut(|task| match block_on(poll_once(task)) {
+        Some(preview) => {
+            finished.push(preview);
+            false
+        }
+        None => true,
+    });
+    if finished.is_empty() {
+        return;
+    }
+    commands.entity(grid).with_children(|grid| {
+        for preview in &finished {
+            let map = images.add(density_image(&preview.density_map));
+            spawn_card(grid, &strings, preview, map);
+        }
+    });
+    for mut text in &mut status_q {
+        **text = status_label(&gallery, &strings);
+    }
+}
+
+/// One card: the seed, the density map and the census figures
+fn spawn_card(
+    grid: &mut ChildBuilder,
+    strings: &Strings,
+    preview: &SeedPreview,
+    map: Handle<Image>,
+) {
+    let estimate = |stage: Stage| strings.count(preview.census.stage(stage).total.round() as u64);
+    grid.spawn((
+        Button,
+        Node {
+            width: Val::Px(200.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(6.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.12, 0.2, 0.9)),
+        GalleryCard { seed: preview.seed },
+    ))
+    .with_children(|card| {
+        card.spawn((
+            Text::new(strings.tr("gallery.seed", &[("seed", &preview.seed)])),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+        card.spawn((
+            ImageNode::new(map),
+            Node {
+                width: Val::Px(THUMBNAIL_PX),
+                height: Val::Px(THUMBNAIL_PX),
+                ..default()
+            },
+        ));
+        card.spawn((
+            Text::new(strings.tr(
+                "gallery.card",
+                &[
+                    ("stars", &strings.count(preview.stars)),
+                    ("habitable", &estimate(Stage::Habitable)),
+                    ("life", &estimate(Stage::Life)),
+                    ("civilizations", &estimate(Stage::Technological)),
+                ],
+            )),
+            TextFont {
+                font_size: 13.0,
+                ..default()
+            },
+            TextColor(Color::srgba(0.7, 0.8, 1.0, 0.9)),
+        ));
+    });
+}
+
+/// Color of a density map cell: dark blue in voids, green and white toward clusters (a
+/// log scale from a tenth to ten times the average)
+fn density_color(density: f64) -> [u8; 4] {
+    let t = ((density.max(1e-3).log10() + 1.0) / 2.0).clamp(0.0, 1.0) as f32;
+    [(255.0 * t * t) as u8, (40.0 + 215.0 * t) as u8, (90.0 + 140.0 * t) as u8, 255]
+}
+
+/// The density map as an image, lowest y at the bottom, with crisp cells
+fn density_image(map: &[f64; PREVIEW_MAP_SIZE * PREVIEW_MAP_SIZE]) -> Image {
+    let data = (0..PREVIEW_MAP_SIZE)
+        .rev()
+        .flat_map(|row| &map[row * PREVIEW_MAP_SIZE..(row + 1) * PREVIEW_MAP_SIZE])
+        .flat_map(|&density| density_color(density))
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: PREVIEW_MAP_SIZE as u32,
+            height: PREVIEW_MAP_SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Presses of a button marked `B`
+type Presses<B> = (Changed<Interaction>, With<B>);
+
+/// Presses of [More seeds] and of [Back]
+type MoreOrBack<'w, 's> = (
+    Query<'w, 's, &'static Interaction, Presses<MoreSeedsButton>>,
+    Query<'w, 's, &'static Interaction, Presses<GalleryBackButton>>,
+);
+
+/// The order button's label and the status line under the cards
+type GalleryTexts<'w, 's> = (
+    Query<'w, 's, &'static mut Text, Without<GalleryStatus>>,
+    Query<'w, 's, &'static mut Text, With<GalleryStatus>>,
+);
+
+/// A card starts its universe; [More seeds] swaps the cards for a new batch, the order
+/// button picks how its seeds are chosen, [Back] returns to the main menu
+pub(crate) fn gallery_button_system(
+    (mut commands, mut next_state, strings): (Commands, ResMut<NextState<AppState>>, Res<Strings>),
+    gallery: Option<ResMut<SeedGallery>>,
+    card_q: Query<(&Interaction, &GalleryCard), Changed<Interaction>>,
+    (more_q, back_q): MoreOrBack,
+    order_q: Query<(&Interaction, &Children), Presses<SeedOrderButton>>,
+    (grid_q, root_q): (Query<Entity, With<GalleryGrid>>, Query<Entity, With<MenuRoot>>),
+    (mut text_q, mut status_q): GalleryTexts,
+) {
+    let Some(mut gallery) = gallery else { return };
+    let pressed = |i: &Interaction| *i == Interaction::Pressed;
+
+    if let Some((_, card)) = card_q.iter().find(|(i, _)| pressed(i)) {
+        let config = SimConfig {
+            seed: card.seed,
+            ..gallery.base.clone()
+        };
+        info!("Gallery: starting seed {}", card.seed);
+        menu::start_new_world(&mut commands, &mut next_state, config);
+        commands.remove_resource::<SeedGallery>();
+        return;
+    }
+
+    if back_q.iter().any(pressed) {
+        for entity in &root_q {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.remove_resource::<SeedGallery>();
+        menu::build_menu(&mut commands, &strings);
+        return;
+    }
+
+    for (interaction, children) in &order_q {
+        if !pressed(interaction) {
+            continue;
+        }
+        gallery.order = match gallery.order {
+            SeedOrder::Sequential => SeedOrder::Random,
+            SeedOrder::Random => SeedOrder::Sequential,
+        };
+        for &child in children {
+            if let Ok(mut text) = text_q.get_mut(child) {
+                **text = order_label(gallery.order, &strings);
+            }
+        }
+    }
+
+    if more_q.iter().any(pressed) {
+        for grid in &grid_q {
+            commands.entity(grid).despawn_descendants();
+        }
+        gallery.start_batch();
+        for mut text in &mut status_q {
+            **text = status_label(&gallery, &strings);
+        }
+    }
+}
+
+/// Leaving the menu (a card was picked, or a save loaded) drops the previews still running
+pub(crate) fn close_gallery(mut commands: Commands) {
+    commands.remove_resource::<SeedGallery>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_batches_follow_on_and_random_ones_are_short() {
+        let base = SimConfig {
+            seed: 1337,
+            ..SimConfig::default()
+        };
+        let mut gallery = SeedGallery::new(base);
+        let first = gallery.next_seeds();
+        assert_eq!(first, (1337..1337 + GALLERY_BATCH as u64).collect::<Vec<_>>());
+        let second = gallery.next_seeds();
+        assert_eq!(second[0], 1337 + GALLERY_BATCH as u64);
+
+        gallery.order = SeedOrder::Random;
+        let random = gallery.next_seeds();
+        assert_eq!(random.len(), GALLERY_BATCH);
+        assert!(random.iter().all(|&seed| seed <= u64::from(u32::MAX)));
+        // Back in order, it carries on where it left off
+        gallery.order = SeedOrder::Sequential;
+        assert_eq!(gallery.next_seeds()[0], 1337 + 2 * GALLERY_BATCH as u64);
+    }
+
+    #[test]
+    fn test_density_map_image_is_brighter_where_denser() {
+        let brightness = |density: f64| -> u32 {
+            density_color(density)[..3].iter().map(|&c| u32::from(c)).sum()
+        };
+        assert!(brightness(0.01) < brightness(0.5));
+        assert!(brightness(0.5) < brightness(2.0));
+        assert_eq!(brightness(10.0), brightness(1000.0));
+
+        // Lowest y at the bottom of the image
+        let mut map = [1.0; PREVIEW_MAP_SIZE * PREVIEW_MAP_SIZE];
+        map[0] = 10.0;
+        let image = density_image(&map);
+        let bottom_left = (PREVIEW_MAP_SIZE - 1) * PREVIEW_MAP_SIZE * 4;
+        assert_eq!(image.data[bottom_left..bottom_left + 4], density_color(10.0));
+        assert_eq!(image.data[..4], density_color(1.0));
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::prelude::*;
+
+use super::settings::{Action, StickInversion, UserSettings};
+
+/// The device the player used last, which the HUD help describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// Gamepad state for this frame, read through `settings::Controls`.
+/// All connected gamepads are merged.
+#[derive(Resource, Default)]
+pub struct GamepadInput {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    /// Left stick past the dead zone: x right, y forward (length ≤ 1)
+    pub movement: Vec2,
+    /// Right stick past the dead zone: x right, y up (length ≤ 1)
+    pub look: Vec2,
+    pub last_device: InputDevice,
+}
+
+impl GamepadInput {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+/// Radial dead zone, rescaled so deflection starts from 0 at its edge
+pub fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - dead_zone) / (1.0 - dead_zone).max(1e-3)).min(1.0);
+    stick / length * scaled
+}
+
+fn invert(stick: Vec2, x: bool, y: bool) -> Vec2 {
+    Vec2::new(
+        if x { -stick.x } else { stick.x },
+        if y { -stick.y } else { stick.y },
+    )
+}
+
+/// Xbox-style name of a button for the HUD help ("A", "LB", "D-pad ←", …)
+pub fn button_glyph(button: GamepadButton) -> &'static str {
+    match button {
+        GamepadButton::South => "A",
+        GamepadButton::East => "B",
+        GamepadButton::West => "X",
+        GamepadButton::North => "Y",
+        GamepadButton::LeftTrigger => "LB",
+        GamepadButton::RightTrigger => "RB",
+        GamepadButton::LeftTrigger2 => "LT",
+        GamepadButton::RightTrigger2 => "RT",
+        GamepadButton::LeftThumb => "L3",
+        GamepadButton::RightThumb => "R3",
+        GamepadButton::Select => "Back",
+        GamepadButton::Start => "Start",
+        GamepadButton::Mode => "Guide",
+        GamepadButton::DPadUp => "D-pad ↑",
+        GamepadButton::DPadDown => "D-pad ↓",
+        GamepadButton::DPadLeft => "D-pad ←",
+        GamepadButton::DPadRight => "D-pad →",
+        _ => "?",
+    }
+}
+
+/// Glyph of the button bound to `action`, or "—" when it has none
+pub fn action_glyph(settings: &UserSettings, action: Action) -> &'static str {
+    settings.button(action).map_or("—", button_glyph)
+}
+
+/// Collect bound buttons and sticks from every gamepad, and note which device was
+/// used last. Runs in `PreUpdate`, after Bevy's input systems.
+pub fn gamepad_input_system(
+    settings: Res<UserSettings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    gamepads: Query<&Gamepad>,
+    mut input: ResMut<GamepadInput>,
+) {
+    let StickInversion {
+        left_x,
+        left_y,
+        right_x,
+        right_y,
+    } = settings.stick_inversion;
+    let dead_zone = settings.stick_dead_zone;
+
+    input.pressed.clear();
+    input.just_pressed.clear();
+    let mut movement = Vec2::ZERO;
+    let mut look = Vec2::ZERO;
+    let mut touched = false;
+    for gamepad in &gamepads {
+        for action in Action::ALL {
+            let Some(button) = settings.button(action) else {
+                continue;
+            };
+            if gamepad.pressed(button) {
+                input.pressed.insert(action);
+            }
+            if gamepad.just_pressed(button) {
+                input.just_pressed.insert(action);
+            }
+        }
+        movement += invert(apply_dead_zone(gamepad.left_stick(), dead_zone), left_x, left_y);
+        look += invert(apply_dead_zone(gamepad.right_stick(), dead_zone), right_x, right_y);
+        touched |= gamepad.get_just_pressed().next().is_some();
+    }
+    input.movement = movement.clamp_length_max(1.0);
+    input.look = look.clamp_length_max(1.0);
+
+    if touched || input.movement != Vec2::ZERO || input.look != Vec2::ZERO {
+        input.last_device = InputDevice::Gamepad;
+    } else if keyboard.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || mouse_motion.delta != Vec2::ZERO
+    {
+        input.last_device = InputDevice::KeyboardMouse;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gamepad_maps_to_actions_with_dead_zone_and_inversion() {
+        assert_eq!(apply_dead_zone(Vec2::new(0.1, 0.0), 0.15), Vec2::ZERO);
+        let half = apply_dead_zone(Vec2::new(0.0, 0.575), 0.15);
+        assert!((half.y - 0.5).abs() < 1e-4);
+
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<AccumulatedMouseMotion>()
+            .init_resource::<GamepadInput>()
+            .insert_resource(UserSettings {
+                stick_inversion: StickInversion {
+                    right_y: true,
+                    ..default()
+                },
+                ..default()
+            })
+            .add_systems(Update, gamepad_input_system);
+
+        let mut gamepad = Gamepad::default();
+        gamepad.digital_mut().press(GamepadButton::South);
+        gamepad.analog_mut().set(GamepadAxis::LeftStickY, 1.0);
+        gamepad.analog_mut().set(GamepadAxis::RightStickY, 0.05);
+        gamepad.analog_mut().set(GamepadAxis::RightStickX, 0.0);
+        let pad = app.world_mut().spawn(gamepad).id();
+        app.update();
+
+        let input = app.world().resource::<GamepadInput>();
+        assert!(input.just_pressed(Action::EnterLevel));
+        assert!(!input.pressed(Action::TrackParticle));
+        assert_eq!(input.movement, Vec2::new(0.0, 1.0));
+        assert_eq!(input.look, Vec2::ZERO, "inside the dead zone");
+        assert_eq!(input.last_device, InputDevice::Gamepad);
+
+        // Inverted right Y: pushing up looks down
+        let mut gamepad = app.world_mut().get_mut::<Gamepad>(pad).unwrap();
+        gamepad.analog_mut().set(GamepadAxis::RightStickY, 1.0);
+        app.update();
+        assert_eq!(app.world().resource::<GamepadInput>().look, Vec2::new(0.0, -1.0));
+    }
+}
@@ -0,0 +1,371 @@
+use bevy::prelude::*;
+use matrix_core::{HistoryMetric, HistorySample};
+use matrix_sim::history::UniverseHistory;
+
+use super::input_capture::CapturesPointer;
+use super::locale::{Strings, Translated};
+use super::settings::{Action, UserSettings, action_just_pressed};
+
+/// Bars across the chart; longer histories are sampled down to this
+const GRAPH_COLUMNS: usize = 120;
+const GRAPH_WIDTH: f32 = 480.0;
+const GRAPH_HEIGHT: f32 = 120.0;
+/// Smallest bar, so a metric at its minimum still shows as a line
+const MIN_BAR: f32 = 0.02;
+
+const TEXT_COLOR: Color = Color::srgba(0.4, 1.0, 0.6, 0.95);
+const BAR_COLOR: Color = Color::srgba(0.3, 0.85, 0.5, 0.9);
+const TAB_COLOR: Color = Color::srgba(0.1, 0.25, 0.15, 0.9);
+const TAB_HOVER_COLOR: Color = Color::srgba(0.15, 0.4, 0.25, 0.95);
+const TAB_ON_COLOR: Color = Color::srgba(0.2, 0.55, 0.3, 0.95);
+
+/// [F11] history graphs state
+#[derive(Resource)]
+pub struct GraphPanel {
+    pub open: bool,
+    pub metric: HistoryMetric,
+    /// History generation and metric the chart was drawn from
+    shown: Option<(u32, HistoryMetric)>,
+}
+
+impl Default for GraphPanel {
+    fn default() -> Self {
+        Self {
+            open: false,
+            metric: HistoryMetric::Temperature,
+            shown: None,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct GraphRoot;
+
+/// A metric tab; clicking graphs that metric
+#[derive(Component)]
+pub struct GraphTab(pub HistoryMetric);
+
+/// One bar of the chart, left to right
+#[derive(Component)]
+pub struct GraphBar(pub usize);
+
+#[derive(Component)]
+pub struct GraphMaxText;
+
+#[derive(Component)]
+pub struct GraphMinText;
+
+/// Under the chart: the span of history it covers
+#[derive(Component)]
+pub struct GraphSpanText;
+
+/// A metric's history squeezed into at most `columns` bars
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    /// Bar heights, 0 (the minimum) to 1 (the maximum)
+    pub heights: Vec<f32>,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Bars for `metric` over `samples`: each column takes the sample nearest its place on
+/// the timeline, the first and last columns the first and last samples. None without samples.
+pub fn sparkline(samples: &[HistorySample], metric: HistoryMetric, columns: usize) -> Option<Sparkline> {
+    let last = samples.len().checked_sub(1)?;
+    let columns = samples.len().min(columns.max(2));
+    let values: Vec<f64> = (0..columns)
+        .map(|c| {
+            let index = (c * last + (columns - 1) / 2) / (columns - 1).max(1);
+            metric.value(&samples[index.min(last)])
+        })
+        .collect();
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let scale = |v: f64| {
+        if metric.log_scale() {
+            v.max(f64::MIN_POSITIVE).log10()
+        } else {
+            v
+        }
+    };
+    let (lo, hi) = (scale(min), scale(max));
+    let heights = values
+        .iter()
+        .map(|&v| {
+            if hi > lo {
+                ((scale(v) - lo) / (hi - lo)) as f32
+            } else {
+                0.5
+            }
+        })
+        .collect();
+    Some(Sparkline { heights, min, max })
+}
+
+/// Locale key of a metric's name
+fn metric_key(metric: HistoryMetric) -> &'static str {
+    match metric {
+        HistoryMetric::Temperature => "graphs.temperature",
+        HistoryMetric::Entropy => "graphs.entropy",
+        HistoryMetric::ScaleFactor => "graphs.scale_factor",
+        HistoryMetric::Particles => "graphs.particles",
+        HistoryMetric::Stars => "graphs.stars",
+        HistoryMetric::Life => "graphs.life",
+        HistoryMetric::Civilizations => "graphs.civilizations",
+        HistoryMetric::Age => "graphs.age",
+    }
+}
+
+fn graph_text(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+    )
+}
+
+/// Spawn the (hidden) graphs panel, bottom center
+pub fn spawn_graph_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(60.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-(GRAPH_WIDTH + 100.0) / 2.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+            GraphRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((graph_text("", 14.0), Translated("graphs.title")));
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(4.0),
+                    row_gap: Val::Px(4.0),
+                    max_width: Val::Px(GRAPH_WIDTH + 100.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for metric in HistoryMetric::ALL {
+                        row.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            BackgroundColor(TAB_COLOR),
+                            GraphTab(metric),
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((graph_text("", 12.0), Translated(metric_key(metric))));
+                        });
+                    }
+                });
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(Node {
+                        width: Val::Px(94.0),
+                        height: Val::Px(GRAPH_HEIGHT),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    })
+                    .with_children(|labels| {
+                        labels.spawn((graph_text("", 11.0), GraphMaxText));
+                        labels.spawn((graph_text("", 11.0), GraphMinText));
+                    });
+                    row.spawn((
+                        Node {
+                            width: Val::Px(GRAPH_WIDTH),
+                            height: Val::Px(GRAPH_HEIGHT),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::FlexEnd,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+                    ))
+                    .with_children(|chart| {
+                        for column in 0..GRAPH_COLUMNS {
+                            chart.spawn((
+                                Node {
+                                    width: Val::Px(GRAPH_WIDTH / GRAPH_COLUMNS as f32),
+                                    height: Val::Percent(0.0),
+                                    display: Display::None,
+                                    ..default()
+                                },
+                                BackgroundColor(BAR_COLOR),
+                                GraphBar(column),
+                            ));
+                        }
+                    });
+                });
+            panel.spawn((graph_text("", 11.0), GraphSpanText));
+        });
+}
+
+/// [F11] shows / hides the graphs
+pub fn graph_keys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut panel: ResMut<GraphPanel>,
+    mut root_q: Query<&mut Node, With<GraphRoot>>,
+) {
+    if !action_just_pressed(&settings, &keyboard, Action::HistoryGraphs) {
+        return;
+    }
+    panel.open = !panel.open;
+    panel.shown = None;
+    for mut node in &mut root_q {
+        node.display = if panel.open { Display::Flex } else { Display::None };
+    }
+}
+
+/// Metric tabs: graph the clicked metric
+pub fn graph_tab_system(
+    mut panel: ResMut<GraphPanel>,
+    mut tab_q: Query<(Ref<Interaction>, &GraphTab, &mut BackgroundColor)>,
+) {
+    for (interaction, tab, _) in &tab_q {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            panel.metric = tab.0;
+        }
+    }
+    if !panel.is_changed() && !tab_q.iter().any(|(i, ..)| i.is_changed()) {
+        return;
+    }
+    for (interaction, tab, mut bg) in &mut tab_q {
+        *bg = BackgroundColor(match (*interaction, tab.0 == panel.metric) {
+            (_, true) => TAB_ON_COLOR,
+            (Interaction::Hovered, false) => TAB_HOVER_COLOR,
+            _ => TAB_COLOR,
+        });
+    }
+}
+
+/// The max, min and span labels, borrowed one at a time
+type GraphLabels<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'static, 'static, &'static mut Text, With<GraphMaxText>>,
+        Query<'static, 'static, &'static mut Text, With<GraphMinText>>,
+        Query<'static, 'static, &'static mut Text, With<GraphSpanText>>,
+    ),
+>;
+
+/// Redraw the chart when a sample lands, another metric is picked or the language changes
+pub fn update_graph_panel(
+    history: Res<UniverseHistory>,
+    strings: Res<Strings>,
+    mut panel: ResMut<GraphPanel>,
+    mut bar_q: Query<(&GraphBar, &mut Node)>,
+    mut texts: GraphLabels,
+) {
+    let key = (history.generation(), panel.metric);
+    if !panel.open || (panel.shown == Some(key) && !strings.is_changed()) {
+        return;
+    }
+    panel.shown = Some(key);
+
+    let metric = panel.metric;
+    let samples = history.samples();
+    let line = sparkline(samples, metric, GRAPH_COLUMNS);
+    let heights = line.as_ref().map_or(&[][..], |l| &l.heights[..]);
+    for (bar, mut node) in &mut bar_q {
+        match heights.get(bar.0) {
+            Some(&h) => {
+                node.display = Display::Flex;
+                node.width = Val::Px(GRAPH_WIDTH / heights.len() as f32);
+                node.height = Val::Percent(h.max(MIN_BAR) * 100.0);
+            }
+            None => node.display = Display::None,
+        }
+    }
+
+    let (max, min) = match &line {
+        Some(line) => (metric.format(line.max), metric.format(line.min)),
+        None => (String::new(), String::new()),
+    };
+    for mut text in &mut texts.p0() {
+        **text = max.clone();
+    }
+    for mut text in &mut texts.p1() {
+        **text = min.clone();
+    }
+    let span = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => strings.tr(
+            if metric.log_scale() { "graphs.span_log" } else { "graphs.span" },
+            &[
+                ("metric", &strings.tr(metric_key(metric), &[])),
+                ("first_cycle", &first.cycle),
+                ("first_age", &strings.number(first.age, 2)),
+                ("last_cycle", &last.cycle),
+                ("last_age", &strings.number(last.age, 2)),
+                ("samples", &samples.len()),
+            ],
+        ),
+        _ => strings.tr("graphs.empty", &[]),
+    };
+    for mut text in &mut texts.p2() {
+        **text = span.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(age: f64, particles: u64) -> HistorySample {
+        HistorySample {
+            age,
+            temperature: 1e10 / (age + 1e-3),
+            particles,
+            ..HistorySample::default()
+        }
+    }
+
+    #[test]
+    fn test_sparkline_spans_the_history() {
+        assert!(sparkline(&[], HistoryMetric::Particles, GRAPH_COLUMNS).is_none());
+
+        let samples: Vec<_> = (0..1000).map(|i| sample(i as f64 * 0.01, 1000 - i)).collect();
+        let line = sparkline(&samples, HistoryMetric::Particles, GRAPH_COLUMNS).unwrap();
+        assert_eq!(line.heights.len(), GRAPH_COLUMNS);
+        assert_eq!((line.min, line.max), (1.0, 1000.0));
+        // First and last samples land in the first and last bars
+        assert_eq!(line.heights[0], 1.0);
+        assert_eq!(*line.heights.last().unwrap(), 0.0);
+        assert!(line.heights.windows(2).all(|w| w[1] <= w[0]));
+
+        // Fewer samples than columns: one bar each
+        let line = sparkline(&samples[..3], HistoryMetric::Temperature, GRAPH_COLUMNS).unwrap();
+        assert_eq!(line.heights.len(), 3);
+        assert_eq!((line.heights[0], line.heights[2]), (1.0, 0.0));
+        // Temperature is on a log scale: the middle sample isn't squashed to the bottom
+        assert!(line.heights[1] > 0.1);
+
+        // A flat series sits mid-chart
+        let flat = vec![sample(1.0, 5); 10];
+        let line = sparkline(&flat, HistoryMetric::Particles, GRAPH_COLUMNS).unwrap();
+        assert!(line.heights.iter().all(|&h| h == 0.5));
+    }
+}
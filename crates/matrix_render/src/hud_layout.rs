@@ -0,0 +1,246 @@
+//! HUD sizes for the window: a UI scale from the window height (or `UserSettings::ui_scale`)
+//! applied to the HUD fonts and panel widths, the minimap as a share of the window, the
+//! right-hand panels kept clear of it, and the [F1] help / [F2] compact HUD toggles.
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use super::photo::PhotoMode;
+use super::settings::{Action, UserSettings, action_just_pressed};
+
+/// Window height (logical pixels) the HUD's base sizes are made for
+const REFERENCE_HEIGHT: f32 = 1080.0;
+/// Range of the UI scale, automatic or set in the settings
+pub const MIN_UI_SCALE: f32 = 0.6;
+pub const MAX_UI_SCALE: f32 = 2.5;
+/// Gap between the HUD panels and the window edges at scale 1 (logical pixels)
+const EDGE_MARGIN: f32 = 10.0;
+
+/// Current HUD scale and placement
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct HudLayout {
+    /// Multiplier for the HUD's font sizes and panel widths
+    pub scale: f32,
+    /// Window size (logical pixels)
+    pub window: Vec2,
+    /// Minimap edge, in the bottom-right corner (logical pixels)
+    pub minimap_edge: f32,
+    /// Gap to the window edges (logical pixels)
+    pub margin: f32,
+    /// [F1] the navigation help is shown under the stats
+    pub help_open: bool,
+    /// [F2] only age, phase and speed are shown
+    pub compact: bool,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            window: Vec2::new(1280.0, REFERENCE_HEIGHT),
+            minimap_edge: 280.0,
+            margin: EDGE_MARGIN,
+            help_open: false,
+            compact: false,
+        }
+    }
+}
+
+impl HudLayout {
+    /// Sizes for a `window` of that many logical pixels; the toggles are kept
+    pub fn fit(&self, window: Vec2, settings: &UserSettings) -> Self {
+        let scale = settings
+            .ui_scale
+            .unwrap_or(window.y / REFERENCE_HEIGHT)
+            .clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        let minimap_edge = (window.y * settings.minimap_percent as f32 / 100.0).min(window.x / 3.0);
+        Self {
+            scale,
+            window,
+            minimap_edge,
+            margin: EDGE_MARGIN * scale,
+            ..self.clone()
+        }
+    }
+
+    /// Minimap viewport in the bottom-right corner of a `window` of that many physical
+    /// pixels: (position, size)
+    pub fn minimap_viewport(&self, window: UVec2, scale_factor: f32) -> (UVec2, UVec2) {
+        let edge = (self.minimap_edge * scale_factor).round() as u32;
+        let margin = (self.margin * scale_factor).round() as u32;
+        let size = UVec2::splat(edge.min(window.x).min(window.y).max(1));
+        (window.saturating_sub(size + margin), size)
+    }
+
+    /// Tallest a right-hand panel may grow without reaching the minimap (logical pixels)
+    pub fn right_panel_height(&self) -> f32 {
+        (self.window.y - self.minimap_edge - 3.0 * self.margin).max(0.0)
+    }
+}
+
+/// Base sizes (at scale 1) of a HUD node that `hud_layout_system` scales
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct HudScaled {
+    /// Of the node's `TextFont`; also scaled by `UserSettings::hud_text_scale`
+    pub font_size: Option<f32>,
+    pub width: Option<f32>,
+    pub max_width: Option<f32>,
+    /// Top-right panel: its height is capped to stay clear of the minimap
+    pub above_minimap: bool,
+}
+
+impl HudScaled {
+    pub fn font(size: f32) -> Self {
+        Self {
+            font_size: Some(size),
+            ..default()
+        }
+    }
+}
+
+/// HUD part hidden by the [F2] compact HUD
+#[derive(Component)]
+pub struct HudExtra;
+
+/// [F1] shows or hides the navigation help, [F2] switches the compact HUD
+pub fn hud_keys_system(
+    settings: Res<UserSettings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut layout: ResMut<HudLayout>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::HudHelp) {
+        layout.help_open = !layout.help_open;
+    }
+    if action_just_pressed(&settings, &keyboard, Action::CompactHud) {
+        layout.compact = !layout.compact;
+        info!("HUD: compact {}", if layout.compact { "on" } else { "off" });
+    }
+}
+
+/// Refit the layout when the window is resized or the settings change, and apply it
+/// to the scaled nodes and the compact HUD (photo mode keeps its own visibilities)
+pub fn hud_layout_system(
+    mut resized: EventReader<WindowResized>,
+    (settings, photo): (Res<UserSettings>, Res<PhotoMode>),
+    mut layout: ResMut<HudLayout>,
+    window_q: Query<&Window, With<PrimaryWindow>>,
+    added_q: Query<(), Added<HudScaled>>,
+    mut scaled_q: Query<(&HudScaled, &mut Node, Option<&mut TextFont>)>,
+    mut extra_q: Query<&mut Visibility, With<HudExtra>>,
+) {
+    let resized = resized.read().count() > 0;
+    if !resized && !settings.is_changed() && !layout.is_changed() && !photo.is_changed() && added_q.is_empty() {
+        return;
+    }
+    if let Ok(window) = window_q.get_single() {
+        let fitted = layout.fit(Vec2::new(window.width(), window.height()), &settings);
+        layout.set_if_neq(fitted);
+    }
+
+    let scale = layout.scale;
+    for (scaled, mut node, font) in &mut scaled_q {
+        if let (Some(size), Some(mut font)) = (scaled.font_size, font) {
+            font.font_size = size * scale * settings.hud_text_scale;
+        }
+        if let Some(width) = scaled.width {
+            node.width = Val::Px(width * scale);
+        }
+        if let Some(width) = scaled.max_width {
+            node.max_width = Val::Px(width * scale);
+        }
+        if scaled.above_minimap {
+            node.max_height = Val::Px(layout.right_panel_height());
+            node.overflow = Overflow::clip_y();
+        }
+    }
+    if photo.active {
+        return;
+    }
+    let visibility = if layout.compact { Visibility::Hidden } else { Visibility::Inherited };
+    for mut extra in &mut extra_q {
+        extra.set_if_neq(visibility);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::window::WindowResolution;
+
+    #[test]
+    fn test_layout_follows_window_resizes() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            bevy::window::WindowPlugin {
+                primary_window: Some(Window {
+                    resolution: WindowResolution::new(1366.0, 768.0).with_scale_factor_override(1.0),
+                    ..default()
+                }),
+                ..default()
+            },
+        ))
+        .insert_resource(UserSettings::default())
+        .init_resource::<PhotoMode>()
+        .init_resource::<HudLayout>()
+        .add_systems(Update, hud_layout_system);
+        let panel = app
+            .world_mut()
+            .spawn((
+                Node::default(),
+                TextFont::default(),
+                HudScaled {
+                    font_size: Some(14.0),
+                    max_width: Some(500.0),
+                    above_minimap: true,
+                    ..default()
+                },
+            ))
+            .id();
+        let extra = app.world_mut().spawn((Node::default(), Visibility::Inherited, HudExtra)).id();
+        let sizes = |app: &App| {
+            let node = app.world().get::<Node>(panel).unwrap();
+            let font = app.world().get::<TextFont>(panel).unwrap().font_size;
+            let (Val::Px(width), Val::Px(height)) = (node.max_width, node.max_height) else {
+                panic!("panel not sized: {node:?}");
+            };
+            (font, width, height)
+        };
+        app.update();
+
+        // 1366×768: smaller than at 1080p, and the panel stops above the minimap
+        let (font, width, height) = sizes(&app);
+        let layout = app.world().resource::<HudLayout>().clone();
+        assert!(font < 14.0 && width < 500.0, "{font} {width}");
+        let (position, size) = layout.minimap_viewport(UVec2::new(1366, 768), 1.0);
+        assert!(size.x < 280 && position.x + size.x < 1366 && position.y + size.y < 768);
+        assert!(layout.margin + height < position.y as f32, "{height} over the minimap at {position}");
+
+        // Resized to 4K: everything grows
+        let window = {
+            let mut window_q = app.world_mut().query_filtered::<(Entity, &mut Window), With<PrimaryWindow>>();
+            let (entity, mut window) = window_q.single_mut(app.world_mut());
+            window.resolution.set(3840.0, 2160.0);
+            entity
+        };
+        app.world_mut().send_event(WindowResized {
+            window,
+            width: 3840.0,
+            height: 2160.0,
+        });
+        app.update();
+        let (font_4k, width_4k, height_4k) = sizes(&app);
+        assert!(font_4k > 2.0 * font && width_4k > 2.0 * width && height_4k > height);
+        assert_eq!(app.world().resource::<HudLayout>().scale, 2.0);
+
+        // A fixed scale wins over the window height
+        app.world_mut().resource_mut::<UserSettings>().ui_scale = Some(1.0);
+        app.update();
+        assert_eq!(sizes(&app).0, 14.0);
+
+        // Compact HUD hides the extras
+        app.world_mut().resource_mut::<HudLayout>().compact = true;
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(extra), Some(&Visibility::Hidden));
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::PrimaryWindow;
+
+use super::camera::{MinimapCamera, cursor_in_viewport};
+use super::console::ConsoleState;
+
+/// Whether the UI has the mouse this frame. Set in `PreUpdate` by `update_ui_capture`;
+/// the camera and picking systems leave the mouse alone while it is set.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiInputCapture {
+    /// The cursor is over a panel, a button or the minimap
+    pub pointer: bool,
+    /// A text field (the console) is taking the keyboard
+    pub text_input: bool,
+}
+
+impl UiInputCapture {
+    /// Mouse look and scrolling are off
+    pub fn blocks_mouse(&self) -> bool {
+        self.pointer || self.text_input
+    }
+}
+
+/// A panel that keeps clicks and scrolling from reaching the world behind it while the
+/// cursor is over it (hidden panels have no size and never do)
+#[derive(Component, Default)]
+#[require(RelativeCursorPosition)]
+pub struct CapturesPointer;
+
+/// Check the cursor against the panels, buttons and the minimap viewport.
+/// Runs in `PreUpdate`, after Bevy's UI focus system has placed the cursor on the nodes.
+pub fn update_ui_capture(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    console: Res<ConsoleState>,
+    panel_q: Query<&RelativeCursorPosition, With<CapturesPointer>>,
+    interaction_q: Query<&Interaction>,
+    minimap_q: Query<&Camera, With<MinimapCamera>>,
+    mut capture: ResMut<UiInputCapture>,
+) {
+    let over_minimap = windows.get_single().ok().is_some_and(|window| {
+        window.cursor_position().is_some_and(|cursor| {
+            minimap_q.iter().any(|camera| {
+                camera.is_active
+                    && camera
+                        .viewport
+                        .as_ref()
+                        .is_some_and(|vp| cursor_in_viewport(cursor, vp, window.scale_factor()).is_some())
+            })
+        })
+    });
+    let pointer = over_minimap
+        || panel_q.iter().any(RelativeCursorPosition::mouse_over)
+        || interaction_q.iter().any(|i| *i != Interaction::None);
+    capture.set_if_neq(UiInputCapture {
+        pointer,
+        text_input: console.open,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::{FlyCamera, ZoomLevel};
+    use crate::cosmos::RegionVisual;
+    use crate::surface::{self, PlanetSelection};
+    use bevy::render::camera::{CameraProjectionPlugin, ManualTextureViews, Viewport};
+    use bevy::window::WindowResolution;
+
+    /// Put the cursor at `pos` and click once
+    fn click_at(app: &mut App, pos: Vec2) {
+        let mut window_q = app.world_mut().query_filtered::<&mut Window, With<PrimaryWindow>>();
+        window_q.single_mut(app.world_mut()).set_cursor_position(Some(pos));
+        let mut mouse = app.world_mut().resource_mut::<ButtonInput<MouseButton>>();
+        mouse.release(MouseButton::Left);
+        mouse.clear();
+        mouse.press(MouseButton::Left);
+        app.update();
+    }
+
+    #[test]
+    fn test_click_over_minimap_does_not_select() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::window::WindowPlugin {
+                primary_window: Some(Window {
+                    resolution: WindowResolution::new(800.0, 600.0).with_scale_factor_override(1.0),
+                    ..default()
+                }),
+                ..default()
+            },
+            CameraProjectionPlugin::<Projection>::default(),
+        ))
+        .init_asset::<Image>()
+        .init_resource::<ManualTextureViews>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<ConsoleState>()
+        .init_resource::<PlanetSelection>()
+        .init_resource::<UiInputCapture>()
+        .add_systems(Update, (update_ui_capture, surface::region_hover_system).chain());
+
+        // A region sprite filling the view, the minimap over the bottom-right corner of it
+        let cam_tf = Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y);
+        app.world_mut().spawn((
+            Camera3d::default(),
+            cam_tf,
+            GlobalTransform::from(cam_tf),
+            FlyCamera {
+                zoom_level: ZoomLevel::Cosmic,
+                ..default()
+            },
+        ));
+        app.world_mut().spawn((
+            Camera {
+                order: 1,
+                viewport: Some(Viewport {
+                    physical_position: UVec2::new(500, 300),
+                    physical_size: UVec2::new(290, 290),
+                    ..default()
+                }),
+                ..default()
+            },
+            MinimapCamera,
+        ));
+        app.world_mut().spawn((
+            Transform::from_scale(Vec3::splat(9.0)),
+            MeshMaterial3d::<StandardMaterial>::default(),
+            RegionVisual { region_id: 7 },
+        ));
+        app.update();
+
+        click_at(&mut app, Vec2::new(650.0, 450.0));
+        assert!(app.world().resource::<UiInputCapture>().pointer);
+        let selection = app.world().resource::<PlanetSelection>();
+        assert!(selection.selected_region.is_none() && selection.hovered_region.is_none());
+
+        // The same sprite away from the minimap is picked
+        click_at(&mut app, Vec2::new(300.0, 200.0));
+        assert!(!app.world().resource::<UiInputCapture>().pointer);
+        assert_eq!(app.world().resource::<PlanetSelection>().selected_region, Some(7));
+    }
+}
@@ -0,0 +1,573 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use matrix_core::RingSystem;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::surface::PlanetSelection;
+
+/// One star drawn by the shared star mesh
+#[derive(Clone, Debug)]
+pub struct StarInstance {
+    pub star_id: u64,
+    pub position: Vec3,
+    pub radius: f32,
+    pub color: LinearRgba,
+}
+
+/// One planet drawn by the shared planet mesh
+#[derive(Clone, Debug)]
+pub struct PlanetInstance {
+    pub planet_id: u64,
+    pub star_id: u64,
+    /// Index of the host star in `LazyUniverse::loaded_stars` (valid until the next rebuild)
+    pub star_index: usize,
+    /// Index of the planet in its star's `planets`
+    pub planet_index: usize,
+    pub position: Vec3,
+    /// Current radius (life planets pulse around `base_radius`)
+    pub radius: f32,
+    pub base_radius: f32,
+    pub color: LinearRgba,
+    pub has_life: bool,
+    pub has_tech: bool,
+    /// Set for a moon: the planet it circles
+    pub moon: Option<MoonVisual>,
+    /// Cloud bands of a giant: drawn on the finer banded sphere instead of `color`
+    pub bands: Option<PlanetBands>,
+    /// Drawn by the ring mesh around the planet
+    pub rings: Option<RingSystem>,
+}
+
+impl PlanetInstance {
+    /// Equator tilted with the rings, so the bands and rings line up
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_x(self.rings.map_or(0.0, |r| r.tilt as f32))
+    }
+}
+
+/// Latitude bands of a giant: a 1D color gradient from the south to the north pole
+#[derive(Clone, Debug)]
+pub struct PlanetBands([LinearRgba; BAND_STOPS]);
+
+impl PlanetBands {
+    /// Alternating pale zones and dark belts of `base`, fixed by the planet id
+    pub fn generate(base: LinearRgba, planet_id: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(planet_id ^ BANDS_SEED);
+        let tint: [f32; 3] = std::array::from_fn(|_| rng.gen_range(0.85..1.15));
+        Self(std::array::from_fn(|i| {
+            let zone = if i % 2 == 0 { 1.15 } else { 0.7 };
+            let shade = zone * rng.gen_range(0.8..1.2);
+            LinearRgba::rgb(
+                base.red * tint[0] * shade,
+                base.green * tint[1] * shade,
+                base.blue * tint[2] * shade,
+            )
+        }))
+    }
+
+    /// Color at height `y` of the unit sphere (-1 at the south pole, 1 at the north pole),
+    /// the stops spread evenly in latitude
+    pub fn at(&self, y: f32) -> LinearRgba {
+        let latitude = y.clamp(-1.0, 1.0).asin() / std::f32::consts::FRAC_PI_2;
+        let t = (latitude + 1.0) / 2.0 * (BAND_STOPS - 1) as f32;
+        let i = (t.floor() as usize).min(BAND_STOPS - 2);
+        self.0[i].mix(&self.0[i + 1], t - i as f32)
+    }
+}
+
+/// Where a moon instance's parent is, to move it along with it
+#[derive(Clone, Debug)]
+pub struct MoonVisual {
+    /// Index of the parent in its star's `planets` (the moon's own index is `planet_index`
+    /// into the parent's `moons`)
+    pub parent_index: usize,
+    /// Render radius of the parent: moon orbits are drawn around it, not to scale
+    pub parent_radius: f32,
+}
+
+/// Marker for the entity drawing every star instance
+#[derive(Component)]
+pub struct StarInstances;
+
+/// Marker for the entity drawing every planet instance
+#[derive(Component)]
+pub struct PlanetInstances;
+
+/// Marker for the entity drawing the rings of every planet instance
+#[derive(Component)]
+pub struct RingInstances;
+
+/// Color of the star / planet under the cursor
+const HIGHLIGHT_COLOR: LinearRgba = LinearRgba::rgb(1.0, 1.0, 0.32);
+/// Color stops of a giant's cloud bands, pole to pole
+const BAND_STOPS: usize = 9;
+/// Offset of the cloud band stream from the planet id
+const BANDS_SEED: u64 = 0xBA4D;
+/// Longitude and latitude divisions of the banded sphere
+const BANDED_SECTORS: u32 = 16;
+const BANDED_STACKS: u32 = 12;
+/// Radial bands (each with its own opacity) and angular segments of a ring mesh
+const RING_BANDS: usize = 8;
+const RING_SEGMENTS: usize = 48;
+/// Offset of the ring band stream from the planet id
+const RINGS_SEED: u64 = 0x5A7;
+
+/// Unit sphere copied once per instance
+struct SphereTemplate {
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+}
+
+impl SphereTemplate {
+    /// Lowest-poly icosphere (12 vertices, 20 triangles)
+    fn ico() -> Self {
+        Self::from_mesh(&Sphere::new(1.0).mesh().ico(0).unwrap())
+    }
+
+    /// UV sphere with rows of vertices along the latitudes, for cloud bands
+    fn banded() -> Self {
+        Self::from_mesh(&Sphere::new(1.0).mesh().uv(BANDED_SECTORS, BANDED_STACKS))
+    }
+
+    fn from_mesh(mesh: &Mesh) -> Self {
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(p)) => p.iter().map(|&v| Vec3::from(v)).collect(),
+            _ => Vec::new(),
+        };
+        let indices = mesh
+            .indices()
+            .map(|i| i.iter().map(|i| i as u32).collect())
+            .unwrap_or_default();
+        Self { positions, indices }
+    }
+}
+
+/// One sphere copy written by `fill_mesh`
+struct MeshItem<'a> {
+    template: &'a SphereTemplate,
+    center: Vec3,
+    radius: f32,
+    rotation: Quat,
+    color: LinearRgba,
+    /// Colors by latitude instead of `color`
+    bands: Option<&'a PlanetBands>,
+}
+
+impl<'a> MeshItem<'a> {
+    fn flat(template: &'a SphereTemplate, center: Vec3, radius: f32, color: LinearRgba) -> Self {
+        Self {
+            template,
+            center,
+            radius,
+            rotation: Quat::IDENTITY,
+            color,
+            bands: None,
+        }
+    }
+}
+
+/// CPU-side star and planet lists, drawn as two merged meshes (one draw call each)
+/// with a shared vertex-colored material, plus a translucent mesh for planet rings.
+/// Rebuilt by `cosmos::update_cosmos_visuals`; the planet and ring meshes follow orbits
+/// and pulsing every frame. Picking ray-tests these lists.
+#[derive(Resource)]
+pub struct CosmosInstances {
+    pub stars: Vec<StarInstance>,
+    pub planets: Vec<PlanetInstance>,
+    /// Star list changed: re-upload the star mesh
+    pub stars_dirty: bool,
+    /// Planet list changed: re-upload colors and indices along with positions
+    pub planets_dirty: bool,
+    star_mesh: Handle<Mesh>,
+    planet_mesh: Handle<Mesh>,
+    ring_mesh: Handle<Mesh>,
+    template: SphereTemplate,
+    /// Sphere of planets with cloud bands
+    banded: SphereTemplate,
+    /// Hovered (star, planet) the meshes were last written with
+    drawn_hover: (Option<u64>, Option<u64>),
+}
+
+impl CosmosInstances {
+    pub fn clear(&mut self) {
+        self.stars.clear();
+        self.planets.clear();
+        self.stars_dirty = true;
+        self.planets_dirty = true;
+    }
+}
+
+/// Startup: the instance meshes, their entities, the material the stars and planets
+/// share and the translucent ring material
+pub fn init_instances(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let template = SphereTemplate::ico();
+    let mut empty = || {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        fill_mesh(&mut mesh, std::iter::empty());
+        meshes.add(mesh)
+    };
+    let star_mesh = empty();
+    let planet_mesh = empty();
+    let ring_mesh = empty();
+
+    // Vertex colors carry the per-instance color
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        ..default()
+    });
+
+    // Vertices move without the AABB being recomputed: never cull
+    commands.spawn((
+        Mesh3d(star_mesh.clone()),
+        MeshMaterial3d(material.clone()),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        StarInstances,
+    ));
+    commands.spawn((
+        Mesh3d(planet_mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        PlanetInstances,
+    ));
+    // Vertex alphas carry the ring bands; seen from both sides
+    let ring_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        cull_mode: None,
+        double_sided: true,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(ring_mesh.clone()),
+        MeshMaterial3d(ring_material),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        RingInstances,
+    ));
+
+    commands.insert_resource(CosmosInstances {
+        stars: Vec::new(),
+        planets: Vec::new(),
+        stars_dirty: false,
+        planets_dirty: false,
+        star_mesh,
+        planet_mesh,
+        ring_mesh,
+        template,
+        banded: SphereTemplate::banded(),
+        drawn_hover: (None, None),
+    });
+}
+
+/// Write changed instance data into the meshes. Stars only when the list or the hover
+/// changed; planets and rings every frame while there are any (orbits move them).
+pub fn upload_instances(
+    mut instances: ResMut<CosmosInstances>,
+    selection: Res<PlanetSelection>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let instances = &mut *instances;
+    let hover = (selection.hovered_star, selection.hovered.map(|(_, planet_id)| planet_id));
+
+    if instances.stars_dirty || hover.0 != instances.drawn_hover.0 {
+        if let Some(mesh) = meshes.get_mut(&instances.star_mesh) {
+            let items = instances.stars.iter().map(|s| {
+                let color = if hover.0 == Some(s.star_id) { HIGHLIGHT_COLOR } else { s.color };
+                MeshItem::flat(&instances.template, s.position, s.radius, color)
+            });
+            fill_mesh(mesh, items);
+        }
+        instances.stars_dirty = false;
+    }
+
+    if instances.planets_dirty || !instances.planets.is_empty() {
+        let (template, banded) = (&instances.template, &instances.banded);
+        let template_of = |p: &PlanetInstance| if p.bands.is_some() { banded } else { template };
+        if let Some(mesh) = meshes.get_mut(&instances.planet_mesh) {
+            if instances.planets_dirty || hover.1 != instances.drawn_hover.1 {
+                let items = instances.planets.iter().map(|p| {
+                    let hovered = hover.1 == Some(p.planet_id);
+                    MeshItem {
+                        template: template_of(p),
+                        center: p.position,
+                        radius: p.radius,
+                        rotation: p.rotation(),
+                        color: if hovered { HIGHLIGHT_COLOR } else { p.color },
+                        bands: p.bands.as_ref().filter(|_| !hovered),
+                    }
+                });
+                fill_mesh(mesh, items);
+            } else {
+                let items = instances
+                    .planets
+                    .iter()
+                    .map(|p| (template_of(p), p.position, p.radius, p.rotation()));
+                update_positions(mesh, items);
+            }
+        }
+        if let Some(mesh) = meshes.get_mut(&instances.ring_mesh) {
+            let ringed = instances.planets.iter().filter(|p| p.rings.is_some());
+            if instances.planets_dirty {
+                fill_rings(mesh, ringed);
+            } else {
+                update_ring_positions(mesh, ringed);
+            }
+        }
+        instances.planets_dirty = false;
+    }
+
+    instances.drawn_hover = hover;
+}
+
+/// Rewrite `mesh` as one scaled, rotated, colored template copy per item
+fn fill_mesh<'a>(mesh: &mut Mesh, items: impl Iterator<Item = MeshItem<'a>>) {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for item in items {
+        let base = positions.len() as u32;
+        for &v in &item.template.positions {
+            let normal = item.rotation * v;
+            positions.push((item.center + normal * item.radius).to_array());
+            normals.push(normal.to_array());
+            let color = item.bands.map_or(item.color, |bands| bands.at(v.y));
+            colors.push(color.to_f32_array());
+        }
+        indices.extend(item.template.indices.iter().map(|&idx| base + idx));
+    }
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+}
+
+/// Move the instances of an already filled mesh (same count, order and templates)
+fn update_positions<'a>(
+    mesh: &mut Mesh,
+    items: impl Iterator<Item = (&'a SphereTemplate, Vec3, f32, Quat)>,
+) {
+    let mut positions = Vec::new();
+    for (template, center, radius, rotation) in items {
+        positions.extend(
+            template
+                .positions
+                .iter()
+                .map(|&v| (center + rotation * v * radius).to_array()),
+        );
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+}
+
+/// Opacity of each radial stop of a planet's rings: uneven bands with a dark gap,
+/// fixed by the planet id, scaled by the ring density
+fn ring_alphas(planet_id: u64, rings: &RingSystem) -> [f32; RING_BANDS + 1] {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id ^ RINGS_SEED);
+    let gap = rng.gen_range(2..RING_BANDS - 1);
+    std::array::from_fn(|k| {
+        let band = if k == gap { 0.05 } else { rng.gen_range(0.35..1.0) };
+        // Fade out at the inner and outer edge
+        let edge = if k == 0 || k == RING_BANDS { 0.3 } else { 1.0 };
+        rings.density as f32 * band * edge
+    })
+}
+
+/// Vertices of a planet's ring annulus: `RING_SEGMENTS` around each radial stop, inner
+/// to outer, in the equatorial plane of the (tilted) planet
+fn ring_positions(planet: &PlanetInstance, rings: &RingSystem) -> impl Iterator<Item = [f32; 3]> {
+    let rotation = planet.rotation();
+    let (center, radius) = (planet.position, planet.radius);
+    (0..=RING_BANDS).flat_map(move |k| {
+        let r = rings.inner_radius + (rings.outer_radius - rings.inner_radius) * k as f64 / RING_BANDS as f64;
+        (0..RING_SEGMENTS).map(move |s| {
+            let angle = s as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+            let v = Vec3::new(angle.cos(), 0.0, angle.sin()) * r as f32;
+            (center + rotation * v * radius).to_array()
+        })
+    })
+}
+
+/// Rewrite `mesh` as the rings of every planet that has them
+fn fill_rings<'a>(mesh: &mut Mesh, planets: impl Iterator<Item = &'a PlanetInstance>) {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for planet in planets {
+        let Some(rings) = &planet.rings else {
+            continue;
+        };
+        let base = positions.len() as u32;
+        positions.extend(ring_positions(planet, rings));
+        let normal = (planet.rotation() * Vec3::Y).to_array();
+        normals.resize(positions.len(), normal);
+        // Paler than the planet, as ice and dust
+        let tint = planet.color.mix(&LinearRgba::WHITE, 0.5);
+        for alpha in ring_alphas(planet.planet_id, rings) {
+            colors.extend(std::iter::repeat_n(tint.with_alpha(alpha).to_f32_array(), RING_SEGMENTS));
+        }
+        for k in 0..RING_BANDS as u32 {
+            for s in 0..RING_SEGMENTS as u32 {
+                let next = (s + 1) % RING_SEGMENTS as u32;
+                let inner = base + k * RING_SEGMENTS as u32;
+                let outer = inner + RING_SEGMENTS as u32;
+                indices.extend([inner + s, outer + s, inner + next, inner + next, outer + s, outer + next]);
+            }
+        }
+    }
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+}
+
+/// Move the rings of an already filled ring mesh (same planets in the same order)
+fn update_ring_positions<'a>(mesh: &mut Mesh, planets: impl Iterator<Item = &'a PlanetInstance>) {
+    let positions: Vec<[f32; 3]> = planets
+        .filter_map(|p| p.rings.as_ref().map(|rings| ring_positions(p, rings)))
+        .flatten()
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_mesh_offsets_indices_per_instance() {
+        let template = SphereTemplate::ico();
+        let n = template.positions.len();
+        assert!(n > 0 && !template.indices.is_empty());
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        let items = [
+            MeshItem::flat(&template, Vec3::ZERO, 1.0, LinearRgba::RED),
+            MeshItem::flat(&template, Vec3::new(10.0, 0.0, 0.0), 2.0, LinearRgba::BLUE),
+        ];
+        fill_mesh(&mut mesh, items.into_iter());
+
+        assert_eq!(mesh.count_vertices(), 2 * n);
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        assert_eq!(indices.len(), 2 * template.indices.len());
+        let (first, second) = indices.split_at(template.indices.len());
+        assert!(first.iter().all(|&i| i < n));
+        assert!(second.iter().all(|&i| (n..2 * n).contains(&i)));
+
+        // Second instance: centered at x=10 with radius 2
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("positions missing");
+        };
+        for p in &positions[n..] {
+            let d = Vec3::from(*p).distance(Vec3::new(10.0, 0.0, 0.0));
+            assert!((d - 2.0).abs() < 1e-4);
+        }
+
+        // Moving keeps the vertex count and shifts the vertices
+        let moved = [(&template, Vec3::ONE, 1.0, Quat::IDENTITY), (&template, Vec3::ZERO, 1.0, Quat::IDENTITY)];
+        update_positions(&mut mesh, moved.into_iter());
+        assert_eq!(mesh.count_vertices(), 2 * n);
+    }
+
+    fn giant(rings: Option<RingSystem>) -> PlanetInstance {
+        let color = LinearRgba::rgb(0.8, 0.7, 0.5);
+        PlanetInstance {
+            planet_id: 3_001,
+            star_id: 3,
+            star_index: 0,
+            planet_index: 0,
+            position: Vec3::new(5.0, 0.0, 0.0),
+            radius: 1.5,
+            base_radius: 1.5,
+            color,
+            has_life: false,
+            has_tech: false,
+            moon: None,
+            bands: Some(PlanetBands::generate(color, 3_001)),
+            rings,
+        }
+    }
+
+    #[test]
+    fn test_bands_are_fixed_by_the_planet() {
+        let color = LinearRgba::rgb(0.8, 0.7, 0.5);
+        let bands = PlanetBands::generate(color, 7);
+        assert_eq!(bands.0, PlanetBands::generate(color, 7).0);
+        assert_ne!(bands.0, PlanetBands::generate(color, 8).0);
+        // The poles are the end stops, and zones alternate with darker belts
+        assert!((bands.at(-1.0).red - bands.0[0].red).abs() < 1e-5);
+        assert!((bands.at(1.0).red - bands.0[BAND_STOPS - 1].red).abs() < 1e-5);
+        assert!(bands.0[0].red > bands.0[1].red && bands.0[2].red > bands.0[1].red);
+
+        // Banded giants use the finer sphere, colored by latitude
+        let template = SphereTemplate::banded();
+        let planet = giant(None);
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        let item = MeshItem {
+            bands: planet.bands.as_ref(),
+            ..MeshItem::flat(&template, planet.position, planet.radius, planet.color)
+        };
+        fill_mesh(&mut mesh, std::iter::once(item));
+        assert_eq!(mesh.count_vertices(), template.positions.len());
+        let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR) else {
+            panic!("colors missing");
+        };
+        let distinct: std::collections::HashSet<_> = colors.iter().map(|c| c.map(f32::to_bits)).collect();
+        assert!(distinct.len() >= BAND_STOPS);
+    }
+
+    #[test]
+    fn test_rings_surround_their_planet() {
+        let rings = RingSystem {
+            inner_radius: 1.4,
+            outer_radius: 2.6,
+            density: 0.7,
+            tilt: 0.3,
+        };
+        let planets = [giant(Some(rings)), giant(None)];
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        fill_rings(&mut mesh, planets.iter());
+
+        // Only the ringed planet: one annulus of RING_BANDS quads around
+        let vertices = (RING_BANDS + 1) * RING_SEGMENTS;
+        assert_eq!(mesh.count_vertices(), vertices);
+        assert_eq!(mesh.indices().unwrap().len(), RING_BANDS * RING_SEGMENTS * 6);
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("positions missing");
+        };
+        let normal = Quat::from_rotation_x(0.3) * Vec3::Y;
+        for p in positions {
+            let offset = Vec3::from(*p) - planets[0].position;
+            let r = offset.length() / planets[0].radius;
+            assert!((1.4 - 1e-4..=2.6 + 1e-4).contains(&r), "{r}");
+            assert!(offset.dot(normal).abs() < 1e-4);
+        }
+        let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR) else {
+            panic!("colors missing");
+        };
+        assert!(colors.iter().all(|c| c[3] > 0.0 && c[3] <= 0.7));
+
+        // Following the planet keeps the vertex count
+        let mut moved = planets.clone();
+        moved[0].position = Vec3::ZERO;
+        update_ring_positions(&mut mesh, moved.iter());
+        assert_eq!(mesh.count_vertices(), vertices);
+    }
+}
@@ -0,0 +1,495 @@
+use bevy::prelude::*;
+use matrix_core::{LifeDiscovery, Planet, Star};
+use matrix_sim::lazy_universe::LazyUniverse;
+
+use super::camera::{self, CameraGlide, FlyCamera};
+use super::cosmos::orbit_offset;
+use super::input_capture::CapturesPointer;
+use super::locale::{Strings, Translated};
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{self, PlanetSelection};
+
+/// Rows shown at once; the list scrolls to keep the selected row in view
+const JOURNAL_ROWS: usize = 15;
+/// Lowest complexity the "Complex" filter keeps
+const COMPLEX_LIFE: f64 = 5.0;
+/// Where the camera stops relative to a planet it flies to
+const PLANET_VIEW_OFFSET: Vec3 = Vec3::new(0.0, 2.0, 6.0);
+/// Column widths (px): name, region, complexity, substrate, tech, discovered, Go
+const COLUMNS: [f32; 7] = [150.0, 110.0, 80.0, 110.0, 50.0, 80.0, 40.0];
+
+const ROW_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.0);
+const SELECTED_COLOR: Color = Color::srgba(0.15, 0.4, 0.25, 0.95);
+const BUTTON_COLOR: Color = Color::srgba(0.1, 0.25, 0.15, 0.9);
+const BUTTON_ON_COLOR: Color = Color::srgba(0.2, 0.55, 0.3, 0.95);
+
+/// [Y] life journal state
+#[derive(Resource, Default)]
+pub struct JournalPanel {
+    pub open: bool,
+    /// Active filters, indexed by `JournalFilter as usize`
+    pub filters: [bool; 3],
+    /// Position of the selected row in the filtered list
+    pub selected: usize,
+    /// Planet flown to whose region is still loading: (region, star, planet)
+    pub pending: Option<(u64, u64, u64)>,
+    /// Filters or selection changed since the rows were built
+    dirty: bool,
+    /// Discovery and civilization counts the rows were built from
+    shown: Option<(usize, usize)>,
+}
+
+impl JournalPanel {
+    /// Indices into `lazy.discoveries` of the entries that pass every active filter
+    pub fn entries(&self, lazy: &LazyUniverse) -> Vec<usize> {
+        (0..lazy.discoveries.len())
+            .filter(|&i| {
+                JournalFilter::ALL
+                    .into_iter()
+                    .all(|f| !self.filters[f as usize] || f.matches(lazy, &lazy.discoveries[i]))
+            })
+            .collect()
+    }
+}
+
+/// Marker for the journal root node
+#[derive(Component)]
+pub struct JournalRoot;
+
+/// Marker for the node holding the entry rows
+#[derive(Component)]
+pub struct JournalRows;
+
+/// One filter button; clicking toggles it
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JournalFilter {
+    Civilizations,
+    ExoticSubstrate,
+    Complex,
+}
+
+/// A row's "Go" button, holding the index into `lazy.discoveries`
+#[derive(Component)]
+pub struct JournalGoButton(pub usize);
+
+impl JournalFilter {
+    const ALL: [JournalFilter; 3] = [Self::Civilizations, Self::ExoticSubstrate, Self::Complex];
+
+    fn matches(self, lazy: &LazyUniverse, discovery: &LifeDiscovery) -> bool {
+        match self {
+            Self::Civilizations => {
+                discovery.has_technology
+                    || lazy.civilization_of(discovery.region_id, discovery.planet_id).is_some()
+            }
+            Self::ExoticSubstrate => discovery.genome.is_exotic(),
+            Self::Complex => discovery.complexity >= COMPLEX_LIFE,
+        }
+    }
+
+    /// Locale key of the filter's button
+    fn label_key(self) -> &'static str {
+        match self {
+            Self::Civilizations => "journal.filter_civilizations",
+            Self::ExoticSubstrate => "journal.filter_exotic",
+            Self::Complex => "journal.filter_complex",
+        }
+    }
+}
+
+/// First and one-past-last row shown for `selected` out of `len` rows
+fn visible_rows(selected: usize, len: usize) -> (usize, usize) {
+    let start = selected
+        .saturating_sub(JOURNAL_ROWS / 2)
+        .min(len.saturating_sub(JOURNAL_ROWS));
+    (start, (start + JOURNAL_ROWS).min(len))
+}
+
+/// Spawn the (hidden) journal screen, center
+pub fn spawn_journal_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(12.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-360.0)),
+                width: Val::Px(720.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.92)),
+            JournalRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((journal_text("", 14.0), Translated("journal.title")));
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for filter in JournalFilter::ALL {
+                        row.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                            filter,
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((journal_text("", 13.0), Translated(filter.label_key())));
+                        });
+                    }
+                });
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                })
+                .with_children(|row| {
+                    let headers = [
+                        "journal.planet",
+                        "journal.region",
+                        "journal.complexity",
+                        "journal.substrate",
+                        "journal.tech",
+                        "journal.found",
+                    ];
+                    for (header, width) in headers.into_iter().zip(COLUMNS) {
+                        row.spawn(cell_node(width)).with_children(|cell| {
+                            cell.spawn((journal_text("", 12.0), Translated(header)));
+                        });
+                    }
+                });
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(1.0),
+                    ..default()
+                },
+                JournalRows,
+            ));
+        });
+}
+
+fn journal_text(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+    )
+}
+
+fn cell_node(width: f32) -> Node {
+    Node {
+        width: Val::Px(width),
+        overflow: Overflow::clip_x(),
+        ..default()
+    }
+}
+
+fn spawn_cell(row: &mut ChildBuilder, text: impl Into<String>, width: f32) {
+    row.spawn(cell_node(width)).with_children(|cell| {
+        cell.spawn(journal_text(text, 12.0));
+    });
+}
+
+/// [Y] shows / hides the journal (the pause menu's Life Journal button opens it too);
+/// [↑] / [↓] move the selection and [Enter] flies to it while it's open
+pub fn journal_keys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    lazy: Res<LazyUniverse>,
+    mut panel: ResMut<JournalPanel>,
+    mut selection: ResMut<PlanetSelection>,
+    mut root_q: Query<&mut Node, With<JournalRoot>>,
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::Journal) {
+        panel.open = !panel.open;
+    }
+    let display = if panel.open { Display::Flex } else { Display::None };
+    for mut node in &mut root_q {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !panel.open {
+        return;
+    }
+
+    let entries = panel.entries(&lazy);
+    if keyboard.just_pressed(KeyCode::ArrowDown) && panel.selected + 1 < entries.len() {
+        panel.selected += 1;
+        panel.dirty = true;
+    }
+    if keyboard.just_pressed(KeyCode::ArrowUp) && panel.selected > 0 {
+        panel.selected -= 1;
+        panel.dirty = true;
+    }
+    if keyboard.just_pressed(KeyCode::Enter)
+        && let Some(&index) = entries.get(panel.selected)
+        && let Ok((mut transform, mut cam)) = cam_q.get_single_mut()
+    {
+        go_to_discovery(&lazy, index, &mut panel, &mut selection, &mut transform, &mut cam);
+    }
+}
+
+/// Filter buttons: toggle the filter and restart the selection at the top
+pub fn journal_filter_system(
+    mut panel: ResMut<JournalPanel>,
+    mut button_q: Query<(&Interaction, &JournalFilter, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, filter, mut bg) in &mut button_q {
+        if *interaction == Interaction::Pressed {
+            let on = &mut panel.filters[*filter as usize];
+            *on = !*on;
+            panel.selected = 0;
+            panel.dirty = true;
+        }
+        *bg = BackgroundColor(match (interaction, panel.filters[*filter as usize]) {
+            (_, true) => BUTTON_ON_COLOR,
+            (Interaction::Hovered, false) => SELECTED_COLOR,
+            _ => BUTTON_COLOR,
+        });
+    }
+}
+
+/// Rebuild the rows when the filters, selection or language change, or something is
+/// discovered
+pub fn journal_rows_system(
+    mut commands: Commands,
+    lazy: Res<LazyUniverse>,
+    strings: Res<Strings>,
+    mut panel: ResMut<JournalPanel>,
+    rows_q: Query<Entity, With<JournalRows>>,
+) {
+    let counts = (lazy.discoveries.len(), lazy.civilizations.len());
+    if !panel.open || (!panel.dirty && panel.shown == Some(counts) && !strings.is_changed()) {
+        return;
+    }
+    panel.dirty = false;
+    panel.shown = Some(counts);
+    let Ok(rows) = rows_q.get_single() else {
+        return;
+    };
+
+    let entries = panel.entries(&lazy);
+    panel.selected = panel.selected.min(entries.len().saturating_sub(1));
+    let (start, end) = visible_rows(panel.selected, entries.len());
+    commands.entity(rows).despawn_descendants();
+    commands.entity(rows).with_children(|list| {
+        if entries.is_empty() {
+            let hint = if lazy.discoveries.is_empty() {
+                "journal.empty"
+            } else {
+                "journal.no_match"
+            };
+            list.spawn(journal_text(strings.tr(hint, &[]), 12.0));
+            return;
+        }
+        for (pos, &index) in entries.iter().enumerate().take(end).skip(start) {
+            let d = &lazy.discoveries[index];
+            let selected = pos == panel.selected;
+            list.spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(if selected { SELECTED_COLOR } else { ROW_COLOR }),
+            ))
+            .with_children(|row| {
+                // Only known while its region is loaded
+                let locked = surface::find_planet(&lazy, d.star_id, d.planet_id)
+                    .is_some_and(|(_, p)| p.tidally_locked);
+                let name = lazy.planet_name_of(d.region_id, d.planet_id);
+                let cells = [
+                    if locked { strings.tr("journal.locked", &[("name", &name)]) } else { name },
+                    lazy.region_name(d.region_id),
+                    strings.number(d.complexity, 1),
+                    d.genome.substrate_name().to_string(),
+                    if d.has_technology { strings.yes_no(true) } else { "—".to_string() },
+                    strings.tr("journal.found_at", &[("age", &strings.number(d.discovery_age, 2))]),
+                ];
+                for (text, width) in cells.into_iter().zip(COLUMNS) {
+                    spawn_cell(row, text, width);
+                }
+                row.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(COLUMNS[6]),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                    JournalGoButton(index),
+                ))
+                .with_children(|btn| {
+                    btn.spawn(journal_text(strings.tr("journal.go", &[]), 12.0));
+                });
+            });
+        }
+        list.spawn(journal_text(
+            strings.tr(
+                "journal.range",
+                &[
+                    ("first", &(start + 1)),
+                    ("last", &end),
+                    ("shown", &entries.len()),
+                    ("discovered", &lazy.discoveries.len()),
+                ],
+            ),
+            12.0,
+        ));
+    });
+}
+
+/// Go buttons: fly to the row's planet
+pub fn journal_go_system(
+    lazy: Res<LazyUniverse>,
+    mut panel: ResMut<JournalPanel>,
+    mut selection: ResMut<PlanetSelection>,
+    mut button_q: Query<(&Interaction, &JournalGoButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
+) {
+    for (interaction, button, mut bg) in &mut button_q {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered => SELECTED_COLOR,
+            _ => BUTTON_COLOR,
+        });
+        if *interaction == Interaction::Pressed
+            && let Ok((mut transform, mut cam)) = cam_q.get_single_mut()
+        {
+            go_to_discovery(&lazy, button.0, &mut panel, &mut selection, &mut transform, &mut cam);
+        }
+    }
+}
+
+/// Glide to the planet of `lazy.discoveries[index]` and select it. A planet outside the
+/// loaded region is reached in two steps: jump into its region, then glide on once the
+/// region's stars are loaded (`journal_pending_system`).
+fn go_to_discovery(
+    lazy: &LazyUniverse,
+    index: usize,
+    panel: &mut JournalPanel,
+    selection: &mut PlanetSelection,
+    transform: &mut Transform,
+    cam: &mut FlyCamera,
+) {
+    let Some(d) = lazy.discoveries.get(index) else {
+        return;
+    };
+    if let Some((star, planet)) = surface::find_planet(lazy, d.star_id, d.planet_id) {
+        fly_to_planet(star, planet, selection, cam);
+        panel.pending = None;
+    } else if let Some(region) = lazy.regions.iter().find(|r| r.id == d.region_id) {
+        camera::enter_region_view(transform, cam, region);
+        panel.pending = Some((d.region_id, d.star_id, d.planet_id));
+        info!("Journal: entering region #{} to reach planet {}", d.region_id, d.planet_id);
+    }
+}
+
+fn fly_to_planet(star: &Star, planet: &Planet, selection: &mut PlanetSelection, cam: &mut FlyCamera) {
+    let planet_pos = Vec3::new(
+        star.position[0] as f32,
+        star.position[1] as f32,
+        star.position[2] as f32,
+    ) + orbit_offset(planet);
+    cam.tracking = None;
+    cam.frame_region = None;
+    cam.glide = Some(CameraGlide::looking_at(planet_pos + PLANET_VIEW_OFFSET, planet_pos));
+    selection.selected_planet = Some((planet.clone(), star.spectral_class));
+    selection.selected_star = None;
+    selection.selected_region = None;
+    info!("Journal: flying to planet id={} of star #{}", planet.id, star.id);
+}
+
+/// Finish a trip into another region once its stars are loaded; drop it if the camera
+/// has left the region meanwhile
+pub fn journal_pending_system(
+    lazy: Res<LazyUniverse>,
+    mut panel: ResMut<JournalPanel>,
+    mut selection: ResMut<PlanetSelection>,
+    mut cam_q: Query<(&Transform, &mut FlyCamera)>,
+) {
+    let Some((region_id, star_id, planet_id)) = panel.pending else {
+        return;
+    };
+    let Ok((transform, mut cam)) = cam_q.get_single_mut() else {
+        return;
+    };
+    let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) else {
+        panel.pending = None;
+        return;
+    };
+    let center = Vec3::from_array(region.center.map(|c| c as f32));
+    if transform.translation.distance(center) > region.size as f32 {
+        panel.pending = None;
+    } else if let Some((star, planet)) = surface::find_planet(&lazy, star_id, planet_id) {
+        fly_to_planet(star, planet, &mut selection, &mut cam);
+        panel.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{Genome, PlanetType, SimConfig};
+
+    fn discovery(planet_id: u64, substrate: u32, complexity: f64, has_technology: bool) -> LifeDiscovery {
+        LifeDiscovery {
+            planet_id,
+            star_id: 1,
+            region_id: 0,
+            seed: 42,
+            genome: Genome {
+                substrate,
+                ..Genome::primordial()
+            },
+            complexity,
+            species_count: 10,
+            has_technology,
+            planet_type: PlanetType::Rocky,
+            surface_temp: 288.0,
+            discovery_age: 9.0,
+        }
+    }
+
+    #[test]
+    fn test_journal_filters_and_scrolls_discoveries() {
+        let mut lazy = LazyUniverse::empty(SimConfig::default());
+        lazy.discoveries = vec![
+            discovery(1, 0, 2.0, false),
+            discovery(2, 3, 6.0, false),
+            discovery(3, 0, 8.0, true),
+            discovery(4, 4, 1.0, false),
+        ];
+        let mut panel = JournalPanel::default();
+        assert_eq!(panel.entries(&lazy), vec![0, 1, 2, 3]);
+
+        panel.filters[JournalFilter::Civilizations as usize] = true;
+        assert_eq!(panel.entries(&lazy), vec![2]);
+        panel.filters = [false, true, false];
+        assert_eq!(panel.entries(&lazy), vec![1, 3]);
+        // Filters combine
+        panel.filters[JournalFilter::Complex as usize] = true;
+        assert_eq!(panel.entries(&lazy), vec![1]);
+
+        // The selection stays in view, the window never runs past either end
+        assert_eq!(visible_rows(0, 3), (0, 3));
+        assert_eq!(visible_rows(0, 40), (0, JOURNAL_ROWS));
+        assert_eq!(visible_rows(20, 40), (20 - JOURNAL_ROWS / 2, 20 - JOURNAL_ROWS / 2 + JOURNAL_ROWS));
+        assert_eq!(visible_rows(39, 40), (40 - JOURNAL_ROWS, 40));
+    }
+}
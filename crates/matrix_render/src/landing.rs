@@ -0,0 +1,515 @@
+//! Landing and take-off on a world with a surface: instead of a cut, the camera comes down
+//! from high above the landing spot to eye height, through thinning fog and a sky that
+//! turns from space black to the planet's, with heat streaks past it in thick
+//! atmospheres; taking off plays it backwards and ends where the camera left space. The
+//! altitude and vertical speed show in the HUD, input is held, and any key skips to the
+//! end. [B] / [Esc] don't change levels while it plays.
+
+use bevy::prelude::*;
+use matrix_core::AtmosphereType;
+use matrix_sim::lazy_universe::LazyUniverse;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::camera::FlyCamera;
+use super::gamepad::GamepadInput;
+use super::hud_layout::HudScaled;
+use super::locale::Strings;
+use super::settings::Action;
+use super::surface::{SurfaceState, WeatherState};
+
+/// Length of a descent or an ascent (s)
+pub const TRANSITION_SECS: f32 = 2.5;
+/// Height above the eye position where a descent starts and an ascent ends (m)
+pub const TRANSITION_ALTITUDE: f32 = 600.0;
+/// Fog density at the top of the thickest atmospheres; thins out toward the ground
+const FOG_DENSITY_TOP: f32 = 0.02;
+/// Atmospheres thinner than this get no heat streaks
+const STREAK_MIN_THICKNESS: f32 = 0.5;
+/// Heat streaks around the camera in the thickest atmospheres
+const STREAK_COUNT: usize = 60;
+/// Streaks sit this far from the camera (m), up to this much above or below it
+const STREAK_RADIUS: (f32, f32) = (3.0, 14.0);
+const STREAK_SPAN: f32 = 30.0;
+/// Length of a streak at full speed (m)
+const STREAK_LENGTH: f32 = 5.0;
+/// Streaks move at this fraction of the camera's vertical speed, so they don't blur
+const STREAK_SPEED_FACTOR: f32 = 0.1;
+/// Font size of the altitude readout
+const PANEL_FONT_SIZE: f32 = 16.0;
+/// Space background, where an ascent ends
+const SPACE_CLEAR_COLOR: Color = Color::srgb(0.0, 0.0, 0.02);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LandingPhase {
+    #[default]
+    Idle,
+    /// Coming down to the landing spot
+    Descending,
+    /// Climbing away from the surface, which is left at the top
+    Ascending,
+}
+
+/// The descent or ascent in progress. `surface_enter_exit_system` starts a descent once
+/// the surface is spawned, `level_transition_system` an ascent on [B] / [Esc];
+/// `landing_transition_system` moves it on and `landing_visuals_system` draws it.
+#[derive(Resource, Debug, Clone)]
+pub struct LandingTransition {
+    pub phase: LandingPhase,
+    /// Seconds into the transition
+    pub elapsed: f32,
+    /// Camera position at eye height, where a descent ends and an ascent starts
+    pub ground: Vec3,
+    pub atmosphere: AtmosphereType,
+    /// Bumped when a transition begins or ends, for the visuals to catch up
+    generation: u32,
+}
+
+impl Default for LandingTransition {
+    fn default() -> Self {
+        Self {
+            phase: LandingPhase::Idle,
+            elapsed: 0.0,
+            ground: Vec3::ZERO,
+            atmosphere: AtmosphereType::None,
+            generation: 0,
+        }
+    }
+}
+
+/// How much of an atmosphere the camera passes through: 0 airless, 1 Venus-like
+pub fn atmosphere_thickness(atmosphere: AtmosphereType) -> f32 {
+    match atmosphere {
+        AtmosphereType::None => 0.0,
+        AtmosphereType::ThinCO2 => 0.2,
+        AtmosphereType::NitrogenOxygen | AtmosphereType::Methane => 0.6,
+        AtmosphereType::Exotic => 0.7,
+        AtmosphereType::ThickCO2 | AtmosphereType::Hydrogen => 1.0,
+    }
+}
+
+impl LandingTransition {
+    pub fn is_active(&self) -> bool {
+        self.phase != LandingPhase::Idle
+    }
+
+    /// Start coming down to `ground`. Not while another transition is on.
+    pub fn begin_descent(&mut self, ground: Vec3, atmosphere: AtmosphereType) -> bool {
+        self.begin(LandingPhase::Descending, ground, atmosphere)
+    }
+
+    /// Start climbing away from `ground`. Not while another transition is on.
+    pub fn begin_ascent(&mut self, ground: Vec3, atmosphere: AtmosphereType) -> bool {
+        self.begin(LandingPhase::Ascending, ground, atmosphere)
+    }
+
+    fn begin(&mut self, phase: LandingPhase, ground: Vec3, atmosphere: AtmosphereType) -> bool {
+        if self.is_active() {
+            return false;
+        }
+        *self = Self {
+            phase,
+            elapsed: 0.0,
+            ground,
+            atmosphere,
+            generation: self.generation.wrapping_add(1),
+        };
+        true
+    }
+
+    /// Jump to the end: the next `advance` finishes the transition
+    pub fn skip(&mut self) {
+        if self.is_active() {
+            self.elapsed = TRANSITION_SECS;
+        }
+    }
+
+    /// End the transition where it is, leaving the camera alone (the surface is gone)
+    pub fn cancel(&mut self) {
+        if self.is_active() {
+            self.phase = LandingPhase::Idle;
+            self.elapsed = 0.0;
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+
+    /// Move the transition on by `dt` seconds. Returns the phase that finished on this
+    /// call, if one did.
+    pub fn advance(&mut self, dt: f32) -> Option<LandingPhase> {
+        if !self.is_active() {
+            return None;
+        }
+        self.elapsed = (self.elapsed + dt).min(TRANSITION_SECS);
+        if self.elapsed < TRANSITION_SECS {
+            return None;
+        }
+        let finished = self.phase;
+        self.phase = LandingPhase::Idle;
+        self.generation = self.generation.wrapping_add(1);
+        Some(finished)
+    }
+
+    /// Share of the transition done, eased in and out (0–1)
+    fn eased(&self) -> f32 {
+        let t = (self.elapsed / TRANSITION_SECS).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Height above the eye position as a share of `TRANSITION_ALTITUDE`: 1 at the top,
+    /// 0 on the ground
+    pub fn altitude_fraction(&self) -> f32 {
+        match self.phase {
+            LandingPhase::Idle => 0.0,
+            LandingPhase::Descending => 1.0 - self.eased(),
+            LandingPhase::Ascending => self.eased(),
+        }
+    }
+
+    /// Height above the eye position (m)
+    pub fn altitude(&self) -> f32 {
+        TRANSITION_ALTITUDE * self.altitude_fraction()
+    }
+
+    pub fn camera_position(&self) -> Vec3 {
+        self.ground + Vec3::Y * self.altitude()
+    }
+
+    /// Vertical speed of the camera (m/s, up is positive)
+    pub fn vertical_speed(&self) -> f32 {
+        let t = (self.elapsed / TRANSITION_SECS).clamp(0.0, 1.0);
+        let rate = TRANSITION_ALTITUDE * 6.0 * t * (1.0 - t) / TRANSITION_SECS;
+        match self.phase {
+            LandingPhase::Idle => 0.0,
+            LandingPhase::Descending => -rate,
+            LandingPhase::Ascending => rate,
+        }
+    }
+
+    /// Fog density at the current altitude: thickest at the top, none without air
+    pub fn fog_density(&self) -> f32 {
+        FOG_DENSITY_TOP * atmosphere_thickness(self.atmosphere) * self.altitude_fraction()
+    }
+
+    /// Heat streaks to draw: none in thin atmospheres or without one
+    pub fn streak_count(&self) -> usize {
+        let thickness = atmosphere_thickness(self.atmosphere);
+        if thickness < STREAK_MIN_THICKNESS {
+            0
+        } else {
+            (STREAK_COUNT as f32 * thickness) as usize
+        }
+    }
+}
+
+/// No descent or ascent under way, for systems that move the camera on a surface
+pub fn not_landing(landing: Res<LandingTransition>) -> bool {
+    !landing.is_active()
+}
+
+/// Everything drawn for a transition (despawned when it ends)
+#[derive(Component)]
+pub struct LandingVisual;
+
+/// A heat streak, kept at `offset` from the camera
+#[derive(Component)]
+pub struct LandingStreak {
+    offset: Vec3,
+}
+
+/// Altitude and vertical speed readout
+#[derive(Component)]
+pub struct LandingPanel;
+
+/// Run the transition: any key or button (from its second frame) skips it, the camera
+/// follows it, and at the top of an ascent the surface is left, so that
+/// `surface_enter_exit_system` saves the spot and puts the camera back in space.
+pub fn landing_transition_system(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad: Res<GamepadInput>,
+    mut state: ResMut<SurfaceState>,
+    mut landing: ResMut<LandingTransition>,
+    mut seen: Local<u32>,
+    mut camera_q: Query<&mut Transform, With<FlyCamera>>,
+) {
+    // The key that started it is still down on its first frame
+    let fresh = *seen != landing.generation;
+    *seen = landing.generation;
+    if !landing.is_active() {
+        return;
+    }
+    let any_pressed = keyboard.get_just_pressed().next().is_some()
+        || Action::ALL.iter().any(|&action| gamepad.just_pressed(action));
+    if !fresh && any_pressed {
+        landing.skip();
+    }
+
+    let finished = landing.advance(time.delta_secs());
+    *seen = landing.generation;
+    if let Ok(mut transform) = camera_q.get_single_mut() {
+        transform.translation = landing.camera_position();
+    }
+    match finished {
+        Some(LandingPhase::Descending) => info!("Surface: touched down"),
+        Some(LandingPhase::Ascending) => {
+            state.active = false;
+            state.generation = state.generation.wrapping_add(1);
+            info!("Surface: leaving planet");
+        }
+        _ => {}
+    }
+}
+
+/// Draw the transition: the sky blends from space to the planet's (as the day / night
+/// system left it this frame) with the altitude, fog thins toward the ground, heat
+/// streaks rush past and the readout follows. When a descent ends the weather's fog is
+/// put back; an ascent's end leaves the clean-up to `surface_enter_exit_system`.
+pub fn landing_visuals_system(
+    (mut commands, time, strings, mut drawn): (Commands, Res<Time>, Res<Strings>, Local<u32>),
+    (state, lazy, weather, landing): (Res<SurfaceState>, Res<LazyUniverse>, Res<WeatherState>, Res<LandingTransition>),
+    (mut clear_color, mut meshes, mut materials): (
+        ResMut<ClearColor>,
+        ResMut<Assets<Mesh>>,
+        ResMut<Assets<StandardMaterial>>,
+    ),
+    camera_q: Query<(Entity, &Transform), With<FlyCamera>>,
+    mut streak_q: Query<(&mut LandingStreak, &mut Transform), Without<FlyCamera>>,
+    mut panel_q: Query<&mut Text, With<LandingPanel>>,
+    visual_q: Query<Entity, With<LandingVisual>>,
+) {
+    let Ok((camera, cam_tf)) = camera_q.get_single() else {
+        return;
+    };
+
+    if *drawn != landing.generation {
+        *drawn = landing.generation;
+        for entity in &visual_q {
+            commands.entity(entity).despawn_recursive();
+        }
+        if landing.is_active() {
+            spawn_landing_visuals(&mut commands, &landing, &mut meshes, &mut materials);
+        } else if state.active {
+            // Touched down: the weather's own fog again
+            let density = weather.kind.fog_density();
+            if density > 0.0 {
+                commands.entity(camera).insert(DistanceFog {
+                    color: clear_color.0,
+                    falloff: FogFalloff::Exponential { density },
+                    ..default()
+                });
+            } else {
+                commands.entity(camera).remove::<DistanceFog>();
+            }
+        }
+    }
+    if !landing.is_active() {
+        return;
+    }
+
+    let ground_sky = clear_color.0.to_linear();
+    let sky = SPACE_CLEAR_COLOR.to_linear().mix(&ground_sky, 1.0 - landing.altitude_fraction());
+    clear_color.0 = Color::from(sky);
+    let density = landing.fog_density();
+    if density > 0.0 {
+        commands.entity(camera).insert(DistanceFog {
+            color: clear_color.0,
+            falloff: FogFalloff::Exponential { density },
+            ..default()
+        });
+    } else {
+        commands.entity(camera).remove::<DistanceFog>();
+    }
+
+    // Streaks move against the camera, longer the faster it goes
+    let speed = landing.vertical_speed();
+    let peak = TRANSITION_ALTITUDE * 1.5 / TRANSITION_SECS;
+    let length = STREAK_LENGTH * (speed.abs() / peak).min(1.0);
+    let dy = -speed * STREAK_SPEED_FACTOR * time.delta_secs();
+    for (mut streak, mut transform) in &mut streak_q {
+        streak.offset.y = (streak.offset.y + dy + STREAK_SPAN).rem_euclid(2.0 * STREAK_SPAN)
+            - STREAK_SPAN;
+        transform.translation = cam_tf.translation + streak.offset;
+        transform.scale = Vec3::new(1.0, length.max(0.01), 1.0);
+    }
+
+    if let Ok(mut text) = panel_q.get_single_mut() {
+        **text = landing_readout(&landing, &state, &lazy, &strings);
+    }
+}
+
+/// Heat streaks around the camera (thick atmospheres only) and the readout panel
+fn spawn_landing_visuals(
+    commands: &mut Commands,
+    landing: &LandingTransition,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let count = landing.streak_count();
+    if count > 0 {
+        let mesh = meshes.add(Cuboid::new(0.04, 1.0, 0.04));
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 0.75, 0.45, 0.35),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let mut rng = ChaCha8Rng::seed_from_u64(u64::from(landing.generation));
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let radius = rng.gen_range(STREAK_RADIUS.0..STREAK_RADIUS.1);
+            let offset = Vec3::new(
+                radius * angle.cos(),
+                rng.gen_range(-STREAK_SPAN..STREAK_SPAN),
+                radius * angle.sin(),
+            );
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(landing.camera_position() + offset),
+                LandingStreak { offset },
+                LandingVisual,
+            ));
+        }
+    }
+
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: PANEL_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::srgba(0.8, 0.95, 1.0, 0.95)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(20.0),
+            left: Val::Percent(50.0),
+            margin: UiRect::left(Val::Px(-160.0)),
+            width: Val::Px(320.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.03, 0.06, 0.6)),
+        HudScaled {
+            font_size: Some(PANEL_FONT_SIZE),
+            width: Some(320.0),
+            ..default()
+        },
+        LandingPanel,
+        LandingVisual,
+    ));
+}
+
+fn landing_readout(
+    landing: &LandingTransition,
+    state: &SurfaceState,
+    lazy: &LazyUniverse,
+    strings: &Strings,
+) -> String {
+    let name = state.planet.as_ref().map_or_else(String::new, |p| lazy.planet_name(p.id));
+    let key = match landing.phase {
+        LandingPhase::Ascending => "landing.ascent",
+        _ => "landing.descent",
+    };
+    strings.tr(
+        key,
+        &[
+            ("name", &name),
+            ("altitude", &strings.number(landing.altitude() as f64, 0)),
+            ("speed", &strings.number(landing.vertical_speed().abs() as f64, 0)),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descent_comes_down_to_the_ground() {
+        let mut landing = LandingTransition::default();
+        assert!(!landing.is_active() && landing.advance(1.0).is_none());
+
+        let ground = Vec3::new(10.0, 3.0, -4.0);
+        assert!(landing.begin_descent(ground, AtmosphereType::NitrogenOxygen));
+        assert_eq!(landing.camera_position(), ground + Vec3::Y * TRANSITION_ALTITUDE);
+        // One at a time: [B] spam doesn't start another
+        assert!(!landing.begin_descent(Vec3::ZERO, AtmosphereType::None));
+        assert!(!landing.begin_ascent(Vec3::ZERO, AtmosphereType::None));
+
+        // Lower, with thinner fog, every step; fastest midway
+        let mut last = (landing.altitude(), landing.fog_density());
+        let mut fastest: f32 = 0.0;
+        while landing.advance(0.1).is_none() {
+            let now = (landing.altitude(), landing.fog_density());
+            assert!(now.0 < last.0 && now.1 < last.1);
+            assert!(landing.vertical_speed() < 0.0);
+            fastest = fastest.max(landing.vertical_speed().abs());
+            last = now;
+        }
+        assert!(fastest > TRANSITION_ALTITUDE / TRANSITION_SECS);
+        assert!(!landing.is_active());
+        assert_eq!(landing.camera_position(), ground);
+        assert_eq!(landing.vertical_speed(), 0.0);
+    }
+
+    #[test]
+    fn test_ascent_skip_and_cancel() {
+        let mut landing = LandingTransition::default();
+        let ground = Vec3::new(0.0, 5.0, 0.0);
+        assert!(landing.begin_ascent(ground, AtmosphereType::ThickCO2));
+        landing.advance(0.5);
+        assert!(landing.altitude() > 0.0 && landing.vertical_speed() > 0.0);
+
+        // Skipping ends it on the next step, at the top
+        landing.skip();
+        assert_eq!(landing.advance(0.0), Some(LandingPhase::Ascending));
+        assert_eq!(landing.advance(0.0), None);
+        assert!(!landing.is_active());
+
+        // Cancelling drops it on the spot; skipping while idle does nothing
+        assert!(landing.begin_descent(ground, AtmosphereType::None));
+        landing.cancel();
+        assert!(!landing.is_active());
+        landing.skip();
+        assert_eq!(landing.advance(10.0), None);
+        assert!(landing.begin_descent(ground, AtmosphereType::None));
+    }
+
+    #[test]
+    fn test_streaks_and_fog_follow_the_atmosphere() {
+        let mut landing = LandingTransition::default();
+        landing.begin_descent(Vec3::ZERO, AtmosphereType::None);
+        assert_eq!((landing.streak_count(), landing.fog_density()), (0, 0.0));
+        landing.cancel();
+
+        landing.begin_descent(Vec3::ZERO, AtmosphereType::ThinCO2);
+        assert_eq!(landing.streak_count(), 0);
+        assert!(landing.fog_density() > 0.0);
+        landing.cancel();
+
+        landing.begin_descent(Vec3::ZERO, AtmosphereType::ThickCO2);
+        assert_eq!(landing.streak_count(), STREAK_COUNT);
+        landing.cancel();
+        landing.begin_descent(Vec3::ZERO, AtmosphereType::NitrogenOxygen);
+        assert!(landing.streak_count() > 0 && landing.streak_count() < STREAK_COUNT);
+    }
+
+    #[test]
+    fn test_readout_follows_the_language() {
+        let state = SurfaceState::default();
+        let lazy = LazyUniverse::empty(matrix_core::SimConfig::default());
+        let english = Strings::default();
+        let ukrainian = Strings::load_from(std::path::Path::new("no_such_dir"), "uk");
+        let mut landing = LandingTransition::default();
+        landing.begin_descent(Vec3::ZERO, AtmosphereType::NitrogenOxygen);
+        let altitude = english.number(TRANSITION_ALTITUDE as f64, 0);
+        let readout = landing_readout(&landing, &state, &lazy, &english);
+        assert!(readout.starts_with("DESCENT") && readout.contains(&format!("Altitude: {altitude} m")));
+        let readout = landing_readout(&landing, &state, &lazy, &ukrainian);
+        assert!(readout.starts_with("СПУСК") && !readout.contains("Altitude"), "{readout}");
+
+        landing.cancel();
+        landing.begin_ascent(Vec3::ZERO, AtmosphereType::NitrogenOxygen);
+        assert!(landing_readout(&landing, &state, &lazy, &ukrainian).starts_with("ЗЛІТ"));
+    }
+}
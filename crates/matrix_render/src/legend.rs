@@ -0,0 +1,258 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use matrix_core::ParticleKind;
+
+use super::input_capture::CapturesPointer;
+use super::locale::{Strings, Translated};
+use super::particles::{ParticleCloud, ParticleCloudState, cloud_visibility, kind_color};
+use super::settings::{Action, UserSettings, action_just_pressed};
+
+const ROW_COLOR: Color = Color::srgba(0.1, 0.25, 0.15, 0.9);
+const ROW_HOVER_COLOR: Color = Color::srgba(0.15, 0.4, 0.25, 0.95);
+/// Side of a row's color swatch (px)
+const SWATCH_SIZE: f32 = 12.0;
+
+/// Which particle kinds are drawn. Hidden kinds keep their cloud entity, hidden, and
+/// are skipped by the mesh updates.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct ParticleViewFilter {
+    hidden: BTreeSet<u32>,
+}
+
+impl ParticleViewFilter {
+    pub fn is_visible(&self, kind: u32) -> bool {
+        !self.hidden.contains(&kind)
+    }
+
+    /// Show a hidden kind, hide a shown one
+    pub fn toggle(&mut self, kind: u32) {
+        if !self.hidden.remove(&kind) {
+            self.hidden.insert(kind);
+        }
+    }
+
+    /// Hide dark matter; pressed again, show everything
+    pub fn toggle_baryonic_only(&mut self) {
+        self.show_only(|kind| kind != ParticleKind::DarkMatter);
+    }
+
+    /// Hide everything but dark matter; pressed again, show everything
+    pub fn toggle_dark_matter_only(&mut self) {
+        self.show_only(|kind| kind == ParticleKind::DarkMatter);
+    }
+
+    fn show_only(&mut self, keep: impl Fn(ParticleKind) -> bool) {
+        let hidden: BTreeSet<u32> = ParticleKind::ALL
+            .into_iter()
+            .filter(|kind| !keep(*kind))
+            .map(|kind| kind as u32)
+            .collect();
+        self.hidden = if self.hidden == hidden { BTreeSet::new() } else { hidden };
+    }
+}
+
+/// Marker for the legend root node
+#[derive(Component)]
+pub struct LegendRoot;
+
+/// Marker for the node holding the kind rows
+#[derive(Component)]
+pub struct LegendRows;
+
+/// A kind's row; clicking toggles its visibility
+#[derive(Component)]
+pub struct LegendRow(pub u32);
+
+/// Spawn the (hidden) legend, right side below the info panel
+pub fn spawn_particle_legend(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(45.0),
+                right: Val::Px(10.0),
+                min_width: Val::Px(200.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(3.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.9)),
+            LegendRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((legend_text("", 13.0), Translated("legend.title")));
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                LegendRows,
+            ));
+        });
+}
+
+fn legend_text(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+    )
+}
+
+/// [R] shows / hides the legend, [F7] / [F8] switch to baryonic / dark matter only
+pub fn legend_keys_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut filter: ResMut<ParticleViewFilter>,
+    mut root_q: Query<&mut Node, With<LegendRoot>>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::ParticleLegend) {
+        for mut node in &mut root_q {
+            node.display = match node.display {
+                Display::None => Display::Flex,
+                _ => Display::None,
+            };
+        }
+    }
+    if action_just_pressed(&settings, &keyboard, Action::BaryonicOnly) {
+        filter.toggle_baryonic_only();
+    }
+    if action_just_pressed(&settings, &keyboard, Action::DarkMatterOnly) {
+        filter.toggle_dark_matter_only();
+    }
+}
+
+/// Kind rows: toggle the kind on click
+pub fn legend_click_system(
+    mut filter: ResMut<ParticleViewFilter>,
+    mut row_q: Query<(&Interaction, &LegendRow, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, row, mut bg) in &mut row_q {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered => ROW_HOVER_COLOR,
+            _ => ROW_COLOR,
+        });
+        if *interaction == Interaction::Pressed {
+            filter.toggle(row.0);
+        }
+    }
+}
+
+/// Show and hide the cloud entities when the filter changes (the meshes stay as they are)
+pub fn apply_particle_filter(
+    filter: Res<ParticleViewFilter>,
+    mut cloud_q: Query<(&ParticleCloud, &mut Visibility)>,
+) {
+    if !filter.is_changed() {
+        return;
+    }
+    for (cloud, mut visibility) in &mut cloud_q {
+        visibility.set_if_neq(cloud_visibility(&filter, cloud.kind));
+    }
+}
+
+/// Rebuild the rows when the clouds are rebuilt, the filter or the language changes
+pub fn update_particle_legend(
+    mut commands: Commands,
+    filter: Res<ParticleViewFilter>,
+    state: Res<ParticleCloudState>,
+    strings: Res<Strings>,
+    mut shown_generation: Local<Option<u32>>,
+    rows_q: Query<Entity, With<LegendRows>>,
+) {
+    if *shown_generation == Some(state.render_generation)
+        && !filter.is_changed()
+        && !strings.is_changed()
+    {
+        return;
+    }
+    *shown_generation = Some(state.render_generation);
+    let Ok(rows) = rows_q.get_single() else {
+        return;
+    };
+
+    commands.entity(rows).despawn_descendants();
+    commands.entity(rows).with_children(|list| {
+        if state.sampled.is_empty() {
+            list.spawn(legend_text(strings.tr("legend.empty", &[]), 12.0));
+            return;
+        }
+        for (&kind, &count) in &state.sampled {
+            let name = ParticleKind::from_u32(kind).map_or(format!("kind {kind}"), |k| format!("{k:?}"));
+            let check = if filter.is_visible(kind) { "[x]" } else { "[ ]" };
+            list.spawn((
+                Button,
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(6.0),
+                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(ROW_COLOR),
+                LegendRow(kind),
+            ))
+            .with_children(|row| {
+                row.spawn(legend_text(check, 12.0));
+                row.spawn((
+                    Node {
+                        width: Val::Px(SWATCH_SIZE),
+                        height: Val::Px(SWATCH_SIZE),
+                        ..default()
+                    },
+                    BackgroundColor(kind_color(kind)),
+                ));
+                row.spawn(legend_text(format!("{name}  {count}"), 12.0));
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_hides_clouds_without_rebuilding() {
+        let dark = ParticleKind::DarkMatter as u32;
+        let hydrogen = ParticleKind::Hydrogen as u32;
+        let mut filter = ParticleViewFilter::default();
+        assert!(filter.is_visible(dark) && filter.is_visible(hydrogen));
+
+        filter.toggle_baryonic_only();
+        assert!(!filter.is_visible(dark) && filter.is_visible(hydrogen));
+        filter.toggle_dark_matter_only();
+        assert!(filter.is_visible(dark) && !filter.is_visible(hydrogen));
+        // The same quick toggle again shows everything
+        filter.toggle_dark_matter_only();
+        assert_eq!(filter, ParticleViewFilter::default());
+        filter.toggle(hydrogen);
+        assert!(!filter.is_visible(hydrogen));
+        filter.toggle(hydrogen);
+        assert!(filter.is_visible(hydrogen));
+
+        // Toggling flips the cloud entities' visibility
+        let mut app = App::new();
+        app.init_resource::<ParticleViewFilter>()
+            .add_systems(Update, apply_particle_filter);
+        let cloud = |kind| (ParticleCloud { kind }, Visibility::Inherited);
+        let dark_cloud = app.world_mut().spawn(cloud(dark)).id();
+        let hydrogen_cloud = app.world_mut().spawn(cloud(hydrogen)).id();
+        app.world_mut().resource_mut::<ParticleViewFilter>().toggle_baryonic_only();
+        app.update();
+        let visibility = |app: &App, entity| *app.world().get::<Visibility>(entity).unwrap();
+        assert_eq!(visibility(&app, dark_cloud), Visibility::Hidden);
+        assert_eq!(visibility(&app, hydrogen_cloud), Visibility::Inherited);
+        app.world_mut().resource_mut::<ParticleViewFilter>().toggle_baryonic_only();
+        app.update();
+        assert_eq!(visibility(&app, dark_cloud), Visibility::Inherited);
+    }
+}
@@ -1,7 +1,42 @@
+pub mod audio;
+pub mod bestiary;
+pub mod bookmarks;
+pub mod budget;
 pub mod camera;
+pub mod census;
+pub mod chunks;
+pub mod console;
 pub mod cosmos;
+pub mod debug_overlay;
+pub mod dive;
+pub mod feed;
+pub mod gallery;
+pub mod gamepad;
+pub mod history_graphs;
+pub mod hud_layout;
+pub mod input_capture;
+pub mod instances;
+pub mod journal;
+pub mod landing;
+pub mod legend;
+pub mod locale;
 pub mod menu;
+pub mod microbes;
 pub mod particles;
+pub mod pause;
+pub mod photo;
 pub mod plugin;
+pub mod props;
+pub mod rewind;
+pub mod saving;
+pub mod search;
+pub mod settings;
+pub mod sky;
+pub mod skybox;
+pub mod small_bodies;
+pub mod speed;
+pub mod story;
 pub mod surface;
+pub mod surface_map;
+pub mod tech_visuals;
 pub mod ui;
@@ -0,0 +1,410 @@
+//! HUD and menu text in the player's language. Each language is a RON file in
+//! `assets/lang/` mapping keys to templates with `{name}` placeholders; English is built
+//! in, and keys missing from another language fall back to it.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::settings::UserSettings;
+
+/// Selectable languages: code (the file name in `assets/lang/`) and native name
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("uk", "Українська")];
+
+const ENGLISH: &str = include_str!("../../../assets/lang/en.ron");
+const UKRAINIAN: &str = include_str!("../../../assets/lang/uk.ron");
+
+/// Where the language files live: `assets/lang/` beside `saves/`
+pub fn lang_dir() -> PathBuf {
+    PathBuf::from("assets").join("lang")
+}
+
+/// Copy of a shipped language built into the binary, used when its file can't be read
+fn bundled(code: &str) -> Option<&'static str> {
+    match code {
+        "en" => Some(ENGLISH),
+        "uk" => Some(UKRAINIAN),
+        _ => None,
+    }
+}
+
+/// Native name of a language code, or the code itself if it isn't in `LANGUAGES`
+pub fn language_name(code: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(code, |(_, name)| name)
+}
+
+/// The code after (or before, `dir` < 0) `code` in `LANGUAGES`, wrapping around
+pub fn next_language(code: &str, dir: i32) -> &'static str {
+    let count = LANGUAGES.len() as i32;
+    let index = LANGUAGES.iter().position(|(c, _)| *c == code).unwrap_or(0) as i32;
+    LANGUAGES[(index + dir).rem_euclid(count) as usize].0
+}
+
+/// One language file
+#[derive(Debug, Deserialize)]
+struct LanguageFile {
+    decimal_separator: char,
+    /// Between groups of three digits; None writes numbers ungrouped
+    group_separator: Option<char>,
+    /// Font (path under `assets/`) covering the language's script; None keeps Bevy's
+    /// built-in font, which only has ASCII
+    #[serde(default)]
+    font: Option<String>,
+    strings: HashMap<String, String>,
+}
+
+fn parse(text: &str) -> Result<LanguageFile, ron::error::SpannedError> {
+    ron::from_str(text)
+}
+
+/// Templates of the chosen language plus English for missing keys, and how the language
+/// writes numbers
+#[derive(Resource, Debug, Clone)]
+pub struct Strings {
+    /// Language code, as in `UserSettings::language`
+    pub language: String,
+    pub font: Option<String>,
+    decimal_separator: char,
+    group_separator: Option<char>,
+    table: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        let english = parse(ENGLISH).expect("built-in English strings parse");
+        Self {
+            language: "en".to_string(),
+            font: english.font,
+            decimal_separator: english.decimal_separator,
+            group_separator: english.group_separator,
+            table: HashMap::new(),
+            english: english.strings,
+        }
+    }
+}
+
+impl Strings {
+    /// `code`'s file from `lang_dir`, or its built-in copy. An unknown or malformed
+    /// language means English (with a warning), and its keys missing from the file are
+    /// listed in a warning once.
+    pub fn load(code: &str) -> Self {
+        Self::load_from(&lang_dir(), code)
+    }
+
+    pub fn load_from(dir: &Path, code: &str) -> Self {
+        let mut strings = Self::default();
+        if code == "en" {
+            return strings;
+        }
+        let path = dir.join(format!("{code}.ron"));
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => match bundled(code) {
+                Some(text) => text.to_string(),
+                None => {
+                    warn!("No language {code:?} ({}: {e}), using English", path.display());
+                    return strings;
+                }
+            },
+        };
+        let file = match parse(&text) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!("Ignoring {}: {e}", path.display());
+                return strings;
+            }
+        };
+        let mut missing: Vec<&str> = strings
+            .english
+            .keys()
+            .filter(|key| !file.strings.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            warn!("Language {code:?} is missing {} strings, shown in English: {}", missing.len(), missing.join(", "));
+        }
+        strings.language = code.to_string();
+        strings.font = file.font;
+        strings.decimal_separator = file.decimal_separator;
+        strings.group_separator = file.group_separator;
+        strings.table = file.strings;
+        strings
+    }
+
+    /// The template for `key` with each `{name}` replaced by its value in `args`. Keys the
+    /// language lacks come from English; a key English lacks too is returned as is.
+    pub fn tr(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(template) = self.table.get(key).or_else(|| self.english.get(key)) else {
+            return key.to_string();
+        };
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let arg = after
+                .find('}')
+                .and_then(|close| Some((args.iter().find(|(name, _)| *name == &after[..close])?, close)));
+            match arg {
+                Some(((_, value), close)) => {
+                    out.push_str(&value.to_string());
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// `value` with `decimals` digits after the language's decimal separator and its
+    /// digit grouping
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        self.format_number(value, decimals, self.group_separator)
+    }
+
+    /// `number` without digit grouping, for lists of numbers such as coordinates
+    pub fn decimal(&self, value: f64, decimals: usize) -> String {
+        self.format_number(value, decimals, None)
+    }
+
+    fn format_number(&self, value: f64, decimals: usize, group_separator: Option<char>) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let text = format!("{value:.decimals$}");
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        let mut out = String::from(sign);
+        for (i, digit) in int.chars().enumerate() {
+            if let Some(separator) = group_separator
+                && i > 0
+                && (int.len() - i).is_multiple_of(3)
+            {
+                out.push(separator);
+            }
+            out.push(digit);
+        }
+        if !frac.is_empty() {
+            out.push(self.decimal_separator);
+            out.push_str(frac);
+        }
+        out
+    }
+
+    /// `value` in scientific notation (`1.23e6`) with the language's decimal separator
+    pub fn scientific(&self, value: f64, decimals: usize) -> String {
+        format!("{value:.decimals$e}").replace('.', &self.decimal_separator.to_string())
+    }
+
+    /// Large counts in human-readable form (`1.5K`, `2.0B`)
+    pub fn count(&self, n: u64) -> String {
+        let scaled = |divisor: f64| self.number(n as f64 / divisor, 1);
+        if n >= 1_000_000_000_000 {
+            self.tr("number.trillions", &[("n", &scaled(1e12))])
+        } else if n >= 1_000_000_000 {
+            self.tr("number.billions", &[("n", &scaled(1e9))])
+        } else if n >= 1_000_000 {
+            self.tr("number.millions", &[("n", &scaled(1e6))])
+        } else if n >= 1_000 {
+            self.tr("number.thousands", &[("n", &scaled(1e3))])
+        } else {
+            n.to_string()
+        }
+    }
+
+    /// "Yes" or "No"
+    pub fn yes_no(&self, yes: bool) -> String {
+        self.tr(if yes { "common.yes" } else { "common.no" }, &[])
+    }
+
+    /// "On" or "Off"
+    pub fn on_off(&self, on: bool) -> String {
+        self.tr(if on { "common.on" } else { "common.off" }, &[])
+    }
+}
+
+/// Reload `Strings` when the language setting changes
+pub fn language_system(settings: Res<UserSettings>, mut strings: ResMut<Strings>) {
+    if settings.is_changed() && settings.language != strings.language {
+        *strings = Strings::load(&settings.language);
+    }
+}
+
+/// A fixed label: its `Text` is the template of this key, redone when the language changes
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Translated(pub &'static str);
+
+/// Fill in `Translated` labels as they spawn and all of them when the language changes
+pub fn translated_text_system(strings: Res<Strings>, mut text_q: Query<(Ref<Translated>, &mut Text)>) {
+    for (translated, mut text) in &mut text_q {
+        if strings.is_changed() || translated.is_added() {
+            **text = strings.tr(translated.0, &[]);
+        }
+    }
+}
+
+/// Give every text the language's font, new texts as they spawn and all of them when the
+/// language changes
+pub fn locale_font_system(
+    strings: Res<Strings>,
+    asset_server: Res<AssetServer>,
+    mut font: Local<Handle<Font>>,
+    mut text_q: Query<&mut TextFont>,
+) {
+    if strings.is_changed() {
+        *font = strings
+            .font
+            .as_ref()
+            .map_or_else(Handle::default, |path| asset_server.load(path.clone()));
+    }
+    for mut text_font in &mut text_q {
+        if (strings.is_changed() || text_font.is_added()) && text_font.font != *font {
+            text_font.font = font.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Keys in `{name}` placeholders of a template
+    fn placeholders(template: &str) -> BTreeSet<&str> {
+        template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    /// Every string literal shaped like a key (`hud.title`) whose first part is one of
+    /// `namespaces`. Code between quotes never has that shape, so escaped quotes don't matter.
+    fn referenced_keys(source: &str, namespaces: &BTreeSet<&str>) -> BTreeSet<String> {
+        source
+            .split('"')
+            .filter(|literal| {
+                let mut parts = literal.split('.');
+                namespaces.contains(parts.next().unwrap_or_default())
+                    && literal.contains('.')
+                    && literal
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_every_key_in_code_is_in_english() {
+        let english = parse(ENGLISH).unwrap().strings;
+        let namespaces: BTreeSet<&str> =
+            english.keys().filter_map(|key| key.split('.').next()).collect();
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&src).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for key in referenced_keys(&source, &namespaces) {
+                assert!(english.contains_key(&key), "{key} ({}) is not in en.ron", path.display());
+                checked += 1;
+            }
+        }
+        assert!(checked > 100, "only {checked} keys found in the source");
+    }
+
+    #[test]
+    fn test_ukrainian_translates_every_key() {
+        let english = parse(ENGLISH).unwrap().strings;
+        let ukrainian = parse(UKRAINIAN).unwrap();
+        assert!(ukrainian.font.is_some());
+        for (key, template) in &english {
+            let translated = ukrainian.strings.get(key).unwrap_or_else(|| panic!("uk.ron lacks {key}"));
+            assert_eq!(placeholders(translated), placeholders(template), "placeholders of {key}");
+        }
+        let extra: Vec<_> = ukrainian.strings.keys().filter(|k| !english.contains_key(*k)).collect();
+        assert!(extra.is_empty(), "uk.ron has keys English lacks: {extra:?}");
+    }
+
+    #[test]
+    fn test_templates_and_fallback() {
+        let dir = std::env::temp_dir().join(format!("matrix_lang_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("xx.ron"),
+            r#"(decimal_separator: ',', group_separator: Some('.'), strings: {"common.yes": "Ja"})"#,
+        )
+        .unwrap();
+
+        let strings = Strings::load_from(&dir, "xx");
+        assert_eq!(strings.language, "xx");
+        assert_eq!(strings.yes_no(true), "Ja");
+        // Missing from the language: English
+        assert_eq!(strings.yes_no(false), "No");
+        // Missing everywhere: the key itself
+        assert_eq!(strings.tr("no.such_key", &[]), "no.such_key");
+        assert_eq!(strings.number(1234567.891, 2), "1.234.567,89");
+
+        let english = Strings::load_from(&dir, "en");
+        assert_eq!(
+            english.tr("hud.counts", &[("regions", &3), ("stars", &"1.5K"), ("planets", &7)]),
+            "Regions: 3 | Stars: 1.5K | Planets: 7"
+        );
+        // Placeholders without an argument stay as they are
+        assert_eq!(english.tr("hud.position", &[("x", &1)]), "Pos: (1, {y}, {z})");
+
+        // An unknown language, or a malformed file, is English
+        assert_eq!(Strings::load_from(&dir, "zz").language, "en");
+        std::fs::write(dir.join("bad.ron"), "(strings: 5").unwrap();
+        assert_eq!(Strings::load_from(&dir, "bad").language, "en");
+        // A shipped language without its file is the built-in copy
+        assert_eq!(Strings::load_from(&dir, "uk").language, "uk");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_numbers_follow_the_locale() {
+        let english = Strings::default();
+        assert_eq!(english.number(1234.5, 1), "1,234.5");
+        assert_eq!(english.number(-999.0, 0), "-999");
+        assert_eq!(english.number(0.25, 3), "0.250");
+        assert_eq!(english.decimal(-1234.5, 1), "-1234.5");
+        assert_eq!(english.count(999), "999");
+        assert_eq!(english.count(1_500), "1.5K");
+        assert_eq!(english.count(2_000_000_000), "2.0B");
+        assert_eq!(english.scientific(1.5e6, 2), "1.50e6");
+
+        let ukrainian = Strings::load_from(Path::new("no_such_dir"), "uk");
+        assert_eq!(ukrainian.number(1234.5, 1), "1 234,5");
+        assert_eq!(ukrainian.count(1_500), "1,5 тис.");
+        assert_eq!(ukrainian.scientific(1.5e6, 2), "1,50e6");
+    }
+
+    #[test]
+    fn test_language_cycle() {
+        assert_eq!(next_language("en", 1), "uk");
+        assert_eq!(next_language("uk", 1), "en");
+        assert_eq!(next_language("en", -1), "uk");
+        assert_eq!(language_name("uk"), "Українська");
+        assert_eq!(language_name("xx"), "xx");
+    }
+}
@@ -1,41 +1,153 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
-use matrix_core::SimConfig;
+use matrix_core::{SimConfig, StartScenario};
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
 use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::scenario::{self, StartedWorld};
 pub use matrix_sim::state::AppState;
 use matrix_sim::universe::UniverseState;
-use rand::SeedableRng;
-use std::path::PathBuf;
+use matrix_storage::{SnapshotError, SnapshotMeta};
+use std::path::{Path, PathBuf};
 
+use super::camera::{self, FlyCamera, ZoomLevel};
+use super::gallery;
+use super::locale::Strings;
+use super::pause;
+
+/// Main menu, loading screen and the in-game pause menu
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Menu), spawn_menu)
-            .add_systems(OnExit(AppState::Menu), despawn_menu)
+            .add_systems(OnExit(AppState::Menu), (despawn_menu, gallery::close_gallery))
             .add_systems(
                 Update,
-                menu_button_system.run_if(in_state(AppState::Menu)),
+                (
+                    menu_button_system,
+                    settings_button_system,
+                    settings_value_system,
+                    save_list_scroll_system,
+                    compare_button_system,
+                    compare_poll_system,
+                    gallery::gallery_button_system,
+                    gallery::gallery_poll_system,
+                )
+                    .run_if(in_state(AppState::Menu)),
             )
             .add_systems(OnEnter(AppState::Loading), spawn_loading_screen)
             .add_systems(OnExit(AppState::Loading), despawn_loading_screen)
             .add_systems(
                 Update,
                 loading_poll_system.run_if(in_state(AppState::Loading)),
+            )
+            .init_resource::<pause::PauseMenu>()
+            .add_systems(OnEnter(AppState::Paused), pause::spawn_pause_menu)
+            .add_systems(OnExit(AppState::Paused), pause::despawn_pause_menu)
+            .add_systems(
+                OnTransition {
+                    exited: AppState::Paused,
+                    entered: AppState::Menu,
+                },
+                pause::teardown_world,
+            )
+            .add_systems(
+                Update,
+                (
+                    pause::pause_input_system,
+                    pause::pause_button_system,
+                    pause::settings_page_system,
+                    pause::binding_scroll_system,
+                    pause::pause_page_system
+                        .after(pause::pause_input_system)
+                        .after(pause::pause_button_system),
+                    pause::pause_text_system
+                        .after(pause::pause_page_system)
+                        .after(pause::settings_page_system),
+                )
+                    .run_if(in_state(AppState::Paused)),
+            )
+            .add_systems(
+                Update,
+                (
+                    pause::apply_settings_system,
+                    pause::autosave_system.run_if(in_state(AppState::Running)),
+                ),
             );
     }
 }
 
 // --- Markers ---
 
+/// Everything on the main menu screens, despawned when one is swapped for another
 #[derive(Component)]
-struct MenuRoot;
+pub(crate) struct MenuRoot;
 
 #[derive(Component)]
 struct NewWorldButton;
 
 #[derive(Component)]
-struct LoadSaveButton;
+struct GalleryButton;
+
+/// Load button for one save slot
+#[derive(Component)]
+struct SaveSlotButton {
+    path: PathBuf,
+}
+
+/// Delete button for one save slot
+#[derive(Component)]
+struct DeleteSlotButton {
+    path: PathBuf,
+}
+
+/// Compare button for one save slot: what changed between it and `newest`, the latest
+/// other save of the same universe
+#[derive(Component)]
+struct CompareSlotButton {
+    path: PathBuf,
+    newest: PathBuf,
+}
+
+/// Overlay with the comparison of two saves, and its text and Close button
+#[derive(Component)]
+struct ComparePanel;
+
+#[derive(Component)]
+struct CompareText;
+
+#[derive(Component)]
+struct CloseCompareButton;
+
+/// Comparison being loaded: both saves decoded and diffed off the main thread
+#[derive(Resource)]
+struct CompareTask(Task<String>);
+
+/// Scrollable container holding the save slot rows
+#[derive(Component)]
+struct SaveList;
+
+/// A +/- stepper for one setting (`dir` is -1 or +1)
+#[derive(Component)]
+struct StepButton {
+    field: SettingField,
+    dir: i32,
+}
+
+/// Text showing the current value of one setting
+#[derive(Component)]
+struct SettingValue(SettingField);
+
+#[derive(Component)]
+struct RandomSeedButton;
+
+#[derive(Component)]
+struct GenerateButton;
+
+#[derive(Component)]
+struct BackButton;
 
 #[derive(Component)]
 struct LoadingRoot;
@@ -51,13 +163,100 @@ struct LoadAction {
     is_save_load: bool,
 }
 
+/// Universe age a mature new world starts at
+const START_AGE_MATURE: f64 = 10.0;
+
+/// Config being edited on the settings screen, used once "Generate" is pressed
+#[derive(Resource)]
+struct PendingConfig {
+    config: SimConfig,
+}
+
+/// Editable fields of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingField {
+    Seed,
+    ParticleCount,
+    DarkMatter,
+    Gravity,
+    StartAge,
+}
+
+impl SettingField {
+    const ALL: [SettingField; 5] = [
+        SettingField::Seed,
+        SettingField::ParticleCount,
+        SettingField::DarkMatter,
+        SettingField::Gravity,
+        SettingField::StartAge,
+    ];
+
+    fn label(self, strings: &Strings) -> String {
+        let key = match self {
+            SettingField::Seed => "menu.seed",
+            SettingField::ParticleCount => "menu.particles",
+            SettingField::DarkMatter => "menu.dark_matter",
+            SettingField::Gravity => "menu.gravity",
+            SettingField::StartAge => "menu.start",
+        };
+        strings.tr(key, &[])
+    }
+
+    fn value(self, pending: &PendingConfig, strings: &Strings) -> String {
+        let config = &pending.config;
+        match self {
+            SettingField::Seed => config.seed.to_string(),
+            SettingField::ParticleCount => strings.number(config.particle_count as f64, 0),
+            SettingField::DarkMatter => {
+                format!("{}%", strings.number(config.dark_matter_fraction as f64 * 100.0, 0))
+            }
+            SettingField::Gravity => format!("{}x", strings.number(config.gravity_scale as f64, 1)),
+            SettingField::StartAge => match config.start {
+                StartScenario::BigBang => strings.tr("menu.big_bang", &[]),
+                StartScenario::MatureUniverse { age } => {
+                    strings.tr("menu.start_age", &[("age", &strings.number(age, 0))])
+                }
+            },
+        }
+    }
+
+    /// Apply one stepper click
+    fn step(self, pending: &mut PendingConfig, dir: i32) {
+        let config = &mut pending.config;
+        match self {
+            SettingField::Seed => config.seed = config.seed.wrapping_add_signed(dir as i64),
+            // Doubling/halving covers the useful range in a few clicks
+            SettingField::ParticleCount => {
+                config.particle_count = if dir > 0 {
+                    config.particle_count.saturating_mul(2).min(1_000_000)
+                } else {
+                    (config.particle_count / 2).max(1_000)
+                }
+            }
+            SettingField::DarkMatter => {
+                config.dark_matter_fraction =
+                    (config.dark_matter_fraction + 0.05 * dir as f32).clamp(0.0, 0.95)
+            }
+            SettingField::Gravity => {
+                config.gravity_scale = (config.gravity_scale + 0.1 * dir as f32).clamp(0.1, 5.0)
+            }
+            SettingField::StartAge => {
+                config.start = match config.start {
+                    StartScenario::BigBang => StartScenario::MatureUniverse {
+                        age: START_AGE_MATURE,
+                    },
+                    StartScenario::MatureUniverse { .. } => StartScenario::BigBang,
+                }
+            }
+        }
+    }
+}
+
 enum WorldGenResult {
-    NewWorld {
-        universe: UniverseState,
-        lazy: LazyUniverse,
-    },
+    // Boxed to keep the two variants close in size
+    NewWorld(Box<StartedWorld>),
     LoadedSave {
-        snapshot: matrix_storage::UniverseSnapshot,
+        snapshot: Box<matrix_storage::UniverseSnapshot>,
     },
 }
 
@@ -65,32 +264,35 @@ fn saves_dir() -> PathBuf {
     PathBuf::from("saves")
 }
 
-fn has_saves() -> bool {
-    std::fs::read_dir(saves_dir())
-        .ok()
-        .map(|entries| {
-            entries
-                .filter_map(|e| e.ok())
-                .any(|e| e.path().extension().map_or(false, |ext| ext == "bin"))
-        })
-        .unwrap_or(false)
+/// Format a unix timestamp relative to now ("5m ago", "3d ago")
+fn fmt_saved_ago(strings: &Strings, saved_at: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let secs = now.saturating_sub(saved_at);
+    if saved_at == 0 {
+        strings.tr("menu.saved_unknown", &[])
+    } else if secs < 60 {
+        strings.tr("menu.saved_now", &[])
+    } else if secs < 3600 {
+        strings.tr("menu.saved_minutes", &[("n", &(secs / 60))])
+    } else if secs < 86_400 {
+        strings.tr("menu.saved_hours", &[("n", &(secs / 3600))])
+    } else {
+        strings.tr("menu.saved_days", &[("n", &(secs / 86_400))])
+    }
 }
 
-fn find_latest_save() -> Option<PathBuf> {
-    std::fs::read_dir(saves_dir())
-        .ok()
-        .and_then(|entries| {
-            entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().extension().map_or(false, |ext| ext == "bin"))
-                .max_by_key(|e| e.metadata().ok().and_then(|m| m.modified().ok()))
-                .map(|e| e.path())
-        })
+// --- Menu ---
+
+fn spawn_menu(mut commands: Commands, strings: Res<Strings>) {
+    build_menu(&mut commands, &strings);
 }
 
-// --- Menu ---
+pub(crate) fn build_menu(commands: &mut Commands, strings: &Strings) {
+    let saves = matrix_storage::list_snapshots(&saves_dir());
 
-fn spawn_menu(mut commands: Commands) {
     commands
         .spawn((
             Node {
@@ -116,7 +318,7 @@ fn spawn_menu(mut commands: Commands) {
             ));
 
             parent.spawn((
-                Text::new("Universe Simulation"),
+                Text::new(strings.tr("menu.subtitle", &[])),
                 TextFont {
                     font_size: 24.0,
                     ..default()
@@ -146,7 +348,7 @@ fn spawn_menu(mut commands: Commands) {
                 ))
                 .with_children(|btn| {
                     btn.spawn((
-                        Text::new("New Universe"),
+                        Text::new(strings.tr("menu.new_universe", &[])),
                         TextFont {
                             font_size: 28.0,
                             ..default()
@@ -155,32 +357,313 @@ fn spawn_menu(mut commands: Commands) {
                     ));
                 });
 
-            // "Load Save" button — only if saves exist
-            if has_saves() {
-                parent
-                    .spawn((
-                        Button,
-                        Node {
-                            width: Val::Px(300.0),
-                            height: Val::Px(60.0),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        BackgroundColor(Color::srgba(0.1, 0.2, 0.5, 0.9)),
-                        LoadSaveButton,
-                    ))
-                    .with_children(|btn| {
-                        btn.spawn((
-                            Text::new("Load Save"),
-                            TextFont {
-                                font_size: 28.0,
-                                ..default()
-                            },
-                            TextColor(Color::WHITE),
-                        ));
-                    });
+            spawn_text_button(
+                parent,
+                &strings.tr("gallery.title", &[]),
+                300.0,
+                Color::srgba(0.0, 0.3, 0.25, 0.9),
+                GalleryButton,
+            );
+
+            // Save slots — only if saves exist
+            if saves.is_empty() {
+                return;
             }
+
+            parent.spawn((
+                Text::new(strings.tr("menu.saved_universes", &[("count", &saves.len())])),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.0, 0.8, 0.3, 0.7)),
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(620.0),
+                        max_height: Val::Px(320.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(6.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    SaveList,
+                ))
+                .with_children(|list| {
+                    for meta in &saves {
+                        spawn_save_slot(list, strings, meta, newest_sibling(&saves, meta));
+                    }
+                });
+        });
+}
+
+/// The most recently written other save of the same universe (seed) as `meta`
+fn newest_sibling<'a>(saves: &'a [SnapshotMeta], meta: &SnapshotMeta) -> Option<&'a SnapshotMeta> {
+    saves
+        .iter()
+        .filter(|m| m.seed == meta.seed && m.path != meta.path)
+        .max_by_key(|m| m.saved_at)
+}
+
+/// One row in the save list: [load button with metadata] [compare] [delete]; compare
+/// only when another save of the universe exists
+fn spawn_save_slot(
+    list: &mut ChildBuilder,
+    strings: &Strings,
+    meta: &SnapshotMeta,
+    newest: Option<&SnapshotMeta>,
+) {
+    list.spawn(Node {
+        width: Val::Percent(100.0),
+        flex_direction: FlexDirection::Row,
+        column_gap: Val::Px(6.0),
+        flex_shrink: 0.0,
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Button,
+            Node {
+                flex_grow: 1.0,
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.2, 0.5, 0.9)),
+            SaveSlotButton {
+                path: meta.path.clone(),
+            },
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new(meta.name.clone()),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            btn.spawn((
+                Text::new(strings.tr(
+                    "menu.save_info",
+                    &[
+                        ("age", &strings.number(meta.age, 2)),
+                        ("cycle", &meta.cycle),
+                        ("seed", &meta.seed),
+                        ("civilizations", &meta.civilization_count),
+                        ("saved", &fmt_saved_ago(strings, meta.saved_at)),
+                    ],
+                )),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.7, 0.8, 1.0, 0.9)),
+            ));
+        });
+
+        if let Some(newest) = newest {
+            row.spawn((
+                Button,
+                Node {
+                    width: Val::Px(90.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.2, 0.3, 0.4, 0.9)),
+                CompareSlotButton {
+                    path: meta.path.clone(),
+                    newest: newest.path.clone(),
+                },
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    Text::new(strings.tr("menu.compare", &[])),
+                    TextFont {
+                        font_size: 15.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+        }
+
+        row.spawn((
+            Button,
+            Node {
+                width: Val::Px(44.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.5, 0.1, 0.1, 0.9)),
+            DeleteSlotButton {
+                path: meta.path.clone(),
+            },
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new("X"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+    });
+}
+
+/// New Universe settings screen: one stepper row per field, then Back / Generate
+fn build_settings(commands: &mut Commands, pending: &PendingConfig, strings: &Strings) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            MenuRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(strings.tr("menu.new_universe", &[])),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.0, 1.0, 0.4, 0.9)),
+            ));
+
+            parent.spawn(Node {
+                height: Val::Px(20.0),
+                ..default()
+            });
+
+            for field in SettingField::ALL {
+                spawn_setting_row(parent, field, pending, strings);
+            }
+
+            parent.spawn(Node {
+                height: Val::Px(20.0),
+                ..default()
+            });
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    let back = strings.tr("menu.back", &[]);
+                    spawn_text_button(row, &back, 140.0, Color::srgba(0.3, 0.3, 0.3, 0.9), BackButton);
+                    spawn_text_button(
+                        row,
+                        &strings.tr("menu.generate", &[]),
+                        220.0,
+                        Color::srgba(0.0, 0.4, 0.1, 0.9),
+                        GenerateButton,
+                    );
+                });
+        });
+}
+
+/// One settings row: [label] [-] [value] [+] (and [Random] for the seed)
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    field: SettingField,
+    pending: &PendingConfig,
+    strings: &Strings,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(8.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Text::new(field.label(strings)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.0, 0.8, 0.3, 0.9)),
+                Node {
+                    width: Val::Px(140.0),
+                    ..default()
+                },
+            ));
+
+            let stepper = Color::srgba(0.1, 0.2, 0.5, 0.9);
+            spawn_text_button(row, "-", 40.0, stepper, StepButton { field, dir: -1 });
+            row.spawn((
+                Text::new(field.value(pending, strings)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    width: Val::Px(200.0),
+                    ..default()
+                },
+                SettingValue(field),
+            ));
+            spawn_text_button(row, "+", 40.0, stepper, StepButton { field, dir: 1 });
+
+            if field == SettingField::Seed {
+                spawn_text_button(row, &strings.tr("menu.random", &[]), 100.0, stepper, RandomSeedButton);
+            } else {
+                // Keep the value column aligned with the seed row
+                row.spawn(Node {
+                    width: Val::Px(100.0),
+                    ..default()
+                });
+            }
+        });
+}
+
+/// Fixed-width button with a centered label
+pub(crate) fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: impl Bundle,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(color),
+            marker,
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
         });
 }
 
@@ -190,81 +673,302 @@ fn despawn_menu(mut commands: Commands, query: Query<Entity, With<MenuRoot>>) {
     }
 }
 
+/// Mouse wheel scrolls the save list
+fn save_list_scroll_system(
+    mut wheel: EventReader<MouseWheel>,
+    mut list_q: Query<&mut ScrollPosition, With<SaveList>>,
+) {
+    for event in wheel.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in &mut list_q {
+            scroll.offset_y = (scroll.offset_y - dy).max(0.0);
+        }
+    }
+}
+
 fn menu_button_system(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<AppState>>,
+    (mut commands, mut next_state): (Commands, ResMut<NextState<AppState>>),
     new_world_q: Query<&Interaction, (Changed<Interaction>, With<NewWorldButton>)>,
-    load_save_q: Query<&Interaction, (Changed<Interaction>, With<LoadSaveButton>)>,
-    universe: Res<UniverseState>,
+    gallery_q: Query<&Interaction, (Changed<Interaction>, With<GalleryButton>)>,
+    slot_q: Query<(&Interaction, &SaveSlotButton), Changed<Interaction>>,
+    delete_q: Query<(&Interaction, &DeleteSlotButton), Changed<Interaction>>,
+    root_q: Query<Entity, With<MenuRoot>>,
+    (universe, strings): (Res<UniverseState>, Res<Strings>),
 ) {
     // Hover color changes
     // (keeping it simple — just check for Pressed)
 
     for interaction in &new_world_q {
         if *interaction == Interaction::Pressed {
-            let config = universe.config.clone();
+            let pending = PendingConfig {
+                config: universe.config.clone(),
+            };
+            for entity in &root_q {
+                commands.entity(entity).despawn_recursive();
+            }
+            build_settings(&mut commands, &pending, &strings);
+            commands.insert_resource(pending);
+            return;
+        }
+    }
+
+    if gallery_q.iter().any(|i| *i == Interaction::Pressed) {
+        for entity in &root_q {
+            commands.entity(entity).despawn_recursive();
+        }
+        gallery::open_gallery(&mut commands, &strings, universe.config.clone());
+        return;
+    }
+
+    for (interaction, slot) in &delete_q {
+        if *interaction == Interaction::Pressed {
+            match matrix_storage::delete_snapshot(&slot.path) {
+                Ok(()) => info!("Deleted save {}", slot.path.display()),
+                Err(e) => error!("Failed to delete {}: {e}", slot.path.display()),
+            }
+            // Rebuild the menu so the list reflects the deletion
+            for entity in &root_q {
+                commands.entity(entity).despawn_recursive();
+            }
+            build_menu(&mut commands, &strings);
+            return;
+        }
+    }
+
+    for (interaction, slot) in &slot_q {
+        if *interaction == Interaction::Pressed {
+            let path = slot.path.clone();
             let pool = AsyncComputeTaskPool::get();
             let task = pool.spawn(async move {
-                let lazy = LazyUniverse::new(config.clone(), 0.0);
-                let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
-                let particles = matrix_physics::particle::generate_big_bang(&config, &mut rng);
-                let uni = UniverseState::new(config, particles);
-                WorldGenResult::NewWorld {
-                    universe: uni,
-                    lazy,
+                match matrix_storage::load_snapshot(&path) {
+                    Ok(snapshot) => WorldGenResult::LoadedSave { snapshot: Box::new(snapshot) },
+                    Err(e) => {
+                        match e {
+                            SnapshotError::VersionTooNew { .. } => {
+                                error!("Save was written by a newer version of Matrix: {e}")
+                            }
+                            _ => error!("Failed to load snapshot: {e}"),
+                        }
+                        // Fallback: generate new world
+                        WorldGenResult::NewWorld(Box::new(scenario::start_world(SimConfig::default())))
+                    }
                 }
             });
             commands.insert_resource(WorldGenTask(task));
             commands.insert_resource(LoadAction {
-                is_save_load: false,
+                is_save_load: true,
             });
             next_state.set(AppState::Loading);
             return;
         }
     }
+}
 
-    for interaction in &load_save_q {
+/// Steppers, random seed, Back and Generate on the settings screen
+fn settings_button_system(
+    (mut commands, mut next_state, strings): (Commands, ResMut<NextState<AppState>>, Res<Strings>),
+    pending: Option<ResMut<PendingConfig>>,
+    step_q: Query<(&Interaction, &StepButton), Changed<Interaction>>,
+    random_q: Query<&Interaction, (Changed<Interaction>, With<RandomSeedButton>)>,
+    generate_q: Query<&Interaction, (Changed<Interaction>, With<GenerateButton>)>,
+    back_q: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    root_q: Query<Entity, With<MenuRoot>>,
+) {
+    let Some(mut pending) = pending else { return };
+
+    for (interaction, step) in &step_q {
         if *interaction == Interaction::Pressed {
-            if let Some(path) = find_latest_save() {
-                let pool = AsyncComputeTaskPool::get();
-                let task = pool.spawn(async move {
-                    match matrix_storage::load_snapshot(&path) {
-                        Ok(snapshot) => WorldGenResult::LoadedSave { snapshot },
-                        Err(e) => {
-                            error!("Failed to load snapshot: {e}");
-                            // Fallback: generate new world
-                            let config = SimConfig::default();
-                            let lazy = LazyUniverse::new(config.clone(), 0.0);
-                            let mut rng =
-                                rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
-                            let particles =
-                                matrix_physics::particle::generate_big_bang(&config, &mut rng);
-                            let uni = UniverseState::new(config, particles);
-                            WorldGenResult::NewWorld {
-                                universe: uni,
-                                lazy,
-                            }
-                        }
-                    }
-                });
-                commands.insert_resource(WorldGenTask(task));
-                commands.insert_resource(LoadAction {
-                    is_save_load: true,
-                });
-                next_state.set(AppState::Loading);
+            step.field.step(&mut pending, step.dir);
+        }
+    }
+
+    for interaction in &random_q {
+        if *interaction == Interaction::Pressed {
+            // u32 keeps the seed short enough to read back and type in later
+            pending.config.seed = u64::from(rand::random::<u32>());
+        }
+    }
+
+    for interaction in &back_q {
+        if *interaction == Interaction::Pressed {
+            for entity in &root_q {
+                commands.entity(entity).despawn_recursive();
             }
+            commands.remove_resource::<PendingConfig>();
+            build_menu(&mut commands, &strings);
             return;
         }
     }
+
+    for interaction in &generate_q {
+        if *interaction == Interaction::Pressed {
+            start_new_world(&mut commands, &mut next_state, pending.config.clone());
+            commands.remove_resource::<PendingConfig>();
+            return;
+        }
+    }
+}
+
+/// Generate a universe from `config` off the main thread, behind the loading screen
+pub(crate) fn start_new_world(
+    commands: &mut Commands,
+    next_state: &mut NextState<AppState>,
+    config: SimConfig,
+) {
+    let pool = AsyncComputeTaskPool::get();
+    let task =
+        pool.spawn(async move { WorldGenResult::NewWorld(Box::new(scenario::start_world(config))) });
+    commands.insert_resource(WorldGenTask(task));
+    commands.insert_resource(LoadAction {
+        is_save_load: false,
+    });
+    next_state.set(AppState::Loading);
+}
+
+/// Refresh the value texts after a stepper click
+fn settings_value_system(
+    pending: Option<Res<PendingConfig>>,
+    strings: Res<Strings>,
+    mut value_q: Query<(&mut Text, &SettingValue)>,
+) {
+    let Some(pending) = pending else { return };
+    if !pending.is_changed() {
+        return;
+    }
+    for (mut text, value) in &mut value_q {
+        **text = value.0.value(&pending, &strings);
+    }
+}
+
+// --- Save comparison ---
+
+/// [Compare] opens the panel and diffs the slot against the newest save of its universe
+/// off the main thread; [Close] removes the panel
+fn compare_button_system(
+    mut commands: Commands,
+    strings: Res<Strings>,
+    compare_q: Query<(&Interaction, &CompareSlotButton), Changed<Interaction>>,
+    close_q: Query<&Interaction, (Changed<Interaction>, With<CloseCompareButton>)>,
+    panel_q: Query<Entity, With<ComparePanel>>,
+) {
+    let compare = compare_q.iter().find(|(i, _)| **i == Interaction::Pressed).map(|(_, slot)| slot);
+    if compare.is_none() && !close_q.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for entity in &panel_q {
+        commands.entity(entity).despawn_recursive();
+    }
+    // Dropping a running comparison cancels it
+    commands.remove_resource::<CompareTask>();
+    let Some(slot) = compare else {
+        return;
+    };
+
+    let (path, newest) = (slot.path.clone(), slot.newest.clone());
+    let task = AsyncComputeTaskPool::get().spawn(async move { compare_saves(&path, &newest) });
+    commands.insert_resource(CompareTask(task));
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ZIndex(1),
+            ComparePanel,
+            MenuRoot,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(680.0),
+                        max_height: Val::Percent(80.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(12.0),
+                        padding: UiRect::all(Val::Px(16.0)),
+                        overflow: Overflow::clip_y(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.02, 0.05, 0.1, 0.97)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(strings.tr("menu.comparing", &[])),
+                        TextFont {
+                            font_size: 15.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgba(0.8, 0.9, 1.0, 0.95)),
+                        Node {
+                            width: Val::Percent(100.0),
+                            ..default()
+                        },
+                        CompareText,
+                    ));
+                    spawn_text_button(
+                        panel,
+                        &strings.tr("menu.close", &[]),
+                        140.0,
+                        Color::srgba(0.3, 0.3, 0.3, 0.9),
+                        CloseCompareButton,
+                    );
+                });
+        });
+}
+
+/// What changed between two saves of a universe, the older one first
+fn compare_saves(a: &Path, b: &Path) -> String {
+    let load = |path: &Path| {
+        matrix_storage::load_snapshot(path).map_err(|e| format!("Could not read {}: {e}", path.display()))
+    };
+    let (a, b) = match (load(a), load(b)) {
+        (Ok(snapshot_a), Ok(snapshot_b)) => ((a, snapshot_a), (b, snapshot_b)),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    let ((old_path, old), (new_path, new)) = if a.1.saved_at <= b.1.saved_at { (a, b) } else { (b, a) };
+    let name = |path: &Path| path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+    match matrix_storage::diff_snapshots(&old, &new) {
+        Ok(diff) if diff.is_empty() => format!("{} and {} are the same", name(old_path), name(new_path)),
+        Ok(diff) => format!("{} -> {}\n\n{diff}", name(old_path), name(new_path)),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Show the comparison once it's done
+fn compare_poll_system(
+    mut commands: Commands,
+    task: Option<ResMut<CompareTask>>,
+    mut text_q: Query<&mut Text, With<CompareText>>,
+) {
+    let Some(mut task) = task else { return };
+    let Some(summary) = block_on(poll_once(&mut task.0)) else {
+        return;
+    };
+    commands.remove_resource::<CompareTask>();
+    for mut text in &mut text_q {
+        **text = summary.clone();
+    }
 }
 
 // --- Loading screen ---
 
-fn spawn_loading_screen(mut commands: Commands, action: Option<Res<LoadAction>>) {
-    let msg = if action.map_or(false, |a| a.is_save_load) {
-        "Loading save..."
+fn spawn_loading_screen(
+    mut commands: Commands,
+    strings: Res<Strings>,
+    action: Option<Res<LoadAction>>,
+) {
+    let msg = if action.is_some_and(|a| a.is_save_load) {
+        strings.tr("menu.loading_save", &[])
     } else {
-        "Generating universe..."
+        strings.tr("menu.generating", &[])
     };
 
     commands
@@ -302,8 +1006,13 @@ fn loading_poll_system(
     mut commands: Commands,
     task: Option<ResMut<WorldGenTask>>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut universe: ResMut<UniverseState>,
-    mut lazy: ResMut<LazyUniverse>,
+    (mut universe, mut lazy, mut feed, mut history): (
+        ResMut<UniverseState>,
+        ResMut<LazyUniverse>,
+        ResMut<EventFeed>,
+        ResMut<UniverseHistory>,
+    ),
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
 ) {
     let Some(mut gen_task) = task else { return };
 
@@ -312,12 +1021,24 @@ fn loading_poll_system(
     };
 
     match result {
-        WorldGenResult::NewWorld {
-            universe: new_uni,
-            lazy: new_lazy,
-        } => {
-            *universe = new_uni;
-            *lazy = new_lazy;
+        WorldGenResult::NewWorld(world) => {
+            *universe = world.universe;
+            *lazy = world.lazy;
+            feed.restore(Vec::new());
+            history.restore(Vec::new());
+            // Into the loaded region of a mature universe, above the Big Bang otherwise
+            if let Ok((mut transform, mut cam)) = cam_q.get_single_mut() {
+                let (position, target, level) = match world.viewpoint {
+                    Some((position, look_at)) => (
+                        Vec3::from_array(position.map(|c| c as f32)),
+                        Vec3::from_array(look_at.map(|c| c as f32)),
+                        ZoomLevel::Stellar,
+                    ),
+                    None => (camera::START_POSITION, Vec3::ZERO, ZoomLevel::Cosmic),
+                };
+                camera::place_camera(&mut transform, &mut cam, position, target);
+                cam.zoom_level = level;
+            }
             info!(
                 "World generated: {} regions, {} particles",
                 lazy.region_count(),
@@ -332,18 +1053,35 @@ fn loading_poll_system(
             universe.temperature = snapshot.temperature;
             universe.total_entropy = snapshot.total_entropy;
             universe.config = snapshot.config;
+            lazy.config = universe.config.clone();
             universe.particles = snapshot.particles.iter().map(|p| p.into()).collect();
             universe.time_scale = snapshot.time_scale;
             universe.paused = snapshot.paused;
             universe.cached_alive_count = universe.particles.len();
             universe.particles_generation = universe.particles_generation.wrapping_add(1);
 
-            lazy.regions = snapshot.regions;
+            lazy.reset_region_loading();
+
+            lazy.regions = snapshot.regions.into();
             lazy.current_region_id = snapshot.current_region_id;
-            lazy.loaded_stars = snapshot.loaded_stars;
+            lazy.loaded_stars = snapshot.loaded_stars.into();
+            lazy.stars_age = snapshot.stars_age;
+            lazy.loaded_galaxies = snapshot.galaxies;
+            // Keep the saved orbits instead of regenerating stars on the next age reload
+            lazy.last_reload_age = snapshot.age;
             lazy.life_planets = snapshot.life_planets;
+            lazy.discoveries = snapshot.discoveries;
+            lazy.civilizations = snapshot.civilizations;
             lazy.civilization_count = snapshot.civilization_count;
+            lazy.surface_memory = snapshot.surface_memory;
+            lazy.bestiary = snapshot.bestiary;
+            lazy.names = snapshot.names;
+            lazy.bookmarks = snapshot.bookmarks;
+            lazy.refresh_region_flags();
+            lazy.region_flags_generation = lazy.region_flags_generation.wrapping_add(1);
             lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
+            feed.restore(snapshot.events);
+            history.restore(snapshot.history);
 
             info!(
                 "Save loaded: age {:.4} Gyr, {} particles",
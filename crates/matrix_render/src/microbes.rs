@@ -0,0 +1,514 @@
+//! Microscopic zoom: the microbe ecology of the dominant genome. Populations are seeded
+//! per half-meter cell from the planet id and the cell, so the same spot always starts
+//! with the same cells; swimmers tumble and run, drifters jiggle, both gather at a few
+//! drifting nutrient hotspots, and in richer biospheres predators pick off smaller prey.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use matrix_core::{Genome, Planet};
+use matrix_physics::terrain::terrain_height;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::budget::{Category, RenderBudget};
+use super::camera::FlyCamera;
+use super::props::{merge, part};
+use super::surface::{SurfaceState, SurfaceZoom, substrate_color};
+
+/// Side of a population cell (m); the 3×3 cells around the camera are populated
+const MICROBE_CELL: f32 = 0.5;
+/// Height band above the ground microbes live in (m)
+const MICROBE_LAYER: f32 = 0.3;
+/// Rendered radius of a ~2 µm cell (magnified to be visible at microscopic zoom)
+const MICROBE_VISUAL: f32 = 0.004;
+/// Typical microbe size (log10 m) when the dominant genome is macroscopic
+const MICROBE_SIZE_LOG: f64 = -5.7;
+/// Nutrient hotspots drifting around the camera
+const HOTSPOTS: usize = 3;
+/// Run lengths of tumble-and-run swimmers: longer when heading up the nutrient gradient
+const RUN_TOWARD: f32 = 1.5;
+const RUN_AWAY: f32 = 0.4;
+/// Step size of Brownian drift (m per √s)
+const BROWNIAN: f32 = 0.01;
+/// How far a predator senses prey (m), and how long it digests after a meal (s)
+const HUNT_RANGE: f32 = 0.12;
+const DIGEST_SECS: f32 = 6.0;
+
+/// Cell shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicrobeShape {
+    /// Sphere (coccus)
+    Coccus,
+    /// Capsule (bacillus)
+    Rod,
+    /// Helix of beads (spirillum)
+    Spiral,
+    /// Beaded chain of cells (colonial life)
+    Chain,
+    /// Sterile worlds: mineral dust
+    Dust,
+}
+
+impl MicrobeShape {
+    pub fn adjective(self) -> &'static str {
+        match self {
+            Self::Coccus => "spherical",
+            Self::Rod => "rod-shaped",
+            Self::Spiral => "spiral",
+            Self::Chain => "chain-forming",
+            Self::Dust => "mineral",
+        }
+    }
+}
+
+/// What the microscopic view of a planet shows, derived from its biosphere
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicrobeProfile {
+    pub shape: MicrobeShape,
+    /// Tumble-and-run swimmers; otherwise Brownian drifters
+    pub swims: bool,
+    /// Cells per population cell
+    pub per_cell: usize,
+    /// Typical size (log10 m)
+    pub size_log: f64,
+    pub color: Color,
+    /// Share of predator cells (complexity ≥ 2)
+    pub predators: f64,
+    /// Run speed (m/s)
+    pub speed: f32,
+    energy: &'static str,
+    eukaryotic: bool,
+}
+
+impl MicrobeProfile {
+    pub fn for_planet(planet: &Planet) -> Self {
+        let Some(ref bio) = planet.life else {
+            return Self {
+                shape: MicrobeShape::Dust,
+                swims: false,
+                per_cell: 1,
+                size_log: -5.0,
+                color: Color::srgba(0.55, 0.52, 0.5, 0.6),
+                predators: 0.0,
+                speed: 0.004,
+                energy: "",
+                eukaryotic: false,
+            };
+        };
+        let genome = &bio.dominant_genome;
+        let shape = match genome.structure {
+            1 | 2 => MicrobeShape::Chain,
+            _ => match (genome.substrate + genome.energy_source) % 3 {
+                0 => MicrobeShape::Coccus,
+                1 => MicrobeShape::Rod,
+                _ => MicrobeShape::Spiral,
+            },
+        };
+        Self {
+            shape,
+            swims: genome.motility >= 2,
+            per_cell: (2.0 + bio.biomass.max(0.0).sqrt() * 2.0 + bio.complexity).clamp(2.0, 10.0)
+                as usize,
+            size_log: if genome.size_log < -4.0 {
+                genome.size_log
+            } else {
+                MICROBE_SIZE_LOG
+            },
+            color: substrate_color(genome.substrate).with_alpha(0.75),
+            predators: if bio.complexity >= 2.0 { 0.1 } else { 0.0 },
+            speed: 0.02 + 0.01 * genome.motility.min(5) as f32,
+            energy: Genome::energy_name(genome.energy_source),
+            eukaryotic: bio.complexity >= 3.0,
+        }
+    }
+
+    /// e.g. "rod-shaped chemosynthetic prokaryotes, ~2 µm"
+    pub fn describe(&self) -> String {
+        let microns = 10f64.powf(self.size_log) * 1e6;
+        if self.shape == MicrobeShape::Dust {
+            return format!("mineral dust motes, no life, ~{microns:.0} µm");
+        }
+        let kind = if self.eukaryotic {
+            "protists"
+        } else {
+            "prokaryotes"
+        };
+        format!(
+            "{} {} {kind}, ~{microns:.0} µm",
+            self.shape.adjective(),
+            self.energy
+        )
+    }
+
+    /// The cells a population cell starts with, deterministic per planet and cell
+    pub fn seed_cell(&self, planet_id: u64, cell: IVec2) -> Vec<MicrobeSeed> {
+        let seed = planet_id.wrapping_mul(0xA24B_AED4_963E_E407)
+            ^ ((cell.x as u32 as u64) << 32 | cell.y as u32 as u64);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let origin = cell.as_vec2() * MICROBE_CELL;
+        (0..self.per_cell)
+            .map(|_| {
+                let predator = rng.gen_bool(self.predators);
+                // Log-normal-ish spread of ±0.3 dex; predators are the big ones
+                let dex = rng.gen_range(-0.3..0.3) + if predator { 0.35 } else { 0.0 };
+                MicrobeSeed {
+                    position: Vec3::new(
+                        origin.x + rng.gen_range(0.0..MICROBE_CELL),
+                        rng.gen_range(0.01..MICROBE_LAYER),
+                        origin.y + rng.gen_range(0.0..MICROBE_CELL),
+                    ),
+                    scale: MICROBE_VISUAL
+                        * 10f32.powf(dex + 0.5 * (self.size_log - MICROBE_SIZE_LOG) as f32),
+                    heading: random_direction(&mut rng),
+                    predator,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One starting cell; `position.y` is the height above the ground
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MicrobeSeed {
+    pub position: Vec3,
+    pub scale: f32,
+    pub heading: Vec3,
+    pub predator: bool,
+}
+
+#[derive(Component)]
+pub struct Microbe {
+    /// Population cell it was seeded in
+    pub cell: IVec2,
+    pub heading: Vec3,
+    /// Time left in the current run (swimmers)
+    pub run: f32,
+    pub predator: bool,
+    /// Time until a predator hunts again
+    pub digesting: f32,
+}
+
+/// Microscopic view state of the current landing
+#[derive(Default)]
+pub struct MicrobeField {
+    /// (planet id, surface generation, budget generation) the assets were built for
+    key: Option<(u64, u32, u32)>,
+    profile: Option<MicrobeProfile>,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    predator_material: Handle<StandardMaterial>,
+    cells: HashSet<IVec2>,
+    hotspots: Vec<(Vec3, Vec3)>,
+    rng: Option<ChaCha8Rng>,
+}
+
+/// Populate the cells around the camera at microscopic zoom and move the microbes
+pub fn microbe_system(
+    mut commands: Commands,
+    (time, state): (Res<Time>, Res<SurfaceState>),
+    mut budget: ResMut<RenderBudget>,
+    mut field: Local<MicrobeField>,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    camera_q: Query<&Transform, With<FlyCamera>>,
+    mut microbe_q: Query<(Entity, &mut Transform, &mut Microbe), Without<FlyCamera>>,
+) {
+    let Some(ref planet) = state.planet else {
+        return;
+    };
+    let Ok(cam_tf) = camera_q.get_single() else {
+        return;
+    };
+    if state.surface_zoom != SurfaceZoom::Microscopic {
+        // Despawn all microbes when not microscopic
+        for (entity, _, _) in &microbe_q {
+            commands.entity(entity).despawn();
+        }
+        field.cells.clear();
+        field.hotspots.clear();
+        return;
+    }
+
+    let key = (planet.id, state.render_generation, budget.generation());
+    if field.key != Some(key) {
+        // A new budget reseeds the 3×3 cells around the camera at its count
+        for (entity, _, _) in &microbe_q {
+            commands.entity(entity).despawn();
+        }
+        let mut profile = MicrobeProfile::for_planet(planet);
+        profile.per_cell = budget.request(Category::Microbes, profile.per_cell * 9) / 9;
+        let predator_color = profile.color.darker(0.15).rotate_hue(40.0);
+        *field = MicrobeField {
+            key: Some(key),
+            mesh: meshes.add(microbe_mesh(profile.shape)),
+            material: materials.add(StandardMaterial {
+                base_color: profile.color,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            predator_material: materials.add(StandardMaterial {
+                base_color: predator_color,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            profile: Some(profile),
+            rng: Some(ChaCha8Rng::seed_from_u64(planet.id)),
+            ..default()
+        };
+    }
+    let field = &mut *field;
+    let (Some(profile), Some(rng)) = (field.profile.as_ref(), field.rng.as_mut()) else {
+        return;
+    };
+    let (seed, planet_type) = (state.terrain_seed, planet.planet_type);
+    let ground = |x: f32, z: f32| terrain_height(x, z, seed, &planet_type);
+    let cam_pos = cam_tf.translation;
+    let dt = time.delta_secs();
+
+    // Keep the 3×3 cells around the camera populated
+    let center = (cam_pos.xz() / MICROBE_CELL).floor().as_ivec2();
+    let near = |cell: IVec2| (cell - center).abs().max_element() <= 1;
+    field.cells.retain(|&cell| near(cell));
+    for (entity, _, microbe) in &microbe_q {
+        if !near(microbe.cell) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for dz in -1..=1 {
+        for dx in -1..=1 {
+            let cell = center + IVec2::new(dx, dz);
+            if !field.cells.insert(cell) {
+                continue;
+            }
+            for microbe in profile.seed_cell(planet.id, cell) {
+                let mut position = microbe.position;
+                position.y += ground(position.x, position.z);
+                let material = if microbe.predator {
+                    &field.predator_material
+                } else {
+                    &field.material
+                };
+                commands.spawn((
+                    Mesh3d(field.mesh.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(position)
+                        .with_rotation(Quat::from_rotation_arc(Vec3::Z, microbe.heading))
+                        .with_scale(Vec3::splat(microbe.scale)),
+                    Microbe {
+                        cell,
+                        heading: microbe.heading,
+                        run: 0.0,
+                        predator: microbe.predator,
+                        digesting: 0.0,
+                    },
+                ));
+            }
+        }
+    }
+
+    // Nutrient hotspots wander; ones left behind reappear near the camera
+    while field.hotspots.len() < HOTSPOTS {
+        field.hotspots.push((cam_pos, Vec3::ZERO));
+    }
+    for (pos, vel) in &mut field.hotspots {
+        if pos.xz().distance(cam_pos.xz()) > MICROBE_CELL * 1.5 {
+            let offset = random_direction(rng).xz() * rng.gen_range(0.0..MICROBE_CELL);
+            *pos = cam_pos + Vec3::new(offset.x, 0.0, offset.y);
+        }
+        *vel = (*vel + random_direction(rng) * 0.02 * dt).clamp_length_max(0.01);
+        *pos += *vel * dt;
+        pos.y = ground(pos.x, pos.z) + MICROBE_LAYER * 0.5;
+    }
+
+    // Prey positions for the predators
+    let prey: Vec<(Entity, Vec3, f32)> = microbe_q
+        .iter()
+        .filter(|(_, _, m)| !m.predator)
+        .map(|(e, tf, _)| (e, tf.translation, tf.scale.x))
+        .collect();
+    let mut eaten = HashSet::new();
+
+    for (entity, mut tf, mut microbe) in &mut microbe_q {
+        if eaten.contains(&entity) {
+            continue;
+        }
+        let pos = tf.translation;
+        let hotspot = field
+            .hotspots
+            .iter()
+            .map(|(p, _)| *p)
+            .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+            .unwrap_or(pos);
+        let mut target = hotspot;
+
+        // A hungry predator goes for the nearest smaller cell it senses, and eats it on contact
+        microbe.digesting -= dt;
+        if microbe.predator && microbe.digesting <= 0.0 {
+            let meal = prey
+                .iter()
+                .filter(|(e, p, s)| {
+                    !eaten.contains(e) && *s < tf.scale.x && p.distance(pos) < HUNT_RANGE
+                })
+                .min_by(|a, b| {
+                    a.1.distance_squared(pos)
+                        .total_cmp(&b.1.distance_squared(pos))
+                });
+            if let Some(&(prey_entity, prey_pos, prey_scale)) = meal {
+                if prey_pos.distance(pos) < (tf.scale.x + prey_scale) * 1.5 {
+                    commands.entity(prey_entity).despawn();
+                    eaten.insert(prey_entity);
+                    microbe.digesting = DIGEST_SECS;
+                    tf.scale = (tf.scale * 1.05).min(Vec3::splat(MICROBE_VISUAL * 6.0));
+                } else {
+                    target = prey_pos;
+                    microbe.run = microbe.run.min(0.1);
+                }
+            }
+        }
+
+        let toward = (target - pos).normalize_or_zero();
+        if profile.swims {
+            // Tumble and run: runs up the gradient last longer
+            microbe.run -= dt;
+            if microbe.run <= 0.0 {
+                microbe.heading = random_direction(rng);
+                microbe.run = if microbe.heading.dot(toward) > 0.0 {
+                    RUN_TOWARD
+                } else {
+                    RUN_AWAY
+                };
+                if target != hotspot {
+                    microbe.heading = toward;
+                }
+            }
+            tf.translation += microbe.heading * profile.speed * dt;
+        } else {
+            // Brownian jiggle with a slow pull toward the hotspot
+            tf.translation +=
+                random_direction(rng) * BROWNIAN * dt.sqrt() + toward * profile.speed * 0.3 * dt;
+        }
+
+        let floor = ground(tf.translation.x, tf.translation.z);
+        tf.translation.y = tf.translation.y.clamp(floor + 0.005, floor + MICROBE_LAYER);
+        if microbe.heading != Vec3::ZERO {
+            tf.rotation = Quat::from_rotation_arc(Vec3::Z, microbe.heading);
+        }
+    }
+}
+
+fn random_direction(rng: &mut ChaCha8Rng) -> Vec3 {
+    let v = Vec3::new(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-0.5..0.5),
+        rng.gen_range(-1.0..1.0),
+    );
+    v.try_normalize().unwrap_or(Vec3::X)
+}
+
+/// Unit-radius cell of the given shape, long axis along Z
+fn microbe_mesh(shape: MicrobeShape) -> Mesh {
+    let bead = |at: Vec3, radius: f32| {
+        part(
+            Sphere::new(radius).mesh().ico(1).unwrap(),
+            Color::WHITE,
+            Transform::from_translation(at),
+        )
+    };
+    match shape {
+        MicrobeShape::Coccus => bead(Vec3::ZERO, 1.0),
+        MicrobeShape::Rod => part(
+            Capsule3d::new(0.6, 2.0),
+            Color::WHITE,
+            Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        ),
+        MicrobeShape::Spiral => merge(
+            (0..10)
+                .map(|i| {
+                    let t = i as f32 / 9.0;
+                    let angle = t * std::f32::consts::TAU * 1.5;
+                    bead(
+                        Vec3::new(angle.cos() * 0.5, angle.sin() * 0.5, t * 3.0 - 1.5),
+                        0.35,
+                    )
+                })
+                .collect(),
+        ),
+        MicrobeShape::Chain => merge(
+            (0..5)
+                .map(|i| bead(Vec3::Z * (i as f32 - 2.0) * 1.6, 0.85))
+                .collect(),
+        ),
+        MicrobeShape::Dust => part(
+            Sphere::new(1.0).mesh().ico(0).unwrap(),
+            Color::WHITE,
+            Transform::from_scale(Vec3::new(1.0, 0.6, 0.8)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{AtmosphereType, Biosphere, PlanetType};
+
+    fn planet(life: Option<Biosphere>) -> Planet {
+        Planet {
+            id: 808,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 300.0,
+            tidally_locked: false,
+            has_water: true,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type: PlanetType::Rocky,
+            life,
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    #[test]
+    fn test_microbes_follow_the_genome() {
+        let sterile = MicrobeProfile::for_planet(&planet(None));
+        assert_eq!(sterile.shape, MicrobeShape::Dust);
+        assert!(
+            sterile
+                .seed_cell(808, IVec2::ZERO)
+                .iter()
+                .all(|m| !m.predator)
+        );
+        assert!(sterile.describe().starts_with("mineral dust"));
+
+        let mut genome = Genome::primordial();
+        genome.substrate = 0;
+        genome.energy_source = 1;
+        genome.motility = 2;
+        genome.size_log = -5.7;
+        let life = MicrobeProfile::for_planet(&planet(Some(Biosphere {
+            age: 1.0,
+            complexity: 2.5,
+            species_count: 50,
+            dominant_genome: genome,
+            has_technology: false,
+            biomass: 4.0,
+        })));
+        assert_eq!(
+            life.describe(),
+            "rod-shaped chemosynthetic prokaryotes, ~2 µm"
+        );
+        assert!(life.swims && life.per_cell > sterile.per_cell);
+
+        // The same spot always starts with the same population, inside its cell
+        let cell = IVec2::new(-3, 7);
+        let population = life.seed_cell(808, cell);
+        assert_eq!(population, life.seed_cell(808, cell));
+        assert_ne!(population, life.seed_cell(808, cell + IVec2::X));
+        for m in &population {
+            assert_eq!((m.position.xz() / MICROBE_CELL).floor().as_ivec2(), cell);
+        }
+    }
+}
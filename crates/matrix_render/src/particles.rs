@@ -1,18 +1,41 @@
 use bevy::prelude::*;
-use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
 use bevy::render::render_asset::RenderAssetUsages;
-use std::collections::HashMap;
-use matrix_core::ParticleKind;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+use matrix_core::{ParticleKind, temperature_to_color};
+use matrix_sim::black_holes::ACCRETION_GLOW_PER_MASS;
+use matrix_sim::timings::SimTimings;
 use matrix_sim::universe::UniverseState;
 
+use super::budget::RenderBudget;
+use super::legend::ParticleViewFilter;
+
 /// Marker for particle cloud entities (one per particle kind)
 #[derive(Component)]
 pub struct ParticleCloud {
     pub kind: u32,
 }
 
-/// Max particles to sample for rendering (fewer = faster)
-const MAX_SAMPLE: usize = 3_000;
+/// Dark sphere drawn for a black hole particle (its accretion disk is a child)
+#[derive(Component)]
+pub struct BlackHoleVisual {
+    /// Index into `UniverseState::particles`
+    pub particle: usize,
+}
+
+/// Emissive accretion disk billboard around a black hole
+#[derive(Component)]
+pub struct AccretionDisk {
+    pub material: Handle<StandardMaterial>,
+}
+
+/// Disk color at rest; recent captures multiply it (see `accretion_brightness`)
+const ACCRETION_DISK_COLOR: [f32; 3] = [1.0, 0.55, 0.2];
+/// Disk outer radius relative to the sphere
+const ACCRETION_DISK_RADIUS: f32 = 3.0;
+/// Sphere radius relative to the point-cloud triangle size at that distance
+const BLACK_HOLE_SCALE: f32 = 4.0;
 
 /// Distance culling for particle updates (squared) — large enough for cosmic view
 const CULL_DIST_SQ: f32 = 2000.0 * 2000.0;
@@ -20,10 +43,41 @@ const CULL_DIST_SQ: f32 = 2000.0 * 2000.0;
 /// Base triangle size (close-up). Scales with camera distance for cosmic visibility.
 const BASE_TRI_SIZE: f32 = 0.04;
 
+/// Without a new particle step, clouds are only resampled once the camera moved this
+/// share of its distance to the cloud center (the triangle size follows that distance)
+const CAMERA_RESAMPLE_FRACTION: f32 = 0.02;
+
+/// Every cloud triangle faces +Z; the normals are written once per vertex slot
+const CLOUD_NORMAL: [f32; 3] = [0.0, 0.0, 1.0];
+
+/// Positions and temperatures of the sampled particles of one kind
+#[derive(Default)]
+struct CloudSample {
+    positions: Vec<[f32; 3]>,
+    temperatures: Vec<f32>,
+}
+
+impl CloudSample {
+    fn push(&mut self, p: &matrix_core::GpuParticle) {
+        self.positions.push(p.pos());
+        self.temperatures.push(p.temperature);
+    }
+
+    /// Empty, keeping the allocations for the next resample
+    fn clear(&mut self) {
+        self.positions.clear();
+        self.temperatures.clear();
+    }
+}
+
+/// Per-kind samples kept by `update_particle_clouds` between resamples
+#[derive(Default)]
+pub struct CloudSamples(HashMap<u32, CloudSample>);
+
 /// Compute triangle size based on camera distance from particle cloud center.
 /// At 640 units (Cosmic): ~2.6 — visible as glowing dots.
 /// At 50 units (Stellar): ~0.2. At 5 units (Planetary): ~0.04 (base).
-fn compute_tri_size(cam_pos: Vec3, cloud_center: Vec3) -> f32 {
+pub(crate) fn compute_tri_size(cam_pos: Vec3, cloud_center: Vec3) -> f32 {
     let dist = cam_pos.distance(cloud_center);
     (dist * 0.004).clamp(BASE_TRI_SIZE, 3.0)
 }
@@ -33,21 +87,31 @@ fn compute_tri_size(cam_pos: Vec3, cloud_center: Vec3) -> f32 {
 pub struct ParticleCloudState {
     /// Last generation rendered
     pub render_generation: u32,
+    /// `RenderBudget::generation` the clouds were sampled for
+    pub budget_generation: u32,
     /// Per-kind: (entity, mesh_handle)
     pub clouds: HashMap<u32, (Entity, Handle<Mesh>)>,
     /// Per-kind material
     pub materials: HashMap<u32, Handle<StandardMaterial>>,
+    /// Per-kind particles sampled at the last rebuild (the legend's counts)
+    pub sampled: BTreeMap<u32, usize>,
     /// Frame counter for throttling mesh updates
     pub update_frame: u32,
+    /// `UniverseState::particle_steps`, camera position and cloud center at the last
+    /// resample; None forces the next one
+    pub resampled: Option<(u32, Vec3, Vec3)>,
 }
 
 impl Default for ParticleCloudState {
     fn default() -> Self {
         Self {
             render_generation: u32::MAX,
+            budget_generation: 0,
             clouds: HashMap::new(),
             materials: HashMap::new(),
+            sampled: BTreeMap::new(),
             update_frame: 0,
+            resampled: None,
         }
     }
 }
@@ -57,25 +121,31 @@ pub fn init_particle_cloud(mut commands: Commands) {
     commands.insert_resource(ParticleCloudState::default());
 }
 
-/// When particle generation changes: rebuild cloud entities (one mesh per kind)
+/// When particle generation (or the render budget's sample size) changes: rebuild cloud
+/// entities (one mesh per kind). Kinds hidden by the `ParticleViewFilter` are spawned hidden.
 pub fn sync_particle_clouds(
     mut commands: Commands,
-    universe: Res<UniverseState>,
+    (universe, budget, filter): (Res<UniverseState>, Res<RenderBudget>, Res<ParticleViewFilter>),
     mut state: ResMut<ParticleCloudState>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     camera_query: Query<&Transform, With<super::camera::FlyCamera>>,
 ) {
-    if universe.particles_generation == state.render_generation {
+    if universe.particles_generation == state.render_generation
+        && budget.generation() == state.budget_generation
+    {
         return;
     }
     state.render_generation = universe.particles_generation;
+    state.budget_generation = budget.generation();
 
     // Despawn old cloud entities
     for (_, (entity, _)) in state.clouds.drain() {
         commands.entity(entity).despawn();
     }
     state.materials.clear();
+    state.sampled.clear();
+    state.resampled = None;
 
     if universe.particles.is_empty() {
         return;
@@ -87,33 +157,36 @@ pub fn sync_particle_clouds(
         .unwrap_or(Vec3::ZERO);
 
     // Group particle positions by kind (with stride sampling)
-    let stride = (universe.particles.len() / MAX_SAMPLE).max(1);
-    let mut groups: HashMap<u32, Vec<[f32; 3]>> = HashMap::new();
+    let stride = (universe.particles.len() / budget.preset().particle_sample()).max(1);
+    let mut groups: HashMap<u32, CloudSample> = HashMap::new();
 
     for (i, p) in universe.particles.iter().enumerate() {
         if i % stride != 0 {
             continue;
         }
-        if !p.is_alive() {
+        if !p.is_alive() || p.kind == ParticleKind::BlackHole as u32 {
             continue;
         }
-        groups.entry(p.kind).or_default().push(p.pos());
+        groups.entry(p.kind).or_default().push(p);
     }
 
-    let total_sampled: usize = groups.values().map(|v| v.len()).sum();
+    let total_sampled: usize = groups.values().map(|v| v.positions.len()).sum();
+    state.sampled = groups.iter().map(|(kind, v)| (*kind, v.positions.len())).collect();
 
     // Compute cloud center (average of all positions) for tri_size scaling
     let cloud_center = compute_cloud_center(&groups);
     let tri_size = compute_tri_size(cam_pos, cloud_center);
 
-    for (kind_id, positions) in &groups {
-        let mesh = build_triangle_cloud(positions, tri_size);
+    for (kind_id, sample) in &groups {
+        let mesh = build_triangle_cloud(&sample.positions, tri_size).with_inserted_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            cloud_vertex_colors(*kind_id, &sample.temperatures).collect::<Vec<_>>(),
+        );
         let mesh_handle = meshes.add(mesh);
 
-        let color = kind_color(*kind_id);
+        // The color is per vertex (kind × temperature)
         let mat = materials.add(StandardMaterial {
-            base_color: color,
-            emissive: LinearRgba::from(color) * 3.0,
+            base_color: Color::WHITE,
             unlit: true,
             ..default()
         });
@@ -123,6 +196,7 @@ pub fn sync_particle_clouds(
                 Mesh3d(mesh_handle.clone()),
                 MeshMaterial3d(mat.clone()),
                 Transform::IDENTITY,
+                cloud_visibility(&filter, *kind_id),
                 ParticleCloud { kind: *kind_id },
             ))
             .id();
@@ -140,11 +214,16 @@ pub fn sync_particle_clouds(
     );
 }
 
-/// Update cloud mesh vertices every 3rd frame (position sync from simulation)
+/// Update cloud mesh vertices every 3rd frame (position sync from simulation), when a
+/// particle step landed since the last update or the camera moved enough to change the
+/// triangle size — so nothing is resampled while paused with the camera still. The
+/// samples and mesh buffers are reused. Hidden kinds are neither sampled nor rebuilt.
 pub fn update_particle_clouds(
-    universe: Res<UniverseState>,
+    (universe, budget, filter): (Res<UniverseState>, Res<RenderBudget>, Res<ParticleViewFilter>),
     mut state: ResMut<ParticleCloudState>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut timings: ResMut<SimTimings>,
+    mut samples: Local<CloudSamples>,
     camera_query: Query<&Transform, (With<super::camera::FlyCamera>, Without<ParticleCloud>)>,
 ) {
     if !universe.particles_active || universe.particles.is_empty() || state.clouds.is_empty() {
@@ -152,7 +231,7 @@ pub fn update_particle_clouds(
     }
 
     state.update_frame = state.update_frame.wrapping_add(1);
-    if state.update_frame % 3 != 0 {
+    if !state.update_frame.is_multiple_of(3) {
         return;
     }
 
@@ -160,46 +239,165 @@ pub fn update_particle_clouds(
         .get_single()
         .map(|t| t.translation)
         .unwrap_or(Vec3::ZERO);
+    if !filter.is_changed()
+        && let Some((steps, last_cam, center)) = state.resampled
+        && steps == universe.particle_steps
+        && cam_pos.distance(last_cam) <= cam_pos.distance(center) * CAMERA_RESAMPLE_FRACTION
+    {
+        return;
+    }
+    let start = Instant::now();
 
-    // Rebuild per-kind position lists with distance culling
-    let stride = (universe.particles.len() / MAX_SAMPLE).max(1);
-    let mut groups: HashMap<u32, Vec<[f32; 3]>> = HashMap::new();
-
-    for (i, p) in universe.particles.iter().enumerate() {
-        if i % stride != 0 {
-            continue;
-        }
-        if !p.is_alive() {
+    // Rebuild per-kind position lists with distance culling, summing the cloud center on the way
+    let stride = (universe.particles.len() / budget.preset().particle_sample()).max(1);
+    let groups = &mut samples.0;
+    for sample in groups.values_mut() {
+        sample.clear();
+    }
+    let mut sum = Vec3::ZERO;
+    let mut count = 0u32;
+    for p in universe.particles.iter().step_by(stride) {
+        if !p.is_alive() || p.kind == ParticleKind::BlackHole as u32 || !filter.is_visible(p.kind) {
             continue;
         }
-        let dx = p.position[0] - cam_pos.x;
-        let dy = p.position[1] - cam_pos.y;
-        let dz = p.position[2] - cam_pos.z;
-        if dx * dx + dy * dy + dz * dz > CULL_DIST_SQ {
+        let pos = Vec3::from_array(p.pos());
+        if pos.distance_squared(cam_pos) > CULL_DIST_SQ {
             continue;
         }
-        groups.entry(p.kind).or_default().push(p.pos());
+        sum += pos;
+        count += 1;
+        groups.entry(p.kind).or_default().push(p);
     }
 
     // Dynamic triangle size based on camera distance from cloud center
-    let cloud_center = compute_cloud_center(&groups);
+    let cloud_center = if count > 0 { sum / count as f32 } else { Vec3::ZERO };
     let tri_size = compute_tri_size(cam_pos, cloud_center);
+    state.resampled = Some((universe.particle_steps, cam_pos, cloud_center));
 
     // Update each cloud mesh
+    let empty = CloudSample::default();
     for (kind_id, (_entity, mesh_handle)) in &state.clouds {
+        if !filter.is_visible(*kind_id) {
+            continue;
+        }
         if let Some(mesh) = meshes.get_mut(mesh_handle) {
-            let positions = groups.remove(kind_id).unwrap_or_default();
-            rebuild_triangle_cloud(mesh, &positions, tri_size);
+            let sample = groups.get(kind_id).unwrap_or(&empty);
+            rebuild_triangle_cloud(mesh, &sample.positions, tri_size);
+            let colors = cloud_vertex_colors(*kind_id, &sample.temperatures);
+            match mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+                Some(VertexAttributeValues::Float32x4(buffer)) => {
+                    buffer.clear();
+                    buffer.extend(colors);
+                }
+                _ => mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors.collect::<Vec<_>>()),
+            }
+        }
+    }
+    timings.record_particle_clouds(start.elapsed());
+}
+
+/// Visibility of the cloud of `kind_id` under `filter`
+pub(crate) fn cloud_visibility(filter: &ParticleViewFilter, kind_id: u32) -> Visibility {
+    if filter.is_visible(kind_id) {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+/// Disk brightness multiplier for a black hole's accretion glow (mass recently captured,
+/// fading between captures)
+pub(crate) fn accretion_brightness(glow: f32) -> f32 {
+    1.0 + (glow / ACCRETION_GLOW_PER_MASS / 10.0).clamp(0.0, 8.0)
+}
+
+/// A black hole sphere with its transform and children (the accretion disk)
+type BlackHoleParts<'a> = (Entity, &'a BlackHoleVisual, &'a mut Transform, Option<&'a Children>);
+
+/// Black holes are drawn apart from the point clouds: a dark sphere per black hole
+/// particle with an accretion disk turned toward the camera, brighter after captures
+pub fn update_black_holes(
+    mut commands: Commands,
+    (universe, mut rendered_generation): (Res<UniverseState>, Local<Option<u32>>),
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    mut holes: Query<BlackHoleParts, Without<super::camera::FlyCamera>>,
+    disks: Query<&AccretionDisk>,
+    camera_query: Query<&Transform, With<super::camera::FlyCamera>>,
+) {
+    // Respawn when the particle set is replaced (region loads, cycles, saves)
+    if *rendered_generation != Some(universe.particles_generation) {
+        *rendered_generation = Some(universe.particles_generation);
+        for (entity, ..) in &holes {
+            commands.entity(entity).despawn_recursive();
+        }
+        let sphere = meshes.add(Sphere::new(1.0));
+        let disk = meshes.add(Annulus::new(1.3, ACCRETION_DISK_RADIUS));
+        let dark = materials.add(StandardMaterial {
+            base_color: Color::BLACK,
+            unlit: true,
+            ..default()
+        });
+        for (i, p) in universe.particles.iter().enumerate() {
+            if !p.is_alive() || p.kind != ParticleKind::BlackHole as u32 {
+                continue;
+            }
+            let [r, g, b] = ACCRETION_DISK_COLOR;
+            let material = materials.add(StandardMaterial {
+                base_color: Color::srgba(r, g, b, 0.8),
+                emissive: LinearRgba::rgb(r, g, b),
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                alpha_mode: AlphaMode::Add,
+                ..default()
+            });
+            commands
+                .spawn((
+                    Mesh3d(sphere.clone()),
+                    MeshMaterial3d(dark.clone()),
+                    Transform::from_translation(Vec3::from(p.pos())),
+                    BlackHoleVisual { particle: i },
+                ))
+                .with_child((
+                    Mesh3d(disk.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::IDENTITY,
+                    AccretionDisk { material },
+                ));
+        }
+        return;
+    }
+
+    let cam_pos = camera_query
+        .get_single()
+        .map(|t| t.translation)
+        .unwrap_or(Vec3::ZERO);
+    for (entity, visual, mut transform, children) in &mut holes {
+        let Some(p) = universe.particles.get(visual.particle).filter(|p| p.is_alive()) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        let pos = Vec3::from(p.pos());
+        // The disk lies in the sphere's XY plane: facing the camera makes it a billboard
+        *transform = Transform::from_translation(pos)
+            .looking_at(cam_pos, Vec3::Y)
+            .with_scale(Vec3::splat(compute_tri_size(cam_pos, pos) * BLACK_HOLE_SCALE));
+        let brightness = accretion_brightness(p.temperature);
+        let [r, g, b] = ACCRETION_DISK_COLOR;
+        for disk in children.into_iter().flatten().filter_map(|c| disks.get(*c).ok()) {
+            if let Some(material) = materials.get_mut(&disk.material) {
+                material.emissive = LinearRgba::rgb(r, g, b) * brightness;
+            }
         }
     }
 }
 
 /// Compute approximate center of all particle groups
-fn compute_cloud_center(groups: &HashMap<u32, Vec<[f32; 3]>>) -> Vec3 {
+fn compute_cloud_center(groups: &HashMap<u32, CloudSample>) -> Vec3 {
     let mut sum = Vec3::ZERO;
     let mut count = 0u32;
-    for positions in groups.values() {
-        for pos in positions {
+    for sample in groups.values() {
+        for pos in &sample.positions {
             sum += Vec3::new(pos[0], pos[1], pos[2]);
             count += 1;
         }
@@ -213,7 +411,7 @@ fn compute_cloud_center(groups: &HashMap<u32, Vec<[f32; 3]>>) -> Vec3 {
 
 /// Build a mesh where each particle = 1 small triangle (3 vertices)
 /// Total: N particles -> 3N vertices, N triangles, ONE draw call
-fn build_triangle_cloud(positions: &[[f32; 3]], tri_size: f32) -> Mesh {
+pub(crate) fn build_triangle_cloud(positions: &[[f32; 3]], tri_size: f32) -> Mesh {
     let vert_count = positions.len() * 3;
     let mut verts = Vec::with_capacity(vert_count);
     let mut normals = Vec::with_capacity(vert_count);
@@ -223,9 +421,7 @@ fn build_triangle_cloud(positions: &[[f32; 3]], tri_size: f32) -> Mesh {
         verts.push([pos[0] - s, pos[1] - s, pos[2]]);
         verts.push([pos[0] + s, pos[1] - s, pos[2]]);
         verts.push([pos[0], pos[1] + s, pos[2]]);
-        normals.push([0.0, 0.0, 1.0]);
-        normals.push([0.0, 0.0, 1.0]);
-        normals.push([0.0, 0.0, 1.0]);
+        normals.extend([CLOUD_NORMAL; 3]);
     }
 
     Mesh::new(
@@ -236,27 +432,48 @@ fn build_triangle_cloud(positions: &[[f32; 3]], tri_size: f32) -> Mesh {
     .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
 }
 
-/// Update an existing mesh's vertices in place (avoids reallocation)
+/// Update an existing mesh's vertices in place, reusing its buffers. The normals never
+/// change, so only the slots of a grown or shrunk cloud are touched.
 fn rebuild_triangle_cloud(mesh: &mut Mesh, positions: &[[f32; 3]], tri_size: f32) {
-    let vert_count = positions.len() * 3;
-    let mut verts = Vec::with_capacity(vert_count);
-    let mut normals = Vec::with_capacity(vert_count);
-
     let s = tri_size;
-    for pos in positions {
-        verts.push([pos[0] - s, pos[1] - s, pos[2]]);
-        verts.push([pos[0] + s, pos[1] - s, pos[2]]);
-        verts.push([pos[0], pos[1] + s, pos[2]]);
-        normals.push([0.0, 0.0, 1.0]);
-        normals.push([0.0, 0.0, 1.0]);
-        normals.push([0.0, 0.0, 1.0]);
+    let verts = positions.iter().flat_map(|pos| {
+        [
+            [pos[0] - s, pos[1] - s, pos[2]],
+            [pos[0] + s, pos[1] - s, pos[2]],
+            [pos[0], pos[1] + s, pos[2]],
+        ]
+    });
+    match mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(buffer)) => {
+            buffer.clear();
+            buffer.extend(verts);
+        }
+        _ => mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, verts.collect::<Vec<_>>()),
+    }
+    match mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => normals.resize(positions.len() * 3, CLOUD_NORMAL),
+        _ => mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![CLOUD_NORMAL; positions.len() * 3]),
     }
+}
 
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, verts);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+/// Vertex colors of a cloud, three per particle: the kind's color dimmed and reddened by
+/// each particle's temperature (`temperature_to_color`). Photons keep their full
+/// brightness and dark matter its faint glow.
+fn cloud_vertex_colors(kind_id: u32, temperatures: &[f32]) -> impl Iterator<Item = [f32; 4]> + '_ {
+    let kind = LinearRgba::from(kind_color(kind_id)).to_f32_array();
+    let fixed = kind_id == ParticleKind::Photon as u32 || kind_id == ParticleKind::DarkMatter as u32;
+    temperatures.iter().flat_map(move |&t| {
+        let glow = if fixed {
+            [1.0; 4]
+        } else {
+            let [r, g, b, a] = temperature_to_color(t);
+            LinearRgba::from(Color::srgba(r, g, b, a)).to_f32_array()
+        };
+        [std::array::from_fn(|k| kind[k] * glow[k]); 3]
+    })
 }
 
-fn kind_color(kind_id: u32) -> Color {
+pub(crate) fn kind_color(kind_id: u32) -> Color {
     let kind = match kind_id {
         0 => ParticleKind::UpQuark,
         1 => ParticleKind::DownQuark,
@@ -273,9 +490,44 @@ fn kind_color(kind_id: u32) -> Color {
         24 => ParticleKind::Oxygen,
         25 => ParticleKind::Iron,
         100 => ParticleKind::DarkMatter,
+        101 => ParticleKind::BlackHole,
         _ => ParticleKind::Hydrogen,
     };
 
     let c = kind.color();
     Color::srgba(c[0], c[1], c[2], c[3])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_colors_cool_with_the_particles() {
+        let hydrogen = ParticleKind::Hydrogen as u32;
+        let colors: Vec<_> = cloud_vertex_colors(hydrogen, &[1e10, 3000.0, 2.7]).collect();
+        assert_eq!(colors.len(), 9);
+        assert_eq!(colors[0], colors[2]);
+        let brightness = |c: [f32; 4]| c[0] + c[1] + c[2];
+        assert!(brightness(colors[0]) > brightness(colors[3]));
+        assert!(brightness(colors[3]) > brightness(colors[6]));
+        // Cold hydrogen is reddish
+        assert!(colors[6][0] > colors[6][2]);
+
+        // Photons and dark matter ignore the temperature
+        for kind in [ParticleKind::Photon, ParticleKind::DarkMatter] {
+            let colors: Vec<_> = cloud_vertex_colors(kind as u32, &[1e10, 2.7]).collect();
+            assert_eq!(colors[0], colors[3]);
+            assert_eq!(colors[0], LinearRgba::from(kind_color(kind as u32)).to_f32_array());
+        }
+    }
+
+    #[test]
+    fn test_accretion_disk_brightens_after_captures() {
+        assert_eq!(accretion_brightness(0.0), 1.0);
+        let fed = accretion_brightness(20.0 * ACCRETION_GLOW_PER_MASS);
+        assert!(fed > 2.0);
+        assert!(accretion_brightness(1e9) > fed);
+        assert_eq!(accretion_brightness(1e9), 9.0);
+    }
+}
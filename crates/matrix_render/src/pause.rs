@@ -0,0 +1,1102 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::state::AppState;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::Compression;
+
+use super::bestiary::BestiaryUi;
+use super::camera::{self, FlyCamera};
+use super::chunks::TerrainChunks;
+use super::dive::{AtmosphereDive, DiveVisual};
+use super::landing::{LandingTransition, LandingVisual};
+use super::cosmos::{
+    CosmosRenderState, GalaxyVisual, HabitableZoneVisual, OrbitOverlay, RegionBadge, RegionVisual,
+    StarLight, SupernovaShell,
+};
+use super::instances::CosmosInstances;
+use super::journal::JournalPanel;
+use super::locale::{self, Strings};
+use super::menu::spawn_text_button;
+use super::particles::{ParticleCloud, ParticleCloudState};
+use super::rewind::RewindBookmarks;
+use super::saving::{self, PendingSaves, SnapshotCapture};
+use super::settings::{self, Action, UserSettings, action_just_pressed};
+use super::small_bodies::{AsteroidBeltVisual, CometTail, CometVisual, SmallBodyState};
+use super::surface::{
+    DetailState, NearestCreatureInfo, PlanetSelection, SurfaceDay, SurfaceEcology, SurfaceEntities,
+    SurfaceState, SystemFocus, WeatherState,
+};
+use super::surface_map::SurfaceMap;
+use super::tech_visuals::{TechVisualState, TechVisuals};
+use super::hud_layout::{MAX_UI_SCALE, MIN_UI_SCALE};
+
+/// Autosaves go to this slot, overwritten each time
+const AUTOSAVE_SLOT: &str = "autosave";
+const MAX_SLOT_NAME: usize = 32;
+
+const PANEL_COLOR: Color = Color::srgba(0.0, 0.05, 0.02, 0.92);
+const TEXT_COLOR: Color = Color::srgba(0.0, 1.0, 0.4, 0.9);
+const BUTTON_COLOR: Color = Color::srgba(0.0, 0.4, 0.1, 0.9);
+const STEPPER_COLOR: Color = Color::srgba(0.1, 0.2, 0.5, 0.9);
+const BINDING_COLOR: Color = Color::srgba(0.05, 0.15, 0.3, 0.9);
+const REBINDING_COLOR: Color = Color::srgba(0.6, 0.45, 0.0, 0.9);
+const QUIT_COLOR: Color = Color::srgba(0.5, 0.1, 0.1, 0.9);
+
+/// Which page of the pause menu is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PausePage {
+    #[default]
+    Main,
+    Settings,
+}
+
+/// Save slot name being typed, the last save / error message and the open page
+#[derive(Resource, Default)]
+pub struct PauseMenu {
+    pub slot_name: String,
+    pub status: String,
+    pub page: PausePage,
+    /// Action waiting for a key press on the settings page
+    pub rebinding: Option<Action>,
+    /// Page currently spawned, rebuilt when `page` changes
+    shown: Option<PausePage>,
+}
+
+/// Editable rows of the settings page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingField {
+    Sensitivity,
+    InvertY,
+    FlySpeed,
+    AutoLevel,
+    WalkSpeed,
+    Fov,
+    SpeedEffects,
+    Minimap,
+    MinimapSize,
+    UiScale,
+    HudScale,
+    RenderBudget,
+    Autosave,
+    StoryMode,
+    StoryCamera,
+    StoryCooldown,
+    StickLook,
+    DeadZone,
+    InvertStickY,
+    MasterVolume,
+    AmbientVolume,
+    EffectsVolume,
+    Language,
+}
+
+impl SettingField {
+    const ALL: [SettingField; 23] = [
+        SettingField::Sensitivity,
+        SettingField::InvertY,
+        SettingField::FlySpeed,
+        SettingField::AutoLevel,
+        SettingField::WalkSpeed,
+        SettingField::Fov,
+        SettingField::SpeedEffects,
+        SettingField::Minimap,
+        SettingField::MinimapSize,
+        SettingField::UiScale,
+        SettingField::HudScale,
+        SettingField::RenderBudget,
+        SettingField::Autosave,
+        SettingField::StoryMode,
+        SettingField::StoryCamera,
+        SettingField::StoryCooldown,
+        SettingField::StickLook,
+        SettingField::DeadZone,
+        SettingField::InvertStickY,
+        SettingField::MasterVolume,
+        SettingField::AmbientVolume,
+        SettingField::EffectsVolume,
+        SettingField::Language,
+    ];
+
+    fn label(self, strings: &Strings) -> String {
+        let key = match self {
+            SettingField::Sensitivity => "option.mouse",
+            SettingField::InvertY => "option.invert_y",
+            SettingField::FlySpeed => "option.fly_speed",
+            SettingField::AutoLevel => "option.auto_level",
+            SettingField::WalkSpeed => "option.walk_speed",
+            SettingField::Fov => "option.fov",
+            SettingField::SpeedEffects => "option.speed_effects",
+            SettingField::Minimap => "option.minimap",
+            SettingField::MinimapSize => "option.minimap_size",
+            SettingField::UiScale => "option.ui_scale",
+            SettingField::HudScale => "option.hud_text",
+            SettingField::RenderBudget => "option.render_budget",
+            SettingField::Autosave => "option.autosave",
+            SettingField::StoryMode => "option.story_mode",
+            SettingField::StoryCamera => "option.story_camera",
+            SettingField::StoryCooldown => "option.story_cooldown",
+            SettingField::StickLook => "option.stick_look",
+            SettingField::DeadZone => "option.dead_zone",
+            SettingField::InvertStickY => "option.invert_stick_y",
+            SettingField::MasterVolume => "option.volume",
+            SettingField::AmbientVolume => "option.ambient",
+            SettingField::EffectsVolume => "option.effects",
+            SettingField::Language => "option.language",
+        };
+        strings.tr(key, &[])
+    }
+
+    fn value(self, settings: &UserSettings, strings: &Strings) -> String {
+        let percent = |fraction: f32| format!("{}%", strings.number(fraction as f64 * 100.0, 0));
+        match self {
+            SettingField::Sensitivity => strings.number(settings.mouse_sensitivity as f64, 4),
+            SettingField::InvertY => strings.on_off(settings.invert_y),
+            SettingField::FlySpeed => strings.number(settings.fly_speed as f64, 0),
+            SettingField::AutoLevel => strings.on_off(settings.auto_zoom_level),
+            SettingField::WalkSpeed => {
+                strings.tr("option.speed", &[("speed", &strings.number(settings.walk_speed as f64, 1))])
+            }
+            SettingField::Fov => format!("{}°", strings.number(settings.fov_degrees as f64, 0)),
+            SettingField::SpeedEffects => strings.on_off(settings.speed_effects),
+            SettingField::Minimap => strings.on_off(settings.minimap_enabled),
+            SettingField::MinimapSize => {
+                strings.tr("option.minimap_percent", &[("percent", &settings.minimap_percent)])
+            }
+            SettingField::UiScale => match settings.ui_scale {
+                Some(scale) => percent(scale),
+                None => strings.tr("option.ui_scale_auto", &[]),
+            },
+            SettingField::HudScale => percent(settings.hud_text_scale),
+            SettingField::RenderBudget => strings.tr(settings.render_budget.label_key(), &[]),
+            SettingField::Autosave if settings.autosave_minutes == 0 => strings.on_off(false),
+            SettingField::Autosave => {
+                strings.tr("option.autosave_every", &[("minutes", &settings.autosave_minutes)])
+            }
+            SettingField::StoryMode => strings.on_off(settings.story.enabled),
+            SettingField::StoryCamera => strings.on_off(settings.story.follow_camera),
+            SettingField::StoryCooldown => strings.tr(
+                "option.story_seconds",
+                &[("seconds", &strings.number(settings.story.cooldown_secs, 0))],
+            ),
+            SettingField::StickLook => strings.tr(
+                "option.turn_rate",
+                &[("speed", &strings.number(settings.gamepad_look_speed as f64, 1))],
+            ),
+            SettingField::DeadZone => percent(settings.stick_dead_zone),
+            SettingField::InvertStickY => strings.on_off(settings.stick_inversion.right_y),
+            SettingField::MasterVolume => percent(settings.master_volume),
+            SettingField::AmbientVolume => percent(settings.ambient_volume),
+            SettingField::EffectsVolume => percent(settings.effects_volume),
+            SettingField::Language => locale::language_name(&settings.language).to_string(),
+        }
+    }
+
+    /// Apply one stepper click (either direction flips a toggle)
+    fn step(self, settings: &mut UserSettings, dir: i32) {
+        let up = dir > 0;
+        match self {
+            SettingField::Sensitivity => {
+                let factor = if up { 1.25 } else { 0.8 };
+                settings.mouse_sensitivity =
+                    (settings.mouse_sensitivity * factor).clamp(0.0005, 0.02)
+            }
+            SettingField::InvertY => settings.invert_y = !settings.invert_y,
+            // Same range as scrolling in flight
+            SettingField::FlySpeed => {
+                let factor = if up { 2.0 } else { 0.5 };
+                settings.fly_speed = (settings.fly_speed * factor).clamp(1.0, 10_000.0)
+            }
+            SettingField::AutoLevel => settings.auto_zoom_level = !settings.auto_zoom_level,
+            SettingField::WalkSpeed => {
+                settings.walk_speed = (settings.walk_speed + dir as f32).clamp(2.0, 30.0)
+            }
+            SettingField::Fov => {
+                settings.fov_degrees = (settings.fov_degrees + dir as f32 * 5.0).clamp(30.0, 110.0)
+            }
+            SettingField::SpeedEffects => settings.speed_effects = !settings.speed_effects,
+            SettingField::Minimap => settings.minimap_enabled = !settings.minimap_enabled,
+            SettingField::MinimapSize => {
+                settings.minimap_percent = settings
+                    .minimap_percent
+                    .saturating_add_signed(dir * 4)
+                    .clamp(10, 40)
+            }
+            // Auto sits below the smallest fixed scale
+            SettingField::UiScale => {
+                settings.ui_scale = match settings.ui_scale {
+                    None if up => Some(MIN_UI_SCALE),
+                    None => None,
+                    Some(scale) if !up && scale <= MIN_UI_SCALE + 0.01 => None,
+                    Some(scale) => Some((scale + dir as f32 * 0.1).clamp(MIN_UI_SCALE, MAX_UI_SCALE)),
+                }
+            }
+            SettingField::HudScale => {
+                settings.hud_text_scale =
+                    (settings.hud_text_scale + dir as f32 * 0.1).clamp(0.5, 2.0)
+            }
+            SettingField::RenderBudget => {
+                settings.render_budget = settings.render_budget.step(dir)
+            }
+            SettingField::Autosave => {
+                settings.autosave_minutes = settings
+                    .autosave_minutes
+                    .saturating_add_signed(dir * 5)
+                    .min(60)
+            }
+            SettingField::StoryMode => settings.story.enabled = !settings.story.enabled,
+            SettingField::StoryCamera => {
+                settings.story.follow_camera = !settings.story.follow_camera
+            }
+            SettingField::StoryCooldown => {
+                settings.story.cooldown_secs =
+                    (settings.story.cooldown_secs + dir as f64 * 10.0).clamp(0.0, 120.0)
+            }
+            SettingField::StickLook => {
+                settings.gamepad_look_speed =
+                    (settings.gamepad_look_speed + dir as f32 * 0.5).clamp(0.5, 8.0)
+            }
+            SettingField::DeadZone => {
+                settings.stick_dead_zone =
+                    (settings.stick_dead_zone + dir as f32 * 0.05).clamp(0.0, 0.5)
+            }
+            SettingField::InvertStickY => {
+                let inversion = &mut settings.stick_inversion;
+                inversion.right_y = !inversion.right_y
+            }
+            SettingField::MasterVolume => step_volume(&mut settings.master_volume, dir),
+            SettingField::AmbientVolume => step_volume(&mut settings.ambient_volume, dir),
+            SettingField::EffectsVolume => step_volume(&mut settings.effects_volume, dir),
+            SettingField::Language => {
+                settings.language = locale::next_language(&settings.language, dir).to_string()
+            }
+        }
+    }
+}
+
+/// Volume stepper: 10% a click
+fn step_volume(volume: &mut f32, dir: i32) {
+    *volume = ((*volume * 10.0).round() + dir as f32).clamp(0.0, 10.0) / 10.0;
+}
+
+// --- Markers ---
+
+/// Marker for the pause menu root node
+#[derive(Component)]
+pub struct PauseRoot;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct SaveGameButton;
+
+/// Resumes with the life journal open
+#[derive(Component)]
+pub struct JournalButton;
+
+/// Switches the pause menu to another page
+#[derive(Component)]
+pub struct PageButton(PausePage);
+
+/// Marker for the save slot name field text
+#[derive(Component)]
+pub struct SlotNameText;
+
+/// Marker for the status line (saves, settings file)
+#[derive(Component)]
+pub struct StatusText;
+
+/// A +/- stepper for one setting (`dir` is -1 or +1)
+#[derive(Component)]
+pub struct StepButton {
+    field: SettingField,
+    dir: i32,
+}
+
+/// Text showing the current value of one setting
+#[derive(Component)]
+pub struct SettingValue(SettingField);
+
+/// Click to rebind an action, then press the new key
+#[derive(Component)]
+pub struct BindButton(Action);
+
+/// Text showing the key bound to an action
+#[derive(Component)]
+pub struct BindingText(Action);
+
+/// Marker for the scrolling key binding list
+#[derive(Component)]
+pub struct BindingList;
+
+#[derive(Component)]
+pub struct SaveSettingsButton;
+
+#[derive(Component)]
+pub struct DefaultSettingsButton;
+
+#[derive(Component)]
+pub struct QuitToMenuButton;
+
+#[derive(Component)]
+pub struct QuitToDesktopButton;
+
+/// Everything the running universe spawned outside the HUD, despawned on quitting to the menu
+type WorldEntities = Or<(
+    With<StarLight>,
+    With<GalaxyVisual>,
+    With<SupernovaShell>,
+    With<HabitableZoneVisual>,
+    With<RegionVisual>,
+    With<RegionBadge>,
+    With<ParticleCloud>,
+    With<AsteroidBeltVisual>,
+    With<CometVisual>,
+    With<CometTail>,
+    With<DiveVisual>,
+    With<LandingVisual>,
+    TechVisuals,
+    SurfaceEntities,
+)>;
+
+/// Open the pause menu on its main page
+pub fn spawn_pause_menu(
+    mut commands: Commands,
+    settings: Res<UserSettings>,
+    strings: Res<Strings>,
+    mut menu: ResMut<PauseMenu>,
+) {
+    menu.status.clear();
+    menu.page = PausePage::Main;
+    menu.rebinding = None;
+    menu.shown = Some(PausePage::Main);
+    spawn_page(&mut commands, &settings, &strings, &menu);
+}
+
+/// Rebuild the menu when a page button switched pages or the language changed
+pub fn pause_page_system(
+    mut commands: Commands,
+    settings: Res<UserSettings>,
+    strings: Res<Strings>,
+    mut menu: ResMut<PauseMenu>,
+    root_q: Query<Entity, With<PauseRoot>>,
+) {
+    if menu.shown == Some(menu.page) && !strings.is_changed() {
+        return;
+    }
+    for entity in &root_q {
+        commands.entity(entity).despawn_recursive();
+    }
+    menu.status.clear();
+    menu.rebinding = None;
+    menu.shown = Some(menu.page);
+    spawn_page(&mut commands, &settings, &strings, &menu);
+}
+
+/// Dimmed full-screen root with the current page's panel in the middle
+fn spawn_page(commands: &mut Commands, settings: &UserSettings, strings: &Strings, menu: &PauseMenu) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            PauseRoot,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(24.0)),
+                    ..default()
+                },
+                BackgroundColor(PANEL_COLOR),
+            ))
+            .with_children(|panel| match menu.page {
+                PausePage::Main => build_main_page(panel, strings),
+                PausePage::Settings => build_settings_page(panel, settings, strings),
+            });
+        });
+}
+
+/// Resume, Save Game (named slot), Life Journal, Settings, Quit to Main Menu, Quit to Desktop
+fn build_main_page(panel: &mut ChildBuilder, strings: &Strings) {
+    let tr = |key: &str| strings.tr(key, &[]);
+    panel.spawn((label(&tr("pause.title"), 40.0), TextColor(TEXT_COLOR)));
+    spawn_text_button(panel, &tr("pause.resume"), 300.0, BUTTON_COLOR, ResumeButton);
+
+    // Save slot: type a name, then [Save] or Enter
+    panel
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(8.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Node {
+                    width: Val::Px(220.0),
+                    height: Val::Px(40.0),
+                    padding: UiRect::horizontal(Val::Px(8.0)),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            ))
+            .with_children(|field| {
+                field.spawn((label("", 20.0), TextColor(Color::WHITE), SlotNameText));
+            });
+            spawn_text_button(row, &tr("pause.save_game"), 140.0, BUTTON_COLOR, SaveGameButton);
+        });
+    panel.spawn((label("", 14.0), TextColor(TEXT_COLOR), StatusText));
+
+    spawn_text_button(panel, &tr("pause.journal"), 300.0, STEPPER_COLOR, JournalButton);
+    spawn_text_button(
+        panel,
+        &tr("pause.settings"),
+        300.0,
+        STEPPER_COLOR,
+        PageButton(PausePage::Settings),
+    );
+    spawn_text_button(
+        panel,
+        &tr("pause.quit_to_menu"),
+        300.0,
+        QUIT_COLOR,
+        QuitToMenuButton,
+    );
+    spawn_text_button(
+        panel,
+        &tr("pause.quit_to_desktop"),
+        300.0,
+        QUIT_COLOR,
+        QuitToDesktopButton,
+    );
+}
+
+/// Setting steppers on the left, key bindings on the right, Save / Defaults / Back below
+fn build_settings_page(panel: &mut ChildBuilder, settings: &UserSettings, strings: &Strings) {
+    let tr = |key: &str| strings.tr(key, &[]);
+    panel.spawn((label(&tr("pause.settings_title"), 32.0), TextColor(TEXT_COLOR)));
+    panel
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(24.0),
+            ..default()
+        })
+        .with_children(|columns| {
+            columns
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|column| {
+                    for field in SettingField::ALL {
+                        spawn_setting_row(column, field, settings, strings);
+                    }
+                });
+            columns
+                .spawn((
+                    Node {
+                        width: Val::Px(330.0),
+                        max_height: Val::Px(520.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(3.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    BindingList,
+                ))
+                .with_children(|list| {
+                    for action in Action::ALL {
+                        spawn_binding_row(list, action, settings, strings);
+                    }
+                });
+        });
+    panel.spawn((
+        label(&tr("pause.rebind_hint"), 14.0),
+        TextColor(TEXT_COLOR),
+        StatusText,
+    ));
+    panel
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(8.0),
+            ..default()
+        })
+        .with_children(|row| {
+            spawn_text_button(row, &tr("pause.save"), 140.0, BUTTON_COLOR, SaveSettingsButton);
+            spawn_text_button(row, &tr("pause.defaults"), 140.0, STEPPER_COLOR, DefaultSettingsButton);
+            spawn_text_button(row, &tr("pause.back"), 140.0, STEPPER_COLOR, PageButton(PausePage::Main));
+        });
+}
+
+fn label(text: &str, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+    )
+}
+
+/// One settings row: [label] [-] [value] [+]
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    field: SettingField,
+    settings: &UserSettings,
+    strings: &Strings,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(8.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                label(&field.label(strings), 18.0),
+                TextColor(TEXT_COLOR),
+                Node {
+                    width: Val::Px(130.0),
+                    ..default()
+                },
+            ));
+            spawn_text_button(row, "-", 40.0, STEPPER_COLOR, StepButton { field, dir: -1 });
+            row.spawn((
+                label(&field.value(settings, strings), 18.0),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    width: Val::Px(120.0),
+                    ..default()
+                },
+                SettingValue(field),
+            ));
+            spawn_text_button(row, "+", 40.0, STEPPER_COLOR, StepButton { field, dir: 1 });
+        });
+}
+
+/// One key binding row: [action] [key button]
+fn spawn_binding_row(list: &mut ChildBuilder, action: Action, settings: &UserSettings, strings: &Strings) {
+    list.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(8.0),
+        flex_shrink: 0.0,
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            label(&strings.tr(action.label_key(), &[]), 15.0),
+            TextColor(TEXT_COLOR),
+            Node {
+                width: Val::Px(180.0),
+                ..default()
+            },
+        ));
+        row.spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(26.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BINDING_COLOR),
+            BindButton(action),
+        ))
+        .with_children(|button| {
+            button.spawn((
+                label(&settings::key_name(settings.key(action)), 15.0),
+                TextColor(Color::WHITE),
+                BindingText(action),
+            ));
+        });
+    });
+}
+
+pub fn despawn_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseRoot>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn save_to_slot(
+    strings: &Strings,
+    name: &str,
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    feed: &EventFeed,
+    history: &UniverseHistory,
+    saves: &mut PendingSaves,
+) -> String {
+    if !saving::valid_slot_name(name) {
+        return strings.tr("pause.bad_slot_name", &[]);
+    }
+    let path = saving::saves_dir().join(format!("{name}.bin"));
+    saves.spawn(
+        SnapshotCapture::capture(universe, lazy, feed, history),
+        path.clone(),
+        Compression::Lz4,
+    );
+    strings.tr("pause.saving", &[("path", &path.display())])
+}
+
+/// Main page: typing edits the slot name, Enter saves to it, Esc resumes.
+/// Settings page: the next key press rebinds the picked action, Esc goes back.
+pub fn pause_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut key_events: EventReader<KeyboardInput>,
+    (mut menu, mut settings, strings): (ResMut<PauseMenu>, ResMut<UserSettings>, Res<Strings>),
+    mut next_state: ResMut<NextState<AppState>>,
+    (universe, lazy, feed, history): (Res<UniverseState>, Res<LazyUniverse>, Res<EventFeed>, Res<UniverseHistory>),
+    mut saves: ResMut<PendingSaves>,
+) {
+    if menu.page == PausePage::Settings {
+        key_events.clear();
+        if let Some(action) = menu.rebinding {
+            if keyboard.just_pressed(KeyCode::Escape) {
+                menu.rebinding = None;
+            } else if let Some(key) = keyboard
+                .get_just_pressed()
+                .copied()
+                .find(|key| settings::is_bindable(*key))
+            {
+                settings.bind(action, key);
+                menu.rebinding = None;
+                menu.status = strings.tr(
+                    "pause.rebound",
+                    &[("action", &strings.tr(action.label_key(), &[])), ("key", &settings::key_name(key))],
+                );
+            }
+        } else if action_just_pressed(&settings, &keyboard, Action::BackLevel) {
+            menu.page = PausePage::Main;
+        }
+        return;
+    }
+
+    if action_just_pressed(&settings, &keyboard, Action::BackLevel) {
+        next_state.set(AppState::Running);
+        return;
+    }
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let name = menu.slot_name.clone();
+                menu.status = save_to_slot(&strings, &name, &universe, &lazy, &feed, &history, &mut saves);
+            }
+            Key::Backspace => {
+                menu.slot_name.pop();
+            }
+            Key::Character(chars) => {
+                for c in chars.chars() {
+                    if menu.slot_name.len() < MAX_SLOT_NAME
+                        && saving::valid_slot_name(&c.to_string())
+                    {
+                        menu.slot_name.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Interaction of a `B` button that changed this frame
+type Clicked<B> = (Changed<Interaction>, With<B>);
+
+/// The pause menu's one-shot buttons
+#[derive(SystemParam)]
+pub struct PauseButtons<'w, 's> {
+    resume: Query<'w, 's, &'static Interaction, Clicked<ResumeButton>>,
+    save: Query<'w, 's, &'static Interaction, Clicked<SaveGameButton>>,
+    journal: Query<'w, 's, &'static Interaction, Clicked<JournalButton>>,
+    quit_to_menu: Query<'w, 's, &'static Interaction, Clicked<QuitToMenuButton>>,
+    quit_to_desktop: Query<'w, 's, &'static Interaction, Clicked<QuitToDesktopButton>>,
+}
+
+/// Resume, Save Game, Life Journal, page switches and the two quit buttons
+pub fn pause_button_system(
+    (mut next_state, mut exit): (ResMut<NextState<AppState>>, EventWriter<AppExit>),
+    (mut menu, mut journal, strings): (ResMut<PauseMenu>, ResMut<JournalPanel>, Res<Strings>),
+    (universe, lazy, feed, history): (Res<UniverseState>, Res<LazyUniverse>, Res<EventFeed>, Res<UniverseHistory>),
+    mut saves: ResMut<PendingSaves>,
+    buttons: PauseButtons,
+    page_q: Query<(&Interaction, &PageButton), Changed<Interaction>>,
+) {
+    let pressed = |i: &Interaction| *i == Interaction::Pressed;
+
+    if buttons.resume.iter().any(pressed) {
+        next_state.set(AppState::Running);
+    }
+    if buttons.save.iter().any(pressed) {
+        let name = menu.slot_name.clone();
+        menu.status = save_to_slot(&strings, &name, &universe, &lazy, &feed, &history, &mut saves);
+    }
+    if buttons.journal.iter().any(pressed) {
+        journal.open = true;
+        next_state.set(AppState::Running);
+    }
+    for (interaction, page) in &page_q {
+        if pressed(interaction) {
+            menu.page = page.0;
+        }
+    }
+    if buttons.quit_to_menu.iter().any(pressed) {
+        next_state.set(AppState::Menu);
+    }
+    if buttons.quit_to_desktop.iter().any(pressed) {
+        exit.send(AppExit::Success);
+    }
+}
+
+/// Settings page: steppers, picking an action to rebind, Save (writes `settings.ron`)
+/// and Defaults
+pub fn settings_page_system(
+    mut settings: ResMut<UserSettings>,
+    strings: Res<Strings>,
+    mut menu: ResMut<PauseMenu>,
+    step_q: Query<(&Interaction, &StepButton), Changed<Interaction>>,
+    bind_q: Query<(&Interaction, &BindButton), Changed<Interaction>>,
+    save_q: Query<&Interaction, Clicked<SaveSettingsButton>>,
+    defaults_q: Query<&Interaction, Clicked<DefaultSettingsButton>>,
+) {
+    let pressed = |i: &Interaction| *i == Interaction::Pressed;
+
+    for (interaction, step) in &step_q {
+        if pressed(interaction) {
+            step.field.step(&mut settings, step.dir);
+        }
+    }
+    for (interaction, bind) in &bind_q {
+        if pressed(interaction) {
+            menu.rebinding = Some(bind.0);
+            let action = strings.tr(bind.0.label_key(), &[]);
+            menu.status = strings.tr("pause.press_key", &[("action", &action)]);
+        }
+    }
+    if defaults_q.iter().any(pressed) {
+        *settings = UserSettings::default();
+        menu.rebinding = None;
+        menu.status = strings.tr("pause.defaults_restored", &[]);
+    }
+    if save_q.iter().any(pressed) {
+        let path = settings::settings_path();
+        menu.status = match settings.save(&path) {
+            Ok(()) => strings.tr("pause.settings_saved", &[("path", &path.display())]),
+            Err(e) => strings.tr("pause.settings_failed", &[("path", &path.display()), ("error", &e)]),
+        };
+    }
+}
+
+/// Mouse wheel scrolls the key binding list
+pub fn binding_scroll_system(
+    mut wheel: EventReader<MouseWheel>,
+    mut list_q: Query<&mut ScrollPosition, With<BindingList>>,
+) {
+    for event in wheel.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in &mut list_q {
+            scroll.offset_y = (scroll.offset_y - dy).max(0.0);
+        }
+    }
+}
+
+/// Setting value labels, kept apart from the slot name and status texts
+type SettingValueTexts = (Without<SlotNameText>, Without<StatusText>);
+/// Key binding labels, kept apart from every other pause menu text
+type BindingTexts = (Without<SlotNameText>, Without<StatusText>, Without<SettingValue>);
+
+/// Refresh the slot name, status line, setting values and key bindings
+pub fn pause_text_system(
+    (menu, settings, strings): (Res<PauseMenu>, Res<UserSettings>, Res<Strings>),
+    mut name_q: Query<&mut Text, (With<SlotNameText>, Without<StatusText>)>,
+    mut status_q: Query<&mut Text, (With<StatusText>, Without<SlotNameText>)>,
+    mut value_q: Query<(&mut Text, &SettingValue), SettingValueTexts>,
+    mut binding_q: Query<(&mut Text, &BindingText), BindingTexts>,
+    mut bind_button_q: Query<(&mut BackgroundColor, &BindButton)>,
+) {
+    if menu.is_changed() {
+        for mut text in &mut name_q {
+            **text = format!("{}_", menu.slot_name);
+        }
+        if !menu.status.is_empty() {
+            for mut text in &mut status_q {
+                **text = menu.status.clone();
+            }
+        }
+        for (mut color, button) in &mut bind_button_q {
+            let waiting = menu.rebinding == Some(button.0);
+            color.0 = if waiting { REBINDING_COLOR } else { BINDING_COLOR };
+        }
+    }
+    if settings.is_changed() || menu.is_changed() {
+        for (mut text, value) in &mut value_q {
+            **text = value.0.value(&settings, &strings);
+        }
+        for (mut text, binding) in &mut binding_q {
+            **text = if menu.rebinding == Some(binding.0) {
+                "…".to_string()
+            } else {
+                settings::key_name(settings.key(binding.0))
+            };
+        }
+    }
+}
+
+/// Settings take effect as soon as they change: fly speed and field of view (HUD sizes in
+/// `hud_layout::hud_layout_system`)
+pub fn apply_settings_system(
+    settings: Res<UserSettings>,
+    mut applied_fly_speed: Local<Option<f32>>,
+    mut applied_auto_level: Local<Option<bool>>,
+    mut cam_q: Query<(&mut FlyCamera, &mut Projection)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    // Only a changed fly speed resets the one scrolled to in flight
+    let reset_speed = *applied_fly_speed != Some(settings.fly_speed);
+    *applied_fly_speed = Some(settings.fly_speed);
+    // Likewise the auto level toggled in flight
+    let reset_auto_level = *applied_auto_level != Some(settings.auto_zoom_level);
+    *applied_auto_level = Some(settings.auto_zoom_level);
+    for (mut cam, mut projection) in &mut cam_q {
+        if reset_speed {
+            cam.speed = settings.fly_speed;
+        }
+        if reset_auto_level {
+            cam.auto_level = settings.auto_zoom_level;
+        }
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = settings.fov_degrees.to_radians();
+        }
+    }
+}
+
+/// Save to the autosave slot every `autosave_minutes` of (unpaused) play
+pub fn autosave_system(
+    time: Res<Time<Real>>,
+    settings: Res<UserSettings>,
+    mut elapsed: Local<f32>,
+    (universe, lazy, feed, history): (Res<UniverseState>, Res<LazyUniverse>, Res<EventFeed>, Res<UniverseHistory>),
+    mut saves: ResMut<PendingSaves>,
+) {
+    if settings.autosave_minutes == 0 {
+        *elapsed = 0.0;
+        return;
+    }
+    *elapsed += time.delta_secs();
+    if *elapsed < settings.autosave_minutes as f32 * 60.0 {
+        return;
+    }
+    *elapsed = 0.0;
+    let path = saving::saves_dir().join(format!("{AUTOSAVE_SLOT}.bin"));
+    info!("Autosaving: {}", path.display());
+    saves.spawn(
+        SnapshotCapture::capture(&universe, &lazy, &feed, &history),
+        path,
+        Compression::Lz4,
+    );
+}
+
+/// Quit to Main Menu: despawn the universe's entities and reset the universe and
+/// every view resource, so the menu can start or load a fresh one
+pub fn teardown_world(
+    mut commands: Commands,
+    (world_q, fog_q): (Query<Entity, WorldEntities>, Query<Entity, With<DistanceFog>>),
+    mut cam_q: Query<(&mut Transform, &mut FlyCamera)>,
+    (mut universe, mut lazy, mut feed): (ResMut<UniverseState>, ResMut<LazyUniverse>, ResMut<EventFeed>),
+    (mut instances, mut selection, mut clear_color): (ResMut<CosmosInstances>, ResMut<PlanetSelection>, ResMut<ClearColor>),
+    (mut dive, mut landing): (ResMut<AtmosphereDive>, ResMut<LandingTransition>),
+    settings: Res<UserSettings>,
+) {
+    for entity in &world_q {
+        commands.entity(entity).despawn();
+    }
+    dive.cancel();
+    landing.cancel();
+    for entity in &fog_q {
+        commands.entity(entity).remove::<DistanceFog>();
+    }
+    for (mut transform, mut cam) in &mut cam_q {
+        *transform =
+            Transform::from_translation(camera::START_POSITION).looking_at(Vec3::ZERO, Vec3::Y);
+        *cam = FlyCamera {
+            speed: settings.fly_speed,
+            auto_level: settings.auto_zoom_level,
+            ..default()
+        };
+    }
+
+    let config = universe.config.clone();
+    *universe = UniverseState::empty(config.clone());
+    *lazy = LazyUniverse::empty(config);
+    feed.restore(Vec::new());
+
+    instances.clear();
+    selection.hovered = None;
+    selection.selected_planet = None;
+    selection.original_materials.clear();
+    selection.hovered_region = None;
+    selection.selected_region = None;
+    selection.hovered_star = None;
+    selection.selected_star = None;
+    clear_color.0 = Color::srgb(0.0, 0.0, 0.02);
+
+    commands.insert_resource(camera::space_ambient());
+    commands.insert_resource(SurfaceState::default());
+    commands.insert_resource(SystemFocus::default());
+    commands.insert_resource(DetailState::default());
+    commands.insert_resource(NearestCreatureInfo::default());
+    commands.insert_resource(SurfaceEcology::default());
+    commands.insert_resource(SurfaceDay::default());
+    commands.insert_resource(WeatherState::default());
+    commands.insert_resource(TerrainChunks::default());
+    commands.insert_resource(SurfaceMap::default());
+    commands.insert_resource(CosmosRenderState::default());
+    commands.insert_resource(OrbitOverlay::default());
+    commands.insert_resource(SmallBodyState::default());
+    commands.insert_resource(TechVisualState::default());
+    commands.insert_resource(ParticleCloudState::default());
+    commands.insert_resource(BestiaryUi::default());
+    commands.insert_resource(RewindBookmarks::default());
+    commands.insert_resource(UniverseHistory::default());
+    info!("Returned to the main menu");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::ZoomLevel;
+    use crate::budget::RenderBudget;
+    use crate::{cosmos, instances, legend, particles, surface};
+    use bevy::state::app::StatesPlugin;
+    use matrix_core::{GpuParticle, ParticleKind, SimConfig};
+    use matrix_sim::lazy_universe::generate_region_detail;
+
+    fn start_universe(app: &mut App) {
+        let config = SimConfig::default();
+        let particle =
+            GpuParticle::new([1.0, 0.0, 0.0], [0.0; 3], 1.0, 0.0, ParticleKind::Hydrogen);
+        let mut lazy = LazyUniverse::new(config.clone(), 13.0);
+        let detail = generate_region_detail(&lazy.regions[0], 13.0);
+        lazy.loaded_stars = detail.stars.into();
+        lazy.loaded_galaxies = detail.galaxies;
+        lazy.current_region_id = Some(detail.region_id);
+        lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
+        let world = app.world_mut();
+        world.insert_resource(UniverseState::new(config, vec![particle; 1_000]));
+        world.insert_resource(lazy);
+        let mut cam_q = world.query::<(&mut Transform, &mut FlyCamera)>();
+        let (mut transform, mut cam) = cam_q.single_mut(world);
+        *transform = Transform::default();
+        cam.zoom_level = ZoomLevel::Stellar;
+        world.spawn((Transform::default(), surface::TerrainMesh));
+        world
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Running);
+        app.update();
+        app.update();
+    }
+
+    fn go_to(app: &mut App, state: AppState) {
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(state);
+        app.update();
+    }
+
+    #[test]
+    fn test_quit_to_menu_leaves_no_entities_behind() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_state::<AppState>()
+            .init_resource::<UserSettings>()
+            .init_resource::<EventFeed>()
+            .init_resource::<ClearColor>()
+            .init_resource::<SystemFocus>()
+            .init_resource::<legend::ParticleViewFilter>()
+            .init_resource::<AtmosphereDive>()
+            .init_resource::<RenderBudget>()
+            .init_resource::<LandingTransition>()
+            .insert_resource(UniverseState::empty(SimConfig::default()))
+            .insert_resource(LazyUniverse::empty(SimConfig::default()))
+            .add_systems(
+                Startup,
+                (
+                    cosmos::init_cosmos_state,
+                    instances::init_instances,
+                    particles::init_particle_cloud,
+                    surface::init_planet_selection,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    cosmos::update_cosmos_visuals,
+                    particles::sync_particle_clouds,
+                )
+                    .run_if(in_state(AppState::Running)),
+            )
+            .add_systems(
+                OnTransition {
+                    exited: AppState::Paused,
+                    entered: AppState::Menu,
+                },
+                teardown_world,
+            );
+        app.world_mut()
+            .spawn((Transform::default(), FlyCamera::default()));
+        app.update();
+        let in_menu = app.world().entities().len();
+
+        start_universe(&mut app);
+        let running = app.world().entities().len();
+        assert!(
+            running > in_menu + 2,
+            "{running} entities running, {in_menu} in the menu"
+        );
+
+        go_to(&mut app, AppState::Paused);
+        go_to(&mut app, AppState::Menu);
+        assert_eq!(app.world().entities().len(), in_menu);
+        assert!(app.world().resource::<UniverseState>().particles.is_empty());
+        assert!(
+            app.world()
+                .resource::<LazyUniverse>()
+                .loaded_stars
+                .is_empty()
+        );
+
+        // A second universe builds the same entities as the first, nothing left over
+        start_universe(&mut app);
+        assert_eq!(app.world().entities().len(), running);
+    }
+}
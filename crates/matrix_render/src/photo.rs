@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::PhotoMetadata;
+use std::path::PathBuf;
+
+use super::camera::FlyCamera;
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::PlanetSelection;
+
+/// Roll speed in photo mode (radians per second)
+pub const PHOTO_ROLL_SPEED: f32 = 1.0;
+
+/// F12 photo mode: UI hidden, time optionally frozen, camera roll enabled
+#[derive(Resource)]
+pub struct PhotoMode {
+    pub active: bool,
+    /// Pause the simulation while composing a shot
+    pub freeze_time: bool,
+    saved_time_scale: f64,
+    saved_paused: bool,
+    /// UI roots hidden on entry, with the visibility they had
+    hidden_ui: Vec<(Entity, Visibility)>,
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            freeze_time: true,
+            saved_time_scale: 1.0,
+            saved_paused: false,
+            hidden_ui: Vec::new(),
+        }
+    }
+}
+
+/// Top-level UI nodes (the ones photo mode hides)
+type UiRoots = (With<Node>, Without<Parent>);
+
+/// F12 enters / leaves photo mode. Entering hides every UI root (the minimap hides
+/// itself) and freezes time; leaving restores the UI, time scale and pause state and levels the roll.
+pub fn photo_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut photo: ResMut<PhotoMode>,
+    mut universe: ResMut<UniverseState>,
+    mut ui_q: Query<(Entity, &mut Visibility), UiRoots>,
+    mut cam_q: Query<&mut FlyCamera>,
+) {
+    if !action_just_pressed(&settings, &keyboard, Action::PhotoMode) {
+        return;
+    }
+
+    if !photo.active {
+        photo.active = true;
+        photo.saved_time_scale = universe.time_scale;
+        photo.saved_paused = universe.paused;
+        if photo.freeze_time {
+            universe.paused = true;
+        }
+        photo.hidden_ui = ui_q
+            .iter_mut()
+            .map(|(entity, mut visibility)| {
+                let previous = *visibility;
+                *visibility = Visibility::Hidden;
+                (entity, previous)
+            })
+            .collect();
+        info!("Photo mode: [Enter] screenshot, [Z]/[C] roll, [F12] exit");
+        return;
+    }
+
+    photo.active = false;
+    universe.time_scale = photo.saved_time_scale;
+    universe.paused = photo.saved_paused;
+    for (entity, previous) in std::mem::take(&mut photo.hidden_ui) {
+        if let Ok((_, mut visibility)) = ui_q.get_mut(entity) {
+            *visibility = previous;
+        }
+    }
+    for mut cam in &mut cam_q {
+        cam.roll = 0.0;
+    }
+}
+
+/// Enter in photo mode: save a screenshot to `screenshots/` with a JSON sidecar
+/// (seed, age, camera, region and the selected planet)
+pub fn photo_capture_system(
+    mut commands: Commands,
+    (keyboard, settings): (Res<ButtonInput<KeyCode>>, Res<UserSettings>),
+    photo: Res<PhotoMode>,
+    (universe, lazy, selection): (Res<UniverseState>, Res<LazyUniverse>, Res<PlanetSelection>),
+    cam_q: Query<(&Transform, &FlyCamera)>,
+) {
+    if !photo.active || !action_just_pressed(&settings, &keyboard, Action::Screenshot) {
+        return;
+    }
+    let Ok((transform, cam)) = cam_q.get_single() else {
+        return;
+    };
+
+    let stem = photo_stem();
+    let image_path = PathBuf::from("screenshots").join(format!("{stem}.png"));
+    let meta_path = image_path.with_extension("json");
+    if let Err(e) = std::fs::create_dir_all("screenshots") {
+        error!("Failed to create screenshots/: {e}");
+        return;
+    }
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(image_path.clone()));
+
+    let meta = PhotoMetadata {
+        seed: universe.config.seed,
+        age: universe.age,
+        cycle: universe.cycle,
+        camera_position: transform.translation.to_array(),
+        camera_rotation: [cam.yaw, cam.pitch, cam.roll],
+        region_id: lazy.current_region_id,
+        planet: selection.selected_planet.as_ref().map(|(planet, _)| planet.clone()),
+        star_class: selection.selected_planet.as_ref().map(|(_, class)| *class),
+    };
+    match matrix_storage::export_photo_metadata(&meta, &meta_path) {
+        Ok(()) => info!("Photo saved: {}", image_path.display()),
+        Err(e) => error!("Failed to write photo metadata {}: {e}", meta_path.display()),
+    }
+}
+
+/// File name for a new photo, unique per millisecond
+fn photo_stem() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("photo_{millis}")
+}
@@ -1,12 +1,45 @@
 use bevy::prelude::*;
 use matrix_sim::lazy_universe::LazyUniverse;
 use matrix_sim::state::AppState;
+use matrix_sim::story::StoryDirector;
 use matrix_sim::universe::UniverseState;
 
+use super::bestiary;
+use super::bookmarks;
+use super::budget;
 use super::camera::{self, FlyCamera};
+use super::census;
+use super::chunks;
+use super::console;
 use super::cosmos;
+use super::debug_overlay;
+use super::dive;
+use super::feed;
+use super::gamepad;
+use super::history_graphs;
+use super::hud_layout;
+use super::input_capture;
+use super::instances;
+use super::microbes;
+use super::journal;
+use super::landing;
+use super::legend;
+use super::locale;
 use super::particles;
+use super::pause;
+use super::photo;
+use super::rewind;
+use super::saving;
+use super::search;
+use super::settings;
+use super::sky;
+use super::skybox;
+use super::small_bodies;
+use super::speed;
+use super::story;
 use super::surface;
+use super::surface_map;
+use super::tech_visuals;
 use super::ui;
 
 /// Main render plugin for the Matrix simulation
@@ -14,48 +47,124 @@ pub struct MatrixRenderPlugin;
 
 impl Plugin for MatrixRenderPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ui::HudThrottle>()
+        let settings = settings::UserSettings::load_or_default(&settings::settings_path());
+        app.insert_resource(locale::Strings::load(&settings.language))
+        .insert_resource(budget::RenderBudget::new(settings.render_budget))
+        .insert_resource(settings)
+        .init_resource::<ui::HudThrottle>()
         .init_resource::<surface::SurfaceState>()
         .init_resource::<surface::DetailState>()
         .init_resource::<surface::NearestCreatureInfo>()
+        .init_resource::<surface::SurfaceEcology>()
+        .init_resource::<surface::SurfaceDay>()
+        .init_resource::<surface::WeatherState>()
+        .init_resource::<chunks::TerrainChunks>()
+        .init_resource::<dive::AtmosphereDive>()
+        .init_resource::<landing::LandingTransition>()
+        .init_resource::<surface_map::SurfaceMap>()
+        .init_resource::<surface::SystemFocus>()
+        .init_resource::<cosmos::OrbitOverlay>()
+        .init_resource::<search::SearchPanel>()
+        .init_resource::<legend::ParticleViewFilter>()
+        .init_resource::<bestiary::BestiaryUi>()
+        .init_resource::<journal::JournalPanel>()
+        .init_resource::<census::CensusPanel>()
+        .init_resource::<history_graphs::GraphPanel>()
+        .init_resource::<console::ConsoleState>()
+        .init_resource::<console::CommandRegistry>()
+        .init_resource::<saving::PendingSaves>()
+        .init_resource::<rewind::RewindBookmarks>()
+        .init_resource::<bookmarks::BookmarkRecall>()
+        .init_resource::<photo::PhotoMode>()
+        .init_resource::<StoryDirector>()
+        .init_resource::<hud_layout::HudLayout>()
+        .init_resource::<gamepad::GamepadInput>()
+        .init_resource::<input_capture::UiInputCapture>()
         .add_systems(
             Startup,
             (
                 camera::spawn_camera,
+                skybox::spawn_skybox,
                 ui::spawn_hud,
                 cosmos::init_cosmos_state,
+                small_bodies::init_small_bodies,
+                tech_visuals::init_tech_visuals,
+                instances::init_instances,
                 particles::init_particle_cloud,
                 surface::init_planet_selection,
+                console::spawn_console,
+                search::spawn_search_panel,
+                legend::spawn_particle_legend,
+                bestiary::spawn_bestiary_ui,
+                journal::spawn_journal_screen,
+                feed::spawn_event_feed,
+                debug_overlay::spawn_debug_overlay,
+                census::spawn_census_panel,
+                history_graphs::spawn_graph_panel,
+                story::spawn_story_banner,
+                speed::spawn_speed_streaks,
             ),
         )
+        // The console swallows keyboard input before any gameplay system reads it
+        .add_systems(
+            PreUpdate,
+            (console::console_input_system, gamepad::gamepad_input_system)
+                .after(bevy::input::InputSystem)
+                .run_if(in_state(AppState::Running)),
+        )
+        // Whether the cursor is over the UI, before any camera or picking system reads the mouse
+        .add_systems(
+            PreUpdate,
+            input_capture::update_ui_capture
+                .after(bevy::ui::UiSystem::Focus)
+                .after(console::console_input_system)
+                .run_if(in_state(AppState::Running)),
+        )
         // Space-mode + always-active systems (only in Running state)
         .add_systems(
             Update,
             (
-                surface::surface_toggle_system,
-                surface::surface_enter_exit_system.after(surface::surface_toggle_system),
+                surface::level_transition_system,
+                surface::surface_enter_exit_system.after(surface::level_transition_system),
                 ui::update_hud,
+                console::update_console_ui,
+                feed::update_event_feed,
                 ui::time_control_system,
                 camera::snapshot_system,
-                camera::minimap_system,
+                // A minimap click wins over the 3D region pick under it
+                camera::minimap_system.after(surface::region_hover_system),
 
                 camera::fly_camera_system.run_if(surface::not_on_surface),
+                camera::camera_glide_system
+                    .run_if(surface::not_on_surface)
+                    .after(camera::fly_camera_system)
+                    .after(surface::level_transition_system),
                 camera::navigation_system.run_if(surface::not_on_surface),
+                camera::replay_camera_system
+                    .run_if(surface::not_on_surface)
+                    .after(camera::navigation_system),
                 camera::tracking_system
                     .run_if(surface::not_on_surface)
                     .after(camera::navigation_system),
+                camera::region_framing_system
+                    .run_if(surface::not_on_surface)
+                    .before(camera::camera_glide_system),
                 camera::zoom_update_system
                     .run_if(surface::not_on_surface)
                     .after(camera::tracking_system),
                 lazy_universe_lod_tick
                     .run_if(surface::not_on_surface)
                     .after(camera::zoom_update_system),
-                cosmos::update_region_visuals
+                (cosmos::update_region_visuals, cosmos::face_region_sprites)
+                    .chain()
                     .run_if(surface::not_on_surface)
                     .after(camera::zoom_update_system),
                 cosmos::update_cosmos_visuals
                     .run_if(surface::not_on_surface)
                     .after(lazy_universe_lod_tick),
+                cosmos::update_region_clusters
+                    .run_if(surface::not_on_surface)
+                    .after(lazy_universe_lod_tick),
             )
                 .run_if(in_state(AppState::Running)),
         )
@@ -65,30 +174,299 @@ impl Plugin for MatrixRenderPlugin {
             (
                 cosmos::animate_life_planets
                     .run_if(surface::not_on_surface),
+                cosmos::animate_supernova_shells
+                    .run_if(surface::not_on_surface),
+                cosmos::update_planet_orbits
+                    .run_if(surface::not_on_surface),
+                (
+                    cosmos::update_orbit_overlay.run_if(surface::not_on_surface),
+                    small_bodies::spawn_small_bodies.run_if(surface::not_on_surface),
+                    small_bodies::update_comets
+                        .run_if(surface::not_on_surface)
+                        .after(small_bodies::spawn_small_bodies),
+                    tech_visuals::spawn_tech_visuals
+                        .run_if(surface::not_on_surface)
+                        .after(cosmos::update_cosmos_visuals),
+                    tech_visuals::update_tech_visuals
+                        .run_if(surface::not_on_surface)
+                        .after(tech_visuals::spawn_tech_visuals)
+                        .after(cosmos::animate_life_planets)
+                        .after(cosmos::update_planet_orbits),
+                ),
                 particles::sync_particle_clouds
                     .run_if(surface::not_on_surface),
-                particles::update_particle_clouds
-                    .run_if(surface::not_on_surface)
-                    .after(particles::sync_particle_clouds),
+                (
+                    particles::update_particle_clouds
+                        .run_if(surface::not_on_surface)
+                        .after(particles::sync_particle_clouds),
+                    particles::update_black_holes.run_if(surface::not_on_surface),
+                ),
                 surface::planet_hover_system
                     .run_if(surface::not_on_surface),
                 surface::region_hover_system
                     .run_if(surface::not_on_surface),
+                surface::star_hover_system
+                    .run_if(surface::not_on_surface)
+                    .after(surface::planet_hover_system),
+                ui::update_tooltip.after(surface::star_hover_system),
+                (
+                    ui::update_region_inspector.after(surface::region_hover_system),
+                    ui::update_signal_indicator,
+                ),
+                instances::upload_instances
+                    .run_if(surface::not_on_surface)
+                    .after(cosmos::animate_life_planets)
+                    .after(cosmos::update_planet_orbits)
+                    .after(surface::star_hover_system),
 
                 surface::surface_camera_system
+                    .run_if(surface::on_surface)
+                    .run_if(landing::not_landing),
+                chunks::terrain_chunk_system
+                    .run_if(surface::on_surface)
+                    .after(surface::surface_camera_system),
+                surface::day_night_system
                     .run_if(surface::on_surface),
+                (
+                    surface::weather_system
+                        .run_if(surface::on_surface)
+                        .after(surface::day_night_system),
+                    sky::update_sky_bodies
+                        .run_if(surface::on_surface)
+                        .after(surface::day_night_system),
+                    sky::update_tech_sky
+                        .run_if(surface::on_surface)
+                        .after(surface::day_night_system),
+                    surface::sky_dome_budget_system.run_if(surface::on_surface),
+                ),
                 surface::creature_behavior_system
                     .run_if(surface::on_surface),
                 surface::surface_detail_system
                     .run_if(surface::on_surface),
-                surface::surface_microbe_system
+                microbes::microbe_system
                     .run_if(surface::on_surface),
                 surface::creature_proximity_system
                     .run_if(surface::on_surface)
                     .after(surface::creature_behavior_system),
             )
                 .run_if(in_state(AppState::Running)),
-        );
+        )
+        // Creature LOD: herds instead of individuals at Landscape height
+        .add_systems(
+            Update,
+            surface::creature_lod_system
+                .run_if(surface::on_surface)
+                .before(surface::creature_behavior_system)
+                .run_if(in_state(AppState::Running)),
+        )
+        // [B] on a giant: the atmosphere dive holds the camera after the flight systems
+        .add_systems(
+            Update,
+            dive::atmosphere_dive_system
+                .after(surface::level_transition_system)
+                .after(camera::camera_glide_system)
+                .after(camera::tracking_system)
+                .run_if(surface::not_on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        // Landing and take-off: the camera comes down from (or climbs to) high above the
+        // spot, then the sky, fog and streaks follow it over the day / night and weather
+        .add_systems(
+            Update,
+            (
+                landing::landing_transition_system
+                    .after(surface::level_transition_system)
+                    .before(surface::surface_enter_exit_system),
+                landing::landing_visuals_system
+                    .after(surface::surface_enter_exit_system)
+                    .after(surface::day_night_system)
+                    .after(surface::weather_system),
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // Surface map in the minimap corner and compass; [Home] back to the landing site
+        .add_systems(
+            Update,
+            (
+                surface_map::return_to_landing_system
+                    .run_if(surface::on_surface)
+                    .run_if(landing::not_landing)
+                    .before(surface::surface_camera_system),
+                surface_map::surface_map_system
+                    .after(surface::surface_enter_exit_system)
+                    .after(surface::surface_camera_system)
+                    .after(camera::minimap_system),
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // Liquid surfaces: ocean swell and lava glow
+        .add_systems(
+            Update,
+            chunks::liquid_animation_system
+                .run_if(surface::on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        // [E] creature inspection card, [I] bestiary screen
+        .add_systems(
+            Update,
+            (
+                bestiary::inspect_system.after(surface::creature_proximity_system),
+                bestiary::bestiary_screen_system,
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // Saves started from the pause menu finish while it's open
+        .add_systems(
+            Update,
+            saving::save_poll_system
+                .run_if(in_state(AppState::Running).or(in_state(AppState::Paused))),
+        )
+        // [K] bookmark, [J] rewind, HUD time slider
+        .add_systems(
+            Update,
+            (
+                rewind::bookmark_system.run_if(surface::not_on_surface),
+                ui::time_slider_system,
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // [Ctrl+1-9] store a view, [Alt+1-9] return to it
+        .add_systems(
+            Update,
+            (bookmarks::bookmark_keys_system, bookmarks::bookmark_pending_system)
+                .chain()
+                .run_if(surface::not_on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        .add_systems(OnExit(AppState::Loading), bookmarks::load_seed_bookmarks)
+        // [/] planet finder
+        .add_systems(
+            Update,
+            (
+                search::search_toggle_system,
+                search::search_criteria_system,
+                search::search_results_system,
+                search::search_hit_system,
+            )
+                .chain()
+                .run_if(surface::not_on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        // [Y] life journal
+        .add_systems(
+            Update,
+            (
+                journal::journal_keys_system,
+                journal::journal_filter_system,
+                journal::journal_rows_system,
+                journal::journal_go_system,
+                journal::journal_pending_system,
+            )
+                .chain()
+                .run_if(surface::not_on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        // [R] particle legend, [F7]/[F8] baryonic / dark matter only
+        .add_systems(
+            Update,
+            (
+                legend::legend_keys_system,
+                legend::legend_click_system,
+                legend::apply_particle_filter,
+                legend::update_particle_legend.after(particles::sync_particle_clouds),
+            )
+                .chain()
+                .run_if(surface::not_on_surface)
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F3] debug overlay
+        .add_systems(
+            Update,
+            (
+                debug_overlay::debug_overlay_toggle_system,
+                debug_overlay::sim_timings_system,
+                debug_overlay::update_debug_overlay,
+            )
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F4] universe census, computed on the async pool
+        .add_systems(
+            Update,
+            (
+                census::census_keys_system,
+                census::census_poll_system,
+                census::update_census_panel,
+            )
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F11] history graphs of the sampled universe metrics
+        .add_systems(
+            Update,
+            (
+                history_graphs::graph_keys_system,
+                history_graphs::graph_tab_system,
+                history_graphs::update_graph_panel,
+            )
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        )
+        // Space background: starfield sphere and distant galaxies
+        .add_systems(
+            Update,
+            (
+                skybox::skybox_generation_system,
+                skybox::update_skybox.after(surface::surface_enter_exit_system),
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F10] story mode: slow to 1x for the feed's big events, [X] resumes
+        .add_systems(
+            Update,
+            (story::story_mode_system, story::update_story_banner)
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        )
+        // Wider view and edge streaks at speed, on top of the field of view from the settings
+        .add_systems(
+            Update,
+            (
+                speed::speed_fov_system
+                    .after(camera::fly_camera_system)
+                    .after(pause::apply_settings_system),
+                speed::speed_streaks_system.after(camera::fly_camera_system),
+            )
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F12] photo mode
+        .add_systems(
+            Update,
+            (photo::photo_toggle_system, photo::photo_capture_system)
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        )
+        // [F1] help, [F2] compact HUD; the layout follows the window in every state
+        .add_systems(
+            Update,
+            (
+                hud_layout::hud_keys_system.run_if(in_state(AppState::Running)),
+                hud_layout::hud_layout_system.after(photo::photo_toggle_system),
+            )
+                .chain(),
+        )
+        // HUD and menu language, fixed labels in it and its font on every text, in every state
+        .add_systems(
+            Update,
+            (
+                locale::language_system,
+                locale::translated_text_system,
+                locale::locale_font_system,
+            )
+                .chain(),
+        )
+        // Render budget preset, changed on the settings page while paused
+        .add_systems(Update, budget::budget_preset_system);
     }
 }
 
@@ -106,9 +484,16 @@ fn lazy_universe_lod_tick(
     // During Big Bang / early universe: particles always visible, skip region LOD entirely
     let big_bang_phase = universe.age < 1.0;
 
+    // Swap in a region generated in the background since last frame
+    lazy.poll_region_detail();
+
     // Only run LOD (region loading) after Stellar Era — no regions during Big Bang
     if !big_bang_phase {
-        lazy.update_lod(cam_transform.translation, universe.age);
+        lazy.update_lod(cam_transform.translation.as_dvec3().to_array(), universe.age);
+        // Looking at the region from outside: get its neighbors ready
+        if cam.zoom_level == camera::ZoomLevel::Galactic {
+            lazy.prefetch_neighbors(universe.age);
+        }
     }
     let was_active = universe.particles_active;
     universe.particles_active = cam.zoom_level.particles_active() || big_bang_phase;
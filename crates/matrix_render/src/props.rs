@@ -0,0 +1,641 @@
+//! Surface props: vegetation shaped by the dominant genome, flora clusters and nests
+//! for complex biospheres, and settlements of technological ones. Placement is planned
+//! per terrain chunk from the planet id and the chunk coordinate, so a chunk always
+//! gets the same props no matter when or from where it is loaded.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+use matrix_core::{Biosphere, Planet, PlanetType};
+use matrix_physics::terrain::TerrainSampler;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::chunks::{CHUNK_SIZE, SurfaceLiquid};
+use super::surface::substrate_color;
+
+/// Hard cap on props per chunk (structures first, then flora and nests, then trees)
+pub const MAX_PROPS_PER_CHUNK: usize = 20;
+/// Settlements within this distance are reported on the HUD (about the load radius)
+pub const SETTLEMENT_SIGHT: f32 = 180.0;
+/// Settlements per planet with technology
+const SETTLEMENT_COUNT: usize = 3;
+/// Candidate sites tried before giving up on the remaining settlements
+const SETTLEMENT_CANDIDATES: usize = 12;
+/// Radius of a settlement (m)
+const SETTLEMENT_RADIUS: f32 = 28.0;
+/// Steepest ground props stand on (rise over run)
+const PROP_MAX_SLOPE: f32 = 0.7;
+/// Above this aridity the ground is too dry for trees
+const TREE_MAX_ARIDITY: f32 = 0.5;
+/// Tree shapes built per surface
+const TREE_VARIANTS: usize = 3;
+
+/// Kind of a surface prop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropKind {
+    /// Tree of one of the surface's `TREE_VARIANTS` shapes
+    Tree(usize),
+    /// Clump of large flora (complexity ≥ 5)
+    Flora,
+    /// Nest (flyers) or burrow mound of the local creatures (complexity ≥ 5)
+    Nest,
+    Hut,
+    /// Glowing slab at a settlement's center
+    Monolith,
+    /// Glowing pylon, what gives a settlement away at night
+    Beacon,
+}
+
+/// One planned prop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropSpot {
+    pub kind: PropKind,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub scale: f32,
+}
+
+/// Marker for prop entities (despawned with their chunk)
+#[derive(Component)]
+pub struct SurfaceProp;
+
+/// What a surface grows and builds: derived once on landing, then asked for every chunk
+#[derive(Debug, Clone)]
+pub struct PropPlanner {
+    planet_id: u64,
+    /// Tree attempts per chunk (0 without vegetation)
+    trees: usize,
+    tree_height: f32,
+    flora: bool,
+    nests: bool,
+    settlements: Vec<Vec2>,
+}
+
+impl PropPlanner {
+    /// None for surfaces without props: giants, lifeless or simple biospheres
+    pub fn new(
+        planet: &Planet,
+        sampler: &TerrainSampler,
+        liquid: Option<SurfaceLiquid>,
+    ) -> Option<Self> {
+        if matches!(
+            planet.planet_type,
+            PlanetType::GasGiant | PlanetType::IceGiant
+        ) {
+            return None;
+        }
+        let bio = planet.life.as_ref()?;
+        // Nothing grows on lava worlds
+        let vegetated = bio.complexity >= 3.0 && planet.planet_type != PlanetType::Lava;
+        let planner = Self {
+            planet_id: planet.id,
+            trees: if vegetated {
+                ((bio.biomass * 1.5) as usize).clamp(3, 16)
+            } else {
+                0
+            },
+            tree_height: (2.0 + (bio.complexity as f32 - 3.0)).clamp(3.0, 9.0),
+            flora: vegetated && bio.complexity >= 5.0,
+            nests: bio.complexity >= 5.0 && bio.dominant_genome.motility != 0,
+            settlements: if bio.has_technology {
+                settlement_sites(planet.id, sampler, liquid)
+            } else {
+                Vec::new()
+            },
+        };
+        let empty = planner.trees == 0 && !planner.nests && planner.settlements.is_empty();
+        (!empty).then_some(planner)
+    }
+
+    pub fn settlements(&self) -> &[Vec2] {
+        &self.settlements
+    }
+
+    /// Props of the chunk at `coord`, at most `MAX_PROPS_PER_CHUNK`
+    pub fn plan(
+        &self,
+        sampler: &TerrainSampler,
+        coord: IVec2,
+        liquid: Option<SurfaceLiquid>,
+    ) -> Vec<PropSpot> {
+        let origin = coord.as_vec2() * CHUNK_SIZE;
+        let in_chunk = |p: Vec2| {
+            let local = p - origin;
+            (0.0..CHUNK_SIZE).contains(&local.x) && (0.0..CHUNK_SIZE).contains(&local.y)
+        };
+        // Ground height where a prop may stand: dry and not too steep
+        let ground = |p: Vec2| {
+            let y = sampler.height(p.x, p.y);
+            let flooded = liquid.is_some_and(|l| y < l.level);
+            (!flooded && sampler.slope(p.x, p.y) <= PROP_MAX_SLOPE).then_some(y)
+        };
+        let mut props = Vec::new();
+
+        // Each settlement has a fixed layout; a chunk takes the part inside it
+        let middle = origin + CHUNK_SIZE / 2.0;
+        for (index, &site) in self.settlements.iter().enumerate() {
+            if site.distance(middle) > SETTLEMENT_RADIUS + CHUNK_SIZE {
+                continue;
+            }
+            for mut spot in settlement_layout(self.planet_id, index, site) {
+                let p = spot.position.xz();
+                if in_chunk(p)
+                    && let Some(y) = ground(p)
+                {
+                    spot.position.y = y;
+                    props.push(spot);
+                }
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(chunk_seed(self.planet_id, coord));
+        let scatter = |rng: &mut ChaCha8Rng| {
+            let p = origin
+                + Vec2::new(
+                    rng.gen_range(0.0..CHUNK_SIZE),
+                    rng.gen_range(0.0..CHUNK_SIZE),
+                );
+            let (yaw, size) = (rng.gen_range(0.0..TAU), rng.gen_range(0.7..1.3));
+            let clear = self
+                .settlements
+                .iter()
+                .all(|s| s.distance(p) > SETTLEMENT_RADIUS);
+            clear
+                .then(|| ground(p))
+                .flatten()
+                .map(|y| (Vec3::new(p.x, y, p.y), yaw, size))
+        };
+        if self.flora {
+            for _ in 0..2 {
+                if let Some((position, yaw, size)) = scatter(&mut rng) {
+                    props.push(PropSpot {
+                        kind: PropKind::Flora,
+                        position,
+                        yaw,
+                        scale: 2.0 * size,
+                    });
+                }
+            }
+        }
+        if self.nests
+            && rng.gen_bool(0.5)
+            && let Some((position, yaw, size)) = scatter(&mut rng)
+        {
+            props.push(PropSpot {
+                kind: PropKind::Nest,
+                position,
+                yaw,
+                scale: size,
+            });
+        }
+        for _ in 0..self.trees {
+            let variant = rng.gen_range(0..TREE_VARIANTS);
+            if let Some((position, yaw, size)) = scatter(&mut rng)
+                && sampler.aridity(position.x, position.z) < TREE_MAX_ARIDITY
+            {
+                props.push(PropSpot {
+                    kind: PropKind::Tree(variant),
+                    position,
+                    yaw,
+                    scale: self.tree_height * size,
+                });
+            }
+        }
+
+        props.truncate(MAX_PROPS_PER_CHUNK);
+        props
+    }
+}
+
+/// Settlement centers of a technological planet: the first a short walk from the
+/// landing site, the rest farther out. Sites on flooded or steep ground are skipped.
+pub fn settlement_sites(
+    planet_id: u64,
+    sampler: &TerrainSampler,
+    liquid: Option<SurfaceLiquid>,
+) -> Vec<Vec2> {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id ^ 0x5E77_1E00);
+    let mut sites = Vec::new();
+    for _ in 0..SETTLEMENT_CANDIDATES {
+        let near = sites.is_empty();
+        let distance = if near {
+            rng.gen_range(90.0..150.0)
+        } else {
+            rng.gen_range(300.0..700.0)
+        };
+        let site = Vec2::from_angle(rng.gen_range(0.0..TAU)) * distance;
+        let y = sampler.height(site.x, site.y);
+        if liquid.is_some_and(|l| y < l.level) || sampler.slope(site.x, site.y) > PROP_MAX_SLOPE {
+            continue;
+        }
+        sites.push(site);
+        if sites.len() == SETTLEMENT_COUNT {
+            break;
+        }
+    }
+    sites
+}
+
+/// Monolith in the middle, a ring of huts facing it and beacons between them
+/// (heights are filled in by the planner)
+fn settlement_layout(planet_id: u64, index: usize, site: Vec2) -> Vec<PropSpot> {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id.wrapping_add(index as u64 * 0x9E37));
+    let at = |p: Vec2| Vec3::new(p.x, 0.0, p.y);
+    let mut spots = vec![PropSpot {
+        kind: PropKind::Monolith,
+        position: at(site),
+        yaw: rng.gen_range(0.0..TAU),
+        scale: 1.0,
+    }];
+    let huts = rng.gen_range(6..=10);
+    for i in 0..huts {
+        let angle = i as f32 / huts as f32 * TAU + rng.gen_range(-0.2..0.2);
+        let offset = Vec2::from_angle(angle) * rng.gen_range(10.0..SETTLEMENT_RADIUS);
+        spots.push(PropSpot {
+            kind: PropKind::Hut,
+            position: at(site + offset),
+            // Door (+z) toward the monolith
+            yaw: 3.0 * FRAC_PI_2 - angle,
+            scale: rng.gen_range(0.8..1.2),
+        });
+    }
+    for i in 0..4 {
+        let angle = (i as f32 + 0.5) / 4.0 * TAU;
+        spots.push(PropSpot {
+            kind: PropKind::Beacon,
+            position: at(site + Vec2::from_angle(angle) * 6.0),
+            yaw: 0.0,
+            scale: 1.0,
+        });
+    }
+    spots
+}
+
+fn chunk_seed(planet_id: u64, coord: IVec2) -> u64 {
+    planet_id.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ ((coord.x as u32 as u64) << 32 | coord.y as u32 as u64)
+}
+
+/// Meshes and materials shared by the props of one surface
+pub struct PropAssets {
+    trees: Vec<Handle<Mesh>>,
+    flora: Handle<Mesh>,
+    nest: Handle<Mesh>,
+    hut: Handle<Mesh>,
+    monolith: Handle<Mesh>,
+    beacon: Handle<Mesh>,
+    /// Vertex-colored props
+    material: Handle<StandardMaterial>,
+    monolith_material: Handle<StandardMaterial>,
+    beacon_material: Handle<StandardMaterial>,
+}
+
+impl PropAssets {
+    /// Trees take their shape from the genome's body plan and their colors from its substrate
+    pub fn new(
+        planet_id: u64,
+        bio: &Biosphere,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Self {
+        let genome = &bio.dominant_genome;
+        let leaf = substrate_color(genome.substrate);
+        let bark = Color::srgb(0.32, 0.22, 0.14).mix(&leaf, 0.2);
+        let mut rng = ChaCha8Rng::seed_from_u64(planet_id ^ 0x7EE5);
+        let trees = (0..TREE_VARIANTS)
+            .map(|v| {
+                let leaf = leaf.rotate_hue(v as f32 * 14.0 - 14.0);
+                meshes.add(tree_mesh(genome.structure, bark, leaf, &mut rng))
+            })
+            .collect();
+
+        let flora_color = leaf.rotate_hue(-25.0).darker(0.08);
+        let flora = merge(
+            (0..5)
+                .map(|i| {
+                    let angle = i as f32 / 5.0 * TAU;
+                    let offset = Vec2::from_angle(angle) * if i == 0 { 0.0 } else { 0.6 };
+                    part(
+                        Sphere::new(0.5).mesh().ico(1).unwrap(),
+                        flora_color.lighter(i as f32 * 0.02),
+                        Transform::from_xyz(offset.x, 0.3, offset.y).with_scale(Vec3::new(
+                            1.0,
+                            0.7 + 0.1 * i as f32,
+                            1.0,
+                        )),
+                    )
+                })
+                .collect(),
+        );
+
+        let earth = Color::srgb(0.36, 0.27, 0.18);
+        let nest = if genome.motility == 7 {
+            // Flyers: a twig ring with a clutch of eggs
+            merge(vec![
+                part(
+                    Torus::new(0.3, 0.55),
+                    earth,
+                    Transform::from_xyz(0.0, 0.12, 0.0),
+                ),
+                part(
+                    Sphere::new(0.12).mesh().ico(1).unwrap(),
+                    Color::srgb(0.9, 0.88, 0.8),
+                    Transform::from_xyz(0.1, 0.12, 0.0),
+                ),
+                part(
+                    Sphere::new(0.12).mesh().ico(1).unwrap(),
+                    Color::srgb(0.9, 0.88, 0.8),
+                    Transform::from_xyz(-0.08, 0.12, 0.08),
+                ),
+            ])
+        } else {
+            // Everything else digs: a mound with a dark entrance
+            merge(vec![
+                part(
+                    Sphere::new(1.0).mesh().ico(2).unwrap(),
+                    earth,
+                    Transform::from_scale(Vec3::new(1.0, 0.4, 1.0)),
+                ),
+                part(
+                    Cylinder::new(0.3, 0.05),
+                    Color::srgb(0.05, 0.04, 0.03),
+                    Transform::from_xyz(0.0, 0.4, 0.0),
+                ),
+            ])
+        };
+
+        let clay = Color::srgb(0.62, 0.52, 0.4).mix(&leaf, 0.1);
+        let hut = merge(vec![
+            part(
+                Cuboid::new(2.0, 1.6, 2.0),
+                clay,
+                Transform::from_xyz(0.0, 0.8, 0.0),
+            ),
+            part(
+                Cone {
+                    radius: 1.7,
+                    height: 1.2,
+                },
+                Color::srgb(0.4, 0.28, 0.16),
+                Transform::from_xyz(0.0, 2.2, 0.0),
+            ),
+            part(
+                Cuboid::new(0.6, 1.0, 0.05),
+                Color::srgb(0.1, 0.07, 0.05),
+                Transform::from_xyz(0.0, 0.5, 1.0),
+            ),
+        ]);
+
+        Self {
+            trees,
+            flora: meshes.add(flora),
+            nest: meshes.add(nest),
+            hut: meshes.add(hut),
+            monolith: meshes
+                .add(Mesh::from(Cuboid::new(1.0, 4.0, 0.4)).translated_by(Vec3::Y * 2.0)),
+            beacon: meshes.add(merge(vec![
+                part(
+                    Cylinder::new(0.08, 2.2),
+                    Color::WHITE,
+                    Transform::from_xyz(0.0, 1.1, 0.0),
+                ),
+                part(
+                    Sphere::new(0.25).mesh().ico(1).unwrap(),
+                    Color::WHITE,
+                    Transform::from_xyz(0.0, 2.35, 0.0),
+                ),
+            ])),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE, // vertex colors handle coloring
+                perceptual_roughness: 0.85,
+                ..default()
+            }),
+            monolith_material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.08, 0.08, 0.1),
+                emissive: LinearRgba::rgb(0.2, 0.6, 1.0) * 0.6,
+                perceptual_roughness: 0.2,
+                ..default()
+            }),
+            beacon_material: materials.add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.8, 0.5),
+                emissive: LinearRgba::rgb(1.0, 0.7, 0.3) * 8.0,
+                ..default()
+            }),
+        }
+    }
+}
+
+/// Spawn planned props, returning their entities
+pub fn spawn_props(
+    commands: &mut Commands,
+    assets: &PropAssets,
+    spots: &[PropSpot],
+) -> Vec<Entity> {
+    spots
+        .iter()
+        .map(|spot| {
+            let (mesh, material) = match spot.kind {
+                PropKind::Tree(v) => (&assets.trees[v], &assets.material),
+                PropKind::Flora => (&assets.flora, &assets.material),
+                PropKind::Nest => (&assets.nest, &assets.material),
+                PropKind::Hut => (&assets.hut, &assets.material),
+                PropKind::Monolith => (&assets.monolith, &assets.monolith_material),
+                PropKind::Beacon => (&assets.beacon, &assets.beacon_material),
+            };
+            commands
+                .spawn((
+                    Mesh3d(mesh.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(spot.position)
+                        .with_rotation(Quat::from_rotation_y(spot.yaw))
+                        .with_scale(Vec3::splat(spot.scale)),
+                    SurfaceProp,
+                ))
+                .id()
+        })
+        .collect()
+}
+
+/// A tree one unit tall. Branching body plans fork, modular ones stack tiers,
+/// radial ones spread an umbrella, the rest carry a single crown.
+fn tree_mesh(structure: u32, bark: Color, leaf: Color, rng: &mut ChaCha8Rng) -> Mesh {
+    let trunk_radius = rng.gen_range(0.03..0.05);
+    let trunk = |height: f32| {
+        part(
+            Cylinder::new(trunk_radius, height),
+            bark,
+            Transform::from_xyz(0.0, height / 2.0, 0.0),
+        )
+    };
+    let crown = |radius: f32, at: Vec3| {
+        part(
+            Sphere::new(radius).mesh().ico(1).unwrap(),
+            leaf,
+            Transform::from_translation(at),
+        )
+    };
+    match structure {
+        6 => {
+            let mut parts = vec![trunk(0.6)];
+            let forks = rng.gen_range(2..=4);
+            for i in 0..forks {
+                let angle = i as f32 / forks as f32 * TAU + rng.gen_range(-0.3..0.3);
+                let tilt = rng.gen_range(0.4..0.8);
+                let dir = Quat::from_rotation_y(angle) * Quat::from_rotation_z(tilt);
+                let base = Vec3::Y * rng.gen_range(0.4..0.6);
+                let tip = base + dir * Vec3::Y * 0.4;
+                parts.push(part(
+                    Cylinder::new(trunk_radius * 0.6, 0.4),
+                    bark,
+                    Transform::from_translation((base + tip) / 2.0).with_rotation(dir),
+                ));
+                parts.push(crown(rng.gen_range(0.12..0.2), tip));
+            }
+            merge(parts)
+        }
+        5 => {
+            let mut parts = vec![trunk(0.3)];
+            let tiers = rng.gen_range(3..=4);
+            for i in 0..tiers {
+                let f = i as f32 / tiers as f32;
+                parts.push(part(
+                    Cone {
+                        radius: 0.35 * (1.0 - f * 0.6),
+                        height: 0.35,
+                    },
+                    leaf.darker(f * 0.05),
+                    Transform::from_xyz(0.0, 0.35 + f * 0.55, 0.0),
+                ));
+            }
+            merge(parts)
+        }
+        3 => merge(vec![
+            trunk(0.95),
+            part(
+                Cylinder::new(rng.gen_range(0.35..0.5), 0.05),
+                leaf,
+                Transform::from_xyz(0.0, 0.95, 0.0),
+            ),
+            crown(0.08, Vec3::Y),
+        ]),
+        _ => {
+            let radius = rng.gen_range(0.22..0.32);
+            merge(vec![
+                trunk(0.6),
+                part(
+                    Sphere::new(radius).mesh().ico(1).unwrap(),
+                    leaf,
+                    Transform::from_xyz(0.0, 0.6 + radius * 0.8, 0.0).with_scale(Vec3::new(
+                        1.0,
+                        rng.gen_range(1.0..1.5),
+                        1.0,
+                    )),
+                ),
+            ])
+        }
+    }
+}
+
+/// A primitive painted one color and moved into place, ready to merge
+pub(crate) fn part(mesh: impl Into<Mesh>, color: Color, transform: Transform) -> Mesh {
+    let mut mesh = mesh.into().transformed_by(transform);
+    let count = mesh.count_vertices();
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_COLOR,
+        vec![color.to_linear().to_f32_array(); count],
+    );
+    mesh
+}
+
+pub(crate) fn merge(parts: Vec<Mesh>) -> Mesh {
+    let mut parts = parts.into_iter();
+    let mut mesh = parts
+        .next()
+        .expect("props are built from at least one part");
+    for other in parts {
+        mesh.merge(&other);
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{AtmosphereType, Genome};
+
+    fn inhabited(planet_type: PlanetType, complexity: f64, has_technology: bool) -> Planet {
+        let mut genome = Genome::primordial();
+        genome.structure = 6;
+        genome.motility = 5;
+        Planet {
+            id: 4242,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 290.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type,
+            life: Some(Biosphere {
+                age: 3.0,
+                complexity,
+                species_count: 1000,
+                dominant_genome: genome,
+                has_technology,
+                biomass: 8.0,
+            }),
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    #[test]
+    fn test_props_follow_the_biosphere() {
+        let planet = inhabited(PlanetType::Rocky, 6.0, true);
+        let sampler = TerrainSampler::new(planet.id, planet.planet_type);
+        let planner = PropPlanner::new(&planet, &sampler, None).unwrap();
+
+        // Same chunk, same props; never over budget
+        let coord = IVec2::new(1, -2);
+        assert_eq!(
+            planner.plan(&sampler, coord, None),
+            planner.plan(&sampler, coord, None)
+        );
+        let mut kinds = Vec::new();
+        for z in -3..3 {
+            for x in -3..3 {
+                let props = planner.plan(&sampler, IVec2::new(x, z), None);
+                assert!(props.len() <= MAX_PROPS_PER_CHUNK);
+                kinds.extend(props.iter().map(|p| p.kind));
+            }
+        }
+        assert!(kinds.iter().any(|k| matches!(k, PropKind::Tree(_))));
+        assert!(kinds.contains(&PropKind::Flora));
+
+        // The first settlement is a short walk away, and its chunks hold its buildings
+        let site = planner.settlements()[0];
+        assert!(site.length() < 160.0);
+        let home = crate::chunks::chunk_coord(site.x, site.y);
+        let props = planner.plan(&sampler, home, None);
+        assert!(props.iter().any(|p| p.kind == PropKind::Monolith));
+
+        // No trees on lava; microbial life builds nothing
+        let lava = inhabited(PlanetType::Lava, 6.0, false);
+        let sampler = TerrainSampler::new(lava.id, lava.planet_type);
+        let planner = PropPlanner::new(&lava, &sampler, None).unwrap();
+        let props = planner.plan(&sampler, IVec2::ZERO, None);
+        assert!(
+            props
+                .iter()
+                .all(|p| !matches!(p.kind, PropKind::Tree(_) | PropKind::Flora))
+        );
+        assert!(
+            PropPlanner::new(&inhabited(PlanetType::Rocky, 1.0, false), &sampler, None).is_none()
+        );
+    }
+}
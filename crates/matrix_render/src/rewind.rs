@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use matrix_core::GpuParticle;
+use matrix_physics::{particle, procgen};
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::UniverseSnapshot;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::saving::{self, SnapshotCapture, unix_now};
+use super::settings::{Action, UserSettings, action_just_pressed};
+
+/// Bookmarks kept in memory; the oldest is dropped past this
+pub const MAX_BOOKMARKS: usize = 5;
+
+/// An in-memory snapshot taken with [K]
+pub struct Bookmark {
+    pub snapshot: UniverseSnapshot,
+    /// False when the particles were over `rewind_particle_budget` and left out
+    pub has_particles: bool,
+}
+
+/// [K] bookmark / [J] rewind ring, most recent last
+#[derive(Resource, Default)]
+pub struct RewindBookmarks {
+    bookmarks: VecDeque<Bookmark>,
+    /// Bookmarks still encoding, in the order [K] was pressed
+    pending: VecDeque<Task<Bookmark>>,
+}
+
+impl RewindBookmarks {
+    pub fn push(&mut self, bookmark: Bookmark) {
+        if self.bookmarks.len() == MAX_BOOKMARKS {
+            self.bookmarks.pop_front();
+        }
+        self.bookmarks.push_back(bookmark);
+    }
+
+    pub fn latest(&self) -> Option<&Bookmark> {
+        self.bookmarks.back()
+    }
+
+    /// Ages (Gyr) of the stored bookmarks, oldest first
+    pub fn ages(&self) -> impl Iterator<Item = f64> + '_ {
+        self.bookmarks.iter().map(|b| b.snapshot.age)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bookmarks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Move finished encodes into the ring in the order they were requested: one that
+    /// finishes early waits for the bookmarks taken before it
+    fn land_finished(&mut self) {
+        while let Some(task) = self.pending.front_mut() {
+            let Some(bookmark) = block_on(poll_once(task)) else {
+                break;
+            };
+            self.pending.pop_front();
+            info!("Bookmarked age {:.4} Gyr", bookmark.snapshot.age);
+            self.push(bookmark);
+        }
+    }
+}
+
+/// Copy the state for a bookmark, leaving the particles out above the budget
+pub fn capture_bookmark(
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    feed: &EventFeed,
+    history: &UniverseHistory,
+) -> (SnapshotCapture, bool) {
+    let with_particles = universe.particles.len() <= universe.config.rewind_particle_budget;
+    (
+        SnapshotCapture::capture_with(universe, lazy, feed, history, with_particles),
+        with_particles,
+    )
+}
+
+/// Particles for a bookmark saved without them: the current region's field, or a
+/// fresh Big Bang of the bookmarked cycle outside any region
+fn regenerate_particles(universe: &UniverseState, lazy: &LazyUniverse) -> Vec<GpuParticle> {
+    let region = lazy
+        .current_region_id
+        .and_then(|id| lazy.regions.iter().find(|r| r.id == id));
+    match region {
+        Some(region) => {
+            let galaxies = procgen::generate_galaxies(region);
+            particle::generate_region_particles(region, &galaxies, universe.age)
+        }
+        None => {
+            let mut rng = ChaCha8Rng::seed_from_u64(universe.cycle_seed());
+            particle::generate_big_bang(&universe.config, &mut rng)
+        }
+    }
+}
+
+/// [K] bookmarks the simulation (encoded on the async pool), [J] rewinds to the
+/// latest bookmark the same way F9 restores a save
+pub fn bookmark_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut bookmarks: ResMut<RewindBookmarks>,
+    mut universe: ResMut<UniverseState>,
+    mut lazy: ResMut<LazyUniverse>,
+    mut feed: ResMut<EventFeed>,
+    mut history: ResMut<UniverseHistory>,
+) {
+    if action_just_pressed(&settings, &keyboard, Action::Bookmark) {
+        let (capture, has_particles) = capture_bookmark(&universe, &lazy, &feed, &history);
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            Bookmark {
+                snapshot: capture.into_snapshot(unix_now()),
+                has_particles,
+            }
+        });
+        bookmarks.pending.push_back(task);
+    }
+    bookmarks.land_finished();
+
+    if action_just_pressed(&settings, &keyboard, Action::Rewind) {
+        let Some(bookmark) = bookmarks.latest() else {
+            warn!("No bookmark to rewind to — press [K] first");
+            return;
+        };
+        let has_particles = bookmark.has_particles;
+        saving::apply_snapshot(
+            bookmark.snapshot.clone(),
+            &mut universe,
+            &mut lazy,
+            &mut feed,
+            &mut history,
+        );
+        if !has_particles {
+            let particles = regenerate_particles(&universe, &lazy);
+            universe.replace_particles(particles);
+        }
+        info!("Rewound to age {:.4} Gyr", universe.age);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::tasks::TaskPoolBuilder;
+    use matrix_core::{ParticleKind, SimConfig};
+
+    #[test]
+    fn test_bookmark_ring_and_particle_budget() {
+        let config = SimConfig {
+            rewind_particle_budget: 10,
+            ..SimConfig::default()
+        };
+        let particle = GpuParticle::new([0.0; 3], [0.0; 3], 1.0, 0.0, ParticleKind::Hydrogen);
+        let mut universe = UniverseState::new(config.clone(), vec![particle; 5]);
+        let lazy = LazyUniverse::new(config, 5.0);
+        let feed = EventFeed::default();
+        let history = UniverseHistory::default();
+
+        let (capture, kept) = capture_bookmark(&universe, &lazy, &feed, &history);
+        assert!(kept);
+        assert_eq!(capture.into_snapshot(0).particles.len(), 5);
+
+        universe.replace_particles(vec![particle; 11]);
+        let (capture, kept) = capture_bookmark(&universe, &lazy, &feed, &history);
+        assert!(!kept);
+        assert!(capture.into_snapshot(0).particles.is_empty());
+
+        let mut bookmarks = RewindBookmarks::default();
+        for i in 0..MAX_BOOKMARKS + 2 {
+            universe.age = i as f64;
+            let (capture, has_particles) = capture_bookmark(&universe, &lazy, &feed, &history);
+            bookmarks.push(Bookmark {
+                snapshot: capture.into_snapshot(0),
+                has_particles,
+            });
+        }
+        assert_eq!(bookmarks.len(), MAX_BOOKMARKS);
+        assert_eq!(bookmarks.ages().next(), Some(2.0));
+        assert_eq!(
+            bookmarks.latest().map(|b| b.snapshot.age),
+            Some((MAX_BOOKMARKS + 1) as f64)
+        );
+    }
+
+    #[test]
+    fn test_bookmarks_land_in_request_order() {
+        let config = SimConfig::default();
+        let mut universe = UniverseState::new(config.clone(), Vec::new());
+        let lazy = LazyUniverse::new(config, 5.0);
+        let feed = EventFeed::default();
+        let history = UniverseHistory::default();
+        let mut bookmark_at = |age: f64| {
+            universe.age = age;
+            let (capture, has_particles) = capture_bookmark(&universe, &lazy, &feed, &history);
+            Bookmark {
+                snapshot: capture.into_snapshot(0),
+                has_particles,
+            }
+        };
+        let (first, second) = (bookmark_at(1.0), bookmark_at(2.0));
+
+        // The first [K] is still encoding when the second one finishes
+        let pool = TaskPoolBuilder::new().num_threads(2).build();
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let mut bookmarks = RewindBookmarks::default();
+        bookmarks.pending.push_back(pool.spawn(async move {
+            wait.recv().unwrap();
+            first
+        }));
+        bookmarks.pending.push_back(pool.spawn(async move { second }));
+        while !bookmarks.pending[1].is_finished() {
+            std::thread::yield_now();
+        }
+        bookmarks.land_finished();
+        assert!(bookmarks.is_empty());
+
+        release.send(()).unwrap();
+        while !bookmarks.pending.is_empty() {
+            bookmarks.land_finished();
+        }
+        assert_eq!(bookmarks.ages().collect::<Vec<_>>(), [1.0, 2.0]);
+    }
+}
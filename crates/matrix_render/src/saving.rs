@@ -0,0 +1,298 @@
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use matrix_core::{
+    Bestiary, Bookmarks, Civilization, CowVec, CustomNames, FeedEntry, Galaxy, GpuParticle, HistorySample,
+    LifeDiscovery, Region, SerializedParticle, SimConfig, SimEvent, Star, SurfaceMemory,
+    UniversePhase,
+};
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::UniverseHistory;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::{Compression, SnapshotError, UniverseSnapshot};
+use std::path::{Path, PathBuf};
+
+/// Get the saves directory path
+pub fn saves_dir() -> PathBuf {
+    PathBuf::from("saves")
+}
+
+/// Save slot names become file names: letters, digits, '_' and '-' only
+pub fn valid_slot_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Unix seconds now (0 if the clock is before the epoch)
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Simulation state captured on the main thread for a save.
+/// Particles, regions and loaded stars are shared copy-on-write with the live state
+/// (no copy here); converting particles to `SerializedParticle`, encoding and file IO
+/// happen on the task pool.
+pub struct SnapshotCapture {
+    age: f64,
+    scale_factor: f64,
+    phase: UniversePhase,
+    cycle: u32,
+    temperature: f64,
+    total_entropy: f64,
+    config: SimConfig,
+    particles: CowVec<GpuParticle>,
+    regions: CowVec<Region>,
+    current_region_id: Option<u64>,
+    loaded_stars: CowVec<Star>,
+    stars_age: f64,
+    galaxies: Vec<Galaxy>,
+    life_planets: Vec<(u64, String)>,
+    discoveries: Vec<LifeDiscovery>,
+    civilizations: Vec<Civilization>,
+    civilization_count: u32,
+    time_scale: f64,
+    paused: bool,
+    events: Vec<FeedEntry>,
+    surface_memory: SurfaceMemory,
+    bestiary: Bestiary,
+    names: CustomNames,
+    bookmarks: Bookmarks,
+    history: Vec<HistorySample>,
+}
+
+impl SnapshotCapture {
+    pub fn capture(
+        universe: &UniverseState,
+        lazy: &LazyUniverse,
+        feed: &EventFeed,
+        history: &UniverseHistory,
+    ) -> Self {
+        Self::capture_with(universe, lazy, feed, history, true)
+    }
+
+    /// `capture`, optionally leaving out the particle vector (the bulk of a snapshot)
+    pub fn capture_with(
+        universe: &UniverseState,
+        lazy: &LazyUniverse,
+        feed: &EventFeed,
+        history: &UniverseHistory,
+        with_particles: bool,
+    ) -> Self {
+        Self {
+            age: universe.age,
+            scale_factor: universe.scale_factor,
+            phase: universe.phase,
+            cycle: universe.cycle,
+            temperature: universe.temperature,
+            total_entropy: universe.total_entropy,
+            config: universe.config.clone(),
+            particles: if with_particles {
+                universe.particles.clone()
+            } else {
+                CowVec::default()
+            },
+            regions: lazy.regions.clone(),
+            current_region_id: lazy.current_region_id,
+            loaded_stars: lazy.loaded_stars.clone(),
+            stars_age: lazy.stars_age,
+            galaxies: lazy.loaded_galaxies.clone(),
+            life_planets: lazy.life_planets.clone(),
+            discoveries: lazy.discoveries.clone(),
+            civilizations: lazy.civilizations.clone(),
+            civilization_count: lazy.civilization_count,
+            time_scale: universe.time_scale,
+            paused: universe.paused,
+            events: feed.entries().iter().cloned().collect(),
+            surface_memory: lazy.surface_memory.clone(),
+            bestiary: lazy.bestiary.clone(),
+            names: lazy.names.clone(),
+            bookmarks: lazy.bookmarks.clone(),
+            history: history.samples().to_vec(),
+        }
+    }
+
+    pub fn into_snapshot(self, saved_at: u64) -> UniverseSnapshot {
+        UniverseSnapshot {
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            particles: self.particles.iter().map(SerializedParticle::from).collect(),
+            regions: self.regions.into_vec(),
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars.into_vec(),
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: self.history,
+        }
+    }
+}
+
+/// Replace the running simulation with `snapshot` (F9, rewind)
+pub fn apply_snapshot(
+    snapshot: UniverseSnapshot,
+    universe: &mut UniverseState,
+    lazy: &mut LazyUniverse,
+    feed: &mut EventFeed,
+    history: &mut UniverseHistory,
+) {
+    universe.age = snapshot.age;
+    universe.scale_factor = snapshot.scale_factor;
+    universe.phase = snapshot.phase;
+    universe.cycle = snapshot.cycle;
+    universe.temperature = snapshot.temperature;
+    universe.total_entropy = snapshot.total_entropy;
+    universe.config = snapshot.config;
+    lazy.config = universe.config.clone();
+    universe.particles = snapshot.particles.iter().map(|p| p.into()).collect();
+    universe.time_scale = snapshot.time_scale;
+    universe.paused = snapshot.paused;
+
+    lazy.reset_region_loading();
+
+    lazy.regions = snapshot.regions.into();
+    lazy.current_region_id = snapshot.current_region_id;
+    lazy.loaded_stars = snapshot.loaded_stars.into();
+    lazy.stars_age = snapshot.stars_age;
+    lazy.loaded_galaxies = snapshot.galaxies;
+    // Keep the saved orbits instead of regenerating stars on the next age reload
+    lazy.last_reload_age = snapshot.age;
+    lazy.life_planets = snapshot.life_planets;
+    lazy.discoveries = snapshot.discoveries;
+    lazy.civilizations = snapshot.civilizations;
+    lazy.civilization_count = snapshot.civilization_count;
+    lazy.surface_memory = snapshot.surface_memory;
+    lazy.bestiary = snapshot.bestiary;
+    lazy.names = snapshot.names;
+    lazy.bookmarks = snapshot.bookmarks;
+    lazy.refresh_region_flags();
+    lazy.region_flags_generation = lazy.region_flags_generation.wrapping_add(1);
+    lazy.stars_generation = lazy.stars_generation.wrapping_add(1);
+    lazy.particles_generation = lazy.particles_generation.wrapping_add(1);
+    universe.cached_alive_count = universe.particles.len();
+    universe.particles_generation = universe.particles_generation.wrapping_add(1);
+    lazy.rebuild_signals(universe.age);
+    feed.restore(snapshot.events);
+    history.restore(snapshot.history);
+}
+
+/// Capture the whole simulation state for saving (synchronously)
+pub fn build_snapshot(
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    feed: &EventFeed,
+    history: &UniverseHistory,
+    saved_at: u64,
+) -> UniverseSnapshot {
+    SnapshotCapture::capture(universe, lazy, feed, history).into_snapshot(saved_at)
+}
+
+/// Saves still being encoded / written in the background
+#[derive(Resource, Default)]
+pub struct PendingSaves(Vec<Task<(PathBuf, Result<(), SnapshotError>)>>);
+
+impl PendingSaves {
+    /// Encode and write `capture` to `path` on the async compute pool
+    pub fn spawn(&mut self, capture: SnapshotCapture, path: PathBuf, compression: Compression) {
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let result = write_capture(capture, &path, compression);
+            (path, result)
+        });
+        self.0.push(task);
+    }
+}
+
+fn write_capture(
+    capture: SnapshotCapture,
+    path: &Path,
+    compression: Compression,
+) -> Result<(), SnapshotError> {
+    let snapshot = capture.into_snapshot(unix_now());
+    matrix_storage::save_snapshot(&snapshot, path, compression)
+}
+
+/// Report finished background saves
+pub fn save_poll_system(
+    mut pending: ResMut<PendingSaves>,
+    universe: Res<UniverseState>,
+    mut feed: ResMut<EventFeed>,
+) {
+    pending.0.retain_mut(|task| {
+        let Some((path, result)) = block_on(poll_once(task)) else {
+            return true;
+        };
+        match result {
+            Ok(()) => {
+                info!("Snapshot saved: {}", path.display());
+                feed.push(FeedEntry {
+                    age_gyr: universe.age,
+                    event: SimEvent::SnapshotSaved {
+                        path: path.display().to_string(),
+                    },
+                });
+            }
+            Err(e) => error!("Failed to save snapshot {}: {e}", path.display()),
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::ParticleKind;
+
+    #[test]
+    fn test_capture_shares_state_instead_of_copying() {
+        let config = SimConfig::default();
+        let particles = vec![
+            GpuParticle::new([1.0, 2.0, 3.0], [0.1, 0.0, 0.0], 1.0, 0.0, ParticleKind::Hydrogen);
+            100_000
+        ];
+        let mut universe = UniverseState::new(config.clone(), particles);
+        let lazy = LazyUniverse::new(config, 5.0);
+        let mut feed = EventFeed::default();
+        feed.push(FeedEntry {
+            age_gyr: 0.0,
+            event: SimEvent::CycleBegan { cycle: 1 },
+        });
+        let mut history = UniverseHistory::default();
+        history.record(matrix_sim::history::sample_of(&universe, &lazy));
+
+        let capture = SnapshotCapture::capture(&universe, &lazy, &feed, &history);
+        assert!(CowVec::ptr_eq(&capture.particles, &universe.particles));
+        assert!(CowVec::ptr_eq(&capture.regions, &lazy.regions));
+        assert!(CowVec::ptr_eq(&capture.loaded_stars, &lazy.loaded_stars));
+
+        // The simulation moving on copies its side; the capture keeps the saved state
+        universe.particles[0].position[0] = 99.0;
+        assert!(!CowVec::ptr_eq(&capture.particles, &universe.particles));
+
+        let snapshot = capture.into_snapshot(1);
+        assert_eq!(snapshot.particles.len(), 100_000);
+        assert_eq!(snapshot.particles[0].position[0], 1.0);
+        assert_eq!(snapshot.regions.len(), lazy.regions.len());
+        assert_eq!(snapshot.events.len(), 1);
+        assert_eq!(snapshot.history.len(), 1);
+    }
+}
@@ -0,0 +1,381 @@
+use bevy::prelude::*;
+use matrix_core::{PlanetType, SpectralClass};
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::search::{PlanetHit, PlanetQuery};
+
+use super::camera::{CameraGlide, FlyCamera};
+use super::cosmos::orbit_offset;
+use super::input_capture::CapturesPointer;
+use super::locale::{Strings, Translated};
+use super::settings::{Action, UserSettings, action_just_pressed};
+use super::surface::{self, PlanetSelection};
+
+/// Hits listed in the panel
+const MAX_HITS: usize = 20;
+/// Temperature presets cycled by the Temp button: habitable, hot, cold (K)
+const TEMP_PRESETS: [(f64, f64); 3] = [
+    (240.0, 400.0),
+    (400.0, f64::INFINITY),
+    (f64::NEG_INFINITY, 240.0),
+];
+/// Where the camera stops relative to a found planet
+const HIT_VIEW_OFFSET: Vec3 = Vec3::new(0.0, 2.0, 6.0);
+
+const BUTTON_COLOR: Color = Color::srgba(0.1, 0.25, 0.15, 0.9);
+const BUTTON_HOVER_COLOR: Color = Color::srgba(0.15, 0.4, 0.25, 0.95);
+
+/// [/] planet finder state
+#[derive(Resource, Default)]
+pub struct SearchPanel {
+    pub open: bool,
+    pub query: PlanetQuery,
+    /// Query or loaded stars changed since the hit list was built
+    dirty: bool,
+    stars_generation: u32,
+}
+
+/// Marker for the finder root node
+#[derive(Component)]
+pub struct SearchRoot;
+
+/// Marker for the node holding the hit rows
+#[derive(Component)]
+pub struct SearchResults;
+
+/// One criterion button; clicking cycles its value
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    Class,
+    Type,
+    Water,
+    Life,
+    Tech,
+    Temp,
+}
+
+/// A clickable hit row
+#[derive(Component)]
+pub struct SearchHitRow {
+    pub star_id: u64,
+    pub planet_id: u64,
+}
+
+impl Criterion {
+    const ALL: [Criterion; 6] = [
+        Self::Class,
+        Self::Type,
+        Self::Water,
+        Self::Life,
+        Self::Tech,
+        Self::Temp,
+    ];
+
+    /// Advance this criterion to its next value (wrapping back to "any")
+    fn cycle(self, query: &mut PlanetQuery) {
+        match self {
+            Self::Class => query.spectral_class = cycle_option(query.spectral_class, &SpectralClass::ALL),
+            Self::Type => query.planet_type = cycle_option(query.planet_type, &PlanetType::ALL),
+            Self::Water => query.has_water = cycle_option(query.has_water, &[true, false]),
+            Self::Life => query.has_life = cycle_option(query.has_life, &[true, false]),
+            Self::Tech => query.has_technology = cycle_option(query.has_technology, &[true, false]),
+            Self::Temp => query.temp_range = cycle_option(query.temp_range, &TEMP_PRESETS),
+        }
+    }
+
+    fn label(self, query: &PlanetQuery, strings: &Strings) -> String {
+        let any = || strings.tr("search.any", &[]);
+        let yes_no = |v: Option<bool>| v.map_or_else(any, |v| strings.yes_no(v));
+        let (key, value) = match self {
+            Self::Class => ("search.class", query.spectral_class.map_or_else(any, |c| format!("{c:?}"))),
+            Self::Type => ("search.type", query.planet_type.map_or_else(any, |t| format!("{t:?}"))),
+            Self::Water => ("search.water", yes_no(query.has_water)),
+            Self::Life => ("search.life", yes_no(query.has_life)),
+            Self::Tech => ("search.tech", yes_no(query.has_technology)),
+            Self::Temp => (
+                "search.temp",
+                match query.temp_range {
+                    None => any(),
+                    Some((lo, hi)) if lo.is_infinite() => format!("<{hi:.0}K"),
+                    Some((lo, hi)) if hi.is_infinite() => format!(">{lo:.0}K"),
+                    Some((lo, hi)) => format!("{lo:.0}–{hi:.0}K"),
+                },
+            ),
+        };
+        strings.tr(key, &[("value", &value)])
+    }
+}
+
+/// None → first → … → last → None
+fn cycle_option<T: Copy + PartialEq>(current: Option<T>, all: &[T]) -> Option<T> {
+    match current {
+        None => all.first().copied(),
+        Some(v) => all
+            .iter()
+            .position(|x| *x == v)
+            .and_then(|i| all.get(i + 1))
+            .copied(),
+    }
+}
+
+/// Spawn the (hidden) finder panel, bottom right
+pub fn spawn_search_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                right: Val::Px(10.0),
+                width: Val::Px(420.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.9)),
+            SearchRoot,
+            CapturesPointer,
+        ))
+        .with_children(|panel| {
+            panel.spawn((panel_text("", 13.0), Translated("search.title")));
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(4.0),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for criterion in Criterion::ALL {
+                        row.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                            criterion,
+                        ))
+                        .with_children(|btn| {
+                            // Labelled by `search_criteria_system`
+                            btn.spawn(panel_text("", 13.0));
+                        });
+                    }
+                });
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                SearchResults,
+            ));
+        });
+}
+
+fn panel_text(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+    )
+}
+
+/// [/] shows / hides the finder
+pub fn search_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<UserSettings>,
+    mut panel: ResMut<SearchPanel>,
+    mut root_q: Query<&mut Node, With<SearchRoot>>,
+) {
+    if !action_just_pressed(&settings, &keyboard, Action::Finder) {
+        return;
+    }
+    panel.open = !panel.open;
+    panel.dirty = true;
+    for mut node in &mut root_q {
+        node.display = if panel.open { Display::Flex } else { Display::None };
+    }
+}
+
+/// Criterion buttons: cycle the value on click; relabel them when the query or the
+/// language changes
+pub fn search_criteria_system(
+    strings: Res<Strings>,
+    mut panel: ResMut<SearchPanel>,
+    mut button_q: Query<(Ref<Interaction>, &Criterion, &Children, &mut BackgroundColor)>,
+    mut text_q: Query<&mut Text>,
+    mut labelled: Local<bool>,
+) {
+    let mut relabel = strings.is_changed() || !*labelled;
+    for (interaction, criterion, _, mut bg) in &mut button_q {
+        if !interaction.is_changed() {
+            continue;
+        }
+        *bg = BackgroundColor(match *interaction {
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            _ => BUTTON_COLOR,
+        });
+        if *interaction == Interaction::Pressed {
+            criterion.cycle(&mut panel.query);
+            panel.dirty = true;
+            relabel = true;
+        }
+    }
+    if !relabel {
+        return;
+    }
+    for (_, criterion, children, _) in &button_q {
+        for &child in children {
+            if let Ok(mut text) = text_q.get_mut(child) {
+                **text = criterion.label(&panel.query, &strings);
+                *labelled = true;
+            }
+        }
+    }
+}
+
+/// Rebuild the hit list when the query, the loaded stars or the language change
+pub fn search_results_system(
+    mut commands: Commands,
+    strings: Res<Strings>,
+    lazy: Res<LazyUniverse>,
+    mut panel: ResMut<SearchPanel>,
+    results_q: Query<Entity, With<SearchResults>>,
+) {
+    if !panel.open
+        || (!panel.dirty && panel.stars_generation == lazy.stars_generation && !strings.is_changed())
+    {
+        return;
+    }
+    panel.dirty = false;
+    panel.stars_generation = lazy.stars_generation;
+    let Ok(results) = results_q.get_single() else {
+        return;
+    };
+
+    let hits = lazy.query_planets(&panel.query);
+    commands.entity(results).despawn_descendants();
+    commands.entity(results).with_children(|list| {
+        if hits.is_empty() {
+            list.spawn(panel_text(strings.tr("search.no_hits", &[]), 13.0));
+            return;
+        }
+        list.spawn(panel_text(
+            strings.tr(
+                "search.hits",
+                &[("count", &hits.len()), ("shown", &hits.len().min(MAX_HITS))],
+            ),
+            12.0,
+        ));
+        for hit in hits.iter().take(MAX_HITS) {
+            list.spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                SearchHitRow {
+                    star_id: hit.star_id,
+                    planet_id: hit.planet_id,
+                },
+            ))
+            .with_children(|row| {
+                row.spawn(panel_text(hit_label(&lazy, hit), 12.0));
+            });
+        }
+    });
+}
+
+fn hit_label(lazy: &LazyUniverse, hit: &PlanetHit) -> String {
+    let Some((star, planet)) = surface::find_planet(lazy, hit.star_id, hit.planet_id) else {
+        return format!("planet #{}", hit.planet_id);
+    };
+    let life = match planet.life.as_ref() {
+        Some(b) if b.has_technology => " | tech",
+        Some(_) => " | life",
+        None => "",
+    };
+    let dist: f64 = (0..3)
+        .map(|k| (hit.position[k] - lazy.camera_pos[k]).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    format!(
+        "{:?} {:.0}K{}{} — {:?} star #{} — {:.1} away",
+        planet.planet_type,
+        planet.surface_temp,
+        if planet.has_water { " | water" } else { "" },
+        life,
+        star.spectral_class,
+        star.id,
+        dist
+    )
+}
+
+/// Hit rows: glide to the planet and pre-select it so [B] lands right away
+pub fn search_hit_system(
+    lazy: Res<LazyUniverse>,
+    mut selection: ResMut<PlanetSelection>,
+    mut row_q: Query<(&Interaction, &SearchHitRow, &mut BackgroundColor), Changed<Interaction>>,
+    mut cam_q: Query<&mut FlyCamera>,
+) {
+    for (interaction, row, mut bg) in &mut row_q {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            _ => BUTTON_COLOR,
+        });
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some((star, planet)) = surface::find_planet(&lazy, row.star_id, row.planet_id) else {
+            continue;
+        };
+        let planet_pos = Vec3::new(
+            star.position[0] as f32,
+            star.position[1] as f32,
+            star.position[2] as f32,
+        ) + orbit_offset(planet);
+        if let Ok(mut cam) = cam_q.get_single_mut() {
+            cam.tracking = None;
+            cam.glide = Some(CameraGlide::looking_at(planet_pos + HIT_VIEW_OFFSET, planet_pos));
+        }
+        selection.selected_planet = Some((planet.clone(), star.spectral_class));
+        selection.selected_star = None;
+        selection.selected_region = None;
+        info!(
+            "Finder: flying to {:?} planet id={} of star #{}",
+            planet.planet_type, planet.id, star.id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_criteria_cycle_back_to_any() {
+        let strings = Strings::default();
+        let mut query = PlanetQuery::default();
+        for criterion in Criterion::ALL {
+            let mut seen = 0;
+            loop {
+                criterion.cycle(&mut query);
+                seen += 1;
+                if query == PlanetQuery::default() {
+                    break;
+                }
+                assert!(seen < 20, "{} never wraps", criterion.label(&query, &strings));
+            }
+        }
+        // Class goes through all seven classes first
+        Criterion::Class.cycle(&mut query);
+        assert_eq!(query.spectral_class, Some(SpectralClass::O));
+        assert_eq!(Criterion::Temp.label(&query, &strings), "Temp: any");
+    }
+}
@@ -0,0 +1,695 @@
+//! Player preferences and key / gamepad bindings, kept in `settings.ron` next to the
+//! saves directory. Loaded once at startup; a missing or malformed file means defaults.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use matrix_sim::story::StorySettings;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::budget::BudgetPreset;
+use super::gamepad::GamepadInput;
+
+/// Something the player can do with a key or gamepad button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Boost,
+    Jump,
+    EnterLevel,
+    BackLevel,
+    ZoomIn,
+    ZoomOut,
+    Origin,
+    Densest,
+    NearestParticle,
+    TrackParticle,
+    CycleParticleKind,
+    NextRegion,
+    PrevRegion,
+    FindLife,
+    Finder,
+    Orbits,
+    Inspect,
+    Bestiary,
+    Pause,
+    SurfacePause,
+    TimeScale1,
+    TimeScale2,
+    TimeScale3,
+    TimeScale4,
+    TimeScale5,
+    Slower,
+    Faster,
+    Bookmark,
+    Rewind,
+    SaveSnapshot,
+    ExportLife,
+    LoadSnapshot,
+    PhotoMode,
+    Screenshot,
+    RollLeft,
+    RollRight,
+    Console,
+    DebugOverlay,
+    AutoSpeed,
+    AutoLevel,
+    ParticleLegend,
+    BaryonicOnly,
+    DarkMatterOnly,
+    Journal,
+    Census,
+    HistoryGraphs,
+    HudHelp,
+    CompactHud,
+    ReturnToLanding,
+    StoryMode,
+    StoryResume,
+}
+
+impl Action {
+    pub const ALL: [Action; 57] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Boost,
+        Action::Jump,
+        Action::EnterLevel,
+        Action::BackLevel,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Origin,
+        Action::Densest,
+        Action::NearestParticle,
+        Action::TrackParticle,
+        Action::CycleParticleKind,
+        Action::NextRegion,
+        Action::PrevRegion,
+        Action::FindLife,
+        Action::Finder,
+        Action::Orbits,
+        Action::Inspect,
+        Action::Bestiary,
+        Action::Pause,
+        Action::SurfacePause,
+        Action::TimeScale1,
+        Action::TimeScale2,
+        Action::TimeScale3,
+        Action::TimeScale4,
+        Action::TimeScale5,
+        Action::Slower,
+        Action::Faster,
+        Action::Bookmark,
+        Action::Rewind,
+        Action::SaveSnapshot,
+        Action::ExportLife,
+        Action::LoadSnapshot,
+        Action::PhotoMode,
+        Action::Screenshot,
+        Action::RollLeft,
+        Action::RollRight,
+        Action::Console,
+        Action::DebugOverlay,
+        Action::AutoSpeed,
+        Action::AutoLevel,
+        Action::ParticleLegend,
+        Action::BaryonicOnly,
+        Action::DarkMatterOnly,
+        Action::Journal,
+        Action::Census,
+        Action::HistoryGraphs,
+        Action::HudHelp,
+        Action::CompactHud,
+        Action::ReturnToLanding,
+        Action::StoryMode,
+        Action::StoryResume,
+    ];
+
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::MoveForward => KeyCode::KeyW,
+            Action::MoveBack => KeyCode::KeyS,
+            Action::MoveLeft => KeyCode::KeyA,
+            Action::MoveRight => KeyCode::KeyD,
+            Action::MoveUp => KeyCode::KeyE,
+            Action::MoveDown => KeyCode::KeyQ,
+            Action::Boost => KeyCode::ShiftLeft,
+            Action::Jump => KeyCode::Space,
+            Action::EnterLevel => KeyCode::KeyB,
+            Action::BackLevel => KeyCode::Escape,
+            Action::ZoomIn => KeyCode::Equal,
+            Action::ZoomOut => KeyCode::Minus,
+            Action::Origin => KeyCode::KeyO,
+            Action::Densest => KeyCode::KeyF,
+            Action::NearestParticle => KeyCode::KeyN,
+            Action::TrackParticle => KeyCode::KeyT,
+            Action::CycleParticleKind => KeyCode::Tab,
+            Action::NextRegion => KeyCode::KeyG,
+            Action::PrevRegion => KeyCode::KeyH,
+            Action::FindLife => KeyCode::KeyL,
+            Action::Finder => KeyCode::Slash,
+            Action::Orbits => KeyCode::KeyV,
+            Action::Inspect => KeyCode::KeyE,
+            Action::Bestiary => KeyCode::KeyI,
+            Action::Pause => KeyCode::Space,
+            Action::SurfacePause => KeyCode::KeyP,
+            Action::TimeScale1 => KeyCode::Digit1,
+            Action::TimeScale2 => KeyCode::Digit2,
+            Action::TimeScale3 => KeyCode::Digit3,
+            Action::TimeScale4 => KeyCode::Digit4,
+            Action::TimeScale5 => KeyCode::Digit5,
+            Action::Slower => KeyCode::Comma,
+            Action::Faster => KeyCode::Period,
+            Action::Bookmark => KeyCode::KeyK,
+            Action::Rewind => KeyCode::KeyJ,
+            Action::SaveSnapshot => KeyCode::F5,
+            Action::ExportLife => KeyCode::F6,
+            Action::LoadSnapshot => KeyCode::F9,
+            Action::PhotoMode => KeyCode::F12,
+            Action::Screenshot => KeyCode::Enter,
+            Action::RollLeft => KeyCode::KeyZ,
+            Action::RollRight => KeyCode::KeyC,
+            Action::Console => KeyCode::Backquote,
+            Action::DebugOverlay => KeyCode::F3,
+            Action::AutoSpeed => KeyCode::KeyM,
+            Action::AutoLevel => KeyCode::KeyU,
+            Action::ParticleLegend => KeyCode::KeyR,
+            Action::BaryonicOnly => KeyCode::F7,
+            Action::DarkMatterOnly => KeyCode::F8,
+            Action::Journal => KeyCode::KeyY,
+            Action::Census => KeyCode::F4,
+            Action::HistoryGraphs => KeyCode::F11,
+            Action::HudHelp => KeyCode::F1,
+            Action::CompactHud => KeyCode::F2,
+            Action::ReturnToLanding => KeyCode::Home,
+            Action::StoryMode => KeyCode::F10,
+            Action::StoryResume => KeyCode::KeyX,
+        }
+    }
+
+    /// Gamepad button for the action, if it has one. Movement and look are on the sticks.
+    pub fn default_button(self) -> Option<GamepadButton> {
+        match self {
+            Action::MoveUp => Some(GamepadButton::RightTrigger2),
+            Action::MoveDown => Some(GamepadButton::LeftTrigger2),
+            Action::Boost => Some(GamepadButton::LeftThumb),
+            Action::Jump => Some(GamepadButton::North),
+            Action::EnterLevel => Some(GamepadButton::South),
+            Action::BackLevel => Some(GamepadButton::East),
+            Action::TrackParticle => Some(GamepadButton::West),
+            Action::Pause | Action::SurfacePause => Some(GamepadButton::Start),
+            Action::Slower => Some(GamepadButton::LeftTrigger),
+            Action::Faster => Some(GamepadButton::RightTrigger),
+            Action::NextRegion => Some(GamepadButton::DPadRight),
+            Action::PrevRegion => Some(GamepadButton::DPadLeft),
+            Action::ZoomIn => Some(GamepadButton::DPadUp),
+            Action::ZoomOut => Some(GamepadButton::DPadDown),
+            _ => None,
+        }
+    }
+
+    /// Locale key of the action's name on the settings page
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Action::MoveForward => "action.move_forward",
+            Action::MoveBack => "action.move_back",
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::MoveUp => "action.move_up",
+            Action::MoveDown => "action.move_down",
+            Action::Boost => "action.boost",
+            Action::Jump => "action.jump",
+            Action::EnterLevel => "action.enter_level",
+            Action::BackLevel => "action.back_level",
+            Action::ZoomIn => "action.zoom_in",
+            Action::ZoomOut => "action.zoom_out",
+            Action::Origin => "action.origin",
+            Action::Densest => "action.densest",
+            Action::NearestParticle => "action.nearest_particle",
+            Action::TrackParticle => "action.track_particle",
+            Action::CycleParticleKind => "action.cycle_particle_kind",
+            Action::NextRegion => "action.next_region",
+            Action::PrevRegion => "action.prev_region",
+            Action::FindLife => "action.find_life",
+            Action::Finder => "action.finder",
+            Action::Orbits => "action.orbits",
+            Action::Inspect => "action.inspect",
+            Action::Bestiary => "action.bestiary",
+            Action::Pause => "action.pause",
+            Action::SurfacePause => "action.surface_pause",
+            Action::TimeScale1 => "action.time_scale_1",
+            Action::TimeScale2 => "action.time_scale_2",
+            Action::TimeScale3 => "action.time_scale_3",
+            Action::TimeScale4 => "action.time_scale_4",
+            Action::TimeScale5 => "action.time_scale_5",
+            Action::Slower => "action.slower",
+            Action::Faster => "action.faster",
+            Action::Bookmark => "action.bookmark",
+            Action::Rewind => "action.rewind",
+            Action::SaveSnapshot => "action.save_snapshot",
+            Action::ExportLife => "action.export_life",
+            Action::LoadSnapshot => "action.load_snapshot",
+            Action::PhotoMode => "action.photo_mode",
+            Action::Screenshot => "action.screenshot",
+            Action::RollLeft => "action.roll_left",
+            Action::RollRight => "action.roll_right",
+            Action::Console => "action.console",
+            Action::DebugOverlay => "action.debug_overlay",
+            Action::AutoSpeed => "action.auto_speed",
+            Action::AutoLevel => "action.auto_level",
+            Action::ParticleLegend => "action.particle_legend",
+            Action::BaryonicOnly => "action.baryonic_only",
+            Action::DarkMatterOnly => "action.dark_matter_only",
+            Action::Journal => "action.journal",
+            Action::Census => "action.census",
+            Action::HistoryGraphs => "action.history_graphs",
+            Action::HudHelp => "action.hud_help",
+            Action::CompactHud => "action.compact_hud",
+            Action::ReturnToLanding => "action.return_to_landing",
+            Action::StoryMode => "action.story_mode",
+            Action::StoryResume => "action.story_resume",
+        }
+    }
+}
+
+/// Keys that can be bound, written to the file by their `KeyCode` name ("KeyW", "F5", …)
+const BINDABLE_KEYS: [KeyCode; 88] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Backquote,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+];
+
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+/// File name of a key ("KeyW"), also shown in the settings page
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.into_iter().find(|key| key_name(*key) == name)
+}
+
+/// A bound key, stored by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding(pub KeyCode);
+
+impl Serialize for Binding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&key_name(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Binding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_key(&name)
+            .map(Binding)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown key {name:?}")))
+    }
+}
+
+/// A bound gamepad button, stored by name ("South", "DPadLeft", …)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PadBinding(pub GamepadButton);
+
+impl Serialize for PadBinding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:?}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for PadBinding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        GamepadButton::all()
+            .into_iter()
+            .find(|button| format!("{button:?}") == name)
+            .map(PadBinding)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown gamepad button {name:?}")))
+    }
+}
+
+/// Which stick axes are flipped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StickInversion {
+    pub left_x: bool,
+    pub left_y: bool,
+    pub right_x: bool,
+    pub right_y: bool,
+}
+
+/// Player preferences. Missing fields in the file take their defaults.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub mouse_sensitivity: f32,
+    pub invert_y: bool,
+    /// Fly speed in manual mode (scrolling still changes it in flight)
+    pub fly_speed: f32,
+    /// Zoom level follows the distance to the nearest object (toggled in flight too)
+    pub auto_zoom_level: bool,
+    /// Walking speed on surfaces at the default eye height (m/s)
+    pub walk_speed: f32,
+    /// Vertical field of view (degrees)
+    pub fov_degrees: f32,
+    /// Widen the view and draw streaks at speed (`speed` module); off for motion-sensitive
+    /// players
+    pub speed_effects: bool,
+    pub minimap_enabled: bool,
+    /// Minimap edge (percent of the window height), capped at a third of the width
+    pub minimap_percent: u32,
+    /// HUD font and panel scale; None follows the window height (`HudLayout::fit`)
+    pub ui_scale: Option<f32>,
+    /// Font scale of the HUD text, on top of the UI scale
+    pub hud_text_scale: f32,
+    /// Minutes between autosaves, 0 = off
+    pub autosave_minutes: u32,
+    /// Actions without an entry use `Action::default_key`
+    pub bindings: BTreeMap<Action, Binding>,
+    /// Right stick turn rate at full tilt (rad/s)
+    pub gamepad_look_speed: f32,
+    /// Stick deflection (0..1) ignored around the center
+    pub stick_dead_zone: f32,
+    pub stick_inversion: StickInversion,
+    /// Actions without an entry use `Action::default_button`
+    pub gamepad_bindings: BTreeMap<Action, PadBinding>,
+    /// Sound volumes (0..1); ambient and effects are scaled by the master volume
+    pub master_volume: f32,
+    pub ambient_volume: f32,
+    pub effects_volume: f32,
+    /// Code of the HUD and menu language (`locale::LANGUAGES`)
+    pub language: String,
+    /// Cap on the entities the renderer spawns (`budget::RenderBudget`)
+    pub render_budget: BudgetPreset,
+    /// Which events slow time to 1× and how (`matrix_sim::story`)
+    pub story: StorySettings,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 0.003,
+            invert_y: false,
+            fly_speed: 50.0,
+            auto_zoom_level: false,
+            walk_speed: 10.0,
+            fov_degrees: 45.0,
+            speed_effects: true,
+            minimap_enabled: true,
+            minimap_percent: 26,
+            ui_scale: None,
+            hud_text_scale: 1.0,
+            autosave_minutes: 0,
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, Binding(action.default_key())))
+                .collect(),
+            gamepad_look_speed: 2.5,
+            stick_dead_zone: 0.15,
+            stick_inversion: StickInversion::default(),
+            gamepad_bindings: Action::ALL
+                .into_iter()
+                .filter_map(|action| Some((action, PadBinding(action.default_button()?))))
+                .collect(),
+            master_volume: 0.8,
+            ambient_volume: 0.6,
+            effects_volume: 0.8,
+            language: "en".to_string(),
+            render_budget: BudgetPreset::High,
+            story: StorySettings::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Encode(ron::Error),
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "IO error: {e}"),
+            SettingsError::Parse(e) => write!(f, "malformed settings: {e}"),
+            SettingsError::Encode(e) => write!(f, "encode error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Where the settings live: beside `saves/`
+pub fn settings_path() -> PathBuf {
+    PathBuf::from("settings.ron")
+}
+
+impl UserSettings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.bindings
+            .get(&action)
+            .map_or(action.default_key(), |binding| binding.0)
+    }
+
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.bindings.insert(action, Binding(key));
+    }
+
+    pub fn button(&self, action: Action) -> Option<GamepadButton> {
+        self.gamepad_bindings
+            .get(&action)
+            .map(|binding| binding.0)
+            .or(action.default_button())
+    }
+
+    /// Yaw and pitch change for a mouse movement
+    pub fn look(&self, delta: Vec2) -> Vec2 {
+        let pitch_sign = if self.invert_y { 1.0 } else { -1.0 };
+        Vec2::new(-delta.x, pitch_sign * delta.y) * self.mouse_sensitivity
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, SettingsError> {
+        ron::from_str(text).map_err(SettingsError::Parse)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        let text = std::fs::read_to_string(path).map_err(SettingsError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    /// `load`, falling back to defaults (with a warning unless the file just doesn't exist yet)
+    pub fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(settings) => settings,
+            Err(SettingsError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Self::default()
+            }
+            Err(e) => {
+                warn!("Ignoring {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SettingsError::Encode)?;
+        std::fs::write(path, text).map_err(SettingsError::Io)
+    }
+}
+
+/// Whether the key bound to `action` is held
+pub fn action_pressed(
+    settings: &UserSettings,
+    keyboard: &ButtonInput<KeyCode>,
+    action: Action,
+) -> bool {
+    keyboard.pressed(settings.key(action))
+}
+
+/// Whether the key bound to `action` went down this frame
+pub fn action_just_pressed(
+    settings: &UserSettings,
+    keyboard: &ButtonInput<KeyCode>,
+    action: Action,
+) -> bool {
+    keyboard.just_pressed(settings.key(action))
+}
+
+/// Keyboard and gamepad together: an action fires from whichever device is used
+#[derive(SystemParam)]
+pub struct Controls<'w> {
+    pub settings: Res<'w, UserSettings>,
+    pub gamepad: Res<'w, GamepadInput>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+}
+
+impl Controls<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        action_pressed(&self.settings, &self.keyboard, action) || self.gamepad.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        action_just_pressed(&self.settings, &self.keyboard, action)
+            || self.gamepad.just_pressed(action)
+    }
+
+    /// Ctrl or Alt held: digits pick bookmark slots (`bookmarks::slot_modifier_held`)
+    pub fn slot_modifier_held(&self) -> bool {
+        super::bookmarks::slot_modifier_held(&self.keyboard)
+    }
+
+    /// Yaw and pitch change from the right stick over `dt` seconds
+    pub fn stick_look(&self, dt: f32) -> Vec2 {
+        let look = self.gamepad.look;
+        Vec2::new(-look.x, look.y) * self.settings.gamepad_look_speed * dt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_or_missing_settings_fall_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!("matrix_settings_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.ron");
+
+        // Missing file
+        assert_eq!(
+            UserSettings::load_or_default(&path),
+            UserSettings::default()
+        );
+
+        // Malformed file, unknown key name
+        std::fs::write(&path, "(mouse_sensitivity: \"fast\"").unwrap();
+        assert_eq!(
+            UserSettings::load_or_default(&path),
+            UserSettings::default()
+        );
+        std::fs::write(&path, "(bindings: {MoveForward: \"KeyWW\"})").unwrap();
+        assert_eq!(
+            UserSettings::load_or_default(&path),
+            UserSettings::default()
+        );
+
+        // Partial file: the rest keeps its defaults, unlisted actions their default keys
+        let partial =
+            UserSettings::from_ron("(invert_y: true, bindings: {Jump: \"KeyX\"})").unwrap();
+        assert!(partial.invert_y);
+        assert_eq!(partial.fly_speed, UserSettings::default().fly_speed);
+        assert_eq!(partial.key(Action::Jump), KeyCode::KeyX);
+        assert_eq!(partial.key(Action::MoveForward), KeyCode::KeyW);
+
+        // Round trip
+        let mut settings = UserSettings::default();
+        settings.bind(Action::Bookmark, KeyCode::F10);
+        settings.fov_degrees = 70.0;
+        settings.ui_scale = Some(1.5);
+        settings.save(&path).unwrap();
+        assert_eq!(UserSettings::load_or_default(&path), settings);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
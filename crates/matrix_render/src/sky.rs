@@ -0,0 +1,435 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use matrix_core::{Planet, Star};
+use matrix_sim::lazy_universe::LazyUniverse;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f32::consts::TAU;
+
+use super::camera::FlyCamera;
+use super::surface::{SurfaceDay, SurfaceState};
+
+/// Distance of the star disk and the planet dots from the camera (inside the star dome)
+const SKY_BODY_DISTANCE: f32 = 450.0;
+/// Solar radius and Earth radius (AU)
+const SOLAR_RADIUS_AU: f64 = 0.00465;
+const EARTH_RADIUS_AU: f64 = 4.26e-5;
+/// Surface temperature of the Sun (K), for star radii from luminosity
+const SOLAR_TEMP: f64 = 5772.0;
+/// The star disk is drawn larger than life, as the eye sees it
+const SUN_DISK_SCALE: f32 = 3.0;
+/// Disk and dot radius limits (world units at `SKY_BODY_DISTANCE`)
+const MIN_SUN_RADIUS: f32 = 3.0;
+const MAX_SUN_RADIUS: f32 = 120.0;
+const MIN_DOT_RADIUS: f32 = 0.8;
+const MAX_DOT_RADIUS: f32 = 40.0;
+/// Real seconds between refreshes of the bodies' sky positions from their orbits
+const REFRESH_SECS: f32 = 1.0;
+/// Satellites and settlement glows of a technological planet's night sky
+const SKY_SATELLITES: (usize, usize) = (3, 8);
+const HORIZON_GLOWS: (usize, usize) = (2, 4);
+/// They fade in below this daylight
+const TECH_SKY_DAYLIGHT: f32 = 0.3;
+const SATELLITE_DOT_RADIUS: f32 = 0.7;
+/// Settlement glows sit just inside the star dome, a little above eye level
+const GLOW_DISTANCE: f32 = 420.0;
+const GLOW_HEIGHT: f32 = 4.0;
+const TECH_SKY_SEED: u64 = 0x5A7E_1173;
+
+/// The host star's disk (`body_id: None`) or another body of the system in the surface sky
+#[derive(Component)]
+pub struct SkyBody {
+    pub body_id: Option<u64>,
+    /// Angle from the star along the ecliptic (radians)
+    pub elongation: f32,
+    /// World units at `SKY_BODY_DISTANCE`
+    pub radius: f32,
+}
+
+/// Satellite of a technological planet crossing its night sky
+#[derive(Component)]
+pub struct SkySatellite {
+    /// Great circle it follows: the XY plane (through the zenith) rotated by it
+    pass: Quat,
+    /// Real seconds per turn
+    period: f32,
+    phase: f32,
+}
+
+/// Glow of a distant settlement over the horizon, at night
+#[derive(Component)]
+pub struct HorizonGlow {
+    /// Direction around the horizon (radians)
+    azimuth: f32,
+    /// World units at `GLOW_DISTANCE`
+    width: f32,
+}
+
+/// Where a body of the system appears from a planet's surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyBodyView {
+    pub body_id: u64,
+    /// Angle from the star along the ecliptic (radians, in -π..π)
+    pub elongation: f32,
+    /// Apparent radius (radians)
+    pub angular_radius: f64,
+}
+
+/// Seed of the background starfield: every planet of a star sees the same sky
+pub fn sky_seed(region_seed: u64, star_position: [f64; 3]) -> u64 {
+    star_position.iter().fold(region_seed, |seed, c| {
+        (seed ^ c.to_bits()).wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(29)
+    })
+}
+
+/// Apparent radius of `star` (radians) from `distance_au`, its size from the
+/// Stefan–Boltzmann law (R ∝ √L / T²)
+pub fn star_angular_radius(star: &Star, distance_au: f64) -> f64 {
+    let radius =
+        star.luminosity.max(0.0).sqrt() * (SOLAR_TEMP / star.surface_temp.max(1.0)).powi(2);
+    radius * SOLAR_RADIUS_AU / distance_au.max(1e-6)
+}
+
+/// Direction of a point `elongation` along the ecliptic from the star at `time_of_day`;
+/// at 0 it is the direction of the sun itself
+pub(crate) fn ecliptic_direction(time_of_day: f64, elongation: f32) -> Vec3 {
+    let angle = (std::f64::consts::TAU * (time_of_day - 0.25)) as f32 + elongation;
+    Vec3::new(angle.cos(), angle.sin(), 0.3).normalize()
+}
+
+/// Position around the star (AU, orbital plane) of a planet or moon of `star`
+fn heliocentric(star: &Star, body_id: u64) -> Option<DVec2> {
+    let orbit = |p: &Planet| DVec2::from_angle(p.orbital_angle) * p.orbital_radius;
+    match star.moon_parent(body_id) {
+        Some(parent) => Some(orbit(parent) + orbit(star.body(body_id)?)),
+        None => star.planets.iter().find(|p| p.id == body_id).map(orbit),
+    }
+}
+
+/// The other planets of `star`, and the moons of the planet `planet_id` belongs to
+/// (itself, or its parent when it is a moon), as seen from `planet_id`
+pub fn sky_bodies(star: &Star, planet_id: u64) -> Vec<SkyBodyView> {
+    let Some(here) = heliocentric(star, planet_id) else {
+        return Vec::new();
+    };
+    let host = star.moon_parent(planet_id).map_or(planet_id, |p| p.id);
+    let moons = star
+        .planets
+        .iter()
+        .filter(|p| p.id == host)
+        .flat_map(|p| &p.moons);
+    let to_star = (-here).to_angle();
+    star.planets
+        .iter()
+        .chain(moons)
+        .filter(|b| b.id != planet_id)
+        .filter_map(|body| {
+            let offset = heliocentric(star, body.id)? - here;
+            let distance = offset.length().max(1e-9);
+            let elongation = (offset.to_angle() - to_star + std::f64::consts::PI)
+                .rem_euclid(std::f64::consts::TAU)
+                - std::f64::consts::PI;
+            Some(SkyBodyView {
+                body_id: body.id,
+                elongation: elongation as f32,
+                angular_radius: body.radius * EARTH_RADIUS_AU / distance,
+            })
+        })
+        .collect()
+}
+
+fn sun_radius(star: &Star, planet: &Planet) -> f32 {
+    // A moon is as far from the star as its planet
+    let distance = star
+        .moon_parent(planet.id)
+        .map_or(planet.orbital_radius, |p| p.orbital_radius);
+    (star_angular_radius(star, distance) as f32 * SKY_BODY_DISTANCE * SUN_DISK_SCALE)
+        .clamp(MIN_SUN_RADIUS, MAX_SUN_RADIUS)
+}
+
+fn dot_radius(view: &SkyBodyView) -> f32 {
+    (view.angular_radius as f32 * SKY_BODY_DISTANCE).clamp(MIN_DOT_RADIUS, MAX_DOT_RADIUS)
+}
+
+/// Star disk tinted by spectral class and one dot per visible body of the system
+pub(crate) fn spawn_system_sky(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    star: &Star,
+    planet: &Planet,
+) {
+    let mesh = meshes.add(Sphere::new(1.0).mesh().ico(2).unwrap());
+    let [r, g, b, _] = star.spectral_class.color();
+    let sun_color = Color::srgb(r, g, b);
+    let radius = sun_radius(star, planet);
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: sun_color,
+            emissive: LinearRgba::from(sun_color) * 100.0,
+            unlit: true,
+            ..default()
+        })),
+        Transform::from_scale(Vec3::splat(radius)),
+        SkyBody {
+            body_id: None,
+            elongation: 0.0,
+            radius,
+        },
+    ));
+
+    let views = sky_bodies(star, planet.id);
+    for view in &views {
+        let Some(body) = star.body(view.body_id) else {
+            continue;
+        };
+        let [r, g, b, _] = body.planet_type.color();
+        let color = Color::srgb(r, g, b);
+        let radius = dot_radius(view);
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: color,
+                emissive: LinearRgba::from(color) * 8.0,
+                unlit: true,
+                ..default()
+            })),
+            Transform::from_scale(Vec3::splat(radius)),
+            SkyBody {
+                body_id: Some(view.body_id),
+                elongation: view.elongation,
+                radius,
+            },
+        ));
+    }
+    info!(
+        "Surface: sky shows a {:?} star disk and {} bodies",
+        star.spectral_class,
+        views.len()
+    );
+}
+
+/// Satellites and settlement glows for the night sky of a technological planet, fixed
+/// by its id; hidden until `update_tech_sky` finds it dark enough
+pub(crate) fn spawn_tech_sky(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    planet: &Planet,
+) {
+    if !planet.life.as_ref().is_some_and(|b| b.has_technology) {
+        return;
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(planet.id ^ TECH_SKY_SEED);
+    let mesh = meshes.add(Sphere::new(1.0).mesh().ico(1).unwrap());
+    let satellite_mat = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        emissive: LinearRgba::rgb(20.0, 20.0, 20.0),
+        unlit: true,
+        ..default()
+    });
+    let satellites = rng.gen_range(SKY_SATELLITES.0..=SKY_SATELLITES.1);
+    for _ in 0..satellites {
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(satellite_mat.clone()),
+            Transform::default(),
+            Visibility::Hidden,
+            SkySatellite {
+                pass: Quat::from_rotation_y(rng.gen_range(0.0..TAU))
+                    * Quat::from_rotation_x(rng.gen_range(-0.7..0.7)),
+                period: rng.gen_range(60.0..180.0),
+                phase: rng.gen_range(0.0..TAU),
+            },
+        ));
+    }
+
+    let glow_mat = materials.add(StandardMaterial {
+        base_color: Color::srgba(1.0, 0.6, 0.3, 0.3),
+        emissive: LinearRgba::rgb(2.0, 1.1, 0.5),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        ..default()
+    });
+    let glows = rng.gen_range(HORIZON_GLOWS.0..=HORIZON_GLOWS.1);
+    for _ in 0..glows {
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(glow_mat.clone()),
+            Transform::default(),
+            Visibility::Hidden,
+            HorizonGlow {
+                azimuth: rng.gen_range(0.0..TAU),
+                width: rng.gen_range(30.0..70.0),
+            },
+        ));
+    }
+    info!("Surface: night sky shows {satellites} satellites and {glows} settlement glows");
+}
+
+/// A night sky dot of kind `C` with what the camera-relative placement writes
+type SkyDotParts<'a, C> = (&'a C, &'a mut Transform, &'a mut Visibility);
+
+/// Direction of a satellite on its pass after `secs`
+pub(crate) fn satellite_direction(pass: Quat, period: f32, phase: f32, secs: f32) -> Vec3 {
+    let angle = phase + secs / period * TAU;
+    pass * Vec3::new(angle.cos(), angle.sin(), 0.0)
+}
+
+/// Move the satellites along their passes around the camera and keep the settlement
+/// glows on its horizon, both fading in as night falls
+pub fn update_tech_sky(
+    time: Res<Time>,
+    day: Res<SurfaceDay>,
+    camera_q: Query<&Transform, With<FlyCamera>>,
+    mut satellite_q: Query<SkyDotParts<SkySatellite>, Without<FlyCamera>>,
+    mut glow_q: Query<SkyDotParts<HorizonGlow>, (Without<FlyCamera>, Without<SkySatellite>)>,
+) {
+    let Ok(camera) = camera_q.get_single() else {
+        return;
+    };
+    let night = 1.0 - (day.daylight / TECH_SKY_DAYLIGHT).min(1.0);
+    let secs = time.elapsed_secs();
+    for (satellite, mut tf, mut visibility) in &mut satellite_q {
+        let dir = satellite_direction(satellite.pass, satellite.period, satellite.phase, secs);
+        tf.translation = camera.translation + dir * SKY_BODY_DISTANCE;
+        tf.scale = Vec3::splat(SATELLITE_DOT_RADIUS * night);
+        visibility.set_if_neq(if night > 0.0 && dir.y > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    for (glow, mut tf, mut visibility) in &mut glow_q {
+        let dir = Vec3::new(glow.azimuth.cos(), 0.0, glow.azimuth.sin());
+        tf.translation = camera.translation + dir * GLOW_DISTANCE + Vec3::Y * GLOW_HEIGHT;
+        // A flattened dome of light, wider than tall
+        tf.scale = Vec3::new(glow.width, glow.width * 0.15, glow.width) * night;
+        visibility.set_if_neq(if night > 0.0 { Visibility::Inherited } else { Visibility::Hidden });
+    }
+}
+
+/// A sky body with what the camera-relative placement writes
+type SkyBodyParts<'a> = (&'a mut SkyBody, &'a mut Transform, &'a mut Visibility);
+
+/// Keep the star disk and the system's bodies on the ecliptic around the camera, turning
+/// with the day; their elongations follow the orbits every `REFRESH_SECS`
+pub fn update_sky_bodies(
+    time: Res<Time>,
+    mut elapsed: Local<f32>,
+    state: Res<SurfaceState>,
+    lazy: Res<LazyUniverse>,
+    day: Res<SurfaceDay>,
+    camera_q: Query<&Transform, With<FlyCamera>>,
+    mut body_q: Query<SkyBodyParts, Without<FlyCamera>>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed >= REFRESH_SECS
+        && let Some(ref planet) = state.planet
+        && let Some(star) = lazy.loaded_stars.iter().find(|s| s.body(planet.id).is_some())
+    {
+        *elapsed = 0.0;
+        let views = sky_bodies(star, planet.id);
+        for (mut body, ..) in &mut body_q {
+            if let Some(view) = body.body_id.and_then(|id| views.iter().find(|v| v.body_id == id)) {
+                body.elongation = view.elongation;
+                body.radius = dot_radius(view);
+            }
+        }
+    }
+
+    let Ok(camera) = camera_q.get_single() else {
+        return;
+    };
+    for (body, mut tf, mut visibility) in &mut body_q {
+        let dir = ecliptic_direction(day.time_of_day, body.elongation);
+        tf.translation = camera.translation + dir * SKY_BODY_DISTANCE;
+        tf.scale = Vec3::splat(body.radius);
+        *visibility = if dir.y * SKY_BODY_DISTANCE > -body.radius {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{AtmosphereType, PlanetType, SpectralClass};
+    use std::f32::consts::PI;
+
+    fn planet(id: u64, orbital_radius: f64, orbital_angle: f64) -> Planet {
+        Planet {
+            id,
+            orbital_radius,
+            orbital_period: 1.0,
+            orbital_angle,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 280.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: false,
+            atmosphere: AtmosphereType::None,
+            planet_type: PlanetType::Rocky,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        }
+    }
+
+    #[test]
+    fn test_sky_bodies_follow_the_orbits() {
+        let mut home = planet(1, 1.0, 0.0);
+        home.moons.push(planet(101, 0.002, std::f64::consts::FRAC_PI_2));
+        let star = Star {
+            id: 7,
+            position: [1.0, 2.0, 3.0],
+            velocity: [0.0; 3],
+            mass: 1.0,
+            luminosity: 1.0,
+            surface_temp: SOLAR_TEMP,
+            spectral_class: SpectralClass::G,
+            age: 4.6,
+            remnant: None,
+            // Behind the star and opposite it, seen from `home`
+            planets: vec![home, planet(2, 2.0, std::f64::consts::PI), planet(3, 2.0, 0.0)],
+            asteroid_belt: None,
+            comets: Vec::new(),
+        };
+        let elongation = |views: &[SkyBodyView], id| {
+            views.iter().find(|v| v.body_id == id).unwrap().elongation.abs()
+        };
+
+        let from_home = sky_bodies(&star, 1);
+        assert_eq!(from_home.len(), 3);
+        assert!(elongation(&from_home, 2) < 1e-4);
+        assert!((elongation(&from_home, 3) - PI).abs() < 1e-4);
+        assert!((elongation(&from_home, 101) - PI / 2.0).abs() < 1e-2);
+
+        // From the moon, its planet looms large and the others are still there
+        let from_moon = sky_bodies(&star, 101);
+        assert_eq!(from_moon.len(), 3);
+        let parent = from_moon.iter().find(|v| v.body_id == 1).unwrap();
+        let neighbour = from_home.iter().find(|v| v.body_id == 3).unwrap();
+        assert!(parent.angular_radius > neighbour.angular_radius * 100.0);
+
+        // The Sun from Earth: about a quarter of a degree
+        let sun = star_angular_radius(&star, 1.0);
+        assert!((sun.to_degrees() - 0.266).abs() < 0.01);
+        let noon = ecliptic_direction(0.5, 0.0);
+        assert!(noon.distance(ecliptic_direction(0.25, PI / 2.0)) < 1e-5);
+
+        // Satellite passes go over the zenith and back under the horizon
+        let pass = Quat::from_rotation_y(1.0);
+        let overhead = satellite_direction(pass, 100.0, 0.0, 25.0);
+        assert!((overhead.y - 1.0).abs() < 1e-5);
+        assert!(satellite_direction(pass, 100.0, 0.0, 75.0).y < -0.99);
+
+        assert_eq!(sky_seed(5, star.position), sky_seed(5, star.position));
+        assert_ne!(sky_seed(5, star.position), sky_seed(5, [1.0, 2.0, 3.5]));
+        assert_ne!(sky_seed(5, star.position), sky_seed(6, star.position));
+    }
+}
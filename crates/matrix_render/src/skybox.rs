@@ -0,0 +1,381 @@
+//! Space-mode background: an inward-facing sphere around the camera textured with a
+//! seeded starfield and nebulae, and a few hundred faint galaxies far outside the region
+//! grid. The texture is built on the async pool once per universe seed.
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::view::{NoFrustumCulling, RenderLayers};
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use matrix_core::PlanetType;
+use matrix_physics::terrain::{Fbm, TerrainSampler};
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::universe::UniverseState;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::camera::FlyCamera;
+use super::cosmos::soft_disc_mesh;
+use super::surface::SurfaceState;
+
+/// Side of the sky texture (pixels, equirectangular)
+pub const SKY_TEXTURE_SIZE: u32 = 1024;
+/// Radius of the sky sphere around the camera: beyond every galaxy billboard
+pub const SKY_RADIUS: f32 = 9000.0;
+/// Background galaxies, and their distance range from the grid center (Mpc) — well
+/// outside the default ±400 Mpc region grid, so flying around barely moves them
+const GALAXY_COUNT: usize = 300;
+const GALAXY_DISTANCE: (f32, f32) = (2500.0, 5000.0);
+/// Long axis of a background galaxy (Mpc)
+const GALAXY_SIZE: (f32, f32) = (15.0, 60.0);
+/// Galaxy tints: old ellipticals, blue spirals, starbursts
+const GALAXY_TINTS: [[f32; 3]; 3] = [[0.5, 0.42, 0.3], [0.32, 0.38, 0.55], [0.5, 0.33, 0.42]];
+/// Pixels per point star
+const PIXELS_PER_STAR: u32 = 150;
+/// Nebula colors; each universe mixes two of them
+const NEBULA_COLORS: [[f32; 3]; 5] = [
+    [0.75, 0.25, 0.55],
+    [0.25, 0.4, 0.9],
+    [0.2, 0.65, 0.65],
+    [0.9, 0.45, 0.25],
+    [0.55, 0.3, 0.85],
+];
+/// Brightest nebula glow (0..1 of full white)
+const NEBULA_STRENGTH: f32 = 0.3;
+/// Dimmest the sky gets deep inside a dense region
+const DENSE_REGION_DIM: f32 = 0.65;
+/// Rate the sky eases toward its target brightness (per second)
+const DIM_RATE: f32 = 1.5;
+
+/// The sky sphere (follows the camera)
+#[derive(Component)]
+pub struct SkySphere;
+
+/// A distant background galaxy billboard
+#[derive(Component)]
+pub struct BackgroundGalaxy;
+
+/// Which universe the sky was drawn for, and the texture being drawn for the next one
+#[derive(Resource, Default)]
+pub struct Skybox {
+    /// Seed of the shown (or generating) sky
+    seed: Option<u64>,
+    task: Option<Task<Image>>,
+    material: Handle<StandardMaterial>,
+    galaxy_mesh: Handle<Mesh>,
+    galaxy_materials: Vec<Handle<StandardMaterial>>,
+    /// Current brightness, easing toward `sky_brightness` of the region the camera is in
+    brightness: f32,
+}
+
+/// How bright the sky is inside a region of `density` (relative to average): full in
+/// average and sparse regions, dimmed a little by the glow of a dense one
+pub fn sky_brightness(density: f64) -> f32 {
+    let t = (density.max(1e-6).ln() / 1.5).clamp(0.0, 1.0) as f32;
+    1.0 - (1.0 - DENSE_REGION_DIM) * t * t * (3.0 - 2.0 * t)
+}
+
+/// RGBA8 equirectangular starfield of `size`² pixels for a universe `seed`: nebulae from
+/// the terrain value noise, wrapped so the seam at longitude 0 doesn't show, then point
+/// stars spread evenly over the sphere
+pub fn sky_texture_data(seed: u64, size: u32) -> Vec<u8> {
+    let sampler = TerrainSampler::new(seed, PlanetType::Rocky);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0x51c4_b0c5_d1e7_a3f9);
+    let w = size as f32;
+    // Blend each sample with the one a texture width to the left: equal at both edges
+    let wrapped = |x: f32, y: f32, fbm: &Fbm| {
+        let t = x / w;
+        sampler.fbm(x, y, fbm) * (1.0 - t) + sampler.fbm(x - w, y, fbm) * t
+    };
+    let shape = Fbm {
+        octaves: 5,
+        frequency: 5.0 / w,
+        lacunarity: 2.0,
+        gain: 0.5,
+    };
+    let hue = Fbm {
+        octaves: 2,
+        frequency: 3.0 / w,
+        lacunarity: 2.0,
+        gain: 0.5,
+    };
+    let first = rng.gen_range(0..NEBULA_COLORS.len());
+    let second = (first + rng.gen_range(1..NEBULA_COLORS.len())) % NEBULA_COLORS.len();
+    let (a, b) = (NEBULA_COLORS[first], NEBULA_COLORS[second]);
+
+    let mut pixels = vec![[0.0f32; 3]; (size * size) as usize];
+    for y in 0..size {
+        for x in 0..size {
+            let (fx, fy) = (x as f32, y as f32);
+            let n = wrapped(fx, fy, &shape);
+            let glow = ((n - 0.05) / 0.5).clamp(0.0, 1.0);
+            let glow = glow * glow * NEBULA_STRENGTH;
+            let mix = (wrapped(fx + 97.0, fy - 41.0, &hue) * 1.5 + 0.5).clamp(0.0, 1.0);
+            pixels[(y * size + x) as usize] =
+                std::array::from_fn(|k| (a[k] + (b[k] - a[k]) * mix) * glow);
+        }
+    }
+
+    let mut add = |x: i64, y: i64, color: [f32; 3]| {
+        if !(0..size as i64).contains(&y) {
+            return;
+        }
+        let p = &mut pixels[(y * size as i64 + x.rem_euclid(size as i64)) as usize];
+        (0..3).for_each(|k| p[k] += color[k]);
+    };
+    for _ in 0..size * size / PIXELS_PER_STAR {
+        let x = rng.gen_range(0..size) as i64;
+        // Even over the sphere, not over the texture's rows
+        let latitude = (1.0 - 2.0 * rng.gen_range(0.0f32..1.0)).acos() / std::f32::consts::PI;
+        let y = ((latitude * w) as i64).min(size as i64 - 1);
+        let brightness = 0.2 + 0.8 * rng.gen_range(0.0f32..1.0).powi(6);
+        let tint = match rng.gen_range(0..4) {
+            0 => [0.75, 0.85, 1.0],
+            1 => [1.0, 0.9, 0.7],
+            2 => [1.0, 0.75, 0.55],
+            _ => [1.0, 1.0, 1.0],
+        };
+        let color = tint.map(|c| c * brightness);
+        add(x, y, color);
+        if brightness > 0.6 {
+            let halo = color.map(|c| c * 0.35);
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                add(x + dx, y + dy, halo);
+            }
+        }
+    }
+
+    pixels
+        .iter()
+        .flat_map(|p| {
+            let [r, g, b] = p.map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8);
+            [r, g, b, 255]
+        })
+        .collect()
+}
+
+fn sky_image(seed: u64, size: u32) -> Image {
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        sky_texture_data(seed, size),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Where the background galaxies of universe `seed` go: (position, size, roll, tint index).
+/// Each faces the grid center.
+pub fn galaxy_placements(seed: u64) -> Vec<(Vec3, Vec2, f32, usize)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0x6a1a_c7e5_0f0f_2b11);
+    (0..GALAXY_COUNT)
+        .map(|_| {
+            let z = rng.gen_range(-1.0f32..1.0);
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let r = (1.0 - z * z).sqrt();
+            let dir = Vec3::new(r * angle.cos(), z, r * angle.sin());
+            let distance = rng.gen_range(GALAXY_DISTANCE.0..GALAXY_DISTANCE.1);
+            let length = rng.gen_range(GALAXY_SIZE.0..GALAXY_SIZE.1);
+            // Face-on discs to edge-on streaks
+            let size = Vec2::new(length, length * rng.gen_range(0.2f32..1.0));
+            let roll = rng.gen_range(0.0..std::f32::consts::TAU);
+            (dir * distance, size, roll, rng.gen_range(0..GALAXY_TINTS.len()))
+        })
+        .collect()
+}
+
+/// Tint of the sky texture at `brightness`
+fn sky_color(brightness: f32) -> Color {
+    Color::linear_rgb(brightness, brightness, brightness)
+}
+
+fn galaxy_color(tint: [f32; 3], brightness: f32) -> Color {
+    let [r, g, b] = tint.map(|c| c * brightness);
+    Color::srgb(r, g, b)
+}
+
+/// Spawn the (black until its texture is ready) sky sphere, main view only
+pub fn spawn_skybox(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::BLACK,
+        unlit: true,
+        cull_mode: None,
+        fog_enabled: false,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(SKY_RADIUS).mesh().uv(64, 32))),
+        MeshMaterial3d(material.clone()),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        // Never drawn by the minimap camera (layer 1)
+        RenderLayers::layer(0),
+        SkySphere,
+    ));
+    let galaxy_materials = GALAXY_TINTS
+        .iter()
+        .map(|&tint| {
+            materials.add(StandardMaterial {
+                base_color: galaxy_color(tint, 1.0),
+                alpha_mode: AlphaMode::Add,
+                unlit: true,
+                cull_mode: None,
+                fog_enabled: false,
+                ..default()
+            })
+        })
+        .collect();
+    commands.insert_resource(Skybox {
+        seed: None,
+        task: None,
+        material,
+        galaxy_mesh: meshes.add(soft_disc_mesh()),
+        galaxy_materials,
+        brightness: 1.0,
+    });
+}
+
+/// Start drawing a new sky when the universe's seed changes (a new universe or a loaded
+/// save), put it up when it's done, and scatter that universe's background galaxies
+pub fn skybox_generation_system(
+    mut commands: Commands,
+    universe: Res<UniverseState>,
+    mut skybox: ResMut<Skybox>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    galaxy_q: Query<Entity, With<BackgroundGalaxy>>,
+) {
+    let seed = universe.config.seed;
+    if skybox.seed != Some(seed) {
+        skybox.seed = Some(seed);
+        // Replacing an unfinished task drops (cancels) it
+        skybox.task = Some(
+            AsyncComputeTaskPool::get().spawn(async move { sky_image(seed, SKY_TEXTURE_SIZE) }),
+        );
+        for entity in &galaxy_q {
+            commands.entity(entity).despawn();
+        }
+        for (position, size, roll, tint) in galaxy_placements(seed) {
+            commands.spawn((
+                Mesh3d(skybox.galaxy_mesh.clone()),
+                MeshMaterial3d(skybox.galaxy_materials[tint].clone()),
+                Transform::from_translation(position)
+                    .looking_at(Vec3::ZERO, Vec3::Y)
+                    .mul_transform(Transform::from_rotation(Quat::from_rotation_z(roll)))
+                    .with_scale(size.extend(1.0)),
+                RenderLayers::layer(0),
+                BackgroundGalaxy,
+            ));
+        }
+    }
+
+    let Some(task) = skybox.task.as_mut() else {
+        return;
+    };
+    let Some(image) = block_on(poll_once(task)) else {
+        return;
+    };
+    skybox.task = None;
+    if let Some(material) = materials.get_mut(&skybox.material) {
+        material.base_color_texture = Some(images.add(image));
+        material.base_color = sky_color(skybox.brightness);
+    }
+    info!("Skybox: starfield drawn for seed {}", seed);
+}
+
+/// Keep the sky centered on the camera, hide it on planet surfaces (they have their own
+/// sky) and dim it a little inside dense regions
+pub fn update_skybox(
+    time: Res<Time>,
+    (lazy, state): (Res<LazyUniverse>, Res<SurfaceState>),
+    (mut skybox, mut materials): (ResMut<Skybox>, ResMut<Assets<StandardMaterial>>),
+    camera_q: Query<&Transform, (With<FlyCamera>, Without<SkySphere>)>,
+    mut sphere_q: Query<(&mut Transform, &mut Visibility), With<SkySphere>>,
+    mut galaxy_q: Query<&mut Visibility, (With<BackgroundGalaxy>, Without<SkySphere>)>,
+) {
+    let visibility = if state.active {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut galaxy in &mut galaxy_q {
+        galaxy.set_if_neq(visibility);
+    }
+    for (mut tf, mut sphere) in &mut sphere_q {
+        sphere.set_if_neq(visibility);
+        if let Ok(camera) = camera_q.get_single() {
+            tf.translation = camera.translation;
+        }
+    }
+    if state.active {
+        return;
+    }
+
+    let target = lazy
+        .current_region_id
+        .and_then(|id| lazy.regions.iter().find(|r| r.id == id))
+        .map_or(1.0, |r| sky_brightness(r.density));
+    if (skybox.brightness - target).abs() < 0.005 {
+        return;
+    }
+    let ease = (DIM_RATE * time.delta_secs()).min(1.0);
+    let next = skybox.brightness + (target - skybox.brightness) * ease;
+    skybox.brightness = if (target - next).abs() < 0.005 { target } else { next };
+    let brightness = skybox.brightness;
+    if let Some(material) = materials.get_mut(&skybox.material)
+        && material.base_color_texture.is_some()
+    {
+        material.base_color = sky_color(brightness);
+    }
+    for (handle, &tint) in skybox.galaxy_materials.iter().zip(&GALAXY_TINTS) {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base_color = galaxy_color(tint, brightness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    #[test]
+    fn test_sky_texture_follows_the_seed() {
+        let size = 64;
+        let sky = sky_texture_data(7, size);
+        assert_eq!(sky.len(), (size * size * 4) as usize);
+        assert_eq!(sky, sky_texture_data(7, size));
+        assert_ne!(sky, sky_texture_data(8, size));
+        // Opaque, mostly dark, with stars and some nebula glow
+        assert!(sky.chunks(4).all(|p| p[3] == 255));
+        let lit = sky.chunks(4).filter(|p| p[..3].iter().any(|&c| c > 0)).count();
+        assert!(lit > sky.len() / 4 / 50);
+        assert!(lit < sky.len() / 4);
+
+        // Galaxies sit far outside the region grid, the same ones for the same seed
+        let lazy = LazyUniverse::new(SimConfig::default(), 13.0);
+        let grid = lazy
+            .regions
+            .iter()
+            .flat_map(|r| r.center.map(|c| c.abs() + r.size / 2.0))
+            .fold(0.0, f64::max) as f32;
+        let galaxies = galaxy_placements(7);
+        assert_eq!(galaxies.len(), GALAXY_COUNT);
+        assert!(galaxies.iter().all(|(p, ..)| p.length() > grid * 4.0 && p.length() < SKY_RADIUS));
+        assert_eq!(galaxies, galaxy_placements(7));
+
+        // Average regions leave the sky alone, dense ones dim it a little
+        assert_eq!(sky_brightness(1.0), 1.0);
+        assert_eq!(sky_brightness(0.2), 1.0);
+        assert!(sky_brightness(2.0) < 1.0 && sky_brightness(2.0) > sky_brightness(10.0));
+        assert!((sky_brightness(100.0) - DENSE_REGION_DIM).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,331 @@
+use bevy::prelude::*;
+use matrix_core::{AsteroidBelt, Comet, Star};
+use matrix_sim::lazy_universe::LazyUniverse;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::camera::{FlyCamera, ZoomLevel};
+use super::cosmos::{AU_RENDER_SCALE, SMALL_BODY_STARS, star_render_pos};
+use super::particles;
+use super::surface::SystemFocus;
+
+/// Point cloud of a star's asteroid belt
+#[derive(Component)]
+pub struct AsteroidBeltVisual {
+    pub star_id: u64,
+}
+
+/// Nucleus of a comet; its tail is a child (`CometTail`)
+#[derive(Component)]
+pub struct CometVisual {
+    pub star_id: u64,
+    /// Index of the star in `LazyUniverse::loaded_stars` and of the comet in its `comets`
+    /// (valid until the next rebuild)
+    pub star_index: usize,
+    pub comet_index: usize,
+}
+
+/// Tail of a comet, pointing away from its star
+#[derive(Component)]
+pub struct CometTail;
+
+/// Stars the belts and comets are spawned for (the focused one first, then the nearest)
+#[derive(Resource, Default)]
+pub struct SmallBodyState {
+    stars: Vec<u64>,
+    /// `stars_generation` they were spawned for
+    stars_generation: u32,
+}
+
+/// Materials and meshes shared by every belt and comet. Belt clouds are the only
+/// per-rebuild meshes; they are removed with their entities.
+#[derive(Resource)]
+pub struct SmallBodyAssets {
+    pub belt_mat: Handle<StandardMaterial>,
+    pub nucleus_mesh: Handle<Mesh>,
+    pub nucleus_mat: Handle<StandardMaterial>,
+    /// Unit-height cone, tip up; scaled to the tail length
+    pub tail_mesh: Handle<Mesh>,
+    pub tail_mat: Handle<StandardMaterial>,
+}
+
+/// Comets grow a tail inside this distance from their star (AU)
+const TAIL_AU: f64 = 5.0;
+/// Tail length at the star itself (render units)
+const TAIL_LENGTH: f32 = 3.0;
+/// Belt points relative to galaxy-cloud points at the same distance
+const BELT_POINT_SCALE: f32 = 0.2;
+/// Vertical scatter of belt points relative to their orbit radius
+const BELT_THICKNESS: f32 = 0.03;
+/// Segments of a drawn comet orbit
+const COMET_PATH_SEGMENTS: u32 = 96;
+
+pub fn init_small_bodies(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Cloud triangles face +Z: draw both sides
+    let belt_mat = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.6, 0.56, 0.5),
+        emissive: LinearRgba::rgb(0.6, 0.56, 0.5),
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    });
+    let nucleus_mat = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.85, 0.95, 1.0),
+        emissive: LinearRgba::rgb(3.0, 3.5, 4.0),
+        unlit: true,
+        ..default()
+    });
+    let tail_mat = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.6, 0.8, 1.0, 0.35),
+        emissive: LinearRgba::rgb(1.5, 2.0, 2.5),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    });
+    commands.insert_resource(SmallBodyAssets {
+        belt_mat,
+        nucleus_mesh: meshes.add(Sphere::new(0.08).mesh().ico(1).unwrap()),
+        nucleus_mat,
+        tail_mesh: meshes.add(Cone {
+            radius: 0.2,
+            height: 1.0,
+        }),
+        tail_mat,
+    });
+    commands.insert_resource(SmallBodyState::default());
+}
+
+/// Belt points around the star in render units, in the local XY plane (the entity is
+/// rotated into the orbital plane so the cloud triangles face up). Uniform over the
+/// annulus's area, the same for every rebuild.
+pub(crate) fn belt_points(belt: &AsteroidBelt) -> Vec<[f32; 3]> {
+    let mut rng = ChaCha8Rng::seed_from_u64(belt.seed);
+    let inner = (belt.inner_radius * AU_RENDER_SCALE) as f32;
+    let outer = (belt.outer_radius * AU_RENDER_SCALE) as f32;
+    (0..belt.particle_budget)
+        .map(|_| {
+            let r = rng.gen_range(inner * inner..=outer * outer).sqrt();
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let height = rng.gen_range(-1.0..=1.0) * r * BELT_THICKNESS;
+            [r * angle.cos(), r * angle.sin(), height]
+        })
+        .collect()
+}
+
+/// Offset of a comet from its star in render units (orbits lie in the XZ plane)
+pub(crate) fn comet_offset(comet: &Comet) -> Vec3 {
+    let [x, z] = comet.position().map(|v| (v * AU_RENDER_SCALE) as f32);
+    Vec3::new(x, 0.0, z)
+}
+
+/// Tail length (render units) at the comet's current distance: none beyond `TAIL_AU`,
+/// longest at the star
+pub(crate) fn tail_length(comet: &Comet) -> f32 {
+    let [x, z] = comet.position();
+    let distance = x.hypot(z);
+    (TAIL_LENGTH as f64 * (1.0 - distance / TAIL_AU)).max(0.0) as f32
+}
+
+/// Stars whose small bodies are drawn: the focused one, then the nearest to `pos`
+fn small_body_stars(stars: &[Star], focus: Option<u64>, pos: Vec3) -> Vec<usize> {
+    let mut by_dist: Vec<(f32, usize)> = stars
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let dist = if focus == Some(s.id) { -1.0 } else { pos.distance_squared(star_render_pos(s)) };
+            (dist, i)
+        })
+        .collect();
+    by_dist.sort_by(|a, b| a.0.total_cmp(&b.0));
+    by_dist.into_iter().take(SMALL_BODY_STARS).map(|(_, i)| i).collect()
+}
+
+/// Spawn asteroid belt clouds and comets for the nearest stars at Stellar/Planetary zoom.
+/// Respawned when the star set or `stars_generation` changes.
+pub fn spawn_small_bodies(
+    mut commands: Commands,
+    (lazy, focus, assets): (Res<LazyUniverse>, Res<SystemFocus>, Res<SmallBodyAssets>),
+    (mut state, mut meshes): (ResMut<SmallBodyState>, ResMut<Assets<Mesh>>),
+    belt_q: Query<(Entity, &Mesh3d), With<AsteroidBeltVisual>>,
+    comet_q: Query<Entity, With<CometVisual>>,
+    camera_query: Query<(&Transform, &FlyCamera)>,
+) {
+    let Ok((cam_transform, cam)) = camera_query.get_single() else {
+        return;
+    };
+    let shown = matches!(cam.zoom_level, ZoomLevel::Stellar | ZoomLevel::Planetary);
+    let indices = if shown {
+        small_body_stars(&lazy.loaded_stars, focus.star_id, cam_transform.translation)
+    } else {
+        Vec::new()
+    };
+    let star_ids: Vec<u64> = indices.iter().map(|&i| lazy.loaded_stars[i].id).collect();
+    if star_ids == state.stars && lazy.stars_generation == state.stars_generation {
+        return;
+    }
+    state.stars = star_ids;
+    state.stars_generation = lazy.stars_generation;
+
+    for (entity, mesh) in belt_q.iter() {
+        meshes.remove(&mesh.0);
+        commands.entity(entity).despawn();
+    }
+    for entity in comet_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for &star_index in &indices {
+        let star = &lazy.loaded_stars[star_index];
+        let star_pos = star_render_pos(star);
+        if let Some(belt) = &star.asteroid_belt {
+            let tri_size = particles::compute_tri_size(cam_transform.translation, star_pos) * BELT_POINT_SCALE;
+            commands.spawn((
+                Mesh3d(meshes.add(particles::build_triangle_cloud(&belt_points(belt), tri_size))),
+                MeshMaterial3d(assets.belt_mat.clone()),
+                Transform::from_translation(star_pos)
+                    .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+                AsteroidBeltVisual { star_id: star.id },
+            ));
+        }
+        for (comet_index, comet) in star.comets.iter().enumerate() {
+            commands
+                .spawn((
+                    Mesh3d(assets.nucleus_mesh.clone()),
+                    MeshMaterial3d(assets.nucleus_mat.clone()),
+                    Transform::from_translation(star_pos + comet_offset(comet)),
+                    CometVisual {
+                        star_id: star.id,
+                        star_index,
+                        comet_index,
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Mesh3d(assets.tail_mesh.clone()),
+                        MeshMaterial3d(assets.tail_mat.clone()),
+                        Transform::default(),
+                        Visibility::Hidden,
+                        CometTail,
+                    ));
+                });
+        }
+    }
+}
+
+/// Comet tails, kept apart from the comet nuclei they hang off
+type CometTails = (With<CometTail>, Without<CometVisual>);
+
+/// Move comets along their orbits, draw their paths and point their tails away from the
+/// star while they are close to it
+pub fn update_comets(
+    lazy: Res<LazyUniverse>,
+    focus: Res<SystemFocus>,
+    mut gizmos: Gizmos,
+    mut comets: Query<(&CometVisual, &mut Transform, &Children)>,
+    mut tails: Query<(&mut Transform, &mut Visibility), CometTails>,
+) {
+    let path_color = Color::srgba(0.6, 0.8, 1.0, 0.12);
+    let focus_path_color = Color::srgba(0.6, 0.8, 1.0, 0.45);
+    for (visual, mut transform, children) in comets.iter_mut() {
+        // Indices are from the last rebuild; skip if the stars were swapped since
+        let Some(star) = lazy
+            .loaded_stars
+            .get(visual.star_index)
+            .filter(|s| s.id == visual.star_id)
+        else {
+            continue;
+        };
+        let Some(comet) = star.comets.get(visual.comet_index) else {
+            continue;
+        };
+        let star_pos = star_render_pos(star);
+        let offset = comet_offset(comet);
+        transform.translation = star_pos + offset;
+
+        // The star sits in a focus: the ellipse's center is a·e back from it
+        let a = (comet.semi_major_axis * AU_RENDER_SCALE) as f32;
+        let e = comet.eccentricity as f32;
+        let angle = comet.perihelion_angle as f32;
+        let perihelion_dir = Vec3::new(angle.cos(), 0.0, angle.sin());
+        let color = if focus.star_id == Some(star.id) { focus_path_color } else { path_color };
+        gizmos
+            .ellipse(
+                Isometry3d::new(
+                    star_pos - perihelion_dir * a * e,
+                    Quat::from_rotation_y(-angle) * Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+                ),
+                Vec2::new(a, a * (1.0 - e * e).sqrt()),
+                color,
+            )
+            .resolution(COMET_PATH_SEGMENTS);
+
+        let length = tail_length(comet);
+        let away = offset.normalize_or_zero();
+        for &child in children {
+            let Ok((mut tail, mut visibility)) = tails.get_mut(child) else {
+                continue;
+            };
+            if length <= 0.0 || away == Vec3::ZERO {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            *visibility = Visibility::Inherited;
+            // The cone's tip (+Y) sits on the nucleus, its base trails away from the star
+            tail.translation = away * length / 2.0;
+            tail.rotation = Quat::from_rotation_arc(Vec3::Y, -away);
+            tail.scale = Vec3::new(1.0, length, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comet_tail_only_near_the_star() {
+        let comet = Comet {
+            id: 1,
+            semi_major_axis: 20.0,
+            eccentricity: 0.9,
+            orbital_period: 20f64.powf(1.5),
+            orbital_angle: 0.0,
+            perihelion_angle: 1.0,
+        };
+        // Perihelion (2 AU): along the perihelion direction, with a tail
+        let at_perihelion = comet_offset(&comet);
+        let expected = (comet.perihelion() * AU_RENDER_SCALE) as f32;
+        assert!((at_perihelion.length() - expected).abs() < 1e-3);
+        assert!((at_perihelion.normalize().dot(Vec3::new(1f32.cos(), 0.0, 1f32.sin())) - 1.0).abs() < 1e-4);
+        assert!(tail_length(&comet) > TAIL_LENGTH * 0.5);
+
+        // Aphelion (38 AU): no tail
+        let far = Comet {
+            orbital_angle: std::f64::consts::PI,
+            ..comet
+        };
+        assert!(comet_offset(&far).length() > 70.0);
+        assert_eq!(tail_length(&far), 0.0);
+
+        let belt = AsteroidBelt {
+            inner_radius: 2.0,
+            outer_radius: 3.0,
+            particle_budget: 500,
+            seed: 3,
+        };
+        let points = belt_points(&belt);
+        assert_eq!(points.len(), 500);
+        assert_eq!(points, belt_points(&belt));
+        for p in points {
+            let r = p[0].hypot(p[1]);
+            assert!((3.99..=6.01).contains(&r), "{r}");
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Speed feedback in flight. Distances are in Mpc and a real second of flight counts as one
+//! time unit (Gyr), so the camera's velocity in units/s is a speed in Mpc/Gyr and can be
+//! held against light speed (`matrix_core::C`, 3000 Mpc/Gyr). The HUD shows it as a
+//! fraction of c, and c is a hard limit: `fly_camera_system` eases the input velocity
+//! into it (`cap_to_light`), and asking for more shows a warp effect instead. The field of
+//! view widens with speed (up to `FOV_BOOST_MAX_DEG` at c) and streaks run out to the
+//! screen edges; `UserSettings::speed_effects` turns both off.
+
+use bevy::prelude::*;
+use matrix_core::C;
+
+use super::camera::FlyCamera;
+use super::settings::UserSettings;
+use super::surface::SurfaceState;
+
+/// Widest the view gets on top of the set field of view, at light speed (degrees)
+pub const FOV_BOOST_MAX_DEG: f32 = 15.0;
+/// Rate (1/s) at which the field of view follows the speed
+const FOV_EASE_RATE: f32 = 3.0;
+/// Fraction of c from which the speed is eased into the cap instead of passed through
+const SOFT_CAP_START: f32 = 0.8;
+/// Fraction of c from which the edge streaks show
+const STREAK_THRESHOLD: f32 = 0.1;
+/// Streaks along each screen edge
+const STREAKS_PER_EDGE: usize = 6;
+/// Part of the screen (percent, from the edge) the streaks run through
+const STREAK_BAND: f32 = 18.0;
+/// Longest streak, at light speed (px)
+const STREAK_LENGTH: f32 = 90.0;
+/// Passes of a streak through its band per second at light speed, and in warp
+const STREAK_RATE: f32 = 1.5;
+const WARP_STREAK_RATE: f32 = 3.0;
+
+/// Speed as a fraction of light speed
+pub fn light_fraction(velocity: Vec3) -> f32 {
+    velocity.length() / C
+}
+
+/// Soft cap at light speed: velocities up to `SOFT_CAP_START` c pass unchanged, faster
+/// ones approach c without reaching it (continuous, and just as steep where the easing
+/// starts). The direction is kept.
+pub fn cap_to_light(velocity: Vec3) -> Vec3 {
+    let speed = velocity.length();
+    let start = SOFT_CAP_START * C;
+    if speed <= start {
+        return velocity;
+    }
+    let room = C - start;
+    let capped = C - room * (-(speed - start) / room).exp();
+    velocity * (capped / speed)
+}
+
+/// Field of view added at `fraction` of light speed (degrees): smooth from rest, the full
+/// `FOV_BOOST_MAX_DEG` from c up
+pub fn fov_boost_degrees(fraction: f32) -> f32 {
+    let t = fraction.clamp(0.0, 1.0);
+    FOV_BOOST_MAX_DEG * t * t * (3.0 - 2.0 * t)
+}
+
+/// How strongly the edge streaks show (0–1): from `STREAK_THRESHOLD` of c to c
+pub fn streak_intensity(fraction: f32) -> f32 {
+    ((fraction - STREAK_THRESHOLD) / (1.0 - STREAK_THRESHOLD)).clamp(0.0, 1.0)
+}
+
+/// Widen the field of view with the flight speed, easing back to the set one when slowing
+/// down, landing or with the speed effects off
+pub fn speed_fov_system(
+    time: Res<Time>,
+    settings: Res<UserSettings>,
+    surface: Res<SurfaceState>,
+    mut boost: Local<f32>,
+    mut cam_q: Query<(&FlyCamera, &mut Projection)>,
+) {
+    let Ok((cam, mut projection)) = cam_q.get_single_mut() else {
+        return;
+    };
+    let target = if settings.speed_effects && !surface.active {
+        fov_boost_degrees(light_fraction(cam.velocity))
+    } else {
+        0.0
+    };
+    *boost += (target - *boost) * (1.0 - (-FOV_EASE_RATE * time.delta_secs()).exp());
+    let fov = (settings.fov_degrees + *boost).to_radians();
+    // Only mark the projection changed when it moves
+    let Projection::Perspective(perspective) = projection.bypass_change_detection() else {
+        return;
+    };
+    if (perspective.fov - fov).abs() > 1e-4 {
+        perspective.fov = fov;
+        projection.set_changed();
+    }
+}
+
+/// Screen-edge streak layer, hidden below `STREAK_THRESHOLD` of c
+#[derive(Component)]
+pub struct SpeedStreaks;
+
+/// One streak: the edge it runs to (0 left, 1 right, 2 top, 3 bottom), where along it
+/// (percent) and how far through its band it starts
+#[derive(Component)]
+pub struct SpeedStreak {
+    edge: u8,
+    along: f32,
+    phase: f32,
+}
+
+/// Spawn the streak layer, hidden
+pub fn spawn_speed_streaks(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                display: Display::None,
+                ..default()
+            },
+            SpeedStreaks,
+        ))
+        .with_children(|layer| {
+            for edge in 0..4u8 {
+                for i in 0..STREAKS_PER_EDGE {
+                    // Spread along the edge, a little off the even spacing
+                    let slot = (i as f32 + 0.5) / STREAKS_PER_EDGE as f32;
+                    let jitter = 0.04 * ((i * 7 + edge as usize * 3) % 5) as f32 - 0.08;
+                    layer.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            ..default()
+                        },
+                        BackgroundColor(Color::NONE),
+                        SpeedStreak {
+                            edge,
+                            along: (100.0 * (slot + jitter)).clamp(3.0, 97.0),
+                            phase: ((i * 5 + edge as usize) % STREAKS_PER_EDGE) as f32
+                                / STREAKS_PER_EDGE as f32,
+                        },
+                    ));
+                }
+            }
+        });
+}
+
+/// Run the streaks out toward the screen edges, longer and brighter toward light speed,
+/// fast and blue-white in warp
+pub fn speed_streaks_system(
+    time: Res<Time>,
+    settings: Res<UserSettings>,
+    surface: Res<SurfaceState>,
+    mut travel: Local<f32>,
+    cam_q: Query<&FlyCamera>,
+    mut layer_q: Query<&mut Node, With<SpeedStreaks>>,
+    mut streak_q: Query<(&SpeedStreak, &mut Node, &mut BackgroundColor), Without<SpeedStreaks>>,
+) {
+    let (Ok(cam), Ok(mut layer)) = (cam_q.get_single(), layer_q.get_single_mut()) else {
+        return;
+    };
+    let intensity = if settings.speed_effects && !surface.active {
+        streak_intensity(light_fraction(cam.velocity))
+    } else {
+        0.0
+    };
+    let display = if intensity > 0.0 { Display::Flex } else { Display::None };
+    if layer.display != display {
+        layer.display = display;
+    }
+    if intensity == 0.0 {
+        return;
+    }
+
+    let rate = if cam.warp { WARP_STREAK_RATE } else { STREAK_RATE * intensity };
+    *travel = (*travel + rate * time.delta_secs()).fract();
+    let length = STREAK_LENGTH * if cam.warp { 1.0 } else { intensity };
+    let color = if cam.warp {
+        Color::srgba(0.75, 0.85, 1.0, 0.5)
+    } else {
+        Color::srgba(1.0, 1.0, 1.0, 0.3 * intensity)
+    };
+    for (streak, mut node, mut background) in &mut streak_q {
+        // Outward through the band: from its inner side to the edge
+        let depth = STREAK_BAND * (1.0 - (streak.phase + *travel).fract());
+        let (along, thickness) = (Val::Percent(streak.along), Val::Px(2.0));
+        *node = match streak.edge {
+            0 | 1 => Node {
+                position_type: PositionType::Absolute,
+                top: along,
+                width: Val::Px(length),
+                height: thickness,
+                ..default()
+            },
+            _ => Node {
+                position_type: PositionType::Absolute,
+                left: along,
+                width: thickness,
+                height: Val::Px(length),
+                ..default()
+            },
+        };
+        match streak.edge {
+            0 => node.left = Val::Percent(depth),
+            1 => node.right = Val::Percent(depth),
+            2 => node.top = Val::Percent(depth),
+            _ => node.bottom = Val::Percent(depth),
+        }
+        background.0 = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_fraction_and_soft_cap() {
+        assert_eq!(light_fraction(Vec3::ZERO), 0.0);
+        assert!((light_fraction(Vec3::new(0.0, 0.0, -0.8 * C)) - 0.8).abs() < 1e-6);
+
+        // Below the soft start nothing changes
+        let slow = Vec3::new(300.0, -400.0, 0.0);
+        assert_eq!(cap_to_light(slow), slow);
+        let start = Vec3::X * SOFT_CAP_START * C;
+        assert_eq!(cap_to_light(start), start);
+
+        // Above it, faster input is still faster, never reaching c, in the same direction
+        let mut last = SOFT_CAP_START;
+        for factor in [0.81, 0.9, 1.0, 1.2, 1.5] {
+            let input = Vec3::new(1.0, 2.0, -2.0).normalize() * factor * C;
+            let capped = cap_to_light(input);
+            let fraction = light_fraction(capped);
+            assert!(fraction > last && fraction <= 1.0, "{factor}c -> {fraction}c");
+            assert!(capped.normalize().distance(input.normalize()) < 1e-5);
+            last = fraction;
+        }
+        assert!(last > 0.99);
+        assert!(light_fraction(cap_to_light(Vec3::Y * 50.0 * C)) <= 1.0);
+
+        // Continuous where the easing starts
+        let just_above = cap_to_light(Vec3::X * (SOFT_CAP_START * C + 1.0));
+        assert!((just_above.x - (SOFT_CAP_START * C + 1.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_fov_boost_and_streaks_follow_the_speed() {
+        assert_eq!(fov_boost_degrees(0.0), 0.0);
+        assert_eq!(fov_boost_degrees(1.0), FOV_BOOST_MAX_DEG);
+        assert_eq!(fov_boost_degrees(30.0), FOV_BOOST_MAX_DEG);
+        let mut last = 0.0;
+        for i in 1..=10 {
+            let boost = fov_boost_degrees(i as f32 / 10.0);
+            assert!(boost > last);
+            last = boost;
+        }
+
+        assert_eq!(streak_intensity(0.05), 0.0);
+        assert_eq!(streak_intensity(STREAK_THRESHOLD), 0.0);
+        assert!(streak_intensity(0.5) > 0.0 && streak_intensity(0.5) < 1.0);
+        assert_eq!(streak_intensity(1.0), 1.0);
+        assert_eq!(streak_intensity(3.0), 1.0);
+    }
+}
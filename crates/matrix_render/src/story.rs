@@ -0,0 +1,146 @@
+//! Story mode on screen: [F10] turns it on or off, a banner under the signal indicator
+//! names the event the clock stopped for (`matrix_sim::story::StoryDirector`), [X] puts
+//! the previous speed back, and the camera glides slowly toward the event if the settings
+//! ask for it.
+
+use bevy::prelude::*;
+use matrix_sim::events::EventFeed;
+use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::replay::ReplayPlayer;
+use matrix_sim::story::StoryDirector;
+use matrix_sim::universe::UniverseState;
+
+use super::camera::{CameraGlide, FlyCamera};
+use super::feed;
+use super::locale::Strings;
+use super::settings::{Action, UserSettings, action_just_pressed, key_name};
+use super::surface::SurfaceState;
+
+/// Ease-out rate of the glide toward an event (1/s), a tenth of the usual one
+const STORY_GLIDE_RATE: f32 = 0.4;
+/// Where the camera stops relative to the event, looking at it
+const STORY_VIEW_OFFSET: Vec3 = Vec3::new(0.0, 20.0, 50.0);
+
+/// Marker for the story banner (top center, while a beat is up)
+#[derive(Component)]
+pub struct StoryBanner;
+
+/// Marker for the story banner text
+#[derive(Component)]
+pub struct StoryBannerText;
+
+/// Spawn the story banner, hidden
+pub fn spawn_story_banner(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(80.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-260.0)),
+                width: Val::Px(520.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                justify_content: JustifyContent::Center,
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.02, 0.04, 0.1, 0.9)),
+            BorderColor(Color::srgba(0.5, 0.8, 1.0, 0.8)),
+            StoryBanner,
+        ))
+        .with_children(|banner| {
+            banner.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.95, 1.0)),
+                TextLayout::new_with_justify(JustifyText::Center),
+                StoryBannerText,
+            ));
+        });
+}
+
+/// Toggle story mode, stop the clock for the feed's story beats and resume on [X]. Skipped
+/// during replay playback, whose recorded speeds must not be overridden.
+pub fn story_mode_system(
+    (time, keyboard): (Res<Time<Real>>, Res<ButtonInput<KeyCode>>),
+    (feed, lazy, surface): (Res<EventFeed>, Res<LazyUniverse>, Res<SurfaceState>),
+    (mut settings, mut universe, mut director): (ResMut<UserSettings>, ResMut<UniverseState>, ResMut<StoryDirector>),
+    mut cam_q: Query<&mut FlyCamera>,
+    replay: Option<Res<ReplayPlayer>>,
+) {
+    if replay.is_some() {
+        return;
+    }
+    let now = time.elapsed_secs_f64();
+    if action_just_pressed(&settings, &keyboard, Action::StoryMode) {
+        settings.story.enabled = !settings.story.enabled;
+        info!("Story mode {}", if settings.story.enabled { "on" } else { "off" });
+    }
+    // Turned off here or on the settings page: the clock stays where it is
+    if !settings.story.enabled && director.beat().is_some() {
+        director.end(&settings.story, now);
+    }
+    let resume = action_just_pressed(&settings, &keyboard, Action::StoryResume);
+    if resume && director.beat().is_some() {
+        director.resume(&settings.story, &mut universe, now);
+        info!("Story mode: resumed at {}x", universe.time_scale);
+        return;
+    }
+
+    if !director.observe(&settings.story, &feed, &lazy, &mut universe, now) {
+        return;
+    }
+    let Some(beat) = director.beat() else {
+        return;
+    };
+    info!("Story mode: {} (from {}x)", beat.entry.event.describe(), beat.resume_scale);
+    let follow = settings.story.follow_camera && !surface.active;
+    let target = beat.location(&lazy).filter(|_| follow);
+    if let (Some(target), Ok(mut cam)) = (target, cam_q.get_single_mut()) {
+        let target = Vec3::from_array(target.map(|c| c as f32));
+        cam.tracking = None;
+        cam.frame_region = None;
+        let glide = CameraGlide::looking_at(target + STORY_VIEW_OFFSET, target);
+        cam.glide = Some(glide.with_rate(STORY_GLIDE_RATE));
+    }
+}
+
+/// Show the beat the clock stopped for, with the key that resumes the previous speed
+pub fn update_story_banner(
+    director: Res<StoryDirector>,
+    settings: Res<UserSettings>,
+    strings: Res<Strings>,
+    lazy: Res<LazyUniverse>,
+    mut banner_q: Query<&mut Node, With<StoryBanner>>,
+    mut text_q: Query<&mut Text, With<StoryBannerText>>,
+) {
+    let Ok(mut banner) = banner_q.get_single_mut() else {
+        return;
+    };
+    let Some(beat) = director.beat() else {
+        banner.display = Display::None;
+        return;
+    };
+    banner.display = Display::Flex;
+    if let Ok(mut text) = text_q.get_single_mut() {
+        let label = format!(
+            "{}\n{}",
+            feed::describe(&beat.entry.event, &lazy, &strings),
+            strings.tr(
+                "story.resume",
+                &[
+                    ("key", &key_name(settings.key(Action::StoryResume))),
+                    ("speed", &strings.number(beat.resume_scale, 0)),
+                    ("age", &strings.number(beat.entry.age_gyr, 2)),
+                ],
+            )
+        );
+        if **text != label {
+            **text = label;
+        }
+    }
+}
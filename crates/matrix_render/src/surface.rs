@@ -1,27 +1,112 @@
 use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll};
 use bevy::prelude::*;
-use bevy::render::mesh::PrimitiveTopology;
-use bevy::render::render_asset::RenderAssetUsages;
 use bevy::window::PrimaryWindow;
-use matrix_core::{AtmosphereType, Planet, PlanetType, SpectralClass};
+use matrix_core::{
+    AtmosphereType, CreatureRole, Genome, IndividualTraits, Planet, PlanetType, SpectralClass, Star,
+};
+use std::collections::HashMap;
+use matrix_physics::procgen;
+use matrix_physics::terrain::{terrain_height, terrain_normal};
 use matrix_sim::lazy_universe::LazyUniverse;
+use matrix_sim::replay::ReplayRecorder;
+use matrix_sim::state::AppState;
+use matrix_sim::universe::UniverseState;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
-use super::camera::{FlyCamera, ZoomLevel};
-use super::cosmos::{PlanetVisual, RegionVisual, AU_RENDER_SCALE};
+use super::budget::{Category, RenderBudget};
+use super::camera::{
+    enter_region_view, record_teleport, CameraGlide, FlyCamera, ZoomLevel, GLIDE_RATE,
+    LEVEL_PIN_SECS,
+};
+use super::chunks::{LiquidKind, SurfaceLiquid, TerrainChunks};
+use super::dive::AtmosphereDive;
+use super::landing::LandingTransition;
+use super::microbes::Microbe;
+use super::props::SurfaceProp;
+use super::settings::{Action, Controls};
+use super::surface_map::SurfaceMapUi;
+use super::sky::{
+    HorizonGlow, SkyBody, SkySatellite, ecliptic_direction, sky_seed, spawn_system_sky, spawn_tech_sky,
+};
+use super::cosmos::{RegionVisual, AU_RENDER_SCALE};
+use super::input_capture::UiInputCapture;
+use super::instances::CosmosInstances;
 
 // --- Constants ---
 
-const TERRAIN_SIZE: f32 = 200.0;
-const TERRAIN_RES: usize = 64;
-const WALK_SPEED: f32 = 10.0;
+/// Creatures spawn within this distance of the landing point
+const CREATURE_RANGE: f32 = 80.0;
+/// Wandering creatures pick targets within this distance of the camera
+const WANDER_RANGE: f32 = 60.0;
 const MAX_CREATURES: usize = 80;
 const MAX_DETAIL: usize = 50;
 const DETAIL_RANGE: f32 = 30.0;
 const DETAIL_RESPAWN_DIST: f32 = 15.0;
-const MAX_MICROBES: usize = 30;
-const MICROBE_RANGE: f32 = 0.5;
+/// Detail objects further than this from the camera are too small to see: not spawned,
+/// and despawned once the camera leaves them behind
+const DETAIL_CULL_DIST: f32 = 60.0;
+/// Frames between nearest-creature scans
+const PROXIMITY_FRAMES: u32 = 4;
+/// Edge of a one-creature herd marker; markers grow with the square root of the herd
+const HERD_MARKER_SIZE: f32 = 0.8;
+/// Frames between predator / prey scans (pursuit targets and threats)
+const ECOLOGY_SCAN_FRAMES: u32 = 10;
+/// Predators catch prey closer than this (plus both bodies' scale)
+const CATCH_DIST: f32 = 0.5;
+/// Seconds a predator stays put after a catch
+const FEED_SECS: f32 = 4.0;
+/// Seconds between respawns of eaten creatures
+const RESPAWN_SECS: f32 = 8.0;
+/// Speed multiplier while chasing or fleeing
+const PURSUIT_SPEED: f32 = 1.3;
+/// Creatures closer than this to the observer hold still, for `FREEZE_SECS`
+const FREEZE_DIST: f32 = 3.0;
+const FREEZE_SECS: f32 = 5.0;
+/// Cruise height of flying creatures above their resting height, and its bob
+const FLIGHT_ALTITUDE: f32 = 3.0;
+const FLIGHT_BOB: f32 = 0.4;
+/// Bob rate of flying creatures (rad/s)
+const FLIGHT_BOB_RATE: f32 = 1.5;
+/// Height easing per unit of speed (1/s per unit/s): fast creatures hug the terrain.
+/// Slower ones, down to sessile ones, ease at the rate of `MIN_SETTLE_SPEED`.
+const HEIGHT_FOLLOW: f32 = 4.0;
+const MIN_SETTLE_SPEED: f32 = 1.0;
+/// Real seconds per 24 h local day at 1× (faster time scales shorten it, down to the minimum).
+/// Simulated days pass far faster than anything watchable, so the sun runs at a capped pace.
+const DAY_SECS_AT_1X: f64 = 240.0;
+const MIN_DAY_SECS: f64 = 20.0;
+/// Sun illuminance at noon and ambient brightness by day / at night
+const SUN_ILLUMINANCE: f32 = 10_000.0;
+const DAY_AMBIENT: f32 = 300.0;
+const NIGHT_AMBIENT: f32 = 60.0;
+/// Starfield seed when the host star or its region isn't loaded
+const DEFAULT_SKY_SEED: u64 = 42;
+/// Hours in a Gyr (for the starting time of day)
+const HOURS_PER_GYR: f64 = 1e9 * 365.25 * 24.0;
+/// Real seconds (unpaused) between weather changes
+const WEATHER_EPOCH_SECS: f32 = 90.0;
+/// Precipitation particles are recycled inside this box around the camera (half size)
+const WEATHER_BOX: Vec3 = Vec3::new(25.0, 12.0, 25.0);
+/// How far (in seconds of wind) details and wander targets drift downwind
+const WIND_DRIFT_SECS: f32 = 3.0;
+/// Eye height above the water while swimming, and how deep a microscopic dive goes
+const SWIM_EYE_HEIGHT: f32 = 0.4;
+const DIVE_DEPTH: f32 = 0.5;
+/// Clear color the sky fades toward below the water surface
+const UNDERWATER_TINT: Color = Color::srgb(0.02, 0.12, 0.2);
+/// Steepest walkable slope (rise over run, tan 50°); steeper ground blocks and slides
+const MAX_CLIMB_SLOPE: f32 = 1.19;
+const SLIDE_SPEED: f32 = 4.0;
+/// Extra drop (meters) a grounded step follows before the camera falls instead
+const STEP_TOLERANCE: f32 = 0.05;
+/// Launch speed of a jump (m/s): the same everywhere, so jumps reach
+/// v² / 2g — about a meter on Earth, higher on low-gravity worlds
+const JUMP_SPEED: f32 = 4.4;
+/// System focus frames the outermost orbit times this margin
+const SYSTEM_FRAMING_MARGIN: f32 = 1.6;
+/// Closest framing distance for systems with tight (or no) orbits
+const SYSTEM_MIN_FRAMING: f32 = 8.0;
 
 // --- Surface zoom levels ---
 
@@ -69,6 +154,18 @@ pub struct SurfaceState {
     pub terrain_seed: u64,
     pub eye_height: f32,
     pub surface_zoom: SurfaceZoom,
+    /// Liquid layer of the current surface (set on landing)
+    pub liquid: Option<SurfaceLiquid>,
+    /// Camera is below the water surface
+    pub underwater: bool,
+    /// Feet on the ground (or afloat); false mid-jump or mid-fall
+    pub grounded: bool,
+    /// Vertical speed while airborne (m/s, up is positive)
+    pub vertical_speed: f32,
+    /// Hemisphere landed on, for a tidally locked planet (worked out on landing)
+    pub locked_side: Option<LockedSide>,
+    /// Seed of the sky dome's starfield, to respawn it at another render budget
+    pub sky_seed: u64,
 }
 
 impl Default for SurfaceState {
@@ -83,31 +180,279 @@ impl Default for SurfaceState {
             terrain_seed: 0,
             eye_height: 2.0,
             surface_zoom: SurfaceZoom::Ground,
+            liquid: None,
+            underwater: false,
+            grounded: true,
+            vertical_speed: 0.0,
+            locked_side: None,
+            sky_seed: DEFAULT_SKY_SEED,
         }
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct PlanetSelection {
-    pub hovered: Option<Entity>,
+    /// Planet under the cursor as (star id, planet id)
+    pub hovered: Option<(u64, u64)>,
     pub selected_planet: Option<(Planet, SpectralClass)>,
     pub highlight_material: Handle<StandardMaterial>,
-    pub original_materials: Vec<(Entity, Handle<StandardMaterial>)>,
+    /// Materials the hover highlight replaced, by region id: the sprite entities change
+    /// whenever `update_region_visuals` rebuilds them
+    pub original_materials: Vec<(u64, Handle<StandardMaterial>)>,
     /// Hovered region entity (at Cosmic/Galactic zoom)
     pub hovered_region: Option<Entity>,
     /// Selected region ID ready for entry with [B]
     pub selected_region: Option<u64>,
+    /// Star under the cursor (when no planet is hovered)
+    pub hovered_star: Option<u64>,
+    /// Selected star ID ready for system focus with [B]
+    pub selected_star: Option<u64>,
+}
+
+impl PlanetSelection {
+    /// Re-resolve the selection after the loaded stars were rebuilt: the selected planet
+    /// gets its current data, and planets and stars no longer loaded are let go
+    pub fn refresh_stars(&mut self, lazy: &LazyUniverse) {
+        self.hovered = self
+            .hovered
+            .filter(|&(star_id, planet_id)| find_planet(lazy, star_id, planet_id).is_some());
+        self.selected_planet = self.selected_planet.take().and_then(|(planet, _)| {
+            lazy.loaded_stars
+                .iter()
+                .find_map(|star| star.body(planet.id).map(|p| (p.clone(), star.spectral_class)))
+        });
+        let loaded = |id: &u64| lazy.loaded_stars.iter().any(|s| s.id == *id);
+        self.hovered_star = self.hovered_star.filter(loaded);
+        self.selected_star = self.selected_star.filter(loaded);
+    }
+
+    /// Forget the region sprites (despawned for a rebuild): their highlight goes with them
+    pub fn forget_region_sprites(&mut self) {
+        self.hovered_region = None;
+        self.original_materials.clear();
+    }
+}
+
+/// System focus mode: the camera frames one star, its planets are enlarged
+/// and the other stars dimmed (see `cosmos::update_cosmos_visuals`)
+#[derive(Resource, Default)]
+pub struct SystemFocus {
+    pub star_id: Option<u64>,
+    /// Camera position before focusing, restored on [Esc]
+    pub return_pos: Vec3,
+    /// Region the camera left with [Esc] and where it was there: entering that region
+    /// again glides back to it
+    pub region_return: Option<(u64, CameraGlide)>,
 }
 
 #[derive(Resource, Default)]
 pub struct DetailState {
     pub last_spawn_pos: Vec3,
+    /// `RenderBudget::generation` the details were spawned for
+    pub budget_generation: u32,
+}
+
+/// Predator-prey state of the current surface; only active for complexity ≥ 5 biospheres
+#[derive(Resource, Default)]
+pub struct SurfaceEcology {
+    /// How far creatures notice each other (0 = no predator-prey behavior)
+    pub detection_radius: f32,
+    /// Mesh, material and traits of each species, for respawns
+    species: Vec<(Handle<Mesh>, Handle<StandardMaterial>, IndividualTraits)>,
+    /// Offsets of the original spawn points from the landing point; eaten creatures
+    /// grow back at the same offsets from the camera
+    spawn_points: Vec<Vec3>,
+    /// Species of eaten creatures waiting to respawn
+    eaten: Vec<usize>,
+    respawn_timer: f32,
+    scan_frame: u32,
+    /// Creatures taken off the ground at Landscape height (`creature_lod_system`), with
+    /// where they were and what they were doing; put back on the way down
+    parked: Vec<(Transform, Creature)>,
+}
+
+/// Local time of day on the current surface
+#[derive(Resource, Default)]
+pub struct SurfaceDay {
+    /// 0 = midnight, 0.25 = sunrise, 0.5 = noon, 0.75 = sunset
+    pub time_of_day: f64,
+    /// 0 at night, 1 in full daylight
+    pub daylight: f32,
+    /// Length of the planet's day (hours)
+    pub period_hours: f64,
+    /// `SurfaceState::render_generation` this cycle was set up for
+    generation: u32,
+}
+
+impl SurfaceDay {
+    /// Local clock time as (hours, minutes) on a 24 h dial
+    pub fn clock(&self) -> (u32, u32) {
+        let minutes = (self.time_of_day * 24.0 * 60.0) as u32;
+        (minutes / 60 % 24, minutes % 60)
+    }
+}
+
+/// Where on a tidally locked planet the landing site is: its star never moves in the sky
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockedSide {
+    Day,
+    Terminator,
+    Night,
+}
+
+impl LockedSide {
+    /// From the cosine of the angle between the landing direction and the star
+    /// (both seen from the planet's center)
+    pub fn from_facing(cos_to_star: f32) -> Self {
+        if cos_to_star > 0.35 {
+            Self::Day
+        } else if cos_to_star < -0.35 {
+            Self::Night
+        } else {
+            Self::Terminator
+        }
+    }
+
+    /// Fixed local time: the sun overhead, on the horizon or below it
+    pub fn time_of_day(self) -> f64 {
+        match self {
+            Self::Day => 0.5,
+            Self::Terminator => 0.25,
+            Self::Night => 0.0,
+        }
+    }
+
+    /// Terrain material tint: baked on the day side, frozen on the night side
+    pub fn terrain_tint(self) -> Color {
+        match self {
+            Self::Day => Color::srgb(1.0, 0.78, 0.6),
+            Self::Terminator => Color::WHITE,
+            Self::Night => Color::srgb(0.7, 0.8, 1.0),
+        }
+    }
+
+    /// HUD note in place of the local time
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Day => "eternal day",
+            Self::Terminator => "eternal twilight",
+            Self::Night => "eternal night",
+        }
+    }
+}
+
+/// Current weather on the surface, re-rolled every `WEATHER_EPOCH_SECS`
+#[derive(Resource, Default)]
+pub struct WeatherState {
+    pub kind: WeatherKind,
+    /// Horizontal wind (units per second)
+    pub wind: Vec3,
+    epoch: u64,
+    epoch_timer: f32,
+    /// Particles and fog match `kind`
+    applied: bool,
+    /// `SurfaceState::render_generation` this weather belongs to
+    generation: u32,
+    /// `RenderBudget::generation` the particles were spawned for
+    budget_generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeatherKind {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+    DustStorm,
+    MethaneDrizzle,
+    AshFall,
+    Embers,
+    BlowingSnow,
+}
+
+impl WeatherKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Clear => "Clear",
+            Self::Rain => "Rain",
+            Self::Snow => "Snow",
+            Self::DustStorm => "Dust storm",
+            Self::MethaneDrizzle => "Methane drizzle",
+            Self::AshFall => "Ash fall",
+            Self::Embers => "Embers",
+            Self::BlowingSnow => "Blowing snow",
+        }
+    }
+
+    /// Particle count, quad size (w, h), fall speed (negative rises) and color
+    fn particles(self) -> (usize, Vec2, f32, Color) {
+        match self {
+            Self::Clear => (0, Vec2::ZERO, 0.0, Color::NONE),
+            Self::Rain => (400, Vec2::new(0.03, 0.5), 15.0, Color::srgba(0.6, 0.7, 0.9, 0.6)),
+            Self::Snow => (300, Vec2::splat(0.08), 1.5, Color::srgba(1.0, 1.0, 1.0, 0.9)),
+            Self::DustStorm => (400, Vec2::splat(0.1), 0.2, Color::srgba(0.6, 0.45, 0.3, 0.7)),
+            Self::MethaneDrizzle => (300, Vec2::new(0.03, 0.3), 4.0, Color::srgba(0.8, 0.55, 0.3, 0.6)),
+            Self::AshFall => (300, Vec2::splat(0.08), 1.0, Color::srgba(0.2, 0.2, 0.2, 0.9)),
+            Self::Embers => (200, Vec2::splat(0.06), -1.5, Color::srgb(1.0, 0.5, 0.1)),
+            Self::BlowingSnow => (400, Vec2::splat(0.06), 0.5, Color::srgba(0.95, 0.97, 1.0, 0.8)),
+        }
+    }
+
+    /// Exponential fog density (0 = no fog)
+    pub(crate) fn fog_density(self) -> f32 {
+        match self {
+            Self::Clear => 0.0,
+            Self::Rain | Self::Embers => 0.02,
+            Self::Snow => 0.03,
+            Self::MethaneDrizzle => 0.04,
+            Self::AshFall | Self::BlowingSnow => 0.05,
+            Self::DustStorm => 0.06,
+        }
+    }
+}
+
+/// Pick the weather for a planet and weather epoch: what can happen depends on the
+/// planet type and atmosphere, which of it happens on the planet id and epoch
+pub fn choose_weather(planet: &Planet, epoch: u64) -> (WeatherKind, Vec3) {
+    use WeatherKind::*;
+    let wet = if planet.surface_temp < 273.0 { Snow } else { Rain };
+    let options: &[WeatherKind] = match (planet.planet_type, planet.atmosphere) {
+        (PlanetType::Lava, _) => &[Embers, Embers, AshFall],
+        (PlanetType::Frozen, AtmosphereType::None) => &[Clear, BlowingSnow],
+        (PlanetType::Frozen, _) => &[BlowingSnow, Snow, Clear],
+        (_, AtmosphereType::None) => &[Clear],
+        (_, AtmosphereType::Methane) => &[Clear, MethaneDrizzle, MethaneDrizzle],
+        (_, AtmosphereType::ThinCO2 | AtmosphereType::ThickCO2 | AtmosphereType::Hydrogen) => {
+            &[Clear, Clear, DustStorm]
+        }
+        (_, AtmosphereType::NitrogenOxygen | AtmosphereType::Exotic) if planet.has_water => {
+            &[Clear, Clear, wet]
+        }
+        _ => &[Clear, DustStorm],
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(planet.id.wrapping_mul(0x5851_F42D).wrapping_add(epoch));
+    let kind = options[rng.gen_range(0..options.len())];
+
+    let strength = match kind {
+        DustStorm | BlowingSnow => rng.gen_range(6.0..12.0),
+        Clear => rng.gen_range(0.0..2.0),
+        _ => rng.gen_range(1.0..4.0),
+    };
+    let heading = rng.gen_range(0.0..std::f32::consts::TAU);
+    (kind, Vec3::new(heading.cos(), 0.0, heading.sin()) * strength)
 }
 
 #[derive(Resource, Default)]
 pub struct NearestCreatureInfo {
     pub distance: f32,
+    /// Where the nearest creature is (at any distance)
+    pub position: Vec3,
     pub description: String,
+    /// Species index of the nearest creature
+    pub species: usize,
+    /// The nearest creature's traits, `None` when none is within 5 m
+    pub traits: Option<IndividualTraits>,
+    pub state: CreatureState,
 }
 
 // --- Components ---
@@ -121,25 +466,127 @@ pub struct WaterPlane;
 #[derive(Component)]
 pub struct SurfaceLight;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Creature {
     pub speed: f32,
+    /// Where a wandering creature is heading, and the time left before it picks another spot
     pub wander_target: Vec3,
     pub wander_timer: f32,
     pub is_flying: bool,
+    /// Index into the planet's `procgen::surface_species`
+    pub species: usize,
+    /// This individual's sampled traits
+    pub traits: IndividualTraits,
+    pub diet: Option<Diet>,
+    pub state: CreatureState,
+    /// Prey being chased
+    pub target: Option<Entity>,
+    /// Predator being fled from (position at the last scan)
+    pub threat: Option<Vec3>,
+}
+
+/// Place in the food chain (complex biospheres only)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diet {
+    Herbivore,
+    Predator,
+}
+
+/// What a creature is doing right now
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CreatureState {
+    #[default]
+    Wandering,
+    Grazing,
+    Hunting,
+    Fleeing,
+    /// Eating a catch, for `timer` more seconds
+    Feeding { timer: f32 },
+    /// Sight-only creatures sit out the night
+    Resting,
+    /// Holding still while the observer is close, for `timer` more seconds
+    Frozen { timer: f32 },
+}
+
+impl CreatureState {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wandering => "wandering",
+            Self::Grazing => "grazing",
+            Self::Hunting => "hunting",
+            Self::Fleeing => "fleeing",
+            Self::Feeding { .. } => "feeding",
+            Self::Resting => "resting",
+            Self::Frozen { .. } => "frozen",
+        }
+    }
+
+    /// Staying put: flying creatures come down to the ground
+    pub fn is_still(self) -> bool {
+        matches!(self, Self::Feeding { .. } | Self::Resting | Self::Frozen { .. })
+    }
+}
+
+impl Creature {
+    /// The observer came within `FREEZE_DIST`: a wandering or grazing creature holds
+    /// still (again) for `FREEZE_SECS`. Hunting, fleeing, feeding and resting go on.
+    pub fn freeze(&mut self) {
+        if matches!(
+            self.state,
+            CreatureState::Wandering | CreatureState::Grazing | CreatureState::Frozen { .. }
+        ) {
+            self.state = CreatureState::Frozen { timer: FREEZE_SECS };
+        }
+    }
+
+    /// Count down a timed state; when it runs out the creature wanders off somewhere new
+    pub fn tick(&mut self, dt: f32) {
+        if let CreatureState::Feeding { timer } | CreatureState::Frozen { timer } = &mut self.state {
+            *timer -= dt;
+            if *timer < 0.0 {
+                self.state = CreatureState::Wandering;
+                self.wander_timer = 0.0;
+            }
+        }
+    }
+
+    /// Height above its resting height: flying creatures cruise at `FLIGHT_ALTITUDE`,
+    /// bobbing with their own `phase`, and land while still
+    pub fn hover(&self, elapsed: f32, phase: f32) -> f32 {
+        if !self.is_flying || self.state.is_still() {
+            return 0.0;
+        }
+        FLIGHT_ALTITUDE + (elapsed * FLIGHT_BOB_RATE + phase).sin() * FLIGHT_BOB
+    }
+
+    /// Where the creature's center belongs: half its `scale` above the `ground`, plus its hover
+    pub fn target_height(&self, ground: f32, scale: f32, elapsed: f32, phase: f32) -> f32 {
+        ground + scale * 0.5 + self.hover(elapsed, phase)
+    }
+
+    /// Ease `y` toward `target`, faster for faster creatures, so stops on slopes settle
+    /// and take-offs and landings glide instead of popping. Sessile creatures settle too.
+    pub fn settle_height(&self, y: f32, target: f32, dt: f32) -> f32 {
+        let rate = self.speed.max(MIN_SETTLE_SPEED) * HEIGHT_FOLLOW * dt;
+        y + (target - y) * rate.min(1.0)
+    }
 }
 
 #[derive(Component)]
 pub struct SurfaceDetail;
 
+/// Stands in for one species' creatures at Landscape height: a billboard at the middle
+/// of the herd, sized by its number
 #[derive(Component)]
-pub struct Microbe {
-    pub drift_speed: f32,
-    pub drift_dir: Vec3,
-}
+pub struct HerdMarker;
+
+/// Star on the sky dome; `0` is its night-time scale
+/// Precipitation quad, recycled around the camera
+#[derive(Component)]
+pub struct WeatherParticle;
 
 #[derive(Component)]
-pub struct SkyDomeStar;
+pub struct SkyDomeStar(pub f32);
 
 // --- Run conditions ---
 
@@ -170,6 +617,8 @@ pub fn init_planet_selection(
         original_materials: Vec::new(),
         hovered_region: None,
         selected_region: None,
+        hovered_star: None,
+        selected_star: None,
     });
 }
 
@@ -178,18 +627,20 @@ pub fn init_planet_selection(
 pub fn planet_hover_system(
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_q: Query<(&Camera, &GlobalTransform, &FlyCamera)>,
-    planet_q: Query<(Entity, &Transform, &PlanetVisual, &MeshMaterial3d<StandardMaterial>)>,
+    instances: Res<CosmosInstances>,
     mut selection: ResMut<PlanetSelection>,
-    mut commands: Commands,
     mouse: Res<ButtonInput<MouseButton>>,
     lazy: Res<LazyUniverse>,
+    capture: Res<UiInputCapture>,
 ) {
-    // Only active at Stellar/Planetary zoom (not Cosmic/Galactic)
-    if let Ok((_, _, cam)) = camera_q.get_single() {
-        if matches!(cam.zoom_level, ZoomLevel::Cosmic | ZoomLevel::Galactic) {
-            clear_hover(&mut selection, &mut commands, &planet_q);
-            return;
-        }
+    // Only active at Stellar/Planetary zoom (not Cosmic/Galactic), with the cursor off the UI
+    if capture.pointer
+        || camera_q
+            .get_single()
+            .is_ok_and(|(_, _, cam)| matches!(cam.zoom_level, ZoomLevel::Cosmic | ZoomLevel::Galactic))
+    {
+        selection.hovered = None;
+        return;
     }
 
     let Ok(window) = windows.get_single() else {
@@ -197,7 +648,7 @@ pub fn planet_hover_system(
     };
     let Some(cursor_pos) = window.cursor_position() else {
         // Cursor outside window — clear hover
-        clear_hover(&mut selection, &mut commands, &planet_q);
+        selection.hovered = None;
         return;
     };
 
@@ -209,80 +660,77 @@ pub fn planet_hover_system(
     };
 
     // Test all planets
-    let mut closest: Option<(Entity, f32)> = None;
-    for (entity, transform, _pv, _mat) in planet_q.iter() {
-        let radius = transform.scale.x;
-        if let Some(t) = ray_sphere_intersect(ray.origin, *ray.direction, transform.translation, radius) {
-            if closest.map_or(true, |(_, best_t)| t < best_t) {
-                closest = Some((entity, t));
-            }
-        }
-    }
-
-    let new_hovered = closest.map(|(e, _)| e);
-
-    // Handle hover change
-    if new_hovered != selection.hovered {
-        // Restore old material
-        if let Some(old_entity) = selection.hovered {
-            if let Some(pos) = selection.original_materials.iter().position(|(e, _)| *e == old_entity) {
-                let (_, original_mat) = selection.original_materials.remove(pos);
-                if planet_q.get(old_entity).is_ok() {
-                    commands.entity(old_entity).insert(MeshMaterial3d(original_mat));
-                }
-            }
-        }
-        // Set new highlight
-        if let Some(new_entity) = new_hovered {
-            if let Ok((_, _, _, current_mat)) = planet_q.get(new_entity) {
-                selection.original_materials.push((new_entity, current_mat.0.clone()));
-                commands
-                    .entity(new_entity)
-                    .insert(MeshMaterial3d(selection.highlight_material.clone()));
-            }
+    let mut closest: Option<((u64, u64), f32)> = None;
+    for planet in &instances.planets {
+        if let Some(t) = ray_sphere_intersect(ray.origin, *ray.direction, planet.position, planet.radius)
+            && closest.is_none_or(|(_, best_t)| t < best_t)
+        {
+            closest = Some(((planet.star_id, planet.planet_id), t));
         }
-        selection.hovered = new_hovered;
     }
+    selection.hovered = closest.map(|(ids, _)| ids);
 
     // Left-click: select planet
-    if mouse.just_pressed(MouseButton::Left) {
-        if let Some(hovered_entity) = selection.hovered {
-            if let Ok((_, _, pv, _)) = planet_q.get(hovered_entity) {
-                // Look up Planet + SpectralClass
-                for star in &lazy.loaded_stars {
-                    if star.id == pv.star_id {
-                        for planet in &star.planets {
-                            if planet.id == pv.planet_id {
-                                selection.selected_planet =
-                                    Some((planet.clone(), star.spectral_class));
-                                info!(
-                                    "Selected: {:?} planet id={} ({:.0}K)",
-                                    planet.planet_type, planet.id, planet.surface_temp
-                                );
-                                break;
-                            }
-                        }
-                        break;
-                    }
-                }
-            }
-        }
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some((star_id, planet_id)) = selection.hovered
+        && let Some((star, planet)) = find_planet(&lazy, star_id, planet_id)
+    {
+        selection.selected_planet = Some((planet.clone(), star.spectral_class));
+        selection.selected_star = None;
+        info!(
+            "Selected: {:?} planet id={} ({:.0}K)",
+            planet.planet_type, planet.id, planet.surface_temp
+        );
     }
 }
 
-fn clear_hover(
-    selection: &mut PlanetSelection,
-    commands: &mut Commands,
-    planet_q: &Query<(Entity, &Transform, &PlanetVisual, &MeshMaterial3d<StandardMaterial>)>,
+/// Look up a loaded planet or moon and its host star
+pub fn find_planet(lazy: &LazyUniverse, star_id: u64, planet_id: u64) -> Option<(&Star, &Planet)> {
+    let star = lazy.loaded_stars.iter().find(|s| s.id == star_id)?;
+    Some((star, star.body(planet_id)?))
+}
+
+/// Star hover/selection at Stellar/Planetary zoom: highlight the sphere under the cursor,
+/// select it on click. Planets take precedence: a hovered planet clears the star hover.
+pub fn star_hover_system(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform, &FlyCamera)>,
+    instances: Res<CosmosInstances>,
+    mut selection: ResMut<PlanetSelection>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    capture: Res<UiInputCapture>,
 ) {
-    if let Some(old_entity) = selection.hovered.take() {
-        if let Some(pos) = selection.original_materials.iter().position(|(e, _)| *e == old_entity) {
-            let (_, original_mat) = selection.original_materials.remove(pos);
-            if planet_q.get(old_entity).is_ok() {
-                commands.entity(old_entity).insert(MeshMaterial3d(original_mat));
+    let Ok((camera, cam_gtf, cam)) = camera_q.get_single() else {
+        return;
+    };
+    let ray = windows
+        .get_single()
+        .ok()
+        .and_then(|w| w.cursor_position())
+        .and_then(|pos| camera.viewport_to_world(cam_gtf, pos).ok());
+    let active = !matches!(cam.zoom_level, ZoomLevel::Cosmic | ZoomLevel::Galactic)
+        && selection.hovered.is_none()
+        && !capture.pointer;
+
+    // Test all stars
+    let mut closest: Option<(u64, f32)> = None;
+    if let (true, Some(ray)) = (active, ray) {
+        for star in &instances.stars {
+            if let Some(t) = ray_sphere_intersect(ray.origin, *ray.direction, star.position, star.radius)
+                && closest.is_none_or(|(_, best_t)| t < best_t)
+            {
+                closest = Some((star.star_id, t));
             }
         }
     }
+    selection.hovered_star = closest.map(|(id, _)| id);
+
+    // Left-click: select star
+    if mouse.just_pressed(MouseButton::Left) && let Some(star_id) = selection.hovered_star {
+        selection.selected_star = Some(star_id);
+        selection.selected_planet = None;
+        info!("Selected star #{}", star_id);
+    }
 }
 
 fn ray_sphere_intersect(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
@@ -315,6 +763,7 @@ pub fn region_hover_system(
     mut selection: ResMut<PlanetSelection>,
     mut commands: Commands,
     mouse: Res<ButtonInput<MouseButton>>,
+    capture: Res<UiInputCapture>,
 ) {
     // Only active at Cosmic/Galactic zoom
     let Ok((camera, cam_gtf, cam)) = camera_q.get_single() else {
@@ -322,19 +771,10 @@ pub fn region_hover_system(
     };
     if !matches!(cam.zoom_level, ZoomLevel::Cosmic | ZoomLevel::Galactic) {
         // Clear region hover when not at right zoom
-        if let Some(old_entity) = selection.hovered_region.take() {
-            if let Some(pos) = selection
-                .original_materials
-                .iter()
-                .position(|(e, _)| *e == old_entity)
-            {
-                let (_, original_mat) = selection.original_materials.remove(pos);
-                if region_q.get(old_entity).is_ok() {
-                    commands
-                        .entity(old_entity)
-                        .insert(MeshMaterial3d(original_mat));
-                }
-            }
+        if let Some(old_entity) = selection.hovered_region.take()
+            && let Ok((_, _, rv, _)) = region_q.get(old_entity)
+        {
+            restore_region_material(&mut commands, &mut selection, old_entity, rv.region_id);
         }
         return;
     }
@@ -349,16 +789,16 @@ pub fn region_hover_system(
         return;
     };
 
-    // Test all region cubes (use sphere intersection with radius = scale)
+    // Test all region sprites (use sphere intersection with radius = scale); none is
+    // hovered while the cursor is over the UI
     let mut closest: Option<(Entity, f32)> = None;
-    for (entity, transform, _rv, _mat) in region_q.iter() {
-        let radius = transform.scale.x; // cube is uniform scale
+    for (entity, transform, _rv, _mat) in region_q.iter().filter(|_| !capture.pointer) {
+        let radius = transform.scale.x; // sprite is a unit disc, uniformly scaled
         if let Some(t) =
             ray_sphere_intersect(ray.origin, *ray.direction, transform.translation, radius)
+            && closest.is_none_or(|(_, best_t)| t < best_t)
         {
-            if closest.map_or(true, |(_, best_t)| t < best_t) {
-                closest = Some((entity, t));
-            }
+            closest = Some((entity, t));
         }
     }
 
@@ -367,80 +807,222 @@ pub fn region_hover_system(
     // Handle hover change
     if new_hovered != selection.hovered_region {
         // Restore old material
-        if let Some(old_entity) = selection.hovered_region {
-            if let Some(pos) = selection
-                .original_materials
-                .iter()
-                .position(|(e, _)| *e == old_entity)
-            {
-                let (_, original_mat) = selection.original_materials.remove(pos);
-                if region_q.get(old_entity).is_ok() {
-                    commands
-                        .entity(old_entity)
-                        .insert(MeshMaterial3d(original_mat));
-                }
-            }
+        if let Some(old_entity) = selection.hovered_region
+            && let Ok((_, _, rv, _)) = region_q.get(old_entity)
+        {
+            restore_region_material(&mut commands, &mut selection, old_entity, rv.region_id);
         }
         // Set new highlight
-        if let Some(new_entity) = new_hovered {
-            if let Ok((_, _, _, current_mat)) = region_q.get(new_entity) {
-                selection
-                    .original_materials
-                    .push((new_entity, current_mat.0.clone()));
-                commands
-                    .entity(new_entity)
-                    .insert(MeshMaterial3d(selection.highlight_material.clone()));
-            }
+        if let Some(new_entity) = new_hovered
+            && let Ok((_, _, rv, current_mat)) = region_q.get(new_entity)
+            && let Some(mut sprite) = commands.get_entity(new_entity)
+        {
+            sprite.insert(MeshMaterial3d(selection.highlight_material.clone()));
+            selection
+                .original_materials
+                .push((rv.region_id, current_mat.0.clone()));
         }
         selection.hovered_region = new_hovered;
     }
 
     // Left-click: select region
-    if mouse.just_pressed(MouseButton::Left) {
-        if let Some(hovered_entity) = selection.hovered_region {
-            if let Ok((_, _, rv, _)) = region_q.get(hovered_entity) {
-                selection.selected_region = Some(rv.region_id);
-                info!("Selected region #{}", rv.region_id);
-            }
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some(hovered_entity) = selection.hovered_region
+        && let Ok((_, _, rv, _)) = region_q.get(hovered_entity)
+    {
+        selection.selected_region = Some(rv.region_id);
+        info!("Selected region #{}", rv.region_id);
+    }
+}
+
+/// Put back the material the hover highlight replaced on a region sprite
+fn restore_region_material(
+    commands: &mut Commands,
+    selection: &mut PlanetSelection,
+    entity: Entity,
+    region_id: u64,
+) {
+    let Some(pos) = selection
+        .original_materials
+        .iter()
+        .position(|(id, _)| *id == region_id)
+    else {
+        return;
+    };
+    let (_, original_mat) = selection.original_materials.remove(pos);
+    if let Some(mut sprite) = commands.get_entity(entity) {
+        sprite.insert(MeshMaterial3d(original_mat));
+    }
+}
+
+// --- Level transitions ([B] / [Esc]) ---
+
+/// Where the player is in the Cosmic → Region → System → Surface hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewLevel {
+    /// Region overview (Cosmic zoom)
+    Cosmic,
+    /// Flying inside a region (Galactic/Stellar zoom)
+    Region,
+    /// Framing one star and its planets (Planetary zoom)
+    System(u64),
+    /// Walking on a planet
+    Surface,
+}
+
+impl ViewLevel {
+    pub fn current(surface: &SurfaceState, focus: &SystemFocus, zoom: ZoomLevel) -> Self {
+        if surface.active {
+            Self::Surface
+        } else if let (Some(star_id), ZoomLevel::Planetary) = (focus.star_id, zoom) {
+            Self::System(star_id)
+        } else if zoom == ZoomLevel::Cosmic {
+            Self::Cosmic
+        } else {
+            Self::Region
         }
     }
 }
 
-// --- Surface toggle system ---
+/// Level-transition keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelKey {
+    /// [B]: go one level deeper
+    Enter,
+    /// [Esc]: go one level up
+    Back,
+}
 
-/// [B] key: enter region / land on planet / exit surface
-/// [Esc] key: exit surface / exit to Cosmic view
-pub fn surface_toggle_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut state: ResMut<SurfaceState>,
-    mut selection: ResMut<PlanetSelection>,
-    lazy: Res<LazyUniverse>,
+/// What a [B] / [Esc] press does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelTransition {
+    LeaveSurface,
+    LeaveSystem,
+    ExitToCosmic,
+    EnterRegion(u64),
+    EnterSystem(u64),
+    /// Land on the selected planet (or the nearest one at Planetary zoom)
+    Land,
+    /// [Esc] with nowhere left to back out to
+    OpenPauseMenu,
+}
+
+/// The level state machine: which transition `key` triggers at `level`
+pub fn next_transition(
+    level: ViewLevel,
+    key: LevelKey,
+    selected_region: Option<u64>,
+    selected_star: Option<u64>,
+) -> Option<LevelTransition> {
+    use LevelTransition::*;
+    match (level, key) {
+        (ViewLevel::Surface, _) => Some(LeaveSurface),
+        (ViewLevel::System(_), LevelKey::Back) => Some(LeaveSystem),
+        (ViewLevel::System(_), LevelKey::Enter) => Some(Land),
+        (ViewLevel::Region, LevelKey::Back) => Some(ExitToCosmic),
+        (ViewLevel::Cosmic, LevelKey::Back) => Some(OpenPauseMenu),
+        (_, LevelKey::Enter) => match (level, selected_region, selected_star) {
+            (_, Some(region_id), _) => Some(EnterRegion(region_id)),
+            (ViewLevel::Region, None, Some(star_id)) => Some(EnterSystem(star_id)),
+            (ViewLevel::Region, None, None) => Some(Land),
+            _ => None,
+        },
+    }
+}
+
+/// Camera framing position for system focus: above the star, far enough to see the outer orbit
+fn system_framing_position(star: &Star) -> Vec3 {
+    let star_pos = Vec3::new(
+        star.position[0] as f32,
+        star.position[1] as f32,
+        star.position[2] as f32,
+    );
+    let outer_orbit = star
+        .planets
+        .iter()
+        .map(|p| p.orbital_radius * AU_RENDER_SCALE)
+        .fold(0.0, f64::max) as f32;
+    let distance = (outer_orbit * SYSTEM_FRAMING_MARGIN).max(SYSTEM_MIN_FRAMING);
+    star_pos + Vec3::new(0.0, distance * 0.8, distance * 0.6)
+}
+
+/// [B] / [Esc] handling for every level: enter region → focus system → land, and back out
+/// ([Esc] at Cosmic view opens the pause menu).
+/// Runs the `next_transition` state machine and applies the result.
+pub fn level_transition_system(
+    controls: Controls,
+    (mut state, mut dive, mut landing): (ResMut<SurfaceState>, ResMut<AtmosphereDive>, ResMut<LandingTransition>),
+    (mut selection, mut focus): (ResMut<PlanetSelection>, ResMut<SystemFocus>),
+    (lazy, universe): (Res<LazyUniverse>, Res<UniverseState>),
     mut camera_query: Query<(&mut Transform, &mut FlyCamera)>,
+    mut recorder: Option<ResMut<ReplayRecorder>>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
-    let b_pressed = keyboard.just_pressed(KeyCode::KeyB);
-    let esc_pressed = keyboard.just_pressed(KeyCode::Escape);
-
-    if !b_pressed && !esc_pressed {
+    let Ok((mut transform, mut cam)) = camera_query.get_single_mut() else {
+        return;
+    };
+    // [B] / [Esc] belong to the dive while it lasts, and wait out a landing or take-off
+    if dive.is_active() || landing.is_active() {
         return;
     }
 
-    // === EXIT SURFACE ===
-    if state.active && (b_pressed || esc_pressed) {
-        state.active = false;
-        state.generation = state.generation.wrapping_add(1);
-        info!("Surface: leaving planet");
-        return;
+    // Focus ends if something else moved the camera to another level or the star unloaded
+    if let Some(star_id) = focus.star_id {
+        let star_loaded = lazy.loaded_stars.iter().any(|s| s.id == star_id);
+        if !state.active && (cam.zoom_level != ZoomLevel::Planetary || !star_loaded) {
+            focus.star_id = None;
+        }
     }
 
-    // === ESC: exit to Cosmic view (from any non-Cosmic zoom) ===
-    if esc_pressed && !state.active {
-        let Ok((mut transform, mut cam)) = camera_query.get_single_mut() else {
-            return;
-        };
-        if !matches!(cam.zoom_level, ZoomLevel::Cosmic) {
-            let target = lazy
+    let key = if controls.just_pressed(Action::EnterLevel) {
+        LevelKey::Enter
+    } else if controls.just_pressed(Action::BackLevel) {
+        LevelKey::Back
+    } else {
+        return;
+    };
+
+    let level = ViewLevel::current(&state, &focus, cam.zoom_level);
+    let Some(transition) =
+        next_transition(level, key, selection.selected_region, selection.selected_star)
+    else {
+        if key == LevelKey::Enter {
+            info!("Select a region (click) then press [B]");
+        }
+        return;
+    };
+
+    // Hold the level against the auto level for a moment
+    if transition != LevelTransition::OpenPauseMenu {
+        cam.level_pin = LEVEL_PIN_SECS;
+    }
+    match transition {
+        LevelTransition::OpenPauseMenu => next_state.set(AppState::Paused),
+        // The surface is left at the top of the climb (`landing_transition_system`)
+        LevelTransition::LeaveSurface => {
+            let atmosphere = state.planet.as_ref().map_or(AtmosphereType::None, |p| p.atmosphere);
+            landing.begin_ascent(transform.translation, atmosphere);
+            info!("Surface: taking off");
+        }
+        LevelTransition::LeaveSystem => {
+            focus.star_id = None;
+            cam.glide = Some(CameraGlide {
+                position: focus.return_pos,
+                yaw: cam.yaw,
+                pitch: cam.pitch,
+                rate: GLIDE_RATE,
+            });
+            if let Some(recorder) = recorder.as_deref_mut() {
+                record_teleport(recorder, universe.age, focus.return_pos);
+            }
+            cam.zoom_level = ZoomLevel::Stellar;
+            info!("Level: left system focus");
+        }
+        LevelTransition::ExitToCosmic => {
+            let region = lazy
                 .current_region_id
-                .and_then(|rid| lazy.regions.iter().find(|r| r.id == rid))
+                .and_then(|rid| lazy.regions.iter().find(|r| r.id == rid));
+            let target = region
                 .map(|r| {
                     Vec3::new(
                         r.center[0] as f32,
@@ -449,91 +1031,163 @@ pub fn surface_toggle_system(
                     )
                 })
                 .unwrap_or(transform.translation);
-            transform.translation = target + Vec3::new(0.0, 300.0, 600.0);
+            focus.region_return = region.map(|r| {
+                let here = CameraGlide {
+                    position: transform.translation,
+                    yaw: cam.yaw,
+                    pitch: cam.pitch,
+                    rate: GLIDE_RATE,
+                };
+                (r.id, here)
+            });
+            let overview = target + Vec3::new(0.0, 300.0, 600.0);
+            cam.glide = Some(CameraGlide::looking_at(overview, target));
+            if let Some(recorder) = recorder.as_deref_mut() {
+                record_teleport(recorder, universe.age, overview);
+            }
             cam.zoom_level = ZoomLevel::Cosmic;
             cam.tracking = None;
+            selection.selected_star = None;
             info!("Level: exited to Cosmic view");
         }
-        return;
-    }
-
-    // === B: enter selected region (teleport to region center) ===
-    if b_pressed {
-        if let Some(region_id) = selection.selected_region.take() {
-            if let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) {
-                let Ok((mut transform, mut cam)) = camera_query.get_single_mut() else {
-                    return;
-                };
-                let rc = Vec3::new(
-                    region.center[0] as f32,
-                    region.center[1] as f32,
-                    region.center[2] as f32,
-                );
-                transform.translation = rc + Vec3::new(0.0, 20.0, 50.0);
-                cam.zoom_level = ZoomLevel::Stellar;
-                cam.tracking = None;
-                selection.hovered_region = None;
-                selection.original_materials.clear();
-                info!(
-                    "Level: entered region #{} (density: {:.2}x, stars: {})",
-                    region_id, region.density, region.star_count
-                );
+        LevelTransition::EnterRegion(region_id) => {
+            selection.selected_region = None;
+            let Some(region) = lazy.regions.iter().find(|r| r.id == region_id) else {
+                return;
+            };
+            // Back to where [Esc] left this region, else the region's entry point
+            if let Some((_, back)) = focus.region_return.filter(|(id, _)| *id == region_id) {
+                cam.glide = Some(back);
+                if let Some(recorder) = recorder.as_deref_mut() {
+                    record_teleport(recorder, universe.age, back.position);
+                }
+            } else {
+                let entry = enter_region_view(&mut transform, &mut cam, region);
+                if let Some(recorder) = recorder.as_deref_mut() {
+                    record_teleport(recorder, universe.age, entry);
+                }
             }
-            return;
+            focus.region_return = None;
+            cam.zoom_level = ZoomLevel::Stellar;
+            cam.tracking = None;
+            selection.forget_region_sprites();
+            info!(
+                "Level: entered region #{} (density: {:.2}x, stars: {})",
+                region_id, region.density, region.star_count
+            );
         }
-    }
-
-    // === B: land on selected planet ===
-    if b_pressed {
-        let Ok((transform, cam)) = camera_query.get_single_mut() else {
-            return;
-        };
-
-        let planet_data = selection.selected_planet.take().or_else(|| {
-            if matches!(cam.zoom_level, ZoomLevel::Planetary | ZoomLevel::Surface) {
-                find_nearest_planet(&lazy, transform.translation)
-            } else {
-                info!("Select a planet (click) or a region (click) then press [B]");
-                None
+        LevelTransition::EnterSystem(star_id) => {
+            selection.selected_star = None;
+            let Some(star) = lazy.loaded_stars.iter().find(|s| s.id == star_id) else {
+                return;
+            };
+            let star_pos = Vec3::new(
+                star.position[0] as f32,
+                star.position[1] as f32,
+                star.position[2] as f32,
+            );
+            let framing = system_framing_position(star);
+            focus.star_id = Some(star_id);
+            focus.return_pos = transform.translation;
+            cam.glide = Some(CameraGlide::looking_at(framing, star_pos));
+            if let Some(recorder) = recorder.as_deref_mut() {
+                record_teleport(recorder, universe.age, framing);
             }
-        });
-
-        if let Some((planet, spectral)) = planet_data {
+            cam.zoom_level = ZoomLevel::Planetary;
+            cam.tracking = None;
             info!(
-                "Surface: landing on {:?} planet (id={})",
-                planet.planet_type, planet.id
+                "Level: focused system of star #{} ({} planets)",
+                star_id,
+                star.planets.len()
             );
-            state.space_return_pos = transform.translation;
-            state.terrain_seed = planet.id;
-            state.star_spectral = Some(spectral);
-            state.planet = Some(planet);
-            state.active = true;
-            state.eye_height = 2.0;
-            state.surface_zoom = SurfaceZoom::Ground;
-            state.generation = state.generation.wrapping_add(1);
+        }
+        LevelTransition::Land => {
+            let planet_data = selection.selected_planet.take().or_else(|| {
+                if matches!(cam.zoom_level, ZoomLevel::Planetary | ZoomLevel::Surface) {
+                    find_nearest_planet(&lazy, transform.translation)
+                } else {
+                    info!("Select a planet, a star or a region (click) then press [B]");
+                    None
+                }
+            });
 
-            selection.hovered = None;
-            selection.original_materials.clear();
+            // Giants have no surface, whether selected or the nearest: dive into them instead
+            if let Some((planet, _)) = planet_data.as_ref().filter(|(p, _)| p.planet_type.is_giant()) {
+                cam.glide = None;
+                dive.begin(planet.clone(), transform.translation);
+                selection.hovered = None;
+                selection.hovered_star = None;
+            } else if let Some((planet, spectral)) = planet_data {
+                info!(
+                    "Surface: landing on {:?} planet (id={})",
+                    planet.planet_type, planet.id
+                );
+                state.locked_side = planet
+                    .tidally_locked
+                    .then(|| locked_side_facing(&lazy, &planet, transform.translation));
+                cam.glide = None;
+                state.space_return_pos = transform.translation;
+                state.terrain_seed = planet.id;
+                state.star_spectral = Some(spectral);
+                state.planet = Some(planet);
+                state.active = true;
+                state.eye_height = 2.0;
+                state.surface_zoom = SurfaceZoom::Ground;
+                state.generation = state.generation.wrapping_add(1);
+
+                selection.hovered = None;
+                selection.hovered_star = None;
+                selection.original_materials.clear();
+            }
         }
     }
 }
 
 // --- Surface enter/exit system ---
 
+/// Everything spawned for the surface view (despawned on exit)
+pub(crate) type SurfaceEntities = Or<(
+    With<TerrainMesh>,
+    With<WaterPlane>,
+    With<SurfaceLight>,
+    With<Creature>,
+    With<SurfaceDetail>,
+    With<Microbe>,
+    With<SkyDomeStar>,
+    With<SkyBody>,
+    With<SkySatellite>,
+    With<HorizonGlow>,
+    With<WeatherParticle>,
+    With<SurfaceProp>,
+    With<HerdMarker>,
+    With<SurfaceMapUi>,
+)>;
+
+/// Asset stores the surface view is built into
+type SurfaceAssetStores<'w> = (
+    ResMut<'w, Assets<Mesh>>,
+    ResMut<'w, Assets<StandardMaterial>>,
+    ResMut<'w, Assets<Image>>,
+);
+
 pub fn surface_enter_exit_system(
     mut commands: Commands,
-    mut state: ResMut<SurfaceState>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut clear_color: ResMut<ClearColor>,
+    (mut state, mut chunks, mut clear_color, mut landing): (
+        ResMut<SurfaceState>,
+        ResMut<TerrainChunks>,
+        ResMut<ClearColor>,
+        ResMut<LandingTransition>,
+    ),
+    (mut lazy, ecology, weather, mut budget): (
+        ResMut<LazyUniverse>,
+        Res<SurfaceEcology>,
+        Res<WeatherState>,
+        ResMut<RenderBudget>,
+    ),
+    (mut meshes, mut materials, mut images): SurfaceAssetStores,
     mut camera_query: Query<(&mut Transform, &mut FlyCamera)>,
-    terrain_q: Query<Entity, With<TerrainMesh>>,
-    water_q: Query<Entity, With<WaterPlane>>,
-    light_q: Query<Entity, With<SurfaceLight>>,
-    creature_q: Query<Entity, With<Creature>>,
-    detail_q: Query<Entity, With<SurfaceDetail>>,
-    microbe_q: Query<Entity, With<Microbe>>,
-    sky_q: Query<Entity, With<SkyDomeStar>>,
+    surface_q: Query<Entity, SurfaceEntities>,
+    fog_q: Query<Entity, With<DistanceFog>>,
 ) {
     if state.generation == state.render_generation {
         return;
@@ -542,40 +1196,38 @@ pub fn surface_enter_exit_system(
 
     if state.active {
         // === ENTER SURFACE ===
+        // Pick up where the last landing on this planet left off
+        let memory = state
+            .planet
+            .as_ref()
+            .and_then(|p| lazy.surface_memory.get(p.id))
+            .cloned();
+        if let Some(ref memory) = memory {
+            state.eye_height = memory.eye_height;
+            state.surface_zoom = SurfaceZoom::from_height(memory.eye_height);
+        }
         let Some(ref planet) = state.planet else {
             return;
         };
-
-        // Terrain mesh with vertex-colored biomes
-        let terrain_mesh = build_terrain_mesh(state.terrain_seed, &planet.planet_type);
-        let terrain_mat = materials.add(StandardMaterial {
-            base_color: Color::WHITE, // vertex colors handle coloring
-            perceptual_roughness: 0.9,
-            ..default()
-        });
-        commands.spawn((
-            Mesh3d(meshes.add(terrain_mesh)),
-            MeshMaterial3d(terrain_mat),
-            Transform::IDENTITY,
-            TerrainMesh,
-        ));
-
-        // Water plane
-        if planet.has_water {
-            let water_mat = materials.add(StandardMaterial {
-                base_color: Color::srgba(0.1, 0.3, 0.8, 0.6),
-                alpha_mode: AlphaMode::Blend,
-                perceptual_roughness: 0.1,
-                ..default()
-            });
-            let water_mesh = meshes.add(Plane3d::default().mesh().size(TERRAIN_SIZE, TERRAIN_SIZE));
-            commands.spawn((
-                Mesh3d(water_mesh),
-                MeshMaterial3d(water_mat),
-                Transform::from_xyz(0.0, -0.5, 0.0),
-                WaterPlane,
-            ));
-        }
+        // Worked out while approaching the planet (`RegionDetail::Planetary`), if it was
+        let preset = lazy.surface_preset(planet.id).copied();
+        let planet_memory = lazy.surface_memory.entry(planet.id);
+        if planet_memory.visits == 0
+            && let Some(ref preset) = preset
+        {
+            planet_memory.weather_epoch = preset.weather_epoch;
+        }
+        planet_memory.visits += 1;
+
+        // Terrain chunks stream in around the camera from here on
+        let liquid = chunks.begin(
+            (state.terrain_seed, planet),
+            preset.as_ref(),
+            state.locked_side.map_or(Color::WHITE, LockedSide::terrain_tint),
+            &mut meshes,
+            &mut materials,
+            &mut images,
+        );
 
         // Sky color
         clear_color.0 = sky_color(&planet.atmosphere);
@@ -592,7 +1244,7 @@ pub fn surface_enter_exit_system(
         commands.spawn((
             DirectionalLight {
                 color: sun_color,
-                illuminance: 10_000.0,
+                illuminance: SUN_ILLUMINANCE,
                 shadows_enabled: false,
                 ..default()
             },
@@ -603,19 +1255,53 @@ pub fn surface_enter_exit_system(
         // Ambient light for terrain visibility
         commands.insert_resource(AmbientLight {
             color: sun_color,
-            brightness: 300.0,
+            brightness: DAY_AMBIENT,
         });
 
-        // Sky dome: scatter stars across a large sphere
-        spawn_sky_dome(&mut commands, &mut meshes, &mut materials, &planet.atmosphere);
-
-        // Creatures
-        spawn_creatures(&mut commands, &mut meshes, &mut materials, planet, state.terrain_seed);
+        // Sky dome: the same starfield for every planet of a star, then the star itself
+        // and the rest of its system
+        let star = lazy.loaded_stars.iter().find(|s| s.body(planet.id).is_some());
+        let region_seed = lazy
+            .current_region_id
+            .and_then(|id| lazy.regions.iter().find(|r| r.id == id))
+            .map(|r| r.seed);
+        let seed = match (region_seed, star) {
+            (Some(region_seed), Some(star)) => sky_seed(region_seed, star.position),
+            _ => DEFAULT_SKY_SEED,
+        };
+        spawn_sky_dome(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut budget,
+            &planet.atmosphere,
+            seed,
+        );
+        if let Some(star) = star {
+            spawn_system_sky(&mut commands, &mut meshes, &mut materials, star, planet);
+        }
+        spawn_tech_sky(&mut commands, &mut meshes, &mut materials, planet);
+
+        // Creatures, around the origin or where the player took off
+        let [x, _, z] = memory.as_ref().map_or([0.0; 3], |m| m.position);
+        let eaten = memory.as_ref().map_or(&[][..], |m| &m.eaten[..]);
+        spawn_creatures(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut budget,
+            (state.terrain_seed, planet),
+            Vec3::new(x, 0.0, z),
+            eaten,
+        );
 
-        // Teleport camera
+        // Camera high above the spot, coming down to eye height
         if let Ok((mut transform, mut cam)) = camera_query.get_single_mut() {
-            let ground_y = terrain_height(0.0, 0.0, state.terrain_seed, &planet.planet_type);
-            transform.translation = Vec3::new(0.0, ground_y + state.eye_height, 0.0);
+            let ground_y = terrain_height(x, z, state.terrain_seed, &planet.planet_type);
+            let floor = liquid.map_or(ground_y, |l| ground_y.max(l.level));
+            landing.cancel();
+            landing.begin_descent(Vec3::new(x, floor + state.eye_height, z), planet.atmosphere);
+            transform.translation = landing.camera_position();
             cam.yaw = 0.0;
             cam.pitch = 0.0;
             transform.rotation = Quat::IDENTITY;
@@ -626,33 +1312,42 @@ pub fn surface_enter_exit_system(
         } else {
             "barren"
         };
+        let liquid_str = liquid.map_or("none".to_string(), |l| {
+            format!("{} at {:.1} m", l.kind.name(), l.level)
+        });
         info!(
-            "Surface: spawned {:?} terrain ({}) | water={} | atmo={:?}",
-            planet.planet_type, life_str, planet.has_water, planet.atmosphere
+            "Surface: spawned {:?} terrain ({}) | liquid={} | atmo={:?}",
+            planet.planet_type, life_str, liquid_str, planet.atmosphere
         );
+        state.liquid = liquid;
+        state.sky_seed = seed;
+        state.underwater = false;
+        state.grounded = true;
+        state.vertical_speed = 0.0;
     } else {
         // === EXIT SURFACE ===
-        for entity in terrain_q.iter() {
-            commands.entity(entity).despawn();
-        }
-        for entity in water_q.iter() {
-            commands.entity(entity).despawn();
+        landing.cancel();
+        if let Some(ref planet) = state.planet
+            && let Ok((transform, _)) = camera_query.get_single()
+        {
+            let memory = lazy.surface_memory.entry(planet.id);
+            memory.position = transform.translation.to_array();
+            memory.eye_height = state.eye_height;
+            memory.weather_epoch = weather.epoch;
+            memory.eaten = ecology.eaten.clone();
         }
-        for entity in light_q.iter() {
+
+        for entity in surface_q.iter() {
             commands.entity(entity).despawn();
         }
-        for entity in creature_q.iter() {
-            commands.entity(entity).despawn();
+        for category in Category::SURFACE {
+            budget.release(category);
         }
-        for entity in detail_q.iter() {
-            commands.entity(entity).despawn();
-        }
-        for entity in microbe_q.iter() {
-            commands.entity(entity).despawn();
-        }
-        for entity in sky_q.iter() {
-            commands.entity(entity).despawn();
+        chunks.clear();
+        for entity in fog_q.iter() {
+            commands.entity(entity).remove::<DistanceFog>();
         }
+        commands.insert_resource(WeatherState::default());
 
         // Reset ambient light
         commands.insert_resource(AmbientLight {
@@ -670,42 +1365,58 @@ pub fn surface_enter_exit_system(
         state.star_spectral = None;
         state.eye_height = 2.0;
         state.surface_zoom = SurfaceZoom::Ground;
+        state.liquid = None;
+        state.underwater = false;
+        state.grounded = true;
+        state.vertical_speed = 0.0;
         info!("Surface: returned to space");
     }
 }
 
 // --- Surface camera system ---
 
+/// First-person walking: WASD / left stick on the ground under planet gravity, Space to jump
+/// (not at microscopic zoom), no climbing slopes steeper than `MAX_CLIMB_SLOPE` —
+/// standing on one slides downhill. Water floats the camera; ice and lava crust carry it.
 pub fn surface_camera_system(
     time: Res<Time>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
     mouse_motion: Res<AccumulatedMouseMotion>,
     mouse_scroll: Res<AccumulatedMouseScroll>,
+    capture: Res<UiInputCapture>,
     mut state: ResMut<SurfaceState>,
     mut query: Query<(&mut Transform, &mut FlyCamera)>,
 ) {
     let Ok((mut transform, mut cam)) = query.get_single_mut() else {
         return;
     };
-    let Some(planet_type) = state.planet.as_ref().map(|p| p.planet_type) else {
+    let Some((planet_type, gravity)) = state
+        .planet
+        .as_ref()
+        .map(|p| (p.planet_type, p.surface_gravity() as f32))
+    else {
         return;
     };
     let terrain_seed = state.terrain_seed;
 
     let dt = time.delta_secs();
 
-    // Mouse look (always active on surface)
-    let delta = mouse_motion.delta;
-    if delta.length_squared() > 0.0 {
-        cam.yaw -= delta.x * cam.sensitivity;
-        cam.pitch -= delta.y * cam.sensitivity;
-        cam.pitch = cam.pitch.clamp(-1.5, 1.5);
+    // Mouse look (unless the UI has the mouse) and right stick look
+    let mouse_look = if capture.blocks_mouse() {
+        Vec2::ZERO
+    } else {
+        controls.settings.look(mouse_motion.delta)
+    };
+    let look = mouse_look + controls.stick_look(dt);
+    if look.length_squared() > 0.0 {
+        cam.yaw += look.x;
+        cam.pitch = (cam.pitch + look.y).clamp(-1.5, 1.5);
     }
     transform.rotation = Quat::from_euler(EulerRot::YXZ, cam.yaw, cam.pitch, 0.0);
 
-    // Scroll wheel adjusts eye height
+    // Scroll wheel adjusts eye height, only with feet on the ground
     let scroll = mouse_scroll.delta.y;
-    if scroll != 0.0 {
+    if scroll != 0.0 && state.grounded && !capture.blocks_mouse() {
         let factor = 1.0 - scroll * 0.15;
         state.eye_height = (state.eye_height * factor).clamp(0.05, 10.0);
         let new_zoom = SurfaceZoom::from_height(state.eye_height);
@@ -719,26 +1430,26 @@ pub fn surface_camera_system(
         }
     }
 
-    // WASD on XZ plane
+    // WASD / left stick on XZ plane
     let forward = *transform.forward();
     let forward_xz = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
     let right_xz = Vec3::new(forward.z, 0.0, -forward.x).normalize_or_zero();
 
     let mut velocity = Vec3::ZERO;
-    if keyboard.pressed(KeyCode::KeyW) {
+    if controls.pressed(Action::MoveForward) {
         velocity += forward_xz;
     }
-    if keyboard.pressed(KeyCode::KeyS) {
+    if controls.pressed(Action::MoveBack) {
         velocity -= forward_xz;
     }
-    if keyboard.pressed(KeyCode::KeyA) {
+    if controls.pressed(Action::MoveLeft) {
         velocity -= right_xz;
     }
-    if keyboard.pressed(KeyCode::KeyD) {
+    if controls.pressed(Action::MoveRight) {
         velocity += right_xz;
     }
 
-    let boost = if keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight) {
+    let boost = if controls.pressed(Action::Boost) {
         3.0
     } else {
         1.0
@@ -747,66 +1458,242 @@ pub fn surface_camera_system(
     // Walk speed scales with height
     let speed_mult = (state.eye_height / 2.0).clamp(0.1, 3.0);
 
+    // Keys walk at full speed, the stick in proportion to its tilt
+    let movement = controls.gamepad.movement;
+    velocity = (velocity.normalize_or_zero() + forward_xz * movement.y + right_xz * movement.x)
+        .clamp_length_max(1.0);
+
+    let mut step = Vec2::ZERO;
     if velocity.length_squared() > 0.0 {
-        velocity = velocity.normalize();
-        transform.translation += velocity * WALK_SPEED * speed_mult * boost * dt;
+        let walk = velocity * controls.settings.walk_speed * speed_mult * boost * dt;
+        step = Vec2::new(walk.x, walk.z);
     }
 
-    // Clamp to terrain bounds
-    let half = TERRAIN_SIZE / 2.0 * 0.95;
-    transform.translation.x = transform.translation.x.clamp(-half, half);
-    transform.translation.z = transform.translation.z.clamp(-half, half);
+    // Where the camera rests at (x, z), and whether that's on the terrain itself
+    let rest = |x: f32, z: f32| {
+        let ground_y = terrain_height(x, z, terrain_seed, &planet_type);
+        let walk_y = ground_y + state.eye_height;
+        match state.liquid {
+            // Deep water: swim at the surface, or dip under it at microscopic zoom
+            Some(SurfaceLiquid { kind: LiquidKind::Water, level })
+                if walk_y < level + SWIM_EYE_HEIGHT =>
+            {
+                if state.surface_zoom == SurfaceZoom::Microscopic {
+                    ((level - DIVE_DEPTH).max(walk_y), false)
+                } else {
+                    (level + SWIM_EYE_HEIGHT, false)
+                }
+            }
+            // Ice sheets and lava crust carry the player like ground
+            Some(SurfaceLiquid { level, .. }) if ground_y < level => (level + state.eye_height, false),
+            _ => (walk_y, true),
+        }
+    };
+
+    let from = transform.translation.xz();
+    let (rest_from, on_terrain) = rest(from.x, from.y);
+    if state.grounded && on_terrain {
+        let normal = terrain_normal(from.x, from.y, terrain_seed, &planet_type);
+        // Too steep to stand on: slide downhill
+        let downhill = Vec2::new(normal[0], normal[2]) / normal[1].max(1e-3);
+        if downhill.length() > MAX_CLIMB_SLOPE {
+            step += downhill.normalize() * SLIDE_SPEED * dt;
+        }
+        let to = from + step;
+        step = limit_climb(step, terrain_normal(to.x, to.y, terrain_seed, &planet_type));
+    }
+    transform.translation.x += step.x;
+    transform.translation.z += step.y;
+    let (rest_y, _) = rest(transform.translation.x, transform.translation.z);
 
-    // Snap to ground + eye height
-    let ground_y = terrain_height(
-        transform.translation.x,
-        transform.translation.z,
-        terrain_seed,
-        &planet_type,
-    );
-    transform.translation.y = ground_y + state.eye_height;
+    if state.grounded
+        && state.surface_zoom != SurfaceZoom::Microscopic
+        && controls.just_pressed(Action::Jump)
+    {
+        state.grounded = false;
+        state.vertical_speed = JUMP_SPEED;
+    }
+    if state.grounded {
+        // Walking off a drop steeper than the climb limit leaves the ground
+        if rest_from - rest_y > step.length() * MAX_CLIMB_SLOPE + STEP_TOLERANCE {
+            state.grounded = false;
+            state.vertical_speed = 0.0;
+        } else {
+            transform.translation.y = rest_y;
+        }
+    }
+    if !state.grounded {
+        state.vertical_speed -= gravity * dt;
+        transform.translation.y += state.vertical_speed * dt;
+        // Landing just puts the feet back on the ground
+        if transform.translation.y <= rest_y {
+            transform.translation.y = rest_y;
+            state.grounded = true;
+            state.vertical_speed = 0.0;
+        }
+    }
+
+    let underwater = state
+        .liquid
+        .is_some_and(|l| l.kind == LiquidKind::Water && transform.translation.y < l.level);
+    if state.underwater != underwater {
+        state.underwater = underwater;
+    }
+}
+
+/// Remove the uphill part of a step onto ground steeper than `MAX_CLIMB_SLOPE`
+fn limit_climb(step: Vec2, normal: [f32; 3]) -> Vec2 {
+    let uphill = -Vec2::new(normal[0], normal[2]) / normal[1].max(1e-3);
+    if uphill.length() <= MAX_CLIMB_SLOPE {
+        return step;
+    }
+    let dir = uphill.normalize();
+    let climb = step.dot(dir);
+    if climb > 0.0 { step - dir * climb } else { step }
 }
 
 // --- Creature systems ---
 
+/// Move surface creatures. In complex biospheres predators chase the nearest herbivore
+/// they can sense, herbivores flee from nearby predators, a catch removes the prey
+/// and eaten creatures respawn at their spawn points over time. Wanderers pick targets
+/// around the camera, so life keeps up with the player on the unbounded terrain.
+/// Life that only senses light rests through the night. Every creature eases toward its
+/// height over the terrain each frame, moving or not; flyers bob at cruise height and
+/// land while frozen, feeding or resting.
 pub fn creature_behavior_system(
+    mut commands: Commands,
     time: Res<Time>,
-    state: Res<SurfaceState>,
-    mut query: Query<(&mut Transform, &mut Creature)>,
+    (state, day, weather): (Res<SurfaceState>, Res<SurfaceDay>, Res<WeatherState>),
+    mut ecology: ResMut<SurfaceEcology>,
+    mut query: Query<(Entity, &mut Transform, &mut Creature)>,
+    camera_q: Query<&Transform, (With<FlyCamera>, Without<Creature>)>,
 ) {
     let Some(ref planet) = state.planet else {
         return;
     };
+    // Parked as herds up there (`creature_lod_system`): nothing to move, nothing respawns
+    if state.surface_zoom == SurfaceZoom::Landscape {
+        return;
+    }
+    let Ok(cam_tf) = camera_q.get_single() else {
+        return;
+    };
+    let cam_pos = Vec3::new(cam_tf.translation.x, 0.0, cam_tf.translation.z);
     let dt = time.delta_secs();
     let elapsed = time.elapsed_secs();
 
-    for (mut transform, mut creature) in query.iter_mut() {
-        if creature.speed < 0.01 {
+    let (seed, planet_type) = (state.terrain_seed, planet.planet_type);
+    let ground = |x: f32, z: f32| terrain_height(x, z, seed, &planet_type);
+
+    let sight_only = planet
+        .life
+        .as_ref()
+        .is_some_and(|bio| bio.dominant_genome.senses == 1);
+    let night = sight_only && day.daylight < 0.2;
+    for (entity, mut transform, mut creature) in query.iter_mut() {
+        if night {
+            creature.state = CreatureState::Resting;
+            creature.target = None;
+        } else if creature.state == CreatureState::Resting {
+            creature.state = CreatureState::Wandering;
+        }
+        // Every creature eases toward its height, moving or not (flyers land to rest)
+        let pos = transform.translation;
+        let phase = bob_phase(entity);
+        let target_y = creature.target_height(ground(pos.x, pos.z), transform.scale.x, elapsed, phase);
+        transform.translation.y = creature.settle_height(pos.y, target_y, dt);
+    }
+    if night {
+        return;
+    }
+
+    // Where everyone is this frame: pursuit follows the target's current position
+    let positions: HashMap<Entity, (Vec3, Option<Diet>, usize)> = query
+        .iter()
+        .map(|(entity, tf, c)| (entity, (tf.translation, c.diet, c.species)))
+        .collect();
+
+    ecology.scan_frame = ecology.scan_frame.wrapping_add(1);
+    if ecology.detection_radius > 0.0 && ecology.scan_frame.is_multiple_of(ECOLOGY_SCAN_FRAMES) {
+        let radius = ecology.detection_radius;
+        for (_, tf, mut creature) in query.iter_mut() {
+            let pos = tf.translation;
+            let frozen = matches!(creature.state, CreatureState::Frozen { .. });
+            match creature.diet {
+                Some(Diet::Predator) if !matches!(creature.state, CreatureState::Feeding { .. }) => {
+                    creature.target = nearest_within(&positions, pos, radius, Diet::Herbivore)
+                        .map(|(entity, _)| entity);
+                    if creature.target.is_some() {
+                        creature.state = CreatureState::Hunting;
+                    } else if !frozen {
+                        creature.state = CreatureState::Wandering;
+                    }
+                }
+                // Prey that senses a predator runs, frozen or not
+                Some(Diet::Herbivore) => {
+                    creature.threat = nearest_within(&positions, pos, radius, Diet::Predator)
+                        .map(|(_, threat)| threat);
+                    if creature.threat.is_some() {
+                        creature.state = CreatureState::Fleeing;
+                    } else if !frozen {
+                        creature.state = CreatureState::Grazing;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut caught: Vec<Entity> = Vec::new();
+    for (entity, mut transform, mut creature) in query.iter_mut() {
+        if caught.contains(&entity) || creature.speed < 0.01 {
             continue;
         }
+        let pos = transform.translation;
+        creature.tick(dt);
+
+        match creature.state {
+            CreatureState::Feeding { .. } | CreatureState::Frozen { .. } => continue,
+            CreatureState::Hunting => {
+                let prey = creature
+                    .target
+                    .filter(|t| !caught.contains(t))
+                    .and_then(|t| positions.get(&t).map(|(p, _, species)| (t, *p, *species)));
+                let Some((prey, prey_pos, prey_species)) = prey else {
+                    creature.target = None;
+                    creature.state = CreatureState::Wandering;
+                    continue;
+                };
+                let reach = CATCH_DIST + transform.scale.x;
+                if horizontal_dist(pos, prey_pos) < reach {
+                    commands.entity(prey).despawn();
+                    caught.push(prey);
+                    ecology.eaten.push(prey_species);
+                    creature.target = None;
+                    creature.state = CreatureState::Feeding { timer: FEED_SECS };
+                    continue;
+                }
+                let speed = creature.speed * PURSUIT_SPEED;
+                step_towards(&mut transform, prey_pos, speed, dt);
+                continue;
+            }
+            CreatureState::Fleeing => {
+                if let Some(threat) = creature.threat {
+                    let away = Vec3::new(pos.x - threat.x, 0.0, pos.z - threat.z).normalize_or_zero();
+                    let speed = creature.speed * PURSUIT_SPEED;
+                    step_towards(&mut transform, pos + away * 10.0, speed, dt);
+                    continue;
+                }
+            }
+            _ => {}
+        }
 
         creature.wander_timer -= dt;
 
-        let dir = Vec3::new(
-            creature.wander_target.x - transform.translation.x,
-            0.0,
-            creature.wander_target.z - transform.translation.z,
-        );
-        let dist = dir.length();
-
+        let dist = horizontal_dist(pos, creature.wander_target);
         if dist > 1.0 {
-            let move_dir = dir.normalize();
-            transform.translation.x += move_dir.x * creature.speed * dt;
-            transform.translation.z += move_dir.z * creature.speed * dt;
-
-            let y = terrain_height(
-                transform.translation.x,
-                transform.translation.z,
-                state.terrain_seed,
-                &planet.planet_type,
-            );
-            let hover = if creature.is_flying { 3.0 } else { 0.0 };
-            transform.translation.y = y + transform.scale.x * 0.5 + hover;
+            step_towards(&mut transform, creature.wander_target, creature.speed, dt);
         }
 
         if dist < 2.0 || creature.wander_timer < 0.0 {
@@ -814,54 +1701,215 @@ pub fn creature_behavior_system(
                 .wrapping_mul(((transform.translation.z * 100.0) as u64).wrapping_add(1))
                 .wrapping_add(elapsed as u64);
             let mut rng = ChaCha8Rng::seed_from_u64(hash);
-            let half = TERRAIN_SIZE / 2.0 * 0.8;
-            creature.wander_target =
-                Vec3::new(rng.gen_range(-half..half), 0.0, rng.gen_range(-half..half));
+            // Wanderers drift with the wind
+            let offset = Vec3::new(
+                rng.gen_range(-WANDER_RANGE..WANDER_RANGE),
+                0.0,
+                rng.gen_range(-WANDER_RANGE..WANDER_RANGE),
+            );
+            creature.wander_target = cam_pos + offset + weather.wind * WIND_DRIFT_SECS;
             creature.wander_timer = rng.gen_range(3.0..10.0);
         }
     }
+
+    // Eaten creatures grow back one at a time
+    if ecology.eaten.is_empty() {
+        return;
+    }
+    ecology.respawn_timer -= dt;
+    if ecology.respawn_timer > 0.0 {
+        return;
+    }
+    ecology.respawn_timer = RESPAWN_SECS;
+    let Some(genome) = planet.life.as_ref().map(|bio| &bio.dominant_genome) else {
+        return;
+    };
+    let species_idx = ecology.eaten.remove(0);
+    let mut rng = ChaCha8Rng::seed_from_u64(state.terrain_seed.wrapping_add(elapsed as u64));
+    if let Some(&offset) = ecology.spawn_points.get(rng.gen_range(0..ecology.spawn_points.len().max(1))) {
+        spawn_creature(
+            &mut commands,
+            &ecology,
+            species_idx,
+            genome,
+            cam_pos + offset,
+            (state.terrain_seed, &planet.planet_type),
+            &mut rng,
+        );
+    }
+}
+
+/// Nearest creature of `diet` within `radius` of `pos` (horizontal distance)
+fn nearest_within(
+    positions: &HashMap<Entity, (Vec3, Option<Diet>, usize)>,
+    pos: Vec3,
+    radius: f32,
+    diet: Diet,
+) -> Option<(Entity, Vec3)> {
+    positions
+        .iter()
+        .filter(|(_, (_, d, _))| *d == Some(diet))
+        .map(|(entity, (p, _, _))| (*entity, *p, horizontal_dist(pos, *p)))
+        .filter(|(_, _, dist)| *dist < radius)
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(entity, p, _)| (entity, p))
 }
 
+fn horizontal_dist(a: Vec3, b: Vec3) -> f32 {
+    Vec2::new(a.x - b.x, a.z - b.z).length()
+}
+
+/// Move a creature horizontally towards `goal`; its height follows in `creature_behavior_system`
+fn step_towards(transform: &mut Transform, goal: Vec3, speed: f32, dt: f32) {
+    let dir = Vec3::new(
+        goal.x - transform.translation.x,
+        0.0,
+        goal.z - transform.translation.z,
+    )
+    .normalize_or_zero();
+    transform.translation.x += dir.x * speed * dt;
+    transform.translation.z += dir.z * speed * dt;
+}
+
+/// Phase of a flying creature's bob, so a flock doesn't rise and fall in step
+fn bob_phase(entity: Entity) -> f32 {
+    entity.index() as f32 * 2.4
+}
+
+/// Nearest creature for the HUD, inspection and calls, every `PROXIMITY_FRAMES` frames
 pub fn creature_proximity_system(
     state: Res<SurfaceState>,
     camera_q: Query<&Transform, With<FlyCamera>>,
     mut creature_q: Query<(&Transform, &mut Creature), Without<FlyCamera>>,
     mut nearest_info: ResMut<NearestCreatureInfo>,
+    mut frame: Local<u32>,
 ) {
     let Some(ref planet) = state.planet else {
         return;
     };
+    let scan = *frame;
+    *frame = frame.wrapping_add(1);
+    if !scan.is_multiple_of(PROXIMITY_FRAMES) {
+        return;
+    }
     let Ok(cam_tf) = camera_q.get_single() else {
         return;
     };
 
     let mut closest_dist = f32::MAX;
+    let mut closest = None;
 
     for (tf, mut creature) in creature_q.iter_mut() {
         let dist = cam_tf.translation.distance(tf.translation);
         if dist < closest_dist {
             closest_dist = dist;
+            closest = Some((creature.species, creature.traits.clone(), creature.state));
+            nearest_info.position = tf.translation;
         }
         // Freeze creature when observer is very close
-        if dist < 3.0 {
-            creature.wander_timer = 5.0;
-            creature.wander_target = tf.translation;
+        if dist < FREEZE_DIST {
+            creature.freeze();
         }
     }
 
     nearest_info.distance = closest_dist;
 
     if closest_dist < 5.0 {
-        if let Some(ref bio) = planet.life {
+        if let (Some(bio), Some((species, traits, creature_state))) = (&planet.life, closest) {
             nearest_info.description = format!(
-                "CREATURE (dist: {:.1}m)\n{}\nSenses: {}",
+                "CREATURE (dist: {:.1}m)\n{} — {}\n{}\nSenses: {}",
                 closest_dist,
+                traits.describe(bio.dominant_genome.size_log),
+                creature_state.name(),
                 bio.dominant_genome.describe(),
                 bio.dominant_genome.sense_list().join(", ")
             );
+            nearest_info.species = species;
+            nearest_info.traits = Some(traits);
+            nearest_info.state = creature_state;
         }
     } else {
         nearest_info.description.clear();
+        nearest_info.traits = None;
+    }
+}
+
+/// Herd markers, kept apart from the creatures they stand for
+type HerdMarkers = (With<HerdMarker>, Without<Creature>);
+/// The camera, kept apart from creatures and herd markers
+type LodCamera = (With<FlyCamera>, Without<Creature>, Without<HerdMarker>);
+
+/// Above Landscape height creatures are specks: park them (where they are and what they
+/// are doing) and show one herd marker per species instead. Coming back down puts them
+/// back as they were.
+pub fn creature_lod_system(
+    mut commands: Commands,
+    state: Res<SurfaceState>,
+    mut ecology: ResMut<SurfaceEcology>,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    creature_q: Query<(Entity, &Transform, &Creature)>,
+    mut herd_q: Query<(Entity, &mut Transform), HerdMarkers>,
+    camera_q: Query<&Transform, LodCamera>,
+) {
+    let ecology = &mut *ecology;
+    let landscape = state.surface_zoom == SurfaceZoom::Landscape;
+    if landscape && !creature_q.is_empty() {
+        let mut herds = vec![(Vec3::ZERO, 0usize); ecology.species.len()];
+        for (entity, transform, creature) in &creature_q {
+            if let Some((sum, count)) = herds.get_mut(creature.species) {
+                *sum += transform.translation;
+                *count += 1;
+            }
+            // Prey entities don't survive the trip: hunters pick a new target
+            let creature = Creature {
+                target: None,
+                ..creature.clone()
+            };
+            ecology.parked.push((*transform, creature));
+            commands.entity(entity).despawn();
+        }
+        let quad = meshes.add(Rectangle::new(1.0, 1.0));
+        for ((sum, count), (_, material, _)) in herds.into_iter().zip(&ecology.species) {
+            if count == 0 {
+                continue;
+            }
+            let color = materials.get(material).map_or(Color::WHITE, |m| m.base_color);
+            let size = HERD_MARKER_SIZE * (count as f32).sqrt();
+            commands.spawn((
+                Mesh3d(quad.clone()),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: color,
+                    unlit: true,
+                    ..default()
+                })),
+                Transform::from_translation(sum / count as f32 + Vec3::Y * size)
+                    .with_scale(Vec3::splat(size)),
+                HerdMarker,
+            ));
+        }
+    } else if !landscape && !ecology.parked.is_empty() {
+        for (entity, _) in &herd_q {
+            commands.entity(entity).despawn();
+        }
+        for (transform, creature) in ecology.parked.drain(..) {
+            let Some((mesh, material, _)) = ecology.species.get(creature.species) else {
+                continue;
+            };
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                transform,
+                creature,
+            ));
+        }
+    }
+
+    // Billboards: face the camera
+    let Ok(cam_tf) = camera_q.get_single() else {
+        return;
+    };
+    for (_, mut transform) in &mut herd_q {
+        transform.rotation = cam_tf.rotation;
     }
 }
 
@@ -869,12 +1917,12 @@ pub fn creature_proximity_system(
 
 pub fn surface_detail_system(
     mut commands: Commands,
-    state: Res<SurfaceState>,
-    mut detail_state: ResMut<DetailState>,
+    (state, weather): (Res<SurfaceState>, Res<WeatherState>),
+    (mut detail_state, mut budget): (ResMut<DetailState>, ResMut<RenderBudget>),
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     camera_q: Query<&Transform, With<FlyCamera>>,
-    detail_q: Query<Entity, With<SurfaceDetail>>,
+    detail_q: Query<(Entity, &Transform), With<SurfaceDetail>>,
 ) {
     let Some(ref planet) = state.planet else {
         return;
@@ -885,16 +1933,24 @@ pub fn surface_detail_system(
 
     let cam_pos = cam_tf.translation;
 
-    // Only respawn if camera moved enough
+    // Only respawn if camera moved enough (or the budget changed); until then drop what
+    // it left out of sight
     if cam_pos.distance(detail_state.last_spawn_pos) < DETAIL_RESPAWN_DIST
-        && detail_q.iter().count() > 0
+        && !detail_q.is_empty()
+        && detail_state.budget_generation == budget.generation()
     {
+        for (entity, tf) in &detail_q {
+            if tf.translation.distance(cam_pos) > DETAIL_CULL_DIST {
+                commands.entity(entity).despawn();
+            }
+        }
         return;
     }
     detail_state.last_spawn_pos = cam_pos;
+    detail_state.budget_generation = budget.generation();
 
     // Despawn old
-    for entity in detail_q.iter() {
+    for (entity, _) in detail_q.iter() {
         commands.entity(entity).despawn();
     }
 
@@ -929,8 +1985,33 @@ pub fn surface_detail_system(
                 ..default()
             }),
         ),
+        PlanetType::Barren => (
+            meshes.add(Sphere::new(0.3).mesh().ico(0).unwrap()),
+            materials.add(StandardMaterial {
+                base_color: Color::srgb(0.4, 0.39, 0.37),
+                perceptual_roughness: 1.0,
+                ..default()
+            }),
+        ),
         _ => return, // no details for gas/ice giants
     };
+    // Once life reaches complexity 3, half the ground cover is tufts of its vegetation
+    let undergrowth = planet
+        .life
+        .as_ref()
+        .filter(|bio| bio.complexity >= 3.0 && planet.planet_type != PlanetType::Lava)
+        .map(|bio| {
+            (
+                meshes.add(Cone {
+                    radius: 0.12,
+                    height: 0.5,
+                }),
+                materials.add(StandardMaterial {
+                    base_color: substrate_color(bio.dominant_genome.substrate).darker(0.1),
+                    ..default()
+                }),
+            )
+        });
 
     let mut rng = ChaCha8Rng::seed_from_u64(
         state
@@ -939,118 +2020,33 @@ pub fn surface_detail_system(
             .wrapping_add((cam_pos.z * 10.0) as u64),
     );
 
-    for _ in 0..MAX_DETAIL {
-        let dx = rng.gen_range(-DETAIL_RANGE..DETAIL_RANGE);
-        let dz = rng.gen_range(-DETAIL_RANGE..DETAIL_RANGE);
+    for i in 0..budget.request(Category::SurfaceDetail, MAX_DETAIL) {
+        // Loose debris ends up scattered downwind
+        let drift = weather.wind * rng.gen_range(0.0..WIND_DRIFT_SECS);
+        let dx = rng.gen_range(-DETAIL_RANGE..DETAIL_RANGE) + drift.x;
+        let dz = rng.gen_range(-DETAIL_RANGE..DETAIL_RANGE) + drift.z;
         let x = cam_pos.x + dx;
         let z = cam_pos.z + dz;
 
-        let half = TERRAIN_SIZE / 2.0 * 0.95;
-        if x.abs() > half || z.abs() > half {
-            continue;
-        }
-
         let y = terrain_height(x, z, state.terrain_seed, &planet.planet_type);
         let scale = rng.gen_range(0.5..1.5);
+        if Vec3::new(x, y, z).distance(cam_pos) > DETAIL_CULL_DIST {
+            continue;
+        }
+        let (mesh, material) = match undergrowth {
+            Some((ref mesh, ref material)) if i % 2 == 0 => (mesh, material),
+            _ => (&detail_mesh, &detail_mat),
+        };
 
         commands.spawn((
-            Mesh3d(detail_mesh.clone()),
-            MeshMaterial3d(detail_mat.clone()),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
             Transform::from_xyz(x, y + scale * 0.2, z).with_scale(Vec3::splat(scale)),
             SurfaceDetail,
         ));
     }
 }
 
-// --- Microbe system ---
-
-pub fn surface_microbe_system(
-    mut commands: Commands,
-    state: Res<SurfaceState>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    time: Res<Time>,
-    camera_q: Query<&Transform, With<FlyCamera>>,
-    mut microbe_q: Query<(Entity, &mut Transform, &Microbe), Without<FlyCamera>>,
-) {
-    let Some(ref planet) = state.planet else {
-        return;
-    };
-
-    if state.surface_zoom != SurfaceZoom::Microscopic {
-        // Despawn all microbes when not microscopic
-        for (entity, _, _) in microbe_q.iter() {
-            commands.entity(entity).despawn();
-        }
-        return;
-    }
-
-    let Ok(cam_tf) = camera_q.get_single() else {
-        return;
-    };
-    let cam_pos = cam_tf.translation;
-    let dt = time.delta_secs();
-
-    // Move existing + despawn if too far
-    let mut count = 0;
-    for (entity, mut tf, microbe) in microbe_q.iter_mut() {
-        tf.translation += microbe.drift_dir * microbe.drift_speed * dt;
-        if tf.translation.distance(cam_pos) > MICROBE_RANGE * 3.0 {
-            commands.entity(entity).despawn();
-        } else {
-            count += 1;
-        }
-    }
-
-    // Spawn new
-    if count < MAX_MICROBES {
-        let microbe_mesh = meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap());
-        let color = if planet.life.is_some() {
-            Color::srgba(0.2, 0.8, 0.3, 0.7)
-        } else {
-            Color::srgba(0.5, 0.5, 0.6, 0.4)
-        };
-        let microbe_mat = materials.add(StandardMaterial {
-            base_color: color,
-            alpha_mode: AlphaMode::Blend,
-            ..default()
-        });
-
-        let mut rng = ChaCha8Rng::seed_from_u64(
-            state
-                .terrain_seed
-                .wrapping_add(time.elapsed_secs() as u64)
-                .wrapping_add(count as u64),
-        );
-
-        let to_spawn = (MAX_MICROBES - count).min(5);
-        for _ in 0..to_spawn {
-            let offset = Vec3::new(
-                rng.gen_range(-MICROBE_RANGE..MICROBE_RANGE),
-                rng.gen_range(-MICROBE_RANGE * 0.5..MICROBE_RANGE * 0.5),
-                rng.gen_range(-MICROBE_RANGE..MICROBE_RANGE),
-            );
-            let scale = rng.gen_range(0.002..0.01);
-            let drift_dir = Vec3::new(
-                rng.gen_range(-1.0..1.0),
-                rng.gen_range(-0.5..0.5),
-                rng.gen_range(-1.0..1.0),
-            )
-            .normalize_or_zero();
-
-            commands.spawn((
-                Mesh3d(microbe_mesh.clone()),
-                MeshMaterial3d(microbe_mat.clone()),
-                Transform::from_translation(cam_pos + offset).with_scale(Vec3::splat(scale)),
-                Microbe {
-                    drift_speed: rng.gen_range(0.01..0.05),
-                    drift_dir,
-                },
-            ));
-        }
-    }
-}
-
 // --- Helpers ---
 
 fn find_nearest_planet(lazy: &LazyUniverse, cam_pos: Vec3) -> Option<(Planet, SpectralClass)> {
@@ -1069,7 +2065,7 @@ fn find_nearest_planet(lazy: &LazyUniverse, cam_pos: Vec3) -> Option<(Planet, Sp
             let pz = star_pos.z + (orbit_r * planet.orbital_angle.sin()) as f32;
             let dist = cam_pos.distance(Vec3::new(px, py, pz));
 
-            let closer = best.as_ref().map_or(true, |(_, _, d)| dist < *d);
+            let closer = best.as_ref().is_none_or(|(_, _, d)| dist < *d);
             if closer {
                 best = Some((planet.clone(), star.spectral_class, dist));
             }
@@ -1079,180 +2075,437 @@ fn find_nearest_planet(lazy: &LazyUniverse, cam_pos: Vec3) -> Option<(Planet, Sp
     best.map(|(p, s, _)| (p, s))
 }
 
-fn terrain_height(x: f32, z: f32, seed: u64, planet_type: &PlanetType) -> f32 {
-    let s = seed as f32 * 0.0001;
-    let amplitude = match planet_type {
-        PlanetType::Rocky => 20.0,
-        PlanetType::Ocean => 6.0,
-        PlanetType::Frozen => 12.0,
-        PlanetType::Lava => 25.0,
-        PlanetType::GasGiant => 2.0,
-        PlanetType::IceGiant => 4.0,
+/// Side of a tidally locked planet the camera comes down on, from where it is
+/// relative to the planet and its star (terminator if the star isn't loaded)
+fn locked_side_facing(lazy: &LazyUniverse, planet: &Planet, cam_pos: Vec3) -> LockedSide {
+    let Some(star) = lazy
+        .loaded_stars
+        .iter()
+        .find(|s| s.planets.iter().any(|p| p.id == planet.id))
+    else {
+        return LockedSide::Terminator;
     };
+    let star_pos = Vec3::new(
+        star.position[0] as f32,
+        star.position[1] as f32,
+        star.position[2] as f32,
+    );
+    let orbit_r = planet.orbital_radius * AU_RENDER_SCALE;
+    let planet_pos = star_pos
+        + Vec3::new(
+            (orbit_r * planet.orbital_angle.cos()) as f32,
+            0.0,
+            (orbit_r * planet.orbital_angle.sin()) as f32,
+        );
+    let to_star = (star_pos - planet_pos).normalize_or_zero();
+    let to_cam = (cam_pos - planet_pos).normalize_or_zero();
+    LockedSide::from_facing(to_cam.dot(to_star))
+}
 
-    // Domain warping for organic shapes
-    let warp_x = (x * 0.02 + s * 0.5).sin() * 5.0;
-    let warp_z = (z * 0.03 + s * 0.7).cos() * 5.0;
-    let wx = x + warp_x;
-    let wz = z + warp_z;
-
-    // 5 octaves
-    let h1 = (wx * 0.05 + s).sin() * (wz * 0.07 + s * 1.3).sin() * amplitude;
-    let h2 = (wx * 0.13 + s * 2.1).sin() * (wz * 0.11 + s * 0.7).sin() * amplitude * 0.4;
-    let h3 = (wx * 0.31 + s * 3.7).sin() * (wz * 0.29 + s * 1.9).sin() * amplitude * 0.15;
-    let h4 = (wx * 0.67 + s * 5.3).sin() * (wz * 0.59 + s * 2.3).sin() * amplitude * 0.07;
-    let h5 = (wx * 1.31 + s * 7.1).sin() * (wz * 1.19 + s * 3.1).sin() * amplitude * 0.03;
-
-    h1 + h2 + h3 + h4 + h5
-}
-
-fn biome_color(height_t: f32, planet_type: &PlanetType) -> [f32; 4] {
-    match planet_type {
-        PlanetType::Rocky => {
-            if height_t < 0.15 {
-                [0.76, 0.70, 0.50, 1.0] // shore/sand
-            } else if height_t < 0.4 {
-                [0.25, 0.50, 0.18, 1.0] // grassland
-            } else if height_t < 0.7 {
-                [0.18, 0.38, 0.12, 1.0] // forest
-            } else if height_t < 0.85 {
-                [0.50, 0.45, 0.38, 1.0] // rock
-            } else {
-                [0.90, 0.92, 0.95, 1.0] // snow
-            }
-        }
-        PlanetType::Frozen => {
-            if height_t < 0.3 {
-                [0.70, 0.80, 0.90, 1.0]
-            } else if height_t < 0.7 {
-                [0.80, 0.85, 0.92, 1.0]
-            } else {
-                [0.95, 0.97, 1.0, 1.0]
-            }
-        }
-        PlanetType::Lava => {
-            if height_t < 0.2 {
-                [1.0, 0.4, 0.0, 1.0] // lava glow
-            } else if height_t < 0.5 {
-                [0.25, 0.08, 0.02, 1.0] // dark basalt
-            } else {
-                [0.35, 0.20, 0.10, 1.0] // cooled rock
-            }
-        }
-        PlanetType::Ocean => {
-            if height_t < 0.2 {
-                [0.60, 0.58, 0.40, 1.0] // sandy shore
-            } else if height_t < 0.6 {
-                [0.30, 0.55, 0.25, 1.0] // vegetation
-            } else {
-                [0.40, 0.50, 0.35, 1.0] // highlands
-            }
-        }
-        PlanetType::GasGiant => [0.70, 0.60, 0.40, 1.0],
-        PlanetType::IceGiant => [0.50, 0.60, 0.80, 1.0],
+fn sky_color(atmosphere: &AtmosphereType) -> Color {
+    // Twilight/night tones so stars on the sky dome remain visible
+    match atmosphere {
+        AtmosphereType::NitrogenOxygen => Color::srgb(0.05, 0.07, 0.15),
+        AtmosphereType::ThickCO2 => Color::srgb(0.12, 0.08, 0.04),
+        AtmosphereType::ThinCO2 => Color::srgb(0.10, 0.06, 0.05),
+        AtmosphereType::Hydrogen => Color::srgb(0.08, 0.06, 0.04),
+        AtmosphereType::Methane => Color::srgb(0.04, 0.07, 0.08),
+        AtmosphereType::Exotic => Color::srgb(0.07, 0.04, 0.09),
+        AtmosphereType::None => Color::srgb(0.01, 0.01, 0.03),
     }
 }
 
-fn build_terrain_mesh(seed: u64, planet_type: &PlanetType) -> Mesh {
-    let res = TERRAIN_RES;
-    let half = TERRAIN_SIZE / 2.0;
-    let step = TERRAIN_SIZE / res as f32;
+/// Daytime sky for an atmosphere (no atmosphere: black sky even at noon)
+fn day_sky_color(atmosphere: &AtmosphereType) -> Color {
+    match atmosphere {
+        AtmosphereType::NitrogenOxygen => Color::srgb(0.35, 0.55, 0.85),
+        AtmosphereType::ThickCO2 => Color::srgb(0.75, 0.55, 0.3),
+        AtmosphereType::ThinCO2 => Color::srgb(0.7, 0.5, 0.4),
+        AtmosphereType::Hydrogen => Color::srgb(0.6, 0.5, 0.35),
+        AtmosphereType::Methane => Color::srgb(0.35, 0.6, 0.6),
+        AtmosphereType::Exotic => Color::srgb(0.55, 0.35, 0.6),
+        AtmosphereType::None => Color::srgb(0.01, 0.01, 0.03),
+    }
+}
 
-    let vert_count = (res + 1) * (res + 1);
-    let mut positions = Vec::with_capacity(vert_count);
-    let mut normals = Vec::with_capacity(vert_count);
-    let mut uvs = Vec::with_capacity(vert_count);
-    let mut heights = Vec::with_capacity(vert_count);
+/// Unit vector towards the sun at a time of day: rises at 0.25, overhead-ish at 0.5
+fn sun_direction(time_of_day: f64) -> Vec3 {
+    ecliptic_direction(time_of_day, 0.0)
+}
 
-    for zi in 0..=res {
-        for xi in 0..=res {
-            let x = xi as f32 * step - half;
-            let z = zi as f32 * step - half;
-            let y = terrain_height(x, z, seed, planet_type);
-            positions.push([x, y, z]);
-            heights.push(y);
-            uvs.push([xi as f32 / res as f32, zi as f32 / res as f32]);
+/// Daylight from the sun's elevation, with a short twilight around the horizon
+fn daylight_at(time_of_day: f64) -> f32 {
+    let t = ((sun_direction(time_of_day).y + 0.1) / 0.3).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
 
-            let dx = terrain_height(x + 0.1, z, seed, planet_type)
-                - terrain_height(x - 0.1, z, seed, planet_type);
-            let dz = terrain_height(x, z + 0.1, seed, planet_type)
-                - terrain_height(x, z - 0.1, seed, planet_type);
-            let n = Vec3::new(-dx, 0.2, -dz).normalize();
-            normals.push([n.x, n.y, n.z]);
-        }
+/// Advance the local day: move the sun, blend the sky between twilight and day color,
+/// shrink sky-dome stars by day and dim the ambient light at night
+pub fn day_night_system(
+    time: Res<Time>,
+    universe: Res<UniverseState>,
+    state: Res<SurfaceState>,
+    mut day: ResMut<SurfaceDay>,
+    (mut clear_color, mut ambient): (ResMut<ClearColor>, ResMut<AmbientLight>),
+    mut sun_q: Query<(&mut Transform, &mut DirectionalLight), With<SurfaceLight>>,
+    mut star_q: Query<(&mut Transform, &SkyDomeStar), Without<SurfaceLight>>,
+) {
+    let Some(ref planet) = state.planet else {
+        return;
+    };
+    if day.generation != state.render_generation {
+        day.generation = state.render_generation;
+        day.period_hours = planet.rotation_period_hours();
+        day.time_of_day = (universe.age * HOURS_PER_GYR / day.period_hours).fract();
     }
 
-    // Compute height range for biome coloring
-    let min_h = heights.iter().cloned().fold(f32::MAX, f32::min);
-    let max_h = heights.iter().cloned().fold(f32::MIN, f32::max);
-    let range = (max_h - min_h).max(0.01);
+    // One face always towards the star: the sun stays where it is
+    if let Some(side) = state.locked_side {
+        day.time_of_day = side.time_of_day();
+    } else if !universe.paused {
+        let speedup = 1.0 + universe.time_scale.max(1.0).log10();
+        let day_secs =
+            (DAY_SECS_AT_1X * day.period_hours / 24.0 / speedup).max(MIN_DAY_SECS);
+        day.time_of_day = (day.time_of_day + time.delta_secs_f64() / day_secs).fract();
+    }
+    day.daylight = daylight_at(day.time_of_day);
 
-    let colors: Vec<[f32; 4]> = heights
-        .iter()
-        .map(|h| {
-            let t = (*h - min_h) / range;
-            biome_color(t, planet_type)
-        })
-        .collect();
+    let sun = sun_direction(day.time_of_day);
+    for (mut tf, mut light) in &mut sun_q {
+        *tf = Transform::default().looking_to(-sun, Vec3::Y);
+        light.illuminance = SUN_ILLUMINANCE * day.daylight;
+    }
+    ambient.brightness = NIGHT_AMBIENT + (DAY_AMBIENT - NIGHT_AMBIENT) * day.daylight;
+
+    let night = sky_color(&planet.atmosphere).to_linear();
+    let noon = day_sky_color(&planet.atmosphere).to_linear();
+    let mut sky = night.mix(&noon, day.daylight);
+    if state.underwater {
+        sky = sky.mix(&UNDERWATER_TINT.to_linear(), 0.8);
+    }
+    clear_color.0 = Color::from(sky);
+
+    // A bright sky washes the stars out; without an atmosphere they stay up all day
+    let washout = if matches!(planet.atmosphere, AtmosphereType::None) {
+        0.0
+    } else {
+        day.daylight
+    };
+    for (mut tf, star) in &mut star_q {
+        tf.scale = Vec3::splat(star.0 * (1.0 - washout));
+    }
+}
+
+/// Precipitation particles, kept apart from the camera's transform
+type WeatherParticles = (With<WeatherParticle>, Without<FlyCamera>);
+
+/// Roll the weather every epoch, keep precipitation particles and fog in sync with it,
+/// and move the particles (fall + wind), recycling them in a box around the camera
+pub fn weather_system(
+    mut commands: Commands,
+    (time, universe): (Res<Time>, Res<UniverseState>),
+    (state, lazy, clear_color): (Res<SurfaceState>, Res<LazyUniverse>, Res<ClearColor>),
+    (mut weather, mut budget): (ResMut<WeatherState>, ResMut<RenderBudget>),
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    mut camera_q: Query<(Entity, &Transform, Option<&mut DistanceFog>), With<FlyCamera>>,
+    mut particle_q: Query<(Entity, &mut Transform), WeatherParticles>,
+) {
+    let Some(ref planet) = state.planet else {
+        return;
+    };
+    let Ok((cam_entity, cam_tf, fog)) = camera_q.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
 
-    let mut indices: Vec<u32> = Vec::with_capacity(res * res * 6);
-    for zi in 0..res {
-        for xi in 0..res {
-            let tl = (zi * (res + 1) + xi) as u32;
-            let tr = tl + 1;
-            let bl = tl + (res + 1) as u32;
-            let br = bl + 1;
-            indices.extend_from_slice(&[tl, bl, tr, tr, bl, br]);
+    if weather.generation != state.render_generation {
+        *weather = WeatherState {
+            epoch: lazy.surface_memory.get(planet.id).map_or(0, |m| m.weather_epoch),
+            generation: state.render_generation,
+            ..default()
+        };
+        (weather.kind, weather.wind) = choose_weather(planet, weather.epoch);
+    }
+    if weather.budget_generation != budget.generation() {
+        weather.budget_generation = budget.generation();
+        weather.applied = false;
+    }
+    if !universe.paused {
+        weather.epoch_timer += dt;
+        if weather.epoch_timer >= WEATHER_EPOCH_SECS {
+            weather.epoch_timer = 0.0;
+            weather.epoch += 1;
+            let (kind, wind) = choose_weather(planet, weather.epoch);
+            weather.applied &= kind == weather.kind;
+            weather.kind = kind;
+            weather.wind = wind;
         }
     }
 
-    Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
-    )
-    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
-    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
-    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
-    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
-    .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
+    let cam_pos = cam_tf.translation;
+    let (count, size, fall, color) = weather.kind.particles();
+    if !weather.applied {
+        weather.applied = true;
+        for (entity, _) in &particle_q {
+            commands.entity(entity).despawn();
+        }
+        let count = budget.request(Category::Weather, count);
+        if count > 0 {
+            let mesh = meshes.add(Rectangle::new(size.x, size.y));
+            let material = materials.add(StandardMaterial {
+                base_color: color,
+                emissive: if weather.kind == WeatherKind::Embers {
+                    LinearRgba::from(color) * 20.0
+                } else {
+                    LinearRgba::BLACK
+                },
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                ..default()
+            });
+            let mut rng = ChaCha8Rng::seed_from_u64(planet.id.wrapping_add(weather.epoch));
+            for _ in 0..count {
+                let offset = Vec3::new(
+                    rng.gen_range(-WEATHER_BOX.x..WEATHER_BOX.x),
+                    rng.gen_range(-WEATHER_BOX.y..WEATHER_BOX.y),
+                    rng.gen_range(-WEATHER_BOX.z..WEATHER_BOX.z),
+                );
+                commands.spawn((
+                    Mesh3d(mesh.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(cam_pos + offset),
+                    WeatherParticle,
+                ));
+            }
+        }
+        let density = weather.kind.fog_density();
+        if density > 0.0 {
+            commands.entity(cam_entity).insert(DistanceFog {
+                color: clear_color.0,
+                falloff: FogFalloff::Exponential { density },
+                ..default()
+            });
+        } else {
+            commands.entity(cam_entity).remove::<DistanceFog>();
+        }
+        info!("Surface weather: {} (wind {:.1})", weather.kind.name(), weather.wind.length());
+        return;
+    }
+
+    // Fog follows the sky through the day
+    if let Some(mut fog) = fog {
+        fog.color = clear_color.0;
+    }
+
+    let velocity = weather.wind + Vec3::NEG_Y * fall;
+    for (_, mut tf) in &mut particle_q {
+        tf.translation += velocity * dt;
+        // Wrap back into the box around the camera
+        let mut rel = tf.translation - cam_pos;
+        for axis in 0..3 {
+            let extent = WEATHER_BOX[axis];
+            if rel[axis] < -extent {
+                rel[axis] += 2.0 * extent;
+            } else if rel[axis] > extent {
+                rel[axis] -= 2.0 * extent;
+            }
+        }
+        tf.translation = cam_pos + rel;
+        // Billboard: face the camera
+        tf.rotation = cam_tf.rotation;
+    }
 }
 
-fn sky_color(atmosphere: &AtmosphereType) -> Color {
-    // Twilight/night tones so stars on the sky dome remain visible
-    match atmosphere {
-        AtmosphereType::NitrogenOxygen => Color::srgb(0.05, 0.07, 0.15),
-        AtmosphereType::ThickCO2 => Color::srgb(0.12, 0.08, 0.04),
-        AtmosphereType::ThinCO2 => Color::srgb(0.10, 0.06, 0.05),
-        AtmosphereType::Hydrogen => Color::srgb(0.08, 0.06, 0.04),
-        AtmosphereType::Methane => Color::srgb(0.04, 0.07, 0.08),
-        AtmosphereType::Exotic => Color::srgb(0.07, 0.04, 0.09),
-        AtmosphereType::None => Color::srgb(0.01, 0.01, 0.03),
+/// Respawn the sky dome's starfield at the new count when the render budget changes
+pub fn sky_dome_budget_system(
+    mut commands: Commands,
+    state: Res<SurfaceState>,
+    mut budget: ResMut<RenderBudget>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut budget_generation: Local<u32>,
+    star_q: Query<Entity, With<SkyDomeStar>>,
+) {
+    if *budget_generation == budget.generation() {
+        return;
+    }
+    *budget_generation = budget.generation();
+    let Some(ref planet) = state.planet else {
+        return;
+    };
+    // Landing spawns its own at the current budget
+    if star_q.is_empty() {
+        return;
     }
+    for entity in &star_q {
+        commands.entity(entity).despawn();
+    }
+    spawn_sky_dome(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &mut budget,
+        &planet.atmosphere,
+        state.sky_seed,
+    );
 }
 
 fn spawn_creatures(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
-    planet: &Planet,
-    terrain_seed: u64,
+    budget: &mut RenderBudget,
+    (terrain_seed, planet): (u64, &Planet),
+    center: Vec3,
+    eaten: &[usize],
 ) {
     let Some(ref bio) = planet.life else {
+        commands.insert_resource(SurfaceEcology::default());
         return;
     };
     let genome = &bio.dominant_genome;
 
-    let count = ((bio.biomass * 5.0) as usize).clamp(5, MAX_CREATURES);
+    let count = budget.request(
+        Category::Creatures,
+        ((bio.biomass * 5.0) as usize).clamp(5, MAX_CREATURES),
+    );
 
-    let creature_mesh = match genome.structure {
-        0 | 1 | 2 => meshes.add(Sphere::new(1.0).mesh().ico(1).unwrap()),
-        3 => meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap()),
-        4 => meshes.add(Cuboid::new(0.6, 0.4, 1.0)),
-        5 | 6 => meshes.add(Cuboid::new(0.5, 1.5, 0.5)),
-        _ => meshes.add(Cuboid::new(0.8, 0.6, 0.7)),
+    let creature_color = substrate_color(genome.substrate);
+
+    // One mesh (body plan stretched by the species' elongation) and tint per species
+    let species = procgen::surface_species(bio, planet.id);
+    let mut ecology = SurfaceEcology {
+        species: species
+            .iter()
+            .map(|s| {
+                let mesh =
+                    creature_mesh(genome.structure).scaled_by(Vec3::new(1.0, 1.0, s.elongation));
+                let material = materials.add(StandardMaterial {
+                    base_color: creature_color.rotate_hue(s.hue_shift),
+                    ..default()
+                });
+                (meshes.add(mesh), material, s.clone())
+            })
+            .collect(),
+        ..default()
     };
+    let has_food_chain = species
+        .iter()
+        .any(|s| creature_diet(s, genome) == Some(Diet::Predator));
+    if has_food_chain {
+        ecology.detection_radius = detection_radius(genome);
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(terrain_seed.wrapping_add(777));
+    let r = CREATURE_RANGE;
+
+    // Creatures eaten on an earlier landing are still waiting to grow back
+    let mut missing = eaten.to_vec();
+    for i in 0..count {
+        let offset = Vec3::new(rng.gen_range(-r..r), 0.0, rng.gen_range(-r..r));
+        let point = center + offset;
+        ecology.spawn_points.push(offset);
+        let species_index = i % species.len();
+        if let Some(slot) = missing.iter().position(|&s| s == species_index) {
+            missing.swap_remove(slot);
+            ecology.eaten.push(species_index);
+            continue;
+        }
+        spawn_creature(
+            commands,
+            &ecology,
+            species_index,
+            genome,
+            point,
+            (terrain_seed, &planet.planet_type),
+            &mut rng,
+        );
+    }
+
+    info!(
+        "Surface: spawned {} creatures of {} species (structure={}, substrate={}, motility={}, food chain={})",
+        count,
+        species.len(),
+        genome.structure,
+        genome.substrate,
+        genome.motility,
+        has_food_chain
+    );
+    commands.insert_resource(ecology);
+}
 
-    let creature_color = match genome.substrate {
+/// Spawn one creature of a species at `point` (x/z; height follows the terrain of
+/// the given seed and planet type)
+fn spawn_creature(
+    commands: &mut Commands,
+    ecology: &SurfaceEcology,
+    species_idx: usize,
+    genome: &Genome,
+    point: Vec3,
+    (terrain_seed, planet_type): (u64, &PlanetType),
+    rng: &mut ChaCha8Rng,
+) {
+    let (mesh, material, template) = &ecology.species[species_idx];
+    // Individuals of a species still differ a little in size
+    let mut traits = template.clone();
+    traits.size_log += rng.gen_range(-0.1..=0.1);
+    let scale = 10.0f32.powf(traits.size_log as f32).clamp(0.2, 5.0);
+    let speed =
+        motility_speed(traits.motility) * traits.role.map_or(1.0, CreatureRole::speed_factor);
+    let is_flying = traits.motility == 7;
+    let diet = creature_diet(&traits, genome);
+
+    let y = terrain_height(point.x, point.z, terrain_seed, planet_type)
+        + scale * 0.5
+        + if is_flying { FLIGHT_ALTITUDE } else { 0.0 };
+    let wander_x = point.x + rng.gen_range(-WANDER_RANGE..WANDER_RANGE);
+    let wander_z = point.z + rng.gen_range(-WANDER_RANGE..WANDER_RANGE);
+
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(material.clone()),
+        Transform::from_xyz(point.x, y, point.z).with_scale(Vec3::splat(scale)),
+        Creature {
+            speed,
+            wander_target: Vec3::new(wander_x, 0.0, wander_z),
+            wander_timer: rng.gen_range(3.0..10.0),
+            is_flying,
+            species: species_idx,
+            traits,
+            diet,
+            state: CreatureState::default(),
+            target: None,
+            threat: None,
+        },
+    ));
+}
+
+/// Predators hunt; scavengers also hunt when the biosphere is heterotrophic.
+/// Creatures without a role (simple biospheres) stay out of the food chain.
+fn creature_diet(traits: &IndividualTraits, genome: &Genome) -> Option<Diet> {
+    match traits.role? {
+        CreatureRole::Predator => Some(Diet::Predator),
+        CreatureRole::Scavenger if genome.energy_source == 7 => Some(Diet::Predator),
+        CreatureRole::Grazer | CreatureRole::Scavenger => Some(Diet::Herbivore),
+    }
+}
+
+/// How far creatures notice each other: every sense helps, sight most of all
+fn detection_radius(genome: &Genome) -> f32 {
+    let radius = 6.0 + 3.0 * genome.sense_count() as f32;
+    if genome.senses & 1 != 0 {
+        radius * 2.5
+    } else {
+        radius
+    }
+}
+
+/// Base mesh for a body plan
+/// Base color of life built on a substrate (creatures, vegetation)
+pub fn substrate_color(substrate: u32) -> Color {
+    match substrate {
         0 => Color::srgb(0.2, 0.7, 0.3),
         1 => Color::srgb(0.3, 0.3, 0.7),
         2 => Color::srgb(0.6, 0.4, 0.2),
@@ -1260,16 +2513,22 @@ fn spawn_creatures(
         4 => Color::srgb(0.7, 0.3, 0.1),
         5 => Color::srgb(0.8, 0.7, 0.2),
         _ => Color::srgb(0.5, 0.5, 0.5),
-    };
-
-    let creature_mat = materials.add(StandardMaterial {
-        base_color: creature_color,
-        ..default()
-    });
+    }
+}
 
-    let scale = 10.0f32.powf(genome.size_log as f32).clamp(0.2, 5.0);
+fn creature_mesh(structure: u32) -> Mesh {
+    match structure {
+        0..=2 => Sphere::new(1.0).mesh().ico(1).unwrap(),
+        3 => Sphere::new(1.0).mesh().ico(0).unwrap(),
+        4 => Cuboid::new(0.6, 0.4, 1.0).into(),
+        5 | 6 => Cuboid::new(0.5, 1.5, 0.5).into(),
+        _ => Cuboid::new(0.8, 0.6, 0.7).into(),
+    }
+}
 
-    let speed = match genome.motility {
+/// Surface movement speed for a motility code
+fn motility_speed(motility: u32) -> f32 {
+    match motility {
         0 => 0.0,
         1 => 0.5,
         2 => 1.0,
@@ -1278,50 +2537,19 @@ fn spawn_creatures(
         5 => 4.0,
         6 => 3.5,
         _ => 6.0,
-    };
-
-    let is_flying = genome.motility == 7;
-
-    let mut rng = ChaCha8Rng::seed_from_u64(terrain_seed.wrapping_add(777));
-    let half = TERRAIN_SIZE / 2.0 * 0.8;
-
-    for _ in 0..count {
-        let x = rng.gen_range(-half..half);
-        let z = rng.gen_range(-half..half);
-        let y = terrain_height(x, z, terrain_seed, &planet.planet_type)
-            + scale * 0.5
-            + if is_flying { 3.0 } else { 0.0 };
-
-        let wander_x = rng.gen_range(-half..half);
-        let wander_z = rng.gen_range(-half..half);
-
-        commands.spawn((
-            Mesh3d(creature_mesh.clone()),
-            MeshMaterial3d(creature_mat.clone()),
-            Transform::from_xyz(x, y, z).with_scale(Vec3::splat(scale)),
-            Creature {
-                speed,
-                wander_target: Vec3::new(wander_x, 0.0, wander_z),
-                wander_timer: rng.gen_range(3.0..10.0),
-                is_flying,
-            },
-        ));
     }
-
-    info!(
-        "Surface: spawned {} creatures (structure={}, substrate={}, motility={}, size={:.1})",
-        count, genome.structure, genome.substrate, genome.motility, scale
-    );
 }
 
 fn spawn_sky_dome(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
+    budget: &mut RenderBudget,
     atmosphere: &AtmosphereType,
+    seed: u64,
 ) {
     let sky_radius = 500.0;
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
     // Atmosphere thickness affects how many stars are visible
     let star_count = match atmosphere {
@@ -1333,6 +2561,10 @@ fn spawn_sky_dome(
         AtmosphereType::Methane => 80,
         AtmosphereType::Exotic => 120,
     };
+    // A tenth as many again are bright stars
+    let granted = budget.request(Category::SkyStars, star_count + star_count / 10);
+    let star_count = granted * 10 / 11;
+    let bright_count = granted - star_count;
 
     let star_mesh = meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap());
 
@@ -1371,12 +2603,11 @@ fn spawn_sky_dome(
             Mesh3d(star_mesh.clone()),
             MeshMaterial3d(star_mats[mat_idx].clone()),
             Transform::from_xyz(x, y, z).with_scale(Vec3::splat(size)),
-            SkyDomeStar,
+            SkyDomeStar(size),
         ));
     }
 
     // Add a few "bright" stars (larger, more emissive)
-    let bright_count = star_count / 10;
     let bright_mat = materials.add(StandardMaterial {
         base_color: Color::WHITE,
         emissive: LinearRgba::from(Color::WHITE) * 200.0,
@@ -1391,11 +2622,12 @@ fn spawn_sky_dome(
         let z = sky_radius * phi.cos() * theta.sin();
         let y = sky_radius * phi.sin();
 
+        let size = rng.gen_range(1.5..3.0);
         commands.spawn((
             Mesh3d(star_mesh.clone()),
             MeshMaterial3d(bright_mat.clone()),
-            Transform::from_xyz(x, y, z).with_scale(Vec3::splat(rng.gen_range(1.5..3.0))),
-            SkyDomeStar,
+            Transform::from_xyz(x, y, z).with_scale(Vec3::splat(size)),
+            SkyDomeStar(size),
         ));
     }
 
@@ -1404,3 +2636,616 @@ fn spawn_sky_dome(
         star_count, bright_count, atmosphere
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_transitions() {
+        use LevelTransition::*;
+        let enter = |level, region, star| next_transition(level, LevelKey::Enter, region, star);
+        let back = |level| next_transition(level, LevelKey::Back, None, None);
+
+        assert_eq!(enter(ViewLevel::Cosmic, Some(4), None), Some(EnterRegion(4)));
+        assert_eq!(enter(ViewLevel::Cosmic, None, None), None);
+        assert_eq!(enter(ViewLevel::Region, None, Some(9)), Some(EnterSystem(9)));
+        assert_eq!(enter(ViewLevel::Region, None, None), Some(Land));
+        assert_eq!(enter(ViewLevel::System(9), None, Some(3)), Some(Land));
+        assert_eq!(enter(ViewLevel::Surface, None, None), Some(LeaveSurface));
+
+        assert_eq!(back(ViewLevel::Surface), Some(LeaveSurface));
+        assert_eq!(back(ViewLevel::System(9)), Some(LeaveSystem));
+        assert_eq!(back(ViewLevel::Region), Some(ExitToCosmic));
+        assert_eq!(back(ViewLevel::Cosmic), Some(OpenPauseMenu));
+    }
+
+    #[test]
+    fn test_one_esc_press_is_one_transition() {
+        use crate::gamepad::GamepadInput;
+        use crate::landing::landing_transition_system;
+        use crate::settings::UserSettings;
+        use bevy::state::app::StatesPlugin;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<UserSettings>()
+            .init_resource::<GamepadInput>()
+            .init_resource::<SurfaceState>()
+            .init_resource::<PlanetSelection>()
+            .init_resource::<SystemFocus>()
+            .init_resource::<AtmosphereDive>()
+            .init_resource::<LandingTransition>()
+            .insert_resource(UniverseState::new(matrix_core::SimConfig::default(), Vec::new()))
+            .add_systems(
+                Update,
+                (
+                    level_transition_system,
+                    landing_transition_system.after(level_transition_system),
+                ),
+            );
+        let mut lazy = LazyUniverse::new(matrix_core::SimConfig::default(), 13.0);
+        lazy.async_loading = false;
+        let region = lazy.regions[0].clone();
+        lazy.enter_region(region.id, 13.0);
+        app.insert_resource(lazy);
+
+        // Landed, somewhere in the region
+        let spot = Vec3::new(region.center[0] as f32 + 7.0, region.center[1] as f32, 3.0);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Transform::from_translation(spot),
+                FlyCamera {
+                    zoom_level: ZoomLevel::Stellar,
+                    ..default()
+                },
+            ))
+            .id();
+        app.world_mut().resource_mut::<SurfaceState>().active = true;
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.release_all();
+            keys.clear();
+            keys.press(key);
+            app.update();
+            // Held on the next frame: not a new press
+            app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+            app.update();
+        };
+        let cam = |app: &App| {
+            let cam = app.world().get::<FlyCamera>(camera).unwrap();
+            (cam.zoom_level, cam.glide.map(|g| g.position))
+        };
+        let paused = |app: &App| *app.world().resource::<State<AppState>>().get() == AppState::Paused;
+
+        // Surface: takes off, and more presses during the climb only skip it
+        press(&mut app, KeyCode::Escape);
+        assert!(app.world().resource::<SurfaceState>().active);
+        assert!(app.world().resource::<LandingTransition>().is_active());
+        press(&mut app, KeyCode::Escape);
+        assert!(!app.world().resource::<LandingTransition>().is_active());
+        // Only left the planet, the camera stays in the region
+        assert!(!app.world().resource::<SurfaceState>().active);
+        assert_eq!(cam(&app), (ZoomLevel::Stellar, None));
+        assert!(!paused(&app));
+
+        // Region: glides out to Cosmic view, remembering the spot
+        press(&mut app, KeyCode::Escape);
+        let (zoom, glide) = cam(&app);
+        assert_eq!(zoom, ZoomLevel::Cosmic);
+        assert!(glide.unwrap().distance(spot) > 100.0);
+        assert_eq!(app.world().get::<Transform>(camera).unwrap().translation, spot);
+        assert!(!paused(&app));
+
+        // Cosmic: opens the pause menu
+        press(&mut app, KeyCode::Escape);
+        assert!(paused(&app));
+        assert_eq!(cam(&app).0, ZoomLevel::Cosmic);
+
+        // Entering the same region again glides back to the spot
+        app.world_mut().resource_mut::<PlanetSelection>().selected_region = Some(region.id);
+        press(&mut app, KeyCode::KeyB);
+        assert_eq!(cam(&app), (ZoomLevel::Stellar, Some(spot)));
+        assert!(app.world().resource::<SystemFocus>().region_return.is_none());
+    }
+
+    #[test]
+    fn test_landing_on_a_giant_dives_instead() {
+        use crate::gamepad::GamepadInput;
+        use crate::settings::UserSettings;
+        use bevy::state::app::StatesPlugin;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<UserSettings>()
+            .init_resource::<GamepadInput>()
+            .init_resource::<SurfaceState>()
+            .init_resource::<PlanetSelection>()
+            .init_resource::<SystemFocus>()
+            .init_resource::<AtmosphereDive>()
+            .init_resource::<LandingTransition>()
+            .insert_resource(UniverseState::new(matrix_core::SimConfig::default(), Vec::new()))
+            .add_systems(Update, level_transition_system);
+        let mut lazy = LazyUniverse::new(matrix_core::SimConfig::default(), 13.0);
+        lazy.async_loading = false;
+        let region = lazy
+            .regions
+            .iter()
+            .max_by(|a, b| a.density.total_cmp(&b.density))
+            .unwrap()
+            .clone();
+        lazy.enter_region(region.id, 13.0);
+        let (star, giant) = lazy
+            .loaded_stars
+            .iter()
+            .find_map(|s| s.planets.iter().find(|p| p.planet_type.is_giant()).map(|p| (s.clone(), p.clone())))
+            .expect("a giant in the densest region");
+        app.insert_resource(lazy);
+
+        // Right next to the giant, nothing selected: the nearest-planet fallback picks it
+        let star_pos = Vec3::from_array(star.position.map(|c| c as f32));
+        let near_giant = star_pos + crate::cosmos::orbit_offset(&giant) + Vec3::Y * 0.01;
+        app.world_mut().spawn((
+            Transform::from_translation(near_giant),
+            FlyCamera {
+                zoom_level: ZoomLevel::Planetary,
+                ..default()
+            },
+        ));
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.release_all();
+            keys.clear();
+            keys.press(key);
+            app.update();
+            app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+            app.update();
+        };
+
+        press(&mut app, KeyCode::KeyB);
+        let dive = app.world().resource::<AtmosphereDive>();
+        assert!(dive.is_active());
+        assert_eq!(dive.planet.as_ref().map(|p| p.id), Some(giant.id));
+        assert_eq!(dive.return_pos, near_giant);
+        assert!(!app.world().resource::<SurfaceState>().active);
+
+        // The dive has [Esc]: no other transition while it lasts
+        press(&mut app, KeyCode::Escape);
+        assert!(app.world().resource::<AtmosphereDive>().is_active());
+        assert_ne!(*app.world().resource::<State<AppState>>().get(), AppState::Paused);
+
+        // Selected rocky worlds are still landed on
+        app.world_mut().insert_resource(AtmosphereDive::default());
+        let rocky = Planet {
+            planet_type: matrix_core::PlanetType::Rocky,
+            ..giant.clone()
+        };
+        app.world_mut().resource_mut::<PlanetSelection>().selected_planet = Some((rocky, star.spectral_class));
+        press(&mut app, KeyCode::KeyB);
+        assert!(app.world().resource::<SurfaceState>().active);
+        assert!(!app.world().resource::<AtmosphereDive>().is_active());
+    }
+
+    #[test]
+    fn test_view_level_follows_focus_and_zoom() {
+        let mut surface = SurfaceState::default();
+        let mut focus = SystemFocus::default();
+        assert_eq!(ViewLevel::current(&surface, &focus, ZoomLevel::Cosmic), ViewLevel::Cosmic);
+        assert_eq!(ViewLevel::current(&surface, &focus, ZoomLevel::Planetary), ViewLevel::Region);
+
+        focus.star_id = Some(5);
+        assert_eq!(ViewLevel::current(&surface, &focus, ZoomLevel::Planetary), ViewLevel::System(5));
+        // Focus only counts while the camera is at Planetary zoom
+        assert_eq!(ViewLevel::current(&surface, &focus, ZoomLevel::Stellar), ViewLevel::Region);
+
+        surface.active = true;
+        assert_eq!(ViewLevel::current(&surface, &focus, ZoomLevel::Planetary), ViewLevel::Surface);
+    }
+
+    #[test]
+    fn test_weather_fits_the_planet() {
+        let mut planet = Planet {
+            id: 77,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 1200.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::ThickCO2,
+            planet_type: PlanetType::Lava,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        };
+        for epoch in 0..20 {
+            let (kind, _) = choose_weather(&planet, epoch);
+            assert!(matches!(kind, WeatherKind::Embers | WeatherKind::AshFall));
+            assert_eq!(choose_weather(&planet, epoch).0, kind);
+        }
+
+        planet.planet_type = PlanetType::Rocky;
+        planet.atmosphere = AtmosphereType::None;
+        assert!((0..20).all(|epoch| choose_weather(&planet, epoch).0 == WeatherKind::Clear));
+    }
+
+    #[test]
+    fn test_walking_limits() {
+        // 60° slope rising toward +x: uphill is blocked, across and downhill are not
+        let rise = 60f32.to_radians().tan();
+        let len = (rise * rise + 1.0).sqrt();
+        let normal = [-rise / len, 1.0 / len, 0.0];
+        assert_eq!(limit_climb(Vec2::new(1.0, 0.0), normal), Vec2::ZERO);
+        assert_eq!(limit_climb(Vec2::new(1.0, 1.0), normal), Vec2::new(0.0, 1.0));
+        assert_eq!(limit_climb(Vec2::new(-1.0, 0.0), normal), Vec2::new(-1.0, 0.0));
+        // 40° is still walkable
+        let rise = 40f32.to_radians().tan();
+        let len = (rise * rise + 1.0).sqrt();
+        let gentle = [-rise / len, 1.0 / len, 0.0];
+        assert_eq!(limit_climb(Vec2::new(1.0, 0.0), gentle), Vec2::new(1.0, 0.0));
+
+        // Earth-sized planets pull at 1 g; jumps go higher where gravity is weaker
+        let mut planet = Planet {
+            id: 5,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 288.0,
+            tidally_locked: false,
+            has_water: true,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type: PlanetType::Rocky,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        };
+        let earth = planet.surface_gravity() as f32;
+        assert!((earth - 9.81).abs() < 0.01);
+        let apex = |g: f32| JUMP_SPEED * JUMP_SPEED / (2.0 * g);
+        assert!((0.9..1.1).contains(&apex(earth)));
+        planet.mass = 0.1;
+        planet.radius = 0.5;
+        assert!(apex(planet.surface_gravity() as f32) > 2.0 * apex(earth));
+    }
+
+    #[test]
+    fn test_landing_again_restores_the_surface() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Image>()
+            .init_resource::<SurfaceState>()
+            .init_resource::<SurfaceEcology>()
+            .init_resource::<WeatherState>()
+            .init_resource::<RenderBudget>()
+            .init_resource::<ClearColor>()
+            .init_resource::<TerrainChunks>()
+            .init_resource::<LandingTransition>()
+            .insert_resource(LazyUniverse::empty(matrix_core::SimConfig::default()))
+            .add_systems(Update, surface_enter_exit_system);
+        let camera = app
+            .world_mut()
+            .spawn((Transform::default(), FlyCamera::default()))
+            .id();
+        let planet = Planet {
+            id: 31,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 280.0,
+            tidally_locked: false,
+            has_water: false,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::ThinCO2,
+            planet_type: PlanetType::Rocky,
+            life: None,
+            moons: Vec::new(),
+            rings: None,
+        };
+        let land = |app: &mut App| {
+            let mut state = app.world_mut().resource_mut::<SurfaceState>();
+            state.active = true;
+            state.terrain_seed = planet.id;
+            state.planet = Some(planet.clone());
+            state.eye_height = 2.0;
+            state.generation += 1;
+            app.update();
+        };
+        let take_off = |app: &mut App| {
+            let mut state = app.world_mut().resource_mut::<SurfaceState>();
+            state.active = false;
+            state.space_return_pos = Vec3::new(500.0, 0.0, 0.0);
+            state.generation += 1;
+            app.update();
+        };
+
+        land(&mut app);
+        let start = app.world().get::<Transform>(camera).unwrap().translation;
+        assert_eq!((start.x, start.z), (0.0, 0.0));
+        // Coming down from high above the spot
+        let landing = app.world().resource::<LandingTransition>();
+        assert!(landing.is_active());
+        assert_eq!(start, landing.camera_position());
+        assert!(start.y > landing.ground.y);
+
+        // Walk somewhere, raise the eye, then leave
+        app.world_mut().get_mut::<Transform>(camera).unwrap().translation = Vec3::new(30.0, 5.0, -12.0);
+        app.world_mut().resource_mut::<SurfaceState>().eye_height = 4.0;
+        take_off(&mut app);
+        assert_eq!(app.world().get::<Transform>(camera).unwrap().translation.x, 500.0);
+        let memory = app.world().resource::<LazyUniverse>().surface_memory.get(31).cloned().unwrap();
+        assert_eq!(memory.position, [30.0, 5.0, -12.0]);
+        assert_eq!(memory.visits, 1);
+
+        land(&mut app);
+        let back = app.world().resource::<LandingTransition>().ground;
+        assert_eq!(app.world().get::<Transform>(camera).unwrap().translation.xz(), back.xz());
+        assert_eq!((back.x, back.z), (30.0, -12.0));
+        let ground = terrain_height(30.0, -12.0, planet.id, &planet.planet_type);
+        assert!((back.y - ground - 4.0).abs() < 1e-4);
+        assert_eq!(app.world().resource::<SurfaceState>().eye_height, 4.0);
+        assert_eq!(app.world().resource::<LazyUniverse>().surface_memory.get(31).unwrap().visits, 2);
+    }
+
+    #[test]
+    fn test_landscape_parks_creatures_as_herds() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Image>()
+            .init_resource::<SurfaceState>()
+            .init_resource::<SurfaceEcology>()
+            .init_resource::<WeatherState>()
+            .init_resource::<RenderBudget>()
+            .init_resource::<ClearColor>()
+            .init_resource::<TerrainChunks>()
+            .init_resource::<LandingTransition>()
+            .insert_resource(LazyUniverse::empty(matrix_core::SimConfig::default()))
+            .add_systems(Update, (surface_enter_exit_system, creature_lod_system).chain());
+        app.world_mut().spawn((Transform::default(), FlyCamera::default()));
+        let mut genome = Genome::primordial();
+        genome.motility = 5;
+        let planet = Planet {
+            id: 32,
+            orbital_radius: 1.0,
+            orbital_period: 1.0,
+            orbital_angle: 0.0,
+            mass: 1.0,
+            radius: 1.0,
+            surface_temp: 288.0,
+            tidally_locked: false,
+            has_water: true,
+            has_atmosphere: true,
+            atmosphere: AtmosphereType::NitrogenOxygen,
+            planet_type: PlanetType::Rocky,
+            life: Some(matrix_core::Biosphere {
+                age: 3.0,
+                complexity: 6.0,
+                species_count: 4_000,
+                dominant_genome: genome,
+                has_technology: false,
+                biomass: 20.0,
+            }),
+            moons: Vec::new(),
+            rings: None,
+        };
+        {
+            let mut state = app.world_mut().resource_mut::<SurfaceState>();
+            state.active = true;
+            state.terrain_seed = planet.id;
+            state.planet = Some(planet);
+            state.generation += 1;
+        }
+        app.update();
+        let positions = |app: &mut App| {
+            let mut positions: Vec<[f32; 3]> = app
+                .world_mut()
+                .query_filtered::<&Transform, With<Creature>>()
+                .iter(app.world())
+                .map(|tf| tf.translation.to_array())
+                .collect();
+            positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            positions
+        };
+        let herds = |app: &mut App| {
+            app.world_mut()
+                .query_filtered::<(), With<HerdMarker>>()
+                .iter(app.world())
+                .count()
+        };
+        let on_ground = positions(&mut app);
+        assert_eq!(on_ground.len(), MAX_CREATURES);
+
+        // Up to Landscape: a marker per species instead of dozens of specks
+        app.world_mut().resource_mut::<SurfaceState>().surface_zoom = SurfaceZoom::Landscape;
+        app.update();
+        assert!(positions(&mut app).len() < 5);
+        let species = app.world().resource::<SurfaceEcology>().species.len();
+        let markers = herds(&mut app);
+        assert!(markers > 0 && markers <= species);
+
+        // Back down: everyone is where they were, the markers are gone
+        app.world_mut().resource_mut::<SurfaceState>().surface_zoom = SurfaceZoom::Ground;
+        app.update();
+        assert_eq!(positions(&mut app), on_ground);
+        assert_eq!(herds(&mut app), 0);
+    }
+
+    #[test]
+    fn test_day_night_cycle() {
+        assert_eq!(daylight_at(0.5), 1.0);
+        assert_eq!(daylight_at(0.0), 0.0);
+        assert!(daylight_at(0.26) > 0.0 && daylight_at(0.26) < 1.0);
+
+        let day = SurfaceDay {
+            time_of_day: 0.75,
+            ..default()
+        };
+        assert_eq!(day.clock(), (18, 0));
+    }
+
+    #[test]
+    fn test_locked_side() {
+        assert_eq!(LockedSide::from_facing(1.0), LockedSide::Day);
+        assert_eq!(LockedSide::from_facing(0.0), LockedSide::Terminator);
+        assert_eq!(LockedSide::from_facing(-0.9), LockedSide::Night);
+
+        // The sun is up on the day side, on the horizon at the terminator, down at night
+        assert_eq!(daylight_at(LockedSide::Day.time_of_day()), 1.0);
+        let twilight = daylight_at(LockedSide::Terminator.time_of_day());
+        assert!(twilight > 0.0 && twilight < 1.0);
+        assert_eq!(daylight_at(LockedSide::Night.time_of_day()), 0.0);
+    }
+
+    #[test]
+    fn test_food_chain_roles_and_senses() {
+        let mut genome = Genome::primordial();
+        let traits = |role| IndividualTraits {
+            size_log: 0.0,
+            hue_shift: 0.0,
+            elongation: 1.0,
+            motility: 5,
+            role,
+        };
+        assert_eq!(creature_diet(&traits(None), &genome), None);
+        assert_eq!(creature_diet(&traits(Some(CreatureRole::Predator)), &genome), Some(Diet::Predator));
+        assert_eq!(creature_diet(&traits(Some(CreatureRole::Scavenger)), &genome), Some(Diet::Herbivore));
+        genome.energy_source = 7;
+        assert_eq!(creature_diet(&traits(Some(CreatureRole::Scavenger)), &genome), Some(Diet::Predator));
+
+        // Without photoreception creatures sense much less far
+        genome.senses = 2 | 4;
+        let blind = detection_radius(&genome);
+        genome.senses = 1 | 2 | 4;
+        assert!(detection_radius(&genome) > 2.0 * blind);
+    }
+
+    fn creature(state: CreatureState, is_flying: bool) -> Creature {
+        Creature {
+            speed: 2.0,
+            wander_target: Vec3::new(10.0, 0.0, 10.0),
+            wander_timer: 6.0,
+            is_flying,
+            species: 0,
+            traits: IndividualTraits {
+                size_log: 0.0,
+                hue_shift: 0.0,
+                elongation: 1.0,
+                motility: if is_flying { 7 } else { 5 },
+                role: None,
+            },
+            diet: None,
+            state,
+            target: None,
+            threat: None,
+        }
+    }
+
+    #[test]
+    fn test_creature_state_transitions() {
+        // An observer freezes wanderers and grazers, and keeps them frozen while near
+        let mut wanderer = creature(CreatureState::Wandering, false);
+        wanderer.freeze();
+        assert_eq!(wanderer.state, CreatureState::Frozen { timer: FREEZE_SECS });
+        wanderer.tick(FREEZE_SECS - 1.0);
+        wanderer.freeze();
+        assert_eq!(wanderer.state, CreatureState::Frozen { timer: FREEZE_SECS });
+        // ...and once it runs out the creature heads somewhere new right away
+        wanderer.tick(FREEZE_SECS + 0.1);
+        assert_eq!(wanderer.state, CreatureState::Wandering);
+        assert_eq!(wanderer.wander_timer, 0.0);
+
+        // Busy creatures ignore the observer
+        for state in [
+            CreatureState::Hunting,
+            CreatureState::Fleeing,
+            CreatureState::Feeding { timer: 1.0 },
+            CreatureState::Resting,
+        ] {
+            let mut busy = creature(state, false);
+            busy.freeze();
+            assert_eq!(busy.state, state);
+        }
+
+        let mut feeder = creature(CreatureState::Feeding { timer: FEED_SECS }, false);
+        feeder.tick(FEED_SECS / 2.0);
+        assert_eq!(feeder.state, CreatureState::Feeding { timer: FEED_SECS / 2.0 });
+        feeder.tick(FEED_SECS);
+        assert_eq!(feeder.state, CreatureState::Wandering);
+
+        // Untimed states don't run out
+        let mut grazer = creature(CreatureState::Grazing, false);
+        grazer.tick(100.0);
+        assert_eq!(grazer.state, CreatureState::Grazing);
+        assert_eq!(grazer.wander_timer, 6.0);
+    }
+
+    #[test]
+    fn test_creature_heights_ease_and_flyers_land() {
+        // Walkers rest on the ground; flyers cruise above it, bobbing, and land when still
+        let walker = creature(CreatureState::Wandering, false);
+        assert_eq!(walker.target_height(10.0, 2.0, 3.0, 0.5), 11.0);
+        let mut flyer = creature(CreatureState::Wandering, true);
+        let cruise: Vec<f32> = (0..40).map(|t| flyer.hover(t as f32 * 0.1, 0.5)).collect();
+        assert!(cruise.iter().all(|h| (h - FLIGHT_ALTITUDE).abs() <= FLIGHT_BOB + 1e-5));
+        assert!(cruise.iter().any(|h| *h > FLIGHT_ALTITUDE));
+        assert!(cruise.iter().any(|h| *h < FLIGHT_ALTITUDE));
+        flyer.freeze();
+        assert_eq!(flyer.hover(1.0, 0.5), 0.0);
+        assert_eq!(flyer.target_height(10.0, 2.0, 1.0, 0.5), 11.0);
+
+        // Landing from cruise height glides down without overshooting, faster for faster
+        // creatures; a frozen creature on a slope still settles onto the ground
+        let dt = 1.0 / 60.0;
+        let start = 11.0 + FLIGHT_ALTITUDE;
+        let mut y = start;
+        let mut previous = y;
+        for _ in 0..120 {
+            y = flyer.settle_height(y, 11.0, dt);
+            assert!(y <= previous && y >= 11.0);
+            previous = y;
+        }
+        assert!((y - 11.0).abs() < 0.01, "still at {y}");
+        let first_step = start - flyer.settle_height(start, 11.0, dt);
+        assert!(first_step < FLIGHT_ALTITUDE * 0.5, "popped {first_step}");
+        let fast = Creature {
+            speed: 8.0,
+            ..flyer.clone()
+        };
+        assert!(start - fast.settle_height(start, 11.0, dt) > first_step);
+        assert_eq!(fast.settle_height(start, 11.0, 10.0), 11.0);
+    }
+
+    #[test]
+    fn test_sessile_creatures_settle_onto_terrain() {
+        // Speed 0 (plants, coral) still eases down to the ground instead of hanging at
+        // its spawn height
+        let sessile = Creature {
+            speed: 0.0,
+            ..creature(CreatureState::Resting, false)
+        };
+        let dt = 1.0 / 60.0;
+        let mut y = 15.0;
+        for _ in 0..120 {
+            let next = sessile.settle_height(y, 11.0, dt);
+            assert!(next < y && next >= 11.0);
+            y = next;
+        }
+        assert!((y - 11.0).abs() < 0.01, "still at {y}");
+    }
+}
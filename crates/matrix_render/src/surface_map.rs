@@ -0,0 +1,425 @@
+//! Surface map: on a planet the minimap corner shows a top-down height and biome map of
+//! the terrain around the camera (`chunks::surface_map_image`, redrawn once the camera has
+//! walked a chunk from where it was last drawn), with markers for creatures, detail
+//! objects, settlements and the landing site. A compass strip at the top of the screen
+//! reads the heading, and [Home] glides the camera back to where it was set down.
+
+use bevy::prelude::*;
+
+use super::camera::FlyCamera;
+use super::chunks::{CHUNK_SIZE, TerrainChunks};
+use super::hud_layout::{HudExtra, HudLayout, HudScaled};
+use super::settings::{Action, Controls};
+use super::surface::{Creature, SurfaceDetail, SurfaceState};
+
+/// Edge of the square of terrain on the map (meters): the loaded chunks and then some
+const MAP_EXTENT: f32 = 320.0;
+/// Edge of the map texture (pixels)
+const MAP_PIXELS: u32 = 128;
+/// Gap between the heading dot and the player marker (share of the map edge)
+const HEADING_OFFSET: f32 = 0.035;
+/// Degrees per character of the compass strip, and characters either side of the heading
+const COMPASS_STEP_DEG: i32 = 5;
+const COMPASS_HALF_WIDTH: i32 = 18;
+const COMPASS_FONT_SIZE: f32 = 15.0;
+/// Glide back to the landing site: speed (m/s), and the shortest and longest trip (s)
+const RETURN_SPEED: f32 = 120.0;
+const RETURN_SECS: (f32, f32) = (1.0, 4.0);
+/// Nearer than this to the landing site, [Home] has nowhere to go (meters)
+const RETURN_MIN_DIST: f32 = 1.0;
+
+/// What a map marker stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapMarker {
+    Player,
+    /// Dot ahead of the player marker, in the direction the camera faces
+    Heading,
+    Landing,
+    Settlement,
+    Creature,
+    Detail,
+}
+
+impl MapMarker {
+    /// Edge at HUD scale 1 (logical pixels)
+    fn size(self) -> f32 {
+        match self {
+            Self::Player => 9.0,
+            Self::Heading => 4.0,
+            Self::Landing => 10.0,
+            Self::Settlement => 8.0,
+            Self::Creature => 5.0,
+            Self::Detail => 3.0,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::Player | Self::Heading => Color::srgb(1.0, 1.0, 0.0),
+            Self::Landing => Color::srgb(0.2, 0.9, 1.0),
+            Self::Settlement => Color::srgb(1.0, 0.55, 0.2),
+            Self::Creature => Color::srgb(1.0, 0.25, 0.3),
+            Self::Detail => Color::srgba(0.85, 1.0, 0.85, 0.7),
+        }
+    }
+
+    /// Settlements are squares, everything else a dot
+    fn radius(self) -> BorderRadius {
+        match self {
+            Self::Settlement => BorderRadius::ZERO,
+            _ => BorderRadius::MAX,
+        }
+    }
+}
+
+/// Glide of the surface camera back to the landing site
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReturnTrip {
+    /// Where it started (x, z)
+    pub from: Vec2,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl ReturnTrip {
+    pub fn new(from: Vec2, to: Vec2) -> Self {
+        Self {
+            from,
+            elapsed: 0.0,
+            duration: (from.distance(to) / RETURN_SPEED).clamp(RETURN_SECS.0, RETURN_SECS.1),
+        }
+    }
+
+    /// Camera position (x, z) on the way to `to`, easing in and out
+    pub fn position(&self, to: Vec2) -> Vec2 {
+        let t = (self.elapsed / self.duration).clamp(0.0, 1.0);
+        self.from.lerp(to, t * t * (3.0 - 2.0 * t))
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// The map of the current landing
+#[derive(Resource, Default)]
+pub struct SurfaceMap {
+    /// `SurfaceState::render_generation` of the landing the map was drawn for
+    generation: u32,
+    /// Where the camera was set down (x, z)
+    pub landing: Option<Vec2>,
+    /// Center of the terrain drawn on the map (x, z)
+    center: Vec2,
+    image: Handle<Image>,
+    /// Marker nodes, reused from frame to frame
+    markers: Vec<Entity>,
+    /// [Home] glide under way
+    pub return_trip: Option<ReturnTrip>,
+}
+
+/// Any node of the surface map or compass; despawned with the rest of the surface
+#[derive(Component)]
+pub struct SurfaceMapUi;
+
+/// Terrain image in the minimap corner, placed by `minimap_system`
+#[derive(Component)]
+pub struct SurfaceMapView;
+
+#[derive(Component)]
+pub struct SurfaceMapMarker;
+
+#[derive(Component)]
+pub struct CompassText;
+
+/// Place the surface map in the minimap corner, shown only while `shown` (called by
+/// `minimap_system`, which turns the space minimap off on a surface)
+pub fn place_map_view(node: &mut Node, layout: &HudLayout, shown: bool) {
+    node.display = if shown { Display::Flex } else { Display::None };
+    node.right = Val::Px(layout.margin);
+    node.bottom = Val::Px(layout.margin);
+    node.width = Val::Px(layout.minimap_edge);
+    node.height = Val::Px(layout.minimap_edge);
+}
+
+/// Heading of a camera facing `forward`: degrees clockwise from north (-Z)
+pub fn heading_degrees(forward: Vec3) -> f32 {
+    forward.x.atan2(-forward.z).to_degrees().rem_euclid(360.0)
+}
+
+/// Eight-point compass direction of a heading
+pub fn compass_point(heading: f32) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((heading.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+/// Compass strip centered on `heading`, one character per `COMPASS_STEP_DEG`: letters
+/// at the cardinal points, `+` between them, `|` every 15° and `.` in between
+pub fn compass_strip(heading: f32) -> String {
+    let center = (heading / COMPASS_STEP_DEG as f32).round() as i32 * COMPASS_STEP_DEG;
+    (-COMPASS_HALF_WIDTH..=COMPASS_HALF_WIDTH)
+        .map(|i| match (center + i * COMPASS_STEP_DEG).rem_euclid(360) {
+            0 => 'N',
+            90 => 'E',
+            180 => 'S',
+            270 => 'W',
+            angle if angle % 45 == 0 => '+',
+            angle if angle % 15 == 0 => '|',
+            _ => '.',
+        })
+        .collect()
+}
+
+/// Compass text: the strip, a pointer under the heading, and the heading with the way
+/// back to the landing site
+fn compass_text(forward: Vec3, camera: Vec2, landing: Option<Vec2>) -> String {
+    let heading = heading_degrees(forward);
+    let degrees = heading.round() as i32 % 360;
+    let mut readout = format!("{degrees:03}° {}", compass_point(heading));
+    if let Some(landing) = landing {
+        let to = landing - camera;
+        let dist = to.length();
+        if dist < RETURN_MIN_DIST {
+            readout.push_str(" | at the landing site");
+        } else {
+            let bearing = heading_degrees(Vec3::new(to.x, 0.0, to.y));
+            let turn = (bearing - heading + 540.0).rem_euclid(360.0) - 180.0;
+            let side = if turn.abs() < COMPASS_STEP_DEG as f32 {
+                "ahead".to_string()
+            } else if turn > 0.0 {
+                format!("{turn:.0}° right")
+            } else {
+                format!("{:.0}° left", -turn)
+            };
+            readout.push_str(&format!(" | Landing site {dist:.0} m, {side}"));
+        }
+    }
+    let pointer = format!("{:>width$}", "^", width = COMPASS_HALF_WIDTH as usize + 1);
+    let width = 2 * COMPASS_HALF_WIDTH as usize + 1;
+    format!("{}\n{pointer:<width$}\n{readout}", compass_strip(heading))
+}
+
+/// [Home] on a surface: glide back to the landing site. Runs before the walking, which
+/// then keeps the camera on the ground under the glide.
+pub fn return_to_landing_system(
+    time: Res<Time>,
+    controls: Controls,
+    mut map: ResMut<SurfaceMap>,
+    mut camera_q: Query<&mut Transform, With<FlyCamera>>,
+) {
+    let (Some(landing), Ok(mut transform)) = (map.landing, camera_q.get_single_mut()) else {
+        return;
+    };
+    let here = transform.translation.xz();
+    if map.return_trip.is_none() && controls.just_pressed(Action::ReturnToLanding) {
+        if here.distance(landing) < RETURN_MIN_DIST {
+            return;
+        }
+        info!("Surface: back to the landing site, {:.0} m", here.distance(landing));
+        map.return_trip = Some(ReturnTrip::new(here, landing));
+    }
+    let Some(trip) = map.return_trip.as_mut() else {
+        return;
+    };
+    trip.elapsed += time.delta_secs();
+    let position = trip.position(landing);
+    transform.translation.x = position.x;
+    transform.translation.z = position.y;
+    if trip.finished() {
+        map.return_trip = None;
+    }
+}
+
+/// Where the entities with a `C` are, for the map markers (kept apart from the camera)
+type Markers<'w, 's, C> = Query<'w, 's, &'static Transform, (With<C>, Without<FlyCamera>)>;
+
+/// Draw the map for a new landing (and forget it after leaving), redraw it when the
+/// camera has walked a chunk from its center, and place the markers and the compass
+pub fn surface_map_system(
+    (mut commands, mut map, mut images): (Commands, ResMut<SurfaceMap>, ResMut<Assets<Image>>),
+    (state, chunks, layout): (Res<SurfaceState>, Res<TerrainChunks>, Res<HudLayout>),
+    camera_q: Query<&Transform, With<FlyCamera>>,
+    (creature_q, detail_q): (Markers<Creature>, Markers<SurfaceDetail>),
+    view_q: Query<&Node, (With<SurfaceMapView>, Without<SurfaceMapMarker>)>,
+    mut marker_q: Query<(&mut Node, &mut BackgroundColor, &mut BorderRadius), With<SurfaceMapMarker>>,
+    mut compass_q: Query<&mut Text, With<CompassText>>,
+) {
+    if !state.active {
+        // The surface exit despawned the nodes
+        if map.landing.is_some() {
+            *map = SurfaceMap::default();
+        }
+        return;
+    }
+    let Ok(camera) = camera_q.get_single() else {
+        return;
+    };
+    let here = camera.translation.xz();
+
+    // New landing: the map and compass start where the camera was set down
+    if map.landing.is_none() || map.generation != state.render_generation {
+        let Some(image) = chunks.map_image(here, MAP_EXTENT, MAP_PIXELS) else {
+            return;
+        };
+        *map = SurfaceMap {
+            generation: state.render_generation,
+            landing: Some(here),
+            center: here,
+            image: images.add(image),
+            ..default()
+        };
+        spawn_map_nodes(&mut commands, &map.image);
+        return;
+    }
+    if here.distance(map.center) > CHUNK_SIZE
+        && let Some(image) = chunks.map_image(here, MAP_EXTENT, MAP_PIXELS)
+    {
+        map.center = here;
+        images.insert(&map.image, image);
+    }
+
+    if let Ok(mut text) = compass_q.get_single_mut() {
+        let compass = compass_text(*camera.forward(), here, map.landing);
+        if **text != compass {
+            **text = compass;
+        }
+    }
+
+    // Markers, on top of the map while it is shown
+    let mut shown = Vec::new();
+    if view_q.get_single().is_ok_and(|view| view.display != Display::None) {
+        let details = detail_q.iter().map(|tf| (tf.translation.xz(), MapMarker::Detail));
+        let creatures = creature_q.iter().map(|tf| (tf.translation.xz(), MapMarker::Creature));
+        let settlements = chunks.settlements().iter().map(|site| (*site, MapMarker::Settlement));
+        let heading = camera.forward().xz().normalize_or_zero() * HEADING_OFFSET * MAP_EXTENT;
+        shown = details
+            .chain(creatures)
+            .chain(settlements)
+            .chain(map.landing.map(|landing| (landing, MapMarker::Landing)))
+            .chain([(here + heading, MapMarker::Heading), (here, MapMarker::Player)])
+            .filter_map(|(at, kind)| {
+                let fraction = (at - map.center) / MAP_EXTENT + Vec2::splat(0.5);
+                let inside = fraction.cmpge(Vec2::ZERO).all() && fraction.cmple(Vec2::ONE).all();
+                inside.then_some((fraction, kind))
+            })
+            .collect::<Vec<_>>();
+    }
+    while map.markers.len() < shown.len() {
+        let marker = commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::None,
+                    ..default()
+                },
+                BackgroundColor(Color::NONE),
+                BorderRadius::MAX,
+                ZIndex(1),
+                SurfaceMapMarker,
+                SurfaceMapUi,
+            ))
+            .id();
+        map.markers.push(marker);
+    }
+    let edge = layout.minimap_edge;
+    let corner = layout.window - Vec2::splat(layout.margin + edge);
+    for (i, entity) in map.markers.iter().enumerate() {
+        let Ok((mut node, mut color, mut radius)) = marker_q.get_mut(*entity) else {
+            continue;
+        };
+        let Some(&(fraction, kind)) = shown.get(i) else {
+            node.display = Display::None;
+            continue;
+        };
+        let size = kind.size() * layout.scale;
+        let at = corner + fraction * edge - Vec2::splat(size / 2.0);
+        node.display = Display::Flex;
+        node.left = Val::Px(at.x);
+        node.top = Val::Px(at.y);
+        node.width = Val::Px(size);
+        node.height = Val::Px(size);
+        color.0 = kind.color();
+        *radius = kind.radius();
+    }
+}
+
+/// Map image in the minimap corner (placed by `minimap_system`) and the compass strip
+fn spawn_map_nodes(commands: &mut Commands, image: &Handle<Image>) {
+    commands.spawn((
+        ImageNode::new(image.clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::None,
+            ..default()
+        },
+        SurfaceMapView,
+        SurfaceMapUi,
+    ));
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: COMPASS_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::srgba(0.4, 1.0, 0.6, 0.9)),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        CompassText,
+        HudScaled::font(COMPASS_FONT_SIZE),
+        HudExtra,
+        SurfaceMapUi,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compass_reads_the_camera_heading() {
+        // Bevy cameras face -Z, which is north; yaw turns them to the left
+        let facing = |yaw: f32| Quat::from_rotation_y(yaw) * Vec3::NEG_Z;
+        assert!(heading_degrees(facing(0.0)).abs() < 1e-3);
+        assert!((heading_degrees(facing(-std::f32::consts::FRAC_PI_2)) - 90.0).abs() < 1e-3);
+        assert!((heading_degrees(facing(std::f32::consts::FRAC_PI_2)) - 270.0).abs() < 1e-3);
+        assert_eq!(compass_point(359.0), "N");
+        assert_eq!(compass_point(130.0), "SE");
+
+        let middle = COMPASS_HALF_WIDTH as usize;
+        let strip = compass_strip(90.0);
+        assert_eq!(strip.len(), 2 * middle + 1);
+        assert_eq!(strip.as_bytes()[middle], b'E');
+        assert_eq!(strip.as_bytes()[middle - 9], b'+');
+        assert_eq!(strip.as_bytes()[middle + 18], b'S');
+        assert_eq!(compass_strip(2.0).as_bytes()[middle], b'N');
+
+        // Landing site 50 m east of a camera facing north: a right turn
+        let text = compass_text(Vec3::NEG_Z, Vec2::ZERO, Some(Vec2::new(50.0, 0.0)));
+        assert!(text.ends_with("000° N | Landing site 50 m, 90° right"), "{text}");
+        let text = compass_text(Vec3::NEG_Z, Vec2::ZERO, Some(Vec2::new(0.0, -30.0)));
+        assert!(text.ends_with("Landing site 30 m, ahead"), "{text}");
+    }
+
+    #[test]
+    fn test_return_trip_ends_at_the_landing_site() {
+        let (from, landing) = (Vec2::new(300.0, -40.0), Vec2::new(12.0, 5.0));
+        let mut trip = ReturnTrip::new(from, landing);
+        assert!(trip.duration >= RETURN_SECS.0 && trip.duration <= RETURN_SECS.1);
+        assert_eq!(trip.position(landing), from);
+        let mut last = from.distance(landing);
+        while !trip.finished() {
+            trip.elapsed += 0.1;
+            let dist = trip.position(landing).distance(landing);
+            assert!(dist <= last, "moved away: {dist} after {last}");
+            last = dist;
+        }
+        assert_eq!(trip.position(landing), landing);
+
+        // A long way still takes at most the longest trip
+        assert_eq!(ReturnTrip::new(Vec2::new(1e5, 0.0), Vec2::ZERO).duration, RETURN_SECS.1);
+    }
+}
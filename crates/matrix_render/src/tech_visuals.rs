@@ -0,0 +1,417 @@
+//! Technological planets seen from space at Stellar/Planetary zoom: city lights on the
+//! night side, a few satellites circling the planet and, around developed civilizations,
+//! a faint orbital ring. Everything is fixed by the planet id and follows the planet
+//! instance it was spawned for.
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use matrix_sim::lazy_universe::LazyUniverse;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f32::consts::TAU;
+
+use super::camera::{FlyCamera, ZoomLevel};
+use super::instances::{CosmosInstances, PlanetInstance};
+
+/// Night-side lights of a technological planet: an emissive shell just over its instance.
+/// Its mesh, material and texture belong to it and are removed with it.
+#[derive(Component)]
+pub struct CityLights {
+    pub planet_id: u64,
+    /// Direction to the star the night side was last shaded for
+    lit_from: Option<Vec3>,
+}
+
+/// Satellite circling a technological planet
+#[derive(Component)]
+pub struct TechSatellite {
+    pub planet_id: u64,
+    pub orbit: SatelliteOrbit,
+}
+
+/// Ring structure around a planet of a developed civilization
+#[derive(Component)]
+pub struct OrbitalRing {
+    pub planet_id: u64,
+    tilt: Quat,
+}
+
+/// Every entity of the technological planets' visuals
+pub(crate) type TechVisuals = Or<(With<CityLights>, With<TechSatellite>, With<OrbitalRing>)>;
+
+/// Orbit of a satellite around its planet's visual
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SatelliteOrbit {
+    /// Distance from the planet's center (planet radii)
+    pub distance: f32,
+    /// Real seconds per turn
+    pub period: f32,
+    pub phase: f32,
+    /// Orbital plane (the XZ plane rotated by it)
+    pub tilt: Quat,
+}
+
+impl SatelliteOrbit {
+    /// Offset from the planet's center after `secs`, for a planet of `radius`
+    pub fn offset(&self, secs: f32, radius: f32) -> Vec3 {
+        let angle = self.phase + secs / self.period * TAU;
+        self.tilt * Vec3::new(angle.cos(), 0.0, angle.sin()) * self.distance * radius
+    }
+}
+
+/// Planets the visuals are spawned for
+#[derive(Resource, Default)]
+pub struct TechVisualState {
+    planets: Vec<u64>,
+    /// `stars_generation` they were spawned for
+    stars_generation: u32,
+}
+
+/// Meshes and materials shared by every satellite and ring. City lights get their own
+/// texture and night-side vertex colors.
+#[derive(Resource)]
+pub struct TechVisualAssets {
+    pub satellite_mesh: Handle<Mesh>,
+    pub satellite_mat: Handle<StandardMaterial>,
+    /// Unit-radius thin torus, scaled to the planet
+    pub ring_mesh: Handle<Mesh>,
+    pub ring_mat: Handle<StandardMaterial>,
+}
+
+/// Technological planets with visuals at once (nearest to the camera), keeping them
+/// to a handful of entities each: lights, satellites and a ring
+pub const MAX_TECH_PLANETS: usize = 6;
+const MAX_SATELLITES: usize = 3;
+/// Civilizations from this Kardashev level up build a ring
+pub const RING_TECH_LEVEL: f64 = 1.0;
+/// Shell radius relative to the planet's
+const LIGHTS_SHELL_SCALE: f32 = 1.03;
+/// City lights texture (equirectangular)
+const LIGHTS_WIDTH: u32 = 128;
+const LIGHTS_HEIGHT: u32 = 64;
+/// Cities per planet and lights per city
+const CITIES: (usize, usize) = (6, 14);
+const CITY_LIGHTS: (usize, usize) = (12, 48);
+/// Reshade the night side once the star has moved this far around the planet (radians)
+const RESHADE_ANGLE: f32 = 0.02;
+/// Satellite and ring sizes relative to the planet's radius
+const SATELLITE_SIZE: f32 = 0.06;
+const RING_RADIUS: f32 = 1.45;
+const TECH_SEED: u64 = 0x7EC4_0C17;
+
+pub fn init_tech_visuals(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let satellite_mat = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.95, 1.0),
+        emissive: LinearRgba::rgb(4.0, 4.5, 5.0),
+        unlit: true,
+        ..default()
+    });
+    let ring_mat = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.7, 0.85, 1.0, 0.25),
+        emissive: LinearRgba::rgb(0.6, 0.8, 1.0),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        ..default()
+    });
+    commands.insert_resource(TechVisualAssets {
+        satellite_mesh: meshes.add(Sphere::new(1.0).mesh().ico(0).unwrap()),
+        satellite_mat,
+        ring_mesh: meshes.add(Torus {
+            minor_radius: 0.012,
+            major_radius: 1.0,
+        }),
+        ring_mat,
+    });
+    commands.insert_resource(TechVisualState::default());
+}
+
+/// 1–3 satellite orbits of a planet and the tilt of its ring, the same for every rebuild
+pub(crate) fn tech_layout(planet_id: u64) -> (Vec<SatelliteOrbit>, Quat) {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id ^ TECH_SEED);
+    let count = rng.gen_range(1..=MAX_SATELLITES);
+    let satellites = (0..count)
+        .map(|_| SatelliteOrbit {
+            distance: rng.gen_range(1.25..1.8),
+            period: rng.gen_range(6.0..16.0),
+            phase: rng.gen_range(0.0..TAU),
+            tilt: Quat::from_rotation_y(rng.gen_range(0.0..TAU))
+                * Quat::from_rotation_x(rng.gen_range(-0.9..0.9)),
+        })
+        .collect();
+    let ring_tilt = Quat::from_rotation_x(rng.gen_range(-0.3..0.3));
+    (satellites, ring_tilt)
+}
+
+/// Equirectangular city lights of a planet (u: longitude, v: latitude from the north
+/// pole): clusters of bright points on black, clear of the poles
+pub(crate) fn city_lights_image(planet_id: u64) -> Image {
+    let mut rng = ChaCha8Rng::seed_from_u64(planet_id.rotate_left(17) ^ TECH_SEED);
+    let (w, h) = (LIGHTS_WIDTH as usize, LIGHTS_HEIGHT as usize);
+    let mut glow = vec![0.0f32; w * h];
+    for _ in 0..rng.gen_range(CITIES.0..=CITIES.1) {
+        let center = Vec2::new(rng.gen_range(0.0..1.0), rng.gen_range(0.2..0.8));
+        let spread = rng.gen_range(0.01..0.04);
+        for _ in 0..rng.gen_range(CITY_LIGHTS.0..=CITY_LIGHTS.1) {
+            // Sum of two uniforms: dense downtowns, sparse suburbs
+            let offset = Vec2::new(
+                rng.gen_range(-1.0..1.0) + rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0) + rng.gen_range(-1.0..1.0),
+            ) * spread;
+            let at = center + Vec2::new(offset.x * 2.0, offset.y);
+            let x = (at.x.rem_euclid(1.0) * w as f32) as usize % w;
+            let y = (at.y.clamp(0.0, 0.999) * h as f32) as usize;
+            let light = &mut glow[y * w + x];
+            *light = (*light + rng.gen_range(0.4..1.0)).min(1.0);
+        }
+    }
+    let data = glow
+        .iter()
+        .flat_map(|&light| {
+            let [r, g, b] = [light, light * 0.8, light * 0.45].map(|c| (c * 255.0) as u8);
+            [r, g, b, 255]
+        })
+        .collect();
+    Image::new(
+        Extent3d {
+            width: LIGHTS_WIDTH,
+            height: LIGHTS_HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Vertex colors of a unit sphere lit from `to_star`: white on the night side, fading out
+/// across the terminator
+fn night_side_colors(positions: &[[f32; 3]], to_star: Vec3) -> Vec<[f32; 4]> {
+    positions
+        .iter()
+        .map(|&p| {
+            let sun = Vec3::from(p).normalize_or_zero().dot(to_star);
+            let night = ((0.1 - sun) / 0.3).clamp(0.0, 1.0);
+            [night, night, night, 1.0]
+        })
+        .collect()
+}
+
+fn shade_night_side(mesh: &mut Mesh, to_star: Vec3) {
+    let Some(positions) = mesh.attribute(Mesh::ATTRIBUTE_POSITION).and_then(|a| a.as_float3()) else {
+        return;
+    };
+    let colors = night_side_colors(positions, to_star);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+}
+
+/// Technological planets (not moons) nearest to `pos`, by id
+fn tech_planets(planets: &[PlanetInstance], pos: Vec3) -> Vec<u64> {
+    let mut by_dist: Vec<(f32, u64)> = planets
+        .iter()
+        .filter(|p| p.has_tech && p.moon.is_none())
+        .map(|p| (pos.distance_squared(p.position), p.planet_id))
+        .collect();
+    by_dist.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut ids: Vec<u64> = by_dist.into_iter().take(MAX_TECH_PLANETS).map(|(_, id)| id).collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// City lights with the mesh and material they own
+type CityLightParts<'a> = (Entity, &'a Mesh3d, &'a MeshMaterial3d<StandardMaterial>);
+/// Satellites and orbital rings: what circles a planet
+type Orbiting = Or<(With<TechSatellite>, With<OrbitalRing>)>;
+
+/// Spawn lights, satellites and rings for the nearest technological planets at
+/// Stellar/Planetary zoom. Respawned when the planet set or `stars_generation` changes.
+pub fn spawn_tech_visuals(
+    (mut commands, mut state): (Commands, ResMut<TechVisualState>),
+    (lazy, instances, assets): (Res<LazyUniverse>, Res<CosmosInstances>, Res<TechVisualAssets>),
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    mut images: ResMut<Assets<Image>>,
+    lights_q: Query<CityLightParts, With<CityLights>>,
+    orbiting_q: Query<Entity, Orbiting>,
+    camera_query: Query<(&Transform, &FlyCamera)>,
+) {
+    let Ok((cam_transform, cam)) = camera_query.get_single() else {
+        return;
+    };
+    let shown = matches!(cam.zoom_level, ZoomLevel::Stellar | ZoomLevel::Planetary);
+    let planet_ids = if shown {
+        tech_planets(&instances.planets, cam_transform.translation)
+    } else {
+        Vec::new()
+    };
+    if planet_ids == state.planets && lazy.stars_generation == state.stars_generation {
+        return;
+    }
+    state.planets = planet_ids;
+    state.stars_generation = lazy.stars_generation;
+
+    for (entity, mesh, material) in lights_q.iter() {
+        meshes.remove(&mesh.0);
+        if let Some(texture) = materials.remove(&material.0).and_then(|m| m.base_color_texture) {
+            images.remove(&texture);
+        }
+        commands.entity(entity).despawn();
+    }
+    for entity in orbiting_q.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for &planet_id in &state.planets {
+        let Some(planet) = instances.planets.iter().find(|p| p.planet_id == planet_id) else {
+            continue;
+        };
+        let transform = Transform::from_translation(planet.position);
+        let material = materials.add(StandardMaterial {
+            base_color: Color::linear_rgb(2.5, 2.0, 1.4),
+            base_color_texture: Some(images.add(city_lights_image(planet_id))),
+            alpha_mode: AlphaMode::Add,
+            unlit: true,
+            ..default()
+        });
+        commands.spawn((
+            Mesh3d(meshes.add(Sphere::new(1.0).mesh().uv(32, 16))),
+            MeshMaterial3d(material),
+            transform.with_scale(Vec3::splat(planet.radius * LIGHTS_SHELL_SCALE)),
+            CityLights {
+                planet_id,
+                lit_from: None,
+            },
+        ));
+
+        let (satellites, ring_tilt) = tech_layout(planet_id);
+        for orbit in satellites {
+            commands.spawn((
+                Mesh3d(assets.satellite_mesh.clone()),
+                MeshMaterial3d(assets.satellite_mat.clone()),
+                transform,
+                TechSatellite { planet_id, orbit },
+            ));
+        }
+        let developed = lazy
+            .civilization_at(planet_id)
+            .is_some_and(|c| c.is_alive() && c.tech_level >= RING_TECH_LEVEL);
+        if developed {
+            commands.spawn((
+                Mesh3d(assets.ring_mesh.clone()),
+                MeshMaterial3d(assets.ring_mat.clone()),
+                transform,
+                OrbitalRing {
+                    planet_id,
+                    tilt: ring_tilt,
+                },
+            ));
+        }
+    }
+}
+
+/// An orbiting visual of kind `C` with what following its planet writes
+type Placed<'a, C> = (&'a C, &'a mut Transform, &'a mut Visibility);
+
+/// Keep the lights, satellites and rings on their planets as they orbit and pulse,
+/// turn the lights' night side away from the star and move the satellites along
+pub fn update_tech_visuals(
+    time: Res<Time>,
+    instances: Res<CosmosInstances>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lights_q: Query<(&mut CityLights, &Mesh3d, &mut Transform, &mut Visibility)>,
+    mut satellite_q: Query<Placed<TechSatellite>, (Without<CityLights>, Without<OrbitalRing>)>,
+    mut ring_q: Query<Placed<OrbitalRing>, (Without<CityLights>, Without<TechSatellite>)>,
+) {
+    // Planets missing since the last rebuild are hidden until the next respawn
+    let planet_of = |id: u64| instances.planets.iter().find(|p| p.planet_id == id);
+    let shown = |found: bool| if found { Visibility::Inherited } else { Visibility::Hidden };
+
+    for (mut lights, mesh, mut tf, mut visibility) in &mut lights_q {
+        let planet = planet_of(lights.planet_id);
+        visibility.set_if_neq(shown(planet.is_some()));
+        let Some(planet) = planet else {
+            continue;
+        };
+        tf.translation = planet.position;
+        tf.scale = Vec3::splat(planet.radius * LIGHTS_SHELL_SCALE);
+        let Some(star) = instances.stars.iter().find(|s| s.star_id == planet.star_id) else {
+            continue;
+        };
+        let to_star = (star.position - planet.position).normalize_or_zero();
+        if lights.lit_from.is_none_or(|lit| lit.angle_between(to_star) > RESHADE_ANGLE)
+            && let Some(mesh) = meshes.get_mut(&mesh.0)
+        {
+            shade_night_side(mesh, to_star);
+            lights.lit_from = Some(to_star);
+        }
+    }
+
+    let secs = time.elapsed_secs();
+    for (satellite, mut tf, mut visibility) in &mut satellite_q {
+        let planet = planet_of(satellite.planet_id);
+        visibility.set_if_neq(shown(planet.is_some()));
+        if let Some(planet) = planet {
+            tf.translation = planet.position + satellite.orbit.offset(secs, planet.radius);
+            tf.scale = Vec3::splat(planet.base_radius * SATELLITE_SIZE);
+        }
+    }
+    for (ring, mut tf, mut visibility) in &mut ring_q {
+        let planet = planet_of(ring.planet_id);
+        visibility.set_if_neq(shown(planet.is_some()));
+        if let Some(planet) = planet {
+            tf.translation = planet.position;
+            tf.rotation = ring.tilt;
+            tf.scale = Vec3::splat(planet.base_radius * RING_RADIUS);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tech_visuals_are_fixed_by_the_planet() {
+        let (satellites, tilt) = tech_layout(42);
+        assert!((1..=MAX_SATELLITES).contains(&satellites.len()));
+        assert_eq!((satellites.clone(), tilt), tech_layout(42));
+        let counts: Vec<usize> = (0..64).map(|id| tech_layout(id).0.len()).collect();
+        assert!((1..=MAX_SATELLITES).all(|n| counts.contains(&n)), "{counts:?}");
+
+        // Satellites stay outside the planet and come back around after a period
+        for orbit in &satellites {
+            let start = orbit.offset(0.0, 2.0);
+            assert!(start.length() > 2.0);
+            assert!(start.distance(orbit.offset(orbit.period, 2.0)) < 1e-3);
+        }
+
+        // Lights: some bright clusters on black, away from the poles
+        let image = city_lights_image(42);
+        assert_eq!(image.data, city_lights_image(42).data);
+        assert_ne!(image.data, city_lights_image(43).data);
+        let lit: Vec<usize> = image
+            .data
+            .chunks(4)
+            .enumerate()
+            .filter(|(_, px)| px[0] > 0)
+            .map(|(i, _)| i / LIGHTS_WIDTH as usize)
+            .collect();
+        let pixels = (LIGHTS_WIDTH * LIGHTS_HEIGHT) as usize;
+        assert!(lit.len() > 30 && lit.len() < pixels / 4, "{} lit", lit.len());
+        assert!(lit.iter().all(|&row| row > 2 && row < LIGHTS_HEIGHT as usize - 3));
+    }
+
+    #[test]
+    fn test_city_lights_only_on_the_night_side() {
+        let to_star = Vec3::X;
+        let colors = night_side_colors(&[[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], to_star);
+        assert_eq!(colors[0][0], 0.0);
+        assert_eq!(colors[1][0], 1.0);
+        // At the terminator the lights are fading in
+        assert!(colors[2][0] > 0.0 && colors[2][0] < 1.0);
+    }
+}
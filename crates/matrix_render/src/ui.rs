@@ -1,9 +1,27 @@
 use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::PrimaryWindow;
+use matrix_core::{GalaxyKind, Planet, Star};
+use matrix_physics::cosmology;
+use matrix_sim::black_holes::nearest_black_hole;
 use matrix_sim::lazy_universe::LazyUniverse;
 use matrix_sim::universe::UniverseState;
 
+use super::bestiary::INSPECT_RANGE;
 use super::camera::FlyCamera;
-use super::surface::{NearestCreatureInfo, PlanetSelection, SurfaceState, SurfaceZoom};
+use super::chunks::TerrainChunks;
+use super::gamepad::{InputDevice, action_glyph};
+use super::hud_layout::{HudExtra, HudLayout, HudScaled};
+use super::input_capture::CapturesPointer;
+use super::locale::Strings;
+use super::microbes::MicrobeProfile;
+use super::props::SETTLEMENT_SIGHT;
+use super::rewind::RewindBookmarks;
+use super::settings::{Action, Controls, key_name};
+use super::speed;
+use super::surface::{
+    self, NearestCreatureInfo, PlanetSelection, SurfaceDay, SurfaceState, WeatherState, SurfaceZoom, SystemFocus,
+};
 
 /// Marker for the HUD text
 #[derive(Component)]
@@ -13,13 +31,76 @@ pub struct HudText;
 #[derive(Component)]
 pub struct LifePanel;
 
+/// Marker for the floating tooltip panel next to the cursor
+#[derive(Component)]
+pub struct HoverTooltip;
+
+/// Marker for the tooltip text
+#[derive(Component)]
+pub struct TooltipText;
+
+/// Marker for the region inspector panel (right side, while a region is selected)
+#[derive(Component)]
+pub struct RegionInspector;
+
+/// Marker for the time slider caption
+#[derive(Component)]
+pub struct TimeSliderLabel;
+
+/// Marker for the time slider track (the draggable area)
+#[derive(Component)]
+pub struct TimeSliderTrack;
+
+/// Marker for the time slider handle
+#[derive(Component)]
+pub struct TimeSliderHandle;
+
+/// Marker for the signal indicator (top center, while a signal is heard)
+#[derive(Component)]
+pub struct SignalIndicator;
+
+/// Marker for the signal indicator caption
+#[derive(Component)]
+pub struct SignalIndicatorText;
+
+/// Marker for the dot on the signal dial pointing at the source
+#[derive(Component)]
+pub struct SignalBearingDot;
+
+/// Time scale range of [,]/[.] and the slider (log scale between the two)
+pub const TIME_SCALE_MIN: f64 = 1.0;
+pub const TIME_SCALE_MAX: f64 = 1e9;
+const SLIDER_WIDTH: f32 = 320.0;
+const SLIDER_HANDLE_WIDTH: f32 = 8.0;
+
+/// Font sizes of the side panels at UI scale and `UserSettings::hud_text_scale` 1.0
+pub const HUD_FONT_SIZE: f32 = 16.0;
+pub const LIFE_PANEL_FONT_SIZE: f32 = 14.0;
+
+/// Signal dial diameter and dot size (logical pixels)
+const SIGNAL_DIAL_SIZE: f32 = 48.0;
+const SIGNAL_DOT_SIZE: f32 = 8.0;
+/// Sources within this angle of the view direction show the dot centered (degrees)
+const SIGNAL_AHEAD_DEG: f32 = 5.0;
+
+/// Tooltip distance from the cursor (logical pixels)
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+/// Region inspector width and sparkline height (logical pixels, at UI scale 1.0)
+const INSPECTOR_WIDTH: f32 = 260.0;
+const SPARKLINE_HEIGHT: f32 = 40.0;
+/// Star formation rate samples in the inspector sparkline
+const SPARKLINE_SAMPLES: usize = 48;
+/// The sparkline spans at least this much cosmic time (Gyr)
+const SPARKLINE_MIN_SPAN: f64 = 13.8;
+
 /// Spawn the HUD overlay
 pub fn spawn_hud(mut commands: Commands) {
     // Left panel — universe stats
     commands.spawn((
         Text::new("Matrix Universe"),
         TextFont {
-            font_size: 16.0,
+            font_size: HUD_FONT_SIZE,
             ..default()
         },
         TextColor(Color::srgba(0.0, 1.0, 0.4, 0.9)),
@@ -30,13 +111,15 @@ pub fn spawn_hud(mut commands: Commands) {
             ..default()
         },
         HudText,
+        HudScaled::font(HUD_FONT_SIZE),
+        CapturesPointer,
     ));
 
     // Right panel — life discoveries
     commands.spawn((
         Text::new(""),
         TextFont {
-            font_size: 14.0,
+            font_size: LIFE_PANEL_FONT_SIZE,
             ..default()
         },
         TextColor(Color::srgba(0.4, 1.0, 0.6, 0.9)),
@@ -48,271 +131,784 @@ pub fn spawn_hud(mut commands: Commands) {
             ..default()
         },
         LifePanel,
+        HudScaled {
+            font_size: Some(LIFE_PANEL_FONT_SIZE),
+            max_width: Some(500.0),
+            above_minimap: true,
+            ..default()
+        },
+        HudExtra,
+        CapturesPointer,
+    ));
+
+    // Region inspector — filled by `update_region_inspector` while a region is selected
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            width: Val::Px(INSPECTOR_WIDTH),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            display: Display::None,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+        RegionInspector,
+        HudScaled {
+            width: Some(INSPECTOR_WIDTH),
+            above_minimap: true,
+            ..default()
+        },
+        HudExtra,
+        CapturesPointer,
     ));
+
+    // Hover tooltip — positioned next to the cursor by `update_tooltip`
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(6.0)),
+                max_width: Val::Px(320.0),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.05, 0.02, 0.85)),
+            HoverTooltip,
+            HudScaled {
+                max_width: Some(320.0),
+                ..default()
+            },
+            HudExtra,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+                TooltipText,
+                HudScaled::font(13.0),
+            ));
+        });
+
+    // Signal indicator — top center, shown by `update_signal_indicator`. The dot sits on
+    // the dial rim in the source's screen direction (center when it is straight ahead).
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-150.0)),
+                width: Val::Px(300.0),
+                column_gap: Val::Px(8.0),
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(6.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.02, 0.0, 0.85)),
+            SignalIndicator,
+            HudExtra,
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn((
+                    Node {
+                        width: Val::Px(SIGNAL_DIAL_SIZE),
+                        height: Val::Px(SIGNAL_DIAL_SIZE),
+                        flex_shrink: 0.0,
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::srgba(1.0, 0.7, 0.2, 0.8)),
+                    BorderRadius::MAX,
+                ))
+                .with_children(|dial| {
+                    dial.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(SIGNAL_DOT_SIZE),
+                            height: Val::Px(SIGNAL_DOT_SIZE),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(1.0, 0.7, 0.2, 0.95)),
+                        BorderRadius::MAX,
+                        SignalBearingDot,
+                    ));
+                });
+            panel.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(1.0, 0.8, 0.4, 0.95)),
+                SignalIndicatorText,
+            ));
+        });
+
+    // Time slider — bottom center, dragged by `time_slider_system` (fixed size: the
+    // handle is placed in pixels of `SLIDER_WIDTH`)
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-SLIDER_WIDTH / 2.0)),
+                width: Val::Px(SLIDER_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            HudExtra,
+        ))
+        .with_children(|slider| {
+            slider.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.4, 1.0, 0.6, 0.9)),
+                TimeSliderLabel,
+            ));
+            slider
+                .spawn((
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(12.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.3, 0.1, 0.6)),
+                    Interaction::default(),
+                    RelativeCursorPosition::default(),
+                    TimeSliderTrack,
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(SLIDER_HANDLE_WIDTH),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+                        TimeSliderHandle,
+                    ));
+                });
+        });
 }
 
-/// Format large numbers in human-readable form
-fn fmt_count(n: u64) -> String {
-    if n >= 1_000_000_000_000 {
-        format!("{:.1}T", n as f64 / 1e12)
-    } else if n >= 1_000_000_000 {
-        format!("{:.1}B", n as f64 / 1e9)
-    } else if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1e6)
-    } else if n >= 1_000 {
-        format!("{:.1}K", n as f64 / 1e3)
+/// One-line civilization status for a planet of the loaded region (home, colony or ruins)
+fn civilization_status(strings: &Strings, lazy: &LazyUniverse, planet_id: u64) -> Option<String> {
+    let civ = lazy.civilization_at(planet_id)?;
+    Some(match civ.extinction {
+        Some(extinction) => strings.tr(
+            "civ.ruins",
+            &[("level", &civ.kardashev_label()), ("cause", &extinction.cause.name())],
+        ),
+        None => {
+            let key = if civ.planet_id == planet_id { "civ.status" } else { "civ.status_colony" };
+            strings.tr(key, &[("level", &civ.kardashev_label().to_uppercase())])
+        }
+    })
+}
+
+/// Space navigation help, for the keyboard or (when used last) the gamepad
+fn space_help(strings: &Strings, controls: &Controls) -> String {
+    if controls.gamepad.last_device == InputDevice::KeyboardMouse {
+        return strings.tr("help.space_keys", &[]);
+    }
+    let glyph = |action| action_glyph(&controls.settings, action);
+    strings.tr(
+        "help.space_pad",
+        &[
+            ("up", &glyph(Action::MoveUp)),
+            ("down", &glyph(Action::MoveDown)),
+            ("boost", &glyph(Action::Boost)),
+            ("zoom_in", &glyph(Action::ZoomIn)),
+            ("zoom_out", &glyph(Action::ZoomOut)),
+            ("enter", &glyph(Action::EnterLevel)),
+            ("back", &glyph(Action::BackLevel)),
+            ("next", &glyph(Action::NextRegion)),
+            ("prev", &glyph(Action::PrevRegion)),
+            ("track", &glyph(Action::TrackParticle)),
+            ("pause", &glyph(Action::Pause)),
+            ("slower", &glyph(Action::Slower)),
+            ("faster", &glyph(Action::Faster)),
+        ],
+    )
+}
+
+/// Navigation help under the stats while [F1] has it open, else a hint how to open it
+fn help_block(strings: &Strings, layout: &HudLayout, controls: &Controls, help: String) -> String {
+    if layout.help_open {
+        return format!("{}\n{help}", strings.tr("help.title", &[]));
+    }
+    strings.tr(
+        "help.hint",
+        &[
+            ("help", &key_name(controls.settings.key(Action::HudHelp))),
+            ("compact", &key_name(controls.settings.key(Action::CompactHud))),
+        ],
+    )
+}
+
+/// Surface navigation help, for the keyboard or (when used last) the gamepad
+fn surface_help(strings: &Strings, controls: &Controls) -> String {
+    if controls.gamepad.last_device == InputDevice::KeyboardMouse {
+        return strings.tr("help.surface_keys", &[]);
+    }
+    let glyph = |action| action_glyph(&controls.settings, action);
+    strings.tr(
+        "help.surface_pad",
+        &[
+            ("boost", &glyph(Action::Boost)),
+            ("back", &glyph(Action::BackLevel)),
+            ("jump", &glyph(Action::Jump)),
+            ("pause", &glyph(Action::SurfacePause)),
+            ("slower", &glyph(Action::Slower)),
+            ("faster", &glyph(Action::Faster)),
+        ],
+    )
+}
+
+/// " [PAUSED]" while paused
+fn paused_tag(strings: &Strings, universe: &UniverseState) -> String {
+    if universe.paused {
+        strings.tr("hud.paused", &[])
     } else {
-        format!("{}", n)
+        String::new()
     }
 }
 
+/// "Pos: (x, y, z)", ungrouped so the separators can't be mistaken for the commas
+fn position_line(strings: &Strings, pos: Vec3) -> String {
+    let coord = |v: f32| strings.decimal(v as f64, 1);
+    strings.tr("hud.position", &[("x", &coord(pos.x)), ("y", &coord(pos.y)), ("z", &coord(pos.z))])
+}
+
+/// Left panel on a planet surface: the planet, local time and weather, life, what's near
+fn surface_hud(
+    strings: &Strings,
+    (universe, lazy): (&UniverseState, &LazyUniverse),
+    (surface, planet): (&SurfaceState, &Planet),
+    (day, weather): (&SurfaceDay, &WeatherState),
+    nearest_creature: &NearestCreatureInfo,
+    settlement: Option<f32>,
+    cam_pos: Vec3,
+) -> Vec<String> {
+    let s = strings;
+    let mut lines = vec![
+        s.tr(
+            "surface.title",
+            &[
+                ("name", &lazy.planet_name(planet.id)),
+                ("kind", &format!("{:?}", planet.planet_type)),
+                ("id", &planet.id),
+                ("visits", &lazy.surface_memory.get(planet.id).map_or(1, |m| m.visits)),
+            ],
+        ),
+        s.tr(
+            "surface.climate",
+            &[
+                ("temperature", &s.number(planet.surface_temp, 0)),
+                ("atmosphere", &format!("{:?}", planet.atmosphere)),
+            ],
+        ),
+        s.tr(
+            "surface.body",
+            &[
+                ("water", &s.yes_no(planet.has_water)),
+                ("radius", &s.number(planet.radius, 1)),
+                ("gravity", &s.number(planet.surface_gravity(), 1)),
+            ],
+        ),
+        s.tr(
+            "surface.zoom",
+            &[
+                ("zoom", &surface.surface_zoom.name()),
+                ("height", &s.number(surface.eye_height as f64, 2)),
+            ],
+        ),
+    ];
+    if surface.surface_zoom == SurfaceZoom::Microscopic {
+        let microbes = MicrobeProfile::for_planet(planet).describe();
+        lines.push(s.tr("surface.microscopic", &[("microbes", &microbes)]));
+    }
+    lines.push(match surface.locked_side {
+        Some(side) => {
+            let temps = cosmology::locked_temperatures_from_terminator(
+                planet.surface_temp,
+                planet.has_atmosphere,
+            );
+            s.tr(
+                "surface.locked",
+                &[
+                    ("side", &side.label()),
+                    ("day", &s.number(temps.day, 0)),
+                    ("night", &s.number(temps.night, 0)),
+                ],
+            )
+        }
+        None => {
+            let (hours, minutes) = day.clock();
+            let period = s.tr(if day.daylight > 0.5 { "surface.day" } else { "surface.night" }, &[]);
+            s.tr(
+                "surface.clock",
+                &[
+                    ("time", &format!("{hours:02}:{minutes:02}")),
+                    ("period", &period),
+                    ("hours", &s.number(day.period_hours, 1)),
+                ],
+            )
+        }
+    });
+    lines.push(s.tr(
+        "surface.weather",
+        &[
+            ("weather", &weather.kind.name()),
+            ("wind", &s.number(weather.wind.length() as f64, 1)),
+        ],
+    ));
+    lines.push(String::new());
+
+    match planet.life {
+        Some(ref bio) => {
+            lines.push(s.tr(
+                "surface.life",
+                &[
+                    ("complexity", &s.number(bio.complexity, 1)),
+                    ("species", &s.count(bio.species_count)),
+                    ("biomass", &s.number(bio.biomass, 1)),
+                ],
+            ));
+            lines.push(bio.dominant_genome.describe());
+        }
+        None => lines.push(s.tr("surface.no_life", &[])),
+    }
+    let tech = civilization_status(s, lazy, planet.id).or_else(|| {
+        planet
+            .life
+            .as_ref()
+            .is_some_and(|b| b.has_technology)
+            .then(|| s.tr("civ.technological", &[]))
+    });
+    lines.extend(tech);
+    if !nearest_creature.description.is_empty() && nearest_creature.distance < 5.0 {
+        let inspect = if nearest_creature.distance < INSPECT_RANGE {
+            s.tr("surface.inspect_hint", &[])
+        } else {
+            String::new()
+        };
+        lines.push(s.tr(
+            "surface.creature",
+            &[
+                ("distance", &s.number(nearest_creature.distance as f64, 1)),
+                ("creature", &nearest_creature.description),
+                ("inspect", &inspect),
+            ],
+        ));
+    }
+    if let Some(distance) = settlement.filter(|d| *d < SETTLEMENT_SIGHT) {
+        lines.push(s.tr("surface.settlements", &[("distance", &s.number(distance as f64, 0))]));
+    }
+
+    lines.push(String::new());
+    lines.push(position_line(s, cam_pos));
+    lines.push(s.tr(
+        "surface.time",
+        &[
+            ("age", &s.number(universe.age, 6)),
+            ("speed", &s.number(universe.time_scale, 0)),
+        ],
+    ));
+    lines
+}
+
+/// Right panel on a planet surface: its life and civilization, the nearest creature
+fn life_panel(
+    strings: &Strings,
+    lazy: &LazyUniverse,
+    surface: &SurfaceState,
+    nearest_creature: &NearestCreatureInfo,
+) -> Vec<String> {
+    let s = strings;
+    let mut lines = Vec::new();
+
+    if let Some(ref planet) = surface.planet {
+        if let Some(ref bio) = planet.life {
+            let genome = &bio.dominant_genome;
+            lines.push(s.tr("life.title", &[]));
+            lines.push(String::new());
+            lines.push(genome.describe());
+            lines.push(s.tr("life.senses", &[("senses", &genome.sense_list().join(", "))]));
+            lines.push(s.tr(
+                "life.age",
+                &[("age", &s.number(bio.age, 1)), ("complexity", &s.number(bio.complexity, 1))],
+            ));
+            lines.push(s.tr(
+                "life.species",
+                &[("species", &s.count(bio.species_count)), ("biomass", &s.number(bio.biomass, 1))],
+            ));
+            if bio.has_technology && lazy.civilization_at(planet.id).is_none() {
+                lines.push(s.tr("civ.technological", &[]));
+            }
+        }
+        if let Some(civ) = lazy.civilization_at(planet.id) {
+            lines.push(String::new());
+            if let Some(extinction) = civ.extinction {
+                lines.push(s.tr("civ.ruins_title", &[]));
+                lines.push(s.tr(
+                    "civ.extinct",
+                    &[
+                        ("level", &civ.kardashev_label()),
+                        ("age", &s.number(extinction.age, 2)),
+                        ("cause", &extinction.cause.name()),
+                    ],
+                ));
+            } else {
+                lines.push(s.tr("civ.title", &[("level", &civ.kardashev_label().to_uppercase())]));
+                lines.push(s.tr(
+                    "civ.population",
+                    &[
+                        ("population", &s.count(civ.population as u64)),
+                        ("colonies", &civ.colonies.len()),
+                        ("reach", &s.number(civ.expansion_radius, 1)),
+                    ],
+                ));
+                lines.push(s.tr("civ.risk", &[("risk", &s.number(civ.extinction_risk * 100.0, 1))]));
+            }
+        }
+    }
+
+    // Creature proximity detail
+    if !nearest_creature.description.is_empty() && nearest_creature.distance < 5.0 {
+        lines.push(String::new());
+        lines.push(s.tr("life.creature_title", &[]));
+        lines.push(s.tr(
+            "life.creature_distance",
+            &[("distance", &s.number(nearest_creature.distance as f64, 1))],
+        ));
+        lines.push(nearest_creature.description.clone());
+    }
+
+    // Microscopic hint
+    if surface.surface_zoom == SurfaceZoom::Microscopic {
+        lines.push(String::new());
+        lines.push(s.tr("life.microscopic", &[]));
+    }
+    lines
+}
+
+/// The region the camera is in, the galaxy around it, the nearest black hole and any
+/// region still loading
+fn region_lines(
+    strings: &Strings,
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    cam_pos: Vec3,
+) -> Vec<String> {
+    let s = strings;
+    let mut region = match lazy.current_region_id {
+        Some(rid) => match lazy.regions.iter().find(|r| r.id == rid) {
+            Some(r) => s.tr(
+                "hud.region",
+                &[
+                    ("name", &lazy.region_name(rid)),
+                    ("id", &rid),
+                    ("density", &s.number(r.density, 2)),
+                    ("stars", &s.count(r.star_count)),
+                    ("loaded", &lazy.loaded_star_count()),
+                ],
+            ),
+            None => s.tr("hud.no_region", &[]),
+        },
+        None => s.tr("hud.deep_space", &[]),
+    };
+    if let Some(id) = lazy.loading_region_id() {
+        region.push_str(&s.tr("hud.loading_region", &[("name", &lazy.region_name(id)), ("id", &id)]));
+    }
+    let mut lines = vec![region];
+
+    // Galaxy the camera is inside (regions from old saves have none)
+    let pos = cam_pos.as_dvec3().to_array();
+    if !lazy.loaded_galaxies.is_empty() {
+        lines.push(match lazy.galaxy_at(pos) {
+            Some(g) if g.kind == GalaxyKind::Spiral => s.tr(
+                "hud.galaxy_spiral",
+                &[("id", &g.id), ("kind", &g.kind.name()), ("arms", &g.arms), ("stars", &g.star_count)],
+            ),
+            Some(g) => s.tr(
+                "hud.galaxy",
+                &[("id", &g.id), ("kind", &g.kind.name()), ("stars", &g.star_count)],
+            ),
+            None => s.tr("hud.intergalactic", &[]),
+        });
+    }
+    if let Some(hole) = nearest_black_hole(universe, lazy, pos) {
+        let key = if hole.supermassive { "hud.black_hole_central" } else { "hud.black_hole_stellar" };
+        lines.push(s.tr(
+            key,
+            &[("mass", &s.scientific(hole.mass, 2)), ("distance", &s.number(hole.distance, 1))],
+        ));
+    }
+    lines
+}
+
+/// What is selected or under the cursor in space, and what [B] does with it
+fn selection_line(
+    strings: &Strings,
+    lazy: &LazyUniverse,
+    selection: &PlanetSelection,
+    focus: &SystemFocus,
+) -> Option<String> {
+    let s = strings;
+    if let Some(rid) = selection.selected_region {
+        return Some(match lazy.regions.iter().find(|r| r.id == rid) {
+            Some(region) => s.tr(
+                "hud.selected_region",
+                &[
+                    ("name", &lazy.region_name(rid)),
+                    ("id", &rid),
+                    ("density", &s.number(region.density, 2)),
+                    ("stars", &region.star_count),
+                ],
+            ),
+            None => s.tr("hud.selected_region_unknown", &[("id", &rid)]),
+        });
+    }
+    if let Some((planet, _)) = selection.selected_planet.as_ref() {
+        let civilization = civilization_status(s, lazy, planet.id)
+            .map(|status| format!(" | {status}"))
+            .unwrap_or_default();
+        return Some(s.tr(
+            "hud.selected_planet",
+            &[
+                ("name", &lazy.planet_name(planet.id)),
+                ("id", &planet.id),
+                ("kind", &format!("{:?}", planet.planet_type)),
+                ("temperature", &s.number(planet.surface_temp, 0)),
+                ("civilization", &civilization),
+            ],
+        ));
+    }
+    if let Some(star_id) = selection.selected_star {
+        let planets = lazy
+            .loaded_stars
+            .iter()
+            .find(|star| star.id == star_id)
+            .map_or(0, |star| star.planets.len());
+        return Some(s.tr(
+            "hud.selected_star",
+            &[("name", &lazy.star_name(star_id)), ("id", &star_id), ("planets", &planets)],
+        ));
+    }
+    if let Some(star_id) = focus.star_id {
+        return Some(s.tr("hud.system", &[("name", &lazy.star_name(star_id)), ("id", &star_id)]));
+    }
+    let hover = if selection.hovered_region.is_some() {
+        "hud.hover_region"
+    } else if selection.hovered.is_some() {
+        "hud.hover_planet"
+    } else if selection.hovered_star.is_some() {
+        "hud.hover_star"
+    } else {
+        return None;
+    };
+    Some(s.tr(hover, &[]))
+}
+
 /// HUD frame counter for throttling
 #[derive(Resource, Default)]
 pub struct HudThrottle {
     pub frame: u32,
 }
 
-/// Update HUD text every 10th frame (string formatting is expensive)
+/// Update HUD text every 10th frame (string formatting is expensive). Each panel is built
+/// from localized lines, joined at the end.
 pub fn update_hud(
-    universe: Res<UniverseState>,
-    lazy: Res<LazyUniverse>,
-    surface: Res<SurfaceState>,
-    selection: Res<PlanetSelection>,
-    nearest_creature: Res<NearestCreatureInfo>,
-    mut throttle: ResMut<HudThrottle>,
+    (universe, lazy, bookmarks): (Res<UniverseState>, Res<LazyUniverse>, Res<RewindBookmarks>),
+    (surface, day, weather, chunks): (Res<SurfaceState>, Res<SurfaceDay>, Res<WeatherState>, Res<TerrainChunks>),
+    (selection, focus, nearest_creature): (Res<PlanetSelection>, Res<SystemFocus>, Res<NearestCreatureInfo>),
+    (mut throttle, layout, strings, controls): (ResMut<HudThrottle>, Res<HudLayout>, Res<Strings>, Controls),
     mut hud_query: Query<&mut Text, (With<HudText>, Without<LifePanel>)>,
     mut life_query: Query<&mut Text, (With<LifePanel>, Without<HudText>)>,
     cam_query: Query<(&Transform, &FlyCamera)>,
 ) {
     throttle.frame = throttle.frame.wrapping_add(1);
-    if throttle.frame % 10 != 0 {
+    if !throttle.frame.is_multiple_of(10) {
         return;
     }
+    let s: &Strings = &strings;
 
     let cam_pos = cam_query
         .get_single()
         .map(|(t, _)| t.translation)
         .unwrap_or(Vec3::ZERO);
 
-    // === SURFACE MODE HUD ===
-    if surface.active {
+    // === COMPACT HUD === (the other panels are hidden by `hud_layout_system`)
+    if layout.compact {
         if let Ok(mut text) = hud_query.get_single_mut() {
-            if let Some(ref planet) = surface.planet {
-                let planet_name = format!("{:?}", planet.planet_type);
-                let life_str = if let Some(ref bio) = planet.life {
-                    format!(
-                        "Complexity: {:.1}/10 | Species: {} | Biomass: {:.1}",
-                        bio.complexity,
-                        fmt_count(bio.species_count),
-                        bio.biomass,
-                    )
-                } else {
-                    "No life detected".to_string()
-                };
-
-                let genome_str = if let Some(ref bio) = planet.life {
-                    bio.dominant_genome.describe()
-                } else {
-                    String::new()
-                };
-
-                let tech_str = planet
-                    .life
-                    .as_ref()
-                    .is_some_and(|b| b.has_technology)
-                    .then_some("** TECHNOLOGICAL CIVILIZATION **")
-                    .unwrap_or("");
-
-                let zoom_name = surface.surface_zoom.name();
-                let micro_banner = if surface.surface_zoom == SurfaceZoom::Microscopic {
-                    "\n** MICROSCOPIC VIEW **"
-                } else {
-                    ""
-                };
-
-                let creature_str = if !nearest_creature.description.is_empty()
-                    && nearest_creature.distance < 5.0
-                {
-                    format!("\nNearest creature ({:.1}m): {}", nearest_creature.distance, nearest_creature.description)
-                } else {
-                    String::new()
-                };
+            **text = s.tr(
+                "hud.compact",
+                &[
+                    ("age", &s.number(universe.age, 6)),
+                    ("phase", &universe.phase.name()),
+                    ("speed", &s.number(universe.time_scale, 0)),
+                    ("paused", &paused_tag(s, &universe)),
+                ],
+            );
+        }
+        return;
+    }
 
-                **text = format!(
-                    "SURFACE VIEW | {} planet\n\
-                     Temp: {:.0}K | Atmosphere: {:?}\n\
-                     Water: {} | Radius: {:.1} Earth\n\
-                     Zoom: {} | Height: {:.2}m{}\n\
-                     \n\
-                     {}\n\
-                     {}\n\
-                     {}{}\n\
-                     \n\
-                     Pos: ({:.1}, {:.1}, {:.1})\n\
-                     Age: {:.6} Gyr | Speed: {:.0}x\n\
-                     \n\
-                     === NAVIGATION ===\n\
-                     [WASD] Walk  [Mouse] Look  [Shift] Sprint\n\
-                     [Scroll] Zoom height\n\
-                     [Esc] or [B] Return to space\n\
-                     [Space] Pause  [1-5] Time",
-                    planet_name,
-                    planet.surface_temp,
-                    planet.atmosphere,
-                    if planet.has_water { "Yes" } else { "No" },
-                    planet.radius,
-                    zoom_name,
-                    surface.eye_height,
-                    micro_banner,
-                    life_str,
-                    genome_str,
-                    tech_str,
-                    creature_str,
-                    cam_pos.x,
-                    cam_pos.y,
-                    cam_pos.z,
-                    universe.age,
-                    universe.time_scale,
-                );
-            }
+    // === SURFACE MODE HUD ===
+    if surface.active {
+        if let Ok(mut text) = hud_query.get_single_mut()
+            && let Some(ref planet) = surface.planet
+        {
+            let mut lines = surface_hud(
+                s,
+                (&universe, &lazy),
+                (&surface, planet),
+                (&day, &weather),
+                &nearest_creature,
+                chunks.nearest_settlement(cam_pos.x, cam_pos.z),
+                cam_pos,
+            );
+            lines.push(String::new());
+            lines.push(help_block(s, &layout, &controls, surface_help(s, &controls)));
+            **text = lines.join("\n");
         }
 
         // Right panel in surface mode — life info + creature proximity
         if let Ok(mut text) = life_query.get_single_mut() {
-            let mut lines = Vec::new();
-
-            if let Some(ref planet) = surface.planet {
-                if let Some(ref bio) = planet.life {
-                    let genome = &bio.dominant_genome;
-                    lines.push("=== LIFE ON THIS PLANET ===".to_string());
-                    lines.push(String::new());
-                    lines.push(genome.describe());
-                    lines.push(format!("Senses: {}", genome.sense_list().join(", ")));
-                    lines.push(format!("Age: {:.1} Gyr | Complexity: {:.1}/10", bio.age, bio.complexity));
-                    lines.push(format!("Species: {} | Biomass: {:.1}", fmt_count(bio.species_count), bio.biomass));
-                    if bio.has_technology {
-                        lines.push("** TECHNOLOGICAL CIVILIZATION **".to_string());
-                    }
-                }
-            }
-
-            // Creature proximity detail
-            if !nearest_creature.description.is_empty() && nearest_creature.distance < 5.0 {
-                lines.push(String::new());
-                lines.push("=== NEARBY CREATURE ===".to_string());
-                lines.push(format!("Distance: {:.1}m", nearest_creature.distance));
-                lines.push(nearest_creature.description.clone());
-            }
-
-            // Microscopic hint
-            if surface.surface_zoom == SurfaceZoom::Microscopic {
-                lines.push(String::new());
-                lines.push("Observing microscopic life...".to_string());
-            }
-
-            **text = lines.join("\n");
+            **text = life_panel(s, &lazy, &surface, &nearest_creature).join("\n");
         }
         return;
     }
 
     // === SPACE MODE HUD ===
-    let (zoom_name, nearest_dist) = cam_query
+    let (zoom_name, level_mode, nearest_dist, fly_info) = cam_query
         .get_single()
-        .map(|(_, c)| (c.zoom_level.name(), c.nearest_dist))
-        .unwrap_or(("?", 0.0));
-
-    if let Ok(mut text) = hud_query.get_single_mut() {
-        let paused = if universe.paused { " [PAUSED]" } else { "" };
-
-        let region_info = if let Some(rid) = lazy.current_region_id {
-            if let Some(r) = lazy.regions.iter().find(|r| r.id == rid) {
-                format!(
-                    "Region #{} | Density: {:.2}x | Stars: {} | Loaded: {}",
-                    rid,
-                    r.density,
-                    fmt_count(r.star_count),
-                    lazy.loaded_star_count()
-                )
+        .map(|(_, c)| {
+            let mode = if c.auto_speed {
+                s.tr("hud.fly_auto", &[("multiplier", &s.number(c.speed_multiplier as f64, 2))])
             } else {
-                "No region".to_string()
-            }
-        } else {
-            "Deep space".to_string()
-        };
-
-        let selection_str = if selection.selected_region.is_some() {
-            let rid = selection.selected_region.unwrap();
-            if let Some(region) = lazy.regions.iter().find(|r| r.id == rid) {
-                format!(
-                    "\n[Selected] Region #{} (density: {:.2}x, stars: {}) — [B] to ENTER",
-                    rid, region.density, region.star_count
-                )
-            } else {
-                format!("\n[Selected] Region #{} — [B] to ENTER", rid)
-            }
-        } else if selection.selected_planet.is_some() {
-            let (planet, _) = selection.selected_planet.as_ref().unwrap();
-            format!(
-                "\n[Selected] {:?} {:.0}K — [B] to LAND",
-                planet.planet_type, planet.surface_temp,
-            )
-        } else if selection.hovered_region.is_some() {
-            "\n[Hover] Region — click to select".to_string()
-        } else if selection.hovered.is_some() {
-            "\n[Hover] Planet — click to select".to_string()
-        } else {
-            String::new()
-        };
+                s.tr("hud.fly_manual", &[])
+            };
+            let level_mode = match (c.auto_level, c.level_pin > 0.0) {
+                (true, false) => s.tr("hud.level_auto", &[]),
+                (true, true) => s.tr("hud.level_pinned", &[]),
+                (false, _) => String::new(),
+            };
+            let light = s.tr(
+                if c.warp { "hud.light_speed_warp" } else { "hud.light_speed" },
+                &[("fraction", &s.number(speed::light_fraction(c.velocity) as f64, 2))],
+            );
+            let fly = s.tr(
+                "hud.fly",
+                &[
+                    ("speed", &s.number(c.effective_speed() as f64, 1)),
+                    ("light", &light),
+                    ("mode", &mode),
+                ],
+            );
+            (c.zoom_level.name(), level_mode, c.nearest_dist, fly)
+        })
+        .unwrap_or(("?", String::new(), 0.0, String::new()));
 
+    if let Ok(mut text) = hud_query.get_single_mut() {
         let view_mode = match zoom_name {
-            "Cosmic" => "** REGIONS (overview) **",
-            "Galactic" => "** CLUSTERS + regions **",
-            "Stellar" => "STARS + planets",
-            "Planetary" => "DETAIL (full)",
-            _ => "SURFACE",
+            "Cosmic" => "hud.view_cosmic",
+            "Galactic" => "hud.view_galactic",
+            "Stellar" => "hud.view_stellar",
+            "Planetary" => "hud.view_planetary",
+            _ => "hud.view_surface",
         };
+        let config = &universe.config;
+        let mut lines = vec![
+            s.tr("hud.title", &[("cycle", &universe.cycle)]),
+            s.tr(
+                "hud.config",
+                &[
+                    ("seed", &universe.cycle_seed()),
+                    ("dark_matter", &s.number(config.dark_matter_fraction as f64 * 100.0, 0)),
+                    ("gravity", &s.number(config.gravity_scale as f64, 1)),
+                ],
+            ),
+            s.tr(
+                "hud.phase",
+                &[("phase", &universe.phase.name()), ("age", &s.number(universe.age, 6))],
+            ),
+            s.tr(
+                "hud.expansion",
+                &[
+                    ("scale", &s.number(universe.scale_factor, 4)),
+                    ("entropy", &s.number(universe.heat_death_progress() * 100.0, 0)),
+                ],
+            ),
+            s.tr(
+                "hud.particles",
+                &[
+                    ("particles", &s.number(universe.alive_count() as f64, 0)),
+                    ("ionized", &s.number(universe.ionized_fraction * 100.0, 0)),
+                    ("speed", &s.number(universe.time_scale, 0)),
+                    ("paused", &paused_tag(s, &universe)),
+                ],
+            ),
+            String::new(),
+            s.tr("hud.render_level", &[("level", &zoom_name), ("mode", &level_mode)]),
+            s.tr(
+                "hud.camera",
+                &[
+                    ("view", &s.tr(view_mode, &[])),
+                    ("distance", &s.number(nearest_dist as f64, 1)),
+                    ("fly", &fly_info),
+                ],
+            ),
+            position_line(s, cam_pos),
+            String::new(),
+            s.tr(
+                "hud.counts",
+                &[
+                    ("regions", &lazy.region_count()),
+                    ("stars", &s.count(lazy.total_stars())),
+                    ("planets", &s.count(lazy.total_planets())),
+                ],
+            ),
+        ];
+        lines.extend(region_lines(s, &universe, &lazy, cam_pos));
+        lines.extend(selection_line(s, &lazy, &selection, &focus));
+        lines.push(if bookmarks.is_empty() {
+            s.tr("hud.bookmarks_none", &[])
+        } else {
+            let ages: Vec<String> = bookmarks.ages().map(|age| s.number(age, 2)).collect();
+            s.tr("hud.bookmarks", &[("count", &bookmarks.len()), ("ages", &ages.join(", "))])
+        });
+        lines.push(super::bookmarks::hud_line(s, &lazy.bookmarks));
+        lines.push(String::new());
 
-        **text = format!(
-            "MATRIX v0.3 | Cycle: {}\n\
-             Phase: {} | Age: {:.6} Gyr\n\
-             Scale: {:.4} | Entropy: {:.1}\n\
-             Particles: {} | Speed: {:.0}x{}\n\
-             \n\
-             === RENDER LEVEL: {} ===\n\
-             Zoom: {} | Dist: {:.1}\n\
-             Pos: ({:.1}, {:.1}, {:.1})\n\
-             \n\
-             Regions: {} | Stars: {} | Planets: {}\n\
-             {}{}\n\
-             \n\
-             === NAVIGATION ===\n\
-             [WASD] Move  [RMB+Drag] Look  [Scroll] Speed\n\
-             [-/=] Zoom in/out\n\
-             [LMB] Select  [B] ENTER selected  [Esc] EXIT level\n\
-             \n\
-             [G/H] Next/Prev region  [F] Densest  [L] Life\n\
-             [N] Nearest  [T] Track  [O] Origin\n\
-             [Space] Pause  [1-5] Time  [F5/F9] Save/Load",
-            universe.cycle,
-            universe.phase.name(),
-            universe.age,
-            universe.scale_factor,
-            universe.total_entropy,
-            universe.alive_count(),
-            universe.time_scale,
-            paused,
-            zoom_name,
-            view_mode,
-            nearest_dist,
-            cam_pos.x,
-            cam_pos.y,
-            cam_pos.z,
-            lazy.region_count(),
-            fmt_count(lazy.total_stars()),
-            fmt_count(lazy.total_planets()),
-            region_info,
-            selection_str,
+        let cache_info = s.tr(
+            "hud.cache",
+            &[
+                ("cached", &lazy.cached_region_count()),
+                ("capacity", &lazy.config.region_cache_size),
+                ("hits", &lazy.cache_hits),
+                ("misses", &lazy.cache_misses),
+            ],
         );
+        let help = format!("{}\n\n{cache_info}", space_help(s, &controls));
+        lines.push(help_block(s, &layout, &controls, help));
+        **text = lines.join("\n");
     }
 
     // Right panel: clear in space mode (only used in surface mode for creature info)
@@ -321,27 +917,462 @@ pub fn update_hud(
     }
 }
 
-/// Handle keyboard input for time controls
+/// Handle keyboard / gamepad input for time controls
 pub fn time_control_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
+    surface: Res<SurfaceState>,
     mut universe: ResMut<UniverseState>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
+    // Space jumps on the surface, so pausing moves to P there
+    let pause = if surface.active { Action::SurfacePause } else { Action::Pause };
+    if controls.just_pressed(pause) {
         universe.paused = !universe.paused;
     }
-    if keyboard.just_pressed(KeyCode::Digit1) {
+    // With Ctrl / Alt the digits store and recall bookmarks instead
+    let presets = !controls.slot_modifier_held();
+    if presets && controls.just_pressed(Action::TimeScale1) {
         universe.time_scale = 1.0;
     }
-    if keyboard.just_pressed(KeyCode::Digit2) {
+    if presets && controls.just_pressed(Action::TimeScale2) {
         universe.time_scale = 100.0;
     }
-    if keyboard.just_pressed(KeyCode::Digit3) {
+    if presets && controls.just_pressed(Action::TimeScale3) {
         universe.time_scale = 10_000.0;
     }
-    if keyboard.just_pressed(KeyCode::Digit4) {
+    if presets && controls.just_pressed(Action::TimeScale4) {
         universe.time_scale = 1_000_000.0;
     }
-    if keyboard.just_pressed(KeyCode::Digit5) {
+    if presets && controls.just_pressed(Action::TimeScale5) {
         universe.time_scale = 1_000_000_000.0;
     }
+    if controls.just_pressed(Action::Slower) {
+        universe.time_scale = (universe.time_scale * 0.5).clamp(TIME_SCALE_MIN, TIME_SCALE_MAX);
+    }
+    if controls.just_pressed(Action::Faster) {
+        universe.time_scale = (universe.time_scale * 2.0).clamp(TIME_SCALE_MIN, TIME_SCALE_MAX);
+    }
+}
+
+/// Slider position (0..1) of a time scale, log-spaced over the allowed range
+fn slider_fraction(time_scale: f64) -> f32 {
+    let span = (TIME_SCALE_MAX / TIME_SCALE_MIN).log10();
+    ((time_scale / TIME_SCALE_MIN).log10() / span).clamp(0.0, 1.0) as f32
+}
+
+/// Time scale at slider position `fraction` (inverse of `slider_fraction`)
+fn slider_time_scale(fraction: f32) -> f64 {
+    let span = (TIME_SCALE_MAX / TIME_SCALE_MIN).log10();
+    TIME_SCALE_MIN * 10f64.powf(f64::from(fraction.clamp(0.0, 1.0)) * span)
+}
+
+/// Drag the HUD time slider to set the time scale; the handle follows keyboard changes too
+pub fn time_slider_system(
+    mut universe: ResMut<UniverseState>,
+    track_q: Query<(&Interaction, &RelativeCursorPosition), With<TimeSliderTrack>>,
+    mut handle_q: Query<&mut Node, With<TimeSliderHandle>>,
+    mut label_q: Query<&mut Text, With<TimeSliderLabel>>,
+    mut shown: Local<Option<(f64, bool)>>,
+) {
+    for (interaction, cursor) in &track_q {
+        if *interaction == Interaction::Pressed
+            && let Some(pos) = cursor.normalized
+        {
+            universe.time_scale = slider_time_scale(pos.x);
+        }
+    }
+    let state = (universe.time_scale, universe.paused);
+    if *shown == Some(state) {
+        return;
+    }
+    *shown = Some(state);
+    let fraction = slider_fraction(universe.time_scale);
+    for mut node in &mut handle_q {
+        node.left = Val::Px(fraction * (SLIDER_WIDTH - SLIDER_HANDLE_WIDTH));
+    }
+    if let Ok(mut text) = label_q.get_single_mut() {
+        **text = format!(
+            "Time ×{:.0}{}   [,/.] ×½ / ×2",
+            universe.time_scale,
+            if universe.paused { "  (paused)" } else { "" }
+        );
+    }
+}
+
+/// Tooltip lines for a hovered planet or moon of `star`
+fn planet_tooltip(strings: &Strings, lazy: &LazyUniverse, star: &Star, planet: &Planet) -> String {
+    let kind = match star.moon_parent(planet.id) {
+        Some(parent) => format!("moon of {}", lazy.planet_name(parent.id)),
+        None => "planet".to_string(),
+    };
+    let mut text = format!(
+        "{}\n\
+         {:?} {kind} #{}\n\
+         Mass: {:.2} Earth | Radius: {:.2} Earth\n\
+         Surface: {:.0}K | Orbit: {:.3} AU\n\
+         Atmosphere: {:?}{}",
+        lazy.planet_name(planet.id),
+        planet.planet_type,
+        planet.id,
+        planet.mass,
+        planet.radius,
+        planet.surface_temp,
+        planet.orbital_radius,
+        planet.atmosphere,
+        if planet.has_water { " | Water" } else { "" },
+    );
+    if planet.tidally_locked {
+        let temps = cosmology::locked_temperatures_from_terminator(planet.surface_temp, planet.has_atmosphere);
+        text.push_str(&format!(
+            "\nTidally locked (terminator {:.0}K, day {:.0}K / night {:.0}K)",
+            planet.surface_temp, temps.day, temps.night
+        ));
+    }
+    if !planet.moons.is_empty() {
+        text.push_str(&format!("\nMoons: {}", planet.moons.len()));
+    }
+    if let Some(rings) = planet.rings {
+        text.push_str(&format!(
+            "\nRings: {:.1}–{:.1} radii",
+            rings.inner_radius, rings.outer_radius
+        ));
+    }
+    if let Some(ref bio) = planet.life {
+        text.push_str(&format!(
+            "\nLife: complexity {:.1}/10 | {} species | {:.2} Gyr old",
+            bio.complexity,
+            strings.count(bio.species_count),
+            bio.age,
+        ));
+    }
+    if let Some(civ) = civilization_status(strings, lazy, planet.id) {
+        text.push_str(&format!("\n{civ}"));
+    }
+    if let Some(memory) = lazy.surface_memory.get(planet.id).filter(|m| m.visits > 0) {
+        let plural = if memory.visits == 1 { "" } else { "s" };
+        text.push_str(&format!("\nVisited ({} landing{plural})", memory.visits));
+    }
+    text
+}
+
+/// Tooltip lines for a hovered star (`Star::age` is the universe age it formed at)
+fn star_tooltip(lazy: &LazyUniverse, star: &Star, universe_age: f64) -> String {
+    let kind = match star.remnant {
+        Some(remnant) => format!("Class {:?} {}", star.spectral_class, remnant.name()),
+        None => format!("Class {:?} star", star.spectral_class),
+    };
+    format!(
+        "{}\n\
+         {} #{}\n\
+         Mass: {:.2} Sun | Luminosity: {:.3} Sun\n\
+         Age: {:.2} Gyr | Planets: {} | Comets: {}{}",
+        lazy.star_name(star.id),
+        kind,
+        star.id,
+        star.mass,
+        star.luminosity,
+        (universe_age - star.age).max(0.0),
+        star.planets.len(),
+        star.comets.len(),
+        star.asteroid_belt.as_ref().map_or(String::new(), |belt| format!(
+            "\nAsteroid belt: {:.1}–{:.1} AU",
+            belt.inner_radius, belt.outer_radius
+        )),
+    )
+}
+
+/// Show details of the hovered planet / star next to the cursor, kept inside the window
+pub fn update_tooltip(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    (universe, lazy, strings): (Res<UniverseState>, Res<LazyUniverse>, Res<Strings>),
+    surface_state: Res<SurfaceState>,
+    selection: Res<PlanetSelection>,
+    mut tooltip_q: Query<(&mut Node, &ComputedNode), With<HoverTooltip>>,
+    mut text_q: Query<&mut Text, With<TooltipText>>,
+) {
+    let Ok((mut node, computed)) = tooltip_q.get_single_mut() else {
+        return;
+    };
+
+    let content = if surface_state.active {
+        None
+    } else if let Some((star_id, planet_id)) = selection.hovered {
+        surface::find_planet(&lazy, star_id, planet_id)
+            .map(|(star, planet)| planet_tooltip(&strings, &lazy, star, planet))
+    } else {
+        selection
+            .hovered_star
+            .and_then(|id| lazy.loaded_stars.iter().find(|s| s.id == id))
+            .map(|star| star_tooltip(&lazy, star, universe.age))
+    };
+    let window = windows.get_single().ok();
+    let cursor = window.and_then(|w| w.cursor_position());
+
+    let (Some(content), Some(window), Some(cursor)) = (content, window, cursor) else {
+        if node.display != Display::None {
+            node.display = Display::None;
+        }
+        return;
+    };
+
+    if let Ok(mut text) = text_q.get_single_mut()
+        && text.0 != content
+    {
+        text.0 = content;
+    }
+
+    // Prefer below-right of the cursor, flip to the other side near the window edges
+    let size = computed.size() * computed.inverse_scale_factor();
+    let window_size = Vec2::new(window.width(), window.height());
+    let mut pos = cursor + TOOLTIP_OFFSET;
+    if pos.x + size.x > window_size.x {
+        pos.x = cursor.x - TOOLTIP_OFFSET.x - size.x;
+    }
+    if pos.y + size.y > window_size.y {
+        pos.y = cursor.y - TOOLTIP_OFFSET.y - size.y;
+    }
+    let pos = pos.clamp(Vec2::ZERO, (window_size - size).max(Vec2::ZERO));
+
+    node.display = Display::Flex;
+    node.left = Val::Px(pos.x);
+    node.top = Val::Px(pos.y);
+}
+
+/// Show the signal heard at the camera (`LazyUniverse::detected_signal`): bearing relative
+/// to the view, distance, and a dial dot pointing at the source. Hidden on planet surfaces.
+pub fn update_signal_indicator(
+    lazy: Res<LazyUniverse>,
+    surface: Res<SurfaceState>,
+    cam_q: Query<&Transform, With<FlyCamera>>,
+    mut panel_q: Query<&mut Node, (With<SignalIndicator>, Without<SignalBearingDot>)>,
+    mut dot_q: Query<&mut Node, (With<SignalBearingDot>, Without<SignalIndicator>)>,
+    mut text_q: Query<&mut Text, With<SignalIndicatorText>>,
+) {
+    let Ok(mut panel) = panel_q.get_single_mut() else {
+        return;
+    };
+    let signal = lazy.detected_signal.filter(|_| !surface.active);
+    let (Some(signal), Ok(cam)) = (signal, cam_q.get_single()) else {
+        panel.display = Display::None;
+        return;
+    };
+    panel.display = Display::Flex;
+
+    // Direction in camera space: +X right, +Y up, -Z forward
+    let local = cam.rotation.inverse() * Vec3::from(signal.direction.map(|d| d as f32));
+    let azimuth = local.x.atan2(-local.z).to_degrees();
+    let elevation = local.y.clamp(-1.0, 1.0).asin().to_degrees();
+    let off_axis = (-local.z).clamp(-1.0, 1.0).acos().to_degrees();
+
+    if let Ok(mut dot) = dot_q.get_single_mut() {
+        let rim = (SIGNAL_DIAL_SIZE - SIGNAL_DOT_SIZE) / 2.0 - 1.0;
+        let offset = if off_axis < SIGNAL_AHEAD_DEG {
+            Vec2::ZERO
+        } else {
+            let angle = local.y.atan2(local.x);
+            Vec2::new(angle.cos(), -angle.sin()) * rim
+        };
+        dot.left = Val::Px(rim + offset.x);
+        dot.top = Val::Px(rim + offset.y);
+    }
+
+    if let Ok(mut text) = text_q.get_single_mut() {
+        let side = if azimuth >= 0.0 { "right" } else { "left" };
+        let tilt = if elevation >= 0.0 { "up" } else { "down" };
+        let title = if signal.fading {
+            "FADING SIGNAL (source silent)"
+        } else {
+            "ARTIFICIAL SIGNAL DETECTED"
+        };
+        **text = format!(
+            "{title}\nBearing {:.0}° {side}, {:.0}° {tilt}\n{:.0} Mpc — {}",
+            azimuth.abs(),
+            elevation.abs(),
+            signal.distance,
+            lazy.region_name(signal.region_id),
+        );
+    }
+}
+
+/// Show the selected region's stats in the inspector panel. Rebuilt only when the
+/// selection, the region stats (`LazyUniverse::stats_generation`), its life flag, its name or the language change.
+pub fn update_region_inspector(
+    mut commands: Commands,
+    (universe, lazy, strings): (Res<UniverseState>, Res<LazyUniverse>, Res<Strings>),
+    surface_state: Res<SurfaceState>,
+    selection: Res<PlanetSelection>,
+    mut shown: Local<Option<(u64, u32, bool, String)>>,
+    mut panel_q: Query<(Entity, &mut Node), With<RegionInspector>>,
+) {
+    let Ok((panel, mut node)) = panel_q.get_single_mut() else {
+        return;
+    };
+    let region = selection
+        .selected_region
+        .filter(|_| !surface_state.active)
+        .and_then(|id| lazy.regions.iter().find(|r| r.id == id));
+
+    let Some(region) = region else {
+        if shown.take().is_some() {
+            node.display = Display::None;
+            commands.entity(panel).despawn_descendants();
+        }
+        return;
+    };
+    let name = lazy.region_name(region.id);
+    let key = (region.id, lazy.stats_generation, region.has_life, name.clone());
+    if shown.as_ref() == Some(&key) && !strings.is_changed() {
+        return;
+    }
+    *shown = Some(key);
+    node.display = Display::Flex;
+
+    let [h, he, metals] = region.composition;
+    let span = universe.age.max(SPARKLINE_MIN_SPAN);
+    let samples = sfr_sparkline(span, SPARKLINE_SAMPLES);
+    let sfr_now = cosmology::star_formation_rate(universe.age) * region.density;
+    let life = if lazy.has_advanced_civilization(region.id) {
+        "Life: yes (Type I+ civilization)"
+    } else if region.has_life {
+        "Life: yes"
+    } else {
+        "Life: none known"
+    };
+
+    commands.entity(panel).despawn_descendants();
+    commands.entity(panel).with_children(|p| {
+        p.spawn(inspector_text(name.to_uppercase(), 15.0));
+        p.spawn(inspector_text(format!("Region #{} — rename with `rename region <name>`", region.id), 11.0));
+        p.spawn(inspector_text(
+            format!(
+                "Density: {:.2}x | Dark matter: {:.0}%\n\
+                 Temperature: {:.2} K\n\
+                 Stars: ~{} | Planets: ~{}\n\
+                 {}",
+                region.density,
+                region.dark_matter * 100.0,
+                region.temperature,
+                strings.count(region.star_count),
+                strings.count(region.planet_count),
+                life,
+            ),
+            13.0,
+        ));
+
+        // Composition: one bar, segments sized by mass fraction
+        p.spawn(inspector_text(
+            format!(
+                "H {:.1}% | He {:.1}% | metals {:.2}%",
+                h * 100.0,
+                he * 100.0,
+                metals * 100.0
+            ),
+            12.0,
+        ));
+        p.spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Px(10.0),
+            flex_direction: FlexDirection::Row,
+            ..default()
+        })
+        .with_children(|bar| {
+            let segments = [
+                (h, Color::srgb(0.4, 0.6, 1.0)),
+                (he, Color::srgb(1.0, 0.9, 0.4)),
+                (metals, Color::srgb(1.0, 0.5, 0.2)),
+            ];
+            for (fraction, color) in segments {
+                bar.spawn((
+                    Node {
+                        width: Val::Percent((fraction * 100.0) as f32),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+            }
+        });
+
+        // Star formation rate over cosmic time; columns after the current age are dimmed
+        p.spawn(inspector_text(
+            format!(
+                "Star formation 0–{:.1} Gyr | now {:.3} M☉/yr/Mpc³",
+                span, sfr_now
+            ),
+            12.0,
+        ));
+        p.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(SPARKLINE_HEIGHT),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::FlexEnd,
+                column_gap: Val::Px(1.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.2, 0.1, 0.4)),
+        ))
+        .with_children(|line| {
+            for (i, value) in samples.iter().enumerate() {
+                let age = (i as f64 + 0.5) / SPARKLINE_SAMPLES as f64 * span;
+                let color = if age <= universe.age {
+                    Color::srgb(0.3, 1.0, 0.5)
+                } else {
+                    Color::srgba(0.3, 1.0, 0.5, 0.25)
+                };
+                line.spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        height: Val::Percent((value * 100.0) as f32),
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+            }
+        });
+    });
+}
+
+fn inspector_text(text: String, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(Color::srgba(0.4, 1.0, 0.6, 0.95)),
+    )
+}
+
+/// Star formation rate at `n` evenly spaced ages over [0, span_gyr] (bin centers),
+/// normalized so the highest sample is 1
+fn sfr_sparkline(span_gyr: f64, n: usize) -> Vec<f64> {
+    let rates: Vec<f64> = (0..n)
+        .map(|i| cosmology::star_formation_rate((i as f64 + 0.5) / n as f64 * span_gyr))
+        .collect();
+    let peak = rates.iter().cloned().fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return rates;
+    }
+    rates.into_iter().map(|r| r / peak).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sfr_sparkline_peaks_at_cosmic_noon() {
+        let samples = sfr_sparkline(13.8, 48);
+        assert_eq!(samples.len(), 48);
+        assert!(samples.iter().all(|v| (0.0..=1.0).contains(v)));
+        // No stars before Cosmic Dawn, peak around 3.3 Gyr
+        assert_eq!(samples[0], 0.0);
+        let peak = samples
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| (i as f64 + 0.5) / 48.0 * 13.8)
+            .unwrap();
+        assert!((2.5..4.0).contains(&peak), "peak at {peak} Gyr");
+    }
 }
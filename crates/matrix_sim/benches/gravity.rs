@@ -0,0 +1,51 @@
+//! One full CPU particle step (hybrid gravity, electromagnetism, integration, black hole
+//! capture) on an expanding Big Bang of 10K, 50K and 100K particles, for each integrator
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use matrix_core::constants::DT;
+use matrix_core::{Integrator, SimConfig};
+use matrix_physics::particle;
+use matrix_sim::universe::UniverseState;
+use rand::SeedableRng;
+
+const PARTICLE_COUNTS: [u32; 3] = [10_000, 50_000, 100_000];
+/// How long the Big Bang coasts before it is measured. A fresh one is a cube 0.02 across
+/// that the near-field hash can't split; this spreads it into a ball like the first steps do.
+const COAST: f32 = 2.0;
+
+fn universe(particle_count: u32, integrator: Integrator) -> UniverseState {
+    let config = SimConfig {
+        particle_count,
+        integrator,
+        ..SimConfig::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+    let mut particles = particle::generate_big_bang(&config, &mut rng);
+    for p in &mut particles {
+        for k in 0..3 {
+            p.position[k] += p.velocity[k] * COAST;
+        }
+    }
+    let mut universe = UniverseState::new(config, particles);
+    // The first leapfrog step also computes the accelerations it starts from
+    universe.force_gravity_step(DT as f64);
+    universe
+}
+
+fn gravity_step(c: &mut Criterion) {
+    for (name, integrator) in [("euler", Integrator::Euler), ("leapfrog", Integrator::Leapfrog)] {
+        let mut group = c.benchmark_group(format!("gravity_step_{name}"));
+        group.sample_size(10);
+        for count in PARTICLE_COUNTS {
+            let mut universe = universe(count, integrator);
+            group.throughput(Throughput::Elements(count as u64));
+            group.bench_function(BenchmarkId::from_parameter(count), |b| {
+                b.iter(|| universe.force_gravity_step(DT as f64))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, gravity_step);
+criterion_main!(benches);
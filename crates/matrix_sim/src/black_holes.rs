@@ -0,0 +1,173 @@
+use matrix_core::constants::BLACK_HOLE_CAPTURE_RADIUS;
+use matrix_core::{ParticleKind, StellarRemnant};
+use matrix_physics::forces::SpatialHash;
+
+use crate::lazy_universe::LazyUniverse;
+use crate::universe::UniverseState;
+
+/// Accretion glow (a black hole particle's `temperature`) added per unit of mass captured
+pub const ACCRETION_GLOW_PER_MASS: f32 = 1.0;
+/// Fraction of the accretion glow lost per unit of simulation time
+pub const ACCRETION_FADE: f32 = 50.0;
+
+/// The black hole closest to a point in the loaded region
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearbyBlackHole {
+    /// Mass (solar masses), including what the central one has swallowed since loading
+    pub mass: f64,
+    /// Distance from the point (Mpc)
+    pub distance: f64,
+    /// The region's central black hole rather than a stellar remnant
+    pub supermassive: bool,
+}
+
+impl UniverseState {
+    /// Let black hole particles swallow everything within `BLACK_HOLE_CAPTURE_RADIUS`:
+    /// the victim dies, its mass and momentum go to the hole and its accretion glow rises.
+    /// `hash` is the near-field hash of the gravity step. Returns the number captured.
+    pub(crate) fn capture_into_black_holes(&mut self, hash: &SpatialHash) -> usize {
+        let holes: Vec<usize> = self
+            .particles
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_alive() && p.kind == ParticleKind::BlackHole as u32)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut captured = 0;
+        for hole in holes {
+            // Swallowed by a heavier hole earlier in this pass
+            if !self.particles[hole].is_alive() {
+                continue;
+            }
+            let pos = self.particles[hole].pos();
+            for victim in hash.within_radius(pos, BLACK_HOLE_CAPTURE_RADIUS, &self.particles) {
+                let prey = self.particles[victim];
+                if victim == hole || !prey.is_alive() {
+                    continue;
+                }
+                // Two holes merge into the heavier one
+                if prey.kind == ParticleKind::BlackHole as u32
+                    && prey.mass() > self.particles[hole].mass()
+                {
+                    continue;
+                }
+                self.particles[victim].flags &= !1;
+                let p = &mut self.particles[hole];
+                let (mass, gained) = (p.mass(), prey.mass());
+                let total = mass + gained;
+                if total > 0.0 {
+                    for k in 0..3 {
+                        p.velocity[k] = (p.velocity[k] * mass + prey.velocity[k] * gained) / total;
+                    }
+                }
+                p.position[3] = total;
+                p.temperature += gained * ACCRETION_GLOW_PER_MASS;
+                captured += 1;
+            }
+        }
+        self.captured_particles += captured as u64;
+        captured
+    }
+
+    /// `capture_into_black_holes` for particles moved outside the CPU gravity step
+    /// (GPU readbacks), with a hash built just for the capture radius
+    pub fn capture_black_hole_infall(&mut self) -> usize {
+        let has_holes = self
+            .particles
+            .iter()
+            .any(|p| p.is_alive() && p.kind == ParticleKind::BlackHole as u32);
+        if !has_holes {
+            return 0;
+        }
+        let hash = SpatialHash::build(&self.particles, BLACK_HOLE_CAPTURE_RADIUS * 4.0);
+        self.capture_into_black_holes(&hash)
+    }
+}
+
+/// The black hole of the loaded region nearest to `pos`: its central one (tracked through
+/// its particle while the particle sim has it) or a stellar remnant among the loaded stars
+pub fn nearest_black_hole(
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    pos: [f64; 3],
+) -> Option<NearbyBlackHole> {
+    let region_id = lazy.current_region_id?;
+    let region = lazy.regions.iter().find(|r| r.id == region_id)?;
+    let distance = |p: [f64; 3]| {
+        (0..3)
+            .map(|k| (p[k] - pos[k]).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+
+    let central = region.central_black_hole.map(|initial_mass| {
+        universe
+            .particles
+            .iter()
+            .filter(|p| p.is_alive() && p.kind == ParticleKind::BlackHole as u32)
+            .map(|p| NearbyBlackHole {
+                mass: initial_mass * (p.mass() / ParticleKind::BlackHole.default_mass()) as f64,
+                distance: distance(p.pos().map(f64::from)),
+                supermassive: true,
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap_or(NearbyBlackHole {
+                mass: initial_mass,
+                distance: distance(region.center),
+                supermassive: true,
+            })
+    });
+    let stellar = lazy
+        .loaded_stars
+        .iter()
+        .filter(|s| s.remnant == Some(StellarRemnant::BlackHole))
+        .map(|s| NearbyBlackHole {
+            mass: s.mass,
+            distance: distance(s.position),
+            supermassive: false,
+        });
+    central
+        .into_iter()
+        .chain(stellar)
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{GpuParticle, SimConfig};
+
+    #[test]
+    fn test_black_hole_captures_nearby_particles() {
+        let hole = GpuParticle::new(
+            [0.0; 3],
+            [0.0; 3],
+            ParticleKind::BlackHole.default_mass(),
+            0.0,
+            ParticleKind::BlackHole,
+        );
+        let mut near = GpuParticle::new([0.3, 0.0, 0.0], [-1.0, 0.0, 0.0], 10.0, 0.0, ParticleKind::Hydrogen);
+        near.temperature = 0.0;
+        let far = GpuParticle::new([5.0, 0.0, 0.0], [0.0; 3], 10.0, 0.0, ParticleKind::Hydrogen);
+        let mut universe = UniverseState::new(SimConfig::default(), vec![hole, near, far]);
+        universe.particles[0].temperature = 0.0;
+
+        assert_eq!(universe.capture_black_hole_infall(), 1);
+        let hole = universe.particles[0];
+        assert!(!universe.particles[1].is_alive());
+        assert!(universe.particles[2].is_alive());
+        assert_eq!(hole.mass(), ParticleKind::BlackHole.default_mass() + 10.0);
+        // Momentum is kept, and the capture lights up the disk
+        assert!((hole.velocity[0] * hole.mass() + 10.0).abs() < 1e-3);
+        assert!(hole.temperature > 0.0);
+        assert_eq!(universe.capture_black_hole_infall(), 0);
+
+        // Holes merge into the heavier one
+        let small = GpuParticle::new([0.0, 0.2, 0.0], [0.0; 3], 100.0, 0.0, ParticleKind::BlackHole);
+        universe.particles.push(small);
+        assert_eq!(universe.capture_black_hole_infall(), 1);
+        assert!(universe.particles[0].is_alive());
+        assert!(!universe.particles[3].is_alive());
+    }
+}
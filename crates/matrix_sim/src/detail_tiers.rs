@@ -0,0 +1,259 @@
+use matrix_core::{GpuParticle, Galaxy, MassPoint, Planet, RegionDetail, Star, SurfacePreset};
+use matrix_physics::procgen;
+
+use crate::lazy_universe::LazyUniverse;
+
+/// Distance from a loaded star (world units, where the camera's Planetary zoom starts)
+/// within which the loaded region is raised to `RegionDetail::Planetary`
+pub const PLANETARY_RANGE: f64 = 10.0;
+/// How often (Gyr) the loaded region is evolved at `RegionDetail::Biosphere`,
+/// instead of once per reload
+pub const BIOSPHERE_TICK_GYR: f64 = 0.5;
+
+/// Heap held by the data of each detail tier (bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DetailMemory {
+    /// Mass points of the Galactic regions
+    pub galactic: usize,
+    /// Stars, galaxies and particles of the loaded and cached regions, and the stars of
+    /// the neighbors
+    pub stellar: usize,
+    /// Surface presets of the star the camera is at
+    pub planetary: usize,
+}
+
+impl DetailMemory {
+    pub fn total(&self) -> usize {
+        self.galactic + self.stellar + self.planetary
+    }
+}
+
+/// Heap held by `stars` and their planets, moons, belts and comets
+pub(crate) fn stars_memory(stars: &[Star]) -> usize {
+    let bodies: usize = stars
+        .iter()
+        .map(|s| {
+            let moons: usize = s.planets.iter().map(|p| p.moons.capacity()).sum();
+            (s.planets.capacity() + moons) * size_of::<Planet>()
+                + s.comets.capacity() * size_of::<matrix_core::Comet>()
+        })
+        .sum();
+    size_of_val(stars) + bodies
+}
+
+impl LazyUniverse {
+    /// Detail of the loaded region (Statistical when nothing is loaded)
+    pub fn current_detail(&self) -> RegionDetail {
+        self.current_region_id
+            .and_then(|id| self.regions.iter().find(|r| r.id == id))
+            .map_or(RegionDetail::Statistical, |r| r.detail.clone())
+    }
+
+    /// Mass points of a region at `RegionDetail::Galactic`
+    pub fn galactic_points_of(&self, region_id: u64) -> Option<&[MassPoint]> {
+        self.galactic_points
+            .iter()
+            .find(|(id, _)| *id == region_id)
+            .map(|(_, points)| &points[..])
+    }
+
+    /// Landing parameters of a planet or moon of the star the camera is at
+    pub fn surface_preset(&self, planet_id: u64) -> Option<&SurfacePreset> {
+        self.surface_presets.iter().find(|p| p.planet_id == planet_id)
+    }
+
+    /// Raise the loaded region to Planetary near one of its stars (Biosphere if that star
+    /// hosts life), then generate the data of every tier that was raised and drop the data
+    /// of every tier that was lowered. Data already loaded for its tier is kept as is.
+    pub(crate) fn update_detail_tiers(&mut self, age_gyr: f64) {
+        let loaded = self.current_region_id.filter(|_| !self.loaded_stars.is_empty());
+        let nearest = self
+            .nearest_loaded_star()
+            .filter(|(_, d)| *d < PLANETARY_RANGE)
+            .map(|(star, _)| (star.id, star.planets.iter().any(|p| p.life.is_some())));
+        for region in self.regions.iter_mut() {
+            if region.detail < RegionDetail::Stellar {
+                continue;
+            }
+            region.detail = match nearest.filter(|_| Some(region.id) == loaded) {
+                Some((_, true)) => RegionDetail::Biosphere,
+                Some((_, false)) => RegionDetail::Planetary,
+                None => RegionDetail::Stellar,
+            };
+        }
+
+        // Galactic: mass points for exactly the regions at that level
+        let regions = &self.regions;
+        let before = self.galactic_points.len();
+        self.galactic_points.retain(|(id, _)| {
+            regions.iter().any(|r| r.id == *id && r.detail == RegionDetail::Galactic)
+        });
+        let mut changed = self.galactic_points.len() != before;
+        for region in regions.iter().filter(|r| r.detail == RegionDetail::Galactic) {
+            if !self.galactic_points.iter().any(|(id, _)| *id == region.id) {
+                self.galactic_points.push((region.id, procgen::generate_mass_points(region)));
+                changed = true;
+            }
+        }
+        if changed {
+            self.galactic_generation = self.galactic_generation.wrapping_add(1);
+        }
+
+        // Planetary: surface presets for the planets of the star the camera is at
+        let preset_star = nearest
+            .filter(|_| self.current_detail() >= RegionDetail::Planetary)
+            .map(|(id, _)| id);
+        if preset_star != self.preset_star {
+            self.surface_presets = preset_star
+                .and_then(|id| self.loaded_stars.iter().find(|s| s.id == id))
+                .map(|star| procgen::generate_surface_presets(star, age_gyr))
+                .unwrap_or_default();
+            self.preset_star = preset_star;
+            if let Some(id) = preset_star {
+                info!(
+                    "Prepared the surfaces of {} bodies around star {}",
+                    self.surface_presets.len(),
+                    id
+                );
+            }
+        }
+    }
+
+    /// The loaded star nearest to the camera and its distance
+    fn nearest_loaded_star(&self) -> Option<(&Star, f64)> {
+        self.loaded_stars
+            .iter()
+            .map(|s| {
+                let d2: f64 = (0..3).map(|k| (s.position[k] - self.camera_pos[k]).powi(2)).sum();
+                (s, d2.sqrt())
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Heap held by each detail tier
+    pub fn detail_memory(&self) -> DetailMemory {
+        let galactic = self
+            .galactic_points
+            .iter()
+            .map(|(_, points)| {
+                size_of::<(u64, Vec<MassPoint>)>() + points.capacity() * size_of::<MassPoint>()
+            })
+            .sum();
+        let cached: usize = self
+            .region_cache
+            .iter()
+            .map(|c| {
+                stars_memory(&c.stars)
+                    + c.galaxies.capacity() * size_of::<Galaxy>()
+                    + c.particles.capacity() * size_of::<GpuParticle>()
+            })
+            .sum();
+        let neighbors: usize = self.neighbor_stars.iter().map(|n| stars_memory(&n.stars)).sum();
+        DetailMemory {
+            galactic,
+            stellar: stars_memory(&self.loaded_stars)
+                + self.loaded_galaxies.capacity() * size_of::<Galaxy>()
+                + self.loaded_particles.capacity() * size_of::<GpuParticle>()
+                + cached
+                + neighbors,
+            planetary: self.surface_presets.capacity() * size_of::<SurfacePreset>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    const AGE: f64 = 13.0;
+
+    /// Run the LOD long enough for one distance check at `pos`
+    fn settle(lazy: &mut LazyUniverse, pos: [f64; 3]) {
+        for _ in 0..5 {
+            lazy.update_lod(pos, AGE);
+        }
+    }
+
+    fn sorted_points(lazy: &LazyUniverse) -> Vec<(u64, Vec<MassPoint>)> {
+        let mut points = lazy.galactic_points.clone();
+        points.sort_by_key(|(id, _)| *id);
+        points
+    }
+
+    #[test]
+    fn test_detail_tiers_raise_and_lower_deterministically() {
+        let mut lazy = LazyUniverse::new(SimConfig::default(), AGE);
+        lazy.async_loading = false;
+        let home = lazy.regions[0].clone();
+        settle(&mut lazy, home.center);
+        assert_eq!(lazy.current_region_id, Some(home.id));
+
+        // A spot of the region away from every star: Stellar detail
+        let open_space = (0..729)
+            .map(|i| [i % 9, i / 9 % 9, i / 81].map(|n| (n as f64 - 4.0) * 6.0))
+            .map(|offset| std::array::from_fn(|k| home.center[k] + offset[k]))
+            .find(|pos: &[f64; 3]| {
+                lazy.loaded_stars.iter().all(|s| {
+                    (0..3).map(|k| (s.position[k] - pos[k]).powi(2)).sum::<f64>().sqrt()
+                        > PLANETARY_RANGE
+                })
+            })
+            .expect("open space in the region");
+        settle(&mut lazy, open_space);
+        assert_eq!(lazy.current_detail(), RegionDetail::Stellar);
+        assert!(lazy.surface_presets.is_empty());
+
+        // Neighbors within two region sizes get mass points, nothing else does
+        let points = sorted_points(&lazy);
+        assert!(!points.is_empty());
+        for (id, region_points) in &points {
+            let region = lazy.regions.iter().find(|r| r.id == *id).unwrap();
+            assert_eq!(region.detail, RegionDetail::Galactic);
+            assert_eq!(region_points.len(), procgen::MASS_POINTS);
+        }
+        assert!(lazy.galactic_points_of(home.id).is_none());
+        let generation = lazy.galactic_generation;
+        settle(&mut lazy, open_space);
+        assert_eq!(lazy.galactic_generation, generation);
+        assert_eq!(sorted_points(&lazy), points);
+        assert!(lazy.detail_memory().galactic > 0);
+
+        // Up to a star: Planetary (Biosphere with life), its bodies prepared for landing
+        let star = lazy.loaded_stars[0].clone();
+        settle(&mut lazy, star.position);
+        let has_life = star.planets.iter().any(|p| p.life.is_some());
+        let expected = if has_life { RegionDetail::Biosphere } else { RegionDetail::Planetary };
+        assert_eq!(lazy.current_detail(), expected);
+        assert_eq!(lazy.preset_star, Some(star.id));
+        let bodies = star.planets.iter().map(|p| 1 + p.moons.len()).sum::<usize>();
+        assert_eq!(lazy.surface_presets.len(), bodies);
+        let presets = lazy.surface_presets.clone();
+        if let Some(planet) = star.planets.first() {
+            assert!(lazy.surface_preset(planet.id).is_some());
+        }
+
+        // Back out and in again: presets dropped, then the same ones again
+        settle(&mut lazy, open_space);
+        assert_eq!(lazy.current_detail(), RegionDetail::Stellar);
+        assert!(lazy.surface_presets.is_empty());
+        assert_eq!(lazy.detail_memory().planetary, 0);
+        settle(&mut lazy, star.position);
+        assert_eq!(lazy.surface_presets, presets);
+
+        // The far corner: the old neighbors drop to Statistical and lose their points,
+        // then come back identical
+        let far = lazy.regions.last().unwrap().center;
+        settle(&mut lazy, far);
+        assert!(points.iter().all(|(id, _)| lazy.galactic_points_of(*id).is_none()));
+        settle(&mut lazy, open_space);
+        assert_eq!(lazy.current_region_id, Some(home.id));
+        assert_eq!(sorted_points(&lazy), points);
+
+        // Another universe with the same seed raises the same detail
+        let mut other = LazyUniverse::new(SimConfig::default(), AGE);
+        other.async_loading = false;
+        settle(&mut other, open_space);
+        assert_eq!(sorted_points(&other), points);
+    }
+}
@@ -0,0 +1,123 @@
+use matrix_core::constants::HEAT_DEATH_ENTROPY;
+use matrix_core::{UniversePhase, MAX_ENTROPY};
+use matrix_physics::{spacetime, stellar, thermodynamics};
+
+use crate::universe::{PHASE_SCHEDULE, UniverseState};
+
+/// Shares of the Heat Death entropy from the scale factor's growth and from stellar
+/// deaths: together they reach `HEAT_DEATH_ENTROPY` at `SimConfig::heat_death_age`
+const EXPANSION_WEIGHT: f64 = 0.6;
+const STELLAR_WEIGHT: f64 = 0.3;
+/// Shares of the particle sources, which only bring the Heat Death forward
+const DISPERSION_WEIGHT: f64 = 0.05;
+const CAPTURE_WEIGHT: f64 = 0.05;
+/// Mean log velocity dispersion per particle counted as fully thermalized
+const DISPERSION_REFERENCE: f64 = 5.0;
+
+/// Where the entropy of a universe comes from, each term normalized
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EntropySources {
+    /// ln(scale factor) relative to its expected value at the heat death age
+    pub expansion: f64,
+    /// Share of stars that are remnants, relative to its value at the heat death age
+    pub stellar_deaths: f64,
+    /// Velocity dispersion of the particles (0–1)
+    pub dispersion: f64,
+    /// Share of the Big Bang's particles swallowed by black holes (0–1)
+    pub captured: f64,
+}
+
+impl EntropySources {
+    /// Total entropy for these sources
+    pub fn entropy(&self) -> f64 {
+        let clock = EXPANSION_WEIGHT * self.expansion + STELLAR_WEIGHT * self.stellar_deaths;
+        let particles = DISPERSION_WEIGHT * self.dispersion + CAPTURE_WEIGHT * self.captured;
+        MAX_ENTROPY * (clock + particles)
+    }
+}
+
+/// ln of the scale factor by `age_gyr` if every phase lasts as scheduled and the
+/// Civilization Era carries on from there
+pub fn expected_log_scale_factor(age_gyr: f64) -> f64 {
+    let mut log_a = 0.0;
+    let mut start = 0.0;
+    for &(phase, end, _) in &PHASE_SCHEDULE {
+        log_a += spacetime::hubble_parameter(start, phase) * (end.min(age_gyr) - start).max(0.0);
+        start = end;
+    }
+    log_a += spacetime::hubble_parameter(start, UniversePhase::CivilizationEra)
+        * (age_gyr - start).max(0.0);
+    log_a * spacetime::EXPANSION_PER_HUBBLE_GYR
+}
+
+impl UniverseState {
+    /// Recompute the temperature, the alive count, the ionized fraction and the entropy
+    pub(crate) fn update_thermodynamics(&mut self) {
+        let (dispersion, temperature) =
+            thermodynamics::calculate_entropy_and_temperature(&self.particles);
+        self.temperature = temperature;
+        self.cached_alive_count = self.particles.iter().filter(|p| p.is_alive()).count();
+        self.ionized_fraction = self.compute_ionized_fraction();
+        // Second law: lazy particle swaps must not undo the universe's entropy
+        let entropy = self.entropy_sources(dispersion).entropy();
+        self.total_entropy = self.total_entropy.max(entropy);
+    }
+
+    /// The entropy sources of the current state, given the particles' dispersion entropy
+    /// (`thermodynamics::calculate_entropy`)
+    fn entropy_sources(&self, dispersion_entropy: f64) -> EntropySources {
+        let heat_death_age = self.config.heat_death_age.max(f64::EPSILON);
+        let per_particle = dispersion_entropy / self.cached_alive_count.max(1) as f64;
+        EntropySources {
+            expansion: self.scale_factor.ln().max(0.0) / expected_log_scale_factor(heat_death_age),
+            stellar_deaths: stellar::dead_star_fraction(self.age)
+                / stellar::dead_star_fraction(heat_death_age),
+            dispersion: (per_particle / DISPERSION_REFERENCE).min(1.0),
+            captured: (self.captured_particles as f64 / self.config.particle_count.max(1) as f64)
+                .min(1.0),
+        }
+    }
+
+    /// Progress toward the Heat Death (1 once it begins)
+    pub fn heat_death_progress(&self) -> f64 {
+        (self.total_entropy / (MAX_ENTROPY * HEAT_DEATH_ENTROPY)).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+    use matrix_physics::particle;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_default_universe_reaches_heat_death_on_time() {
+        let config = SimConfig {
+            particle_count: 200,
+            ..SimConfig::default()
+        };
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+        let mut universe = UniverseState::new(config.clone(), particles);
+        // Out of view: gravity only runs as a heartbeat
+        universe.particles_active = false;
+
+        let mut heat_death_at = None;
+        let mut entropy = 0.0;
+        for _ in 0..10_000 {
+            universe.tick(0.01);
+            assert!(universe.total_entropy >= entropy, "entropy fell at {} Gyr", universe.age);
+            entropy = universe.total_entropy;
+            if universe.phase == UniversePhase::HeatDeath && heat_death_at.is_none() {
+                heat_death_at = Some(universe.age);
+            }
+        }
+        assert_eq!(universe.cycle, 1);
+        assert_eq!(universe.phase, UniversePhase::Collapse);
+        assert_eq!(universe.heat_death_progress(), 1.0);
+        let age = heat_death_at.expect("no heat death in 100 Gyr");
+        let expected = config.heat_death_age;
+        assert!((age - expected).abs() < expected * 0.2, "heat death at {age} Gyr");
+    }
+}
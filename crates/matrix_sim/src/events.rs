@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use matrix_core::FeedEntry;
+
+use crate::lazy_universe::LazyUniverse;
+use crate::universe::UniverseState;
+
+/// Entries kept for the feed and future log views; older ones are dropped
+pub const FEED_CAPACITY: usize = 200;
+
+/// Recent simulation events, oldest first. `UniverseState` and `LazyUniverse` queue
+/// theirs in `pending_events`; `collect` moves them here once per frame.
+#[derive(Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct EventFeed {
+    entries: VecDeque<FeedEntry>,
+    /// Entries ever pushed (UIs compare it to spot new ones; not reset by `restore`)
+    pushed: u64,
+}
+
+impl EventFeed {
+    pub fn push(&mut self, entry: FeedEntry) {
+        if self.entries.len() == FEED_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.pushed += 1;
+    }
+
+    pub fn entries(&self) -> &VecDeque<FeedEntry> {
+        &self.entries
+    }
+
+    pub fn pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Replace the history with a loaded snapshot's (not announced as new)
+    pub fn restore(&mut self, entries: Vec<FeedEntry>) {
+        let skip = entries.len().saturating_sub(FEED_CAPACITY);
+        self.entries = entries.into_iter().skip(skip).collect();
+    }
+
+    /// Move events queued by the simulation into the feed
+    pub fn collect(&mut self, universe: &mut UniverseState, lazy: &mut LazyUniverse) {
+        for entry in universe.pending_events.drain(..).chain(lazy.pending_events.drain(..)) {
+            self.push(entry);
+        }
+    }
+}
+
+/// Drain the simulation's queued events into `EventFeed`
+#[cfg(feature = "bevy")]
+pub fn collect_sim_events(
+    mut universe: ResMut<UniverseState>,
+    mut lazy: ResMut<LazyUniverse>,
+    mut feed: ResMut<EventFeed>,
+) {
+    // Only take mutable access when there is something to move
+    if universe.pending_events.is_empty() && lazy.pending_events.is_empty() {
+        return;
+    }
+    feed.collect(&mut universe, &mut lazy);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimEvent;
+
+    fn entry(cycle: u32) -> FeedEntry {
+        FeedEntry {
+            age_gyr: cycle as f64,
+            event: SimEvent::CycleBegan { cycle },
+        }
+    }
+
+    #[test]
+    fn test_feed_keeps_the_newest_entries() {
+        let mut feed = EventFeed::default();
+        for i in 0..(FEED_CAPACITY as u32 + 50) {
+            feed.push(entry(i));
+        }
+        assert_eq!(feed.entries().len(), FEED_CAPACITY);
+        assert_eq!(feed.entries()[0], entry(50));
+        assert_eq!(feed.pushed(), FEED_CAPACITY as u64 + 50);
+
+        // Restoring a save swaps the history without counting it as new
+        feed.restore(vec![entry(1), entry(2)]);
+        assert_eq!(feed.entries().len(), 2);
+        assert_eq!(feed.pushed(), FEED_CAPACITY as u64 + 50);
+    }
+}
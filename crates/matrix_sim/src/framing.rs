@@ -0,0 +1,135 @@
+use crate::lazy_universe::LazyUniverse;
+
+/// Grid cells per region edge when looking for the densest clump of stars
+const FRAMING_GRID: f64 = 8.0;
+/// Camera distance from the framed clump, relative to its radius
+const FRAMING_MARGIN: f64 = 1.5;
+/// Closest the camera is put to a clump (world units, inside Stellar zoom)
+pub const FRAMING_MIN_DISTANCE: f64 = 20.0;
+
+/// Center and radius of the densest clump of `positions`, on a grid of `cell`-sized cells
+fn densest_clump(positions: &[[f64; 3]], cell: f64) -> Option<([f64; 3], f64)> {
+    let key = |p: &[f64; 3]| p.map(|c| (c / cell).floor() as i64);
+    let mut keys: Vec<[i64; 3]> = positions.iter().map(key).collect();
+    keys.sort_unstable();
+    // Longest run of one key; the first on ties, so the result doesn't depend on star order
+    let mut best = (0, None);
+    for run in keys.chunk_by(|a, b| a == b) {
+        if run.len() > best.0 {
+            best = (run.len(), Some(run[0]));
+        }
+    }
+    let densest = best.1?;
+
+    let centroid = |stars: &mut dyn Iterator<Item = &[f64; 3]>| {
+        let (mut sum, mut n) = ([0.0; 3], 0.0);
+        for p in stars {
+            (0..3).for_each(|k| sum[k] += p[k]);
+            n += 1.0;
+        }
+        sum.map(|c| c / n)
+    };
+    let dist = |a: &[f64; 3], b: &[f64; 3]| {
+        (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f64>().sqrt()
+    };
+    // The cell's stars, then everything around their center that spills into other cells
+    let seed = centroid(&mut positions.iter().filter(|p| key(p) == densest));
+    let clump: Vec<&[f64; 3]> = positions.iter().filter(|p| dist(p, &seed) <= cell).collect();
+    let center = centroid(&mut clump.iter().copied());
+    let radius = clump.iter().map(|p| dist(p, &center)).fold(0.0, f64::max);
+    Some((center, radius))
+}
+
+/// Viewpoint framing the star `positions` of a region: back from their densest clump along
+/// `view_dir`, or at the region's center if there are none. Returns (position, look-at).
+pub fn frame_stars(
+    positions: &[[f64; 3]],
+    region_center: [f64; 3],
+    region_size: f64,
+    view_dir: [f64; 3],
+) -> ([f64; 3], [f64; 3]) {
+    let Some((center, radius)) = densest_clump(positions, region_size / FRAMING_GRID) else {
+        return (region_center, region_center);
+    };
+    let len = view_dir.iter().map(|c| c * c).sum::<f64>().sqrt();
+    let dir = if len > 0.0 {
+        view_dir.map(|c| c / len)
+    } else {
+        [0.0, 0.0, -1.0]
+    };
+    let distance = (radius * FRAMING_MARGIN).max(FRAMING_MIN_DISTANCE);
+    (std::array::from_fn(|k| center[k] - dir[k] * distance), center)
+}
+
+impl LazyUniverse {
+    /// Where to put a camera looking along `view_dir` so it sees the densest clump of
+    /// `region_id`'s stars: (position, look-at). The region's center if it has no stars;
+    /// `None` until its stars are loaded (current region, neighbor or cache).
+    pub fn suggest_viewpoint(
+        &self,
+        region_id: u64,
+        view_dir: [f64; 3],
+    ) -> Option<([f64; 3], [f64; 3])> {
+        let region = self.regions.iter().find(|r| r.id == region_id)?;
+        let stars = if self.current_region_id == Some(region_id)
+            && (!self.loaded_stars.is_empty() || self.loading_region_id().is_none())
+        {
+            &self.loaded_stars
+        } else if let Some(neighbor) = self.neighbor_stars.iter().find(|n| n.region_id == region_id) {
+            &neighbor.stars
+        } else {
+            &self.region_cache.iter().find(|c| c.region_id == region_id)?.stars
+        };
+        let positions: Vec<[f64; 3]> = stars.iter().map(|s| s.position).collect();
+        Some(frame_stars(&positions, region.center, region.size, view_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    #[test]
+    fn test_viewpoint_frames_the_densest_clump() {
+        // A tight clump near one corner, a few strays spread over the region
+        let mut stars: Vec<[f64; 3]> = (0..20)
+            .map(|i| [30.0 + (i % 4) as f64, 31.0 + (i / 4) as f64 * 0.5, -20.0])
+            .collect();
+        stars.extend([[-40.0, 0.0, 10.0], [0.0, -45.0, 40.0], [10.0, 10.0, 10.0]]);
+
+        let (pos, look) = frame_stars(&stars, [0.0; 3], 100.0, [0.0, 0.0, -1.0]);
+        assert!((look[0] - 31.5).abs() < 0.1 && (look[1] - 32.0).abs() < 0.1);
+        assert_eq!(look[2], -20.0);
+        // Backed off along the view direction, at least the minimum distance
+        assert_eq!((pos[0], pos[1]), (look[0], look[1]));
+        assert_eq!(pos[2] - look[2], FRAMING_MIN_DISTANCE);
+
+        // The order of the stars doesn't matter, a wider clump backs the camera off more
+        stars.reverse();
+        let (_, reversed) = frame_stars(&stars, [0.0; 3], 100.0, [0.0, 0.0, -1.0]);
+        assert!((0..3).all(|k| (reversed[k] - look[k]).abs() < 1e-9));
+        let wide: Vec<[f64; 3]> = (0..30)
+            .map(|i| [(i % 5) as f64 * 6.0, (i / 5) as f64 * 6.0, 0.0])
+            .collect();
+        let (pos, look) = frame_stars(&wide, [0.0; 3], 400.0, [1.0, 0.0, 0.0]);
+        assert!(look[0] - pos[0] > FRAMING_MIN_DISTANCE);
+        assert_eq!((pos[1], pos[2]), (look[1], look[2]));
+
+        // No stars: the region's center
+        assert_eq!(frame_stars(&[], [5.0, 6.0, 7.0], 100.0, [0.0, 0.0, -1.0]).0, [5.0, 6.0, 7.0]);
+
+        // Only regions with loaded stars get a viewpoint
+        let mut lazy = LazyUniverse::new(SimConfig::default(), 13.0);
+        lazy.async_loading = false;
+        let (home, other) = (lazy.regions[0].id, lazy.regions[1].id);
+        assert!(lazy.suggest_viewpoint(home, [0.0, 0.0, -1.0]).is_none());
+        lazy.enter_region(home, 13.0);
+        let (_, look) = lazy.suggest_viewpoint(home, [0.0, 0.0, -1.0]).unwrap();
+        assert!(lazy.loaded_stars.iter().any(|s| {
+            (0..3).map(|k| (s.position[k] - look[k]).powi(2)).sum::<f64>().sqrt() < 20.0
+        }));
+        assert!(lazy.suggest_viewpoint(other, [0.0, 0.0, -1.0]).is_none());
+        assert!(lazy.suggest_viewpoint(u64::MAX, [0.0, 0.0, -1.0]).is_none());
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy::render::renderer::{RenderAdapter, RenderAdapterInfo, RenderDevice, RenderQueue};
+use matrix_core::Integrator;
+use matrix_core::constants::SOFTENING;
+use matrix_gpu::buffers::ReadbackBuffer;
+use matrix_gpu::context::{self, GpuContext, SimParams};
+use matrix_gpu::dispatch::dispatch_nbody;
+
+use super::pipeline::simulation_tick;
+use super::state::AppState;
+use super::universe::UniverseState;
+
+/// Runs particle gravity through the `matrix_gpu` compute pipeline.
+/// Falls back to the CPU path in `UniverseState::tick` when no compatible adapter exists,
+/// `SimConfig::use_gpu` is off, or the leapfrog integrator is selected (the shader only does Euler).
+pub struct GpuSimPlugin;
+
+impl Plugin for GpuSimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GpuSim>()
+            .add_systems(Startup, detect_gpu)
+            .add_systems(
+                Update,
+                gpu_gravity_system
+                    .after(simulation_tick)
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// GPU-side particle state
+#[derive(Resource, Default)]
+pub struct GpuSim {
+    /// Adapter can run the compute shader
+    pub supported: bool,
+    ctx: Option<GpuContext>,
+    readback: Option<ReadbackBuffer>,
+    /// (particles_generation, len) the GPU buffers were last filled from
+    uploaded: Option<(u32, usize)>,
+    /// (particles_generation, len) of the step currently being read back
+    in_flight: Option<(u32, usize)>,
+}
+
+fn detect_gpu(
+    mut sim: ResMut<GpuSim>,
+    device: Option<Res<RenderDevice>>,
+    adapter: Option<Res<RenderAdapter>>,
+    info: Option<Res<RenderAdapterInfo>>,
+) {
+    sim.supported = match (device, adapter) {
+        (Some(device), Some(adapter)) => context::is_supported(&adapter, &device),
+        _ => false,
+    };
+    if sim.supported {
+        let name = info.map(|i| i.name.clone()).unwrap_or_default();
+        info!("GPU gravity available ({name})");
+    } else {
+        info!("No compute-capable GPU adapter — gravity runs on the CPU");
+    }
+}
+
+/// SimParams for one gravity step (matches the scaling used by the CPU path)
+fn sim_params(universe: &UniverseState, effective_dt: f64) -> SimParams {
+    SimParams {
+        dt: effective_dt as f32 * 0.1,
+        softening: SOFTENING,
+        gravity_scale: universe.config.gravity_scale * 0.5,
+        particle_count: universe.particles.len() as u32,
+        scale_factor: universe.scale_factor as f32,
+        hubble: universe.hubble() as f32,
+        _pad1: 0.0,
+        _pad2: 0.0,
+    }
+}
+
+/// Collect finished readbacks, re-upload changed particles, dispatch queued steps.
+/// Never blocks: a step is only dispatched when the previous readback has landed.
+fn gpu_gravity_system(
+    mut sim: ResMut<GpuSim>,
+    mut universe: ResMut<UniverseState>,
+    device: Option<Res<RenderDevice>>,
+    queue: Option<Res<RenderQueue>>,
+) {
+    let (Some(device), Some(queue)) = (device, queue) else {
+        universe.gpu_active = false;
+        return;
+    };
+
+    let count = universe.particles.len();
+    let active = sim.supported
+        && universe.config.use_gpu
+        && universe.config.integrator == Integrator::Euler
+        && count > 0
+        && context::fits_in_storage(&device, count);
+    if !active {
+        if universe.gpu_active {
+            info!("GPU gravity disabled — using the CPU path");
+        }
+        universe.gpu_active = false;
+        universe.pending_gpu_dt = 0.0;
+        sim.uploaded = None;
+        return;
+    }
+    universe.gpu_active = true;
+    let current = (universe.particles_generation, count);
+
+    // 1. Land the previous step
+    if let Some(dispatched) = sim.in_flight {
+        let Some(readback) = sim.readback.as_ref() else {
+            sim.in_flight = None;
+            return;
+        };
+        match readback.try_read(&device) {
+            None => return,
+            Some(Ok(particles)) => {
+                // Discard if particles were replaced/compacted meanwhile
+                if dispatched == current && particles.len() >= count {
+                    // Take only the lanes the shader integrates, so CPU-side edits made while
+                    // the step was in flight (mass, charge, kind, flags) survive
+                    for (p, g) in universe.particles.iter_mut().zip(&particles) {
+                        p.position[..3].copy_from_slice(&g.position[..3]);
+                        p.velocity[..3].copy_from_slice(&g.velocity[..3]);
+                        p.temperature = g.temperature;
+                    }
+                    universe.particle_steps = universe.particle_steps.wrapping_add(1);
+                    // The shader has no capture pass: swallowed particles go back up dead
+                    if universe.capture_black_hole_infall() > 0 {
+                        sim.uploaded = None;
+                    }
+                }
+            }
+            Some(Err(())) => warn!("GPU readback failed; step dropped"),
+        }
+        sim.in_flight = None;
+    }
+
+    // 2. Re-upload when the CPU side changed the particle set
+    if sim.uploaded != Some(current) {
+        let params = sim_params(&universe, 0.0);
+        match sim.ctx.as_mut() {
+            Some(ctx) if ctx.particle_count as usize == count => {
+                ctx.upload(&queue, &universe.particles);
+            }
+            _ => {
+                sim.ctx = Some(GpuContext::new(&device, &universe.particles, &params));
+                sim.readback = Some(ReadbackBuffer::new(&device, count));
+            }
+        }
+        sim.uploaded = Some(current);
+    }
+
+    // 3. Dispatch the queued step and start reading it back
+    if universe.pending_gpu_dt > 0.0 {
+        let params = sim_params(&universe, universe.pending_gpu_dt);
+        universe.pending_gpu_dt = 0.0;
+        let GpuSim { ctx, readback, in_flight, .. } = &mut *sim;
+        if let (Some(ctx), Some(readback)) = (ctx.as_mut(), readback.as_ref()) {
+            dispatch_nbody(&device, &queue, ctx, &params);
+            readback.start(&device, &queue, ctx.current_read_buffer());
+            *in_flight = Some(current);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use matrix_core::HistorySample;
+
+use crate::lazy_universe::LazyUniverse;
+use crate::universe::UniverseState;
+
+/// Most samples kept; past it the series is thinned out evenly
+pub const HISTORY_CAPACITY: usize = 2000;
+
+/// Simulated time between samples at `age` (Gyr): dense around the Big Bang, when
+/// everything changes fast, and sparser as the universe settles
+pub fn sample_interval(age: f64) -> f64 {
+    (age * 0.01).clamp(1e-4, 0.25)
+}
+
+/// Key metrics of the universe over time, oldest first; persisted in snapshots
+#[derive(Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct UniverseHistory {
+    samples: Vec<HistorySample>,
+    /// Incremented whenever the series changes (graphs compare it to redraw)
+    generation: u32,
+}
+
+impl UniverseHistory {
+    pub fn samples(&self) -> &[HistorySample] {
+        &self.samples
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Whether `sample` should be recorded: the first one, a new cycle, a rewind, or
+    /// `sample_interval` after the last sample
+    pub fn is_due(&self, sample: &HistorySample) -> bool {
+        self.samples.last().is_none_or(|last| {
+            !sample.is_after(last)
+                || sample.cycle != last.cycle
+                || sample.age - last.age >= sample_interval(last.age)
+        })
+    }
+
+    /// Add `sample` if due. After a rewind the samples later than it are dropped first,
+    /// and past `HISTORY_CAPACITY` the series is downsampled. Returns whether it was added.
+    pub fn record(&mut self, sample: HistorySample) -> bool {
+        if !self.is_due(&sample) {
+            return false;
+        }
+        while self.samples.last().is_some_and(|last| !sample.is_after(last)) {
+            self.samples.pop();
+        }
+        self.samples.push(sample);
+        if self.samples.len() > HISTORY_CAPACITY {
+            downsample(&mut self.samples, HISTORY_CAPACITY);
+        }
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Replace the series with a loaded snapshot's
+    pub fn restore(&mut self, mut samples: Vec<HistorySample>) {
+        downsample(&mut samples, HISTORY_CAPACITY);
+        self.samples = samples;
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// Thin `samples` to at most `cap` by dropping every other sample, keeping the first
+/// and the last
+pub fn downsample(samples: &mut Vec<HistorySample>, cap: usize) {
+    let cap = cap.max(2);
+    while samples.len() > cap {
+        let last = samples.len() - 1;
+        let mut i = 0;
+        samples.retain(|_| {
+            let keep = i % 2 == 0 || i == last;
+            i += 1;
+            keep
+        });
+    }
+}
+
+/// The metrics of the universe right now
+pub fn sample_of(universe: &UniverseState, lazy: &LazyUniverse) -> HistorySample {
+    HistorySample {
+        cycle: universe.cycle,
+        age: universe.age,
+        temperature: universe.temperature,
+        total_entropy: universe.total_entropy,
+        scale_factor: universe.scale_factor,
+        particles: universe.alive_count() as u64,
+        stars: lazy.total_stars(),
+        life: lazy.life_planets.len() as u32,
+        civilizations: lazy.civilization_count,
+    }
+}
+
+/// Sample the universe into `UniverseHistory` when due
+#[cfg(feature = "bevy")]
+pub fn record_history(
+    universe: Res<UniverseState>,
+    lazy: Res<LazyUniverse>,
+    mut history: ResMut<UniverseHistory>,
+) {
+    let sample = sample_of(&universe, &lazy);
+    // Only mark the resource changed when a sample lands
+    if history.is_due(&sample) {
+        history.record(sample);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(cycle: u32, age: f64) -> HistorySample {
+        HistorySample {
+            cycle,
+            age,
+            temperature: 1.0 / (age + 1.0),
+            ..HistorySample::default()
+        }
+    }
+
+    #[test]
+    fn test_downsampling_keeps_the_cap_and_endpoints() {
+        let mut history = UniverseHistory::default();
+        let mut age = 0.0;
+        let mut recorded = 0;
+        while age < 400.0 {
+            if history.record(at(0, age)) {
+                recorded += 1;
+            }
+            age += 1e-3;
+        }
+        assert!(recorded > HISTORY_CAPACITY);
+        let samples = history.samples();
+        assert!(samples.len() <= HISTORY_CAPACITY);
+        assert!(samples.len() > HISTORY_CAPACITY / 2);
+        assert_eq!(samples[0], at(0, 0.0));
+        assert!(samples.windows(2).all(|w| w[1].is_after(&w[0])));
+
+        // The newest sample is always the last one, even right after thinning
+        let mut samples: Vec<_> = (0..HISTORY_CAPACITY * 3 + 7).map(|i| at(0, i as f64)).collect();
+        let (first, last) = (samples[0], *samples.last().unwrap());
+        downsample(&mut samples, HISTORY_CAPACITY);
+        assert!(samples.len() <= HISTORY_CAPACITY);
+        assert_eq!((samples[0], *samples.last().unwrap()), (first, last));
+    }
+
+    #[test]
+    fn test_sampling_is_denser_early() {
+        let mut history = UniverseHistory::default();
+        for i in 0..=10_000 {
+            history.record(at(0, i as f64 * 1e-3));
+        }
+        let early = history.samples().iter().filter(|s| s.age < 1.0).count();
+        let late = history.samples().iter().filter(|s| s.age >= 9.0).count();
+        assert!(early > late * 5, "{early} samples in the first Gyr, {late} in the last");
+        // Not due again until the interval passed
+        let last = *history.samples().last().unwrap();
+        assert!(!history.record(at(0, last.age + 1e-3)));
+    }
+
+    #[test]
+    fn test_rewind_and_new_cycle() {
+        let mut history = UniverseHistory::default();
+        for i in 0..100 {
+            history.record(at(0, i as f64 * 0.1));
+        }
+        // Rewinding drops what came after the restored moment
+        assert!(history.record(at(0, 2.0)));
+        assert!(history.samples().iter().all(|s| s.age <= 2.0));
+        assert_eq!(history.samples().last(), Some(&at(0, 2.0)));
+
+        // A new cycle starts over at age 0 but continues the series
+        let before = history.samples().len();
+        assert!(history.record(at(1, 0.0)));
+        assert_eq!(history.samples().len(), before + 1);
+    }
+}
@@ -1,31 +1,50 @@
-use bevy::prelude::*;
+#[cfg(feature = "bevy")]
+use bevy::tasks::{AsyncComputeTaskPool, Task, TaskPool, block_on, poll_once};
 use matrix_core::*;
-use matrix_physics::{cosmology, particle, procgen};
+use matrix_physics::{cosmology, particle, procgen, stellar};
 use rand::SeedableRng;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::detail_tiers::BIOSPHERE_TICK_GYR;
+use crate::neighbors::{NeighborStars, PRIMARY_HYSTERESIS};
+use crate::signals::{DetectedSignal, SignalSource};
+use crate::universe::UniverseState;
 
 /// The LazyUniverse manages the region-based simulation.
 /// Regions far from the camera are purely mathematical.
 /// Regions near the camera get procedurally generated detail.
-#[derive(Resource)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
 pub struct LazyUniverse {
     /// All regions of the universe
-    pub regions: Vec<Region>,
+    pub regions: CowVec<Region>,
     /// Stars currently loaded (from detailed regions)
-    pub loaded_stars: Vec<Star>,
+    pub loaded_stars: CowVec<Star>,
+    /// Galaxies of the loaded region (the stars are sampled from their profiles)
+    pub loaded_galaxies: Vec<Galaxy>,
     /// Current camera position (updated each frame)
     pub camera_pos: [f64; 3],
     /// Which region the camera is currently in
     pub current_region_id: Option<u64>,
     /// Planets with life (discovered so far)
     pub life_planets: Vec<(u64, String)>, // (planet_id, description)
-    /// Total count of civilizations discovered
+    /// Structured records for every entry in `life_planets`
+    pub discoveries: Vec<LifeDiscovery>,
+    /// Living civilizations discovered (extinct ones are no longer counted)
     pub civilization_count: u32,
+    /// Every tracked civilization, alive or extinct (ruins), keyed by region + home planet
+    pub civilizations: Vec<Civilization>,
     /// Configuration
     pub config: SimConfig,
     /// Last age at which region stats were recalculated
     pub last_stats_age: f64,
+    /// Incremented each time `update_region_stats` runs (region inspector uses this)
+    pub stats_generation: u32,
     /// Last age at which stars were regenerated for the loaded region
     pub last_reload_age: f64,
+    /// Age the loaded stars were generated at, before being evolved to `last_reload_age`
+    /// (saves regenerate them from it)
+    pub stars_age: f64,
     /// Frame counter for throttling LOD updates
     pub lod_frame: u32,
     /// Incremented each time loaded_stars changes (cosmos renderer uses this)
@@ -34,25 +53,216 @@ pub struct LazyUniverse {
     pub loaded_particles: Vec<matrix_core::GpuParticle>,
     /// Incremented each time loaded_particles changes (particle renderer uses this)
     pub particles_generation: u32,
+    /// When set, region detail is only loaded through `enter_region` (replay playback)
+    pub playback: bool,
+    /// Supernovae in the loaded region recent enough to still be visible
+    pub recent_supernovae: Vec<stellar::Supernova>,
+    /// Generate region detail on `AsyncComputeTaskPool` instead of inside `update_lod`.
+    /// Off for headless runs, which want the region loaded before the next tick.
+    pub async_loading: bool,
+    /// Region detail being generated in the background (at most one; newer requests replace it)
+    pending_detail: Option<PendingDetail>,
+    /// Neighbors of the current region being generated ahead of time
+    prefetching: Vec<PendingDetail>,
+    /// Recently left / prefetched regions, most recent first (at most `config.region_cache_size`)
+    pub(crate) region_cache: Vec<RegionDetailData>,
+    /// Region loads served from the cache (or an in-flight prefetch)
+    pub cache_hits: u32,
+    /// Region loads that had to generate from scratch
+    pub cache_misses: u32,
+    /// Discoveries / civilization changes / supernovae not yet moved into `EventFeed`
+    pub pending_events: Vec<FeedEntry>,
+    /// Where the player was on each planet they landed on
+    pub surface_memory: SurfaceMemory,
+    /// Surface species scanned with the inspection card
+    pub bestiary: Bestiary,
+    /// Player renames of regions, stars and planets
+    pub names: CustomNames,
+    /// Camera views stored by the player
+    pub bookmarks: Bookmarks,
+    /// Measure main-thread region loads (set from `SimTimings::enabled`)
+    pub time_loads: bool,
+    /// Region and duration of the last measured load, until `take_load_time`
+    last_load_time: Option<(u64, Duration)>,
+    /// Every technological civilization whose stars have been generated, found or not
+    pub signals: Vec<SignalSource>,
+    /// Nearest signal audible at the camera (updated with the LOD)
+    pub detected_signal: Option<DetectedSignal>,
+    /// Aggregate mass points of every region at `RegionDetail::Galactic`, by region id
+    pub galactic_points: Vec<(u64, Vec<MassPoint>)>,
+    /// Incremented each time `galactic_points` changes (cluster renderer uses this)
+    pub galactic_generation: u32,
+    /// Stars of the regions around the loaded one, drawn with it (see `neighbors`)
+    pub neighbor_stars: Vec<NeighborStars>,
+    /// Incremented each time `neighbor_stars` changes (cosmos renderer uses this)
+    pub neighbor_generation: u32,
+    /// Incremented each time a region's `has_life` / `has_civilization` changes
+    /// (region overview and minimap use this)
+    pub region_flags_generation: u32,
+    /// Subdivided regions close enough to the camera that their children stand in for
+    /// them (see `subdivision`)
+    pub expanded: Vec<u64>,
+    /// Incremented each time `expanded` changes (region overview uses this)
+    pub expanded_generation: u32,
+    /// Neighbor stars being generated in the background
+    pub(crate) neighbor_loading: Vec<PendingDetail<NeighborStars>>,
+    /// Landing parameters for the bodies of `preset_star` (`RegionDetail::Planetary`)
+    pub surface_presets: Vec<SurfacePreset>,
+    /// Loaded star the camera is at, whose bodies have surface presets
+    pub preset_star: Option<u64>,
+}
+
+/// Stars, particles and supernovae generated for one region at one age
+#[derive(Clone)]
+pub struct RegionDetailData {
+    pub region_id: u64,
+    pub age_gyr: f64,
+    /// Age the stars were generated at (`age_gyr` moves on as they are evolved)
+    pub stars_age: f64,
+    pub galaxies: Vec<Galaxy>,
+    pub stars: Vec<Star>,
+    pub particles: Vec<GpuParticle>,
+    pub supernovae: Vec<stellar::Supernova>,
+}
+
+/// Background generation of a region's detail (or of part of it), keyed by region id
+pub(crate) struct PendingDetail<T = RegionDetailData> {
+    pub(crate) region_id: u64,
+    pub(crate) task: BackgroundTask<T>,
+}
+
+#[cfg(feature = "bevy")]
+pub(crate) type BackgroundTask<T> = Task<T>;
+/// Without Bevy's task pools the work is done when requested
+/// and handed over on the next poll, like a task that finished at once
+#[cfg(not(feature = "bevy"))]
+pub(crate) type BackgroundTask<T> = Option<T>;
+
+/// Generate the full detail of `region` at `age_gyr`. Pure: only depends on the region
+/// seed and age, so it can run off the main thread.
+pub fn generate_region_detail(region: &Region, age_gyr: f64) -> RegionDetailData {
+    // Stars past their main-sequence lifetime become remnants
+    let (stars, supernovae) = stellar::generate_region_stars(region, age_gyr);
+    let galaxies = procgen::generate_galaxies(region);
+    let particles = particle::generate_region_particles(region, &galaxies, age_gyr);
+    RegionDetailData {
+        region_id: region.id,
+        age_gyr,
+        stars_age: age_gyr,
+        galaxies,
+        stars,
+        particles,
+        supernovae,
+    }
+}
+
+/// Run `work` on the async compute pool
+#[cfg(feature = "bevy")]
+pub(crate) fn spawn_background<T: Send + 'static>(
+    work: impl FnOnce() -> T + Send + 'static,
+) -> BackgroundTask<T> {
+    let pool = AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    pool.spawn(async move { work() })
+}
+
+#[cfg(not(feature = "bevy"))]
+pub(crate) fn spawn_background<T>(work: impl FnOnce() -> T) -> BackgroundTask<T> {
+    Some(work())
 }
 
+/// The result, if the task is done
+#[cfg(feature = "bevy")]
+pub(crate) fn poll_background<T>(task: &mut BackgroundTask<T>) -> Option<T> {
+    block_on(poll_once(task))
+}
+
+#[cfg(not(feature = "bevy"))]
+pub(crate) fn poll_background<T>(task: &mut BackgroundTask<T>) -> Option<T> {
+    task.take()
+}
+
+/// Start generating `region`'s detail in the background
+fn spawn_detail_task(region: &Region, age_gyr: f64) -> PendingDetail {
+    let region_id = region.id;
+    let region = region.clone();
+    PendingDetail {
+        region_id,
+        task: spawn_background(move || generate_region_detail(&region, age_gyr)),
+    }
+}
+
+/// Whether `other` shares a face with `region`: one axis as far apart as their half sides
+/// add up to, the others within the bigger one (equal for regions of the same size)
+fn is_face_neighbor(region: &Region, other: &Region) -> bool {
+    let tolerance = region.size.min(other.size) * 0.1;
+    let touching = (region.size + other.size) / 2.0;
+    let inside = (region.size - other.size).abs() / 2.0;
+    let mut adjacent_axes = 0;
+    for axis in 0..3 {
+        let d = (other.center[axis] - region.center[axis]).abs();
+        if (d - touching).abs() < tolerance {
+            adjacent_axes += 1;
+        } else if d > inside + tolerance {
+            return false;
+        }
+    }
+    adjacent_axes == 1
+}
+
+/// How far back (Gyr) a supernova still counts as recent for the renderer
+const RECENT_SUPERNOVA_GYR: f64 = 0.5;
+/// Loaded detail older than this (Gyr) is brought up to date; cached detail is regenerated
+pub(crate) const RELOAD_THRESHOLD_GYR: f64 = 5.0;
+
 impl LazyUniverse {
     /// Placeholder with no regions (used before world generation completes)
     pub fn empty(config: SimConfig) -> Self {
         Self {
-            regions: Vec::new(),
-            loaded_stars: Vec::new(),
+            regions: CowVec::default(),
+            loaded_stars: CowVec::default(),
+            loaded_galaxies: Vec::new(),
             camera_pos: [0.0; 3],
             current_region_id: None,
             life_planets: Vec::new(),
+            discoveries: Vec::new(),
             civilization_count: 0,
+            civilizations: Vec::new(),
             config,
             last_stats_age: 0.0,
             last_reload_age: 0.0,
+            stars_age: 0.0,
+            stats_generation: 0,
             lod_frame: 0,
             stars_generation: 0,
             loaded_particles: Vec::new(),
             particles_generation: 0,
+            playback: false,
+            recent_supernovae: Vec::new(),
+            async_loading: true,
+            pending_detail: None,
+            prefetching: Vec::new(),
+            region_cache: Vec::new(),
+            cache_hits: 0,
+            cache_misses: 0,
+            pending_events: Vec::new(),
+            surface_memory: SurfaceMemory::default(),
+            bestiary: Bestiary::default(),
+            names: CustomNames::default(),
+            bookmarks: Bookmarks::default(),
+            time_loads: false,
+            last_load_time: None,
+            signals: Vec::new(),
+            detected_signal: None,
+            galactic_points: Vec::new(),
+            galactic_generation: 0,
+            neighbor_stars: Vec::new(),
+            neighbor_generation: 0,
+            region_flags_generation: 0,
+            expanded: Vec::new(),
+            expanded_generation: 0,
+            neighbor_loading: Vec::new(),
+            surface_presets: Vec::new(),
+            preset_star: None,
         }
     }
 
@@ -60,42 +270,75 @@ impl LazyUniverse {
         let regions = procgen::generate_regions(&config, age_gyr);
 
         Self {
-            regions,
-            loaded_stars: Vec::new(),
+            regions: regions.into(),
+            loaded_stars: CowVec::default(),
+            loaded_galaxies: Vec::new(),
             camera_pos: [0.0; 3],
             current_region_id: None,
             life_planets: Vec::new(),
+            discoveries: Vec::new(),
             civilization_count: 0,
+            civilizations: Vec::new(),
             config,
             last_stats_age: age_gyr,
             last_reload_age: age_gyr,
+            stars_age: age_gyr,
+            stats_generation: 0,
             lod_frame: 0,
             stars_generation: 0,
             loaded_particles: Vec::new(),
             particles_generation: 0,
+            playback: false,
+            recent_supernovae: Vec::new(),
+            async_loading: true,
+            pending_detail: None,
+            prefetching: Vec::new(),
+            region_cache: Vec::new(),
+            cache_hits: 0,
+            cache_misses: 0,
+            pending_events: Vec::new(),
+            surface_memory: SurfaceMemory::default(),
+            bestiary: Bestiary::default(),
+            names: CustomNames::default(),
+            bookmarks: Bookmarks::default(),
+            time_loads: false,
+            last_load_time: None,
+            signals: Vec::new(),
+            detected_signal: None,
+            galactic_points: Vec::new(),
+            galactic_generation: 0,
+            neighbor_stars: Vec::new(),
+            neighbor_generation: 0,
+            region_flags_generation: 0,
+            expanded: Vec::new(),
+            expanded_generation: 0,
+            neighbor_loading: Vec::new(),
+            surface_presets: Vec::new(),
+            preset_star: None,
         }
     }
 
     /// Update the LOD system based on camera position
-    pub fn update_lod(&mut self, camera_pos: Vec3, age_gyr: f64) {
+    pub fn update_lod(&mut self, camera_pos: [f64; 3], age_gyr: f64) {
         self.lod_frame = self.lod_frame.wrapping_add(1);
 
-        // Only check distances every 5th frame (512 regions × distance calc is not free)
-        if self.lod_frame % 5 != 0 {
-            return;
-        }
-
-        self.camera_pos = [camera_pos.x as f64, camera_pos.y as f64, camera_pos.z as f64];
-
-        // Update region stats (just numbers for HUD) — max once per 2 Gyr, very cheap
+        // Update region stats (just numbers for HUD) — once per 2 Gyr, keyed on age alone
         let stats_delta = (age_gyr - self.last_stats_age).abs();
         if stats_delta > 2.0 {
             self.update_region_stats(age_gyr);
             self.last_stats_age = age_gyr;
         }
 
+        // Only check distances every 5th frame (a distance per region is not free)
+        if !self.lod_frame.is_multiple_of(5) {
+            return;
+        }
+
+        self.camera_pos = camera_pos;
+        let subdivided = self.subdivided_regions();
+
         let mut closest_id = None;
-        let mut closest_dist = f64::MAX;
+        let mut closest_score = f64::MAX;
 
         for region in &mut self.regions {
             let dx = region.center[0] - self.camera_pos[0];
@@ -103,6 +346,12 @@ impl LazyUniverse {
             let dz = region.center[2] - self.camera_pos[2];
             let dist = (dx * dx + dy * dy + dz * dz).sqrt();
 
+            // Its children carry the detail, and one of them is loaded instead
+            if subdivided.contains(&region.id) {
+                region.detail = RegionDetail::Statistical;
+                continue;
+            }
+
             let desired = if dist < region.size * 0.5 {
                 RegionDetail::Stellar
             } else if dist < region.size * 2.0 {
@@ -111,34 +360,233 @@ impl LazyUniverse {
                 RegionDetail::Statistical
             };
 
-            if desired != region.detail {
+            // The loaded region's Planetary / Biosphere detail is refined below
+            let refined = desired == RegionDetail::Stellar && region.detail > desired;
+            if desired != region.detail && !refined {
                 region.detail = desired.clone();
             }
 
-            if dist < closest_dist {
-                closest_dist = dist;
+            // In region sizes, so a child wins over a bigger neighbor exactly up to the
+            // face between them
+            let score = dist / region.size;
+            if score < closest_score {
+                closest_score = score;
                 closest_id = Some(region.id);
             }
         }
 
+        self.update_expanded(&subdivided);
+        self.update_detail_tiers(age_gyr);
+        self.update_neighbor_stars(age_gyr);
+
         // Only regenerate stars when camera enters a NEW region
         // Age-based reload: max once per 5 Gyr AND only if >60 real frames passed
-        let region_changed = closest_id != self.current_region_id;
+        // During replay playback region loads (and traced signals) come from the log, not the camera
+        if self.playback {
+            return;
+        }
+        self.update_signals(age_gyr);
+
+        // The region being loaded counts as entered: don't re-request it every frame
+        let target_id = self
+            .pending_detail
+            .as_ref()
+            .map(|p| p.region_id)
+            .or(self.current_region_id);
+        // Keep it until the camera is `PRIMARY_HYSTERESIS` past the midpoint toward another,
+        // so flying along a boundary doesn't swap regions back and forth
+        if let Some(target) = target_id.and_then(|id| self.regions.iter().find(|r| r.id == id))
+            && closest_id.is_some_and(|id| id != target.id)
+            && !subdivided.contains(&target.id)
+        {
+            let d2: f64 = (0..3).map(|k| (target.center[k] - self.camera_pos[k]).powi(2)).sum();
+            if (d2.sqrt() / target.size - closest_score) * target.size < 2.0 * PRIMARY_HYSTERESIS {
+                closest_id = Some(target.id);
+            }
+        }
+        let region_changed = closest_id != target_id;
         let age_reload_delta = (age_gyr - self.last_reload_age).abs();
-        let age_reload_needed = age_reload_delta > 5.0 && closest_id.is_some();
+        // Life evolves in finer steps while the camera is at a living system
+        let reload_after = if self.current_detail() == RegionDetail::Biosphere {
+            BIOSPHERE_TICK_GYR
+        } else {
+            RELOAD_THRESHOLD_GYR
+        };
+        let age_reload_needed = age_reload_delta > reload_after && closest_id.is_some();
 
         if region_changed {
-            self.current_region_id = closest_id;
+            match closest_id {
+                // Back in the region that is still loaded: drop the in-flight load
+                Some(id) if Some(id) == self.current_region_id => self.pending_detail = None,
+                Some(id) => self.request_region(id, age_gyr),
+                None => {
+                    self.pending_detail = None;
+                    self.current_region_id = None;
+                }
+            }
+        } else if age_reload_needed && self.pending_detail.is_none()
+            && let Some(id) = self.current_region_id
+        {
+            self.enter_region(id, age_gyr);
+        }
+    }
+
+    /// Load `region_id` in the background (or right away without `async_loading`).
+    /// The old stars stay until `poll_region_detail` installs the new ones.
+    fn request_region(&mut self, region_id: u64, age_gyr: f64) {
+        if !self.async_loading {
+            self.enter_region(region_id, age_gyr);
+            return;
+        }
+        if let Some(detail) = self.take_cached(region_id, age_gyr) {
+            self.pending_detail = None;
+            self.timed_load(region_id, |lazy| lazy.install_region_detail(detail));
+            return;
+        }
+        // A prefetch of this region is already running: wait for it instead
+        if let Some(idx) = self.prefetching.iter().position(|p| p.region_id == region_id) {
+            self.cache_hits += 1;
+            self.pending_detail = Some(self.prefetching.swap_remove(idx));
+            return;
+        }
+        self.cache_misses += 1;
+        let Some(region) = self.regions.iter().find(|r| r.id == region_id) else {
+            return;
+        };
+        info!(
+            "Loading detail for region {} in the background (density: {:.2}, stars: {})",
+            region_id, region.density, region.star_count
+        );
+        // Replacing an older pending load drops (cancels) its task
+        self.pending_detail = Some(spawn_detail_task(region, age_gyr));
+    }
+
+    /// Install the background-generated region once its task has finished,
+    /// and move finished prefetches into the cache. Call every frame.
+    pub fn poll_region_detail(&mut self) {
+        self.poll_neighbor_stars();
+        let mut i = 0;
+        while i < self.prefetching.len() {
+            match poll_background(&mut self.prefetching[i].task) {
+                Some(detail) => {
+                    self.prefetching.swap_remove(i);
+                    self.register_signals(detail.region_id, &detail.stars, detail.age_gyr);
+                    self.cache_store(detail);
+                }
+                None => i += 1,
+            }
+        }
+
+        let Some(pending) = self.pending_detail.as_mut() else {
+            return;
+        };
+        let Some(detail) = poll_background(&mut pending.task) else {
+            return;
+        };
+        self.pending_detail = None;
+        self.timed_load(detail.region_id, |lazy| lazy.install_region_detail(detail));
+    }
+
+    /// Run a region load, noting how long it took when `time_loads` is set
+    fn timed_load(&mut self, region_id: u64, load: impl FnOnce(&mut Self)) {
+        let start = self.time_loads.then(Instant::now);
+        load(self);
+        if let Some(start) = start {
+            self.last_load_time = Some((region_id, start.elapsed()));
         }
+    }
+
+    /// Region and main-thread duration of the last load measured since the previous call
+    pub fn take_load_time(&mut self) -> Option<(u64, Duration)> {
+        self.last_load_time.take()
+    }
+
+    /// Generate the face-adjacent neighbors of the current region in the background
+    /// so crossing a boundary is served from the cache. Throttled like `update_lod`.
+    pub fn prefetch_neighbors(&mut self, age_gyr: f64) {
+        if !self.async_loading || self.playback || self.config.region_cache_size == 0 {
+            return;
+        }
+        if !self.lod_frame.is_multiple_of(5) {
+            return;
+        }
+        let Some(current) = self.current_region_id else {
+            return;
+        };
+        let Some(center) = self.regions.iter().find(|r| r.id == current) else {
+            return;
+        };
+
+        let subdivided = self.subdivided_regions();
+        let wanted: Vec<u64> = self
+            .regions
+            .iter()
+            .filter(|r| is_face_neighbor(center, r) && !subdivided.contains(&r.id))
+            .map(|r| r.id)
+            .filter(|&id| {
+                self.pending_detail.as_ref().is_none_or(|p| p.region_id != id)
+                    && !self.prefetching.iter().any(|p| p.region_id == id)
+                    && !self.region_cache.iter().any(|c| {
+                        c.region_id == id && (age_gyr - c.age_gyr).abs() <= RELOAD_THRESHOLD_GYR
+                    })
+            })
+            .collect();
 
-        if region_changed || age_reload_needed {
-            if let Some(id) = closest_id {
-                self.load_region_detail(id, age_gyr);
-                self.last_reload_age = age_gyr;
+        for id in wanted {
+            if let Some(region) = self.regions.iter().find(|r| r.id == id) {
+                self.prefetching.push(spawn_detail_task(region, age_gyr));
             }
         }
     }
 
+    /// Cached detail for `region_id`, removed from the cache.
+    /// Entries more than `RELOAD_THRESHOLD_GYR` away from `age_gyr` are dropped (miss).
+    fn take_cached(&mut self, region_id: u64, age_gyr: f64) -> Option<RegionDetailData> {
+        let idx = self.region_cache.iter().position(|c| c.region_id == region_id)?;
+        let entry = self.region_cache.remove(idx);
+        if (age_gyr - entry.age_gyr).abs() > RELOAD_THRESHOLD_GYR {
+            return None;
+        }
+        self.cache_hits += 1;
+        Some(entry)
+    }
+
+    /// Put detail at the front of the cache, evicting the least recently used entries
+    fn cache_store(&mut self, detail: RegionDetailData) {
+        if Some(detail.region_id) == self.current_region_id {
+            return;
+        }
+        self.region_cache.retain(|c| c.region_id != detail.region_id);
+        self.region_cache.insert(0, detail);
+        self.region_cache.truncate(self.config.region_cache_size);
+    }
+
+    /// Number of regions currently cached
+    pub fn cached_region_count(&self) -> usize {
+        self.region_cache.len()
+    }
+
+    /// Region whose detail is being generated in the background, if any
+    pub fn loading_region_id(&self) -> Option<u64> {
+        self.pending_detail.as_ref().map(|p| p.region_id)
+    }
+
+    /// Forget background loads and cached regions (e.g. before replacing the state from a save)
+    pub fn reset_region_loading(&mut self) {
+        self.pending_detail = None;
+        self.prefetching.clear();
+        self.region_cache.clear();
+        self.galactic_points.clear();
+        self.galactic_generation = self.galactic_generation.wrapping_add(1);
+        self.neighbor_stars.clear();
+        self.neighbor_loading.clear();
+        self.neighbor_generation = self.neighbor_generation.wrapping_add(1);
+        self.surface_presets.clear();
+        self.preset_star = None;
+        self.expanded.clear();
+        self.expanded_generation = self.expanded_generation.wrapping_add(1);
+    }
+
     /// Recalculate region statistics based on current universe age
     fn update_region_stats(&mut self, age_gyr: f64) {
         let composition = cosmology::chemical_composition(age_gyr);
@@ -146,81 +594,466 @@ impl LazyUniverse {
 
         for region in &mut self.regions {
             let volume = region.size.powi(3);
-            region.star_count = cosmology::estimate_stars(region.density, volume, age_gyr);
+            let baryons = cosmology::baryonic_density(
+                region.density,
+                region.dark_matter,
+                self.config.dark_matter_fraction as f64,
+            );
+            region.star_count = cosmology::estimate_stars(baryons, volume, age_gyr);
             region.temperature = temperature;
             region.composition = composition;
 
+            // Keep the supernova enrichment of the region whose stars are loaded
+            if Some(region.id) == self.current_region_id {
+                region.composition = stellar::enrich_composition(
+                    composition,
+                    stellar::supernova_count(&self.loaded_stars),
+                );
+            }
+
             // Rough planet estimate
             let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(region.seed.wrapping_add(99));
             use rand::Rng;
             region.planet_count =
                 (region.star_count as f64 * rng.gen_range(1.0..8.0)) as u64;
         }
+        self.stats_generation = self.stats_generation.wrapping_add(1);
     }
 
-    /// Generate detailed stars for a region
-    fn load_region_detail(&mut self, region_id: u64, age_gyr: f64) {
-        if let Some(region) = self.regions.iter().find(|r| r.id == region_id) {
+    /// Make `region_id` the current region and bring its detail to `age_gyr`.
+    /// A region that is already loaded is evolved in place (keeping discovered life);
+    /// a recently visited one comes from the cache; anything else is generated from scratch.
+    /// Always synchronous (replay playback relies on this); cancels any background load.
+    pub fn enter_region(&mut self, region_id: u64, age_gyr: f64) {
+        self.timed_load(region_id, |lazy| lazy.load_region(region_id, age_gyr));
+    }
+
+    fn load_region(&mut self, region_id: u64, age_gyr: f64) {
+        self.pending_detail = None;
+        if self.current_region_id == Some(region_id) && !self.loaded_stars.is_empty() {
+            self.evolve_loaded_region(region_id, age_gyr);
+            self.last_reload_age = age_gyr;
+            return;
+        }
+
+        // Playback always regenerates so it only depends on seed and age
+        let cached = if self.playback {
+            None
+        } else {
+            self.take_cached(region_id, age_gyr)
+        };
+        if let Some(detail) = cached {
+            self.install_region_detail(detail);
+        } else if let Some(region) = self.regions.iter().find(|r| r.id == region_id) {
             info!(
                 "Loading detail for region {} (density: {:.2}, stars: {})",
                 region_id, region.density, region.star_count
             );
+            let detail = generate_region_detail(region, age_gyr);
+            self.cache_misses += 1;
+            self.install_region_detail(detail);
+        }
+    }
 
-            let stars = procgen::generate_stellar_detail(region, age_gyr);
-
-            // Check for life on planets (deduplicate by planet_id)
-            for star in &stars {
-                for planet in &star.planets {
-                    if let Some(ref bio) = planet.life {
-                        // Skip if already discovered
-                        if self.life_planets.iter().any(|(id, _)| *id == planet.id) {
-                            continue;
-                        }
-
-                        let desc = format!(
-                            "Planet {} orbiting Star {} — {} (complexity: {:.1}, species: {})",
-                            planet.id,
-                            star.id,
-                            bio.dominant_genome.describe(),
-                            bio.complexity,
-                            bio.species_count,
+    /// Advance the loaded stars from `last_reload_age` to `age_gyr`:
+    /// stellar evolution, biosphere evolution on every life planet,
+    /// then growth / colonization / extinction of the region's civilizations
+    fn evolve_loaded_region(&mut self, region_id: u64, age_gyr: f64) {
+        let dt_gyr = age_gyr - self.last_reload_age;
+        if dt_gyr <= 0.0 {
+            return;
+        }
+        let Some(region) = self.regions.iter_mut().find(|r| r.id == region_id) else {
+            return;
+        };
+
+        let supernovae = stellar::evolve_stars(&mut self.loaded_stars, age_gyr);
+        region.composition = stellar::enrich_composition(
+            cosmology::chemical_composition(age_gyr),
+            stellar::supernova_count(&self.loaded_stars),
+        );
+        if !supernovae.is_empty() {
+            self.pending_events.push(FeedEntry {
+                age_gyr,
+                event: SimEvent::SupernovaNearby {
+                    region_id,
+                    count: supernovae.len() as u32,
+                },
+            });
+        }
+        self.recent_supernovae = supernovae
+            .into_iter()
+            .filter(|sn| age_gyr - sn.age < RECENT_SUPERNOVA_GYR)
+            .collect();
+
+        let mut emerged = Vec::new();
+        let technological =
+            procgen::evolve_biospheres(&mut self.loaded_stars, region.seed, dt_gyr, age_gyr);
+        for (star_id, planet_id) in technological {
+            if !self.life_planets.iter().any(|(id, _)| *id == planet_id)
+                || self.civilizations.iter().any(|c| c.occupies(planet_id))
+            {
+                continue;
+            }
+            self.civilization_count += 1;
+            if let Some(bio) = self
+                .loaded_stars
+                .iter()
+                .find(|s| s.id == star_id)
+                .and_then(|s| s.body(planet_id))
+                .and_then(|p| p.life.as_ref())
+            {
+                info!(
+                    "CIVILIZATION #{} emerged on planet {} after {:.1} Gyr of evolution: {}",
+                    self.civilization_count,
+                    planet_id,
+                    bio.age,
+                    bio.dominant_genome.describe()
+                );
+            }
+            emerged.push(Civilization::new(region_id, star_id, planet_id, age_gyr));
+            self.pending_events.push(FeedEntry {
+                age_gyr,
+                event: SimEvent::CivilizationDetected { region_id, planet_id },
+            });
+        }
+
+        let mut silenced = Vec::new();
+        for civ in self
+            .civilizations
+            .iter_mut()
+            .filter(|c| c.region_id == region_id && c.is_alive())
+        {
+            let Some(star) = self.loaded_stars.iter().find(|s| s.id == civ.star_id) else {
+                continue;
+            };
+            let mut rng =
+                procgen::biosphere_rng(region.seed.wrapping_add(1), civ.planet_id, age_gyr);
+            if procgen::advance_civilization(civ, dt_gyr, star, age_gyr, &mut rng) {
+                self.civilization_count = self.civilization_count.saturating_sub(1);
+                silenced.push(civ.planet_id);
+                let cause = civ.extinction.map_or("unknown", |e| e.cause.name());
+                info!(
+                    "Civilization on planet {} went extinct at {} ({}) — ruins remain",
+                    civ.planet_id,
+                    civ.kardashev_label(),
+                    cause
+                );
+                self.pending_events.push(FeedEntry {
+                    age_gyr,
+                    event: SimEvent::CivilizationExtinct {
+                        region_id,
+                        planet_id: civ.planet_id,
+                        cause: cause.to_string(),
+                    },
+                });
+            }
+        }
+        self.civilizations.extend(emerged);
+        for planet_id in silenced {
+            self.silence_signal(region_id, planet_id, age_gyr);
+        }
+        // Civilizations that emerged here start broadcasting, watched or not
+        let stars = std::mem::take(&mut self.loaded_stars);
+        self.register_signals(region_id, &stars, age_gyr);
+        self.loaded_stars = stars;
+
+        self.refresh_region_flags();
+
+        info!(
+            "Evolved region {} by {:.2} Gyr (age {:.2} Gyr)",
+            region_id, dt_gyr, age_gyr
+        );
+        self.stars_generation = self.stars_generation.wrapping_add(1);
+    }
+
+    /// Make generated detail the loaded region: supernova enrichment, life discovery
+    /// (each planet is recorded once) and new star/particle generations for the renderers
+    fn install_region_detail(&mut self, detail: RegionDetailData) {
+        let RegionDetailData {
+            region_id,
+            age_gyr,
+            stars_age,
+            galaxies,
+            stars,
+            particles,
+            supernovae,
+        } = detail;
+        if !self.regions.iter().any(|r| r.id == region_id) {
+            return;
+        }
+
+        // Keep the region being left (with its evolved stars) for a quick revisit
+        if let Some(old_id) = self.current_region_id.filter(|&id| id != region_id)
+            && !self.loaded_stars.is_empty()
+        {
+            self.current_region_id = None;
+            let outgoing = RegionDetailData {
+                region_id: old_id,
+                age_gyr: self.last_reload_age,
+                stars_age: self.stars_age,
+                galaxies: std::mem::take(&mut self.loaded_galaxies),
+                stars: std::mem::take(&mut self.loaded_stars),
+                particles: std::mem::take(&mut self.loaded_particles),
+                supernovae: std::mem::take(&mut self.recent_supernovae),
+            };
+            self.cache_store(outgoing);
+        }
+
+        self.current_region_id = Some(region_id);
+        self.last_reload_age = age_gyr;
+        self.stars_age = stars_age;
+        // Its stars are loaded in full now
+        self.drop_neighbor(region_id);
+        let Some(region) = self.regions.iter_mut().find(|r| r.id == region_id) else {
+            return;
+        };
+
+        // Supernovae seed the region with metals (counted from the remnants, so cached
+        // detail whose explosions are long past keeps its enrichment)
+        region.composition = stellar::enrich_composition(
+            cosmology::chemical_composition(age_gyr),
+            stellar::supernova_count(&stars),
+        );
+        if !supernovae.is_empty() {
+            info!(
+                "{} supernovae in region {} (metals now {:.4})",
+                supernovae.len(),
+                region_id,
+                region.composition[2]
+            );
+        }
+        self.recent_supernovae = supernovae
+            .into_iter()
+            .filter(|sn| age_gyr - sn.age < RECENT_SUPERNOVA_GYR)
+            .collect();
+        if !self.recent_supernovae.is_empty() {
+            self.pending_events.push(FeedEntry {
+                age_gyr,
+                event: SimEvent::SupernovaNearby {
+                    region_id,
+                    count: self.recent_supernovae.len() as u32,
+                },
+            });
+        }
+
+        // Check for life on planets (planet ids are unique across regions)
+        for star in &stars {
+            for planet in &star.planets {
+                if let Some(ref bio) = planet.life {
+                    // Skip if already discovered
+                    if self.life_planets.iter().any(|(id, _)| *id == planet.id) {
+                        continue;
+                    }
+
+                    let discovery = LifeDiscovery::new(
+                        self.config.seed,
+                        region_id,
+                        star,
+                        planet,
+                        bio,
+                        age_gyr,
+                    );
+                    let desc = discovery.describe(
+                        &self.planet_name_of(region_id, planet.id),
+                        &self.star_name_of(region_id, star.id),
+                    );
+                    info!("LIFE FOUND: {}", desc);
+                    self.pending_events.push(FeedEntry {
+                        age_gyr,
+                        event: SimEvent::LifeDiscovered {
+                            region_id,
+                            planet_id: planet.id,
+                            description: desc.clone(),
+                        },
+                    });
+                    self.life_planets.push((planet.id, desc));
+                    self.discoveries.push(discovery);
+
+                    // A colony, or a civilization already counted, isn't a new one
+                    let known = self
+                        .civilizations
+                        .iter()
+                        .any(|c| c.region_id == region_id && c.occupies(planet.id));
+                    if bio.has_technology && !known {
+                        self.civilization_count += 1;
+                        info!(
+                            "CIVILIZATION #{} detected! {}",
+                            self.civilization_count,
+                            bio.dominant_genome.describe()
                         );
-                        info!("LIFE FOUND: {}", desc);
-                        self.life_planets.push((planet.id, desc));
-
-                        if bio.has_technology {
-                            self.civilization_count += 1;
-                            info!(
-                                "CIVILIZATION #{} detected! {}",
-                                self.civilization_count,
-                                bio.dominant_genome.describe()
-                            );
-                        }
+                        self.pending_events.push(FeedEntry {
+                            age_gyr,
+                            event: SimEvent::CivilizationDetected {
+                                region_id,
+                                planet_id: planet.id,
+                            },
+                        });
+                        self.civilizations.push(Civilization::new(
+                            region_id, star.id, planet.id, age_gyr,
+                        ));
                     }
                 }
             }
+        }
 
-            self.loaded_stars = stars;
-            self.stars_generation = self.stars_generation.wrapping_add(1);
+        self.register_signals(region_id, &stars, age_gyr);
+        self.refresh_region_flags();
+        self.loaded_stars = stars.into();
+        self.loaded_galaxies = galaxies;
+        self.stars_generation = self.stars_generation.wrapping_add(1);
 
-            // Generate particles for this region
-            self.loaded_particles = particle::generate_region_particles(region, age_gyr);
-            self.particles_generation = self.particles_generation.wrapping_add(1);
-            info!(
-                "Loaded {} particles for region {}",
-                self.loaded_particles.len(),
-                region_id
-            );
+        self.loaded_particles = particles;
+        self.particles_generation = self.particles_generation.wrapping_add(1);
+        info!(
+            "Loaded {} particles for region {}",
+            self.loaded_particles.len(),
+            region_id
+        );
+    }
+
+    /// Advance planet orbits of the loaded stars by `dt_gyr` of simulated time.
+    /// `orbital_period` is in years; only the fractional orbit is kept so the
+    /// angle stays precise at huge time scales.
+    pub fn advance_orbits(&mut self, dt_gyr: f64) {
+        if dt_gyr <= 0.0 {
+            return;
+        }
+        let dt_years = dt_gyr * 1e9;
+        let advance = |angle: &mut f64, period: f64| {
+            let orbits = dt_years / period.max(1e-6);
+            *angle = (*angle + std::f64::consts::TAU * orbits.fract())
+                .rem_euclid(std::f64::consts::TAU);
+        };
+        for star in &mut self.loaded_stars {
+            for planet in &mut star.planets {
+                advance(&mut planet.orbital_angle, planet.orbital_period);
+                for moon in &mut planet.moons {
+                    advance(&mut moon.orbital_angle, moon.orbital_period);
+                }
+            }
+            // A comet's angle is its mean anomaly, which also advances uniformly
+            for comet in &mut star.comets {
+                advance(&mut comet.orbital_angle, comet.orbital_period);
+            }
         }
     }
 
-    /// Get total statistics across all regions
+    /// Civilization living on (or having left ruins on) a planet of `region_id`.
+    /// Prefers a living one if several have occupied the planet.
+    pub fn civilization_of(&self, region_id: u64, planet_id: u64) -> Option<&Civilization> {
+        self.civilizations
+            .iter()
+            .filter(|c| c.region_id == region_id && c.occupies(planet_id))
+            .max_by_key(|c| c.is_alive())
+    }
+
+    /// Same as `civilization_of` for the currently loaded region
+    pub fn civilization_at(&self, planet_id: u64) -> Option<&Civilization> {
+        self.civilization_of(self.current_region_id?, planet_id)
+    }
+
+    /// Key of a region (its id) or of a star / planet of it (`names::name_seed`) in `names`
+    pub fn name_key(&self, kind: NameKind, region_id: u64, id: u64) -> u64 {
+        match kind {
+            NameKind::Region => region_id,
+            NameKind::Star | NameKind::Planet => {
+                let region_seed = self.regions.iter().find(|r| r.id == region_id).map_or(region_id, |r| r.seed);
+                names::name_seed(region_seed, names::local_id(kind, id))
+            }
+        }
+    }
+
+    /// Name of a region: the player's, else generated from its seed
+    pub fn region_name(&self, region_id: u64) -> String {
+        if let Some(name) = self.names.get(NameKind::Region, region_id) {
+            return name.to_string();
+        }
+        match self.regions.iter().find(|r| r.id == region_id) {
+            Some(region) => names::name_for_region(region.seed),
+            None => format!("Region #{region_id}"),
+        }
+    }
+
+    /// Name of star `star_id` of `region_id`
+    pub fn star_name_of(&self, region_id: u64, star_id: u64) -> String {
+        let key = self.name_key(NameKind::Star, region_id, star_id);
+        self.names
+            .get(NameKind::Star, key)
+            .map_or_else(|| names::name_for_star(key), str::to_string)
+    }
+
+    /// Name of planet `planet_id` of `region_id`: its star's name and a letter unless renamed
+    pub fn planet_name_of(&self, region_id: u64, planet_id: u64) -> String {
+        let key = self.name_key(NameKind::Planet, region_id, planet_id);
+        if let Some(name) = self.names.get(NameKind::Planet, key) {
+            return name.to_string();
+        }
+        let star_name = self.star_name_of(region_id, planet_id / names::PLANET_ID_STRIDE);
+        names::name_for_planet(&star_name, planet_id % names::PLANET_ID_STRIDE)
+    }
+
+    /// Same as `star_name_of` for the currently loaded region
+    pub fn star_name(&self, star_id: u64) -> String {
+        self.star_name_of(self.current_region_id.unwrap_or_default(), star_id)
+    }
+
+    /// Same as `planet_name_of` for the currently loaded region
+    pub fn planet_name(&self, planet_id: u64) -> String {
+        self.planet_name_of(self.current_region_id.unwrap_or_default(), planet_id)
+    }
+
+    /// Whether a region (or one of its children) hosts a living Type I+ civilization
+    pub fn has_advanced_civilization(&self, region_id: u64) -> bool {
+        let within = |id: u64| {
+            id == region_id || self.regions.iter().any(|r| r.id == id && r.parent == Some(region_id))
+        };
+        self.civilizations
+            .iter()
+            .any(|c| within(c.region_id) && c.is_alive() && c.tech_level >= 1.0)
+    }
+
+    /// Set `has_life` and `has_civilization` on every region from the discoveries and the
+    /// living civilizations, bumping `region_flags_generation` if any changed. A subdivided
+    /// region has what its children have. Runs after each load, evolution step or traced
+    /// signal, and on restoring a save.
+    pub fn refresh_region_flags(&mut self) {
+        let parents: HashMap<u64, u64> =
+            self.regions.iter().filter_map(|r| Some((r.id, r.parent?))).collect();
+        let within =
+            |id: u64, region_id: u64| id == region_id || parents.get(&id) == Some(&region_id);
+        let mut changed = false;
+        for region in &mut self.regions {
+            let has_life = self.discoveries.iter().any(|d| within(d.region_id, region.id));
+            let has_civilization = self
+                .civilizations
+                .iter()
+                .any(|c| within(c.region_id, region.id) && c.is_alive());
+            if (region.has_life, region.has_civilization) != (has_life, has_civilization) {
+                region.has_life = has_life;
+                region.has_civilization = has_civilization;
+                changed = true;
+            }
+        }
+        if changed {
+            self.region_flags_generation = self.region_flags_generation.wrapping_add(1);
+        }
+    }
+
+    /// Get total statistics across all regions. Only the cubes of the grid count: their
+    /// children hold the same stars.
     pub fn total_stars(&self) -> u64 {
-        self.regions.iter().fold(0u64, |acc, r| acc.saturating_add(r.star_count))
+        self.regions
+            .iter()
+            .filter(|r| r.parent.is_none())
+            .fold(0u64, |acc, r| acc.saturating_add(r.star_count))
     }
 
     pub fn total_planets(&self) -> u64 {
-        self.regions.iter().fold(0u64, |acc, r| acc.saturating_add(r.planet_count))
+        self.regions
+            .iter()
+            .filter(|r| r.parent.is_none())
+            .fold(0u64, |acc, r| acc.saturating_add(r.planet_count))
     }
 
     pub fn region_count(&self) -> usize {
@@ -231,19 +1064,55 @@ impl LazyUniverse {
         self.loaded_stars.len()
     }
 
-    /// Find the nearest region with the highest density (to teleport to)
-    pub fn find_densest_region(&self) -> Option<[f64; 3]> {
+    /// Find the region with the highest density (to teleport to)
+    pub fn find_densest_region(&self) -> Option<&Region> {
         self.regions
             .iter()
             .max_by(|a, b| a.density.partial_cmp(&b.density).unwrap())
-            .map(|r| r.center)
+    }
+
+    /// Loaded galaxy closest to `pos` (relative to its radius)
+    pub fn nearest_galaxy(&self, pos: [f64; 3]) -> Option<&Galaxy> {
+        let scaled_dist = |g: &Galaxy| {
+            let d2: f64 = (0..3).map(|k| (pos[k] - g.position[k]).powi(2)).sum();
+            d2.sqrt() / g.radius
+        };
+        self.loaded_galaxies
+            .iter()
+            .min_by(|a, b| scaled_dist(a).total_cmp(&scaled_dist(b)))
+    }
+
+    /// Loaded galaxy `pos` is inside of (within its radius)
+    pub fn galaxy_at(&self, pos: [f64; 3]) -> Option<&Galaxy> {
+        self.nearest_galaxy(pos).filter(|g| {
+            let d2: f64 = (0..3).map(|k| (pos[k] - g.position[k]).powi(2)).sum();
+            d2.sqrt() <= g.radius
+        })
+    }
+
+    /// Galaxy a loaded star belongs to
+    pub fn galaxy_of_star(&self, star_id: u64) -> Option<&Galaxy> {
+        self.loaded_galaxies.iter().find(|g| g.contains_star(star_id))
     }
 
     /// Find a planet with life
     pub fn find_life(&self) -> Option<[f64; 3]> {
+        self.find_planet(|planet| planet.life.is_some())
+    }
+
+    /// Find a planet with a living technological civilization
+    pub fn find_technology(&self) -> Option<[f64; 3]> {
+        self.find_planet(|planet| match self.civilization_at(planet.id) {
+            Some(civ) => civ.is_alive(),
+            None => planet.life.as_ref().is_some_and(|b| b.has_technology),
+        })
+    }
+
+    /// World position of the first loaded planet matching `pred`
+    fn find_planet(&self, pred: impl Fn(&Planet) -> bool) -> Option<[f64; 3]> {
         for star in &self.loaded_stars {
             for planet in &star.planets {
-                if planet.life.is_some() {
+                if pred(planet) {
                     // Compute planet world position from orbit
                     let px = star.position[0]
                         + planet.orbital_radius * planet.orbital_angle.cos();
@@ -257,3 +1126,60 @@ impl LazyUniverse {
         None
     }
 }
+
+/// Regenerate `lazy` for the cycle `universe` just started (no-op unless a cycle restarted)
+pub fn restart_regions_for_cycle(universe: &mut UniverseState, lazy: &mut LazyUniverse) {
+    if !universe.cycle_restarted {
+        return;
+    }
+    universe.cycle_restarted = false;
+
+    let config = SimConfig {
+        seed: universe.cycle_seed(),
+        ..universe.config.clone()
+    };
+    let mut fresh = LazyUniverse::new(config, 0.0);
+    fresh.playback = lazy.playback;
+    fresh.async_loading = lazy.async_loading;
+    // Keep generations moving so renderers drop the old cycle's visuals
+    fresh.stars_generation = lazy.stars_generation.wrapping_add(1);
+    fresh.particles_generation = lazy.particles_generation.wrapping_add(1);
+    fresh.galactic_generation = lazy.galactic_generation.wrapping_add(1);
+    fresh.neighbor_generation = lazy.neighbor_generation.wrapping_add(1);
+    fresh.region_flags_generation = lazy.region_flags_generation.wrapping_add(1);
+    *lazy = fresh;
+    info!("Regions regenerated for cycle {}", universe.cycle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_reload_keeps_planet_ids_and_civilization_count() {
+        let mut lazy = LazyUniverse::new(SimConfig::default(), 13.8);
+        // Regenerate on every visit instead of reusing the region cache
+        lazy.playback = true;
+        let planet_ids = |lazy: &LazyUniverse| -> Vec<u64> {
+            lazy.loaded_stars
+                .iter()
+                .flat_map(|s| s.planets.iter().map(|p| p.id))
+                .collect()
+        };
+        // Region 173 of the default seed has a technological planet at 13.8 Gyr
+        lazy.enter_region(173, 13.8);
+        assert!(lazy.civilization_count > 0);
+        let ids = planet_ids(&lazy);
+
+        lazy.enter_region(172, 13.8);
+        let other = planet_ids(&lazy);
+        assert!(!other.is_empty() && other.iter().all(|id| !ids.contains(id)));
+        let (count, civilizations) = (lazy.civilization_count, lazy.civilizations.len());
+
+        // Coming back finds the same planets and counts nothing twice
+        lazy.enter_region(173, 13.8);
+        assert_eq!(planet_ids(&lazy), ids);
+        assert_eq!(lazy.civilization_count, count);
+        assert_eq!(lazy.civilizations.len(), civilizations);
+    }
+}
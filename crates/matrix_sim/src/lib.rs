@@ -1,4 +1,41 @@
-pub mod lazy_universe;
+//! Universe simulation: particles (`universe`), regions and their detail (`lazy_universe`)
+//! and the event feed. Without the default `bevy` feature the crate builds without Bevy:
+//! the resources are plain structs and the plugin, GPU path, replays and app states are left out.
+
+// Bevy-only modules come before the `info!` below and use the prelude's
+#[cfg(feature = "bevy")]
+pub mod gpu;
+#[cfg(feature = "bevy")]
 pub mod pipeline;
+#[cfg(feature = "bevy")]
+pub mod replay;
+#[cfg(feature = "bevy")]
 pub mod state;
+
+/// `info!` through Bevy's log, or nothing without the `bevy` feature
+macro_rules! info {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "bevy")]
+        bevy::log::info!($($arg)+);
+        #[cfg(not(feature = "bevy"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+pub mod black_holes;
+pub mod detail_tiers;
+pub mod entropy;
+pub mod events;
+pub mod framing;
+pub mod history;
+pub mod lazy_universe;
+pub mod neighbors;
+pub mod recombination;
+pub mod scenario;
+pub mod search;
+pub mod signals;
+pub mod story;
+pub mod subdivision;
+pub mod throttle;
+pub mod timings;
 pub mod universe;
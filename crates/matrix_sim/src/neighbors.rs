@@ -0,0 +1,218 @@
+use matrix_core::{Region, Star};
+use matrix_physics::stellar;
+
+use crate::lazy_universe::{
+    LazyUniverse, PendingDetail, RELOAD_THRESHOLD_GYR, poll_background, spawn_background,
+};
+
+/// Neighbors whose center is further than this many region sizes from the camera
+/// keep only their statistics
+pub const NEIGHBOR_STAR_RANGE: f64 = 1.5;
+/// How far past the midpoint between two regions (Mpc) the camera goes before the
+/// closer one becomes the primary region
+pub const PRIMARY_HYSTERESIS: f64 = 3.0;
+
+/// Stars of a region next to the primary one: drawn around it, but not evolved,
+/// searched for life or given particles
+#[derive(Debug, Clone)]
+pub struct NeighborStars {
+    pub region_id: u64,
+    /// Age the stars were generated at
+    pub age_gyr: f64,
+    pub stars: Vec<Star>,
+}
+
+/// Whether `other` is one of the regions around `region` (26 on a grid of equal regions):
+/// on every axis no further apart than their half sides add up to
+fn is_adjacent(region: &Region, other: &Region) -> bool {
+    let reach = (region.size + other.size) / 2.0 * 1.1;
+    other.id != region.id && (0..3).all(|k| (other.center[k] - region.center[k]).abs() < reach)
+}
+
+impl LazyUniverse {
+    /// The primary region, then every neighbor whose stars are loaded
+    pub fn active_regions(&self) -> Vec<u64> {
+        self.current_region_id
+            .into_iter()
+            .chain(self.neighbor_stars.iter().map(|n| n.region_id))
+            .collect()
+    }
+
+    /// Neighbors of the primary region within `NEIGHBOR_STAR_RANGE` of the camera
+    fn wanted_neighbors(&self) -> Vec<u64> {
+        let Some(primary) = self
+            .current_region_id
+            .and_then(|id| self.regions.iter().find(|r| r.id == id))
+        else {
+            return Vec::new();
+        };
+        let range = primary.size * NEIGHBOR_STAR_RANGE;
+        let subdivided = self.subdivided_regions();
+        self.regions
+            .iter()
+            .filter(|r| is_adjacent(primary, r) && !subdivided.contains(&r.id))
+            .filter(|r| {
+                let d2: f64 = (0..3).map(|k| (r.center[k] - self.camera_pos[k]).powi(2)).sum();
+                d2.sqrt() < range
+            })
+            .map(|r| r.id)
+            .collect()
+    }
+
+    /// Bring `neighbor_stars` in line with the primary region and the camera: drop
+    /// regions that left the set (or went stale), take the ones the cache holds and
+    /// generate the rest in the background. Only with `async_loading` (the starfield
+    /// is for the renderer).
+    pub(crate) fn update_neighbor_stars(&mut self, age_gyr: f64) {
+        if !self.async_loading {
+            return;
+        }
+        let wanted = self.wanted_neighbors();
+        let fresh = |age: f64| (age_gyr - age).abs() <= RELOAD_THRESHOLD_GYR;
+
+        let before = self.neighbor_stars.len();
+        self.neighbor_stars
+            .retain(|n| wanted.contains(&n.region_id) && fresh(n.age_gyr));
+        // Dropping a task cancels it
+        self.neighbor_loading.retain(|p| wanted.contains(&p.region_id));
+        let mut changed = self.neighbor_stars.len() != before;
+
+        for id in wanted {
+            if self.neighbor_stars.iter().any(|n| n.region_id == id)
+                || self.neighbor_loading.iter().any(|p| p.region_id == id)
+            {
+                continue;
+            }
+            if let Some(cached) = self
+                .region_cache
+                .iter()
+                .find(|c| c.region_id == id && fresh(c.age_gyr))
+            {
+                self.neighbor_stars.push(NeighborStars {
+                    region_id: id,
+                    age_gyr: cached.age_gyr,
+                    stars: cached.stars.clone(),
+                });
+                changed = true;
+                continue;
+            }
+            let Some(region) = self.regions.iter().find(|r| r.id == id).cloned() else {
+                continue;
+            };
+            self.neighbor_loading.push(PendingDetail {
+                region_id: id,
+                task: spawn_background(move || NeighborStars {
+                    region_id: region.id,
+                    age_gyr,
+                    stars: stellar::generate_region_stars(&region, age_gyr).0,
+                }),
+            });
+        }
+        if changed {
+            self.neighbor_generation = self.neighbor_generation.wrapping_add(1);
+        }
+    }
+
+    /// Add the neighbor stars whose generation has finished
+    pub(crate) fn poll_neighbor_stars(&mut self) {
+        let mut i = 0;
+        while i < self.neighbor_loading.len() {
+            let Some(neighbor) = poll_background(&mut self.neighbor_loading[i].task) else {
+                i += 1;
+                continue;
+            };
+            self.neighbor_loading.swap_remove(i);
+            if Some(neighbor.region_id) == self.current_region_id {
+                continue;
+            }
+            self.register_signals(neighbor.region_id, &neighbor.stars, neighbor.age_gyr);
+            self.neighbor_stars.push(neighbor);
+            self.neighbor_generation = self.neighbor_generation.wrapping_add(1);
+        }
+    }
+
+    /// Forget the neighbor stars of `region_id` (it became the primary region)
+    pub(crate) fn drop_neighbor(&mut self, region_id: u64) {
+        self.neighbor_loading.retain(|p| p.region_id != region_id);
+        let before = self.neighbor_stars.len();
+        self.neighbor_stars.retain(|n| n.region_id != region_id);
+        if self.neighbor_stars.len() != before {
+            self.neighbor_generation = self.neighbor_generation.wrapping_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+
+    const AGE: f64 = 13.0;
+
+    /// Run frames at `pos` until the primary region and its neighbors are loaded
+    fn settle(lazy: &mut LazyUniverse, pos: [f64; 3]) {
+        for frame in 0..2000 {
+            lazy.poll_region_detail();
+            lazy.update_lod(pos, AGE);
+            // Checked right after a distance check, which starts any missing load
+            let idle = lazy.loading_region_id().is_none() && lazy.neighbor_loading.is_empty();
+            if frame >= 10 && lazy.lod_frame.is_multiple_of(5) && idle {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("regions still loading");
+    }
+
+    #[test]
+    fn test_neighbor_stars_follow_the_primary_region() {
+        let mut lazy = LazyUniverse::new(SimConfig::default(), AGE);
+        // A region away from the edge of the grid has all 26 neighbors
+        let home = lazy
+            .regions
+            .iter()
+            .find(|r| r.center.iter().all(|c| c.abs() < r.size))
+            .cloned()
+            .unwrap();
+        settle(&mut lazy, home.center);
+        assert_eq!(lazy.current_region_id, Some(home.id));
+
+        // From the center, the 6 face and 12 edge neighbors are in range, the corners aren't
+        assert_eq!(lazy.neighbor_stars.len(), 18);
+        assert_eq!(lazy.active_regions().len(), 19);
+        assert_eq!(lazy.active_regions()[0], home.id);
+        let neighbor = &lazy.neighbor_stars[0];
+        let region = lazy.regions.iter().find(|r| r.id == neighbor.region_id).unwrap();
+        assert_eq!(neighbor.stars, stellar::generate_region_stars(region, AGE).0);
+        assert!(!lazy.loaded_stars.is_empty());
+        let generation = lazy.neighbor_generation;
+        let stars_generation = lazy.stars_generation;
+        settle(&mut lazy, home.center);
+        assert_eq!(lazy.neighbor_generation, generation);
+        assert_eq!(lazy.stars_generation, stars_generation);
+
+        // Toward the +x neighbor: it only takes over a few Mpc past the midpoint
+        let east = lazy
+            .regions
+            .iter()
+            .find(|r| r.center[0] == home.center[0] + home.size
+                && r.center[1] == home.center[1]
+                && r.center[2] == home.center[2])
+            .cloned()
+            .unwrap();
+        let at = |past_midpoint: f64| {
+            let mut pos = home.center;
+            pos[0] += home.size / 2.0 + past_midpoint;
+            pos
+        };
+        settle(&mut lazy, at(PRIMARY_HYSTERESIS - 1.0));
+        assert_eq!(lazy.current_region_id, Some(home.id));
+        settle(&mut lazy, at(PRIMARY_HYSTERESIS + 1.0));
+        assert_eq!(lazy.current_region_id, Some(east.id));
+        let active = lazy.active_regions();
+        assert_eq!(active.iter().filter(|&&id| id == east.id).count(), 1);
+        assert!(active.contains(&home.id));
+        settle(&mut lazy, at(1.0 - PRIMARY_HYSTERESIS));
+        assert_eq!(lazy.current_region_id, Some(east.id));
+    }
+}
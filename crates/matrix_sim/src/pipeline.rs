@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
+use super::events::{EventFeed, collect_sim_events};
+use super::history::{UniverseHistory, record_history};
+use super::lazy_universe::{LazyUniverse, restart_regions_for_cycle};
 use super::state::AppState;
+use super::timings::SimTimings;
 use super::universe::UniverseState;
 
 /// Bevy plugin for the simulation pipeline
@@ -8,12 +12,45 @@ pub struct SimulationPlugin;
 
 impl Plugin for SimulationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, simulation_tick.run_if(in_state(AppState::Running)));
+        app.init_resource::<EventFeed>()
+            .init_resource::<SimTimings>()
+            .init_resource::<UniverseHistory>()
+            .add_systems(
+                Update,
+                (
+                    simulation_tick,
+                    orbit_tick.after(simulation_tick),
+                    // Before a restart replaces `LazyUniverse` with its queue
+                    collect_sim_events
+                        .after(simulation_tick)
+                        .before(cycle_restart_system),
+                    cycle_restart_system.after(simulation_tick),
+                    record_history.after(cycle_restart_system),
+                )
+                    .run_if(in_state(AppState::Running)),
+            );
     }
 }
 
 /// Main simulation tick — updates particles and universe state
-fn simulation_tick(mut universe: ResMut<UniverseState>, time: Res<Time>) {
+pub fn simulation_tick(
+    mut universe: ResMut<UniverseState>,
+    mut timings: ResMut<SimTimings>,
+    time: Res<Time>,
+) {
     let dt = time.delta_secs_f64();
-    universe.tick(dt);
+    universe.tick_timed(dt, &mut timings);
+}
+
+/// Move planets of the loaded region along their orbits
+pub fn orbit_tick(universe: Res<UniverseState>, mut lazy: ResMut<LazyUniverse>, time: Res<Time>) {
+    if universe.paused {
+        return;
+    }
+    lazy.advance_orbits(time.delta_secs_f64() * universe.time_scale);
+}
+
+/// After a Big Crunch, regenerate the region universe for the new cycle's seed
+pub fn cycle_restart_system(mut universe: ResMut<UniverseState>, mut lazy: ResMut<LazyUniverse>) {
+    restart_regions_for_cycle(&mut universe, &mut lazy);
 }
@@ -0,0 +1,133 @@
+use matrix_core::constants::{RECOMBINATION_CAPTURE_RADIUS, RECOMBINATION_TEMPERATURE};
+use matrix_core::ParticleKind;
+use matrix_physics::cosmology;
+use matrix_physics::forces::SpatialHash;
+
+use crate::universe::UniverseState;
+
+/// Whether a kind can carry charge, bound or free: the ionized fraction is over these
+fn is_charged_matter(kind: u32) -> bool {
+    matches!(
+        ParticleKind::from_u32(kind),
+        Some(
+            ParticleKind::UpQuark
+                | ParticleKind::DownQuark
+                | ParticleKind::Electron
+                | ParticleKind::Proton
+                | ParticleKind::Hydrogen
+        )
+    )
+}
+
+impl UniverseState {
+    /// Whether the universe is still a plasma: hotter than `RECOMBINATION_TEMPERATURE`, so
+    /// charges move freely and electromagnetism acts between particles
+    pub fn is_ionized(&self) -> bool {
+        cosmology::cosmic_temperature(self.age) > RECOMBINATION_TEMPERATURE
+    }
+
+    /// Share of the charged-capable matter (quarks, electrons, protons, hydrogen) that
+    /// carries a charge, 0 when there is none
+    pub fn compute_ionized_fraction(&self) -> f64 {
+        let (charged, total) = self
+            .particles
+            .iter()
+            .filter(|p| p.is_alive() && is_charged_matter(p.kind))
+            .fold((0usize, 0usize), |(charged, total), p| {
+                (charged + usize::from(p.charge() != 0.0), total + 1)
+            });
+        if total == 0 {
+            0.0
+        } else {
+            charged as f64 / total as f64
+        }
+    }
+
+    /// Recombination: every proton binds the nearest free electron within
+    /// `RECOMBINATION_CAPTURE_RADIUS` into neutral hydrogen, keeping mass and momentum.
+    /// `hash` is the near-field hash of the gravity step. Returns the atoms formed.
+    pub(crate) fn recombine(&mut self, hash: &SpatialHash) -> usize {
+        let protons: Vec<usize> = self
+            .particles
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_alive() && p.kind == ParticleKind::Proton as u32)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut formed = 0;
+        for proton in protons {
+            let pos = self.particles[proton].pos();
+            let distance_sq = |i: usize| {
+                let q = self.particles[i].pos();
+                (0..3).map(|k| (q[k] - pos[k]).powi(2)).sum::<f32>()
+            };
+            let electron = hash
+                .within_radius(pos, RECOMBINATION_CAPTURE_RADIUS, &self.particles)
+                .into_iter()
+                .filter(|&i| {
+                    let p = &self.particles[i];
+                    p.is_alive() && p.kind == ParticleKind::Electron as u32
+                })
+                .min_by(|&a, &b| distance_sq(a).total_cmp(&distance_sq(b)));
+            let Some(electron) = electron else {
+                continue;
+            };
+
+            let e = self.particles[electron];
+            self.particles[electron].flags &= !1;
+            let p = &mut self.particles[proton];
+            let (mass, gained) = (p.mass(), e.mass());
+            let total = mass + gained;
+            for k in 0..3 {
+                p.velocity[k] = (p.velocity[k] * mass + e.velocity[k] * gained) / total;
+            }
+            p.position[3] = total;
+            p.velocity[3] = p.charge() + e.charge();
+            p.kind = ParticleKind::Hydrogen as u32;
+            formed += 1;
+        }
+        formed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{GpuParticle, SimConfig};
+
+    fn particle(x: f32, vel: f32, kind: ParticleKind) -> GpuParticle {
+        GpuParticle::new([x, 0.0, 0.0], [vel, 0.0, 0.0], kind.default_mass(), kind.charge(), kind)
+    }
+
+    #[test]
+    fn test_cooled_electron_and_proton_become_hydrogen() {
+        let particles = vec![
+            particle(0.0, 0.0, ParticleKind::Proton),
+            particle(0.1, 2.0, ParticleKind::Electron),
+            // Too far to bind
+            particle(30.0, 0.0, ParticleKind::Electron),
+        ];
+        let mut universe = UniverseState::new(SimConfig::default(), particles);
+        universe.age = 0.001;
+        assert!(!universe.is_ionized());
+        assert!((universe.compute_ionized_fraction() - 1.0).abs() < 1e-9);
+
+        let hash = SpatialHash::build(&universe.particles, 1.0);
+        assert_eq!(universe.recombine(&hash), 1);
+
+        let atom = universe.particles[0];
+        assert_eq!(atom.kind, ParticleKind::Hydrogen as u32);
+        assert_eq!(atom.charge(), 0.0);
+        assert!((atom.mass() - 1.0005).abs() < 1e-6);
+        // The electron's momentum went into the atom
+        assert!((atom.velocity[0] * atom.mass() - 0.0005 * 2.0).abs() < 1e-6);
+        assert!(!universe.particles[1].is_alive());
+        assert!(universe.particles[2].is_alive());
+        assert!((universe.compute_ionized_fraction() - 0.5).abs() < 1e-9);
+
+        // The Big Bang is a plasma
+        universe.age = 0.0;
+        assert!(universe.is_ionized());
+    }
+}
@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+use matrix_core::SimConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::lazy_universe::LazyUniverse;
+use super::state::AppState;
+use super::universe::UniverseState;
+
+/// A user action that influences which parts of the universe get generated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReplayEvent {
+    /// Camera jumped to a new position (navigation hotkeys, [B] region entry)
+    Teleport { position: [f64; 3] },
+    /// Region detail was loaded (by entering or reloading a region)
+    EnterRegion { region_id: u64 },
+    /// Time scale multiplier changed
+    TimeScale(f64),
+    /// Simulation paused / resumed
+    SetPaused(bool),
+}
+
+/// An event stamped with the universe age it happened at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    /// Universe age in Gyr when the event occurred
+    pub age: f64,
+    pub event: ReplayEvent,
+}
+
+/// Recorded session: the config (seed) plus the ordered event list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayLog {
+    pub config: SimConfig,
+    pub entries: Vec<ReplayEntry>,
+}
+
+impl ReplayLog {
+    pub fn new(config: SimConfig) -> Self {
+        Self {
+            config,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, age: f64, event: ReplayEvent) {
+        self.entries.push(ReplayEntry { age, event });
+    }
+
+    /// Write the log to disk (bincode)
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = bincode::serialize(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, data)
+    }
+
+    /// Read a log written by `save`
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        bincode::deserialize(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Apply one event to the simulation resources.
+/// Returns the camera target for teleports (the renderer moves the camera).
+pub fn apply_event(
+    entry: &ReplayEntry,
+    universe: &mut UniverseState,
+    lazy: &mut LazyUniverse,
+) -> Option<[f64; 3]> {
+    match entry.event {
+        ReplayEvent::Teleport { position } => {
+            lazy.camera_pos = position;
+            return Some(position);
+        }
+        ReplayEvent::EnterRegion { region_id } => lazy.enter_region(region_id, entry.age),
+        ReplayEvent::TimeScale(scale) => universe.time_scale = scale,
+        ReplayEvent::SetPaused(paused) => universe.paused = paused,
+    }
+    None
+}
+
+/// Replay a whole log without rendering or particle integration.
+/// Region detail depends only on the seed, region id and age, so this
+/// reproduces the discoveries (`life_planets`, `civilization_count`) of the recorded session.
+pub fn replay_headless(log: &ReplayLog) -> LazyUniverse {
+    let mut universe = UniverseState::empty(log.config.clone());
+    let mut lazy = LazyUniverse::new(log.config.clone(), 0.0);
+    lazy.playback = true;
+    for entry in &log.entries {
+        universe.age = entry.age;
+        apply_event(entry, &mut universe, &mut lazy);
+    }
+    lazy
+}
+
+/// Active recording (`--record file.replay`)
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub log: ReplayLog,
+    pub path: PathBuf,
+    /// (time_scale, paused, stars_generation) seen last frame
+    last_seen: Option<(f64, bool, u32)>,
+    dirty: bool,
+}
+
+impl ReplayRecorder {
+    pub fn new(config: SimConfig, path: PathBuf) -> Self {
+        Self {
+            log: ReplayLog::new(config),
+            path,
+            last_seen: None,
+            dirty: false,
+        }
+    }
+
+    /// Record an event at the given universe age
+    pub fn record(&mut self, age: f64, event: ReplayEvent) {
+        self.log.push(age, event);
+        self.dirty = true;
+    }
+}
+
+/// Active playback (`--replay file.replay`)
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub log: ReplayLog,
+    /// Index of the next entry to apply
+    pub cursor: usize,
+    /// Camera target from the latest teleport, consumed by the renderer
+    pub pending_camera: Option<[f64; 3]>,
+}
+
+impl ReplayPlayer {
+    pub fn new(log: ReplayLog) -> Self {
+        Self {
+            log,
+            cursor: 0,
+            pending_camera: None,
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.cursor >= self.log.entries.len()
+    }
+}
+
+/// Bevy plugin that drives recording and playback when the resources are present
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                record_system.run_if(resource_exists::<ReplayRecorder>),
+                playback_system.run_if(resource_exists::<ReplayPlayer>),
+            )
+                .run_if(in_state(AppState::Running)),
+        );
+    }
+}
+
+/// Watch time controls and region loads, flush the log when it changes
+fn record_system(
+    mut recorder: ResMut<ReplayRecorder>,
+    universe: Res<UniverseState>,
+    lazy: Res<LazyUniverse>,
+) {
+    let now = (universe.time_scale, universe.paused, lazy.stars_generation);
+
+    if let Some((time_scale, paused, stars_generation)) = recorder.last_seen {
+        if now.0 != time_scale {
+            recorder.record(universe.age, ReplayEvent::TimeScale(now.0));
+        }
+        if now.1 != paused {
+            recorder.record(universe.age, ReplayEvent::SetPaused(now.1));
+        }
+        if now.2 != stars_generation && let Some(region_id) = lazy.current_region_id {
+            recorder.record(lazy.last_reload_age, ReplayEvent::EnterRegion { region_id });
+        }
+    }
+    recorder.last_seen = Some(now);
+
+    if recorder.dirty {
+        recorder.dirty = false;
+        if let Err(e) = recorder.log.save(&recorder.path) {
+            error!("Failed to write replay {}: {e}", recorder.path.display());
+        }
+    }
+}
+
+/// Apply every entry whose age has been reached
+fn playback_system(
+    mut player: ResMut<ReplayPlayer>,
+    mut universe: ResMut<UniverseState>,
+    mut lazy: ResMut<LazyUniverse>,
+) {
+    lazy.playback = true;
+
+    while let Some(entry) = player.log.entries.get(player.cursor).cloned() {
+        if entry.age > universe.age {
+            break;
+        }
+        if let Some(pos) = apply_event(&entry, &mut universe, &mut lazy) {
+            player.pending_camera = Some(pos);
+        }
+        player.cursor += 1;
+
+        if player.finished() {
+            info!(
+                "Replay finished: {} life planets, {} civilizations",
+                lazy.life_planets.len(),
+                lazy.civilization_count
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synthetic_log() -> ReplayLog {
+        let config = SimConfig {
+            seed: 7,
+            ..SimConfig::default()
+        };
+        let mut log = ReplayLog::new(config);
+        log.push(1.5, ReplayEvent::TimeScale(10_000.0));
+        log.push(2.0, ReplayEvent::EnterRegion { region_id: 0 });
+        log.push(6.0, ReplayEvent::Teleport { position: [120.0, 0.0, -40.0] });
+        log.push(6.0, ReplayEvent::EnterRegion { region_id: 137 });
+        log.push(9.0, ReplayEvent::SetPaused(true));
+        log.push(12.5, ReplayEvent::EnterRegion { region_id: 300 });
+        log.push(13.4, ReplayEvent::EnterRegion { region_id: 0 });
+        log
+    }
+
+    #[test]
+    fn log_round_trip() {
+        let log = synthetic_log();
+        let path = std::env::temp_dir().join(format!("matrix_replay_{}.replay", std::process::id()));
+        log.save(&path).unwrap();
+        let loaded = ReplayLog::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.entries, log.entries);
+        assert_eq!(loaded.config.seed, log.config.seed);
+    }
+
+    #[test]
+    fn playback_reproduces_discoveries() {
+        let log = synthetic_log();
+
+        // The "recorded" session: same events applied live
+        let mut universe = UniverseState::empty(log.config.clone());
+        let mut lazy = LazyUniverse::new(log.config.clone(), 0.0);
+        for entry in &log.entries {
+            universe.age = entry.age;
+            apply_event(entry, &mut universe, &mut lazy);
+        }
+
+        let a = replay_headless(&log);
+        let b = replay_headless(&log);
+
+        assert_eq!(a.life_planets, lazy.life_planets);
+        assert_eq!(a.civilization_count, lazy.civilization_count);
+        assert_eq!(a.life_planets, b.life_planets);
+        assert_eq!(a.civilization_count, b.civilization_count);
+        assert_eq!(a.current_region_id, Some(0));
+    }
+}
@@ -0,0 +1,92 @@
+use matrix_core::{SimConfig, StartScenario};
+use matrix_physics::particle;
+use rand::SeedableRng;
+
+use crate::lazy_universe::LazyUniverse;
+use crate::universe::UniverseState;
+
+/// Direction a mature universe is first seen from: ahead and a little from above
+pub const START_VIEW_DIR: [f64; 3] = [0.0, -0.5, -1.0];
+
+/// A freshly generated universe, ready for its first frame
+pub struct StartedWorld {
+    pub universe: UniverseState,
+    pub lazy: LazyUniverse,
+    /// Where the camera starts: (position, look-at). None at the Big Bang, which is
+    /// watched from the renderer's default start position
+    pub viewpoint: Option<([f64; 3], [f64; 3])>,
+}
+
+/// Generate the universe `config.start` describes. The Big Bang gets its particle cloud
+/// and no loaded region; a mature universe skips the cloud and loads its densest region
+/// in place, so its stars and particles are there before the first frame.
+pub fn start_world(config: SimConfig) -> StartedWorld {
+    match config.start {
+        StartScenario::BigBang => {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+            let particles = particle::generate_big_bang(&config, &mut rng);
+            StartedWorld {
+                lazy: LazyUniverse::new(config.clone(), 0.0),
+                universe: UniverseState::new(config, particles),
+                viewpoint: None,
+            }
+        }
+        StartScenario::MatureUniverse { age } => {
+            let mut lazy = LazyUniverse::new(config.clone(), age);
+            let mut universe = UniverseState::empty(config);
+            // Phases catch up one per tick from here
+            universe.age = age;
+            let viewpoint = lazy.find_densest_region().map(|r| r.id).and_then(|id| {
+                lazy.enter_region(id, age);
+                lazy.suggest_viewpoint(id, START_VIEW_DIR)
+            });
+            if let Some((position, _)) = viewpoint {
+                lazy.camera_pos = position;
+            }
+            info!(
+                "Mature universe at {:.1} Gyr: starting in region {:?} with {} stars",
+                age,
+                lazy.current_region_id,
+                lazy.loaded_stars.len()
+            );
+            StartedWorld {
+                universe,
+                lazy,
+                viewpoint,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mature_universe_starts_in_its_densest_region() {
+        let config = SimConfig {
+            particle_count: 1000,
+            start: StartScenario::MatureUniverse { age: 10.0 },
+            ..SimConfig::default()
+        };
+        let world = start_world(config.clone());
+        let densest = world.lazy.find_densest_region().unwrap().id;
+        assert_eq!(world.lazy.current_region_id, Some(densest));
+        assert!(!world.lazy.loaded_stars.is_empty());
+        assert!(!world.lazy.loaded_particles.is_empty());
+        // No Big Bang cloud: the region's particles are swapped in once they're in view
+        assert!(world.universe.particles.is_empty());
+        assert_eq!(world.universe.age, 10.0);
+        let (position, _) = world.viewpoint.unwrap();
+        assert_eq!(world.lazy.camera_pos, position);
+
+        let world = start_world(SimConfig {
+            start: StartScenario::BigBang,
+            ..config
+        });
+        assert_eq!(world.universe.age, 0.0);
+        assert_eq!(world.universe.particles.len(), 1000);
+        assert!(world.lazy.current_region_id.is_none() && world.lazy.loaded_stars.is_empty());
+        assert!(world.viewpoint.is_none());
+    }
+}
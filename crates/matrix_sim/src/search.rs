@@ -0,0 +1,206 @@
+use matrix_core::{Planet, PlanetType, SpectralClass, Star};
+
+use crate::lazy_universe::LazyUniverse;
+
+/// Filter for `LazyUniverse::query_planets`. `None` criteria match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanetQuery {
+    /// Host star class
+    pub spectral_class: Option<SpectralClass>,
+    pub planet_type: Option<PlanetType>,
+    pub has_water: Option<bool>,
+    pub has_life: Option<bool>,
+    pub has_technology: Option<bool>,
+    /// Inclusive surface temperature range (K)
+    pub temp_range: Option<(f64, f64)>,
+}
+
+/// One planet matching a `PlanetQuery`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanetHit {
+    pub star_id: u64,
+    pub planet_id: u64,
+    /// World position (host star + orbit, same convention as `LazyUniverse::find_life`)
+    pub position: [f64; 3],
+}
+
+impl PlanetQuery {
+    /// Parse `key=value` criteria, e.g. `class=G type=Ocean life=yes temp=240..400`.
+    /// Keys: class, type, water, life, tech, temp. Values are case-insensitive.
+    pub fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut query = Self::default();
+        for arg in args {
+            let Some((key, value)) = arg.split_once('=') else {
+                return Err(format!("expected key=value, got '{arg}'"));
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "class" => {
+                    query.spectral_class = Some(
+                        parse_variant(&SpectralClass::ALL, value)
+                            .ok_or_else(|| format!("unknown spectral class '{value}' (O B A F G K M)"))?,
+                    )
+                }
+                "type" => {
+                    query.planet_type = Some(parse_variant(&PlanetType::ALL, value).ok_or_else(|| {
+                        format!("unknown planet type '{value}' (Rocky GasGiant IceGiant Ocean Lava Frozen)")
+                    })?)
+                }
+                "water" => query.has_water = Some(parse_yes_no(value)?),
+                "life" => query.has_life = Some(parse_yes_no(value)?),
+                "tech" => query.has_technology = Some(parse_yes_no(value)?),
+                "temp" => query.temp_range = Some(parse_range(value)?),
+                _ => return Err(format!("unknown criterion '{key}' (class type water life tech temp)")),
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, star: &Star, planet: &Planet) -> bool {
+        let life = planet.life.as_ref();
+        self.spectral_class.is_none_or(|c| c == star.spectral_class)
+            && self.planet_type.is_none_or(|t| t == planet.planet_type)
+            && self.has_water.is_none_or(|w| w == planet.has_water)
+            && self.has_life.is_none_or(|l| l == life.is_some())
+            && self
+                .has_technology
+                .is_none_or(|t| t == life.is_some_and(|b| b.has_technology))
+            && self
+                .temp_range
+                .is_none_or(|(lo, hi)| (lo..=hi).contains(&planet.surface_temp))
+    }
+}
+
+/// Variant whose `Debug` name equals `value`, ignoring case
+fn parse_variant<T: Copy + std::fmt::Debug>(all: &[T], value: &str) -> Option<T> {
+    all.iter()
+        .copied()
+        .find(|v| format!("{v:?}").eq_ignore_ascii_case(value))
+}
+
+fn parse_yes_no(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Ok(true),
+        "no" | "n" | "false" | "0" => Ok(false),
+        _ => Err(format!("expected yes/no, got '{value}'")),
+    }
+}
+
+/// `lo..hi`, `lo..` or `..hi` (K)
+fn parse_range(value: &str) -> Result<(f64, f64), String> {
+    let err = || format!("expected a temperature range like 240..400, got '{value}'");
+    let (lo, hi) = value.split_once("..").ok_or_else(err)?;
+    let bound = |s: &str, open: f64| -> Result<f64, String> {
+        if s.is_empty() {
+            Ok(open)
+        } else {
+            s.parse::<f64>().ok().filter(|v| !v.is_nan()).ok_or_else(err)
+        }
+    };
+    let range = (bound(lo, f64::NEG_INFINITY)?, bound(hi, f64::INFINITY)?);
+    if range.0 > range.1 {
+        return Err(err());
+    }
+    Ok(range)
+}
+
+impl LazyUniverse {
+    /// Loaded planets matching `query`, nearest to the camera first
+    pub fn query_planets(&self, query: &PlanetQuery) -> Vec<PlanetHit> {
+        let dist_sq = |p: &[f64; 3]| -> f64 { (0..3).map(|k| (p[k] - self.camera_pos[k]).powi(2)).sum() };
+        let mut hits: Vec<PlanetHit> = self
+            .loaded_stars
+            .iter()
+            .flat_map(|star| star.planets.iter().map(move |planet| (star, planet)))
+            .filter(|(star, planet)| query.matches(star, planet))
+            .map(|(star, planet)| PlanetHit {
+                star_id: star.id,
+                planet_id: planet.id,
+                position: [
+                    star.position[0] + planet.orbital_radius * planet.orbital_angle.cos(),
+                    star.position[1],
+                    star.position[2] + planet.orbital_radius * planet.orbital_angle.sin(),
+                ],
+            })
+            .collect();
+        hits.sort_by(|a, b| dist_sq(&a.position).total_cmp(&dist_sq(&b.position)));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+    use matrix_physics::procgen;
+
+    fn loaded_universe() -> LazyUniverse {
+        let mut lazy = LazyUniverse::new(SimConfig::default(), 10.0);
+        let region = lazy.regions[0].clone();
+        lazy.loaded_stars = procgen::generate_stellar_detail(&region, 10.0).into();
+        lazy
+    }
+
+    #[test]
+    fn test_parse_criteria() {
+        let query = PlanetQuery::parse(&["class=g", "type=OCEAN", "life=yes", "temp=240..400"]).unwrap();
+        assert_eq!(
+            query,
+            PlanetQuery {
+                spectral_class: Some(SpectralClass::G),
+                planet_type: Some(PlanetType::Ocean),
+                has_life: Some(true),
+                temp_range: Some((240.0, 400.0)),
+                ..PlanetQuery::default()
+            }
+        );
+        assert_eq!(
+            PlanetQuery::parse(&["temp=..200"]).unwrap().temp_range,
+            Some((f64::NEG_INFINITY, 200.0))
+        );
+        assert!(PlanetQuery::parse(&["class=X"]).is_err());
+        assert!(PlanetQuery::parse(&["life"]).is_err());
+        assert!(PlanetQuery::parse(&["temp=400..240"]).is_err());
+        assert!(PlanetQuery::parse(&["colour=red"]).is_err());
+    }
+
+    #[test]
+    fn test_empty_query_matches_every_loaded_planet() {
+        let lazy = loaded_universe();
+        let total: usize = lazy.loaded_stars.iter().map(|s| s.planets.len()).sum();
+        assert!(total > 0);
+        assert_eq!(lazy.query_planets(&PlanetQuery::default()).len(), total);
+    }
+
+    #[test]
+    fn test_hits_match_criteria_and_are_sorted_by_distance() {
+        let mut lazy = loaded_universe();
+        lazy.camera_pos = lazy.loaded_stars[0].position;
+        let query = PlanetQuery {
+            planet_type: Some(PlanetType::Rocky),
+            temp_range: Some((100.0, 600.0)),
+            ..PlanetQuery::default()
+        };
+        let hits = lazy.query_planets(&query);
+        assert!(!hits.is_empty());
+        for hit in &hits {
+            let star = lazy.loaded_stars.iter().find(|s| s.id == hit.star_id).unwrap();
+            let planet = star.planets.iter().find(|p| p.id == hit.planet_id).unwrap();
+            assert!(matches!(planet.planet_type, PlanetType::Rocky));
+            assert!((100.0..=600.0).contains(&planet.surface_temp));
+        }
+        let dist = |h: &PlanetHit| -> f64 {
+            (0..3).map(|k| (h.position[k] - lazy.camera_pos[k]).powi(2)).sum()
+        };
+        assert!(hits.windows(2).all(|w| dist(&w[0]) <= dist(&w[1])));
+
+        // Excluding a class removes exactly its planets
+        let g_only = lazy.query_planets(&PlanetQuery {
+            spectral_class: Some(SpectralClass::G),
+            ..PlanetQuery::default()
+        });
+        assert!(g_only.iter().all(|h| {
+            let star = lazy.loaded_stars.iter().find(|s| s.id == h.star_id).unwrap();
+            star.spectral_class == SpectralClass::G
+        }));
+    }
+}
@@ -0,0 +1,298 @@
+use matrix_core::{C, Civilization, FeedEntry, LifeDiscovery, SimEvent, Star};
+
+use crate::lazy_universe::LazyUniverse;
+
+/// Signal propagation speed (Mpc/Gyr)
+pub const SIGNAL_SPEED: f64 = C as f64;
+/// Following a signal this close to its source (Mpc, one region across) pins down the
+/// civilization without loading its region
+pub const SIGNAL_REVEAL_DISTANCE: f64 = 100.0;
+
+/// Radio emission of a technological civilization, found or not. Registered when its
+/// region's stars are generated (loads, prefetches) or when it emerges while watched.
+#[derive(Debug, Clone)]
+pub struct SignalSource {
+    pub region_id: u64,
+    pub star_id: u64,
+    pub planet_id: u64,
+    /// Home star (universe coordinates)
+    pub position: [f64; 3],
+    /// Universe age (Gyr) it started broadcasting: when it was first seen with technology
+    pub birth_age: f64,
+    /// Set when the civilization dies out; its last broadcasts keep expanding as a shell
+    pub death_age: Option<f64>,
+    /// Its home world's life, recorded if the signal is traced before the region loads
+    pub discovery: LifeDiscovery,
+}
+
+/// The signal heard at the camera (nearest audible source), refreshed by `update_lod`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedSignal {
+    pub region_id: u64,
+    pub planet_id: u64,
+    /// Unit vector from the camera to the source
+    pub direction: [f64; 3],
+    /// Distance to the source (Mpc)
+    pub distance: f64,
+    /// The source is extinct: only its last broadcasts are passing by
+    pub fading: bool,
+}
+
+/// Whether a receiver `distance` from a source hears it at `age_gyr`. The first broadcast
+/// has reached c·(age − birth); once extinct, the last one has only reached c·(age − death),
+/// so the signal is a shell between the two.
+pub fn in_signal_shell(distance: f64, age_gyr: f64, birth_age: f64, death_age: Option<f64>) -> bool {
+    if age_gyr < birth_age {
+        return false;
+    }
+    let outer = SIGNAL_SPEED * (age_gyr - birth_age);
+    let inner = death_age.map_or(0.0, |death| SIGNAL_SPEED * (age_gyr - death).max(0.0));
+    (inner..=outer).contains(&distance)
+}
+
+impl LazyUniverse {
+    /// Add every technological planet of `stars` (region `region_id` at `age_gyr`)
+    /// to the signal registry, once per planet
+    pub(crate) fn register_signals(&mut self, region_id: u64, stars: &[Star], age_gyr: f64) {
+        for star in stars {
+            for planet in &star.planets {
+                let Some(bio) = planet.life.as_ref().filter(|b| b.has_technology) else {
+                    continue;
+                };
+                if self
+                    .signals
+                    .iter()
+                    .any(|s| s.region_id == region_id && s.planet_id == planet.id)
+                {
+                    continue;
+                }
+                // A civilization tracked earlier started broadcasting when it was found
+                let birth_age = self
+                    .civilization_of(region_id, planet.id)
+                    .map_or(age_gyr, |c| c.founded_age);
+                self.signals.push(SignalSource {
+                    region_id,
+                    star_id: star.id,
+                    planet_id: planet.id,
+                    position: star.position,
+                    birth_age,
+                    death_age: None,
+                    discovery: LifeDiscovery::new(
+                        self.config.seed,
+                        region_id,
+                        star,
+                        planet,
+                        bio,
+                        age_gyr,
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Stop the broadcasts of the civilization on `planet_id` at `age_gyr`
+    pub(crate) fn silence_signal(&mut self, region_id: u64, planet_id: u64, age_gyr: f64) {
+        if let Some(source) = self
+            .signals
+            .iter_mut()
+            .find(|s| s.region_id == region_id && s.planet_id == planet_id)
+        {
+            source.death_age.get_or_insert(age_gyr);
+        }
+    }
+
+    /// Rebuild the registry from the loaded region (after replacing the state from a save;
+    /// other regions register again as they load)
+    pub fn rebuild_signals(&mut self, age_gyr: f64) {
+        self.signals.clear();
+        self.detected_signal = None;
+        let Some(region_id) = self.current_region_id else {
+            return;
+        };
+        let stars = std::mem::take(&mut self.loaded_stars);
+        self.register_signals(region_id, &stars, age_gyr);
+        self.loaded_stars = stars;
+        for civ in &self.civilizations {
+            if let Some(extinction) = civ.extinction
+                && let Some(source) = self
+                    .signals
+                    .iter_mut()
+                    .find(|s| s.region_id == civ.region_id && s.planet_id == civ.planet_id)
+            {
+                source.death_age = Some(extinction.age);
+            }
+        }
+    }
+
+    /// Pick the nearest signal audible at the camera and reveal sources the camera has
+    /// followed to within `SIGNAL_REVEAL_DISTANCE`
+    pub(crate) fn update_signals(&mut self, age_gyr: f64) {
+        let camera = self.camera_pos;
+        let mut nearest: Option<DetectedSignal> = None;
+        let mut traced = Vec::new();
+        for (i, source) in self.signals.iter().enumerate() {
+            let offset: [f64; 3] = std::array::from_fn(|k| source.position[k] - camera[k]);
+            let distance = offset.iter().map(|d| d * d).sum::<f64>().sqrt();
+            if !in_signal_shell(distance, age_gyr, source.birth_age, source.death_age) {
+                continue;
+            }
+            if nearest.is_none_or(|n| distance < n.distance) {
+                nearest = Some(DetectedSignal {
+                    region_id: source.region_id,
+                    planet_id: source.planet_id,
+                    direction: offset.map(|d| d / distance.max(f64::EPSILON)),
+                    distance,
+                    fading: source.death_age.is_some(),
+                });
+            }
+            let known = self.life_planets.iter().any(|(id, _)| *id == source.planet_id);
+            if distance < SIGNAL_REVEAL_DISTANCE && !known {
+                traced.push(i);
+            }
+        }
+
+        let heard_new = nearest.is_some_and(|n| {
+            self.detected_signal
+                .is_none_or(|d| (d.region_id, d.planet_id) != (n.region_id, n.planet_id))
+        });
+        if let Some(signal) = nearest.filter(|_| heard_new) {
+            self.pending_events.push(FeedEntry {
+                age_gyr,
+                event: SimEvent::SignalDetected {
+                    region_id: signal.region_id,
+                    planet_id: signal.planet_id,
+                },
+            });
+        }
+        self.detected_signal = nearest;
+
+        for i in traced {
+            self.reveal_signal_source(i, age_gyr);
+        }
+    }
+
+    /// Record a traced source's life and civilization as if its region had been loaded
+    /// (the load later finds them already known)
+    fn reveal_signal_source(&mut self, index: usize, age_gyr: f64) {
+        let source = self.signals[index].clone();
+        let desc = source.discovery.describe(
+            &self.planet_name_of(source.region_id, source.planet_id),
+            &self.star_name_of(source.region_id, source.star_id),
+        );
+        info!("SIGNAL TRACED: {}", desc);
+        self.pending_events.push(FeedEntry {
+            age_gyr,
+            event: SimEvent::LifeDiscovered {
+                region_id: source.region_id,
+                planet_id: source.planet_id,
+                description: desc.clone(),
+            },
+        });
+        self.life_planets.push((source.planet_id, desc));
+        self.discoveries.push(source.discovery);
+
+        let known = self
+            .civilizations
+            .iter()
+            .any(|c| c.region_id == source.region_id && c.occupies(source.planet_id));
+        if !known {
+            self.civilization_count += 1;
+            self.civilizations.push(Civilization::new(
+                source.region_id,
+                source.star_id,
+                source.planet_id,
+                source.birth_age,
+            ));
+            self.pending_events.push(FeedEntry {
+                age_gyr,
+                event: SimEvent::CivilizationDetected {
+                    region_id: source.region_id,
+                    planet_id: source.planet_id,
+                },
+            });
+        }
+        self.refresh_region_flags();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SimConfig;
+    use matrix_physics::procgen;
+
+    #[test]
+    fn test_signal_shell_membership() {
+        let c = SIGNAL_SPEED;
+        // Living: a sphere growing at light speed from the birth age
+        assert!(!in_signal_shell(0.0, 9.9, 10.0, None));
+        assert!(in_signal_shell(0.0, 10.0, 10.0, None));
+        assert!(in_signal_shell(c * 0.5, 10.5, 10.0, None));
+        assert!(in_signal_shell(c * 0.49, 10.5, 10.0, None));
+        assert!(!in_signal_shell(c * 0.51, 10.5, 10.0, None));
+
+        // Extinct at 10.2: heard between c·(t − 10.2) and c·(t − 10.0)
+        let death = Some(10.2);
+        assert!(in_signal_shell(0.0, 10.1, 10.0, death));
+        assert!(in_signal_shell(c * 0.1, 10.2, 10.0, death));
+        assert!(!in_signal_shell(c * 0.2, 10.5, 10.0, death));
+        assert!(in_signal_shell(c * 0.31, 10.5, 10.0, death));
+        assert!(in_signal_shell(c * 0.4, 10.5, 10.0, death));
+        assert!(in_signal_shell(c * 0.49, 10.5, 10.0, death));
+        assert!(!in_signal_shell(c * 0.6, 10.5, 10.0, death));
+        // The shell moves on: the source itself has gone quiet
+        assert!(!in_signal_shell(0.0, 11.0, 10.0, death));
+    }
+
+    #[test]
+    fn test_traced_signal_reveals_civilization() {
+        let age = 13.8;
+        let mut lazy = LazyUniverse::new(SimConfig::default(), age);
+        let (region, stars) = lazy
+            .regions
+            .iter()
+            .find_map(|region| {
+                let stars = procgen::generate_stellar_detail(region, age);
+                let has_tech = stars
+                    .iter()
+                    .flat_map(|s| &s.planets)
+                    .any(|p| p.life.as_ref().is_some_and(|b| b.has_technology));
+                has_tech.then(|| (region.clone(), stars))
+            })
+            .expect("a region with a technological planet");
+        lazy.register_signals(region.id, &stars, age);
+        let registered = lazy.signals.len();
+        lazy.register_signals(region.id, &stars, age);
+        assert_eq!(lazy.signals.len(), registered);
+        lazy.signals.truncate(1);
+        let source = lazy.signals[0].clone();
+
+        // Heard from afar once the first broadcast has arrived, but not yet traced
+        lazy.camera_pos = [source.position[0] + 300.0, source.position[1], source.position[2]];
+        lazy.update_signals(age);
+        assert!(lazy.detected_signal.is_none());
+        lazy.update_signals(age + 0.2);
+        let heard = lazy.detected_signal.expect("signal within the light sphere");
+        assert!((heard.direction[0] + 1.0).abs() < 1e-9);
+        assert!(lazy.civilizations.is_empty());
+
+        // Following it in reveals the civilization before the region is loaded
+        let flags_generation = lazy.region_flags_generation;
+        lazy.camera_pos = source.position;
+        lazy.update_signals(age + 0.2);
+        assert!(lazy.current_region_id.is_none());
+        assert!(lazy.civilization_of(region.id, source.planet_id).is_some());
+        assert!(lazy.life_planets.iter().any(|(id, _)| *id == source.planet_id));
+        let flagged = lazy.regions.iter().find(|r| r.id == region.id).unwrap();
+        assert!(flagged.has_life && flagged.has_civilization);
+        assert_ne!(lazy.region_flags_generation, flags_generation);
+
+        // Once extinct, the signal leaves the source behind as a shell
+        lazy.silence_signal(region.id, source.planet_id, age + 0.2);
+        lazy.update_signals(age + 0.3);
+        assert!(lazy.detected_signal.is_none());
+        lazy.camera_pos[0] += SIGNAL_SPEED * 0.2;
+        lazy.update_signals(age + 0.3);
+        assert!(lazy.detected_signal.is_some_and(|d| d.fading));
+    }
+}
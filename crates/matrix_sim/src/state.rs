@@ -6,4 +6,6 @@ pub enum AppState {
     Menu,
     Loading,
     Running,
+    /// Pause menu over a running universe: nothing gated on `Running` ticks
+    Paused,
 }
@@ -0,0 +1,321 @@
+//! Story mode: stop fast-forwarding when something worth watching happens. `StoryDirector`
+//! reads the new `EventFeed` entries; the first one of an enabled kind (`StoryTriggers`)
+//! drops the time scale to 1× and is held as a `StoryBeat` until `resume` puts the previous
+//! scale back. For `StorySettings::cooldown_secs` after that, events only go to the feed,
+//! so a burst of discoveries interrupts once.
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use matrix_core::{FeedEntry, SimEvent, names};
+use serde::{Deserialize, Serialize};
+
+use crate::events::EventFeed;
+use crate::lazy_universe::LazyUniverse;
+use crate::universe::UniverseState;
+
+/// Which events stop the clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoryTriggers {
+    /// The first life of a universe (and of each new cycle)
+    pub first_life: bool,
+    /// Any civilization detected
+    pub civilization: bool,
+    pub extinction: bool,
+    pub phase_transition: bool,
+}
+
+impl Default for StoryTriggers {
+    fn default() -> Self {
+        Self {
+            first_life: true,
+            civilization: true,
+            extinction: true,
+            phase_transition: true,
+        }
+    }
+}
+
+impl StoryTriggers {
+    /// Whether `event` stops the clock; `first_life` tells a universe's first find apart
+    pub fn matches(&self, event: &SimEvent, first_life: bool) -> bool {
+        match event {
+            SimEvent::LifeDiscovered { .. } => self.first_life && first_life,
+            SimEvent::CivilizationDetected { .. } => self.civilization,
+            SimEvent::CivilizationExtinct { .. } => self.extinction,
+            SimEvent::PhaseTransition { .. } => self.phase_transition,
+            _ => false,
+        }
+    }
+}
+
+/// Story mode preferences, kept with the player's settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorySettings {
+    pub enabled: bool,
+    pub triggers: StoryTriggers,
+    /// Glide the camera slowly toward where the event happened
+    pub follow_camera: bool,
+    /// Real seconds after a resume during which no event stops the clock
+    pub cooldown_secs: f64,
+}
+
+impl Default for StorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            triggers: StoryTriggers::default(),
+            follow_camera: true,
+            cooldown_secs: 30.0,
+        }
+    }
+}
+
+/// An event the clock stopped for
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryBeat {
+    pub entry: FeedEntry,
+    /// Time scale before the stop, put back by `StoryDirector::resume`
+    pub resume_scale: f64,
+    /// Time scale the stop set; the player changing it ends the beat where it is
+    held_scale: f64,
+}
+
+impl StoryBeat {
+    /// Where the event happened: its planet's star if that is in the loaded region, else
+    /// its region's center. None for events without a place (phase transitions).
+    pub fn location(&self, lazy: &LazyUniverse) -> Option<[f64; 3]> {
+        let (region_id, planet_id) = match self.entry.event {
+            SimEvent::LifeDiscovered {
+                region_id,
+                planet_id,
+                ..
+            }
+            | SimEvent::CivilizationDetected {
+                region_id,
+                planet_id,
+            }
+            | SimEvent::CivilizationExtinct {
+                region_id,
+                planet_id,
+                ..
+            } => (region_id, planet_id),
+            _ => return None,
+        };
+        let star_id = planet_id / names::PLANET_ID_STRIDE;
+        let star = lazy
+            .loaded_stars
+            .iter()
+            .find(|s| s.id == star_id)
+            .filter(|_| lazy.current_region_id == Some(region_id));
+        match star {
+            Some(star) => Some(star.position),
+            None => lazy.regions.iter().find(|r| r.id == region_id).map(|r| r.center),
+        }
+    }
+}
+
+/// Watches the feed for story beats and holds the one on screen
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct StoryDirector {
+    /// `EventFeed::pushed` up to which entries were read
+    seen: u64,
+    /// Life discoveries at the last read (0 before a universe's first)
+    known_life: usize,
+    beat: Option<StoryBeat>,
+    /// Real time (s) before which no event stops the clock
+    quiet_until: f64,
+}
+
+impl StoryDirector {
+    /// The beat on screen, if any
+    pub fn beat(&self) -> Option<&StoryBeat> {
+        self.beat.as_ref()
+    }
+
+    /// Read the feed's new entries at real time `now_secs`. Returns true when one of them
+    /// starts a beat: the time scale is then at most 1×. New entries are skipped (but
+    /// read) while story mode is off, a beat is up or the cooldown runs.
+    pub fn observe(
+        &mut self,
+        settings: &StorySettings,
+        feed: &EventFeed,
+        lazy: &LazyUniverse,
+        universe: &mut UniverseState,
+        now_secs: f64,
+    ) -> bool {
+        let entries = feed.entries();
+        let fresh = ((feed.pushed() - self.seen) as usize).min(entries.len());
+        self.seen = feed.pushed();
+        let mut life_found = self.known_life > 0;
+        self.known_life = lazy.discoveries.len();
+
+        // Changing the speed by hand ends a beat without the resume
+        if self.beat.as_ref().is_some_and(|b| universe.time_scale != b.held_scale) {
+            self.end(settings, now_secs);
+        }
+        if !settings.enabled || self.beat.is_some() || now_secs < self.quiet_until {
+            return false;
+        }
+
+        for entry in entries.range(entries.len() - fresh..) {
+            let first_life = matches!(entry.event, SimEvent::LifeDiscovered { .. }) && !life_found;
+            life_found |= first_life;
+            if settings.triggers.matches(&entry.event, first_life) {
+                let held_scale = universe.time_scale.min(1.0);
+                self.beat = Some(StoryBeat {
+                    entry: entry.clone(),
+                    resume_scale: universe.time_scale,
+                    held_scale,
+                });
+                universe.time_scale = held_scale;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Put the time scale from before the beat back and start the cooldown
+    pub fn resume(&mut self, settings: &StorySettings, universe: &mut UniverseState, now_secs: f64) {
+        if let Some(beat) = self.end(settings, now_secs) {
+            universe.time_scale = beat.resume_scale;
+        }
+    }
+
+    /// Drop the beat, leaving the time scale as it is, and start the cooldown
+    pub fn end(&mut self, settings: &StorySettings, now_secs: f64) -> Option<StoryBeat> {
+        let beat = self.beat.take()?;
+        self.quiet_until = now_secs + settings.cooldown_secs;
+        Some(beat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{Genome, LifeDiscovery, PlanetType, SimConfig, UniversePhase};
+
+    fn inject(feed: &mut EventFeed, age_gyr: f64, event: SimEvent) {
+        feed.push(FeedEntry { age_gyr, event });
+    }
+
+    fn civilization(planet_id: u64) -> SimEvent {
+        SimEvent::CivilizationDetected {
+            region_id: 0,
+            planet_id,
+        }
+    }
+
+    fn life(planet_id: u64) -> SimEvent {
+        SimEvent::LifeDiscovered {
+            region_id: 0,
+            planet_id,
+            description: String::new(),
+        }
+    }
+
+    fn setup() -> (StorySettings, EventFeed, LazyUniverse, UniverseState) {
+        let settings = StorySettings {
+            enabled: true,
+            ..StorySettings::default()
+        };
+        let lazy = LazyUniverse::new(SimConfig::default(), 13.0);
+        let mut universe = UniverseState::empty(SimConfig::default());
+        universe.time_scale = 1.0e9;
+        (settings, EventFeed::default(), lazy, universe)
+    }
+
+    #[test]
+    fn test_event_slows_time_and_resume_restores_it() {
+        let (settings, mut feed, lazy, mut universe) = setup();
+        let mut director = StoryDirector::default();
+
+        // A supernova is feed news only
+        inject(&mut feed, 5.0, SimEvent::SupernovaNearby { region_id: 0, count: 1 });
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 0.0));
+        assert_eq!(universe.time_scale, 1.0e9);
+
+        inject(&mut feed, 5.1, civilization(2_001));
+        assert!(director.observe(&settings, &feed, &lazy, &mut universe, 1.0));
+        assert_eq!(universe.time_scale, 1.0);
+        let beat = director.beat().unwrap();
+        assert_eq!(beat.entry.event, civilization(2_001));
+        assert_eq!(beat.location(&lazy), Some(lazy.regions[0].center));
+
+        // Another one while the banner is up doesn't take over, or forget the speed
+        inject(&mut feed, 5.2, civilization(3_001));
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 2.0));
+        assert_eq!(director.beat().unwrap().entry.event, civilization(2_001));
+
+        director.resume(&settings, &mut universe, 3.0);
+        assert_eq!(universe.time_scale, 1.0e9);
+        assert!(director.beat().is_none());
+
+        // Within the cooldown events pass; after it they stop the clock again
+        let transition = SimEvent::PhaseTransition {
+            from: UniversePhase::CivilizationEra,
+            to: UniversePhase::HeatDeath,
+        };
+        inject(&mut feed, 5.3, transition);
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 3.0 + 10.0));
+        assert_eq!(universe.time_scale, 1.0e9);
+        inject(&mut feed, 5.4, civilization(4_001));
+        let later = 3.0 + settings.cooldown_secs + 1.0;
+        assert!(director.observe(&settings, &feed, &lazy, &mut universe, later));
+        assert_eq!(universe.time_scale, 1.0);
+
+        // Picking a speed by hand ends the beat and keeps that speed
+        universe.time_scale = 100.0;
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, later + 1.0));
+        assert!(director.beat().is_none());
+        assert_eq!(universe.time_scale, 100.0);
+    }
+
+    #[test]
+    fn test_only_enabled_and_first_events_interrupt() {
+        let (mut settings, mut feed, mut lazy, mut universe) = setup();
+        settings.triggers.civilization = false;
+        settings.cooldown_secs = 0.0;
+        let mut director = StoryDirector::default();
+
+        inject(&mut feed, 5.0, civilization(2_001));
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 0.0));
+
+        // The universe's first life stops the clock, later finds don't
+        inject(&mut feed, 5.1, life(2_001));
+        inject(&mut feed, 5.1, life(3_001));
+        assert!(director.observe(&settings, &feed, &lazy, &mut universe, 1.0));
+        assert_eq!(director.beat().unwrap().entry.event, life(2_001));
+        director.resume(&settings, &mut universe, 2.0);
+        assert_eq!(universe.time_scale, 1.0e9);
+        lazy.discoveries.push(LifeDiscovery {
+            planet_id: 2_001,
+            star_id: 2,
+            region_id: 0,
+            seed: 0,
+            genome: Genome::primordial(),
+            complexity: 1.0,
+            species_count: 1,
+            has_technology: false,
+            planet_type: PlanetType::Rocky,
+            surface_temp: 290.0,
+            discovery_age: 5.1,
+        });
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 2.0));
+        inject(&mut feed, 5.2, life(4_001));
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 3.0));
+
+        // Off, nothing interrupts
+        settings.enabled = false;
+        let extinct = SimEvent::CivilizationExtinct {
+            region_id: 0,
+            planet_id: 2_001,
+            cause: String::new(),
+        };
+        inject(&mut feed, 5.3, extinct);
+        assert!(!director.observe(&settings, &feed, &lazy, &mut universe, 4.0));
+        assert_eq!(universe.time_scale, 1.0e9);
+    }
+}
@@ -0,0 +1,151 @@
+//! Two-level region grids (`RegionLayout::subdivide_above`): which cubes were split into
+//! 8 children, which of the two levels the overview draws, and the level of detail that
+//! swaps a cube for its children once the camera comes within `CHILD_RANGE` of it.
+
+use std::collections::HashSet;
+
+use matrix_core::Region;
+
+use crate::lazy_universe::LazyUniverse;
+
+/// A subdivided region's children stand in for it while the camera is within this many
+/// of its sizes from its center (about as far as its children get galactic detail)
+pub const CHILD_RANGE: f64 = 1.0;
+
+impl LazyUniverse {
+    /// Ids of the regions split into children (`RegionLayout::subdivide_above`). Their
+    /// children carry the detail: they are never loaded or given mass points themselves.
+    pub fn subdivided_regions(&self) -> HashSet<u64> {
+        self.regions.iter().filter_map(|r| r.parent).collect()
+    }
+
+    /// The children of `region_id`, if it was subdivided
+    pub fn children_of(&self, region_id: u64) -> impl Iterator<Item = &Region> + '_ {
+        self.regions.iter().filter(move |r| r.parent == Some(region_id))
+    }
+
+    /// Regions to draw in the overview: each cube of the grid, or its children while it is
+    /// in `expanded`
+    pub fn overview_regions(&self) -> Vec<&Region> {
+        let subdivided = self.subdivided_regions();
+        self.regions
+            .iter()
+            .filter(|r| match r.parent {
+                Some(parent) => self.expanded.contains(&parent),
+                None => !subdivided.contains(&r.id) || !self.expanded.contains(&r.id),
+            })
+            .collect()
+    }
+
+    /// Set `expanded` to the subdivided regions within `CHILD_RANGE` of the camera
+    pub(crate) fn update_expanded(&mut self, subdivided: &HashSet<u64>) {
+        let expanded: Vec<u64> = self
+            .regions
+            .iter()
+            .filter(|r| subdivided.contains(&r.id))
+            .filter(|r| {
+                let d2: f64 = (0..3).map(|k| (r.center[k] - self.camera_pos[k]).powi(2)).sum();
+                d2.sqrt() < r.size * CHILD_RANGE
+            })
+            .map(|r| r.id)
+            .collect();
+        if expanded != self.expanded {
+            self.expanded = expanded;
+            self.expanded_generation = self.expanded_generation.wrapping_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::{LifeDiscovery, RegionDetail, SimConfig};
+    use matrix_physics::procgen;
+
+    const AGE: f64 = 13.0;
+
+    fn subdivided_universe() -> LazyUniverse {
+        let mut config = SimConfig::default();
+        config.region_layout.subdivide_above = Some(1.5);
+        let mut lazy = LazyUniverse::new(config, AGE);
+        lazy.async_loading = false;
+        lazy
+    }
+
+    /// Run enough frames for a distance check at `pos`
+    fn fly_to(lazy: &mut LazyUniverse, pos: [f64; 3]) {
+        for _ in 0..5 {
+            lazy.update_lod(pos, AGE);
+        }
+    }
+
+    #[test]
+    fn test_children_take_over_close_to_their_parent() {
+        let mut lazy = subdivided_universe();
+        let parent = lazy
+            .regions
+            .iter()
+            .find(|r| r.parent.is_none() && r.density > 1.5)
+            .cloned()
+            .unwrap();
+        let children: Vec<Region> = lazy.children_of(parent.id).cloned().collect();
+        assert_eq!(children.len(), 8);
+        let overview = |lazy: &LazyUniverse| -> Vec<u64> {
+            lazy.overview_regions().iter().map(|r| r.id).collect()
+        };
+
+        // From afar the parent is drawn, not its children
+        fly_to(&mut lazy, [1.0e5, 0.0, 0.0]);
+        assert!(lazy.expanded.is_empty());
+        assert!(overview(&lazy).contains(&parent.id));
+        assert!(!overview(&lazy).contains(&children[0].id));
+        assert_eq!(overview(&lazy).len(), lazy.config.region_layout.cell_count() as usize);
+
+        // Inside it, the child the camera is in is loaded and the children are drawn
+        let generation = lazy.expanded_generation;
+        let inside = children[5].center;
+        fly_to(&mut lazy, inside);
+        assert!(lazy.expanded.contains(&parent.id));
+        assert_ne!(lazy.expanded_generation, generation);
+        assert_eq!(lazy.current_region_id, Some(children[5].id));
+        let drawn = overview(&lazy);
+        assert!(!drawn.contains(&parent.id));
+        assert!(children.iter().all(|c| drawn.contains(&c.id)));
+
+        // The parent itself stays statistical, its child gets the detail
+        let detail = |id: u64| lazy.regions.iter().find(|r| r.id == id).unwrap().detail.clone();
+        assert_eq!(detail(parent.id), RegionDetail::Statistical);
+        assert!(detail(children[5].id) >= RegionDetail::Stellar);
+    }
+
+    #[test]
+    fn test_totals_and_flags_count_children_once() {
+        let mut lazy = subdivided_universe();
+        let flat = LazyUniverse::new(SimConfig::default(), AGE);
+        // The cubes are the same universe; the children only split their stars
+        let ratio = lazy.total_stars() as f64 / flat.total_stars() as f64;
+        assert!((ratio - 1.0).abs() < 1e-9, "{ratio}");
+
+        // Life found in a child shows on its parent too
+        let (child, discovery) = lazy
+            .regions
+            .iter()
+            .filter(|r| r.parent.is_some())
+            .find_map(|child| {
+                let stars = procgen::generate_stellar_detail(child, AGE);
+                let (star, planet) = stars.iter().find_map(|s| {
+                    s.planets.iter().find(|p| p.life.is_some()).map(|p| (s, p))
+                })?;
+                let bio = planet.life.as_ref()?;
+                let discovery =
+                    LifeDiscovery::new(lazy.config.seed, child.id, star, planet, bio, AGE);
+                Some((child.clone(), discovery))
+            })
+            .expect("life in some child region");
+        lazy.discoveries.push(discovery);
+        lazy.refresh_region_flags();
+        let has_life = |id: u64| lazy.regions.iter().find(|r| r.id == id).unwrap().has_life;
+        assert!(has_life(child.id) && has_life(child.parent.unwrap()));
+        assert_eq!(lazy.regions.iter().filter(|r| r.has_life).count(), 2);
+    }
+}
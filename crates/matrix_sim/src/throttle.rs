@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+/// Frames between gravity steps until the first step has been measured
+const INITIAL_INTERVAL: u32 = 3;
+/// Longest gap between steps while particles are in view
+pub const MAX_INTERVAL: u32 = 120;
+/// Frames between steps while particles are out of view (Cosmic / Galactic zoom)
+pub const HEARTBEAT_INTERVAL: u32 = 240;
+/// Weight of the newest sample in the moving averages
+const AVERAGE_WEIGHT: f64 = 0.2;
+/// A frame owes the particles at most this many average frames of time, so a hitch
+/// doesn't turn into one huge step that flings them apart
+const HITCH_FACTOR: f64 = 4.0;
+
+/// Decides how often the particle gravity step runs. The cost of each CPU step is
+/// averaged, and the interval is the fewest frames that keep the amortized cost within
+/// `SimConfig::gravity_budget_ms`. Simulated time accumulates between steps, so the
+/// next step integrates all of it and particle dynamics don't depend on the interval
+/// or the frame rate.
+#[derive(Debug, Clone)]
+pub struct GravityThrottle {
+    /// Moving average of a step's cost (ms); `None` before the first measured step
+    pub average_step_ms: Option<f64>,
+    /// Frames between steps while particles are active
+    pub interval: u32,
+    /// Moving average of a frame's dt (before the time scale), for hitch detection
+    average_frame_dt: Option<f64>,
+    /// Frames since the last step
+    frames_waiting: u32,
+    /// Simulated time owed to the particles since the last step
+    pending_dt: f64,
+}
+
+impl Default for GravityThrottle {
+    fn default() -> Self {
+        Self {
+            average_step_ms: None,
+            interval: INITIAL_INTERVAL,
+            average_frame_dt: None,
+            frames_waiting: 0,
+            pending_dt: 0.0,
+        }
+    }
+}
+
+impl GravityThrottle {
+    /// Count one frame of `dt` × `time_scale` simulated time (`dt` clamped after a
+    /// hitch). Returns the time the step should integrate when one is due: every
+    /// `interval` frames, or every `HEARTBEAT_INTERVAL` frames while particles are
+    /// out of view.
+    pub fn advance(&mut self, dt: f64, time_scale: f64, particles_active: bool) -> Option<f64> {
+        let owed = match self.average_frame_dt {
+            // (Bevy's first frame has a dt of 0)
+            Some(average) if average > 0.0 => dt.min(average * HITCH_FACTOR),
+            _ => dt,
+        };
+        self.average_frame_dt = Some(match self.average_frame_dt {
+            Some(average) => average + AVERAGE_WEIGHT * (owed - average),
+            None => owed,
+        });
+        self.frames_waiting += 1;
+        self.pending_dt += owed * time_scale;
+        let interval = if particles_active {
+            self.interval
+        } else {
+            HEARTBEAT_INTERVAL
+        };
+        if self.frames_waiting < interval {
+            return None;
+        }
+        self.frames_waiting = 0;
+        Some(std::mem::take(&mut self.pending_dt))
+    }
+
+    /// Fold a measured step into the average and re-derive the interval
+    pub fn record_step(&mut self, elapsed: Duration, budget_ms: f32) {
+        let step_ms = elapsed.as_secs_f64() * 1000.0;
+        let average = match self.average_step_ms {
+            Some(average) => average + AVERAGE_WEIGHT * (step_ms - average),
+            None => step_ms,
+        };
+        self.average_step_ms = Some(average);
+        self.interval = interval_for(average, budget_ms);
+    }
+
+    /// Forget the accumulated time (the particles it belonged to were replaced)
+    pub fn reset(&mut self) {
+        self.frames_waiting = 0;
+        self.pending_dt = 0.0;
+    }
+}
+
+/// Fewest frames between steps costing `step_ms` that keep gravity within
+/// `budget_ms` per frame (1..=`MAX_INTERVAL`; a budget of 0 or less means the maximum)
+pub fn interval_for(step_ms: f64, budget_ms: f32) -> u32 {
+    if budget_ms <= 0.0 {
+        return MAX_INTERVAL;
+    }
+    (step_ms / budget_ms as f64)
+        .ceil()
+        .clamp(1.0, MAX_INTERVAL as f64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_keeps_gravity_within_budget() {
+        assert_eq!(interval_for(2.0, 4.0), 1);
+        assert_eq!(interval_for(10.0, 4.0), 3);
+        assert_eq!(interval_for(10_000.0, 4.0), MAX_INTERVAL);
+        assert_eq!(interval_for(10.0, 0.0), MAX_INTERVAL);
+
+        // The average follows the measured cost without jumping on one slow step
+        let mut throttle = GravityThrottle::default();
+        throttle.record_step(Duration::from_millis(8), 4.0);
+        assert_eq!(throttle.interval, 2);
+        throttle.record_step(Duration::from_millis(48), 4.0);
+        assert_eq!(throttle.average_step_ms, Some(16.0));
+        assert_eq!(throttle.interval, 4);
+
+        // Steps come every `interval` frames and carry all the time since the last one
+        let steps: Vec<_> = (0..8)
+            .filter_map(|_| throttle.advance(0.5, 1.0, true))
+            .collect();
+        assert_eq!(steps, vec![2.0, 2.0]);
+
+        // A hitch only owes `HITCH_FACTOR` average frames
+        let steps: Vec<_> = [0.5, 60.0, 0.5, 0.5]
+            .into_iter()
+            .filter_map(|dt| throttle.advance(dt, 10.0, true))
+            .collect();
+        assert_eq!(steps, vec![(0.5 + 0.5 * HITCH_FACTOR + 0.5 + 0.5) * 10.0]);
+
+        // Out of view: only the heartbeat
+        let steps = (0..HEARTBEAT_INTERVAL).filter_map(|_| throttle.advance(0.5, 2.0, false));
+        assert_eq!(steps.collect::<Vec<_>>(), vec![HEARTBEAT_INTERVAL as f64]);
+    }
+}
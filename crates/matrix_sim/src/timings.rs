@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+/// Wall-clock cost of the heavy simulation steps, for the debug overlay.
+/// Nothing is recorded unless `enabled` is set, so the hooks cost a branch when off.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct SimTimings {
+    pub enabled: bool,
+    /// Last CPU gravity step (GPU steps are not timed here)
+    pub gravity: Option<Duration>,
+    /// Particles integrated by that step
+    pub gravity_particles: usize,
+    /// Last region detail load on the main thread (generation + install, or install only
+    /// when the detail came from a background task or the cache)
+    pub region_load: Option<Duration>,
+    pub region_load_id: Option<u64>,
+    /// Last particle cloud resample and mesh update (render side)
+    pub particle_clouds: Option<Duration>,
+}
+
+impl SimTimings {
+    pub fn record_gravity(&mut self, elapsed: Duration, particles: usize) {
+        if self.enabled {
+            self.gravity = Some(elapsed);
+            self.gravity_particles = particles;
+        }
+    }
+
+    pub fn record_particle_clouds(&mut self, elapsed: Duration) {
+        if self.enabled {
+            self.particle_clouds = Some(elapsed);
+        }
+    }
+
+    pub fn record_region_load(&mut self, region_id: u64, elapsed: Duration) {
+        self.region_load = Some(elapsed);
+        self.region_load_id = Some(region_id);
+    }
+
+    /// Forget all measurements (when the overlay is closed, stale numbers would mislead)
+    pub fn clear(&mut self) {
+        *self = Self {
+            enabled: self.enabled,
+            ..Self::default()
+        };
+    }
+}
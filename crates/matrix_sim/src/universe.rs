@@ -1,18 +1,84 @@
-use bevy::prelude::*;
 use matrix_core::constants::NEAR_FIELD_K;
-use matrix_core::{GpuParticle, SimConfig, UniversePhase, MAX_ENTROPY};
-use matrix_physics::forces::{near_field_gravity, SpatialHash};
+use matrix_core::constants::{COULOMB_K, HEAT_DEATH_ENTROPY};
+use matrix_core::{
+    CowVec, FeedEntry, GpuParticle, Integrator, ParticleKind, SimConfig, SimEvent, UniversePhase,
+    MAX_ENTROPY,
+};
+use matrix_physics::forces::{near_field_electromagnetic, near_field_gravity, SpatialHash};
+use matrix_physics::integrator;
+use matrix_physics::particle;
 use matrix_physics::spacetime;
-use matrix_physics::thermodynamics;
+use rand::SeedableRng;
+use std::time::Instant;
+
+use crate::black_holes::ACCRETION_FADE;
+use crate::throttle::GravityThrottle;
+use crate::timings::SimTimings;
+
+/// Scale factor below which a collapsing universe bounces into a new Big Bang
+const BOUNCE_SCALE_FACTOR: f64 = 0.01;
+/// Inward acceleration toward the origin during Collapse (per unit distance)
+const COLLAPSE_PULL: f32 = 0.5;
+
+/// Phases left at a fixed age (Gyr): (phase, age it ends at, next phase). The Heat Death
+/// and the Collapse come with entropy instead.
+pub(crate) const PHASE_SCHEDULE: [(UniversePhase, f64, UniversePhase); 7] = [
+    (UniversePhase::BigBang, 0.000001, UniversePhase::Inflation),
+    (UniversePhase::Inflation, 0.00001, UniversePhase::NuclearEra),
+    (UniversePhase::NuclearEra, 0.0004, UniversePhase::AtomicEra),
+    (UniversePhase::AtomicEra, 0.4, UniversePhase::CosmicDawn),
+    (UniversePhase::CosmicDawn, 1.0, UniversePhase::StellarEra),
+    (UniversePhase::StellarEra, 10.0, UniversePhase::BiologicalEra),
+    (UniversePhase::BiologicalEra, 13.0, UniversePhase::CivilizationEra),
+];
+
+/// Cells per side of the far-field gravity grid
+pub const FAR_FIELD_GRID: i32 = 16;
+
+/// Far-field mass grid of the last gravity step: `FAR_FIELD_GRID`³ cells over the particles'
+/// bounding box, baryonic and dark mass kept apart so the dark scaffold can be shown
+#[derive(Debug, Clone, Default)]
+pub struct MassGrid {
+    /// Corner of the bounding box
+    pub min: [f32; 3],
+    /// Size of the bounding box
+    pub range: [f32; 3],
+    /// Baryonic mass per cell, x-major
+    pub baryonic: Vec<f32>,
+    /// Dark matter mass per cell
+    pub dark: Vec<f32>,
+}
+
+impl MassGrid {
+    /// Cell index of a position (clamped into the grid)
+    pub fn cell_index(&self, pos: [f32; 3]) -> usize {
+        let [gx, gy, gz] = std::array::from_fn(|k| {
+            (((pos[k] - self.min[k]) / self.range[k] * FAR_FIELD_GRID as f32) as i32)
+                .clamp(0, FAR_FIELD_GRID - 1)
+        });
+        (gx * FAR_FIELD_GRID * FAR_FIELD_GRID + gy * FAR_FIELD_GRID + gz) as usize
+    }
+
+    /// Share of all the grid's mass that is dark (0 when empty)
+    pub fn dark_fraction(&self) -> f64 {
+        let dark: f64 = self.dark.iter().map(|&m| m as f64).sum();
+        let baryonic: f64 = self.baryonic.iter().map(|&m| m as f64).sum();
+        if dark + baryonic > 0.0 {
+            dark / (dark + baryonic)
+        } else {
+            0.0
+        }
+    }
+}
 
 /// Global universe state, tracked as a Bevy Resource
-#[derive(Resource)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
 pub struct UniverseState {
     /// Age of the universe in Gyr (billions of years)
     pub age: f64,
     /// Current scale factor (1.0 at Big Bang, grows with expansion)
     pub scale_factor: f64,
-    /// Total entropy of the system
+    /// Total entropy of the system (never decreases within a cycle)
     pub total_entropy: f64,
     /// Current phase of the universe
     pub phase: UniversePhase,
@@ -25,17 +91,40 @@ pub struct UniverseState {
     /// Time scale multiplier (1.0 = normal, 1000.0 = fast, etc.)
     pub time_scale: f64,
     /// Particle data on CPU (synced from GPU periodically)
-    pub particles: Vec<GpuParticle>,
+    pub particles: CowVec<GpuParticle>,
     /// Simulation config
     pub config: SimConfig,
-    /// Frame counter for throttling gravity
+    /// Frame counter for the periodic thermodynamics / compaction passes
     pub gravity_frame: u32,
+    /// When the particle gravity step runs, and with how much simulated time
+    pub gravity_throttle: GravityThrottle,
     /// Whether particle gravity should be computed (set by render based on camera distance)
     pub particles_active: bool,
     /// Cached alive particle count (updated periodically, not every frame)
     pub cached_alive_count: usize,
     /// Incremented when particles are replaced by lazy loading (render uses this)
     pub particles_generation: u32,
+    /// Incremented when a particle step lands (CPU step or GPU readback); the render
+    /// only resamples its point clouds when this or the camera moved
+    pub particle_steps: u32,
+    /// Gravity is computed on the GPU (set by `GpuSimPlugin`); CPU path is skipped
+    pub gpu_active: bool,
+    /// Effective dt of the next GPU gravity step (0 = nothing queued)
+    pub pending_gpu_dt: f64,
+    /// Per-particle acceleration from the last leapfrog step
+    pub prev_accel: Vec<[f32; 3]>,
+    /// `particles_generation` that `prev_accel` belongs to
+    pub prev_accel_generation: u32,
+    /// Particles swallowed by black holes this cycle (an entropy source)
+    pub captured_particles: u64,
+    /// Share of the charged-capable matter still ionized (updated with the thermodynamics)
+    pub ionized_fraction: f64,
+    /// Far-field mass grid of the last CPU gravity step
+    pub mass_grid: MassGrid,
+    /// Set when a collapse bounced into a new cycle; cleared once `LazyUniverse` is regenerated
+    pub cycle_restarted: bool,
+    /// Phase transitions / new cycles not yet moved into `EventFeed`
+    pub pending_events: Vec<FeedEntry>,
 }
 
 impl UniverseState {
@@ -55,17 +144,40 @@ impl UniverseState {
             temperature: 1e10,
             paused: false,
             time_scale: 1.0,
-            particles,
+            particles: particles.into(),
             config,
             gravity_frame: 0,
+            gravity_throttle: GravityThrottle::default(),
             particles_active: true,
             cached_alive_count: count,
             particles_generation: 0,
+            particle_steps: 0,
+            gpu_active: false,
+            pending_gpu_dt: 0.0,
+            prev_accel: Vec::new(),
+            prev_accel_generation: 0,
+            captured_particles: 0,
+            ionized_fraction: 0.0,
+            mass_grid: MassGrid::default(),
+            cycle_restarted: false,
+            pending_events: Vec::new(),
         }
     }
 
     /// Advance the universe by one tick
     pub fn tick(&mut self, dt: f64) {
+        self.tick_timed(dt, &mut SimTimings::default());
+    }
+
+    /// One CPU particle step with `dt` of simulated time (Gyr), bypassing the throttle
+    /// and the clock: the particles move, nothing else does (benchmarks)
+    pub fn force_gravity_step(&mut self, dt: f64) {
+        self.tick_particles(dt);
+        self.particle_steps = self.particle_steps.wrapping_add(1);
+    }
+
+    /// `tick`, recording the CPU gravity step into `timings` while they are enabled
+    pub fn tick_timed(&mut self, dt: f64, timings: &mut SimTimings) {
         if self.paused {
             return;
         }
@@ -75,22 +187,27 @@ impl UniverseState {
 
         self.gravity_frame = self.gravity_frame.wrapping_add(1);
 
-        // Throttle gravity: hybrid gravity is heavy (~400M ops)
-        // Even at time_scale 1, run every 3rd frame for smooth 60fps
-        let gravity_interval = if self.time_scale >= 1_000_000.0 {
-            120
-        } else if self.time_scale >= 10_000.0 {
-            30
-        } else if self.time_scale >= 100.0 {
-            5
-        } else {
-            3
-        };
-
-        let run_gravity = self.particles_active && (self.gravity_frame % gravity_interval == 0);
-
-        if run_gravity {
-            self.tick_particles(effective_dt);
+        // Throttle gravity: hybrid gravity is heavy (~400M ops), so it runs every few
+        // frames within a frame-time budget, integrating the time since the last step
+        let step = self.gravity_throttle.advance(dt, self.time_scale, self.particles_active);
+        if let Some(step_dt) = step {
+            if self.phase == UniversePhase::Collapse {
+                self.pull_toward_origin(step_dt);
+            }
+            if self.gpu_active {
+                // Same per-step dt as the CPU path; while a step is in flight the time adds up
+                self.pending_gpu_dt += step_dt;
+            } else {
+                let start = Instant::now();
+                self.tick_particles(step_dt);
+                self.particle_steps = self.particle_steps.wrapping_add(1);
+                let elapsed = start.elapsed();
+                // Heartbeat steps (particles out of view) are not representative
+                if self.particles_active {
+                    self.gravity_throttle.record_step(elapsed, self.config.gravity_budget_ms);
+                }
+                timings.record_gravity(elapsed, self.particles.len());
+            }
         }
 
         // These are cheap — always run
@@ -98,17 +215,18 @@ impl UniverseState {
         self.scale_factor =
             spacetime::expand_scale_factor(self.scale_factor, hubble, effective_dt);
 
+        if self.phase == UniversePhase::Collapse && self.scale_factor < BOUNCE_SCALE_FACTOR {
+            self.begin_new_cycle();
+            return;
+        }
+
         // Thermodynamics + alive count: every 30 frames
-        if self.gravity_frame % 30 == 0 {
-            let (entropy, temp) =
-                thermodynamics::calculate_entropy_and_temperature(&self.particles);
-            self.total_entropy = entropy;
-            self.temperature = temp;
-            self.cached_alive_count = self.particles.iter().filter(|p| p.is_alive()).count();
+        if self.gravity_frame.is_multiple_of(30) {
+            self.update_thermodynamics();
         }
 
         // Compact: remove dead particles every 100 frames
-        if self.gravity_frame % 100 == 0 {
+        if self.gravity_frame.is_multiple_of(100) {
             self.compact_particles();
         }
 
@@ -116,6 +234,56 @@ impl UniverseState {
         self.update_phase();
     }
 
+    /// Collapse: everything falls back toward the origin (applied with each gravity step)
+    fn pull_toward_origin(&mut self, step_dt: f64) {
+        let pull = COLLAPSE_PULL * step_dt as f32;
+        for p in self.particles.iter_mut().filter(|p| p.is_alive()) {
+            let [x, y, z, _mass] = p.position;
+            p.velocity[0] -= x * pull;
+            p.velocity[1] -= y * pull;
+            p.velocity[2] -= z * pull;
+        }
+    }
+
+    /// Seed of the current cycle (cycle 1 uses `config.seed` itself)
+    pub fn cycle_seed(&self) -> u64 {
+        self.config
+            .seed
+            .wrapping_add(u64::from(self.cycle - 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    /// The collapse reached the bounce point: start the next cycle with a fresh Big Bang
+    fn begin_new_cycle(&mut self) {
+        self.cycle += 1;
+        let seed = self.cycle_seed();
+        info!(
+            "Big Crunch at age {:.3} Gyr — cycle {} begins (seed {})",
+            self.age, self.cycle, seed
+        );
+
+        let config = SimConfig {
+            seed,
+            ..self.config.clone()
+        };
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+
+        self.age = 0.0;
+        self.scale_factor = 1.0;
+        self.total_entropy = 0.0;
+        self.captured_particles = 0;
+        self.temperature = 1e10;
+        self.phase = UniversePhase::BigBang;
+        self.pending_gpu_dt = 0.0;
+        self.prev_accel.clear();
+        self.replace_particles(particles);
+        self.cycle_restarted = true;
+        self.pending_events.push(FeedEntry {
+            age_gyr: 0.0,
+            event: SimEvent::CycleBegan { cycle: self.cycle },
+        });
+    }
+
     /// Remove dead particles from the array to reduce iteration cost
     fn compact_particles(&mut self) {
         let before = self.particles.len();
@@ -126,15 +294,89 @@ impl UniverseState {
         }
     }
 
-    /// Heavy particle simulation: hybrid gravity (near-field direct + far-field grid) + integration
+    /// Heavy particle simulation: hybrid gravity (near-field direct + far-field grid), plus
+    /// near-field electromagnetism while the universe is ionized, + integration; then black
+    /// holes swallow what came within their capture radius and, once cooled, electrons and
+    /// protons recombine
     fn tick_particles(&mut self, effective_dt: f64) {
         let sim_dt = effective_dt as f32 * 0.1;
         let hubble = spacetime::hubble_parameter(self.age, self.phase) as f32;
         let gravity_strength = self.config.gravity_scale * 0.5;
+        let coulomb_strength = if self.is_ionized() { COULOMB_K } else { 0.0 };
+
+        let spatial_hash = match self.config.integrator {
+            Integrator::Euler => {
+                let (accel, spatial_hash, grid) =
+                    self.compute_accelerations(gravity_strength, coulomb_strength);
+                integrator::euler_step(&mut self.particles, &accel, sim_dt);
+                self.prev_accel.clear();
+                self.mass_grid = grid;
+                spatial_hash
+            }
+            Integrator::Leapfrog => {
+                // Previous step's acceleration is only valid for the same particle set
+                if self.prev_accel.len() != self.particles.len()
+                    || self.prev_accel_generation != self.particles_generation
+                {
+                    self.prev_accel = self.compute_accelerations(gravity_strength, coulomb_strength).0;
+                }
+                integrator::leapfrog_kick_drift(&mut self.particles, &self.prev_accel, sim_dt);
+                let (accel, spatial_hash, grid) =
+                    self.compute_accelerations(gravity_strength, coulomb_strength);
+                integrator::leapfrog_kick(&mut self.particles, &accel, sim_dt);
+                self.prev_accel = accel;
+                self.prev_accel_generation = self.particles_generation;
+                self.mass_grid = grid;
+                spatial_hash
+            }
+        };
+        self.capture_into_black_holes(&spatial_hash);
+        if !self.is_ionized() {
+            self.recombine(&spatial_hash);
+        }
+
+        for p in self.particles.iter_mut() {
+            if !p.is_alive() {
+                continue;
+            }
+
+            // Hubble expansion
+            p.position[0] += p.position[0] * hubble * sim_dt * 0.001;
+            p.position[1] += p.position[1] * hubble * sim_dt * 0.001;
+            p.position[2] += p.position[2] * hubble * sim_dt * 0.001;
+
+            // Dark matter is collisionless: it neither loses energy to friction nor radiates
+            if p.kind == ParticleKind::DarkMatter as u32 {
+                continue;
+            }
+
+            // Velocity damping
+            let damping = 1.0 - sim_dt * 0.002;
+            p.velocity[0] *= damping;
+            p.velocity[1] *= damping;
+            p.velocity[2] *= damping;
 
+            // Cool down temperature (a black hole's accretion glow fades much faster)
+            let cooling = if p.kind == ParticleKind::BlackHole as u32 {
+                ACCRETION_FADE
+            } else {
+                0.01
+            };
+            p.temperature *= (1.0 - sim_dt * cooling).max(0.0);
+        }
+    }
+
+    /// Hybrid gravity: near-field direct (K nearest) + far-field grid centers-of-mass, and the
+    /// Coulomb pull of the same K nearest (none with `coulomb_strength` 0).
+    /// Returns one acceleration per particle (zero for dead ones), the near-field hash and
+    /// the far-field mass grid.
+    fn compute_accelerations(
+        &self,
+        gravity_strength: f32,
+        coulomb_strength: f32,
+    ) -> (Vec<[f32; 3]>, SpatialHash, MassGrid) {
         // --- Far-field: grid-based gravity approximation ---
-        let grid_size: i32 = 16;
-        let total_cells = (grid_size * grid_size * grid_size) as usize;
+        let total_cells = (FAR_FIELD_GRID * FAR_FIELD_GRID * FAR_FIELD_GRID) as usize;
 
         // Find bounding box
         let mut bb_min = [f32::MAX; 3];
@@ -154,29 +396,33 @@ impl UniverseState {
             (bb_max[2] - bb_min[2]).max(1.0),
         ];
 
-        // Accumulate mass and position per grid cell
-        let mut cell_mass = vec![0.0f32; total_cells];
+        // Accumulate mass (baryonic and dark apart) and position per grid cell
+        let mut grid = MassGrid {
+            min: bb_min,
+            range: bb_range,
+            baryonic: vec![0.0; total_cells],
+            dark: vec![0.0; total_cells],
+        };
         let mut cell_pos = vec![[0.0f64; 3]; total_cells];
 
         for p in self.particles.iter() {
             if !p.is_alive() {
                 continue;
             }
-            let gx = (((p.position[0] - bb_min[0]) / bb_range[0] * grid_size as f32) as i32)
-                .clamp(0, grid_size - 1);
-            let gy = (((p.position[1] - bb_min[1]) / bb_range[1] * grid_size as f32) as i32)
-                .clamp(0, grid_size - 1);
-            let gz = (((p.position[2] - bb_min[2]) / bb_range[2] * grid_size as f32) as i32)
-                .clamp(0, grid_size - 1);
-            let idx = (gx * grid_size * grid_size + gy * grid_size + gz) as usize;
+            let idx = grid.cell_index(p.pos());
             let m = p.mass();
-            cell_mass[idx] += m;
+            if p.kind == ParticleKind::DarkMatter as u32 {
+                grid.dark[idx] += m;
+            } else {
+                grid.baryonic[idx] += m;
+            }
             cell_pos[idx][0] += p.position[0] as f64 * m as f64;
             cell_pos[idx][1] += p.position[1] as f64 * m as f64;
             cell_pos[idx][2] += p.position[2] as f64 * m as f64;
         }
 
         // Finalize center-of-mass
+        let cell_mass: Vec<f32> = grid.baryonic.iter().zip(&grid.dark).map(|(b, d)| b + d).collect();
         for i in 0..total_cells {
             if cell_mass[i] > 0.0 {
                 let m = cell_mass[i] as f64;
@@ -218,21 +464,36 @@ impl UniverseState {
         let near_accels: Vec<(usize, [f32; 3])> = neighbor_lists
             .iter()
             .map(|(i, neighbors, pos)| {
-                let acc = near_field_gravity(*pos, neighbors, &self.particles, gravity_strength);
+                let mut acc = near_field_gravity(*pos, neighbors, &self.particles, gravity_strength);
+                if coulomb_strength > 0.0 {
+                    let p = &self.particles[*i];
+                    let em = near_field_electromagnetic(
+                        *pos,
+                        p.charge(),
+                        p.mass(),
+                        neighbors,
+                        &self.particles,
+                        coulomb_strength,
+                    );
+                    for k in 0..3 {
+                        acc[k] += em[k];
+                    }
+                }
                 (*i, acc)
             })
             .collect();
 
         let softening = 0.5f32;
 
-        // --- Update each particle with combined near + far gravity ---
+        // --- Combine near + far gravity per particle ---
         // Build a map from particle index to near-field acceleration
         let mut near_acc_map = vec![[0.0f32; 3]; self.particles.len()];
         for (idx, acc) in near_accels {
             near_acc_map[idx] = acc;
         }
 
-        for (pi, p) in self.particles.iter_mut().enumerate() {
+        let mut accel = vec![[0.0f32; 3]; self.particles.len()];
+        for (pi, p) in self.particles.iter().enumerate() {
             if !p.is_alive() {
                 continue;
             }
@@ -264,49 +525,27 @@ impl UniverseState {
                 az += f * dz;
             }
 
-            p.velocity[0] += ax * sim_dt;
-            p.velocity[1] += ay * sim_dt;
-            p.velocity[2] += az * sim_dt;
-
-            p.position[0] += p.velocity[0] * sim_dt;
-            p.position[1] += p.velocity[1] * sim_dt;
-            p.position[2] += p.velocity[2] * sim_dt;
-
-            // Hubble expansion
-            p.position[0] += p.position[0] * hubble * sim_dt * 0.001;
-            p.position[1] += p.position[1] * hubble * sim_dt * 0.001;
-            p.position[2] += p.position[2] * hubble * sim_dt * 0.001;
-
-            // Velocity damping
-            let damping = 1.0 - sim_dt * 0.002;
-            p.velocity[0] *= damping;
-            p.velocity[1] *= damping;
-            p.velocity[2] *= damping;
-
-            // Cool down temperature
-            p.temperature *= 1.0 - sim_dt * 0.01;
+            accel[pi] = [ax, ay, az];
         }
+        (accel, spatial_hash, grid)
     }
 
     fn update_phase(&mut self) {
-        let new_phase = match self.phase {
-            UniversePhase::BigBang if self.age > 0.000001 => Some(UniversePhase::Inflation),
-            UniversePhase::Inflation if self.age > 0.00001 => Some(UniversePhase::NuclearEra),
-            UniversePhase::NuclearEra if self.age > 0.0004 => Some(UniversePhase::AtomicEra),
-            UniversePhase::AtomicEra if self.age > 0.4 => Some(UniversePhase::CosmicDawn),
-            UniversePhase::CosmicDawn if self.age > 1.0 => Some(UniversePhase::StellarEra),
-            UniversePhase::StellarEra if self.age > 10.0 => Some(UniversePhase::BiologicalEra),
-            UniversePhase::BiologicalEra if self.age > 13.0 => {
-                Some(UniversePhase::CivilizationEra)
-            }
-            UniversePhase::CivilizationEra if self.total_entropy > MAX_ENTROPY * 0.9 => {
+        let scheduled = PHASE_SCHEDULE
+            .iter()
+            .find(|(phase, end, _)| *phase == self.phase && self.age > *end)
+            .map(|&(_, _, next)| next);
+        let new_phase = scheduled.or(match self.phase {
+            UniversePhase::CivilizationEra
+                if self.total_entropy > MAX_ENTROPY * HEAT_DEATH_ENTROPY =>
+            {
                 Some(UniversePhase::HeatDeath)
             }
             UniversePhase::HeatDeath if self.total_entropy > MAX_ENTROPY => {
                 Some(UniversePhase::Collapse)
             }
             _ => None,
-        };
+        });
 
         if let Some(phase) = new_phase {
             info!(
@@ -315,6 +554,13 @@ impl UniverseState {
                 phase.name(),
                 self.age
             );
+            self.pending_events.push(FeedEntry {
+                age_gyr: self.age,
+                event: SimEvent::PhaseTransition {
+                    from: self.phase,
+                    to: phase,
+                },
+            });
             self.phase = phase;
         }
     }
@@ -322,8 +568,9 @@ impl UniverseState {
     /// Replace particle vec with new data (lazy loading)
     pub fn replace_particles(&mut self, particles: Vec<GpuParticle>) {
         self.cached_alive_count = particles.len();
-        self.particles = particles;
+        self.particles = particles.into();
         self.particles_generation = self.particles_generation.wrapping_add(1);
+        self.gravity_throttle.reset();
     }
 
     /// Get the current Hubble parameter
@@ -431,7 +678,7 @@ impl UniverseState {
             .iter()
             .enumerate()
             .filter(|(_, p)| {
-                p.is_alive() && kind.map_or(true, |k| p.kind == k)
+                p.is_alive() && kind.is_none_or(|k| p.kind == k)
             })
             .map(|(i, p)| (i, p.pos()))
             .collect();
@@ -444,3 +691,218 @@ impl UniverseState {
         Some(candidates[candidates.len() / 2])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::throttle;
+
+    #[test]
+    fn heat_death_collapses_into_new_cycle() {
+        let config = SimConfig {
+            particle_count: 300,
+            ..SimConfig::default()
+        };
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+        let first_cycle_particles = particles.clone();
+        let mut universe = UniverseState::new(config, particles);
+        universe.total_entropy = MAX_ENTROPY * 1.5;
+
+        let mut ticks = 0;
+        while universe.cycle < 2 {
+            universe.tick(1.0);
+            ticks += 1;
+            assert!(ticks < 10_000, "no bounce (phase {:?})", universe.phase);
+        }
+
+        assert!(universe.cycle_restarted);
+        assert_eq!(universe.age, 0.0);
+        assert_eq!(universe.phase, UniversePhase::BigBang);
+        assert_eq!(universe.total_entropy, 0.0);
+        assert_eq!(universe.particles.len(), 300);
+        // New seed → a different Big Bang
+        assert_ne!(universe.particles[0].position, first_cycle_particles[0].position);
+    }
+
+    #[test]
+    fn particles_evolve_the_same_at_any_frame_rate() {
+        // A loose lattice: smooth gravity, no close encounters to amplify step differences
+        let mut particles = Vec::new();
+        for i in 0..216 {
+            let (x, y, z) = ((i % 6) as f32, (i / 6 % 6) as f32, (i / 36) as f32);
+            particles.push(GpuParticle::new(
+                [x * 20.0 - 50.0, y * 20.0 - 50.0, z * 20.0 - 50.0],
+                [y - 2.5, z - 2.5, x - 2.5],
+                1.0,
+                0.0,
+                ParticleKind::DarkMatter,
+            ));
+        }
+        let config = SimConfig {
+            gravity_budget_ms: f32::INFINITY,
+            ..SimConfig::default()
+        };
+        let run = |ticks: usize, dt: f64| {
+            let mut universe = UniverseState::new(config.clone(), particles.clone());
+            universe.particles_active = true;
+            for _ in 0..ticks {
+                universe.tick(dt);
+            }
+            universe
+        };
+
+        let at_60 = run(1000, 1.0 / 60.0);
+        let at_30 = run(500, 1.0 / 30.0);
+        assert!((at_60.age - at_30.age).abs() < 1e-9);
+        let mut moved = 0.0;
+        let mut error = 0.0;
+        for ((a, b), start) in at_60.particles.iter().zip(&at_30.particles).zip(&particles) {
+            for i in 0..3 {
+                moved += (a.position[i] - start.position[i]).abs();
+                error += (a.position[i] - b.position[i]).abs();
+            }
+        }
+        assert!(moved > 0.0);
+        assert!(error / moved < 0.02, "positions diverge: {error} over {moved}");
+    }
+
+    #[test]
+    fn dark_halos_hold_their_clusters_together() {
+        use matrix_physics::stats;
+        use rand::Rng;
+
+        // 100 hydrogen atoms too fast for their own gravity, in a halo of `dark` particles
+        let run = |dark: usize| {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+            let mut sphere = |r: f32| -> [f32; 3] {
+                loop {
+                    let p: [f32; 3] = std::array::from_fn(|_| rng.gen_range(-r..r));
+                    if p.iter().map(|x| x * x).sum::<f32>() < r * r {
+                        return p;
+                    }
+                }
+            };
+            let mut particles = Vec::new();
+            for _ in 0..100 {
+                let pos = sphere(5.0);
+                let vel = sphere(8.0);
+                particles.push(GpuParticle::new(pos, vel, 1.0, 0.0, ParticleKind::Hydrogen));
+            }
+            for _ in 0..dark {
+                let pos = sphere(5.0);
+                let vel = sphere(6.0);
+                particles.push(GpuParticle::new(pos, vel, 2.5, 0.0, ParticleKind::DarkMatter));
+            }
+            let config = SimConfig {
+                gravity_budget_ms: f32::INFINITY,
+                ..SimConfig::default()
+            };
+            let mut universe = UniverseState::new(config, particles);
+            universe.age = 5.0;
+            universe.phase = UniversePhase::StellarEra;
+            universe.particles_active = true;
+            for _ in 0..150 {
+                universe.tick(0.1);
+            }
+            let gravity_strength = universe.config.gravity_scale * 0.5;
+            let dynamics =
+                stats::cluster_dynamics(&universe.particles, [0.0; 3], 10.0, gravity_strength);
+            (dynamics, universe.mass_grid.dark_fraction())
+        };
+
+        let (bare, no_dark) = run(0);
+        let (haloed, dark_share) = run(200);
+        assert_eq!(no_dark, 0.0);
+        assert!(dark_share > 0.8, "dark share {dark_share}");
+        assert!(
+            haloed.with_dark.bound > 2 * bare.with_dark.bound,
+            "{} bound with a halo vs {} without",
+            haloed.with_dark.bound,
+            bare.with_dark.bound
+        );
+        // The halo is what binds them: by their own mass alone fewer would be
+        assert!(haloed.without_dark.bound < haloed.with_dark.bound);
+        assert!(haloed.with_dark.dispersion > 0.0);
+    }
+
+    #[test]
+    fn displacement_does_not_depend_on_gravity_interval() {
+        let config = SimConfig {
+            particle_count: 200,
+            gravity_scale: 0.0,
+            ..SimConfig::default()
+        };
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+        let run = |budget_ms: f32| {
+            let mut universe = UniverseState::new(
+                SimConfig {
+                    gravity_budget_ms: budget_ms,
+                    ..config.clone()
+                },
+                particles.clone(),
+            );
+            universe.particles_active = true;
+            // First step after 3 frames, then every `interval` frames: 243 covers both
+            for _ in 0..243 {
+                universe.tick(0.01);
+            }
+            (universe.gravity_throttle.interval, universe.particles)
+        };
+
+        // Unlimited budget: a step every frame; no budget: the longest interval
+        let (every_frame, fine) = run(f32::INFINITY);
+        let (sparse, coarse) = run(0.0);
+        assert_eq!(every_frame, 1);
+        assert_eq!(sparse, throttle::MAX_INTERVAL);
+
+        let displacement = |moved: &[GpuParticle]| -> f32 {
+            moved
+                .iter()
+                .zip(&particles)
+                .map(|(a, b)| {
+                    let d = [
+                        a.position[0] - b.position[0],
+                        a.position[1] - b.position[1],
+                        a.position[2] - b.position[2],
+                    ];
+                    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+                })
+                .sum()
+        };
+        let (fine, coarse) = (displacement(&fine), displacement(&coarse));
+        assert!(fine > 0.0);
+        assert!(
+            (fine - coarse).abs() / fine < 0.01,
+            "every frame {fine} vs every {sparse} frames {coarse}"
+        );
+    }
+
+    #[test]
+    fn particle_steps_count_only_landed_steps() {
+        let config = SimConfig {
+            particle_count: 100,
+            gravity_budget_ms: f32::INFINITY,
+            ..SimConfig::default()
+        };
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(config.seed);
+        let particles = particle::generate_big_bang(&config, &mut rng);
+        let mut universe = UniverseState::new(config, particles);
+        universe.particles_active = true;
+
+        universe.paused = true;
+        for _ in 0..10 {
+            universe.tick(0.01);
+        }
+        assert_eq!(universe.particle_steps, 0);
+
+        universe.paused = false;
+        let before = universe.particles.clone();
+        for _ in 0..10 {
+            universe.tick(0.01);
+        }
+        assert!(universe.particle_steps > 0 && universe.particle_steps <= 10);
+        assert_ne!(universe.particles[0].position, before[0].position);
+    }
+}
@@ -0,0 +1,418 @@
+//! Differences between two snapshots of the same universe (same seed): what a stretch of
+//! evolution changed in its regions, life and global state.
+
+use matrix_core::UniversePhase;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::UniverseSnapshot;
+
+/// Composition fractions closer than this count as unchanged
+const COMPOSITION_EPSILON: f64 = 1e-6;
+/// Regions and life planets listed one by one in the text summary
+const LISTED: usize = 10;
+
+/// A value in the old and the new snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Change<T> {
+    pub old: T,
+    pub new: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    pub fn new(old: T, new: T) -> Self {
+        Self { old, new }
+    }
+
+    pub fn changed(&self) -> bool {
+        self.old != self.new
+    }
+}
+
+impl Change<f64> {
+    pub fn delta(&self) -> f64 {
+        self.new - self.old
+    }
+}
+
+/// A region present in both snapshots whose stars, composition or life changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegionDiff {
+    pub region_id: u64,
+    pub star_count: Change<u64>,
+    /// [H, He, metals] fractions
+    pub composition: Change<[f64; 3]>,
+    pub has_life: Change<bool>,
+    pub has_civilization: Change<bool>,
+}
+
+impl RegionDiff {
+    pub fn star_delta(&self) -> i64 {
+        self.star_count.new as i64 - self.star_count.old as i64
+    }
+}
+
+/// A life-bearing planet that appeared or disappeared between the snapshots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LifeChange {
+    pub planet_id: u64,
+    pub description: String,
+}
+
+/// Everything that differs between two snapshots of one universe; `Display` prints a
+/// readable summary, `Serialize` gives the same as JSON
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotDiff {
+    pub seed: u64,
+    /// Universe age (Gyr)
+    pub age: Change<f64>,
+    pub cycle: Change<u32>,
+    pub phase: Change<UniversePhase>,
+    /// Background temperature (K)
+    pub temperature: Change<f64>,
+    pub total_entropy: Change<f64>,
+    pub particle_count: Change<usize>,
+    pub civilization_count: Change<u32>,
+    /// Total of all regions
+    pub star_count: Change<u64>,
+    /// Mean [H, He, metals] fractions over all regions
+    pub mean_composition: Change<[f64; 3]>,
+    /// Changed regions, most stars gained or lost first
+    pub regions: Vec<RegionDiff>,
+    /// Region ids only in the new / only in the old snapshot
+    pub regions_added: Vec<u64>,
+    pub regions_removed: Vec<u64>,
+    /// Life planets only in the new / only in the old snapshot, by id
+    pub life_gained: Vec<LifeChange>,
+    pub life_lost: Vec<LifeChange>,
+}
+
+impl SnapshotDiff {
+    /// Nothing but the save time differs
+    pub fn is_empty(&self) -> bool {
+        !self.age.changed()
+            && !self.cycle.changed()
+            && !self.phase.changed()
+            && !self.temperature.changed()
+            && !self.total_entropy.changed()
+            && !self.particle_count.changed()
+            && !self.civilization_count.changed()
+            && self.regions.is_empty()
+            && self.regions_added.is_empty()
+            && self.regions_removed.is_empty()
+            && self.life_gained.is_empty()
+            && self.life_lost.is_empty()
+    }
+}
+
+/// The snapshots are of different universes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedMismatch {
+    pub old: u64,
+    pub new: u64,
+}
+
+impl fmt::Display for SeedMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Snapshots are of different universes (seed {} and {})",
+            self.old, self.new
+        )
+    }
+}
+
+impl std::error::Error for SeedMismatch {}
+
+/// Stars of the grid's cubes (their children hold the same ones)
+fn total_stars(snapshot: &UniverseSnapshot) -> u64 {
+    snapshot.regions.iter().filter(|r| r.parent.is_none()).map(|r| r.star_count).sum()
+}
+
+fn mean_composition(snapshot: &UniverseSnapshot) -> [f64; 3] {
+    let n = snapshot.regions.len().max(1) as f64;
+    snapshot.regions.iter().fold([0.0; 3], |sum, r| {
+        std::array::from_fn(|i| sum[i] + r.composition[i] / n)
+    })
+}
+
+/// What changed from `old` to `new`, regions and life planets matched by id.
+/// Fails if they aren't of the same universe.
+pub fn diff_snapshots(
+    old: &UniverseSnapshot,
+    new: &UniverseSnapshot,
+) -> Result<SnapshotDiff, SeedMismatch> {
+    if old.config.seed != new.config.seed {
+        return Err(SeedMismatch {
+            old: old.config.seed,
+            new: new.config.seed,
+        });
+    }
+
+    let old_regions: BTreeMap<u64, _> = old.regions.iter().map(|r| (r.id, r)).collect();
+    let new_regions: BTreeMap<u64, _> = new.regions.iter().map(|r| (r.id, r)).collect();
+    let mut regions: Vec<RegionDiff> = old_regions
+        .iter()
+        .filter_map(|(id, a)| {
+            let b = new_regions.get(id)?;
+            let diff = RegionDiff {
+                region_id: *id,
+                star_count: Change::new(a.star_count, b.star_count),
+                composition: Change::new(a.composition, b.composition),
+                has_life: Change::new(a.has_life, b.has_life),
+                has_civilization: Change::new(a.has_civilization, b.has_civilization),
+            };
+            let composition_moved = (0..3)
+                .any(|i| (a.composition[i] - b.composition[i]).abs() > COMPOSITION_EPSILON);
+            (diff.star_count.changed()
+                || composition_moved
+                || diff.has_life.changed()
+                || diff.has_civilization.changed())
+            .then_some(diff)
+        })
+        .collect();
+    regions.sort_by_key(|r| std::cmp::Reverse(r.star_delta().unsigned_abs()));
+
+    let old_life: BTreeMap<u64, &String> = old.life_planets.iter().map(|(id, d)| (*id, d)).collect();
+    let new_life: BTreeMap<u64, &String> = new.life_planets.iter().map(|(id, d)| (*id, d)).collect();
+    let only_in = |a: &BTreeMap<u64, &String>, b: &BTreeMap<u64, &String>| -> Vec<LifeChange> {
+        a.iter()
+            .filter(|(id, _)| !b.contains_key(id))
+            .map(|(id, description)| LifeChange {
+                planet_id: *id,
+                description: description.to_string(),
+            })
+            .collect()
+    };
+    let region_ids = |regions: &BTreeMap<u64, _>| regions.keys().copied().collect::<BTreeSet<u64>>();
+    let (old_ids, new_ids) = (region_ids(&old_regions), region_ids(&new_regions));
+
+    Ok(SnapshotDiff {
+        seed: new.config.seed,
+        age: Change::new(old.age, new.age),
+        cycle: Change::new(old.cycle, new.cycle),
+        phase: Change::new(old.phase, new.phase),
+        temperature: Change::new(old.temperature, new.temperature),
+        total_entropy: Change::new(old.total_entropy, new.total_entropy),
+        particle_count: Change::new(old.particles.len(), new.particles.len()),
+        civilization_count: Change::new(old.civilization_count, new.civilization_count),
+        star_count: Change::new(total_stars(old), total_stars(new)),
+        mean_composition: Change::new(mean_composition(old), mean_composition(new)),
+        regions,
+        regions_added: new_ids.difference(&old_ids).copied().collect(),
+        regions_removed: old_ids.difference(&new_ids).copied().collect(),
+        life_gained: only_in(&new_life, &old_life),
+        life_lost: only_in(&old_life, &new_life),
+    })
+}
+
+/// Signed difference with its sign always shown
+fn signed(delta: i64) -> String {
+    if delta >= 0 { format!("+{delta}") } else { delta.to_string() }
+}
+
+fn percentages(c: &[f64; 3]) -> String {
+    format!("{:.2}/{:.2}/{:.2}%", c[0] * 100.0, c[1] * 100.0, c[2] * 100.0)
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Seed {}: age {:.3} -> {:.3} Gyr ({:+.3}), cycle {} -> {}",
+            self.seed,
+            self.age.old,
+            self.age.new,
+            self.age.delta(),
+            self.cycle.old,
+            self.cycle.new
+        )?;
+        if self.phase.changed() {
+            writeln!(f, "Phase: {:?} -> {:?}", self.phase.old, self.phase.new)?;
+        } else {
+            writeln!(f, "Phase: {:?}", self.phase.new)?;
+        }
+        writeln!(
+            f,
+            "Temperature: {:.3} -> {:.3} K ({:+.3})",
+            self.temperature.old,
+            self.temperature.new,
+            self.temperature.delta()
+        )?;
+        writeln!(
+            f,
+            "Entropy: {:.4e} -> {:.4e} ({:+.3e})",
+            self.total_entropy.old,
+            self.total_entropy.new,
+            self.total_entropy.delta()
+        )?;
+        writeln!(
+            f,
+            "Particles: {} -> {} ({})",
+            self.particle_count.old,
+            self.particle_count.new,
+            signed(self.particle_count.new as i64 - self.particle_count.old as i64)
+        )?;
+        writeln!(
+            f,
+            "Civilizations: {} -> {} ({})",
+            self.civilization_count.old,
+            self.civilization_count.new,
+            signed(self.civilization_count.new as i64 - self.civilization_count.old as i64)
+        )?;
+        writeln!(
+            f,
+            "Stars: {:.3e} -> {:.3e} ({})",
+            self.star_count.old as f64,
+            self.star_count.new as f64,
+            signed(self.star_count.new as i64 - self.star_count.old as i64)
+        )?;
+        writeln!(
+            f,
+            "Mean composition H/He/metals: {} -> {}",
+            percentages(&self.mean_composition.old),
+            percentages(&self.mean_composition.new)
+        )?;
+
+        writeln!(f, "Regions changed: {}", self.regions.len())?;
+        for region in self.regions.iter().take(LISTED) {
+            write!(
+                f,
+                "  region {}: stars {}",
+                region.region_id,
+                signed(region.star_delta())
+            )?;
+            if region.composition.changed() {
+                write!(f, ", metals {:.3}%", region.composition.new[2] * 100.0)?;
+            }
+            if region.has_life.changed() {
+                write!(f, ", {}", if region.has_life.new { "life found" } else { "life lost" })?;
+            }
+            if region.has_civilization.changed() {
+                let civilization = if region.has_civilization.new {
+                    "civilization known"
+                } else {
+                    "civilization gone"
+                };
+                write!(f, ", {civilization}")?;
+            }
+            writeln!(f)?;
+        }
+        if self.regions.len() > LISTED {
+            writeln!(f, "  ... and {} more", self.regions.len() - LISTED)?;
+        }
+        if !self.regions_added.is_empty() || !self.regions_removed.is_empty() {
+            writeln!(
+                f,
+                "Regions added: {}, removed: {}",
+                self.regions_added.len(),
+                self.regions_removed.len()
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Life planets: {} new, {} gone",
+            self.life_gained.len(),
+            self.life_lost.len()
+        )?;
+        for (sign, list) in [("+", &self.life_gained), ("-", &self.life_lost)] {
+            for life in list.iter().take(LISTED) {
+                writeln!(f, "  {sign} planet {}: {}", life.planet_id, life.description)?;
+            }
+            if list.len() > LISTED {
+                writeln!(f, "  {sign} ... and {} more", list.len() - LISTED)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_core::SerializedParticle;
+    use matrix_physics::{particle, procgen};
+
+    #[test]
+    fn test_diff_detects_every_category() {
+        let mut old = crate::tests::sample_snapshot();
+        old.regions = procgen::generate_regions(&old.config, 4.2);
+        old.particles = particle::generate_region_particles(
+            &old.regions[0],
+            &procgen::generate_galaxies(&old.regions[0]),
+            4.2,
+        )
+        .iter()
+        .take(50)
+        .map(SerializedParticle::from)
+        .collect();
+        assert!(diff_snapshots(&old, &old).unwrap().is_empty());
+
+        let mut new = old.clone();
+        new.age = 6.0;
+        new.cycle = 3;
+        new.phase = UniversePhase::BiologicalEra;
+        new.temperature = 2.5;
+        new.total_entropy = 2000.0;
+        new.particles.truncate(40);
+        new.civilization_count = 3;
+        new.regions[3].star_count += 5_000;
+        new.regions[5].composition[2] += 0.01;
+        new.regions[8].has_life = true;
+        new.regions[8].has_civilization = true;
+        let removed = new.regions.pop().unwrap().id;
+        new.life_planets = vec![(77, "new life".to_string())];
+
+        let diff = diff_snapshots(&old, &new).unwrap();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.seed, old.config.seed);
+        assert!((diff.age.delta() - 1.8).abs() < 1e-9);
+        assert_eq!(diff.cycle, Change::new(2, 3));
+        assert_eq!(diff.phase.new, UniversePhase::BiologicalEra);
+        assert!(diff.temperature.delta() < 0.0 && diff.total_entropy.delta() > 0.0);
+        assert_eq!(diff.particle_count, Change::new(50, 40));
+        assert_eq!(diff.civilization_count, Change::new(1, 3));
+        assert!(diff.mean_composition.changed());
+
+        // Regions by id: the removed one isn't a change, the star gain sorts first
+        assert_eq!(diff.regions.len(), 3);
+        assert_eq!(diff.regions[0].region_id, old.regions[3].id);
+        assert_eq!(diff.regions[0].star_delta(), 5_000);
+        let region = |i: usize| diff.regions.iter().find(|r| r.region_id == old.regions[i].id).unwrap();
+        assert!(region(5).composition.changed() && !region(5).star_count.changed());
+        assert!(region(8).has_life.new && region(8).has_civilization.new);
+        assert_eq!(diff.regions_removed, vec![removed]);
+        assert!(diff.regions_added.is_empty());
+
+        assert_eq!(diff.life_gained.len(), 1);
+        assert_eq!(diff.life_gained[0].planet_id, 77);
+        assert_eq!(diff.life_lost[0].planet_id, 42);
+
+        let text = diff.to_string();
+        for expected in [
+            "StellarEra -> BiologicalEra",
+            "Particles: 50 -> 40 (-10)",
+            "Civilizations: 1 -> 3 (+2)",
+            "stars +5000",
+            "life found, civilization known",
+            "+ planet 77: new life",
+            "- planet 42: test life",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} in\n{text}");
+        }
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["civilization_count"]["new"], 3);
+
+        // Another universe can't be compared
+        new.config.seed += 1;
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            Err(SeedMismatch {
+                old: old.config.seed,
+                new: old.config.seed + 1,
+            })
+        );
+    }
+}
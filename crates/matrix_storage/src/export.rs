@@ -0,0 +1,112 @@
+//! Export of discovered life and photo metadata to formats readable by external tools.
+
+use matrix_core::{LifeDiscovery, Planet, SpectralClass};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::SnapshotError;
+
+/// Output format for `export_life_catalogue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Array of full records (genome included as a nested object)
+    Json,
+    /// One row per discovery, genome flattened to its main traits
+    Csv,
+}
+
+impl ExportFormat {
+    /// Pick a format from a file extension (`.csv` → CSV, anything else → JSON)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "planet_id,star_id,region_id,seed,planet_type,surface_temp,discovery_age,\
+complexity,species_count,has_technology,substrate,structure,size_log,cognition,collective,description";
+
+/// Write a life catalogue to `path`, creating parent directories
+pub fn export_life_catalogue(
+    discoveries: &[LifeDiscovery],
+    path: &Path,
+    format: ExportFormat,
+) -> Result<(), SnapshotError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(discoveries)
+            .map_err(|e| SnapshotError::Serialize(e.to_string()))?,
+        ExportFormat::Csv => catalogue_csv(discoveries),
+    };
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Sidecar JSON written next to a photo-mode screenshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoMetadata {
+    pub seed: u64,
+    /// Universe age (Gyr)
+    pub age: f64,
+    pub cycle: u32,
+    pub camera_position: [f32; 3],
+    /// Camera yaw, pitch and roll (radians)
+    pub camera_rotation: [f32; 3],
+    pub region_id: Option<u64>,
+    /// Selected planet, if any, with its star's spectral class
+    pub planet: Option<Planet>,
+    pub star_class: Option<SpectralClass>,
+}
+
+/// Write photo metadata to `path` as pretty JSON, creating parent directories
+pub fn export_photo_metadata(meta: &PhotoMetadata, path: &Path) -> Result<(), SnapshotError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data =
+        serde_json::to_string_pretty(meta).map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
+fn catalogue_csv(discoveries: &[LifeDiscovery]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for d in discoveries {
+        let g = &d.genome;
+        out.push_str(&format!(
+            "{},{},{},{},{:?},{:.1},{:.3},{:.2},{},{},{},{},{:.2},{:.3},{:.3},{}\n",
+            d.planet_id,
+            d.star_id,
+            d.region_id,
+            d.seed,
+            d.planet_type,
+            d.surface_temp,
+            d.discovery_age,
+            d.complexity,
+            d.species_count,
+            d.has_technology,
+            g.substrate,
+            g.structure,
+            g.size_log,
+            g.cognition,
+            g.collective,
+            csv_field(&g.describe()),
+        ));
+    }
+    out
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
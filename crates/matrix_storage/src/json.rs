@@ -0,0 +1,96 @@
+//! Snapshots as JSON, for inspecting or hand-editing a universe with external tools.
+//! The document wraps the snapshot with the format version it was written at; it is not
+//! migrated, so only JSON from this version loads. Floats read back bit for bit (serde_json's
+//! `float_roundtrip`), so a JSON detour doesn't move a single star.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::{SNAPSHOT_VERSION, SnapshotError, UniverseSnapshot};
+
+/// `format` of every JSON snapshot (the binary magic, as text)
+const JSON_FORMAT: &str = "MTRXSNAP";
+
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    format: &'a str,
+    version: u32,
+    snapshot: &'a UniverseSnapshot,
+}
+
+/// Read first, so a document from another version fails on its version rather than on
+/// whichever field changed since
+#[derive(Deserialize)]
+struct JsonHeader {
+    format: String,
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonBody {
+    snapshot: UniverseSnapshot,
+}
+
+/// Save a snapshot as JSON, indented if `pretty`, creating parent directories
+pub fn save_snapshot_json(
+    snapshot: &UniverseSnapshot,
+    path: &Path,
+    pretty: bool,
+) -> Result<(), SnapshotError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    let document = JsonDocument {
+        format: JSON_FORMAT,
+        version: SNAPSHOT_VERSION,
+        snapshot,
+    };
+    if pretty {
+        serde_json::to_writer_pretty(&mut writer, &document)
+    } else {
+        serde_json::to_writer(&mut writer, &document)
+    }
+    .map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Load a snapshot written by `save_snapshot_json` (or edited since)
+pub fn load_snapshot_json(path: &Path) -> Result<UniverseSnapshot, SnapshotError> {
+    decode_snapshot_json(&fs::read(path)?)
+}
+
+/// Decode a JSON snapshot from bytes
+pub fn decode_snapshot_json(data: &[u8]) -> Result<UniverseSnapshot, SnapshotError> {
+    let corrupt = |e: serde_json::Error| SnapshotError::Corrupt(format!("JSON: {e}"));
+    let header: JsonHeader = serde_json::from_slice(data).map_err(corrupt)?;
+    if header.format != JSON_FORMAT {
+        return Err(SnapshotError::Corrupt(format!("unknown format {:?}", header.format)));
+    }
+    match header.version {
+        SNAPSHOT_VERSION => {
+            let body: JsonBody = serde_json::from_slice(data).map_err(corrupt)?;
+            Ok(body.snapshot)
+        }
+        v if v > SNAPSHOT_VERSION => Err(SnapshotError::VersionTooNew {
+            found: v,
+            supported: SNAPSHOT_VERSION,
+        }),
+        v => Err(SnapshotError::Corrupt(format!(
+            "JSON snapshot version {v} can't be migrated, export it again from its save"
+        ))),
+    }
+}
+
+/// Whether `path` holds a JSON snapshot: by its `.json` / `.bin` extension, else by whether
+/// it starts with `{` (binary saves start with the magic, legacy ones with a float)
+pub fn is_json_snapshot(path: &Path, data: &[u8]) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => true,
+        Some(ext) if ext.eq_ignore_ascii_case("bin") => false,
+        _ => data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{'),
+    }
+}
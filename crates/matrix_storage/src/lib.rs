@@ -1,10 +1,60 @@
-use matrix_core::{Region, SerializedParticle, SimConfig, Star, UniversePhase};
+pub mod diff;
+pub mod export;
+pub mod json;
+pub mod migration;
+mod packed;
+
+pub use diff::{SeedMismatch, SnapshotDiff, diff_snapshots};
+pub use export::{ExportFormat, PhotoMetadata, export_life_catalogue, export_photo_metadata};
+pub use json::{load_snapshot_json, save_snapshot_json};
+
+use matrix_core::{
+    Bestiary, Bookmarks, Civilization, CustomNames, FeedEntry, Galaxy, HistorySample,
+    LifeDiscovery, Region, SerializedParticle, SimConfig, Star, SurfaceMemory, UniversePhase,
+};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of every versioned snapshot file
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"MTRXSNAP";
+
+/// Current snapshot format version.
+/// Bump this whenever `UniverseSnapshot` (or anything it embeds) changes layout,
+/// and add a migration from the previous version in `migration.rs`.
+///
+/// v3+ layout: magic, version, `u32` header length, bincode `SnapshotHeader`, bincode payload.
+/// v4 added `discoveries` to the payload, v5 added `SimConfig::integrator`,
+/// v6 added `Star::remnant`, v7 added `civilizations`.
+/// v8 added a `Compression` byte between the metadata header and the payload
+/// (the uncompressed payload layout is the same as v7).
+/// v9 stores particles as `SerializedParticle` with separate mass/charge and an enum `kind`,
+/// v10 added `galaxies`, v11 added `events`, v12 added `surface_memory`,
+/// v13 added `bestiary`, v14 added `names`.
+/// v15 packs the payload (see `packed.rs`): particles as quantized columns, the loaded
+/// stars as a procgen overlay regenerated from the region seed and `stars_age`.
+/// v16 added `Region::central_black_hole`, v17 added moons (`Planet::moons`),
+/// `Star::asteroid_belt` and `Star::comets`, v18 added `SimConfig::heat_death_age`,
+/// v19 added `Region::has_civilization`.
+/// v20 folds the region id into star and planet ids (same layout, see `migrate_v19_to_v20`).
+/// v21 seeds every star, planet and biosphere from its own stream (same layout): star
+/// overlays from before no longer match procgen.
+/// v22 added `Planet::tidally_locked` (a close-in planet's `surface_temp` becomes its
+/// terminator's), v23 added `bookmarks`, v24 added `Planet::rings`, v25 added `history`.
+/// v26 added `SimConfig::region_layout` and `Region::parent`.
+pub const SNAPSHOT_VERSION: u32 = 26;
+
+/// Header size: magic + u32 version
+const HEADER_LEN: usize = SNAPSHOT_MAGIC.len() + 4;
+
+/// Upper bound on the metadata header (guards against reading garbage lengths)
+const MAX_META_LEN: usize = 64 * 1024;
 
 /// Complete universe snapshot for save/load
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct UniverseSnapshot {
     pub age: f64,
     pub scale_factor: f64,
@@ -17,26 +67,1369 @@ pub struct UniverseSnapshot {
     pub regions: Vec<Region>,
     pub current_region_id: Option<u64>,
     pub loaded_stars: Vec<Star>,
+    /// Universe age the loaded stars were generated at (0 for saves older than v15)
+    pub stars_age: f64,
+    /// Galaxies of the loaded region (empty for saves older than v10)
+    pub galaxies: Vec<Galaxy>,
     pub life_planets: Vec<(u64, String)>,
     pub civilization_count: u32,
     pub time_scale: f64,
     pub paused: bool,
+    /// Wall-clock time the snapshot was written (unix seconds, 0 if unknown)
+    pub saved_at: u64,
+    /// Structured life records (empty for saves older than v4)
+    pub discoveries: Vec<LifeDiscovery>,
+    /// Tracked civilizations, including extinct ones (empty for saves older than v7)
+    pub civilizations: Vec<Civilization>,
+    /// Event feed history, oldest first (empty for saves older than v11)
+    pub events: Vec<FeedEntry>,
+    /// Per-planet surface state (empty for saves older than v12)
+    pub surface_memory: SurfaceMemory,
+    /// Scanned surface species (empty for saves older than v13)
+    pub bestiary: Bestiary,
+    /// Player renames (empty for saves older than v14)
+    pub names: CustomNames,
+    /// Camera bookmarks (empty for saves older than v23)
+    pub bookmarks: Bookmarks,
+    /// Sampled metrics over time, oldest first (empty for saves older than v25)
+    pub history: Vec<HistorySample>,
+}
+
+/// Small metadata block written ahead of the payload (v3+),
+/// so save lists can be built without decoding the particle vector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotHeader {
+    age: f64,
+    cycle: u32,
+    seed: u64,
+    civilization_count: u32,
+    saved_at: u64,
+}
+
+impl SnapshotHeader {
+    fn from_snapshot(snapshot: &UniverseSnapshot) -> Self {
+        Self {
+            age: snapshot.age,
+            cycle: snapshot.cycle,
+            seed: snapshot.config.seed,
+            civilization_count: snapshot.civilization_count,
+            saved_at: snapshot.saved_at,
+        }
+    }
+}
+
+/// How the payload of a v8+ snapshot is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Plain bincode
+    #[default]
+    None,
+    /// LZ4 frame around the bincode payload
+    Lz4,
+}
+
+impl Compression {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Lz4 => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, SnapshotError> {
+        match byte {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Lz4),
+            b => Err(SnapshotError::Corrupt(format!("unknown compression {b}"))),
+        }
+    }
+}
+
+/// Lightweight description of a save file for the load picker
+#[derive(Debug, Clone)]
+pub struct SnapshotMeta {
+    pub path: PathBuf,
+    /// Slot name (file stem)
+    pub name: String,
+    /// On-disk format version (1 for legacy unversioned files)
+    pub version: u32,
+    pub age: f64,
+    pub cycle: u32,
+    pub seed: u64,
+    pub civilization_count: u32,
+    /// Unix seconds; falls back to file mtime for saves that predate `saved_at`
+    pub saved_at: u64,
 }
 
-/// Save a snapshot to disk as bincode
-pub fn save_snapshot(snapshot: &UniverseSnapshot, path: &Path) -> Result<(), String> {
+/// Errors from reading or writing snapshots
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Filesystem error (missing file, permissions, ...)
+    Io(std::io::Error),
+    /// File could not be decoded as any known snapshot format
+    Corrupt(String),
+    /// File was written by a newer build than this one
+    VersionTooNew { found: u32, supported: u32 },
+    /// Snapshot could not be encoded
+    Serialize(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Corrupt(msg) => write!(f, "Corrupt snapshot: {msg}"),
+            Self::VersionTooNew { found, supported } => write!(
+                f,
+                "Snapshot version {found} is newer than supported version {supported}"
+            ),
+            Self::Serialize(msg) => write!(f, "Serialize error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Save a snapshot to disk: magic + format version + metadata header + compression + payload.
+/// The payload is streamed through a `BufWriter` rather than built in memory first.
+pub fn save_snapshot(
+    snapshot: &UniverseSnapshot,
+    path: &Path,
+    compression: Compression,
+) -> Result<(), SnapshotError> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {e}"))?;
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    write_snapshot(snapshot, &mut writer, compression)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Load a snapshot from disk, migrating older formats to the current one.
+/// JSON snapshots (see `json.rs`) are recognised and loaded too.
+pub fn load_snapshot(path: &Path) -> Result<UniverseSnapshot, SnapshotError> {
+    let data = fs::read(path)?;
+    if json::is_json_snapshot(path, &data) {
+        return json::decode_snapshot_json(&data);
     }
-    let data = bincode::serialize(snapshot).map_err(|e| format!("Serialize error: {e}"))?;
-    fs::write(path, data).map_err(|e| format!("Write error: {e}"))?;
+    decode_snapshot(&data)
+}
+
+/// Delete a save file
+pub fn delete_snapshot(path: &Path) -> Result<(), SnapshotError> {
+    fs::remove_file(path)?;
     Ok(())
 }
 
-/// Load a snapshot from disk
-pub fn load_snapshot(path: &Path) -> Result<UniverseSnapshot, String> {
-    let data = fs::read(path).map_err(|e| format!("Read error: {e}"))?;
-    let snapshot =
-        bincode::deserialize(&data).map_err(|e| format!("Deserialize error: {e}"))?;
-    Ok(snapshot)
+/// List all `.bin` snapshots in a directory, newest first.
+/// Only the metadata header is read for current-format files; unreadable files are skipped.
+pub fn list_snapshots(dir: &Path) -> Vec<SnapshotMeta> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut metas: Vec<SnapshotMeta> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bin"))
+        .filter_map(|p| read_snapshot_meta(&p).ok())
+        .collect();
+
+    metas.sort_by_key(|m| std::cmp::Reverse(m.saved_at));
+    metas
+}
+
+/// Read the metadata of a single snapshot.
+/// Cheap for v3+ files; older files are fully decoded once to recover it.
+pub fn read_snapshot_meta(path: &Path) -> Result<SnapshotMeta, SnapshotError> {
+    let mut file = fs::File::open(path)?;
+    let mut prefix = [0u8; HEADER_LEN + 4];
+    let header = match file.read_exact(&mut prefix) {
+        Ok(()) => match read_version(&prefix) {
+            Some(version) if version >= 3 => {
+                let meta_len = read_meta_len(&prefix[HEADER_LEN..])?;
+                let mut meta_bytes = vec![0u8; meta_len];
+                file.read_exact(&mut meta_bytes)?;
+                let header: SnapshotHeader = bincode::deserialize(&meta_bytes)
+                    .map_err(|e| SnapshotError::Corrupt(format!("header: {e}")))?;
+                Some((version, header))
+            }
+            _ => None,
+        },
+        Err(_) => None,
+    };
+
+    let (version, mut header) = match header {
+        Some(found) => found,
+        None => {
+            let data = fs::read(path)?;
+            let version = read_version(&data).unwrap_or(1);
+            let snapshot = decode_snapshot(&data)?;
+            (version, SnapshotHeader::from_snapshot(&snapshot))
+        }
+    };
+
+    if header.saved_at == 0 {
+        header.saved_at = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+    }
+
+    Ok(SnapshotMeta {
+        path: path.to_path_buf(),
+        name: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        version,
+        age: header.age,
+        cycle: header.cycle,
+        seed: header.seed,
+        civilization_count: header.civilization_count,
+        saved_at: header.saved_at,
+    })
+}
+
+/// Encode a snapshot into the versioned on-disk representation
+pub fn encode_snapshot(
+    snapshot: &UniverseSnapshot,
+    compression: Compression,
+) -> Result<Vec<u8>, SnapshotError> {
+    let mut data = Vec::new();
+    write_snapshot(snapshot, &mut data, compression)?;
+    Ok(data)
+}
+
+/// Write the versioned representation of `snapshot` to `writer`
+pub fn write_snapshot<W: Write>(
+    snapshot: &UniverseSnapshot,
+    mut writer: W,
+    compression: Compression,
+) -> Result<(), SnapshotError> {
+    let meta = bincode::serialize(&SnapshotHeader::from_snapshot(snapshot))
+        .map_err(|e| SnapshotError::Serialize(e.to_string()))?;
+    writer.write_all(SNAPSHOT_MAGIC)?;
+    writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+    writer.write_all(&(meta.len() as u32).to_le_bytes())?;
+    writer.write_all(&meta)?;
+    writer.write_all(&[compression.to_byte()])?;
+
+    let payload = packed::PackedSnapshot::pack(snapshot);
+    let serialize_err = |e: bincode::Error| SnapshotError::Serialize(e.to_string());
+    match compression {
+        Compression::None => bincode::serialize_into(writer, &payload).map_err(serialize_err),
+        Compression::Lz4 => {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
+            bincode::serialize_into(&mut encoder, &payload).map_err(serialize_err)?;
+            encoder
+                .finish()
+                .map_err(|e| SnapshotError::Serialize(format!("lz4: {e}")))?;
+            Ok(())
+        }
+    }
+}
+
+/// Decode a snapshot from bytes: decode the payload at the version it was written at, then
+/// migrate it one version at a time up to the current one.
+/// Files without the magic header are treated as the legacy unversioned (v1) format.
+pub fn decode_snapshot(data: &[u8]) -> Result<UniverseSnapshot, SnapshotError> {
+    let (mut version, mut snapshot) = match read_version(data) {
+        Some(v) if v > SNAPSHOT_VERSION => {
+            return Err(SnapshotError::VersionTooNew {
+                found: v,
+                supported: SNAPSHOT_VERSION,
+            });
+        }
+        Some(v) => (v, migration::Migrating::decode(v, &data[HEADER_LEN..])?),
+        None => (1, migration::Migrating::V1(migration::decode_legacy(data)?)),
+    };
+    while version < SNAPSHOT_VERSION {
+        snapshot = snapshot.migrate()?;
+        version += 1;
+    }
+    match snapshot {
+        migration::Migrating::Current(snapshot) => Ok(snapshot),
+        // Only now: unpacking regenerates the stars from the (current) regions
+        migration::Migrating::Packed(packed) => packed.unpack(),
+        _ => unreachable!("every older version migrates up to the current one"),
+    }
+}
+
+/// Decode a v8+ payload: compression byte, then the (possibly compressed) bincode snapshot
+fn decode_compressed<T: DeserializeOwned>(data: &[u8]) -> Result<T, SnapshotError> {
+    let (&byte, payload) = data
+        .split_first()
+        .ok_or_else(|| SnapshotError::Corrupt("missing compression byte".to_string()))?;
+    let corrupt = |e: bincode::Error| SnapshotError::Corrupt(format!("payload: {e}"));
+    match Compression::from_byte(byte)? {
+        Compression::None => bincode::deserialize(payload).map_err(corrupt),
+        Compression::Lz4 => {
+            bincode::deserialize_from(lz4_flex::frame::FrameDecoder::new(payload)).map_err(corrupt)
+        }
+    }
+}
+
+/// Read the format version from a versioned header (None if the magic is missing)
+fn read_version(data: &[u8]) -> Option<u32> {
+    if data.len() < HEADER_LEN || !data.starts_with(SNAPSHOT_MAGIC) {
+        return None;
+    }
+    let bytes: [u8; 4] = data[SNAPSHOT_MAGIC.len()..HEADER_LEN].try_into().ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// Read the `u32` metadata header length that follows the version (v3+)
+fn read_meta_len(data: &[u8]) -> Result<usize, SnapshotError> {
+    let bytes: [u8; 4] = data
+        .get(..4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| SnapshotError::Corrupt("truncated header".to_string()))?;
+    let len = u32::from_le_bytes(bytes) as usize;
+    if len > MAX_META_LEN {
+        return Err(SnapshotError::Corrupt(format!("header length {len} too large")));
+    }
+    Ok(len)
+}
+
+/// Skip the metadata header (v3+) and return the payload that follows it
+fn skip_meta(data: &[u8]) -> Result<&[u8], SnapshotError> {
+    let meta_len = read_meta_len(data)?;
+    data.get(4 + meta_len..)
+        .ok_or_else(|| SnapshotError::Corrupt("truncated header".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use migration::{SimConfigV1, SnapshotV1, SnapshotV2};
+
+    pub(crate) fn sample_snapshot() -> UniverseSnapshot {
+        UniverseSnapshot {
+            age: 4.2,
+            scale_factor: 0.6,
+            phase: UniversePhase::StellarEra,
+            cycle: 2,
+            temperature: 3.1,
+            total_entropy: 1234.5,
+            config: SimConfig {
+                heat_death_age: 45.0,
+                ..SimConfig::default()
+            },
+            particles: Vec::new(),
+            regions: Vec::new(),
+            current_region_id: Some(7),
+            loaded_stars: vec![Star {
+                id: 3,
+                position: [1.0, 2.0, 3.0],
+                velocity: [0.0; 3],
+                mass: 1.4,
+                luminosity: 1e-4,
+                surface_temp: 600_000.0,
+                spectral_class: matrix_core::SpectralClass::O,
+                age: 1.0,
+                remnant: Some(matrix_core::StellarRemnant::NeutronStar),
+                planets: vec![matrix_core::Planet {
+                    id: 3_001,
+                    orbital_radius: 5.2,
+                    orbital_period: 11.9,
+                    orbital_angle: 0.4,
+                    mass: 318.0,
+                    radius: 11.2,
+                    surface_temp: 110.0,
+                    tidally_locked: false,
+                    has_water: false,
+                    has_atmosphere: true,
+                    atmosphere: matrix_core::AtmosphereType::Hydrogen,
+                    planet_type: matrix_core::PlanetType::GasGiant,
+                    life: None,
+                    moons: Vec::new(),
+                    rings: Some(matrix_core::RingSystem {
+                        inner_radius: 1.3,
+                        outer_radius: 2.4,
+                        density: 0.6,
+                        tilt: 0.1,
+                    }),
+                }],
+                asteroid_belt: None,
+                comets: Vec::new(),
+            }],
+            stars_age: 4.0,
+            galaxies: vec![Galaxy {
+                id: 0,
+                position: [1.0, 2.0, 3.0],
+                kind: matrix_core::GalaxyKind::Spiral,
+                radius: 6.0,
+                axis: [0.0, 1.0, 0.0],
+                arms: 2,
+                star_budget: 1_000_000,
+                first_star: 0,
+                star_count: 1,
+                seed: 9,
+            }],
+            life_planets: vec![(42, "test life".to_string())],
+            civilization_count: 1,
+            time_scale: 100.0,
+            paused: true,
+            saved_at: 1_700_000_000,
+            discoveries: Vec::new(),
+            civilizations: vec![Civilization::new(7, 3, 3_001, 4.0)],
+            events: vec![FeedEntry {
+                age_gyr: 4.1,
+                event: matrix_core::SimEvent::PhaseTransition {
+                    from: UniversePhase::CosmicDawn,
+                    to: UniversePhase::StellarEra,
+                },
+            }],
+            surface_memory: {
+                let mut memory = SurfaceMemory::default();
+                let planet = memory.entry(3_001);
+                planet.position = [12.0, 3.5, -40.0];
+                planet.visits = 2;
+                planet.eaten = vec![1];
+                memory
+            },
+            bestiary: {
+                let mut bestiary = Bestiary::default();
+                bestiary.record(matrix_core::BestiaryEntry {
+                    name: "Vorth khelar".to_string(),
+                    planet_id: 3_001,
+                    planet_type: matrix_core::PlanetType::Rocky,
+                    species: 1,
+                    traits: matrix_core::IndividualTraits {
+                        size_log: 0.2,
+                        hue_shift: 12.0,
+                        elongation: 1.1,
+                        motility: 5,
+                        role: Some(matrix_core::CreatureRole::Grazer),
+                    },
+                    genome: matrix_core::Genome::primordial(),
+                    scanned_age: 4.1,
+                });
+                bestiary
+            },
+            names: {
+                let mut names = CustomNames::default();
+                names.set(matrix_core::NameKind::Planet, 3_001, "Aurelia");
+                names
+            },
+            bookmarks: {
+                let mut bookmarks = Bookmarks::default();
+                bookmarks.set(
+                    2,
+                    matrix_core::Bookmark {
+                        position: [123.4, -56.7, 890.1],
+                        yaw: 1.2,
+                        pitch: -0.3,
+                        zoom_level: 3,
+                        region_id: Some(0),
+                        planet: Some((3, 3_001)),
+                        label: "Aurelia".to_string(),
+                    },
+                );
+                bookmarks
+            },
+            history: (0..5)
+                .map(|i| HistorySample {
+                    cycle: 2,
+                    age: i as f64,
+                    temperature: 3.1 + 100.0 / (i as f64 + 1.0),
+                    total_entropy: 1000.0 + i as f64 * 50.0,
+                    scale_factor: 0.2 + i as f64 * 0.1,
+                    particles: 500 - i * 10,
+                    stars: i * 1_000_000,
+                    life: i as u32,
+                    civilizations: i as u32 / 2,
+                })
+                .collect(),
+        }
+    }
+
+    fn sample_v1() -> SnapshotV1 {
+        SnapshotV1 {
+            age: 9.0,
+            scale_factor: 0.8,
+            phase: UniversePhase::StellarEra,
+            cycle: 1,
+            temperature: 2.9,
+            total_entropy: 10.0,
+            config: SimConfigV1 {
+                particle_count: 500,
+                seed: 7,
+                big_bang_velocity: 5.0,
+                gravity_scale: 1.0,
+                dark_matter_fraction: 0.27,
+            },
+            particles: Vec::new(),
+            regions: Vec::new(),
+            current_region_id: None,
+            loaded_stars: Vec::new(),
+            life_planets: vec![(3, "old life".to_string())],
+            civilization_count: 0,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+
+    fn sample_v2() -> SnapshotV2 {
+        let mut v2 = migration::migrate_v1_to_v2(sample_v1());
+        v2.saved_at = 500;
+        v2
+    }
+
+    #[test]
+    fn test_round_trip_current() {
+        let path = std::env::temp_dir().join(format!("matrix_{}.bin", uuid::Uuid::new_v4()));
+        save_snapshot(&sample_snapshot(), &path, Compression::None).unwrap();
+        let loaded = load_snapshot(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.age, 4.2);
+        assert_eq!(loaded.cycle, 2);
+        assert_eq!(loaded.current_region_id, Some(7));
+        assert_eq!(loaded.life_planets[0].0, 42);
+        assert_eq!(loaded.saved_at, 1_700_000_000);
+        assert_eq!(
+            loaded.loaded_stars[0].remnant,
+            Some(matrix_core::StellarRemnant::NeutronStar)
+        );
+        assert_eq!(loaded.civilizations[0].planet_id, 3_001);
+        assert_eq!(loaded.galaxies[0].kind, matrix_core::GalaxyKind::Spiral);
+        assert!(loaded.galaxies[0].contains_star(0));
+        assert_eq!(loaded.events, sample_snapshot().events);
+        assert_eq!(loaded.surface_memory, sample_snapshot().surface_memory);
+        assert!(loaded.surface_memory.is_visited(3_001));
+        assert_eq!(loaded.bestiary, sample_snapshot().bestiary);
+        assert!(loaded.bestiary.contains(3_001, 1));
+        assert_eq!(loaded.names.get(matrix_core::NameKind::Planet, 3_001), Some("Aurelia"));
+        assert_eq!(loaded.bookmarks, sample_snapshot().bookmarks);
+        assert_eq!(loaded.history, sample_snapshot().history);
+        assert_eq!(loaded.config.heat_death_age, 45.0);
+    }
+
+    #[test]
+    fn test_round_trip_lz4() {
+        let mut snapshot = sample_snapshot();
+        snapshot.particles = (0..2_000)
+            .map(|i| SerializedParticle {
+                position: [i as f32, 0.0, 0.0],
+                velocity: [0.0; 3],
+                mass: 1.0,
+                charge: 0.0,
+                kind: matrix_core::ParticleKind::Hydrogen,
+                flags: 1,
+                temperature: 3.0,
+            })
+            .collect();
+        let plain = encode_snapshot(&snapshot, Compression::None).unwrap();
+        let packed = encode_snapshot(&snapshot, Compression::Lz4).unwrap();
+        assert!(packed.len() < plain.len());
+
+        let loaded = decode_snapshot(&packed).unwrap();
+        assert_eq!(loaded.particles.len(), 2_000);
+        assert_eq!(loaded.particles[1_999].position[0], 1_999.0);
+        assert_eq!(loaded.civilizations[0].planet_id, 3_001);
+    }
+
+    #[test]
+    fn test_list_snapshots_reads_meta() {
+        let dir = std::env::temp_dir().join(format!("matrix_saves_{}", uuid::Uuid::new_v4()));
+        let mut older = sample_snapshot();
+        older.saved_at = 100;
+        older.config.seed = 11;
+        save_snapshot(&older, &dir.join("older.bin"), Compression::None).unwrap();
+        save_snapshot(&sample_snapshot(), &dir.join("newer.bin"), Compression::Lz4).unwrap();
+        fs::write(dir.join("legacy.bin"), bincode::serialize(&sample_v1()).unwrap()).unwrap();
+        fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        let metas = list_snapshots(&dir);
+        assert_eq!(metas.len(), 3);
+        assert_eq!(metas[0].name, "legacy"); // saved_at falls back to mtime (now)
+        assert_eq!(metas[0].version, 1);
+        assert_eq!(metas[1].name, "newer");
+        assert_eq!(metas[1].civilization_count, 1);
+        assert_eq!(metas[2].seed, 11);
+        assert_eq!(metas[2].version, SNAPSHOT_VERSION);
+
+        delete_snapshot(&metas[2].path).unwrap();
+        assert_eq!(list_snapshots(&dir).len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_v2_without_header() {
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&bincode::serialize(&sample_v2()).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.cycle, 1);
+        assert_eq!(loaded.saved_at, 500);
+        assert!(loaded.discoveries.is_empty());
+        assert!(loaded.civilizations.is_empty());
+        assert!(loaded.galaxies.is_empty());
+    }
+
+    #[test]
+    fn test_load_v3_with_header() {
+        let v2 = sample_v2();
+        let meta = bincode::serialize(&SnapshotHeader {
+            age: v2.age,
+            cycle: v2.cycle,
+            seed: v2.config.seed,
+            civilization_count: v2.civilization_count,
+            saved_at: v2.saved_at,
+        })
+        .unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.extend_from_slice(&bincode::serialize(&v2).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.age, 9.0);
+        assert_eq!(loaded.life_planets[0].1, "old life");
+        assert_eq!(loaded.config.integrator, matrix_core::Integrator::Euler);
+    }
+
+    #[test]
+    fn test_load_v8_unpacks_particles() {
+        let mut v8 = migration::migrate_v6_to_v7(migration::migrate_v5_to_v6(
+            migration::migrate_v4_to_v5(migration::migrate_v3_to_v4(sample_v2())),
+        ));
+        v8.particles = vec![
+            migration::SerializedParticleV1 {
+                position: [1.0, 2.0, 3.0, 4.0],
+                velocity: [0.5, 0.0, 0.0, -1.0],
+                kind: matrix_core::ParticleKind::Helium as u32,
+                flags: 3,
+                temperature: 1e4,
+            },
+            migration::SerializedParticleV1 {
+                position: [0.0; 4],
+                velocity: [0.0; 4],
+                kind: 999,
+                flags: 1,
+                temperature: 0.0,
+            },
+        ];
+        let meta = bincode::serialize(&SnapshotHeader {
+            age: v8.age,
+            cycle: v8.cycle,
+            seed: v8.config.seed,
+            civilization_count: v8.civilization_count,
+            saved_at: v8.saved_at,
+        })
+        .unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&8u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v8).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        let p = &loaded.particles[0];
+        assert_eq!(p.position, [1.0, 2.0, 3.0]);
+        assert_eq!(p.mass, 4.0);
+        assert_eq!(p.charge, -1.0);
+        assert_eq!(p.kind, matrix_core::ParticleKind::Helium);
+        assert_eq!(p.flags, 3);
+        assert_eq!(loaded.particles[1].kind, matrix_core::serialized::FALLBACK_KIND);
+        assert!(loaded.events.is_empty());
+        assert!(loaded.surface_memory.is_empty());
+        assert!(loaded.bestiary.is_empty());
+        assert!(loaded.names.is_empty());
+    }
+
+    #[test]
+    fn test_load_v15_places_central_black_holes() {
+        let mut snapshot = sample_snapshot();
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 4.2);
+        let v15 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(|r| migration::RegionV1 {
+                id: r.id,
+                center: r.center,
+                size: r.size,
+                density: r.density,
+                temperature: r.temperature,
+                composition: r.composition,
+                dark_matter: r.dark_matter,
+                star_count: r.star_count,
+                planet_count: r.planet_count,
+                has_life: r.has_life,
+                detail: r.detail,
+                seed: r.seed,
+            })
+            .map_stars(star_v6, planet_v1)
+            .map_config(config_v5)
+            .map_bookmarks(|_| ())
+            .map_history(|_| ());
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&15u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v15).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.regions.len(), snapshot.regions.len());
+        assert!(loaded.regions.iter().any(|r| r.central_black_hole.is_some()));
+        for (region, saved) in loaded.regions.iter().zip(&snapshot.regions) {
+            assert_eq!(region.central_black_hole, saved.central_black_hole);
+        }
+        assert_eq!(loaded.names, snapshot.names);
+        // Older configs get the default heat death age
+        assert_eq!(loaded.config.heat_death_age, SimConfig::default().heat_death_age);
+        assert_eq!(loaded.config.seed, snapshot.config.seed);
+    }
+
+    /// The config as v5–v17 saved it
+    fn region_v16(r: Region) -> migration::RegionV16 {
+        migration::RegionV16 {
+            id: r.id,
+            center: r.center,
+            size: r.size,
+            density: r.density,
+            temperature: r.temperature,
+            composition: r.composition,
+            dark_matter: r.dark_matter,
+            star_count: r.star_count,
+            planet_count: r.planet_count,
+            has_life: r.has_life,
+            central_black_hole: r.central_black_hole,
+            detail: r.detail,
+            seed: r.seed,
+        }
+    }
+
+    /// A region as v19–v25 saved it
+    fn region_v19(r: Region) -> migration::RegionV19 {
+        migration::RegionV19 {
+            id: r.id,
+            center: r.center,
+            size: r.size,
+            density: r.density,
+            temperature: r.temperature,
+            composition: r.composition,
+            dark_matter: r.dark_matter,
+            star_count: r.star_count,
+            planet_count: r.planet_count,
+            has_life: r.has_life,
+            has_civilization: r.has_civilization,
+            central_black_hole: r.central_black_hole,
+            detail: r.detail,
+            seed: r.seed,
+        }
+    }
+
+    /// The config as v18–v25 saved it
+    fn config_v18(c: SimConfig) -> migration::SimConfigV18 {
+        migration::SimConfigV18 {
+            particle_count: c.particle_count,
+            seed: c.seed,
+            big_bang_velocity: c.big_bang_velocity,
+            gravity_scale: c.gravity_scale,
+            dark_matter_fraction: c.dark_matter_fraction,
+            integrator: c.integrator,
+            heat_death_age: c.heat_death_age,
+        }
+    }
+
+    fn config_v5(c: SimConfig) -> migration::SimConfigV5 {
+        migration::SimConfigV5 {
+            particle_count: c.particle_count,
+            seed: c.seed,
+            big_bang_velocity: c.big_bang_velocity,
+            gravity_scale: c.gravity_scale,
+            dark_matter_fraction: c.dark_matter_fraction,
+            integrator: c.integrator,
+        }
+    }
+
+    /// A star as v6–v16 saved it
+    fn star_v6(s: Star) -> migration::StarV6 {
+        migration::StarV6 {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets: s.planets.into_iter().map(planet_v1).collect(),
+        }
+    }
+
+    /// A planet as v1–v16 saved it
+    fn planet_v1(p: matrix_core::Planet) -> migration::PlanetV1 {
+        migration::PlanetV1 {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+        }
+    }
+
+    /// A star as v17–v21 saved it
+    fn star_v17(s: Star) -> migration::StarV17 {
+        migration::StarV17 {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets: s.planets.into_iter().map(planet_v17).collect(),
+            asteroid_belt: s.asteroid_belt,
+            comets: s.comets,
+        }
+    }
+
+    /// A planet as v17–v21 saved it
+    fn planet_v17(p: matrix_core::Planet) -> migration::PlanetV17 {
+        migration::PlanetV17 {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+            moons: p.moons.into_iter().map(planet_v17).collect(),
+        }
+    }
+
+    /// A star as v22–v23 saved it
+    fn star_v22(s: Star) -> migration::StarV22 {
+        migration::StarV22 {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets: s.planets.into_iter().map(planet_v22).collect(),
+            asteroid_belt: s.asteroid_belt,
+            comets: s.comets,
+        }
+    }
+
+    /// A planet as v22–v23 saved it
+    fn planet_v22(p: matrix_core::Planet) -> migration::PlanetV22 {
+        migration::PlanetV22 {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            tidally_locked: p.tidally_locked,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+            moons: p.moons.into_iter().map(planet_v22).collect(),
+        }
+    }
+
+    #[test]
+    fn test_load_v21_locks_close_in_planets() {
+        let mut snapshot = sample_snapshot();
+        let region = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0).remove(0);
+        snapshot.regions = vec![region.clone()];
+        snapshot.current_region_id = Some(region.id);
+        // Stored whole (age unknown), so the planets go through the migration
+        snapshot.stars_age = 0.0;
+        snapshot.loaded_stars = matrix_physics::procgen::generate_stellar_detail(&region, 13.0);
+        let v21 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(region_v19)
+            .map_stars(star_v17, planet_v17)
+            .map_config(config_v18)
+            .map_bookmarks(|_| ())
+            .map_history(|_| ());
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&21u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v21).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.loaded_stars.len(), snapshot.loaded_stars.len());
+        let planets = |stars: &[Star]| stars.iter().flat_map(|s| s.planets.clone()).collect::<Vec<_>>();
+        let (saved, migrated) = (planets(&snapshot.loaded_stars), planets(&loaded.loaded_stars));
+        assert!(saved.iter().any(|p| p.tidally_locked));
+        for (saved, migrated) in saved.iter().zip(&migrated) {
+            assert_eq!(migrated.tidally_locked, saved.tidally_locked);
+            if saved.tidally_locked {
+                // Saved as a turning planet: its temperature moves to the terminator
+                let terminator = matrix_physics::cosmology::locked_temperatures(
+                    saved.surface_temp,
+                    saved.has_atmosphere,
+                )
+                .terminator;
+                assert!((migrated.surface_temp - terminator).abs() < 1e-9);
+            } else {
+                assert_eq!(migrated.surface_temp, saved.surface_temp);
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_v22_has_no_bookmarks() {
+        let snapshot = sample_snapshot();
+        assert!(!snapshot.bookmarks.is_empty());
+        let v22 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(region_v19)
+            .map_stars(star_v22, planet_v22)
+            .map_config(config_v18)
+            .map_bookmarks(|_| ())
+            .map_history(|_| ());
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&22u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v22).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert!(loaded.bookmarks.is_empty());
+        assert_eq!(loaded.names, snapshot.names);
+        assert_eq!(loaded.loaded_stars.len(), snapshot.loaded_stars.len());
+    }
+
+    #[test]
+    fn test_load_v23_rings() {
+        let mut snapshot = sample_snapshot();
+        let region = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0)
+            .into_iter()
+            .max_by(|a, b| a.density.total_cmp(&b.density))
+            .unwrap();
+        snapshot.regions = vec![region.clone()];
+        snapshot.current_region_id = Some(region.id);
+        snapshot.stars_age = 12.0;
+        snapshot.loaded_stars = matrix_physics::stellar::generate_region_stars(&region, 12.0).0;
+        let rings = |stars: &[Star]| {
+            stars
+                .iter()
+                .flat_map(|s| &s.planets)
+                .map(|p| p.rings)
+                .collect::<Vec<_>>()
+        };
+        assert!(rings(&snapshot.loaded_stars).iter().any(Option::is_some));
+        let encode_v23 = |snapshot: &UniverseSnapshot| {
+            let v23 = packed::PackedSnapshot::pack(snapshot)
+                .map_regions(region_v19)
+                .map_stars(star_v22, planet_v22)
+                .map_config(config_v18)
+                .map_history(|_| ());
+            let meta = bincode::serialize(&SnapshotHeader::from_snapshot(snapshot)).unwrap();
+            let mut data = SNAPSHOT_MAGIC.to_vec();
+            data.extend_from_slice(&23u32.to_le_bytes());
+            data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+            data.extend_from_slice(&meta);
+            data.push(Compression::None.to_byte());
+            data.extend_from_slice(&bincode::serialize(&v23).unwrap());
+            data
+        };
+
+        // Regenerated from procgen: the rings are back
+        let loaded = decode_snapshot(&encode_v23(&snapshot)).unwrap();
+        assert_eq!(rings(&loaded.loaded_stars), rings(&snapshot.loaded_stars));
+        assert_eq!(loaded.bookmarks, snapshot.bookmarks);
+
+        // Stored whole: no rings until the region is generated again
+        snapshot.stars_age = 0.0;
+        let loaded = decode_snapshot(&encode_v23(&snapshot)).unwrap();
+        assert_eq!(loaded.loaded_stars.len(), snapshot.loaded_stars.len());
+        assert!(rings(&loaded.loaded_stars).iter().all(Option::is_none));
+
+        // The current version keeps them
+        let data = encode_snapshot(&snapshot, Compression::None).unwrap();
+        assert_eq!(decode_snapshot(&data).unwrap().loaded_stars, snapshot.loaded_stars);
+    }
+
+    #[test]
+    fn test_load_v24_has_no_history() {
+        let snapshot = sample_snapshot();
+        assert!(!snapshot.history.is_empty());
+        let v24 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(region_v19)
+            .map_config(config_v18)
+            .map_history(|_| ());
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&24u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v24).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert!(loaded.history.is_empty());
+        assert_eq!(loaded.bookmarks, snapshot.bookmarks);
+    }
+
+    #[test]
+    fn test_load_v25_is_on_the_default_grid() {
+        let mut snapshot = sample_snapshot();
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0);
+        let v25 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(region_v19)
+            .map_config(config_v18);
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&25u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v25).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.config.region_layout, matrix_core::RegionLayout::default());
+        assert_eq!(loaded.config.heat_death_age, snapshot.config.heat_death_age);
+        assert_eq!(loaded.history, snapshot.history);
+        assert_eq!(loaded.regions.len(), 512);
+        assert!(loaded.regions.iter().all(|r| r.parent.is_none()));
+        // The same regions a new universe of that seed generates
+        let fresh = matrix_physics::procgen::generate_regions(&loaded.config, 13.0);
+        assert!(loaded.regions.iter().zip(&fresh).all(|(a, b)| a.id == b.id && a.center == b.center));
+    }
+
+    #[test]
+    fn test_region_layout_round_trips() {
+        let mut snapshot = sample_snapshot();
+        snapshot.config.region_layout = matrix_core::RegionLayout {
+            grid: [12, 2, 12],
+            size: 40.0,
+            subdivide_above: Some(1.5),
+        };
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0);
+        assert!(snapshot.regions.iter().any(|r| r.parent.is_some()));
+
+        let data = encode_snapshot(&snapshot, Compression::Lz4).unwrap();
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.config.region_layout, snapshot.config.region_layout);
+        assert_eq!(loaded.regions.len(), snapshot.regions.len());
+        for (region, saved) in loaded.regions.iter().zip(&snapshot.regions) {
+            assert_eq!((region.id, region.parent, region.size), (saved.id, saved.parent, saved.size));
+        }
+    }
+
+    #[test]
+    fn test_load_v18_leaves_civilization_flags_to_the_loader() {
+        let mut snapshot = sample_snapshot();
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0);
+        snapshot.regions[3].has_life = true;
+        snapshot.regions[3].has_civilization = true;
+        let v18 = packed::PackedSnapshot::pack(&snapshot)
+            .map_regions(region_v16)
+            .map_stars(star_v17, planet_v17)
+            .map_config(config_v18)
+            .map_bookmarks(|_| ())
+            .map_history(|_| ());
+        let meta = bincode::serialize(&SnapshotHeader::from_snapshot(&snapshot)).unwrap();
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&18u32.to_le_bytes());
+        data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+        data.extend_from_slice(&meta);
+        data.push(Compression::None.to_byte());
+        data.extend_from_slice(&bincode::serialize(&v18).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert!(loaded.regions[3].has_life);
+        assert!(loaded.regions.iter().all(|r| !r.has_civilization));
+        // The current version keeps both flags
+        let data = encode_snapshot(&snapshot, Compression::None).unwrap();
+        let loaded = decode_snapshot(&data).unwrap();
+        assert!(loaded.regions[3].has_life && loaded.regions[3].has_civilization);
+    }
+
+    #[test]
+    fn test_load_v16_small_bodies() {
+        let mut snapshot = sample_snapshot();
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0);
+        let region = snapshot
+            .regions
+            .iter()
+            .find(|r| r.star_count >= 200)
+            .unwrap()
+            .clone();
+        snapshot.current_region_id = Some(region.id);
+        snapshot.stars_age = 12.0;
+        snapshot.loaded_stars = matrix_physics::stellar::generate_region_stars(&region, 12.0).0;
+        let encode_v16 = |snapshot: &UniverseSnapshot| {
+            let v16 = packed::PackedSnapshot::pack(snapshot)
+                .map_regions(region_v16)
+                .map_stars(star_v6, planet_v1)
+                .map_config(config_v5);
+            let meta = bincode::serialize(&SnapshotHeader::from_snapshot(snapshot)).unwrap();
+            let mut data = SNAPSHOT_MAGIC.to_vec();
+            data.extend_from_slice(&16u32.to_le_bytes());
+            data.extend_from_slice(&(meta.len() as u32).to_le_bytes());
+            data.extend_from_slice(&meta);
+            data.push(Compression::None.to_byte());
+            data.extend_from_slice(&bincode::serialize(&v16).unwrap());
+            data
+        };
+
+        // Stars stored as a procgen overlay can't be rebuilt with pre-v20 ids: the region
+        // is unloaded and regenerates, moons, belts and comets included, on the next visit
+        let loaded = decode_snapshot(&encode_v16(&snapshot)).unwrap();
+        assert!(loaded.loaded_stars.is_empty());
+        assert_eq!((loaded.current_region_id, loaded.stars_age), (None, 0.0));
+
+        // Stars stored whole gain them on the next reload
+        snapshot.stars_age = 0.0;
+        let loaded = decode_snapshot(&encode_v16(&snapshot)).unwrap();
+        assert_eq!(loaded.loaded_stars.len(), snapshot.loaded_stars.len());
+        for (star, saved) in loaded.loaded_stars.iter().zip(&snapshot.loaded_stars) {
+            assert_eq!(star.planets.len(), saved.planets.len());
+            assert!(star.comets.is_empty() && star.asteroid_belt.is_none());
+            assert!(star.planets.iter().all(|p| p.moons.is_empty()));
+        }
+    }
+
+    /// Star overlays (`packed.rs`) are only valid while procgen regenerates the same stars:
+    /// if this fails, procgen changed, and `SNAPSHOT_VERSION` needs a bump that drops them
+    #[test]
+    fn test_procgen_golden_values() {
+        use matrix_core::PlanetType::*;
+
+        let config = SimConfig { seed: 42, ..SimConfig::default() };
+        let region = matrix_physics::procgen::generate_regions(&config, 13.8).remove(0);
+        let stars = matrix_physics::procgen::generate_stellar_detail(&region, 13.8);
+        assert_eq!(stars.len(), 1000);
+
+        assert!((stars[0].mass - 0.821_187_711_780_806_7).abs() < 1e-12);
+        assert!((stars[1].mass - 0.529_027_161_542_165_2).abs() < 1e-12);
+        let types = |i: usize| stars[i].planets.iter().map(|p| p.planet_type).collect::<Vec<_>>();
+        assert_eq!(types(0), [Lava, IceGiant, GasGiant]);
+        assert_eq!(types(2), [Lava, GasGiant, Rocky, Rocky, Frozen, Frozen, GasGiant]);
+
+        let life: Vec<u64> = stars
+            .iter()
+            .flat_map(|s| &s.planets)
+            .filter(|p| p.life.is_some())
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(life[..4], [165_002, 286_001, 306_001, 383_004]);
+        assert_eq!(life.len(), 9);
+        let locked = stars.iter().flat_map(|s| &s.planets).filter(|p| p.tidally_locked).count();
+        assert_eq!(locked, 1405);
+    }
+
+    #[test]
+    fn test_load_versioned_v1() {
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&bincode::serialize(&sample_v1()).unwrap());
+
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.age, 9.0);
+        assert_eq!(loaded.config.seed, 7);
+        assert_eq!(loaded.config.particle_count, 500);
+        assert_eq!(loaded.life_planets[0].1, "old life");
+        assert_eq!(loaded.saved_at, 0);
+    }
+
+    #[test]
+    fn test_load_legacy_unversioned() {
+        let data = bincode::serialize(&sample_v1()).unwrap();
+        let loaded = decode_snapshot(&data).unwrap();
+        assert_eq!(loaded.config.seed, 7);
+    }
+
+    #[test]
+    fn test_reject_future_version() {
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        data.extend_from_slice(&[0u8; 16]);
+
+        match decode_snapshot(&data) {
+            Err(SnapshotError::VersionTooNew { found, supported }) => {
+                assert_eq!(found, SNAPSHOT_VERSION + 1);
+                assert_eq!(supported, SNAPSHOT_VERSION);
+            }
+            other => panic!("expected VersionTooNew, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_export_life_catalogue() {
+        let discovery = LifeDiscovery {
+            planet_id: 5,
+            star_id: 2,
+            region_id: 9,
+            seed: 42,
+            genome: matrix_core::Genome::primordial(),
+            complexity: 1.5,
+            species_count: 30,
+            has_technology: false,
+            planet_type: matrix_core::PlanetType::Ocean,
+            surface_temp: 290.0,
+            discovery_age: 11.2,
+        };
+        let dir = std::env::temp_dir().join(format!("matrix_export_{}", uuid::Uuid::new_v4()));
+
+        let json_path = dir.join("life.json");
+        export_life_catalogue(std::slice::from_ref(&discovery), &json_path, ExportFormat::Json).unwrap();
+        let parsed: Vec<LifeDiscovery> =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(parsed[0].planet_id, 5);
+        assert_eq!(parsed[0].region_id, 9);
+
+        let csv_path = dir.join("life.csv");
+        assert_eq!(ExportFormat::from_path(&csv_path), ExportFormat::Csv);
+        export_life_catalogue(&[discovery], &csv_path, ExportFormat::Csv).unwrap();
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("planet_id,star_id,region_id"));
+        assert!(lines.next().unwrap().starts_with("5,2,9,42,Ocean,"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_photo_metadata() {
+        let meta = PhotoMetadata {
+            seed: 42,
+            age: 9.5,
+            cycle: 2,
+            camera_position: [1.0, -2.0, 3.0],
+            camera_rotation: [0.5, 0.1, 0.25],
+            region_id: Some(17),
+            planet: None,
+            star_class: None,
+        };
+        let dir = std::env::temp_dir().join(format!("matrix_photo_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("photo.json");
+        export_photo_metadata(&meta, &path).unwrap();
+        let parsed: PhotoMetadata = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed.seed, 42);
+        assert_eq!(parsed.region_id, Some(17));
+        assert_eq!(parsed.camera_rotation, [0.5, 0.1, 0.25]);
+        assert!(parsed.planet.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Compare two snapshots field by field, as JSON values (so by number, not formatting)
+    fn assert_same_fields(a: &UniverseSnapshot, b: &UniverseSnapshot) {
+        let (serde_json::Value::Object(a), serde_json::Value::Object(b)) =
+            (serde_json::to_value(a).unwrap(), serde_json::to_value(b).unwrap())
+        else {
+            panic!("snapshots serialize to objects");
+        };
+        assert_eq!(a.len(), b.len());
+        for (field, value) in &a {
+            assert!(b.get(field) == Some(value), "{field} differs");
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut snapshot = sample_snapshot();
+        snapshot.regions = matrix_physics::procgen::generate_regions(&snapshot.config, 13.0);
+        snapshot.loaded_stars =
+            matrix_physics::procgen::generate_stellar_detail(&snapshot.regions[0], 13.0);
+        snapshot.stars_age = 13.0;
+        snapshot.particles = (0..500)
+            .map(|i| SerializedParticle {
+                position: [i as f32 * 0.37, -1e-7 * i as f32, 3.0e5],
+                velocity: [0.1, -0.0, i as f32 / 3.0],
+                mass: 1.0 + i as f32 * 1e-3,
+                charge: if i % 2 == 0 { -1.0 } else { 1.0 },
+                kind: matrix_core::ParticleKind::ALL[i % matrix_core::ParticleKind::ALL.len()],
+                flags: 1,
+                temperature: 2.725,
+            })
+            .collect();
+        let dir = std::env::temp_dir().join(format!("matrix_json_{}", uuid::Uuid::new_v4()));
+        save_snapshot(&snapshot, &dir.join("a.bin"), Compression::Lz4).unwrap();
+
+        let loaded = load_snapshot(&dir.join("a.bin")).unwrap();
+        save_snapshot_json(&loaded, &dir.join("a.json"), true).unwrap();
+        let from_json = load_snapshot(&dir.join("a.json")).unwrap();
+        save_snapshot(&from_json, &dir.join("b.bin"), Compression::Lz4).unwrap();
+        let reloaded = load_snapshot(&dir.join("b.bin")).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(reloaded.particles.len(), 500);
+        assert!(!reloaded.loaded_stars.is_empty());
+        assert_same_fields(&loaded, &from_json);
+        assert_same_fields(&loaded, &reloaded);
+    }
+
+    #[test]
+    fn test_json_detected_without_extension() {
+        let mut snapshot = sample_snapshot();
+        snapshot.particles.clear();
+        let dir = std::env::temp_dir().join(format!("matrix_json_{}", uuid::Uuid::new_v4()));
+        save_snapshot_json(&snapshot, &dir.join("universe"), false).unwrap();
+        let text = fs::read_to_string(dir.join("universe")).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(json::is_json_snapshot(&dir.join("universe"), text.as_bytes()));
+        let legacy = bincode::serialize(&sample_v1()).unwrap();
+        assert!(!json::is_json_snapshot(&dir.join("legacy"), &legacy));
+
+        let loaded = load_snapshot(&dir.join("universe")).unwrap();
+        assert!(loaded.particles.is_empty());
+        assert_same_fields(&snapshot, &loaded);
+
+        // A hand edit from a newer build is refused by version, not by a missing field
+        let newer = text.replacen(
+            &format!("\"version\":{SNAPSHOT_VERSION}"),
+            &format!("\"version\":{}", SNAPSHOT_VERSION + 1),
+            1,
+        );
+        fs::write(dir.join("newer.json"), newer).unwrap();
+        let result = load_snapshot_json(&dir.join("newer.json"));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(matches!(result, Err(SnapshotError::VersionTooNew { .. })));
+    }
+
+    #[test]
+    fn test_reject_garbage() {
+        assert!(matches!(
+            decode_snapshot(b"not a snapshot"),
+            Err(SnapshotError::Corrupt(_))
+        ));
+    }
 }
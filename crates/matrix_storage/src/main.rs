@@ -0,0 +1,139 @@
+//! Convert saves between the binary format and JSON, to inspect or hand-edit a universe:
+//! `matrix_storage --export-json in.bin out.json` writes pretty JSON (`--compact` for one
+//! line, `--no-particles` to leave out the particle list), and
+//! `matrix_storage --import-json in.json out.bin` writes it back as an LZ4 save the game loads.
+//! Exits with 1 if the input can't be read or the output can't be written.
+
+use std::path::PathBuf;
+
+use matrix_storage::Compression;
+
+const USAGE: &str = "usage: matrix_storage --export-json <in.bin> <out.json> [--compact] [--no-particles]\n       \
+                     matrix_storage --import-json <in.json> <out.bin>\n\n\
+                     --import-json refuses JSON from an older format version: export it again from its save";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Export,
+    Import,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    direction: Direction,
+    input: PathBuf,
+    output: PathBuf,
+    compact: bool,
+    no_particles: bool,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut direction = None;
+        let mut paths = Vec::new();
+        let mut compact = false;
+        let mut no_particles = false;
+        for arg in args {
+            match arg.as_str() {
+                "--export-json" | "--import-json" => {
+                    if direction.is_some() {
+                        return Err(USAGE.to_string());
+                    }
+                    direction = Some(if arg == "--export-json" {
+                        Direction::Export
+                    } else {
+                        Direction::Import
+                    });
+                }
+                "--compact" => compact = true,
+                "--no-particles" => no_particles = true,
+                flag if flag.starts_with("--") => return Err(format!("unknown argument: {arg}")),
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+        let direction = direction.ok_or_else(|| USAGE.to_string())?;
+        if direction == Direction::Import && (compact || no_particles) {
+            return Err("--compact and --no-particles only apply to --export-json".to_string());
+        }
+        let [input, output] = <[PathBuf; 2]>::try_from(paths).map_err(|_| USAGE.to_string())?;
+        Ok(Self {
+            direction,
+            input,
+            output,
+            compact,
+            no_particles,
+        })
+    }
+}
+
+fn run(opts: &Options) -> Result<String, String> {
+    let mut snapshot = matrix_storage::load_snapshot(&opts.input)
+        .map_err(|e| format!("{}: {e}", opts.input.display()))?;
+    let written = match opts.direction {
+        Direction::Export => {
+            if opts.no_particles {
+                snapshot.particles.clear();
+            }
+            matrix_storage::save_snapshot_json(&snapshot, &opts.output, !opts.compact)
+        }
+        Direction::Import => matrix_storage::save_snapshot(&snapshot, &opts.output, Compression::Lz4),
+    };
+    written.map_err(|e| format!("{}: {e}", opts.output.display()))?;
+    Ok(format!(
+        "Wrote {} ({} regions, {} particles)",
+        opts.output.display(),
+        snapshot.regions.len(),
+        snapshot.particles.len()
+    ))
+}
+
+fn main() {
+    let opts = match Options::parse(std::env::args().skip(1)) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+    match run(&opts) {
+        Ok(text) => println!("{text}"),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arguments() {
+        let opts =
+            Options::parse(["--export-json", "a.bin", "a.json", "--no-particles"].map(String::from))
+                .unwrap();
+        assert_eq!(
+            opts,
+            Options {
+                direction: Direction::Export,
+                input: PathBuf::from("a.bin"),
+                output: PathBuf::from("a.json"),
+                compact: false,
+                no_particles: true,
+            }
+        );
+        let opts = Options::parse(["--import-json", "a.json", "b.bin"].map(String::from)).unwrap();
+        assert_eq!(opts.direction, Direction::Import);
+        assert!(Options::parse(["a.bin", "a.json"].map(String::from)).is_err());
+        assert!(Options::parse(["--export-json", "a.bin"].map(String::from)).is_err());
+        assert!(
+            Options::parse(["--export-json", "--import-json", "a", "b"].map(String::from)).is_err()
+        );
+        assert!(
+            Options::parse(["--import-json", "a.json", "b.bin", "--compact"].map(String::from))
+                .is_err()
+        );
+        assert!(Options::parse(["--export-json", "a", "b", "--frobnicate"].map(String::from)).is_err());
+    }
+}
@@ -0,0 +1,1430 @@
+//! Legacy snapshot layouts and migrations to the current `UniverseSnapshot`.
+//!
+//! Each older format keeps a frozen copy of the structs it serialized, so that
+//! later changes to `matrix_core` types don't break decoding of old saves.
+
+use std::collections::HashMap;
+
+use matrix_core::names::{PLANET_ID_STRIDE, STAR_ID_STRIDE};
+use matrix_core::serialized::FALLBACK_KIND;
+use matrix_core::{
+    AsteroidBelt, AtmosphereType, Bestiary, Biosphere, Bookmarks, Civilization, Comet, CustomNames, FeedEntry,
+    Galaxy, HistorySample, Integrator, LifeDiscovery, ParticleKind, Planet, PlanetType, Region, RegionDetail,
+    RegionLayout, SerializedParticle, SimConfig, SimEvent, SpectralClass, Star, StellarRemnant, SurfaceMemory,
+    UniversePhase,
+};
+use matrix_physics::{cosmology, procgen};
+use serde::{Deserialize, Serialize};
+
+use super::packed::PackedSnapshot;
+use super::{SNAPSHOT_VERSION, SnapshotError, UniverseSnapshot};
+
+/// `SimConfig` as it was serialized in v1–v4 snapshots (before `integrator`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimConfigV1 {
+    pub particle_count: u32,
+    pub seed: u64,
+    pub big_bang_velocity: f32,
+    pub gravity_scale: f32,
+    pub dark_matter_fraction: f32,
+}
+
+impl From<SimConfigV1> for SimConfigV5 {
+    fn from(c: SimConfigV1) -> Self {
+        Self {
+            particle_count: c.particle_count,
+            seed: c.seed,
+            big_bang_velocity: c.big_bang_velocity,
+            gravity_scale: c.gravity_scale,
+            dark_matter_fraction: c.dark_matter_fraction,
+            // Everything before v5 was integrated with Euler
+            integrator: Integrator::Euler,
+        }
+    }
+}
+
+/// `SimConfig` as it was serialized in v5–v17 snapshots (before `heat_death_age`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimConfigV5 {
+    pub particle_count: u32,
+    pub seed: u64,
+    pub big_bang_velocity: f32,
+    pub gravity_scale: f32,
+    pub dark_matter_fraction: f32,
+    pub integrator: Integrator,
+}
+
+impl From<SimConfigV5> for SimConfigV18 {
+    fn from(c: SimConfigV5) -> Self {
+        Self {
+            particle_count: c.particle_count,
+            seed: c.seed,
+            big_bang_velocity: c.big_bang_velocity,
+            gravity_scale: c.gravity_scale,
+            dark_matter_fraction: c.dark_matter_fraction,
+            integrator: c.integrator,
+            heat_death_age: SimConfig::default().heat_death_age,
+        }
+    }
+}
+
+/// `SimConfig` as it was serialized in v18–v25 snapshots (before `region_layout`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimConfigV18 {
+    pub particle_count: u32,
+    pub seed: u64,
+    pub big_bang_velocity: f32,
+    pub gravity_scale: f32,
+    pub dark_matter_fraction: f32,
+    pub integrator: Integrator,
+    pub heat_death_age: f64,
+}
+
+impl From<SimConfigV18> for SimConfig {
+    fn from(c: SimConfigV18) -> Self {
+        Self {
+            particle_count: c.particle_count,
+            seed: c.seed,
+            big_bang_velocity: c.big_bang_velocity,
+            gravity_scale: c.gravity_scale,
+            dark_matter_fraction: c.dark_matter_fraction,
+            integrator: c.integrator,
+            heat_death_age: c.heat_death_age,
+            // Every universe before v26 was generated on the default grid
+            region_layout: RegionLayout::default(),
+            ..SimConfig::default()
+        }
+    }
+}
+
+/// `SerializedParticle` as it was serialized in v1–v8 snapshots
+/// (raw GPU layout: mass in `position[3]`, charge in `velocity[3]`, `kind` as an index)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedParticleV1 {
+    pub position: [f32; 4],
+    pub velocity: [f32; 4],
+    pub kind: u32,
+    pub flags: u32,
+    pub temperature: f32,
+}
+
+impl From<SerializedParticleV1> for SerializedParticle {
+    fn from(p: SerializedParticleV1) -> Self {
+        Self {
+            position: [p.position[0], p.position[1], p.position[2]],
+            velocity: [p.velocity[0], p.velocity[1], p.velocity[2]],
+            mass: p.position[3],
+            charge: p.velocity[3],
+            kind: ParticleKind::from_u32(p.kind).unwrap_or(FALLBACK_KIND),
+            flags: p.flags,
+            temperature: p.temperature,
+        }
+    }
+}
+
+/// `Star` as it was serialized in v1–v5 snapshots (before `remnant`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarV1 {
+    pub id: u64,
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub mass: f64,
+    pub luminosity: f64,
+    pub surface_temp: f64,
+    pub spectral_class: SpectralClass,
+    pub age: f64,
+    pub planets: Vec<PlanetV1>,
+}
+
+impl From<StarV1> for StarV6 {
+    fn from(s: StarV1) -> Self {
+        Self {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            // Stellar evolution is re-applied the next time the region loads
+            remnant: None,
+            planets: s.planets,
+        }
+    }
+}
+
+/// `Star` as it was serialized in v6–v16 snapshots (before moons, belts and comets)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarV6 {
+    pub id: u64,
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub mass: f64,
+    pub luminosity: f64,
+    pub surface_temp: f64,
+    pub spectral_class: SpectralClass,
+    pub age: f64,
+    pub remnant: Option<StellarRemnant>,
+    pub planets: Vec<PlanetV1>,
+}
+
+impl From<StarV6> for Star {
+    fn from(s: StarV6) -> Self {
+        StarV22::from(StarV17::from(s)).into()
+    }
+}
+
+impl From<StarV6> for StarV17 {
+    fn from(s: StarV6) -> Self {
+        Self {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets: s.planets.into_iter().map(PlanetV17::from).collect(),
+            // Small bodies come back when the region is generated again
+            asteroid_belt: None,
+            comets: Vec::new(),
+        }
+    }
+}
+
+/// `Planet` as it was serialized in v1–v16 snapshots (before `moons`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanetV1 {
+    pub id: u64,
+    pub orbital_radius: f64,
+    pub orbital_period: f64,
+    pub orbital_angle: f64,
+    pub mass: f64,
+    pub radius: f64,
+    pub surface_temp: f64,
+    pub has_water: bool,
+    pub has_atmosphere: bool,
+    pub atmosphere: AtmosphereType,
+    pub planet_type: PlanetType,
+    pub life: Option<Biosphere>,
+}
+
+impl From<PlanetV1> for PlanetV17 {
+    fn from(p: PlanetV1) -> Self {
+        Self {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+            moons: Vec::new(),
+        }
+    }
+}
+
+/// `Star` as it was serialized in v17–v21 snapshots (before `Planet::tidally_locked`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarV17 {
+    pub id: u64,
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub mass: f64,
+    pub luminosity: f64,
+    pub surface_temp: f64,
+    pub spectral_class: SpectralClass,
+    pub age: f64,
+    pub remnant: Option<StellarRemnant>,
+    pub planets: Vec<PlanetV17>,
+    pub asteroid_belt: Option<AsteroidBelt>,
+    pub comets: Vec<Comet>,
+}
+
+impl From<StarV17> for StarV22 {
+    /// Planets close enough to lock are locked, their temperature becoming the terminator's
+    fn from(s: StarV17) -> Self {
+        let planets = s
+            .planets
+            .into_iter()
+            .map(|p| {
+                let mut planet = PlanetV22::from(p);
+                if cosmology::is_tidally_locked(s.mass, planet.orbital_radius) {
+                    planet.tidally_locked = true;
+                    planet.surface_temp =
+                        cosmology::locked_temperatures(planet.surface_temp, planet.has_atmosphere).terminator;
+                }
+                planet
+            })
+            .collect();
+        Self {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets,
+            asteroid_belt: s.asteroid_belt,
+            comets: s.comets,
+        }
+    }
+}
+
+/// `Planet` as it was serialized in v17–v21 snapshots (before `tidally_locked`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanetV17 {
+    pub id: u64,
+    pub orbital_radius: f64,
+    pub orbital_period: f64,
+    pub orbital_angle: f64,
+    pub mass: f64,
+    pub radius: f64,
+    pub surface_temp: f64,
+    pub has_water: bool,
+    pub has_atmosphere: bool,
+    pub atmosphere: AtmosphereType,
+    pub planet_type: PlanetType,
+    pub life: Option<Biosphere>,
+    pub moons: Vec<PlanetV17>,
+}
+
+/// Not locked: a planet on its own doesn't know its star (`From<StarV17>` works it out)
+impl From<PlanetV17> for PlanetV22 {
+    fn from(p: PlanetV17) -> Self {
+        Self {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            tidally_locked: false,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+            moons: p.moons.into_iter().map(PlanetV22::from).collect(),
+        }
+    }
+}
+
+/// `Star` as it was serialized in v22–v23 snapshots (before `Planet::rings`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarV22 {
+    pub id: u64,
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub mass: f64,
+    pub luminosity: f64,
+    pub surface_temp: f64,
+    pub spectral_class: SpectralClass,
+    pub age: f64,
+    pub remnant: Option<StellarRemnant>,
+    pub planets: Vec<PlanetV22>,
+    pub asteroid_belt: Option<AsteroidBelt>,
+    pub comets: Vec<Comet>,
+}
+
+impl From<StarV22> for Star {
+    fn from(s: StarV22) -> Self {
+        Self {
+            id: s.id,
+            position: s.position,
+            velocity: s.velocity,
+            mass: s.mass,
+            luminosity: s.luminosity,
+            surface_temp: s.surface_temp,
+            spectral_class: s.spectral_class,
+            age: s.age,
+            remnant: s.remnant,
+            planets: s.planets.into_iter().map(Planet::from).collect(),
+            asteroid_belt: s.asteroid_belt,
+            comets: s.comets,
+        }
+    }
+}
+
+/// `Planet` as it was serialized in v22–v23 snapshots (before `rings`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanetV22 {
+    pub id: u64,
+    pub orbital_radius: f64,
+    pub orbital_period: f64,
+    pub orbital_angle: f64,
+    pub mass: f64,
+    pub radius: f64,
+    pub surface_temp: f64,
+    pub tidally_locked: bool,
+    pub has_water: bool,
+    pub has_atmosphere: bool,
+    pub atmosphere: AtmosphereType,
+    pub planet_type: PlanetType,
+    pub life: Option<Biosphere>,
+    pub moons: Vec<PlanetV22>,
+}
+
+/// No rings: procgen draws them from their own stream, so planets regenerated from the
+/// region seed have them, and only planets saved whole lose them
+impl From<PlanetV22> for Planet {
+    fn from(p: PlanetV22) -> Self {
+        Self {
+            id: p.id,
+            orbital_radius: p.orbital_radius,
+            orbital_period: p.orbital_period,
+            orbital_angle: p.orbital_angle,
+            mass: p.mass,
+            radius: p.radius,
+            surface_temp: p.surface_temp,
+            tidally_locked: p.tidally_locked,
+            has_water: p.has_water,
+            has_atmosphere: p.has_atmosphere,
+            atmosphere: p.atmosphere,
+            planet_type: p.planet_type,
+            life: p.life,
+            moons: p.moons.into_iter().map(Planet::from).collect(),
+            rings: None,
+        }
+    }
+}
+
+/// `Region` as it was serialized in v1–v15 snapshots (before `central_black_hole`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionV1 {
+    pub id: u64,
+    pub center: [f64; 3],
+    pub size: f64,
+    pub density: f64,
+    pub temperature: f64,
+    pub composition: [f64; 3],
+    pub dark_matter: f64,
+    pub star_count: u64,
+    pub planet_count: u64,
+    pub has_life: bool,
+    pub detail: RegionDetail,
+    pub seed: u64,
+}
+
+impl From<RegionV1> for RegionV16 {
+    fn from(r: RegionV1) -> Self {
+        Self {
+            id: r.id,
+            center: r.center,
+            size: r.size,
+            density: r.density,
+            temperature: r.temperature,
+            composition: r.composition,
+            dark_matter: r.dark_matter,
+            star_count: r.star_count,
+            planet_count: r.planet_count,
+            has_life: r.has_life,
+            // Procgen places it from the seed and density alone, as for a new region
+            central_black_hole: procgen::central_black_hole(r.seed, r.density),
+            detail: r.detail,
+            seed: r.seed,
+        }
+    }
+}
+
+/// `Region` as it was serialized in v16–v18 snapshots (before `has_civilization`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionV16 {
+    pub id: u64,
+    pub center: [f64; 3],
+    pub size: f64,
+    pub density: f64,
+    pub temperature: f64,
+    pub composition: [f64; 3],
+    pub dark_matter: f64,
+    pub star_count: u64,
+    pub planet_count: u64,
+    pub has_life: bool,
+    pub central_black_hole: Option<f64>,
+    pub detail: RegionDetail,
+    pub seed: u64,
+}
+
+impl From<RegionV16> for RegionV19 {
+    fn from(r: RegionV16) -> Self {
+        Self {
+            id: r.id,
+            center: r.center,
+            size: r.size,
+            density: r.density,
+            temperature: r.temperature,
+            composition: r.composition,
+            dark_matter: r.dark_matter,
+            star_count: r.star_count,
+            planet_count: r.planet_count,
+            has_life: r.has_life,
+            // Worked out again from the saved civilizations on load
+            has_civilization: false,
+            central_black_hole: r.central_black_hole,
+            detail: r.detail,
+            seed: r.seed,
+        }
+    }
+}
+
+/// `Region` as it was serialized in v19–v25 snapshots (before `parent`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionV19 {
+    pub id: u64,
+    pub center: [f64; 3],
+    pub size: f64,
+    pub density: f64,
+    pub temperature: f64,
+    pub composition: [f64; 3],
+    pub dark_matter: f64,
+    pub star_count: u64,
+    pub planet_count: u64,
+    pub has_life: bool,
+    pub has_civilization: bool,
+    pub central_black_hole: Option<f64>,
+    pub detail: RegionDetail,
+    pub seed: u64,
+}
+
+impl From<RegionV19> for Region {
+    fn from(r: RegionV19) -> Self {
+        Self {
+            id: r.id,
+            // No region was subdivided before v26
+            parent: None,
+            center: r.center,
+            size: r.size,
+            density: r.density,
+            temperature: r.temperature,
+            composition: r.composition,
+            dark_matter: r.dark_matter,
+            star_count: r.star_count,
+            planet_count: r.planet_count,
+            has_life: r.has_life,
+            has_civilization: r.has_civilization,
+            central_black_hole: r.central_black_hole,
+            detail: r.detail,
+            seed: r.seed,
+        }
+    }
+}
+
+/// v1 snapshot: the original unversioned bincode layout (no `saved_at`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV1 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV1,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV1>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+}
+
+/// v2/v3 snapshot payload (v3 only added the metadata header in front of it)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV2 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV1,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV1>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+}
+
+/// v1 → v2: add `saved_at` (unknown for old saves)
+pub fn migrate_v1_to_v2(v1: SnapshotV1) -> SnapshotV2 {
+    SnapshotV2 {
+        age: v1.age,
+        scale_factor: v1.scale_factor,
+        phase: v1.phase,
+        cycle: v1.cycle,
+        temperature: v1.temperature,
+        total_entropy: v1.total_entropy,
+        config: v1.config,
+        particles: v1.particles,
+        regions: v1.regions,
+        current_region_id: v1.current_region_id,
+        loaded_stars: v1.loaded_stars,
+        life_planets: v1.life_planets,
+        civilization_count: v1.civilization_count,
+        time_scale: v1.time_scale,
+        paused: v1.paused,
+        saved_at: 0,
+    }
+}
+
+/// v4 snapshot payload (added `discoveries`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV4 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV1,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV1>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+}
+
+/// v3 → v4: add structured `discoveries` (not recoverable from the old description strings)
+pub fn migrate_v3_to_v4(v3: SnapshotV2) -> SnapshotV4 {
+    SnapshotV4 {
+        age: v3.age,
+        scale_factor: v3.scale_factor,
+        phase: v3.phase,
+        cycle: v3.cycle,
+        temperature: v3.temperature,
+        total_entropy: v3.total_entropy,
+        config: v3.config,
+        particles: v3.particles,
+        regions: v3.regions,
+        current_region_id: v3.current_region_id,
+        loaded_stars: v3.loaded_stars,
+        life_planets: v3.life_planets,
+        civilization_count: v3.civilization_count,
+        time_scale: v3.time_scale,
+        paused: v3.paused,
+        saved_at: v3.saved_at,
+        discoveries: Vec::new(),
+    }
+}
+
+/// v5 snapshot payload (`SimConfig` gained `integrator`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV5 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV1>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+}
+
+/// v4 → v5: `SimConfig` gained `integrator`
+pub fn migrate_v4_to_v5(v4: SnapshotV4) -> SnapshotV5 {
+    SnapshotV5 {
+        age: v4.age,
+        scale_factor: v4.scale_factor,
+        phase: v4.phase,
+        cycle: v4.cycle,
+        temperature: v4.temperature,
+        total_entropy: v4.total_entropy,
+        config: v4.config.into(),
+        particles: v4.particles,
+        regions: v4.regions,
+        current_region_id: v4.current_region_id,
+        loaded_stars: v4.loaded_stars,
+        life_planets: v4.life_planets,
+        civilization_count: v4.civilization_count,
+        time_scale: v4.time_scale,
+        paused: v4.paused,
+        saved_at: v4.saved_at,
+        discoveries: v4.discoveries,
+    }
+}
+
+/// v6 snapshot payload (`Star` gained `remnant`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV6 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+}
+
+/// v5 → v6: `Star` gained `remnant`
+pub fn migrate_v5_to_v6(v5: SnapshotV5) -> SnapshotV6 {
+    SnapshotV6 {
+        age: v5.age,
+        scale_factor: v5.scale_factor,
+        phase: v5.phase,
+        cycle: v5.cycle,
+        temperature: v5.temperature,
+        total_entropy: v5.total_entropy,
+        config: v5.config,
+        particles: v5.particles,
+        regions: v5.regions,
+        current_region_id: v5.current_region_id,
+        loaded_stars: v5.loaded_stars.into_iter().map(StarV6::from).collect(),
+        life_planets: v5.life_planets,
+        civilization_count: v5.civilization_count,
+        time_scale: v5.time_scale,
+        paused: v5.paused,
+        saved_at: v5.saved_at,
+        discoveries: v5.discoveries,
+    }
+}
+
+/// v7/v8 snapshot payload (v8 only added the compression byte in front of it)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV7 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticleV1>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+}
+
+/// v6 → v7: add `civilizations` (civilizations of older saves start being tracked
+/// the next time their region is loaded)
+pub fn migrate_v6_to_v7(v6: SnapshotV6) -> SnapshotV7 {
+    SnapshotV7 {
+        age: v6.age,
+        scale_factor: v6.scale_factor,
+        phase: v6.phase,
+        cycle: v6.cycle,
+        temperature: v6.temperature,
+        total_entropy: v6.total_entropy,
+        config: v6.config,
+        particles: v6.particles,
+        regions: v6.regions,
+        current_region_id: v6.current_region_id,
+        loaded_stars: v6.loaded_stars,
+        life_planets: v6.life_planets,
+        civilization_count: v6.civilization_count,
+        time_scale: v6.time_scale,
+        paused: v6.paused,
+        saved_at: v6.saved_at,
+        discoveries: v6.discoveries,
+        civilizations: Vec::new(),
+    }
+}
+
+/// v8 → v9: `SerializedParticle` unpacked mass/charge and stores `kind` as the enum
+/// (unknown kind indices become `FALLBACK_KIND`)
+pub fn migrate_v8_to_v9(v8: SnapshotV7) -> SnapshotV9 {
+    SnapshotV9 {
+        age: v8.age,
+        scale_factor: v8.scale_factor,
+        phase: v8.phase,
+        cycle: v8.cycle,
+        temperature: v8.temperature,
+        total_entropy: v8.total_entropy,
+        config: v8.config,
+        particles: v8.particles.into_iter().map(SerializedParticle::from).collect(),
+        regions: v8.regions,
+        current_region_id: v8.current_region_id,
+        loaded_stars: v8.loaded_stars,
+        life_planets: v8.life_planets,
+        civilization_count: v8.civilization_count,
+        time_scale: v8.time_scale,
+        paused: v8.paused,
+        saved_at: v8.saved_at,
+        discoveries: v8.discoveries,
+        civilizations: v8.civilizations,
+    }
+}
+
+/// v9 snapshot payload (before `galaxies`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV9 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+}
+
+/// v9 → v10: add `galaxies`. Stars of older saves were scattered uniformly, so no galaxies
+/// are made up for them; the region gets its galaxies the next time it is generated.
+pub fn migrate_v9_to_v10(v9: SnapshotV9) -> SnapshotV10 {
+    SnapshotV10 {
+        age: v9.age,
+        scale_factor: v9.scale_factor,
+        phase: v9.phase,
+        cycle: v9.cycle,
+        temperature: v9.temperature,
+        total_entropy: v9.total_entropy,
+        config: v9.config,
+        particles: v9.particles,
+        regions: v9.regions,
+        current_region_id: v9.current_region_id,
+        loaded_stars: v9.loaded_stars,
+        galaxies: Vec::new(),
+        life_planets: v9.life_planets,
+        civilization_count: v9.civilization_count,
+        time_scale: v9.time_scale,
+        paused: v9.paused,
+        saved_at: v9.saved_at,
+        discoveries: v9.discoveries,
+        civilizations: v9.civilizations,
+    }
+}
+
+/// v10 snapshot payload (before `events`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV10 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub galaxies: Vec<Galaxy>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+}
+
+/// v10 → v11: add `events`. Older saves start with an empty feed history.
+pub fn migrate_v10_to_v11(v10: SnapshotV10) -> SnapshotV11 {
+    SnapshotV11 {
+        age: v10.age,
+        scale_factor: v10.scale_factor,
+        phase: v10.phase,
+        cycle: v10.cycle,
+        temperature: v10.temperature,
+        total_entropy: v10.total_entropy,
+        config: v10.config,
+        particles: v10.particles,
+        regions: v10.regions,
+        current_region_id: v10.current_region_id,
+        loaded_stars: v10.loaded_stars,
+        galaxies: v10.galaxies,
+        life_planets: v10.life_planets,
+        civilization_count: v10.civilization_count,
+        time_scale: v10.time_scale,
+        paused: v10.paused,
+        saved_at: v10.saved_at,
+        discoveries: v10.discoveries,
+        civilizations: v10.civilizations,
+        events: Vec::new(),
+    }
+}
+
+/// v11 snapshot payload (before `surface_memory`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV11 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub galaxies: Vec<Galaxy>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+    pub events: Vec<FeedEntry>,
+}
+
+/// v11 → v12: add `surface_memory`. Older saves have no planets visited.
+pub fn migrate_v11_to_v12(v11: SnapshotV11) -> SnapshotV12 {
+    SnapshotV12 {
+        age: v11.age,
+        scale_factor: v11.scale_factor,
+        phase: v11.phase,
+        cycle: v11.cycle,
+        temperature: v11.temperature,
+        total_entropy: v11.total_entropy,
+        config: v11.config,
+        particles: v11.particles,
+        regions: v11.regions,
+        current_region_id: v11.current_region_id,
+        loaded_stars: v11.loaded_stars,
+        galaxies: v11.galaxies,
+        life_planets: v11.life_planets,
+        civilization_count: v11.civilization_count,
+        time_scale: v11.time_scale,
+        paused: v11.paused,
+        saved_at: v11.saved_at,
+        discoveries: v11.discoveries,
+        civilizations: v11.civilizations,
+        events: v11.events,
+        surface_memory: SurfaceMemory::default(),
+    }
+}
+
+/// v12 snapshot payload (before `bestiary`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV12 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub galaxies: Vec<Galaxy>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+    pub events: Vec<FeedEntry>,
+    pub surface_memory: SurfaceMemory,
+}
+
+/// v12 → v13: add `bestiary`. Older saves have scanned nothing.
+pub fn migrate_v12_to_v13(v12: SnapshotV12) -> SnapshotV13 {
+    SnapshotV13 {
+        age: v12.age,
+        scale_factor: v12.scale_factor,
+        phase: v12.phase,
+        cycle: v12.cycle,
+        temperature: v12.temperature,
+        total_entropy: v12.total_entropy,
+        config: v12.config,
+        particles: v12.particles,
+        regions: v12.regions,
+        current_region_id: v12.current_region_id,
+        loaded_stars: v12.loaded_stars,
+        galaxies: v12.galaxies,
+        life_planets: v12.life_planets,
+        civilization_count: v12.civilization_count,
+        time_scale: v12.time_scale,
+        paused: v12.paused,
+        saved_at: v12.saved_at,
+        discoveries: v12.discoveries,
+        civilizations: v12.civilizations,
+        events: v12.events,
+        surface_memory: v12.surface_memory,
+        bestiary: Bestiary::default(),
+    }
+}
+
+/// v13 snapshot payload (before `names`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV13 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub galaxies: Vec<Galaxy>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+    pub events: Vec<FeedEntry>,
+    pub surface_memory: SurfaceMemory,
+    pub bestiary: Bestiary,
+}
+
+/// v13 → v14: add `names`. Older saves have nothing renamed.
+pub fn migrate_v13_to_v14(v13: SnapshotV13) -> SnapshotV14 {
+    SnapshotV14 {
+        age: v13.age,
+        scale_factor: v13.scale_factor,
+        phase: v13.phase,
+        cycle: v13.cycle,
+        temperature: v13.temperature,
+        total_entropy: v13.total_entropy,
+        config: v13.config,
+        particles: v13.particles,
+        regions: v13.regions,
+        current_region_id: v13.current_region_id,
+        loaded_stars: v13.loaded_stars,
+        galaxies: v13.galaxies,
+        life_planets: v13.life_planets,
+        civilization_count: v13.civilization_count,
+        time_scale: v13.time_scale,
+        paused: v13.paused,
+        saved_at: v13.saved_at,
+        discoveries: v13.discoveries,
+        civilizations: v13.civilizations,
+        events: v13.events,
+        surface_memory: v13.surface_memory,
+        bestiary: v13.bestiary,
+        names: CustomNames::default(),
+    }
+}
+
+/// v14 snapshot payload (before the packed v15 layout added `stars_age`)
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotV14 {
+    pub age: f64,
+    pub scale_factor: f64,
+    pub phase: UniversePhase,
+    pub cycle: u32,
+    pub temperature: f64,
+    pub total_entropy: f64,
+    pub config: SimConfigV5,
+    pub particles: Vec<SerializedParticle>,
+    pub regions: Vec<RegionV1>,
+    pub current_region_id: Option<u64>,
+    pub loaded_stars: Vec<StarV6>,
+    pub galaxies: Vec<Galaxy>,
+    pub life_planets: Vec<(u64, String)>,
+    pub civilization_count: u32,
+    pub time_scale: f64,
+    pub paused: bool,
+    pub saved_at: u64,
+    pub discoveries: Vec<LifeDiscovery>,
+    pub civilizations: Vec<Civilization>,
+    pub events: Vec<FeedEntry>,
+    pub surface_memory: SurfaceMemory,
+    pub bestiary: Bestiary,
+    pub names: CustomNames,
+}
+
+/// v14 → v15: add `stars_age`. The age the old stars were generated at is unknown,
+/// so the next save stores them in full instead of as a procgen overlay.
+/// Regions gain their central black hole (v16) and stars their small bodies (v17) on the way.
+pub fn migrate_v14_to_v15(v14: SnapshotV14) -> UniverseSnapshot {
+    UniverseSnapshot {
+        age: v14.age,
+        scale_factor: v14.scale_factor,
+        phase: v14.phase,
+        cycle: v14.cycle,
+        temperature: v14.temperature,
+        total_entropy: v14.total_entropy,
+        config: SimConfigV18::from(v14.config).into(),
+        particles: v14.particles,
+        regions: v14
+            .regions
+            .into_iter()
+            .map(|r| RegionV19::from(RegionV16::from(r)).into())
+            .collect(),
+        current_region_id: v14.current_region_id,
+        loaded_stars: v14.loaded_stars.into_iter().map(Star::from).collect(),
+        stars_age: 0.0,
+        galaxies: v14.galaxies,
+        life_planets: v14.life_planets,
+        civilization_count: v14.civilization_count,
+        time_scale: v14.time_scale,
+        paused: v14.paused,
+        saved_at: v14.saved_at,
+        discoveries: v14.discoveries,
+        civilizations: v14.civilizations,
+        events: v14.events,
+        surface_memory: v14.surface_memory,
+        bestiary: v14.bestiary,
+        names: v14.names,
+        bookmarks: Bookmarks::default(),
+        history: Vec::new(),
+    }
+}
+
+/// v15 packed payload, with regions before `central_black_hole`
+pub type PackedSnapshotV15 = PackedSnapshot<RegionV1, StarV6, PlanetV1, SimConfigV5, (), ()>;
+
+/// v16 packed payload, with stars before moons, belts and comets
+pub type PackedSnapshotV16 = PackedSnapshot<RegionV16, StarV6, PlanetV1, SimConfigV5, (), ()>;
+
+/// v17 packed payload, with the config before `heat_death_age`
+pub type PackedSnapshotV17 = PackedSnapshot<RegionV16, StarV17, PlanetV17, SimConfigV5, (), ()>;
+
+/// v18 packed payload, with regions before `has_civilization`
+pub type PackedSnapshotV18 = PackedSnapshot<RegionV16, StarV17, PlanetV17, SimConfigV18, (), ()>;
+
+/// v19–v21 packed payload, with planets before `tidally_locked`
+pub type PackedSnapshotV19 = PackedSnapshot<RegionV19, StarV17, PlanetV17, SimConfigV18, (), ()>;
+
+/// v22 packed payload, before `bookmarks`
+pub type PackedSnapshotV22 = PackedSnapshot<RegionV19, StarV22, PlanetV22, SimConfigV18, (), ()>;
+
+/// v23 packed payload, with planets before `rings`
+pub type PackedSnapshotV23 =
+    PackedSnapshot<RegionV19, StarV22, PlanetV22, SimConfigV18, Bookmarks, ()>;
+
+/// v24 packed payload, before `history`
+pub type PackedSnapshotV24 = PackedSnapshot<RegionV19, Star, Planet, SimConfigV18, Bookmarks, ()>;
+
+/// v25 packed payload, with the config before `region_layout` and regions before `parent`
+pub type PackedSnapshotV25 =
+    PackedSnapshot<RegionV19, Star, Planet, SimConfigV18, Bookmarks, Vec<HistorySample>>;
+
+/// v15 → v16: add `Region::central_black_hole`
+pub fn migrate_v15_to_v16(v15: PackedSnapshotV15) -> PackedSnapshotV16 {
+    v15.map_regions(RegionV16::from)
+}
+
+/// v16 → v17: add moons, asteroid belts and comets. Stars regenerated from procgen get
+/// them straight away; stars and planets saved whole get them when the region reloads.
+pub fn migrate_v16_to_v17(v16: PackedSnapshotV16) -> PackedSnapshotV17 {
+    v16.map_stars(StarV17::from, PlanetV17::from)
+}
+
+/// v17 → v18: add `SimConfig::heat_death_age` (the default). The saved entropy was
+/// measured by the old model and is kept; the new one only raises it.
+pub fn migrate_v17_to_v18(v17: PackedSnapshotV17) -> PackedSnapshotV18 {
+    v17.map_config(SimConfigV18::from)
+}
+
+/// v18 → v19: add `Region::has_civilization` (false until the loader works it out from
+/// the saved civilizations)
+pub fn migrate_v18_to_v19(v18: PackedSnapshotV18) -> PackedSnapshotV19 {
+    v18.map_regions(RegionV19::from)
+}
+
+/// v21 → v22: add `Planet::tidally_locked`. Stars saved whole have their close-in planets
+/// locked; procgen now generates locked planets differently, so stars stored as an overlay
+/// must be dropped after this (`PackedSnapshot::forget_generated_stars`).
+pub fn migrate_v21_to_v22(v21: PackedSnapshotV19) -> PackedSnapshotV22 {
+    v21.map_stars(StarV22::from, PlanetV22::from)
+}
+
+/// v22 → v23: add `bookmarks` (none stored yet)
+pub fn migrate_v22_to_v23(v22: PackedSnapshotV22) -> PackedSnapshotV23 {
+    v22.map_bookmarks(|()| Bookmarks::default())
+}
+
+/// v23 → v24: add `Planet::rings`. Regenerated stars get theirs from procgen; giants of
+/// stars saved whole have none until their region is generated again.
+pub fn migrate_v23_to_v24(v23: PackedSnapshotV23) -> PackedSnapshotV24 {
+    v23.map_stars(Star::from, Planet::from)
+}
+
+/// v24 → v25: add `history` (nothing sampled yet)
+pub fn migrate_v24_to_v25(v24: PackedSnapshotV24) -> PackedSnapshotV25 {
+    v24.map_history(|()| Vec::new())
+}
+
+/// v25 → v26: add `SimConfig::region_layout` (the 8×8×8 grid every older universe was
+/// generated on) and `Region::parent` (none: nothing was subdivided)
+pub fn migrate_v25_to_v26(v25: PackedSnapshotV25) -> PackedSnapshot {
+    v25.map_config(SimConfig::from).map_regions(Region::from)
+}
+
+/// v19 → v20: star ids gain their region (`names::STAR_ID_STRIDE`), and planet ids with
+/// them. Discoveries, civilizations and events carry their region; the planets of
+/// `life_planets`, surface memory and the bestiary take that of the discovery with the same
+/// id, else the loaded region's.
+pub fn migrate_v19_to_v20(mut v19: UniverseSnapshot) -> UniverseSnapshot {
+    let star = |region_id: u64, id: u64| region_id * STAR_ID_STRIDE + id;
+    let body = |region_id: u64, id: u64| region_id * STAR_ID_STRIDE * PLANET_ID_STRIDE + id;
+    let loaded = v19.current_region_id.unwrap_or_default();
+    let discovered: HashMap<u64, u64> =
+        v19.discoveries.iter().map(|d| (d.planet_id, d.region_id)).collect();
+    let planet = |id: u64| body(discovered.get(&id).copied().unwrap_or(loaded), id);
+
+    fn shift_planet(planet: &mut Planet, offset: u64) {
+        planet.id += offset;
+        for moon in &mut planet.moons {
+            shift_planet(moon, offset);
+        }
+    }
+    for s in &mut v19.loaded_stars {
+        s.id = star(loaded, s.id);
+        for p in &mut s.planets {
+            shift_planet(p, body(loaded, 0));
+        }
+        for comet in &mut s.comets {
+            comet.id = body(loaded, comet.id);
+        }
+    }
+    for galaxy in &mut v19.galaxies {
+        galaxy.first_star = star(loaded, galaxy.first_star);
+    }
+    for (id, _) in &mut v19.life_planets {
+        *id = planet(*id);
+    }
+    for d in &mut v19.discoveries {
+        d.star_id = star(d.region_id, d.star_id);
+        d.planet_id = body(d.region_id, d.planet_id);
+    }
+    for c in &mut v19.civilizations {
+        c.star_id = star(c.region_id, c.star_id);
+        c.planet_id = body(c.region_id, c.planet_id);
+        for colony in &mut c.colonies {
+            *colony = body(c.region_id, *colony);
+        }
+    }
+    for entry in &mut v19.events {
+        match &mut entry.event {
+            SimEvent::LifeDiscovered { region_id, planet_id, .. }
+            | SimEvent::CivilizationDetected { region_id, planet_id }
+            | SimEvent::CivilizationExtinct { region_id, planet_id, .. }
+            | SimEvent::SignalDetected { region_id, planet_id } => {
+                *planet_id = body(*region_id, *planet_id);
+            }
+            _ => {}
+        }
+    }
+    v19.surface_memory.map_planet_ids(planet);
+    v19.bestiary.map_planet_ids(planet);
+    v19
+}
+
+/// v20 → v21: procgen draws every star, planet and biosphere from its own seed, so stars
+/// stored as an overlay (dropped by `PackedSnapshot::forget_generated_stars`) can't be
+/// rebuilt. A loaded region left without stars is unloaded and generated again.
+pub fn migrate_v20_to_v21(mut v20: UniverseSnapshot) -> UniverseSnapshot {
+    if v20.loaded_stars.is_empty() {
+        v20.current_region_id = None;
+        v20.stars_age = 0.0;
+    }
+    v20
+}
+
+/// Decode a v1 payload (after the versioned header)
+pub fn decode_v1(payload: &[u8]) -> Result<SnapshotV1, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v1 payload: {e}")))
+}
+
+/// Decode a v2/v3 payload
+pub fn decode_v2(payload: &[u8]) -> Result<SnapshotV2, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v2 payload: {e}")))
+}
+
+/// Decode a v4 payload
+pub fn decode_v4(payload: &[u8]) -> Result<SnapshotV4, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v4 payload: {e}")))
+}
+
+/// Decode a v5 payload
+pub fn decode_v5(payload: &[u8]) -> Result<SnapshotV5, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v5 payload: {e}")))
+}
+
+/// Decode a v6 payload
+pub fn decode_v6(payload: &[u8]) -> Result<SnapshotV6, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v6 payload: {e}")))
+}
+
+/// Decode a v7 payload
+pub fn decode_v7(payload: &[u8]) -> Result<SnapshotV7, SnapshotError> {
+    bincode::deserialize(payload).map_err(|e| SnapshotError::Corrupt(format!("v7 payload: {e}")))
+}
+
+/// Decode a file written before the versioned header existed (raw v1 bincode)
+pub fn decode_legacy(data: &[u8]) -> Result<SnapshotV1, SnapshotError> {
+    bincode::deserialize(data)
+        .map_err(|e| SnapshotError::Corrupt(format!("not a snapshot (no header, legacy decode failed: {e})")))
+}
+
+/// Unpack a v19–v21 payload. Unpacking needs the current layout, so the later packed
+/// steps come first; stars generated by the old procgen can't be kept and the region is
+/// made again.
+fn unpack_v19(v19: PackedSnapshotV19) -> Result<UniverseSnapshot, SnapshotError> {
+    let v25 = migrate_v24_to_v25(migrate_v23_to_v24(migrate_v22_to_v23(migrate_v21_to_v22(v19))));
+    migrate_v25_to_v26(v25).forget_generated_stars().unpack()
+}
+
+/// A payload on its way up to the current format, in the frozen layout of its version.
+/// `migrate` takes one step at a time, so a format bump only adds a variant, its decode
+/// arm and the step from the version before. Packed payloads (v15 on) step through their
+/// packed layouts up to v19, where they are unpacked for the steps that need the whole
+/// snapshot. Packed payloads with bookmarks (v23 on) are much the largest and boxed.
+pub enum Migrating {
+    V1(SnapshotV1),
+    V2(SnapshotV2),
+    V3(SnapshotV2),
+    V4(SnapshotV4),
+    V5(SnapshotV5),
+    V6(SnapshotV6),
+    V7(SnapshotV7),
+    V8(SnapshotV7),
+    V9(SnapshotV9),
+    V10(SnapshotV10),
+    V11(SnapshotV11),
+    V12(SnapshotV12),
+    V13(SnapshotV13),
+    V14(SnapshotV14),
+    V15(PackedSnapshotV15),
+    V16(PackedSnapshotV16),
+    V17(PackedSnapshotV17),
+    V18(PackedSnapshotV18),
+    V19(PackedSnapshotV19),
+    V20(PackedSnapshotV19),
+    V21(PackedSnapshotV19),
+    V22(PackedSnapshotV22),
+    V23(Box<PackedSnapshotV23>),
+    V24(Box<PackedSnapshotV24>),
+    V25(Box<PackedSnapshotV25>),
+    /// A pre-v15 save, unpacked, still with v19 (per-region) star and planet ids
+    Unpacked19(UniverseSnapshot),
+    Unpacked20(UniverseSnapshot),
+    Unpacked21(UniverseSnapshot),
+    /// Packed payload in the current layout, unpacked once migration is done
+    Packed(Box<PackedSnapshot>),
+    Current(UniverseSnapshot),
+}
+
+impl Migrating {
+    /// Decode the payload (after the versioned header) of a file at `version`
+    pub fn decode(version: u32, payload: &[u8]) -> Result<Self, SnapshotError> {
+        match version {
+            1 => decode_v1(payload).map(Self::V1),
+            2 => decode_v2(payload).map(Self::V2),
+            3 => decode_v2(super::skip_meta(payload)?).map(Self::V3),
+            4 => decode_v4(super::skip_meta(payload)?).map(Self::V4),
+            5 => decode_v5(super::skip_meta(payload)?).map(Self::V5),
+            6 => decode_v6(super::skip_meta(payload)?).map(Self::V6),
+            7 => decode_v7(super::skip_meta(payload)?).map(Self::V7),
+            8 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V8),
+            9 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V9),
+            10 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V10),
+            11 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V11),
+            12 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V12),
+            13 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V13),
+            14 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V14),
+            15 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V15),
+            16 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V16),
+            17 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V17),
+            18 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V18),
+            19 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V19),
+            20 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V20),
+            21 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V21),
+            22 => super::decode_compressed(super::skip_meta(payload)?).map(Self::V22),
+            23 => super::decode_compressed(super::skip_meta(payload)?).map(|p| Self::V23(Box::new(p))),
+            24 => super::decode_compressed(super::skip_meta(payload)?).map(|p| Self::V24(Box::new(p))),
+            25 => super::decode_compressed(super::skip_meta(payload)?).map(|p| Self::V25(Box::new(p))),
+            SNAPSHOT_VERSION => super::decode_compressed(super::skip_meta(payload)?).map(|p| Self::Packed(Box::new(p))),
+            v => Err(SnapshotError::Corrupt(format!("unknown snapshot version {v}"))),
+        }
+    }
+
+    /// Migrate one version up (the current version stays as it is)
+    pub fn migrate(self) -> Result<Self, SnapshotError> {
+        Ok(match self {
+            Self::V1(v1) => Self::V2(migrate_v1_to_v2(v1)),
+            // v2 → v3 only added the metadata header; the payload layout is unchanged
+            Self::V2(v2) => Self::V3(v2),
+            Self::V3(v3) => Self::V4(migrate_v3_to_v4(v3)),
+            Self::V4(v4) => Self::V5(migrate_v4_to_v5(v4)),
+            Self::V5(v5) => Self::V6(migrate_v5_to_v6(v5)),
+            Self::V6(v6) => Self::V7(migrate_v6_to_v7(v6)),
+            // v7 → v8 only added the compression byte
+            Self::V7(v7) => Self::V8(v7),
+            Self::V8(v8) => Self::V9(migrate_v8_to_v9(v8)),
+            Self::V9(v9) => Self::V10(migrate_v9_to_v10(v9)),
+            Self::V10(v10) => Self::V11(migrate_v10_to_v11(v10)),
+            Self::V11(v11) => Self::V12(migrate_v11_to_v12(v11)),
+            Self::V12(v12) => Self::V13(migrate_v12_to_v13(v12)),
+            Self::V13(v13) => Self::V14(migrate_v13_to_v14(v13)),
+            // Older saves are unpacked: v14 → v15 also applies every later packed step
+            Self::V14(v14) => Self::Unpacked19(migrate_v14_to_v15(v14)),
+            Self::V15(v15) => Self::V16(migrate_v15_to_v16(v15)),
+            Self::V16(v16) => Self::V17(migrate_v16_to_v17(v16)),
+            Self::V17(v17) => Self::V18(migrate_v17_to_v18(v17)),
+            Self::V18(v18) => Self::V19(migrate_v18_to_v19(v18)),
+            Self::V19(v19) => Self::Unpacked20(migrate_v19_to_v20(unpack_v19(v19)?)),
+            Self::Unpacked19(v19) => Self::Unpacked20(migrate_v19_to_v20(v19)),
+            Self::V20(v20) => Self::Unpacked21(migrate_v20_to_v21(unpack_v19(v20)?)),
+            Self::Unpacked20(v20) => Self::Unpacked21(migrate_v20_to_v21(v20)),
+            Self::V21(v21) => Self::Current(unpack_v19(v21)?),
+            // The later packed steps were applied on unpacking
+            Self::Unpacked21(v21) => Self::Current(v21),
+            Self::V22(v22) => Self::V23(Box::new(migrate_v22_to_v23(v22))),
+            Self::V23(v23) => Self::V24(Box::new(migrate_v23_to_v24(*v23))),
+            Self::V24(v24) => Self::V25(Box::new(migrate_v24_to_v25(*v24))),
+            Self::V25(v25) => Self::Packed(Box::new(migrate_v25_to_v26(*v25))),
+            Self::Packed(_) | Self::Current(_) => self,
+        })
+    }
+}
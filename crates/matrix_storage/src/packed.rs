@@ -0,0 +1,723 @@
+//! v15+ on-disk payload. Particles are stored column by column and quantized: positions
+//! and velocities as 16-bit offsets from the middle of their range, mass and temperature
+//! as 16-bit steps on a log scale. The loaded stars are not stored at all when procgen
+//! can regenerate them from the region seed and `stars_age`: only what changed since
+//! (orbital angles, remnants, evolved planets) is written as an overlay.
+//!
+//! Everything is generic over the region, star, planet, config, bookmark and history
+//! layouts so that older packed payloads (`migration::PackedSnapshotV15` to
+//! `PackedSnapshotV25`) decode with the same code.
+
+use matrix_core::serialized::FALLBACK_KIND;
+use matrix_core::{
+    Bestiary, Bookmarks, Civilization, CustomNames, FeedEntry, Galaxy, HistorySample, LifeDiscovery, ParticleKind, Planet,
+    Region, SerializedParticle, SimConfig, Star, SurfaceMemory, UniversePhase,
+};
+use matrix_physics::stellar;
+use serde::{Deserialize, Serialize};
+
+use super::{SnapshotError, UniverseSnapshot};
+
+/// Largest quantized offset: a column spans `-QUANT_MAX..=QUANT_MAX` steps
+const QUANT_MAX: f32 = i16::MAX as f32;
+/// Steps between the smallest and largest value of a log-scaled column
+const LOG_STEPS: f64 = (u16::MAX - 1) as f64;
+
+/// One `f32` per particle as an `i16` offset from the middle of the column's range.
+/// The error is at most half a step: `half_range / 65534`.
+#[derive(Debug, Serialize, Deserialize)]
+struct QuantizedColumn {
+    center: f32,
+    half_range: f32,
+    steps: Vec<i16>,
+}
+
+impl QuantizedColumn {
+    /// Non-finite values are stored as the center
+    fn pack(values: &[f32]) -> Self {
+        let (min, max) = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        if min > max {
+            return Self {
+                center: 0.0,
+                half_range: 0.0,
+                steps: vec![0; values.len()],
+            };
+        }
+        let center = ((min as f64 + max as f64) / 2.0) as f32;
+        let half_range = ((max as f64 - min as f64) / 2.0) as f32;
+        let steps = values
+            .iter()
+            .map(|&v| {
+                if half_range > 0.0 && v.is_finite() {
+                    ((v - center) / half_range * QUANT_MAX)
+                        .round()
+                        .clamp(-QUANT_MAX, QUANT_MAX) as i16
+                } else {
+                    0
+                }
+            })
+            .collect();
+        Self {
+            center,
+            half_range,
+            steps,
+        }
+    }
+
+    fn value(&self, i: usize) -> f32 {
+        self.center + self.steps[i] as f32 / QUANT_MAX * self.half_range
+    }
+}
+
+/// One positive `f32` per particle as a `u16` step between the column's smallest and
+/// largest value on a log scale (relative error ≤ ln(max / min) / 131068).
+/// Step 0 stands for zero (and anything not positive and finite).
+#[derive(Debug, Serialize, Deserialize)]
+struct LogColumn {
+    min: f32,
+    max: f32,
+    steps: Vec<u16>,
+}
+
+impl LogColumn {
+    fn pack(values: &[f32]) -> Self {
+        let positive = |v: f32| v > 0.0 && v.is_finite();
+        let (min, max) = values
+            .iter()
+            .copied()
+            .filter(|&v| positive(v))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+        let ln_min = (min as f64).ln();
+        let ln_span = (max as f64).ln() - ln_min;
+        let steps = values
+            .iter()
+            .map(|&v| {
+                if !positive(v) {
+                    0
+                } else if ln_span > 0.0 {
+                    1 + (((v as f64).ln() - ln_min) / ln_span * LOG_STEPS).round() as u16
+                } else {
+                    1
+                }
+            })
+            .collect();
+        Self { min, max, steps }
+    }
+
+    fn value(&self, i: usize) -> f32 {
+        match self.steps[i] {
+            0 => 0.0,
+            1 => self.min,
+            u16::MAX => self.max,
+            step => {
+                let (ln_min, ln_max) = ((self.min as f64).ln(), (self.max as f64).ln());
+                (ln_min + (step - 1) as f64 / LOG_STEPS * (ln_max - ln_min)).exp() as f32
+            }
+        }
+    }
+}
+
+/// Particles as columns (structure of arrays) instead of one `SerializedParticle` each
+#[derive(Debug, Serialize, Deserialize)]
+struct PackedParticles {
+    positions: [QuantizedColumn; 3],
+    velocities: [QuantizedColumn; 3],
+    masses: LogColumn,
+    temperatures: LogColumn,
+    /// `ParticleKind` discriminants (all below 256)
+    kinds: Vec<u8>,
+    /// Only bits 0 (alive) and 1 (interacting) are used
+    flags: Vec<u8>,
+    /// Non-zero charges by particle index (almost every particle is neutral)
+    charges: Vec<(u32, f32)>,
+}
+
+impl PackedParticles {
+    fn pack(particles: &[SerializedParticle]) -> Self {
+        let column = |field: fn(&SerializedParticle) -> f32| -> Vec<f32> {
+            particles.iter().map(field).collect()
+        };
+        Self {
+            positions: [
+                QuantizedColumn::pack(&column(|p| p.position[0])),
+                QuantizedColumn::pack(&column(|p| p.position[1])),
+                QuantizedColumn::pack(&column(|p| p.position[2])),
+            ],
+            velocities: [
+                QuantizedColumn::pack(&column(|p| p.velocity[0])),
+                QuantizedColumn::pack(&column(|p| p.velocity[1])),
+                QuantizedColumn::pack(&column(|p| p.velocity[2])),
+            ],
+            masses: LogColumn::pack(&column(|p| p.mass)),
+            temperatures: LogColumn::pack(&column(|p| p.temperature)),
+            kinds: particles.iter().map(|p| p.kind as u8).collect(),
+            flags: particles.iter().map(|p| p.flags as u8).collect(),
+            charges: particles
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.charge != 0.0)
+                .map(|(i, p)| (i as u32, p.charge))
+                .collect(),
+        }
+    }
+
+    fn unpack(self) -> Result<Vec<SerializedParticle>, SnapshotError> {
+        let count = self.kinds.len();
+        let lengths = self
+            .positions
+            .iter()
+            .chain(&self.velocities)
+            .map(|c| c.steps.len())
+            .chain([
+                self.masses.steps.len(),
+                self.temperatures.steps.len(),
+                self.flags.len(),
+            ]);
+        if lengths.into_iter().any(|len| len != count) {
+            return Err(SnapshotError::Corrupt(
+                "particle columns differ in length".to_string(),
+            ));
+        }
+
+        let mut particles: Vec<SerializedParticle> = (0..count)
+            .map(|i| SerializedParticle {
+                position: self.positions.each_ref().map(|c| c.value(i)),
+                velocity: self.velocities.each_ref().map(|c| c.value(i)),
+                mass: self.masses.value(i),
+                charge: 0.0,
+                kind: ParticleKind::from_u32(self.kinds[i] as u32).unwrap_or(FALLBACK_KIND),
+                flags: self.flags[i] as u32,
+                temperature: self.temperatures.value(i),
+            })
+            .collect();
+        for (i, charge) in self.charges {
+            let particle = particles
+                .get_mut(i as usize)
+                .ok_or_else(|| SnapshotError::Corrupt(format!("charge of particle {i}")))?;
+            particle.charge = charge;
+        }
+        Ok(particles)
+    }
+}
+
+/// What procgen doesn't reproduce of the loaded stars. Moons and comets of regenerated
+/// stars restart from their generated positions: only planet orbits are kept.
+#[derive(Debug, Serialize, Deserialize)]
+struct StarOverlay<S = Star, P = Planet> {
+    region_id: u64,
+    /// Region seed at save time (a mismatch on load means the regions don't match)
+    seed: u64,
+    /// Orbital angle of every planet, star by star (orbits advance every frame)
+    orbital_angles: Vec<f32>,
+    /// Stars that changed since they were generated (remnants, supernova heating), whole
+    stars: Vec<(u32, S)>,
+    /// Planets that changed since (evolved life), by star and planet index
+    planets: Vec<(u32, u32, P)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum PackedStars<S = Star, P = Planet> {
+    /// Nothing to regenerate from (no region, unknown `stars_age`, too many changes)
+    Full(Vec<S>),
+    Regenerated(StarOverlay<S, P>),
+}
+
+impl<S, P> PackedStars<S, P> {
+    fn map<T, Q>(self, mut star: impl FnMut(S) -> T, mut planet: impl FnMut(P) -> Q) -> PackedStars<T, Q> {
+        match self {
+            Self::Full(stars) => PackedStars::Full(stars.into_iter().map(star).collect()),
+            Self::Regenerated(o) => PackedStars::Regenerated(StarOverlay {
+                region_id: o.region_id,
+                seed: o.seed,
+                orbital_angles: o.orbital_angles,
+                stars: o.stars.into_iter().map(|(i, s)| (i, star(s))).collect(),
+                planets: o.planets.into_iter().map(|(i, j, p)| (i, j, planet(p))).collect(),
+            }),
+        }
+    }
+}
+
+impl PackedStars {
+    fn pack(stars: &[Star], region: Option<&Region>, stars_age: f64) -> Self {
+        let full = || Self::Full(stars.to_vec());
+        let Some(region) = region.filter(|_| !stars.is_empty() && stars_age > 0.0) else {
+            return full();
+        };
+        let (mut generated, _) = stellar::generate_region_stars(region, stars_age);
+        if generated.len() != stars.len() {
+            return full();
+        }
+
+        let mut overlay = StarOverlay {
+            region_id: region.id,
+            seed: region.seed,
+            orbital_angles: Vec::new(),
+            stars: Vec::new(),
+            planets: Vec::new(),
+        };
+        for (i, (star, expected)) in stars.iter().zip(&mut generated).enumerate() {
+            overlay
+                .orbital_angles
+                .extend(star.planets.iter().map(|p| p.orbital_angle as f32));
+            if star.planets.len() != expected.planets.len() {
+                overlay.stars.push((i as u32, star.clone()));
+                continue;
+            }
+            for (planet, expected) in star.planets.iter().zip(&mut expected.planets) {
+                expected.orbital_angle = planet.orbital_angle;
+                for (moon, expected) in planet.moons.iter().zip(&mut expected.moons) {
+                    expected.orbital_angle = moon.orbital_angle;
+                }
+            }
+            for (comet, expected) in star.comets.iter().zip(&mut expected.comets) {
+                expected.orbital_angle = comet.orbital_angle;
+            }
+            if star == expected {
+                continue;
+            }
+            // Only planets changed: patch those and keep the star itself from procgen
+            let mut body = expected.clone();
+            body.planets.clone_from(&star.planets);
+            if body != *star {
+                overlay.stars.push((i as u32, star.clone()));
+                continue;
+            }
+            for (j, (planet, expected)) in star.planets.iter().zip(&expected.planets).enumerate() {
+                if planet != expected {
+                    overlay.planets.push((i as u32, j as u32, planet.clone()));
+                }
+            }
+        }
+
+        if overlay.stars.len() * 2 > stars.len() {
+            return full();
+        }
+        Self::Regenerated(overlay)
+    }
+
+    fn unpack(self, regions: &[Region], stars_age: f64) -> Result<Vec<Star>, SnapshotError> {
+        let overlay = match self {
+            Self::Full(stars) => return Ok(stars),
+            Self::Regenerated(overlay) => overlay,
+        };
+        let corrupt = |what: &str| SnapshotError::Corrupt(format!("star overlay: {what}"));
+        let region = regions
+            .iter()
+            .find(|r| r.id == overlay.region_id && r.seed == overlay.seed)
+            .ok_or_else(|| corrupt("region missing"))?;
+
+        let (mut stars, _) = stellar::generate_region_stars(region, stars_age);
+        for (i, star) in overlay.stars {
+            *stars
+                .get_mut(i as usize)
+                .ok_or_else(|| corrupt("star index"))? = star;
+        }
+        for (i, j, planet) in overlay.planets {
+            *stars
+                .get_mut(i as usize)
+                .and_then(|s| s.planets.get_mut(j as usize))
+                .ok_or_else(|| corrupt("planet index"))? = planet;
+        }
+        let planets = stars.iter_mut().flat_map(|s| &mut s.planets);
+        let mut angles = overlay.orbital_angles.into_iter();
+        for planet in planets {
+            planet.orbital_angle = angles.next().ok_or_else(|| corrupt("orbital angles"))? as f64;
+        }
+        if angles.next().is_some() {
+            return Err(corrupt("orbital angles"));
+        }
+        Ok(stars)
+    }
+}
+
+/// `UniverseSnapshot` as written since v15, with `particles` and `loaded_stars` packed.
+/// Generic over the region, star, planet and config layouts so v15–v17 files decode too,
+/// and over the bookmarks and history, which are `()` (nothing on disk) before v23 and v25.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackedSnapshot<R = Region, S = Star, P = Planet, C = SimConfig, B = Bookmarks, H = Vec<HistorySample>> {
+    age: f64,
+    scale_factor: f64,
+    phase: UniversePhase,
+    cycle: u32,
+    temperature: f64,
+    total_entropy: f64,
+    config: C,
+    particles: PackedParticles,
+    regions: Vec<R>,
+    current_region_id: Option<u64>,
+    loaded_stars: PackedStars<S, P>,
+    stars_age: f64,
+    galaxies: Vec<Galaxy>,
+    life_planets: Vec<(u64, String)>,
+    civilization_count: u32,
+    time_scale: f64,
+    paused: bool,
+    saved_at: u64,
+    discoveries: Vec<LifeDiscovery>,
+    civilizations: Vec<Civilization>,
+    events: Vec<FeedEntry>,
+    surface_memory: SurfaceMemory,
+    bestiary: Bestiary,
+    names: CustomNames,
+    bookmarks: B,
+    history: H,
+}
+
+impl PackedSnapshot {
+    pub fn pack(snapshot: &UniverseSnapshot) -> Self {
+        let region = snapshot
+            .current_region_id
+            .and_then(|id| snapshot.regions.iter().find(|r| r.id == id));
+        Self {
+            age: snapshot.age,
+            scale_factor: snapshot.scale_factor,
+            phase: snapshot.phase,
+            cycle: snapshot.cycle,
+            temperature: snapshot.temperature,
+            total_entropy: snapshot.total_entropy,
+            config: snapshot.config.clone(),
+            particles: PackedParticles::pack(&snapshot.particles),
+            regions: snapshot.regions.clone(),
+            current_region_id: snapshot.current_region_id,
+            loaded_stars: PackedStars::pack(&snapshot.loaded_stars, region, snapshot.stars_age),
+            stars_age: snapshot.stars_age,
+            galaxies: snapshot.galaxies.clone(),
+            life_planets: snapshot.life_planets.clone(),
+            civilization_count: snapshot.civilization_count,
+            time_scale: snapshot.time_scale,
+            paused: snapshot.paused,
+            saved_at: snapshot.saved_at,
+            discoveries: snapshot.discoveries.clone(),
+            civilizations: snapshot.civilizations.clone(),
+            events: snapshot.events.clone(),
+            surface_memory: snapshot.surface_memory.clone(),
+            bestiary: snapshot.bestiary.clone(),
+            names: snapshot.names.clone(),
+            bookmarks: snapshot.bookmarks.clone(),
+            history: snapshot.history.clone(),
+        }
+    }
+
+    pub fn unpack(self) -> Result<UniverseSnapshot, SnapshotError> {
+        Ok(UniverseSnapshot {
+            loaded_stars: self.loaded_stars.unpack(&self.regions, self.stars_age)?,
+            particles: self.particles.unpack()?,
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            regions: self.regions,
+            current_region_id: self.current_region_id,
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: self.history,
+        })
+    }
+}
+
+impl<R, S, P, C, B, H> PackedSnapshot<R, S, P, C, B, H> {
+    /// Drop loaded stars stored as an overlay on procgen output: before v22 procgen gave
+    /// them other ids, draws or climates, so the overlay no longer fits (for migrations).
+    /// The stars are left empty and `migration::migrate_v20_to_v21` unloads the region.
+    pub fn forget_generated_stars(mut self) -> Self {
+        if matches!(self.loaded_stars, PackedStars::Regenerated(_)) {
+            self.loaded_stars = PackedStars::Full(Vec::new());
+            self.galaxies.clear();
+        }
+        self
+    }
+
+    /// The same snapshot with every region converted (for migrations)
+    pub fn map_regions<T>(self, f: impl FnMut(R) -> T) -> PackedSnapshot<T, S, P, C, B, H> {
+        PackedSnapshot {
+            regions: self.regions.into_iter().map(f).collect(),
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            particles: self.particles,
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars,
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: self.history,
+        }
+    }
+
+    /// The same snapshot with every star and planet converted (for migrations)
+    pub fn map_stars<T, Q>(self, star: impl FnMut(S) -> T, planet: impl FnMut(P) -> Q) -> PackedSnapshot<R, T, Q, C, B, H> {
+        PackedSnapshot {
+            regions: self.regions,
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            particles: self.particles,
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars.map(star, planet),
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: self.history,
+        }
+    }
+
+    /// The same snapshot with its config converted (for migrations)
+    pub fn map_config<T>(self, f: impl FnOnce(C) -> T) -> PackedSnapshot<R, S, P, T, B, H> {
+        PackedSnapshot {
+            regions: self.regions,
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: f(self.config),
+            particles: self.particles,
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars,
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: self.history,
+        }
+    }
+
+    /// The same snapshot with its bookmarks converted (for migrations)
+    pub fn map_bookmarks<T>(self, f: impl FnOnce(B) -> T) -> PackedSnapshot<R, S, P, C, T, H> {
+        PackedSnapshot {
+            regions: self.regions,
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            particles: self.particles,
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars,
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: f(self.bookmarks),
+            history: self.history,
+        }
+    }
+
+    /// The same snapshot with its history converted (for migrations)
+    pub fn map_history<T>(self, f: impl FnOnce(H) -> T) -> PackedSnapshot<R, S, P, C, B, T> {
+        PackedSnapshot {
+            regions: self.regions,
+            age: self.age,
+            scale_factor: self.scale_factor,
+            phase: self.phase,
+            cycle: self.cycle,
+            temperature: self.temperature,
+            total_entropy: self.total_entropy,
+            config: self.config,
+            particles: self.particles,
+            current_region_id: self.current_region_id,
+            loaded_stars: self.loaded_stars,
+            stars_age: self.stars_age,
+            galaxies: self.galaxies,
+            life_planets: self.life_planets,
+            civilization_count: self.civilization_count,
+            time_scale: self.time_scale,
+            paused: self.paused,
+            saved_at: self.saved_at,
+            discoveries: self.discoveries,
+            civilizations: self.civilizations,
+            events: self.events,
+            surface_memory: self.surface_memory,
+            bestiary: self.bestiary,
+            names: self.names,
+            bookmarks: self.bookmarks,
+            history: f(self.history),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Compression, decode_snapshot, encode_snapshot};
+    use matrix_physics::{particle, procgen};
+
+    #[test]
+    fn test_packed_snapshot_is_smaller_and_round_trips() {
+        let mut snapshot = crate::tests::sample_snapshot();
+        snapshot.regions = procgen::generate_regions(&snapshot.config, 13.0);
+        snapshot.particles = snapshot
+            .regions
+            .iter()
+            .flat_map(|r| particle::generate_region_particles(r, &procgen::generate_galaxies(r), 13.0))
+            .take(100_000)
+            .map(|p| SerializedParticle::from(&p))
+            .collect();
+        snapshot.particles[7].charge = -1.0;
+        let region = snapshot
+            .regions
+            .iter()
+            .find(|r| r.star_count >= 1000)
+            .unwrap()
+            .clone();
+        snapshot.current_region_id = Some(region.id);
+        snapshot.stars_age = 12.0;
+        snapshot.loaded_stars = stellar::generate_region_stars(&region, 12.0).0;
+        assert_eq!(snapshot.loaded_stars.len(), 1000);
+
+        // What a session changes after loading: orbits, a late remnant, evolved life
+        for (i, planet) in snapshot
+            .loaded_stars
+            .iter_mut()
+            .flat_map(|s| &mut s.planets)
+            .enumerate()
+        {
+            planet.orbital_angle = (i as f64 * 0.37) % std::f64::consts::TAU;
+        }
+        snapshot.loaded_stars[3].remnant = Some(matrix_core::StellarRemnant::WhiteDwarf);
+        let (star, planet) = snapshot
+            .loaded_stars
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| (s.planets.len() > 1).then_some((i, 1)))
+            .unwrap();
+        snapshot.loaded_stars[star].planets[planet].surface_temp += 40.0;
+
+        let PackedStars::Regenerated(overlay) =
+            PackedStars::pack(&snapshot.loaded_stars, Some(&region), snapshot.stars_age)
+        else {
+            panic!("stars should be stored as an overlay");
+        };
+        assert_eq!(overlay.stars.len(), 1);
+        assert_eq!(overlay.planets.len(), 1);
+
+        // Before v15 the payload was this struct as is (minus `stars_age`). Measured:
+        // 4.9 MB -> 1.9 MB packed, 1.5 MB packed + LZ4. The six 16-bit position and velocity
+        // columns of random particles are nearly all of what is left and don't compress
+        let full = bincode::serialize(&snapshot).unwrap();
+        let packed = encode_snapshot(&snapshot, Compression::None).unwrap();
+        let ratio = full.len() as f64 / packed.len() as f64;
+        assert!(ratio > 2.5, "packed only {ratio:.2}x smaller");
+        let compressed = encode_snapshot(&snapshot, Compression::Lz4).unwrap();
+        let ratio = full.len() as f64 / compressed.len() as f64;
+        assert!(ratio > 3.0, "packed + LZ4 only {ratio:.2}x smaller");
+
+        let loaded = decode_snapshot(&packed).unwrap();
+        assert_eq!(loaded.loaded_stars.len(), 1000);
+        for (star, saved) in loaded.loaded_stars.iter().zip(&snapshot.loaded_stars) {
+            for (planet, saved) in star.planets.iter().zip(&saved.planets) {
+                assert!((planet.orbital_angle - saved.orbital_angle).abs() < 1e-6);
+                let restored = Planet {
+                    orbital_angle: saved.orbital_angle,
+                    ..planet.clone()
+                };
+                assert_eq!(&restored, saved);
+            }
+            assert_eq!(star.remnant, saved.remnant);
+        }
+
+        let half_range = |axis: usize, f: fn(&SerializedParticle) -> [f32; 3]| {
+            let values = snapshot.particles.iter().map(|p| f(p)[axis]);
+            (values.clone().fold(f32::MIN, f32::max) - values.fold(f32::MAX, f32::min)) / 2.0
+        };
+        for axis in 0..3 {
+            let position_error = half_range(axis, |p| p.position) / QUANT_MAX;
+            let velocity_error = half_range(axis, |p| p.velocity) / QUANT_MAX;
+            for (p, saved) in loaded.particles.iter().zip(&snapshot.particles) {
+                assert!((p.position[axis] - saved.position[axis]).abs() <= position_error);
+                assert!((p.velocity[axis] - saved.velocity[axis]).abs() <= velocity_error);
+            }
+        }
+        for (region, saved) in loaded.regions.iter().zip(&snapshot.regions) {
+            assert_eq!(region.central_black_hole, saved.central_black_hole);
+        }
+        let black_hole = ParticleKind::BlackHole;
+        assert!(loaded.particles.iter().any(|p| p.kind == black_hole));
+        for (p, saved) in loaded.particles.iter().zip(&snapshot.particles) {
+            assert!((p.mass / saved.mass - 1.0).abs() < 1e-3);
+            // Zero (a black hole's unlit disk) is stored exactly
+            assert!(
+                p.temperature == saved.temperature
+                    || (p.temperature / saved.temperature - 1.0).abs() < 1e-3
+            );
+            assert_eq!(
+                (p.kind, p.flags, p.charge),
+                (saved.kind, saved.flags, saved.charge)
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! What changed between two saves of the same universe: `diff old.bin new.bin` prints a
+//! summary (age, phase, entropy, particles, civilizations, changed regions, life planets
+//! found and lost), `--json` prints the full `matrix_storage::SnapshotDiff` instead.
+//! Exits with 1 if a save can't be read or they are of different universes.
+
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    old: PathBuf,
+    new: PathBuf,
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        let mut json = false;
+        for arg in args {
+            match arg.as_str() {
+                "--json" => json = true,
+                flag if flag.starts_with("--") => return Err(format!("unknown argument: {arg}")),
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+        let [old, new] = <[PathBuf; 2]>::try_from(paths)
+            .map_err(|_| "usage: diff <old save> <new save> [--json]".to_string())?;
+        Ok(Self { old, new, json })
+    }
+}
+
+fn run(opts: &Options) -> Result<String, String> {
+    let load = |path: &PathBuf| {
+        matrix_storage::load_snapshot(path).map_err(|e| format!("{}: {e}", path.display()))
+    };
+    let (old, new) = (load(&opts.old)?, load(&opts.new)?);
+    let diff = matrix_storage::diff_snapshots(&old, &new).map_err(|e| e.to_string())?;
+    if opts.json {
+        serde_json::to_string_pretty(&diff).map_err(|e| e.to_string())
+    } else {
+        Ok(diff.to_string())
+    }
+}
+
+fn main() {
+    let opts = match Options::parse(std::env::args().skip(1)) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+    match run(&opts) {
+        Ok(text) => println!("{}", text.trim_end()),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arguments() {
+        let opts = Options::parse(["a.bin", "--json", "b.bin"].map(String::from)).unwrap();
+        assert_eq!(
+            opts,
+            Options {
+                old: PathBuf::from("a.bin"),
+                new: PathBuf::from("b.bin"),
+                json: true,
+            }
+        );
+        assert!(!Options::parse(["a.bin", "b.bin"].map(String::from)).unwrap().json);
+        assert!(Options::parse(["a.bin".to_string()]).is_err());
+        assert!(Options::parse(["a.bin", "b.bin", "c.bin"].map(String::from)).is_err());
+        assert!(Options::parse(["a.bin", "b.bin", "--frobnicate"].map(String::from)).is_err());
+    }
+}
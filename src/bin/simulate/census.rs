@@ -0,0 +1,47 @@
+//! `simulate census [--seed S] [--age GYR] [--regions N]`: the Drake breakdown of one
+//! universe (`matrix_physics::stats::UniverseCensus`) as a table.
+
+use matrix_core::SimConfig;
+use matrix_physics::stats::UniverseCensus;
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    fn value<T: std::str::FromStr>(flag: &str, arg: Option<String>) -> Result<T, String> {
+        let arg = arg.ok_or(format!("{flag} needs a value"))?;
+        arg.parse().map_err(|_| format!("bad value for {flag}: {arg}"))
+    }
+    let mut config = SimConfig::default();
+    let mut age = 13.8;
+    let mut regions = 64;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => config.seed = value(&arg, args.next())?,
+            "--age" => age = value(&arg, args.next())?,
+            "--regions" => regions = value(&arg, args.next())?,
+            _ => return Err(format!("unknown census argument: {arg}")),
+        }
+    }
+
+    eprintln!("Sampling {regions} regions of seed {} at {age} Gyr...", config.seed);
+    let census = UniverseCensus::compute(&config, age, regions);
+    println!(
+        "Census of seed {} at {:.1} Gyr — {} of {} regions sampled, 95% intervals",
+        census.seed, census.age_gyr, census.sampled_regions, census.total_regions
+    );
+    println!(
+        "{:<14} {:>10} {:>10} {:>14} {:>14} {:>14}",
+        "stage", "sampled", "rate", "total", "low", "high"
+    );
+    for estimate in &census.stages {
+        println!(
+            "{:<14} {:>10} {:>9.4}% {:>14.0} {:>14.0} {:>14.0}",
+            estimate.stage.label(),
+            estimate.sampled,
+            estimate.rate * 100.0,
+            estimate.total,
+            estimate.low,
+            estimate.high
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,458 @@
+//! Monte Carlo survey of many universes, driven through `matrix_api`. Universes run in
+//! parallel (each is deterministic by seed) and every life-bearing planet found is
+//! appended to a JSON Lines file as its universe finishes, so an interrupted run picks up
+//! where it stopped.
+//!
+//! `--universes N` (100), `--seed-base S` (1000), `--ages 8,10,13.8` (Gyr),
+//! `--regions N` (densest regions sampled per age, 20), `--output <file.jsonl>`.
+//! `--report` prints the catalogue of life from the file afterwards;
+//! `--export <file.json|file.csv>` also writes this run's discoveries via `matrix_storage`.
+//! `simulate census ...` prints the Drake breakdown of one universe instead (see `census`).
+
+mod census;
+mod report;
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use matrix_api::{LifeDiscovery, SimConfig, Universe};
+use matrix_core::{Biosphere, PlanetType, SpectralClass};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Ticks are only for orbits and region loading here, so they can be coarse
+const STEP_GYR: f64 = 0.1;
+/// Seeds of consecutive universes are this far apart
+const SEED_STRIDE: u64 = 7919;
+/// Width of the progress bar (characters)
+const PROGRESS_WIDTH: usize = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    universes: u32,
+    seed_base: u64,
+    /// Ages the universes are sampled at (Gyr, ascending)
+    ages: Vec<f64>,
+    /// Densest regions sampled at each age
+    regions: usize,
+    output: PathBuf,
+    report: bool,
+    export: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            universes: 100,
+            seed_base: 1000,
+            ages: vec![8.0, 10.0, 13.8, 18.0, 25.0, 30.0],
+            regions: 20,
+            output: PathBuf::from("simulate.jsonl"),
+            report: false,
+            export: None,
+        }
+    }
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        fn value<T: std::str::FromStr>(flag: &str, arg: Option<String>) -> Result<T, String> {
+            let arg = arg.ok_or(format!("{flag} needs a value"))?;
+            arg.parse().map_err(|_| format!("bad value for {flag}: {arg}"))
+        }
+        let mut opts = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--universes" => opts.universes = value(&arg, args.next())?,
+                "--seed-base" => opts.seed_base = value(&arg, args.next())?,
+                "--ages" => {
+                    let list: String = value(&arg, args.next())?;
+                    opts.ages = list
+                        .split(',')
+                        .map(|age| value(&arg, Some(age.trim().to_string())))
+                        .collect::<Result<_, _>>()?;
+                    opts.ages.sort_by(f64::total_cmp);
+                }
+                "--regions" => opts.regions = value(&arg, args.next())?,
+                "--output" => opts.output = value(&arg, args.next())?,
+                "--report" => opts.report = true,
+                "--export" => opts.export = Some(value(&arg, args.next())?),
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        Ok(opts)
+    }
+
+    fn seed(&self, universe: u32) -> u64 {
+        self.seed_base.wrapping_add((universe as u64).wrapping_mul(SEED_STRIDE))
+    }
+}
+
+/// One line of the output file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record {
+    /// A life-bearing planet as seen at one of the sampled ages
+    Planet(PlanetRecord),
+    /// Written after a universe's planets: that universe is complete
+    Universe(UniverseRecord),
+}
+
+impl Record {
+    fn seed(&self) -> u64 {
+        match self {
+            Record::Planet(planet) => planet.seed,
+            Record::Universe(universe) => universe.seed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PlanetRecord {
+    universe: u32,
+    seed: u64,
+    age_gyr: f64,
+    region_id: u64,
+    star_id: u64,
+    star_class: SpectralClass,
+    star_temp: f64,
+    planet_id: u64,
+    planet_type: PlanetType,
+    planet_temp: f64,
+    has_water: bool,
+    orbital_radius_au: f64,
+    /// The biosphere, dominant genome included
+    life: Biosphere,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct UniverseRecord {
+    universe: u32,
+    seed: u64,
+    ages: Vec<f64>,
+    regions: usize,
+    life_planets: usize,
+}
+
+/// Run universe `index` through the sampled ages: its records (planets, then the
+/// universe) and its discoveries
+fn simulate_universe(opts: &Options, index: u32) -> (Vec<Record>, Vec<LifeDiscovery>) {
+    let seed = opts.seed(index);
+    // Regions, stars and life don't need the particle simulation
+    let config = SimConfig {
+        seed,
+        particle_count: 0,
+        ..SimConfig::default()
+    };
+    let mut universe = Universe::from_config(config).with_step(STEP_GYR);
+    let mut records = Vec::new();
+    for &age in &opts.ages {
+        universe.advance(age - universe.age());
+        for region_id in universe.densest_regions(opts.regions) {
+            let Some(report) = universe.sample_region(region_id) else {
+                continue;
+            };
+            for star in &report.stars {
+                for planet in &star.planets {
+                    let Some(ref life) = planet.life else {
+                        continue;
+                    };
+                    records.push(Record::Planet(PlanetRecord {
+                        universe: index,
+                        seed,
+                        age_gyr: report.age_gyr,
+                        region_id,
+                        star_id: star.id,
+                        star_class: star.spectral_class,
+                        star_temp: star.surface_temp,
+                        planet_id: planet.id,
+                        planet_type: planet.planet_type,
+                        planet_temp: planet.surface_temp,
+                        has_water: planet.has_water,
+                        orbital_radius_au: planet.orbital_radius,
+                        life: life.clone(),
+                    }));
+                }
+            }
+        }
+    }
+    records.push(Record::Universe(UniverseRecord {
+        universe: index,
+        seed,
+        ages: opts.ages.clone(),
+        regions: opts.regions,
+        life_planets: records.len(),
+    }));
+    (records, universe.enumerate_life())
+}
+
+/// The text of `path`, empty if there's no such file yet
+fn read_text(path: &Path) -> std::io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// Every record of `text` that parses (a line cut short by a crash doesn't)
+fn parse_records(text: &str) -> Vec<Record> {
+    text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+/// Keep the records of universes already completed with the same ages and region count,
+/// rewriting the file without the rest (a universe or a line cut off part way); their seeds
+fn resume(opts: &Options) -> std::io::Result<HashSet<u64>> {
+    let text = read_text(&opts.output)?;
+    // Lines are kept as they are: floats don't always survive a parse and re-serialize
+    let lines: Vec<(&str, Record)> = text
+        .lines()
+        .filter_map(|line| Some((line, serde_json::from_str(line).ok()?)))
+        .collect();
+    let done: HashSet<u64> = lines
+        .iter()
+        .filter_map(|(_, record)| match record {
+            Record::Universe(u) if u.ages == opts.ages && u.regions == opts.regions => Some(u.seed),
+            _ => None,
+        })
+        .collect();
+    let kept: Vec<&str> = lines
+        .iter()
+        .filter(|(_, record)| done.contains(&record.seed()))
+        .map(|&(line, _)| line)
+        .collect();
+    if kept.len() < text.lines().count() || !(text.is_empty() || text.ends_with('\n')) {
+        let partial = opts.output.with_extension("jsonl.tmp");
+        std::fs::write(&partial, kept.iter().map(|line| format!("{line}\n")).collect::<String>())?;
+        std::fs::rename(&partial, &opts.output)?;
+    }
+    Ok(done)
+}
+
+fn write_records(out: &mut impl Write, records: &[Record]) -> std::io::Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut *out, record)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+/// Progress bar with the rate so far and the time left at that rate
+struct Progress {
+    total: usize,
+    done: usize,
+    start: Instant,
+}
+
+impl Progress {
+    fn new(total: usize) -> Self {
+        Self {
+            total,
+            done: 0,
+            start: Instant::now(),
+        }
+    }
+
+    fn tick(&mut self) {
+        self.done += 1;
+        let fraction = self.done as f64 / self.total.max(1) as f64;
+        let filled = (fraction * PROGRESS_WIDTH as f64) as usize;
+        let elapsed = self.start.elapsed();
+        let eta = elapsed.mul_f64((self.total - self.done) as f64 / self.done as f64);
+        eprint!(
+            "\r[{}{}] {}/{} ({:.0}%) {:.2} universes/s, ETA {}  ",
+            "#".repeat(filled),
+            "-".repeat(PROGRESS_WIDTH - filled),
+            self.done,
+            self.total,
+            fraction * 100.0,
+            self.done as f64 / elapsed.as_secs_f64().max(1e-9),
+            clock(eta)
+        );
+    }
+}
+
+/// h:mm:ss
+fn clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Simulate the universes the output file doesn't have yet, in parallel, appending each
+/// one's records in universe order. Returns how many were run and their discoveries.
+fn run(opts: &Options) -> std::io::Result<(usize, Vec<LifeDiscovery>)> {
+    let done = resume(opts)?;
+    let pending: Vec<u32> = (0..opts.universes)
+        .filter(|&u| !done.contains(&opts.seed(u)))
+        .collect();
+    if pending.is_empty() {
+        return Ok((0, Vec::new()));
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&opts.output)?;
+    let mut out = BufWriter::new(file);
+    let mut progress = Progress::new(pending.len());
+    let mut catalogue = Vec::new();
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            pending.par_iter().for_each_with(tx, |tx, &u| {
+                // The receiver only goes away on a write error, which ends the run anyway
+                let _ = tx.send((u, simulate_universe(opts, u)));
+            });
+        });
+        // Finished universes wait here until the ones before them are written
+        let mut finished = BTreeMap::new();
+        let mut next = 0;
+        for (u, result) in rx {
+            progress.tick();
+            finished.insert(u, result);
+            while let Some((records, discoveries)) =
+                pending.get(next).and_then(|u| finished.remove(u))
+            {
+                write_records(&mut out, &records)?;
+                catalogue.extend(discoveries);
+                next += 1;
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    })?;
+    eprintln!();
+    Ok((pending.len(), catalogue))
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().is_some_and(|arg| arg == "census") {
+        if let Err(e) = census::run(args.skip(1)) {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+        return;
+    }
+    let opts = match Options::parse(args) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    eprintln!(
+        "Simulating {} universes (seeds from {}) into {}...",
+        opts.universes,
+        opts.seed_base,
+        opts.output.display()
+    );
+    let (ran, catalogue) = match run(&opts) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Writing {} failed: {e}", opts.output.display());
+            std::process::exit(1);
+        }
+    };
+    eprintln!(
+        "Done. Ran {} universes ({} already in the file), {} discoveries.",
+        ran,
+        opts.universes as usize - ran,
+        catalogue.len()
+    );
+
+    if let Some(path) = &opts.export {
+        let format = matrix_storage::ExportFormat::from_path(path);
+        match matrix_storage::export_life_catalogue(&catalogue, path, format) {
+            Ok(()) => eprintln!("Exported {} discoveries to {}", catalogue.len(), path.display()),
+            Err(e) => eprintln!("Export failed: {e}"),
+        }
+    }
+
+    if opts.report {
+        match read_text(&opts.output) {
+            Ok(text) => report::print_report(&parse_records(&text)),
+            Err(e) => eprintln!("Reading {} failed: {e}", opts.output.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_seeds_give_identical_jsonl() {
+        let dir = std::env::temp_dir().join(format!("matrix_simulate_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let opts = |name: &str| {
+            Options::parse(
+                ["--universes", "5", "--seed-base", "42", "--ages", "10", "--regions", "2"]
+                    .map(String::from),
+            )
+            .map(|opts| Options {
+                output: dir.join(name),
+                ..opts
+            })
+            .unwrap()
+        };
+
+        let (first, second) = (opts("first.jsonl"), opts("second.jsonl"));
+        assert_eq!(run(&first).unwrap().0, 5);
+        assert_eq!(run(&second).unwrap().0, 5);
+        let text = std::fs::read_to_string(&first.output).unwrap();
+        assert_eq!(text, std::fs::read_to_string(&second.output).unwrap());
+        let seeds: Vec<u64> = parse_records(&text)
+            .iter()
+            .filter(|r| matches!(r, Record::Universe(_)))
+            .map(Record::seed)
+            .collect();
+        assert_eq!(seeds, (0..5).map(|u| first.seed(u)).collect::<Vec<_>>());
+        // Everything is there already: nothing to run
+        assert_eq!(run(&first).unwrap().0, 0);
+
+        // Cut off in the middle of the third universe: it and the rest run again, and the
+        // file ends up as if nothing had happened
+        let third = text.match_indices("\"record\":\"universe\"").nth(1).unwrap().0;
+        let cut = third + text[third..].find('\n').unwrap() + 20;
+        std::fs::write(&second.output, &text[..cut.min(text.len() - 1)]).unwrap();
+        assert_eq!(run(&second).unwrap().0, 3);
+        assert_eq!(std::fs::read_to_string(&second.output).unwrap(), text);
+
+        assert!(Options::parse(["--universes".to_string()]).is_err());
+        assert!(Options::parse(["--frobnicate".to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_seed_base_near_the_top_wraps_around() {
+        let dir = std::env::temp_dir().join(format!("matrix_simulate_wrap_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let top = u64::MAX.to_string();
+        let opts = Options::parse(
+            ["--universes", "3", "--seed-base", top.as_str(), "--ages", "10", "--regions", "1"]
+                .map(String::from),
+        )
+        .map(|opts| Options {
+            output: dir.join("wrap.jsonl"),
+            ..opts
+        })
+        .unwrap();
+
+        assert_eq!(opts.seed(0), u64::MAX);
+        assert_eq!(opts.seed(1), SEED_STRIDE - 1);
+        assert_eq!(opts.seed(2), 2 * SEED_STRIDE - 1);
+        assert_eq!(run(&opts).unwrap().0, 3);
+        let text = std::fs::read_to_string(&opts.output).unwrap();
+        let seeds: Vec<u64> = parse_records(&text)
+            .iter()
+            .filter(|r| matches!(r, Record::Universe(_)))
+            .map(Record::seed)
+            .collect();
+        assert_eq!(seeds, vec![u64::MAX, SEED_STRIDE - 1, 2 * SEED_STRIDE - 1]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,11 @@
-//! Monte Carlo simulation of 100 universes.
-//! Focus: catalogue the most interesting life forms that emerge.
+//! The catalogue of life: the most remarkable life forms of a run, read back from its
+//! JSON Lines records, with a substrate census and a portrait of each.
 
-use matrix_core::{Biosphere, SimConfig};
-use matrix_physics::procgen;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use matrix_core::Biosphere;
+
+use super::{PlanetRecord, Record};
 
 /// A discovered creature with full context
 #[derive(Clone)]
@@ -19,6 +21,22 @@ struct Creature {
     bio: Biosphere,
 }
 
+impl From<&PlanetRecord> for Creature {
+    fn from(record: &PlanetRecord) -> Self {
+        Creature {
+            universe_id: record.universe,
+            universe_seed: record.seed,
+            age_gyr: record.age_gyr,
+            star_class: spectral_name(record.star_temp),
+            planet_type: planet_type_name(&record.planet_type).to_string(),
+            planet_temp: record.planet_temp,
+            planet_has_water: record.has_water,
+            orbital_radius_au: record.orbital_radius_au,
+            bio: record.life.clone(),
+        }
+    }
+}
+
 impl Creature {
     fn uniqueness_score(&self) -> f64 {
         let g = &self.bio.dominant_genome;
@@ -217,79 +235,39 @@ fn planet_type_name(pt: &matrix_core::PlanetType) -> &'static str {
         matrix_core::PlanetType::Ocean => "Ocean",
         matrix_core::PlanetType::Lava => "Lava",
         matrix_core::PlanetType::Frozen => "Frozen",
+        matrix_core::PlanetType::Barren => "Barren",
     }
 }
 
-fn main() {
-    let num_universes = 100;
-    let ages = [8.0, 10.0, 13.8, 18.0, 25.0, 30.0];
-    let sample_regions = 20;
-
-    eprintln!("Simulating {} universes...", num_universes);
-
+/// Print the catalogue of the universes in `records`
+pub fn print_report(records: &[Record]) {
     let mut all_creatures: Vec<Creature> = Vec::new();
-
-    // Track substrate counts for stats
     let mut substrate_counts = [0u32; 8];
-    let mut total_life_planets = 0u32;
     let mut total_civ = 0u32;
+    let mut num_universes = 0u32;
     let mut universes_with_life = 0u32;
-    let mut universes_with_civ = 0u32;
-
-    for u in 0..num_universes {
-        let seed = 1000 + u as u64 * 7919;
-        let config = SimConfig { seed, ..SimConfig::default() };
-
-        let mut found_life = false;
-        let mut found_civ = false;
-
-        for &age in &ages {
-            let regions = procgen::generate_regions(&config, age);
-            let mut sorted: Vec<_> = regions.iter().collect();
-            sorted.sort_by(|a, b| b.density.partial_cmp(&a.density).unwrap());
-            sorted.truncate(sample_regions);
-
-            for region in &sorted {
-                let stars = procgen::generate_stellar_detail(region, age);
-                for star in &stars {
-                    for planet in &star.planets {
-                        if let Some(ref bio) = planet.life {
-                            total_life_planets += 1;
-                            found_life = true;
-
-                            let sub = (bio.dominant_genome.substrate as usize).min(7);
-                            substrate_counts[sub] += 1;
-
-                            if bio.has_technology {
-                                total_civ += 1;
-                                found_civ = true;
-                            }
-
-                            all_creatures.push(Creature {
-                                universe_id: u as u32,
-                                universe_seed: seed,
-                                age_gyr: age,
-                                star_class: spectral_name(star.surface_temp),
-                                planet_type: planet_type_name(&planet.planet_type).to_string(),
-                                planet_temp: planet.surface_temp,
-                                planet_has_water: planet.has_water,
-                                orbital_radius_au: planet.orbital_radius,
-                                bio: bio.clone(),
-                            });
-                        }
-                    }
+    let mut civ_seeds = HashSet::new();
+    for record in records {
+        match record {
+            Record::Planet(planet) => {
+                let sub = (planet.life.dominant_genome.substrate as usize).min(7);
+                substrate_counts[sub] += 1;
+                if planet.life.has_technology {
+                    total_civ += 1;
+                    civ_seeds.insert(planet.seed);
+                }
+                all_creatures.push(Creature::from(planet));
+            }
+            Record::Universe(universe) => {
+                num_universes += 1;
+                if universe.life_planets > 0 {
+                    universes_with_life += 1;
                 }
             }
-        }
-
-        if found_life { universes_with_life += 1; }
-        if found_civ { universes_with_civ += 1; }
-
-        if (u + 1) % 20 == 0 {
-            eprint!("  {}/{}...\r", u + 1, num_universes);
         }
     }
-    eprintln!("Done. Found {} life forms across {} universes.", all_creatures.len(), num_universes);
+    let total_life_planets = all_creatures.len();
+    let universes_with_civ = civ_seeds.len();
 
     // Sort by uniqueness and pick the most interesting, but ensure diversity
     all_creatures.sort_by(|a, b| b.uniqueness_score().partial_cmp(&a.uniqueness_score()).unwrap());
@@ -313,7 +291,7 @@ fn main() {
     // Print the catalogue
     println!();
     println!("╔══════════════════════════════════════════════════════════════╗");
-    println!("║    CATALOGUE OF LIFE: 100 SIMULATED UNIVERSES              ║");
+    println!("║    CATALOGUE OF LIFE: {:<5} SIMULATED UNIVERSES            ║", num_universes);
     println!("║    {} life forms found on {} life-bearing planets           ", all_creatures.len(), total_life_planets);
     println!("║    {}/{} universes developed life                           ", universes_with_life, num_universes);
     println!("║    {}/{} developed civilizations                            ", universes_with_civ, num_universes);
@@ -0,0 +1,128 @@
+use bevy::prelude::Vec3;
+use matrix_core::SimConfig;
+use matrix_render::saving;
+use matrix_sim::events::EventFeed;
+use matrix_sim::history::{UniverseHistory, sample_of};
+use matrix_sim::lazy_universe::{LazyUniverse, restart_regions_for_cycle};
+use matrix_sim::scenario;
+use matrix_sim::universe::UniverseState;
+use matrix_storage::Compression;
+use std::path::PathBuf;
+
+/// Options for `--headless` runs
+pub struct HeadlessOptions {
+    /// Stop once the universe is this old (Gyr)
+    pub until_age: f64,
+    /// Simulated time per tick (Gyr)
+    pub dt: f64,
+    /// Where the virtual camera sits for region loading / life discovery
+    pub camera: Vec3,
+    /// Write the final snapshot here
+    pub save: Option<PathBuf>,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            until_age: 13.8,
+            dt: 0.001,
+            camera: Vec3::ZERO,
+            save: None,
+        }
+    }
+}
+
+/// Run the simulation without a window until `opts.until_age`.
+/// Same per-tick work as the windowed app: universe tick, orbits, cycle restarts
+/// and LOD at the virtual camera (regions only exist after the Big Bang phase).
+/// Feed events are printed as they happen and returned, with the sampled history, for
+/// the snapshot.
+pub fn run(
+    config: SimConfig,
+    opts: &HeadlessOptions,
+) -> (UniverseState, LazyUniverse, EventFeed, UniverseHistory) {
+    let world = scenario::start_world(config);
+    let (mut universe, mut lazy) = (world.universe, world.lazy);
+    // No frame loop to poll background loads: generate regions in place
+    lazy.async_loading = false;
+    // `dt` is already in Gyr
+    universe.time_scale = 1.0;
+    let mut feed = EventFeed::default();
+    let mut history = UniverseHistory::default();
+
+    let mut next_report = 1.0;
+    while universe.age < opts.until_age {
+        universe.tick(opts.dt);
+        lazy.advance_orbits(opts.dt);
+        restart_regions_for_cycle(&mut universe, &mut lazy);
+        if universe.age >= 1.0 {
+            lazy.update_lod(opts.camera.as_dvec3().to_array(), universe.age);
+        }
+        let seen = feed.pushed();
+        feed.collect(&mut universe, &mut lazy);
+        let fresh = (feed.pushed() - seen) as usize;
+        for entry in feed.entries().iter().skip(feed.entries().len() - fresh) {
+            println!("[{:>6.2} Gyr] {}", entry.age_gyr, entry.event.describe());
+        }
+        history.record(sample_of(&universe, &lazy));
+
+        if universe.age >= next_report {
+            println!(
+                "[{:>6.2} Gyr] cycle {} | {} | particles {} | region {:?} | life {} | civilizations {}",
+                universe.age,
+                universe.cycle,
+                universe.phase.name(),
+                universe.alive_count(),
+                lazy.current_region_id,
+                lazy.life_planets.len(),
+                lazy.civilization_count,
+            );
+            next_report = universe.age.floor() + 1.0;
+        }
+    }
+
+    (universe, lazy, feed, history)
+}
+
+/// Save the end state of a headless run
+pub fn save(
+    universe: &UniverseState,
+    lazy: &LazyUniverse,
+    feed: &EventFeed,
+    history: &UniverseHistory,
+    path: &std::path::Path,
+) -> Result<(), matrix_storage::SnapshotError> {
+    let snapshot = saving::build_snapshot(universe, lazy, feed, history, saving::unix_now());
+    matrix_storage::save_snapshot(&snapshot, path, Compression::Lz4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headless_smoke_run() {
+        let config = SimConfig {
+            particle_count: 1000,
+            use_gpu: false,
+            ..SimConfig::default()
+        };
+        let opts = HeadlessOptions {
+            until_age: 1.0,
+            dt: 0.01,
+            ..HeadlessOptions::default()
+        };
+        let (universe, lazy, feed, history) = run(config, &opts);
+        assert!(universe.age >= 1.0);
+        assert!(lazy.region_count() > 0);
+
+        let path = std::env::temp_dir().join(format!("matrix_headless_{}.bin", std::process::id()));
+        save(&universe, &lazy, &feed, &history, &path).unwrap();
+        let loaded = matrix_storage::load_snapshot(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.age, universe.age);
+        assert_eq!(loaded.config.particle_count, 1000);
+        assert_eq!(loaded.events.len(), feed.entries().len());
+        assert_eq!(loaded.history, history.samples());
+    }
+}
@@ -1,29 +1,218 @@
+mod headless;
+
 use bevy::prelude::*;
-use matrix_core::SimConfig;
+use matrix_core::{SimConfig, StartScenario};
+use matrix_render::audio::MatrixAudioPlugin;
 use matrix_render::menu::{AppState, MenuPlugin};
 use matrix_render::plugin::MatrixRenderPlugin;
+use matrix_sim::gpu::GpuSimPlugin;
 use matrix_sim::lazy_universe::LazyUniverse;
 use matrix_sim::pipeline::SimulationPlugin;
+use matrix_sim::replay::{ReplayLog, ReplayPlayer, ReplayPlugin, ReplayRecorder};
 use matrix_sim::universe::UniverseState;
+use std::path::PathBuf;
+
+/// Command line options: `--record file.replay` / `--replay file.replay`,
+/// or `--headless [--until-age 20] [--save out.bin] [--camera x,y,z] [--dt 0.001]
+/// [--seed N] [--particles N]` to run without a window. `--start-age N` starts a mature
+/// universe N Gyr in instead of at the Big Bang. `--grid x,y,z`, `--region-size N` (Mpc)
+/// and `--subdivide D` (split regions denser than D) shape the region grid.
+#[derive(Default)]
+struct Args {
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    headless: bool,
+    headless_opts: headless::HeadlessOptions,
+    seed: Option<u64>,
+    particles: Option<u32>,
+    start_age: Option<f64>,
+    grid: Option<[u32; 3]>,
+    region_size: Option<f64>,
+    subdivide_above: Option<f64>,
+}
+
+/// Parse the next argument as a number, warning (and ignoring it) if it isn't one
+fn parse_next<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Option<T> {
+    let parsed = value.as_deref().and_then(|v| v.parse().ok());
+    if parsed.is_none() {
+        eprintln!("{flag} expects a number, got {value:?}");
+    }
+    parsed
+}
+
+/// Most cubes `--grid` may ask for (32³): every region is generated up front
+const MAX_GRID_REGIONS: u64 = 32_768;
+
+/// "x,y,z" → regions along each axis, each at least 1, at most `MAX_GRID_REGIONS` in all
+fn parse_grid(value: Option<String>) -> Result<[u32; 3], String> {
+    let parts: Option<Vec<u32>> = value.as_deref().and_then(|value| {
+        value
+            .split(',')
+            .map(|p| p.trim().parse().ok().filter(|n| *n > 0))
+            .collect()
+    });
+    let grid: [u32; 3] = parts
+        .and_then(|parts| parts.try_into().ok())
+        .ok_or_else(|| format!("--grid expects x,y,z region counts, got {value:?}"))?;
+    let regions: u64 = grid.iter().map(|&n| n as u64).product();
+    if regions > MAX_GRID_REGIONS {
+        return Err(format!(
+            "--grid {}x{}x{} is {regions} regions, at most {MAX_GRID_REGIONS} are allowed",
+            grid[0], grid[1], grid[2]
+        ));
+    }
+    Ok(grid)
+}
+
+/// "x,y,z" → camera position
+fn parse_vec3(value: Option<String>) -> Option<Vec3> {
+    let parts: Vec<f32> = value?
+        .split(',')
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    match parts[..] {
+        [x, y, z] => Some(Vec3::new(x, y, z)),
+        _ => None,
+    }
+}
+
+fn parse_args() -> Args {
+    let mut args = Args::default();
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--record" => args.record = iter.next().map(PathBuf::from),
+            "--replay" => args.replay = iter.next().map(PathBuf::from),
+            "--headless" => args.headless = true,
+            "--until-age" => {
+                if let Some(age) = parse_next("--until-age", iter.next()) {
+                    args.headless_opts.until_age = age;
+                }
+            }
+            "--dt" => {
+                if let Some(dt) = parse_next::<f64>("--dt", iter.next()).filter(|dt| *dt > 0.0) {
+                    args.headless_opts.dt = dt;
+                }
+            }
+            "--save" => args.headless_opts.save = iter.next().map(PathBuf::from),
+            "--camera" => match parse_vec3(iter.next()) {
+                Some(pos) => args.headless_opts.camera = pos,
+                None => eprintln!("--camera expects x,y,z"),
+            },
+            "--seed" => args.seed = parse_next("--seed", iter.next()),
+            "--particles" => args.particles = parse_next("--particles", iter.next()),
+            "--start-age" => {
+                args.start_age =
+                    parse_next::<f64>("--start-age", iter.next()).filter(|age| *age > 0.0)
+            }
+            "--grid" => match parse_grid(iter.next()) {
+                Ok(grid) => args.grid = Some(grid),
+                Err(e) => eprintln!("{e}"),
+            },
+            "--region-size" => {
+                args.region_size =
+                    parse_next::<f64>("--region-size", iter.next()).filter(|size| *size > 0.0)
+            }
+            "--subdivide" => args.subdivide_above = parse_next("--subdivide", iter.next()),
+            other => eprintln!("Unknown argument: {other}"),
+        }
+    }
+    args
+}
 
 fn main() {
-    let config = SimConfig::default();
-
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Matrix — Universe Simulation".into(),
-                resolution: (1920.0, 1080.0).into(),
-                ..default()
-            }),
+    let args = parse_args();
+    let mut config = SimConfig::default();
+    if let Some(seed) = args.seed {
+        config.seed = seed;
+    }
+    if let Some(particles) = args.particles {
+        config.particle_count = particles;
+    }
+    if let Some(age) = args.start_age {
+        config.start = StartScenario::MatureUniverse { age };
+    }
+    if let Some(grid) = args.grid {
+        config.region_layout.grid = grid;
+    }
+    if let Some(size) = args.region_size {
+        config.region_layout.size = size;
+    }
+    if args.subdivide_above.is_some() {
+        config.region_layout.subdivide_above = args.subdivide_above;
+    }
+
+    if args.headless {
+        run_headless(config, &args.headless_opts);
+        return;
+    }
+
+    // Playback must run against the recorded seed/config
+    let player = args.replay.as_ref().and_then(|path| match ReplayLog::load(path) {
+        Ok(log) => {
+            config = log.config.clone();
+            Some(ReplayPlayer::new(log))
+        }
+        Err(e) => {
+            eprintln!("Failed to load replay {}: {e}", path.display());
+            None
+        }
+    });
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Matrix — Universe Simulation".into(),
+            resolution: (1920.0, 1080.0).into(),
             ..default()
-        }))
-        .insert_resource(ClearColor(Color::srgb(0.0, 0.0, 0.02)))
-        .insert_resource(UniverseState::empty(config.clone()))
-        .insert_resource(LazyUniverse::empty(config))
-        .init_state::<AppState>()
-        .add_plugins(SimulationPlugin)
-        .add_plugins(MatrixRenderPlugin)
-        .add_plugins(MenuPlugin)
-        .run();
+        }),
+        ..default()
+    }))
+    .insert_resource(ClearColor(Color::srgb(0.0, 0.0, 0.02)))
+    .insert_resource(UniverseState::empty(config.clone()))
+    .insert_resource(LazyUniverse::empty(config.clone()))
+    .init_state::<AppState>()
+    .add_plugins(SimulationPlugin)
+    .add_plugins(GpuSimPlugin)
+    .add_plugins(ReplayPlugin)
+    .add_plugins(MatrixRenderPlugin)
+    .add_plugins(MatrixAudioPlugin)
+    .add_plugins(MenuPlugin);
+
+    if let Some(player) = player {
+        app.insert_resource(player);
+    }
+    if let Some(path) = args.record {
+        app.insert_resource(ReplayRecorder::new(config, path));
+    }
+
+    app.run();
+}
+
+/// `--headless`: simulate to the target age on the CPU, then optionally save
+fn run_headless(mut config: SimConfig, opts: &headless::HeadlessOptions) {
+    config.use_gpu = false;
+    println!(
+        "Headless run: seed {}, {} particles, until {:.2} Gyr (dt {} Gyr)",
+        config.seed, config.particle_count, opts.until_age, opts.dt
+    );
+    let started = std::time::Instant::now();
+    let (universe, lazy, feed, history) = headless::run(config, opts);
+    println!(
+        "Reached {:.3} Gyr in {:.1}s: {} life planets, {} civilizations",
+        universe.age,
+        started.elapsed().as_secs_f64(),
+        lazy.life_planets.len(),
+        lazy.civilization_count
+    );
+
+    if let Some(path) = &opts.save {
+        match headless::save(&universe, &lazy, &feed, &history, path) {
+            Ok(()) => println!("Snapshot saved: {}", path.display()),
+            Err(e) => {
+                eprintln!("Failed to save snapshot {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
 }
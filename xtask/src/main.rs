@@ -0,0 +1,347 @@
+//! Project chores, run as `cargo xtask <command>`:
+//!
+//! - `bench [ARGS]`: run the criterion benchmarks (`cargo bench` with ARGS, by default the
+//!   bench targets of every crate that has some), then write `BENCHMARKS.md` from the results
+//! - `bench-report`: write `BENCHMARKS.md` from the last results in `target/criterion`
+//!
+//! Exits with 1 if the benchmarks fail or there are no results to report.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+/// Crates with a `benches/` directory
+const BENCH_PACKAGES: [&str; 4] = ["matrix_physics", "matrix_sim", "matrix_api", "matrix_render"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Task {
+    /// Run `cargo bench` with these arguments, then write the report
+    Bench(Vec<String>),
+    /// Only write the report
+    BenchReport,
+}
+
+impl Task {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let usage = "usage: cargo xtask bench [cargo bench args] | cargo xtask bench-report";
+        let mut args = args.into_iter();
+        match args.next().as_deref() {
+            Some("bench") => {
+                let rest: Vec<String> = args.collect();
+                if rest.is_empty() {
+                    let mut default = vec!["--benches".to_string()];
+                    for package in BENCH_PACKAGES {
+                        default.extend(["-p".to_string(), package.to_string()]);
+                    }
+                    Ok(Task::Bench(default))
+                } else {
+                    Ok(Task::Bench(rest))
+                }
+            }
+            Some("bench-report") if args.next().is_none() => Ok(Task::BenchReport),
+            _ => Err(usage.to_string()),
+        }
+    }
+}
+
+/// One benchmark's result, from criterion's `new/benchmark.json` and `new/estimates.json`
+#[derive(Debug, Clone, PartialEq)]
+struct BenchResult {
+    group: String,
+    /// Function and parameter within the group ("encode/lz4", "50000")
+    id: String,
+    /// Nanoseconds per iteration
+    mean: f64,
+    std_dev: f64,
+    throughput: Option<Throughput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Throughput {
+    Elements(u64),
+    Bytes(u64),
+}
+
+impl BenchResult {
+    fn read(dir: &Path) -> Result<Self, String> {
+        let json = |name: &str| -> Result<Value, String> {
+            let path = dir.join(name);
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+            serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+        };
+        let (benchmark, estimates) = (json("benchmark.json")?, json("estimates.json")?);
+        let text = |value: &Value| value.as_str().map(str::to_string);
+        let group = text(&benchmark["group_id"]).ok_or("benchmark.json without group_id")?;
+        let id = [text(&benchmark["function_id"]), text(&benchmark["value_str"])]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("/");
+        let throughput = &benchmark["throughput"];
+        let throughput = if let Some(n) = throughput["Elements"].as_u64() {
+            Some(Throughput::Elements(n))
+        } else {
+            throughput["Bytes"].as_u64().map(Throughput::Bytes)
+        };
+        let estimate = |name: &str| {
+            estimates[name]["point_estimate"]
+                .as_f64()
+                .ok_or(format!("estimates.json without {name}"))
+        };
+        Ok(Self {
+            group,
+            id,
+            mean: estimate("mean")?,
+            std_dev: estimate("std_dev")?,
+            throughput,
+        })
+    }
+
+    /// Numeric parameters sort by value, so 10000 comes before 50000 and 100000
+    fn sort_key(&self) -> (String, f64, String) {
+        let number = self.id.parse().unwrap_or(f64::MAX);
+        (self.group.clone(), number, self.id.clone())
+    }
+
+    /// Throughput at the mean time
+    fn rate(&self) -> Option<String> {
+        let per_second = |n: u64| n as f64 / (self.mean * 1e-9);
+        match self.throughput? {
+            Throughput::Elements(n) => Some(format!("{} elem/s", si(per_second(n)))),
+            Throughput::Bytes(n) => Some(format!("{:.1} MiB/s", per_second(n) / (1024.0 * 1024.0))),
+        }
+    }
+}
+
+/// Every result under criterion's output directory
+fn collect_results(criterion_dir: &Path) -> Result<Vec<BenchResult>, String> {
+    let mut results = Vec::new();
+    let mut dirs = vec![criterion_dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            // `report/` is criterion's HTML, `base/` and `change/` the previous run
+            if path.file_name().is_some_and(|name| name == "new") {
+                if path.join("benchmark.json").exists() {
+                    results.push(BenchResult::read(&path)?);
+                }
+            } else if !path.file_name().is_some_and(|name| name == "report" || name == "base") {
+                dirs.push(path);
+            }
+        }
+    }
+    results.sort_by(|a, b| {
+        let (a, b) = (a.sort_key(), b.sort_key());
+        a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2))
+    });
+    Ok(results)
+}
+
+/// 1234567.0 → "1.23 M"
+fn si(value: f64) -> String {
+    let (scaled, prefix) = if value >= 1e9 {
+        (value / 1e9, " G")
+    } else if value >= 1e6 {
+        (value / 1e6, " M")
+    } else if value >= 1e3 {
+        (value / 1e3, " K")
+    } else {
+        (value, " ")
+    };
+    format!("{scaled:.2}{prefix}")
+}
+
+/// Nanoseconds as the largest unit that keeps them ≥ 1
+fn format_time(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{ns:.1} ns")
+    }
+}
+
+/// `BENCHMARKS.md`: where the numbers came from, then one table per group
+fn render_report(results: &[BenchResult], machine: &str) -> String {
+    let mut out = String::from("# Benchmarks\n\n");
+    out.push_str(
+        "Baseline numbers for the criterion benchmarks in `crates/*/benches`, written by\n\
+         `cargo xtask bench`. Run it before and after a change to compare; criterion also\n\
+         prints the change against the previous run of each benchmark.\n\n",
+    );
+    let _ = writeln!(out, "Measured on {machine}.");
+    let mut group = None;
+    for result in results {
+        if group != Some(&result.group) {
+            group = Some(&result.group);
+            let _ = write!(
+                out,
+                "\n## {}\n\n| Benchmark | Mean | Std. dev. | Throughput |\n|---|---|---|---|\n",
+                result.group
+            );
+        }
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            result.id,
+            format_time(result.mean),
+            format_time(result.std_dev),
+            result.rate().unwrap_or_else(|| "—".to_string())
+        );
+    }
+    out
+}
+
+/// First line of a command's output, if it runs
+fn command_line(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    output.status.success().then(|| text.lines().next().unwrap_or_default().trim().to_string())
+}
+
+/// CPU, thread count, OS, compiler and commit
+fn machine_description() -> String {
+    let cpu = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split(':').nth(1))
+                .map(|name| name.trim().to_string())
+        })
+        .unwrap_or_else(|| "an unknown CPU".to_string());
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let rustc = command_line("rustc", &["--version"]).unwrap_or_else(|| "rustc".to_string());
+    let commit = command_line("git", &["rev-parse", "--short", "HEAD"])
+        .map(|sha| format!(", commit `{sha}`"))
+        .unwrap_or_default();
+    format!(
+        "{cpu} ({threads} thread{}), {}/{}, `{rustc}`{commit}",
+        if threads == 1 { "" } else { "s" },
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the workspace")
+        .to_path_buf()
+}
+
+fn run(task: &Task) -> Result<String, String> {
+    let root = workspace_root();
+    if let Task::Bench(args) = task {
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let status = Command::new(cargo)
+            .arg("bench")
+            .args(args)
+            .current_dir(&root)
+            .status()
+            .map_err(|e| format!("cargo bench: {e}"))?;
+        if !status.success() {
+            return Err(format!("cargo bench failed ({status})"));
+        }
+    }
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| root.join("target"), PathBuf::from);
+    let results = collect_results(&target.join("criterion"))?;
+    if results.is_empty() {
+        return Err(format!("no benchmark results in {}", target.join("criterion").display()));
+    }
+    let path = root.join("BENCHMARKS.md");
+    std::fs::write(&path, render_report(&results, &machine_description()))
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(format!("Wrote {} ({} benchmarks)", path.display(), results.len()))
+}
+
+fn main() {
+    let task = match Task::parse(std::env::args().skip(1)) {
+        Ok(task) => task,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+    match run(&task) {
+        Ok(text) => println!("{text}"),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks() {
+        let Ok(Task::Bench(args)) = Task::parse(["bench".to_string()]) else { panic!() };
+        assert!(args.contains(&"matrix_render".to_string()));
+        assert_eq!(
+            Task::parse(["bench", "-p", "matrix_sim"].map(String::from)),
+            Ok(Task::Bench(vec!["-p".to_string(), "matrix_sim".to_string()]))
+        );
+        assert_eq!(Task::parse(["bench-report".to_string()]), Ok(Task::BenchReport));
+        assert!(Task::parse(["bench-report", "x"].map(String::from)).is_err());
+        assert!(Task::parse(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_report_orders_parameters_numerically() {
+        let result = |id: &str, mean: f64| BenchResult {
+            group: "gravity_step_euler".to_string(),
+            id: id.to_string(),
+            mean,
+            std_dev: mean / 100.0,
+            throughput: Some(Throughput::Elements(10_000)),
+        };
+        let dir = std::env::temp_dir().join(format!("xtask-report-{}", std::process::id()));
+        for (id, mean) in [("100000", 2.5e8), ("10000", 2.0e7), ("50000", 1.2e8)] {
+            let new = dir.join("gravity_step_euler").join(id).join("new");
+            std::fs::create_dir_all(&new).unwrap();
+            let benchmark = format!(
+                r#"{{"group_id":"gravity_step_euler","function_id":null,"value_str":"{id}","throughput":{{"Elements":10000}}}}"#
+            );
+            let estimates = format!(
+                r#"{{"mean":{{"point_estimate":{mean}}},"std_dev":{{"point_estimate":{}}}}}"#,
+                mean / 100.0
+            );
+            std::fs::write(new.join("benchmark.json"), benchmark).unwrap();
+            std::fs::write(new.join("estimates.json"), estimates).unwrap();
+        }
+        let results = collect_results(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(results, vec![result("10000", 2.0e7), result("50000", 1.2e8), result("100000", 2.5e8)]);
+
+        let report = render_report(&results, "a test machine");
+        assert!(report.contains("## gravity_step_euler"));
+        assert!(report.contains("| 10000 | 20.00 ms | 200.00 µs | 500.00 K elem/s |"));
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(format_time(850.0), "850.0 ns");
+        assert_eq!(format_time(1.5e9), "1.50 s");
+        assert_eq!(si(2.5e6), "2.50 M");
+        let bytes = BenchResult {
+            group: "snapshot".to_string(),
+            id: "encode/lz4".to_string(),
+            mean: 1e9,
+            std_dev: 0.0,
+            throughput: Some(Throughput::Bytes(3 * 1024 * 1024)),
+        };
+        assert_eq!(bytes.rate().as_deref(), Some("3.0 MiB/s"));
+    }
+}